@@ -43,6 +43,18 @@ fn benchmark_read_latency(c: &mut Criterion) {
             black_box(&cfg.value);
         });
     });
+    group.bench_function("guarded_read", |b| {
+        b.iter(|| {
+            let cfg = config.load();
+            black_box(&cfg.value);
+        });
+    });
+    group.bench_function("pinned_read", |b| {
+        let mut pinned = config.pin();
+        b.iter(|| {
+            black_box(&pinned.get().value);
+        });
+    });
     group.finish();
 }
 
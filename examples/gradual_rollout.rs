@@ -40,7 +40,7 @@ async fn main() -> Result<()> {
     println!("Enabled gradual rollout\n");
 
     println!("Stable configuration (v1.0):");
-    let stable = rollout.get_stable().await;
+    let stable = rollout.get_stable();
     print_config(&stable);
     println!();
 
@@ -52,11 +52,11 @@ async fn main() -> Result<()> {
         timeout_ms: 3000,
         experimental_features: true,
     };
-    rollout.set_canary(Arc::new(canary_config), 10).await;
+    rollout.set_canary(Arc::new(canary_config), 10);
 
     println!("Canary configuration (v2.0):");
-    print_config(&rollout.get_canary().await.unwrap());
-    println!("Rollout: {}%\n", rollout.get_percentage().await);
+    print_config(&rollout.get_canary().unwrap());
+    println!("Rollout: {}%\n", rollout.get_percentage());
 
     // Simulate random requests
     println!("--- Simulating 20 random requests ---");
@@ -64,7 +64,7 @@ async fn main() -> Result<()> {
     let mut canary_count = 0;
 
     for i in 1..=20 {
-        let cfg = rollout.get(None).await;
+        let cfg = rollout.get(None);
         if cfg.version == "v1.0" {
             stable_count += 1;
             println!("Request {}: Stable (v1.0)", i);
@@ -85,9 +85,9 @@ async fn main() -> Result<()> {
     let test_users = vec!["user123", "user456", "user789"];
 
     for user in &test_users {
-        let cfg1 = rollout.get(Some(user)).await;
-        let cfg2 = rollout.get(Some(user)).await;
-        let cfg3 = rollout.get(Some(user)).await;
+        let cfg1 = rollout.get(Some(user));
+        let cfg2 = rollout.get(Some(user));
+        let cfg3 = rollout.get(Some(user));
 
         println!(
             "User '{}': {} (consistent across 3 requests: {})",
@@ -100,8 +100,8 @@ async fn main() -> Result<()> {
 
     // Increase rollout percentage
     println!("--- Increasing rollout to 25% ---");
-    rollout.increase_percentage(15).await;
-    println!("New rollout: {}%\n", rollout.get_percentage().await);
+    rollout.increase_percentage(15);
+    println!("New rollout: {}%\n", rollout.get_percentage());
 
     // Simulate more requests
     println!("Simulating 20 more requests:");
@@ -109,7 +109,7 @@ async fn main() -> Result<()> {
     canary_count = 0;
 
     for _ in 1..=20 {
-        let cfg = rollout.get(None).await;
+        let cfg = rollout.get(None);
         if cfg.version == "v1.0" {
             stable_count += 1;
         } else {
@@ -125,8 +125,8 @@ async fn main() -> Result<()> {
 
     // Increase to 50%
     println!("--- Increasing rollout to 50% ---");
-    rollout.increase_percentage(25).await;
-    println!("New rollout: {}%\n", rollout.get_percentage().await);
+    rollout.increase_percentage(25);
+    println!("New rollout: {}%\n", rollout.get_percentage());
 
     // Simulate more requests
     println!("Simulating 20 more requests:");
@@ -134,7 +134,7 @@ async fn main() -> Result<()> {
     canary_count = 0;
 
     for _ in 1..=20 {
-        let cfg = rollout.get(None).await;
+        let cfg = rollout.get(None);
         if cfg.version == "v1.0" {
             stable_count += 1;
         } else {
@@ -154,15 +154,15 @@ async fn main() -> Result<()> {
     println!("Canary promoted! All traffic now on v2.0\n");
 
     println!("New stable configuration:");
-    let stable = rollout.get_stable().await;
+    let stable = rollout.get_stable();
     print_config(&stable);
-    println!("Has canary: {}", rollout.has_canary().await);
-    println!("Rollout: {}%\n", rollout.get_percentage().await);
+    println!("Has canary: {}", rollout.has_canary());
+    println!("Rollout: {}%\n", rollout.get_percentage());
 
     // Verify all requests get v2.0
     println!("--- Verifying all requests use v2.0 ---");
     for i in 1..=5 {
-        let cfg = rollout.get(None).await;
+        let cfg = rollout.get(None);
         println!("Request {}: {}", i, cfg.version);
     }
     println!();
@@ -177,19 +177,19 @@ async fn main() -> Result<()> {
         timeout_ms: 2000,
         experimental_features: true,
     };
-    rollout.set_canary(Arc::new(canary_v2_1), 30).await;
+    rollout.set_canary(Arc::new(canary_v2_1), 30);
     println!("Canary v2.1 set\n");
 
     // Simulate discovering an issue
     println!("Issue detected in canary! Rolling back...");
-    rollout.rollback_canary().await;
+    rollout.rollback_canary();
     println!("Canary rolled back\n");
 
     println!("Current state:");
-    let stable = rollout.get_stable().await;
+    let stable = rollout.get_stable();
     println!("  Stable: {}", stable.version);
-    println!("  Has canary: {}", rollout.has_canary().await);
-    println!("  Rollout: {}%", rollout.get_percentage().await);
+    println!("  Has canary: {}", rollout.has_canary());
+    println!("  Rollout: {}%", rollout.get_percentage());
 
     println!("\n\nExample complete!");
     println!("\nKey benefits of gradual rollout:");
@@ -54,11 +54,11 @@ database:
     }
 
     // Build configuration with file watching enabled
-    let config = HotswapConfig::builder()
+    let config = HotswapConfig::<AppConfig>::builder()
         .with_file(config_path)
         .with_file_watch(true) // Enable automatic reloading
         .with_watch_debounce(std::time::Duration::from_millis(500)) // Debounce file changes
-        .build::<AppConfig>()
+        .build()
         .await?;
 
     println!("Configuration loaded with file watching enabled");
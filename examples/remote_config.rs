@@ -83,11 +83,11 @@ features:
 
     // Build configuration with both file and remote sources
     // Remote source has higher priority, so it can override file values
-    let config = HotswapConfig::builder()
+    let config = HotswapConfig::<AppConfig>::builder()
         .with_file(&config_path) // Priority 100
         // .with_source(http_source) // Priority 250 - would override file values
         .with_env_overrides("APP", "__") // Priority 300 - overrides everything
-        .build::<AppConfig>()
+        .build()
         .await?;
 
     println!("✓ Configuration loaded successfully");
@@ -133,8 +133,8 @@ features:
     println!();
 
     println!("Try overriding with environment variables:");
-    println!("  export APP_SERVER__PORT=9090");
-    println!("  export APP_FEATURES__ENABLE_METRICS=true");
+    println!("  export APP__SERVER__PORT=9090");
+    println!("  export APP__FEATURES__ENABLE_METRICS=true");
     println!();
 
     // Example 6: Basic auth alternative
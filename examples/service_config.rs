@@ -261,11 +261,11 @@ observability:
     println!("Loading configuration with validation...\n");
 
     // Build configuration with all features
-    let config = HotswapConfig::builder()
+    let config = HotswapConfig::<ServiceConfig>::builder()
         // 1. Load from default file (priority: 100)
         .with_file(config_path)
         // 2. Override with environment variables (priority: 300)
-        // Example: APP_SERVER__PORT=9000 will override server.port
+        // Example: APP__SERVER__PORT=9000 will override server.port
         .with_env_overrides("APP", "__")
         // 3. Enable file watching for automatic reloads
         .with_file_watch(true)
@@ -273,7 +273,7 @@ observability:
         // 4. Add validation logic
         .with_validation(validate_service_config)
         // Build the configuration
-        .build::<ServiceConfig>()
+        .build()
         .await?;
 
     println!("✓ Configuration loaded and validated successfully\n");
@@ -329,8 +329,8 @@ observability:
     println!("\n=== Usage Examples ===\n");
 
     println!("1. Environment Variable Override:");
-    println!("   export APP_SERVER__PORT=9000");
-    println!("   export APP_FEATURES__BETA_FEATURES=true");
+    println!("   export APP__SERVER__PORT=9000");
+    println!("   export APP__FEATURES__BETA_FEATURES=true");
     println!("   cargo run --example service_config --features yaml");
 
     println!("\n2. Hot Reload:");
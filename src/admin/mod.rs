@@ -0,0 +1,735 @@
+//! Admin HTTP API for runtime configuration management.
+//!
+//! Exposes an [`axum`] router with operational endpoints so applications don't
+//! need to reinvent them:
+//!
+//! | Method | Path | Description | Feature |
+//! |--------|------|-------------|---------|
+//! | `GET` | `/config` | Current config as JSON (secrets redacted) | - |
+//! | `POST` | `/config/reload` | Reload from all sources | - |
+//! | `POST` | `/config/reload/dry-run` | Load and validate without swapping; returns the changes | - |
+//! | `POST` | `/config/prepare` | Load and validate without swapping; returns the version to activate | - |
+//! | `POST` | `/config/activate/{version}` | Swap in the prepared version | - |
+//! | `POST` | `/config/freeze` | Freeze the config, with an optional `{"reason": ...}` body | - |
+//! | `POST` | `/config/unfreeze` | Unfreeze the config | - |
+//! | `PATCH` | `/config` | Apply an RFC 6902 JSON Patch | `partial-updates` |
+//! | `GET` | `/config/history` | List recorded versions | `rollback` |
+//! | `POST` | `/config/rollback/{version}` | Roll back to a recorded version | `rollback` |
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use hotswap_config::admin::AdminApi;
+//! use hotswap_config::prelude::*;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Serialize, Deserialize, Clone)]
+//! struct AppConfig {
+//!     port: u16,
+//! }
+//!
+//! # async fn example(config: HotswapConfig<AppConfig>) {
+//! let app = AdminApi::new(config).router();
+//!
+//! // Mount under your own server, e.g. `Router::new().nest("/admin", app)`
+//! # let _ = app;
+//! # }
+//! ```
+
+use crate::core::{ChangeKind, ConfigChange, ConfigRegistry, HotswapConfig, ReloadReason};
+use crate::error::ConfigError;
+use crate::secrets::REDACTED;
+use axum::body::Bytes;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+#[cfg(feature = "rollback")]
+use crate::features::{ConfigHistory, Rollback};
+
+#[cfg(feature = "partial-updates")]
+use crate::core::{UpdateContext, UpdateOrigin};
+#[cfg(feature = "partial-updates")]
+use crate::features::PartialUpdate;
+
+/// Field names redacted by name unless configured otherwise.
+const DEFAULT_REDACTED_FIELDS: [&str; 4] = ["password", "secret", "token", "api_key"];
+
+/// Builder for the admin HTTP API.
+///
+/// See the [module documentation](self) for the available endpoints.
+pub struct AdminApi<T> {
+    config: HotswapConfig<T>,
+    redacted_fields: Vec<String>,
+    #[cfg(feature = "rollback")]
+    history: Option<ConfigHistory<T>>,
+}
+
+impl<T> AdminApi<T>
+where
+    T: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+{
+    /// Create an admin API for the given configuration handle.
+    ///
+    /// [`Secret`](crate::secrets::Secret) fields are always redacted from
+    /// `GET /config` responses. In addition, fields named `password`, `secret`,
+    /// `token`, and `api_key` are redacted by name.
+    pub fn new(config: HotswapConfig<T>) -> Self {
+        Self {
+            config,
+            redacted_fields: DEFAULT_REDACTED_FIELDS
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            #[cfg(feature = "rollback")]
+            history: None,
+        }
+    }
+
+    /// Replace the list of field names redacted by name from `GET /config` and
+    /// dry-run responses.
+    ///
+    /// Matching is case-insensitive and applies at any nesting depth.
+    pub fn with_redacted_fields<I, S>(mut self, fields: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.redacted_fields = fields.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Attach a configuration history to enable the history and rollback endpoints.
+    ///
+    /// Reloads and patches applied through the API are recorded in this history.
+    #[cfg(feature = "rollback")]
+    pub fn with_history(mut self, history: ConfigHistory<T>) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    /// Build the router.
+    pub fn router(self) -> Router {
+        let state = Arc::new(self);
+
+        #[cfg(not(feature = "partial-updates"))]
+        let config_route = get(get_config::<T>);
+        #[cfg(feature = "partial-updates")]
+        let config_route = get(get_config::<T>).patch(patch_config::<T>);
+
+        let router = Router::new()
+            .route("/config", config_route)
+            .route("/config/reload", post(reload_config::<T>))
+            .route("/config/reload/dry-run", post(dry_run_config::<T>))
+            .route("/config/prepare", post(prepare_config::<T>))
+            .route("/config/activate/{version}", post(activate_config::<T>))
+            .route("/config/freeze", post(freeze_config::<T>))
+            .route("/config/unfreeze", post(unfreeze_config::<T>));
+
+        #[cfg(feature = "rollback")]
+        let router = router
+            .route("/config/history", get(get_history::<T>))
+            .route("/config/rollback/{version}", post(rollback_config::<T>));
+
+        router.with_state(state)
+    }
+
+    /// Record the current configuration in the attached history, if any.
+    #[cfg_attr(not(feature = "rollback"), allow(unused_variables))]
+    async fn record(&self, source: &str) {
+        #[cfg(feature = "rollback")]
+        if let Some(history) = &self.history {
+            history
+                .record(self.config.get(), Some(source.to_string()))
+                .await;
+        }
+    }
+}
+
+/// Map a configuration error to an HTTP status code.
+fn status_for(error: &ConfigError) -> StatusCode {
+    match error.root() {
+        ConfigError::ValidationError(_)
+        | ConfigError::DeserializationError(_)
+        | ConfigError::UnknownKeys(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        #[cfg(feature = "partial-updates")]
+        ConfigError::PatchError(_) => StatusCode::BAD_REQUEST,
+        ConfigError::Frozen { .. } | ConfigError::SwapVetoed { .. } => StatusCode::CONFLICT,
+        ConfigError::UpdateDenied { .. } => StatusCode::FORBIDDEN,
+        ConfigError::NotPrepared { .. } => StatusCode::NOT_FOUND,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Build a JSON error response.
+fn error_response(status: StatusCode, message: impl std::fmt::Display) -> Response {
+    (status, Json(json!({ "error": message.to_string() }))).into_response()
+}
+
+/// Recursively replace values of sensitive fields with a placeholder.
+fn redact(value: &mut Value, fields: &[String]) {
+    match value {
+        Value::Object(map) => {
+            for (key, val) in map.iter_mut() {
+                if fields.iter().any(|f| f.eq_ignore_ascii_case(key)) {
+                    *val = Value::String(REDACTED.to_string());
+                } else {
+                    redact(val, fields);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|v| redact(v, fields)),
+        _ => {}
+    }
+}
+
+async fn get_config<T>(State(api): State<Arc<AdminApi<T>>>) -> Response
+where
+    T: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+{
+    match api.config.get_redacted_json() {
+        Ok(mut value) => {
+            redact(&mut value, &api.redacted_fields);
+            Json(value).into_response()
+        }
+        Err(e) => error_response(status_for(&e), e),
+    }
+}
+
+async fn reload_config<T>(State(api): State<Arc<AdminApi<T>>>) -> Response
+where
+    T: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+{
+    match api.config.reload_with_reason(ReloadReason::Admin).await {
+        Ok(()) => {
+            api.record("admin: reload").await;
+            Json(json!({ "status": "reloaded" })).into_response()
+        }
+        Err(e) => error_response(status_for(&e), e),
+    }
+}
+
+async fn dry_run_config<T>(State(api): State<Arc<AdminApi<T>>>) -> Response
+where
+    T: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+{
+    match api.config.reload_dry_run().await {
+        Ok(diff) => {
+            let changes: Vec<Value> = diff
+                .changes()
+                .iter()
+                .map(|change| change_to_json(change, &api.redacted_fields))
+                .collect();
+            Json(json!({ "changes": changes })).into_response()
+        }
+        Err(e) => error_response(status_for(&e), e),
+    }
+}
+
+async fn prepare_config<T>(State(api): State<Arc<AdminApi<T>>>) -> Response
+where
+    T: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+{
+    match api.config.prepare().await {
+        Ok(version) => Json(json!({ "status": "prepared", "version": version })).into_response(),
+        Err(e) => error_response(status_for(&e), e),
+    }
+}
+
+async fn activate_config<T>(
+    State(api): State<Arc<AdminApi<T>>>,
+    Path(version): Path<String>,
+) -> Response
+where
+    T: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+{
+    match api.config.activate(&version).await {
+        Ok(()) => {
+            api.record("admin: activate").await;
+            Json(json!({ "status": "activated", "version": version })).into_response()
+        }
+        Err(e) => error_response(status_for(&e), e),
+    }
+}
+
+/// Convert a diff entry to JSON, e.g. `{"path": "port", "kind": "modified", ...}`.
+///
+/// Values are redacted by name like `GET /config` responses: wholly if any
+/// segment of the path is a redacted field, otherwise any such fields nested
+/// in them.
+fn change_to_json(change: &ConfigChange, redacted_fields: &[String]) -> Value {
+    let kind = match change.kind {
+        ChangeKind::Added => "added",
+        ChangeKind::Removed => "removed",
+        ChangeKind::Modified => "modified",
+    };
+    let sensitive = change.path.split('.').any(|segment| {
+        redacted_fields
+            .iter()
+            .any(|f| f.eq_ignore_ascii_case(segment))
+    });
+    let to_json = |value: &config::Value| {
+        if sensitive {
+            return Value::String(REDACTED.to_string());
+        }
+        let mut value = value_to_json(value);
+        redact(&mut value, redacted_fields);
+        value
+    };
+    json!({
+        "path": change.path,
+        "kind": kind,
+        "old": change.old.as_ref().map(to_json),
+        "new": change.new.as_ref().map(to_json),
+    })
+}
+
+fn value_to_json(value: &config::Value) -> Value {
+    use config::ValueKind;
+
+    match &value.kind {
+        ValueKind::Nil => Value::Null,
+        ValueKind::Boolean(b) => json!(b),
+        ValueKind::I64(n) => json!(n),
+        ValueKind::I128(n) => json!(n),
+        ValueKind::U64(n) => json!(n),
+        ValueKind::U128(n) => json!(n),
+        ValueKind::Float(f) => json!(f),
+        ValueKind::String(s) => json!(s),
+        ValueKind::Table(table) => Value::Object(
+            table
+                .iter()
+                .map(|(k, v)| (k.clone(), value_to_json(v)))
+                .collect(),
+        ),
+        ValueKind::Array(items) => Value::Array(items.iter().map(value_to_json).collect()),
+    }
+}
+
+async fn freeze_config<T>(State(api): State<Arc<AdminApi<T>>>, body: Bytes) -> Response
+where
+    T: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+{
+    // The body is optional, so it's parsed by hand rather than with `Json`
+    let reason = if body.is_empty() {
+        None
+    } else {
+        match serde_json::from_slice::<Value>(&body) {
+            Ok(value) => value["reason"].as_str().map(str::to_string),
+            Err(e) => return error_response(StatusCode::BAD_REQUEST, e),
+        }
+    };
+
+    api.config.freeze(reason.as_deref());
+    Json(json!({ "status": "frozen", "reason": reason })).into_response()
+}
+
+async fn unfreeze_config<T>(State(api): State<Arc<AdminApi<T>>>) -> Response
+where
+    T: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+{
+    match api.config.unfreeze().await {
+        Ok(()) => Json(json!({ "status": "unfrozen" })).into_response(),
+        Err(e) => error_response(status_for(&e), e),
+    }
+}
+
+#[cfg(feature = "partial-updates")]
+async fn patch_config<T>(State(api): State<Arc<AdminApi<T>>>, Json(patch): Json<Value>) -> Response
+where
+    T: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+{
+    let context = UpdateContext::new(UpdateOrigin::Admin);
+    match api.config.apply_patch_with_context(patch, &context).await {
+        Ok(()) => {
+            api.record("admin: patch").await;
+            Json(json!({ "status": "patched" })).into_response()
+        }
+        Err(e) => error_response(status_for(&e), e),
+    }
+}
+
+#[cfg(feature = "rollback")]
+async fn get_history<T>(State(api): State<Arc<AdminApi<T>>>) -> Response
+where
+    T: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+{
+    let Some(history) = &api.history else {
+        return error_response(StatusCode::NOT_FOUND, "History is not enabled");
+    };
+
+    let versions: Vec<Value> = history
+        .get_all()
+        .await
+        .iter()
+        .map(|v| {
+            json!({
+                "version": v.version,
+                "timestamp": v.timestamp.to_rfc3339(),
+                "source": v.source,
+            })
+        })
+        .collect();
+
+    Json(json!({ "versions": versions })).into_response()
+}
+
+#[cfg(feature = "rollback")]
+async fn rollback_config<T>(
+    State(api): State<Arc<AdminApi<T>>>,
+    Path(version): Path<u64>,
+) -> Response
+where
+    T: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+{
+    let Some(history) = &api.history else {
+        return error_response(StatusCode::NOT_FOUND, "History is not enabled");
+    };
+
+    if history.get_version(version).await.is_none() {
+        return error_response(
+            StatusCode::NOT_FOUND,
+            format!("Version {} not found in history", version),
+        );
+    }
+
+    match api.config.rollback_to_version(history, version).await {
+        Ok(()) => Json(json!({ "status": "rolled_back", "version": version })).into_response(),
+        Err(e) => error_response(status_for(&e), e),
+    }
+}
+
+/// Build the router for [`ConfigRegistry::admin_router`].
+pub(crate) fn registry_router(registry: ConfigRegistry) -> Router {
+    Router::new()
+        .route("/configs", get(list_configs))
+        .route("/configs/{name}", get(get_registered_config))
+        .route("/configs/{name}/reload", post(reload_registered_config))
+        .with_state(registry)
+}
+
+fn not_registered(name: &str) -> Response {
+    error_response(
+        StatusCode::NOT_FOUND,
+        format!("No configuration named '{}' is registered", name),
+    )
+}
+
+async fn list_configs(State(registry): State<ConfigRegistry>) -> Response {
+    Json(json!({ "configs": registry.names() })).into_response()
+}
+
+async fn get_registered_config(
+    State(registry): State<ConfigRegistry>,
+    Path(name): Path<String>,
+) -> Response {
+    match registry.redacted_json(&name) {
+        Some(Ok(mut value)) => {
+            let fields = DEFAULT_REDACTED_FIELDS.map(str::to_string);
+            redact(&mut value, &fields);
+            Json(value).into_response()
+        }
+        Some(Err(e)) => error_response(status_for(&e), e),
+        None => not_registered(&name),
+    }
+}
+
+async fn reload_registered_config(
+    State(registry): State<ConfigRegistry>,
+    Path(name): Path<String>,
+) -> Response {
+    if !registry.names().contains(&name) {
+        return not_registered(&name);
+    }
+    match registry.reload(&name).await {
+        Ok(()) => Json(json!({ "status": "reloaded" })).into_response(),
+        Err(e) => error_response(status_for(&e), e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::{Method, Request};
+    use serde::Deserialize;
+    use tower::ServiceExt;
+
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+    struct TestConfig {
+        port: u16,
+        database: DatabaseConfig,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+    struct DatabaseConfig {
+        url: String,
+        password: String,
+        credentials: crate::secrets::Secret<String>,
+    }
+
+    fn test_config() -> HotswapConfig<TestConfig> {
+        HotswapConfig::new(TestConfig {
+            port: 8080,
+            database: DatabaseConfig {
+                url: "postgres://localhost/db".to_string(),
+                password: "hunter2".to_string(),
+                credentials: crate::secrets::Secret::new("s3cr3t".to_string()),
+            },
+        })
+    }
+
+    async fn send(
+        router: Router,
+        method: Method,
+        uri: &str,
+        body: Option<Value>,
+    ) -> (StatusCode, Value) {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(match body {
+                Some(body) => Body::from(body.to_string()),
+                None => Body::empty(),
+            })
+            .unwrap();
+
+        let response = router.oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (
+            status,
+            serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+        )
+    }
+
+    #[test]
+    fn test_redact_nested() {
+        let mut value = json!({
+            "database": { "Password": "x", "url": "y" },
+            "tokens": [{ "token": "z" }]
+        });
+        redact(&mut value, &["password".to_string(), "token".to_string()]);
+
+        assert_eq!(value["database"]["Password"], REDACTED);
+        assert_eq!(value["database"]["url"], "y");
+        assert_eq!(value["tokens"][0]["token"], REDACTED);
+    }
+
+    #[test]
+    fn test_change_to_json() {
+        let current = test_config().get();
+        let mut proposed = (*current).clone();
+        proposed.port = 9090;
+        proposed.database.password = "correct-horse".to_string();
+
+        let diff = crate::core::ConfigDiff::new(current, Arc::new(proposed)).unwrap();
+        let fields = ["password".to_string()];
+        let changes: Vec<Value> = diff
+            .changes()
+            .iter()
+            .map(|change| change_to_json(change, &fields))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                json!({ "path": "database.password", "kind": "modified", "old": REDACTED, "new": REDACTED }),
+                json!({ "path": "port", "kind": "modified", "old": 8080, "new": 9090 }),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_is_redacted() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.yaml");
+        let write = |password: &str| {
+            std::fs::write(
+                &config_path,
+                format!(
+                    "port: 8080\ndatabase:\n  url: postgres://localhost/db\n  password: {}\n  credentials: s3cr3t\n",
+                    password
+                ),
+            )
+            .unwrap();
+        };
+        write("hunter2");
+        let config = HotswapConfig::<TestConfig>::builder()
+            .with_file(&config_path)
+            .with_file_watch(false)
+            .build()
+            .await
+            .unwrap();
+
+        write("correct-horse");
+        let router = AdminApi::new(config).router();
+        let (status, body) = send(router, Method::POST, "/config/reload/dry-run", None).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            body["changes"],
+            json!([{ "path": "database.password", "kind": "modified", "old": REDACTED, "new": REDACTED }])
+        );
+        let body = body.to_string();
+        assert!(!body.contains("hunter2") && !body.contains("correct-horse"));
+    }
+
+    #[tokio::test]
+    async fn test_get_config_is_redacted() {
+        let router = AdminApi::new(test_config()).router();
+        let (status, body) = send(router, Method::GET, "/config", None).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["port"], 8080);
+        assert_eq!(body["database"]["password"], REDACTED);
+        assert_eq!(body["database"]["credentials"], REDACTED);
+    }
+
+    #[tokio::test]
+    async fn test_reload_without_loader_fails() {
+        let router = AdminApi::new(test_config()).router();
+        let (status, body) = send(router, Method::POST, "/config/reload", None).await;
+
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(body["error"].as_str().unwrap().contains("No loader"));
+    }
+
+    #[tokio::test]
+    async fn test_activate_unprepared_version() {
+        let router = AdminApi::new(test_config()).router();
+        let (status, body) = send(router, Method::POST, "/config/activate/v2", None).await;
+
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert!(body["error"].as_str().unwrap().contains("'v2'"));
+    }
+
+    #[tokio::test]
+    async fn test_freeze_and_unfreeze() {
+        let config = test_config();
+        let router = AdminApi::new(config.clone()).router();
+
+        let reason = json!({ "reason": "incident" });
+        let (status, body) =
+            send(router.clone(), Method::POST, "/config/freeze", Some(reason)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["reason"], "incident");
+        assert!(config.is_frozen());
+
+        let (status, body) = send(router.clone(), Method::POST, "/config/reload", None).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert!(body["error"].as_str().unwrap().contains("incident"));
+
+        // The rejected reload is retried on unfreeze, which fails without a loader
+        let (status, _) = send(router, Method::POST, "/config/unfreeze", None).await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(!config.is_frozen());
+    }
+
+    #[cfg(feature = "partial-updates")]
+    #[tokio::test]
+    async fn test_patch_config() {
+        let config = test_config();
+        let router = AdminApi::new(config.clone()).router();
+
+        let patch = json!([{ "op": "replace", "path": "/port", "value": 9090 }]);
+        let (status, _) = send(router.clone(), Method::PATCH, "/config", Some(patch)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(config.get().port, 9090);
+
+        let bad_patch = json!([{ "op": "replace", "path": "/port", "value": "abc" }]);
+        let (status, _) = send(router, Method::PATCH, "/config", Some(bad_patch)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(config.get().port, 9090);
+    }
+
+    #[cfg(feature = "partial-updates")]
+    #[tokio::test]
+    async fn test_patch_denied_by_policy() {
+        use crate::core::PolicyDecision;
+
+        let config = test_config().with_update_policy(Some(Arc::new(
+            |paths: &[String], context: &UpdateContext| {
+                if context.origin == UpdateOrigin::Admin
+                    && paths.iter().any(|p| p.starts_with("/database"))
+                {
+                    PolicyDecision::deny("database settings are not editable")
+                } else {
+                    PolicyDecision::Allow
+                }
+            },
+        )));
+        let router = AdminApi::new(config.clone()).router();
+
+        let patch =
+            json!([{ "op": "replace", "path": "/database/url", "value": "postgres://db2" }]);
+        let (status, body) = send(router, Method::PATCH, "/config", Some(patch)).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert!(
+            body["error"]
+                .as_str()
+                .unwrap()
+                .contains("database settings are not editable")
+        );
+        assert_eq!(config.get().database.url, "postgres://localhost/db");
+    }
+
+    #[tokio::test]
+    async fn test_registry_router() {
+        let registry = ConfigRegistry::new();
+        registry.register("app", test_config()).unwrap();
+        let router = registry.admin_router();
+
+        let (status, body) = send(router.clone(), Method::GET, "/configs", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["configs"], json!(["app"]));
+
+        let (status, body) = send(router.clone(), Method::GET, "/configs/app", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["port"], 8080);
+        assert_eq!(body["database"]["password"], REDACTED);
+
+        let (status, _) = send(router.clone(), Method::POST, "/configs/app/reload", None).await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+
+        let (status, _) = send(router, Method::GET, "/configs/missing", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[cfg(feature = "rollback")]
+    #[tokio::test]
+    async fn test_history_and_rollback() {
+        let config = test_config();
+        let history = ConfigHistory::new(10);
+        history
+            .record(config.get(), Some("initial".to_string()))
+            .await;
+
+        let mut updated = (*config.get()).clone();
+        updated.port = 9090;
+        config.update(updated).await.unwrap();
+        history
+            .record(config.get(), Some("update".to_string()))
+            .await;
+
+        let router = AdminApi::new(config.clone()).with_history(history).router();
+
+        let (status, body) = send(router.clone(), Method::GET, "/config/history", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["versions"].as_array().unwrap().len(), 2);
+
+        let (status, _) = send(router.clone(), Method::POST, "/config/rollback/0", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(config.get().port, 8080);
+
+        let (status, _) = send(router, Method::POST, "/config/rollback/42", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
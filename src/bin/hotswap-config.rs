@@ -0,0 +1,239 @@
+//! Command-line tool for checking and inspecting configuration files.
+//!
+//! Loads files the same way an application using [`HotswapConfig::builder`]
+//! would, without knowing the application's config type:
+//!
+//! - `validate`: check that files load, merge, and match a JSON Schema,
+//!   reporting every error
+//! - `render`: print the effective merged config, including env overrides
+//! - `diff`: compare two sets of files key by key
+//! - `watch`: print changes as files are edited
+//!
+//! Run with: `cargo run --features cli -- render config/default.yaml config/prod.yaml`
+
+use clap::{Arg, ArgAction, ArgMatches, Command, value_parser};
+use hotswap_config::core::{ConfigDiff, HotswapConfigBuilder, SnapshotFormat};
+use hotswap_config::prelude::*;
+use serde_json::Value;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::{Arc, Mutex};
+
+/// Exit code for invalid configs, or differing configs in `diff`.
+const EXIT_FAILURE: u8 = 1;
+/// Exit code for errors that prevented the check from running.
+const EXIT_ERROR: u8 = 2;
+
+fn cli() -> Command {
+    let files = Arg::new("files")
+        .help("Config files or conf.d directories, lowest precedence first")
+        .value_parser(value_parser!(PathBuf))
+        .num_args(1..)
+        .required(true);
+    let env_prefix = Arg::new("env-prefix")
+        .long("env-prefix")
+        .value_name("PREFIX")
+        .help("Apply environment variable overrides with this prefix, e.g. APP");
+    let env_separator = Arg::new("env-separator")
+        .long("env-separator")
+        .value_name("SEP")
+        .default_value("__")
+        .help("Separator for nested keys in environment variable names");
+
+    Command::new("hotswap-config")
+        .about("Validate, render, diff, and watch hotswap-config configuration files")
+        .version(env!("CARGO_PKG_VERSION"))
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .subcommand(
+            Command::new("validate")
+                .about("Check that files load and merge, optionally against a JSON Schema")
+                .arg(files.clone())
+                .arg(env_prefix.clone())
+                .arg(env_separator.clone())
+                .arg(
+                    Arg::new("schema")
+                        .long("schema")
+                        .value_name("FILE")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("JSON Schema the merged config must match"),
+                ),
+        )
+        .subcommand(
+            Command::new("render")
+                .about("Print the effective merged configuration")
+                .arg(files.clone())
+                .arg(env_prefix.clone())
+                .arg(env_separator.clone())
+                .arg(
+                    Arg::new("format")
+                        .long("format")
+                        .short('f')
+                        .value_parser(["yaml", "toml", "json"])
+                        .default_value("yaml")
+                        .help("Output format"),
+                ),
+        )
+        .subcommand(
+            Command::new("diff")
+                .about("Compare two sets of config files; exits 1 if they differ")
+                .arg(
+                    Arg::new("from")
+                        .long("from")
+                        .value_name("FILE")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(1..)
+                        .required(true)
+                        .help("Files making up the old configuration"),
+                )
+                .arg(
+                    Arg::new("to")
+                        .long("to")
+                        .value_name("FILE")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(1..)
+                        .required(true)
+                        .help("Files making up the new configuration"),
+                )
+                .arg(env_prefix.clone())
+                .arg(env_separator.clone()),
+        )
+        .subcommand(
+            Command::new("watch")
+                .about("Print changes as the files are edited, until interrupted")
+                .arg(files)
+                .arg(env_prefix)
+                .arg(env_separator)
+                .arg(
+                    Arg::new("quiet")
+                        .long("quiet")
+                        .short('q')
+                        .action(ArgAction::SetTrue)
+                        .help("Don't print the initial configuration"),
+                ),
+        )
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let matches = cli().get_matches();
+    let result = match matches.subcommand() {
+        Some(("validate", args)) => validate(args).await,
+        Some(("render", args)) => render(args).await,
+        Some(("diff", args)) => diff(args).await,
+        Some(("watch", args)) => watch(args).await,
+        _ => unreachable!("a subcommand is required"),
+    };
+
+    match result {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::from(exit_code(&e))
+        }
+    }
+}
+
+/// Exit code for an error: a failure if the config was loaded but is
+/// invalid, an error otherwise.
+fn exit_code(error: &ConfigError) -> u8 {
+    match error.root() {
+        ConfigError::ValidationError(_)
+        | ConfigError::DeserializationError(_)
+        | ConfigError::UnknownKeys(_) => EXIT_FAILURE,
+        ConfigError::Multiple(errors) if errors.iter().all(|e| exit_code(e) == EXIT_FAILURE) => {
+            EXIT_FAILURE
+        }
+        _ => EXIT_ERROR,
+    }
+}
+
+/// A builder loading `files` in order, with env overrides if requested.
+fn builder(args: &ArgMatches, files: &str) -> HotswapConfigBuilder<Value> {
+    let mut builder = HotswapConfig::<Value>::builder();
+    for path in args.get_many::<PathBuf>(files).into_iter().flatten() {
+        builder = if path.is_dir() {
+            builder.with_directory(path)
+        } else {
+            builder.with_file(path)
+        };
+    }
+    if let Some(prefix) = args.get_one::<String>("env-prefix") {
+        let separator = args.get_one::<String>("env-separator").unwrap();
+        builder = builder.with_env_overrides(prefix, separator);
+    }
+    builder
+}
+
+async fn validate(args: &ArgMatches) -> Result<ExitCode> {
+    // Report everything wrong at once
+    let mut builder = builder(args, "files").with_error_mode(ErrorMode::CollectAll);
+    if let Some(path) = args.get_one::<PathBuf>("schema") {
+        let schema = std::fs::read_to_string(path)?;
+        let schema: Value = serde_json::from_str(&schema).map_err(|e| {
+            ConfigError::LoadError(format!("Invalid schema {}: {}", path.display(), e))
+        })?;
+        builder = builder.with_json_schema(schema);
+    }
+
+    builder.build().await?;
+    println!("ok");
+    Ok(ExitCode::SUCCESS)
+}
+
+async fn render(args: &ArgMatches) -> Result<ExitCode> {
+    let format = match args.get_one::<String>("format").map(String::as_str) {
+        Some("toml") => SnapshotFormat::Toml,
+        Some("json") => SnapshotFormat::Json,
+        _ => SnapshotFormat::Yaml,
+    };
+
+    let config = builder(args, "files").build().await?;
+    println!("{}", config.export_snapshot(format)?.trim_end());
+    Ok(ExitCode::SUCCESS)
+}
+
+async fn diff(args: &ArgMatches) -> Result<ExitCode> {
+    let from = builder(args, "from").build().await?;
+    let to = builder(args, "to").build().await?;
+
+    let diff = ConfigDiff::new(from.get(), to.get())?;
+    print!("{}", diff);
+    Ok(if diff.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(EXIT_FAILURE)
+    })
+}
+
+async fn watch(args: &ArgMatches) -> Result<ExitCode> {
+    let config = builder(args, "files").with_file_watch(true).build().await?;
+    if !args.get_flag("quiet") {
+        println!(
+            "{}",
+            config.export_snapshot(SnapshotFormat::Yaml)?.trim_end()
+        );
+    }
+
+    let previous = Arc::new(Mutex::new(config.get()));
+    let handle = config.clone();
+    let _subscription = config
+        .subscribe(move || {
+            let current = handle.get();
+            let previous = std::mem::replace(&mut *previous.lock().unwrap(), Arc::clone(&current));
+            match ConfigDiff::new(previous, current) {
+                Ok(diff) if !diff.is_empty() => {
+                    println!("--- reloaded\n{}", diff.to_string().trim_end())
+                }
+                Ok(_) => {}
+                Err(e) => eprintln!("error: {}", e),
+            }
+        })
+        .await;
+    let _errors = config
+        .subscribe_errors(|e| eprintln!("error: reload failed: {}", e))
+        .await;
+
+    std::future::pending::<()>().await;
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,214 @@
+//! Synchronous facade for applications without an async runtime.
+//!
+//! [`HotswapConfigBuilder::build_blocking`](crate::core::HotswapConfigBuilder::build_blocking)
+//! returns a [`HotswapConfig`] whose `reload` and `update` block the calling
+//! thread. The handle owns a single background thread that runs file
+//! watching, scheduled reloads, and other background work, so the
+//! application never has to start or enter a runtime itself.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use hotswap_config::prelude::*;
+//! use serde::Deserialize;
+//!
+//! #[derive(Debug, Deserialize, Clone)]
+//! struct AppConfig {
+//!     port: u16,
+//! }
+//!
+//! fn main() -> Result<()> {
+//!     let config = HotswapConfig::<AppConfig>::builder()
+//!         .with_file("config.yaml")
+//!         .with_file_watch(true)
+//!         .build_blocking()?;
+//!
+//!     println!("Port: {}", config.get().port);
+//!     config.reload()?;
+//!     Ok(())
+//! }
+//! ```
+
+use crate::core;
+use crate::error::{ConfigError, Result};
+use serde::de::DeserializeOwned;
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+
+/// Start the background thread that drives a blocking handle.
+pub(crate) fn runtime() -> Result<Arc<Runtime>> {
+    tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .thread_name("hotswap-config")
+        .enable_all()
+        .build()
+        .map(Arc::new)
+        .map_err(|e| ConfigError::Other(format!("Failed to start background thread: {}", e)))
+}
+
+/// A configuration handle with blocking reloads and updates.
+///
+/// Reads are the same lock-free [`get`](Self::get) as the async handle.
+/// Cloning is cheap and every clone shares the configuration and the
+/// background thread, which stops when the last clone is dropped.
+///
+/// Methods that block must not be called from within an async runtime.
+pub struct HotswapConfig<T> {
+    inner: core::HotswapConfig<T>,
+    runtime: Arc<Runtime>,
+}
+
+impl<T> HotswapConfig<T> {
+    /// Wrap an async handle built on `runtime`.
+    pub(crate) fn new(inner: core::HotswapConfig<T>, runtime: Arc<Runtime>) -> Self {
+        Self { inner, runtime }
+    }
+
+    /// Get a reference-counted handle to the current configuration.
+    pub fn get(&self) -> Arc<T> {
+        self.inner.get()
+    }
+
+    /// Reload configuration from all sources, blocking until it is applied.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`core::HotswapConfig::reload`]; the current config is kept.
+    pub fn reload(&self) -> Result<()>
+    where
+        T: DeserializeOwned + Clone + Send + Sync + 'static,
+    {
+        self.runtime.block_on(self.inner.reload())
+    }
+
+    /// Replace the configuration, blocking until subscribers are notified.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`core::HotswapConfig::update`]; the current config is kept.
+    pub fn update(&self, new_config: T) -> Result<()>
+    where
+        T: Send + Sync + 'static,
+    {
+        self.runtime.block_on(self.inner.update(new_config))
+    }
+
+    /// Subscribe to configuration changes.
+    ///
+    /// The callback runs on the background thread after each swap. Drop the
+    /// returned handle to unsubscribe.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use hotswap_config::blocking::HotswapConfig;
+    /// # fn example(config: HotswapConfig<u16>) {
+    /// let handle = config.subscribe(|| println!("Configuration changed!"));
+    /// drop(handle);
+    /// # }
+    /// ```
+    #[cfg(feature = "file-watch")]
+    pub fn subscribe<F>(&self, callback: F) -> SubscriptionHandle
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        SubscriptionHandle {
+            _handle: self.runtime.block_on(self.inner.subscribe(callback)),
+        }
+    }
+
+    /// Get the underlying async handle, for methods without a blocking
+    /// counterpart.
+    pub fn as_async(&self) -> &core::HotswapConfig<T> {
+        &self.inner
+    }
+}
+
+impl<T> Clone for HotswapConfig<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            runtime: Arc::clone(&self.runtime),
+        }
+    }
+}
+
+/// Handle for a subscription made through a blocking [`HotswapConfig`].
+///
+/// When the handle is dropped, the subscription is removed.
+#[cfg(feature = "file-watch")]
+pub struct SubscriptionHandle {
+    _handle: crate::notify::SubscriptionHandle,
+}
+
+#[cfg(all(test, feature = "file-watch"))]
+mod tests {
+    use crate::prelude::*;
+    use serde::Deserialize;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[derive(Debug, Deserialize, Clone, PartialEq)]
+    struct TestConfig {
+        port: u16,
+    }
+
+    #[test]
+    fn test_blocking_reload_update_and_subscribe() {
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.yaml");
+        fs::write(&path, "port: 8080\n").unwrap();
+
+        let config = HotswapConfig::<TestConfig>::builder()
+            .with_file(&path)
+            .build_blocking()
+            .unwrap();
+        assert_eq!(config.get().port, 8080);
+
+        let notified = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&notified);
+        let handle = config.subscribe(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+
+        fs::write(&path, "port: 9090\n").unwrap();
+        config.reload().unwrap();
+        assert_eq!(config.get().port, 9090);
+
+        config.update(TestConfig { port: 7070 }).unwrap();
+        assert_eq!(config.get().port, 7070);
+        assert_eq!(notified.load(Ordering::SeqCst), 2);
+
+        drop(handle);
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        config.update(TestConfig { port: 6060 }).unwrap();
+        assert_eq!(notified.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_blocking_file_watch() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.yaml");
+        fs::write(&path, "port: 8080\n").unwrap();
+
+        let config = HotswapConfig::<TestConfig>::builder()
+            .with_file(&path)
+            .with_file_watch(true)
+            .with_watch_debounce(std::time::Duration::from_millis(50))
+            .build_blocking()
+            .unwrap();
+
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        fs::write(&path, "port: 9090\n").unwrap();
+        for _ in 0..300 {
+            if config.get().port == 9090 {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert_eq!(config.get().port, 9090);
+    }
+}
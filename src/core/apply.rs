@@ -0,0 +1,194 @@
+//! Transactional subscribers that can reject a new configuration.
+//!
+//! Applying a config is two-phase: every transactional subscriber is handed the
+//! new config before it is swapped in. If one fails, the subscribers that had
+//! already applied it are handed the previous config again, and the swap never
+//! happens, so readers never see a config a subscriber couldn't apply.
+
+use crate::error::{ApplyError, ConfigError, Result};
+use std::sync::{Arc, Mutex, Weak};
+
+/// Callback that applies a new configuration.
+type ApplyCallback<T> = Arc<dyn Fn(&T) -> std::result::Result<(), ApplyError> + Send + Sync>;
+
+/// A registered transactional subscriber.
+struct Applier<T> {
+    id: usize,
+    name: String,
+    callback: ApplyCallback<T>,
+}
+
+struct ApplyRegistryInner<T> {
+    appliers: Vec<Applier<T>>,
+    next_id: usize,
+}
+
+/// Transactional subscribers of a configuration handle.
+pub(crate) struct ApplyRegistry<T> {
+    inner: Arc<Mutex<ApplyRegistryInner<T>>>,
+}
+
+impl<T> ApplyRegistry<T> {
+    pub(crate) fn new() -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(ApplyRegistryInner {
+                appliers: Vec::new(),
+                next_id: 0,
+            })),
+        }
+    }
+
+    /// Register a transactional subscriber, removed when the handle is dropped.
+    pub(crate) fn subscribe<F>(&self, name: String, callback: F) -> ApplyHandle
+    where
+        F: Fn(&T) -> std::result::Result<(), ApplyError> + Send + Sync + 'static,
+        T: 'static,
+    {
+        let mut inner = self.inner.lock().unwrap();
+        let id = inner.next_id;
+        inner.next_id += 1;
+        inner.appliers.push(Applier {
+            id,
+            name,
+            callback: Arc::new(callback),
+        });
+
+        let registry: Weak<Mutex<ApplyRegistryInner<T>>> = Arc::downgrade(&self.inner);
+        ApplyHandle {
+            unsubscribe: Some(Box::new(move || {
+                if let Some(registry) = registry.upgrade() {
+                    registry
+                        .lock()
+                        .unwrap()
+                        .appliers
+                        .retain(|applier| applier.id != id);
+                }
+            })),
+        }
+    }
+
+    /// Hand `new` to every transactional subscriber in registration order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigError::ApplyFailed`] naming the first subscriber that
+    /// rejected `new`, after handing `previous` back to the subscribers that
+    /// had already applied it.
+    pub(crate) fn apply(&self, previous: &T, new: &T) -> Result<()> {
+        // Callbacks run without the lock, so they may (un)subscribe
+        let appliers: Vec<(String, ApplyCallback<T>)> = self
+            .inner
+            .lock()
+            .unwrap()
+            .appliers
+            .iter()
+            .map(|applier| (applier.name.clone(), Arc::clone(&applier.callback)))
+            .collect();
+
+        for (index, (name, callback)) in appliers.iter().enumerate() {
+            let Err(e) = callback(new) else { continue };
+
+            #[cfg(feature = "tracing")]
+            tracing::warn!(subscriber = %name, error = %e, "Subscriber rejected configuration, reverting");
+
+            // Revert in reverse order; failures here can't be undone, only reported
+            for (applied_name, applied) in appliers[..index].iter().rev() {
+                if let Err(revert_err) = applied(previous) {
+                    #[cfg(feature = "tracing")]
+                    tracing::error!(
+                        subscriber = %applied_name,
+                        error = %revert_err,
+                        "Subscriber failed to re-apply previous configuration"
+                    );
+                    #[cfg(not(feature = "tracing"))]
+                    eprintln!(
+                        "Subscriber '{}' failed to re-apply previous configuration: {}",
+                        applied_name, revert_err
+                    );
+                }
+            }
+
+            return Err(ConfigError::ApplyFailed {
+                subscriber: name.clone(),
+                reason: e.to_string(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl<T> Clone for ApplyRegistry<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+/// Handle for a transactional subscription; dropping it unsubscribes.
+#[must_use = "dropping the handle unsubscribes immediately"]
+pub struct ApplyHandle {
+    unsubscribe: Option<Box<dyn FnOnce() + Send + Sync>>,
+}
+
+impl Drop for ApplyHandle {
+    fn drop(&mut self) {
+        if let Some(unsubscribe) = self.unsubscribe.take() {
+            unsubscribe();
+        }
+    }
+}
+
+impl std::fmt::Debug for ApplyHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ApplyHandle").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_reverts_earlier_subscribers() {
+        let registry = ApplyRegistry::<u16>::new();
+        let applied = Arc::new(Mutex::new(Vec::new()));
+
+        let log = Arc::clone(&applied);
+        let _first = registry.subscribe("first".to_string(), move |port: &u16| {
+            log.lock().unwrap().push(*port);
+            Ok(())
+        });
+        let _second = registry.subscribe("tls".to_string(), |port: &u16| {
+            if *port == 443 {
+                return Err(ApplyError::new("certificate failed to load"));
+            }
+            Ok(())
+        });
+
+        registry.apply(&80, &8080).unwrap();
+        match registry.apply(&8080, &443) {
+            Err(ConfigError::ApplyFailed { subscriber, reason }) => {
+                assert_eq!(subscriber, "tls");
+                assert!(reason.contains("certificate"));
+            }
+            other => panic!("expected apply failure, got {:?}", other),
+        }
+
+        // The first subscriber was handed the new config, then the previous one
+        assert_eq!(*applied.lock().unwrap(), vec![8080, 443, 8080]);
+    }
+
+    #[test]
+    fn test_dropping_handle_unsubscribes() {
+        let registry = ApplyRegistry::<u16>::new();
+        let handle = registry.subscribe("reject".to_string(), |_: &u16| {
+            Err(ApplyError::new("always"))
+        });
+        assert!(registry.apply(&1, &2).is_err());
+
+        drop(handle);
+        assert!(registry.apply(&1, &2).is_ok());
+    }
+}
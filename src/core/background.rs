@@ -0,0 +1,60 @@
+//! Background tasks of a handle, and stopping them.
+
+use std::future::Future;
+use std::sync::Mutex;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+/// Tasks spawned for a handle and its clones: file watching, scheduled
+/// refreshes, source streams, and startup retries.
+///
+/// Every task stops at its next await point once the token is cancelled.
+pub(crate) struct BackgroundTasks {
+    token: CancellationToken,
+    tasks: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl BackgroundTasks {
+    /// Track tasks stopped by cancelling `token`.
+    pub(crate) fn new(token: CancellationToken) -> Self {
+        Self {
+            token,
+            tasks: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// The token that stops the tasks.
+    pub(crate) fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+
+    /// Spawn `task`, to run until it finishes or the tasks are stopped.
+    pub(crate) fn spawn(&self, task: impl Future<Output = ()> + Send + 'static) {
+        let token = self.token.clone();
+        let handle = tokio::spawn(async move {
+            tokio::select! {
+                _ = token.cancelled() => {}
+                _ = task => {}
+            }
+        });
+        let mut tasks = self.tasks.lock().unwrap();
+        tasks.retain(|task| !task.is_finished());
+        tasks.push(handle);
+    }
+
+    /// Stop the tasks and wait for them to finish.
+    pub(crate) async fn shutdown(&self) {
+        self.token.cancel();
+        let tasks = std::mem::take(&mut *self.tasks.lock().unwrap());
+        for task in tasks {
+            // A task that panicked has stopped all the same
+            let _ = task.await;
+        }
+    }
+}
+
+impl Default for BackgroundTasks {
+    fn default() -> Self {
+        Self::new(CancellationToken::new())
+    }
+}
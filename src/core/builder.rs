@@ -1,12 +1,30 @@
 //! Builder for constructing HotswapConfig instances.
 
-use crate::core::{ConfigLoader, HotswapConfig};
+#[cfg(feature = "tokio")]
+use crate::core::VerifyFuture;
+use crate::core::config_handle::{PostLoadHook, PreSwapHook, PrepareHandler, RestartHandler};
+#[cfg(feature = "tokio")]
+use crate::core::config_handle::{ReloadErrorHandler, report_reload_error};
+use crate::core::{
+    AsyncValidator, ConfigLoader, ErrorMode, HotswapConfig, ImmutableKeys, Migrations,
+    PolicyDecision, PreLoadHook, SectionValidator, UnknownKeys, UpdateContext, UpdatePolicy,
+};
+#[cfg(feature = "tokio")]
+use crate::core::{ReloadReason, Turn};
+#[cfg(feature = "tokio")]
+use crate::error::VerificationError;
 use crate::error::{ConfigError, Result, ValidationError};
-use crate::sources::{ConfigSource, EnvSource, FileSource};
+use crate::metrics::MetricsRecorder;
+use crate::sources::{
+    ConfigSource, DefaultsSource, DirectorySource, EnvSource, FileSource, SourceLimits,
+};
+use serde::Serialize;
 use serde::de::DeserializeOwned;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+#[cfg(feature = "metrics")]
+use crate::metrics::ConfigMetrics;
 #[cfg(feature = "metrics")]
 use opentelemetry::metrics::Meter;
 
@@ -15,19 +33,35 @@ use crate::core::Validate;
 
 #[cfg(feature = "file-watch")]
 use crate::notify::ConfigWatcher;
-#[cfg(feature = "file-watch")]
 use std::time::Duration;
 
-/// Type alias for any-based validator functions used during building.
-type AnyValidator =
-    Arc<dyn Fn(&dyn std::any::Any) -> std::result::Result<(), ValidationError> + Send + Sync>;
-
 /// Type alias for typed validator functions.
 type TypedValidator<T> = Arc<dyn Fn(&T) -> std::result::Result<(), ValidationError> + Send + Sync>;
 
-/// Builder for constructing a `HotswapConfig` instance.
+/// Type alias for typed transition validator functions.
+type TypedTransitionValidator<T> =
+    Arc<dyn Fn(&T, &T) -> std::result::Result<(), ValidationError> + Send + Sync>;
+
+/// Default environment variable selecting the environment for `with_env_file`.
+const DEFAULT_ENV_SELECTOR: &str = "APP_ENV";
+
+/// A file or set of files added with the builder, in priority order.
+enum FileSpec {
+    File(FileSource),
+    Directory(DirectorySource),
+    /// A path template containing `{env}`, resolved at build time
+    EnvFile(String),
+    /// The default and active profile files in the profile directory
+    Profiles,
+}
+
+/// Builder for constructing a `HotswapConfig<T>` instance.
 ///
 /// Provides a fluent interface for configuring all aspects of configuration loading.
+/// The builder is typed by the configuration it builds, so validators and
+/// fallback defaults for the wrong type are rejected at compile time. Start
+/// one with `HotswapConfig::<AppConfig>::builder()`, or let `T` be inferred
+/// from how the handle is used.
 ///
 /// # Examples
 ///
@@ -41,44 +75,165 @@ type TypedValidator<T> = Arc<dyn Fn(&T) -> std::result::Result<(), ValidationErr
 /// }
 ///
 /// # async fn example() -> Result<()> {
-/// let config = HotswapConfig::builder()
+/// let config = HotswapConfig::<AppConfig>::builder()
 ///     .with_file("config/default.yaml")
 ///     .with_file("config/production.yaml")
 ///     .with_env_overrides("APP", "__")
-///     .build::<AppConfig>()
+///     .build()
 ///     .await?;
 /// # Ok(())
 /// # }
 /// ```
-pub struct HotswapConfigBuilder {
-    file_paths: Vec<PathBuf>,
+pub struct HotswapConfigBuilder<T> {
+    files: Vec<FileSpec>,
+    environment: Option<String>,
+    env_selector: String,
+    profile_dir: PathBuf,
     env_prefix: Option<String>,
     env_separator: Option<String>,
     custom_sources: Vec<Box<dyn ConfigSource>>,
-    validator: Option<AnyValidator>,
+    /// Overlay sources and how often each is refreshed on its own
+    overlays: Vec<(Box<dyn ConfigSource>, Option<Duration>)>,
+    #[cfg(feature = "dotenv")]
+    dotenv_paths: Vec<PathBuf>,
+    snapshot_fallback: Option<PathBuf>,
+    validators: Vec<TypedValidator<T>>,
+    section_validators: Vec<SectionValidator>,
+    transition_validators: Vec<TypedTransitionValidator<T>>,
+    async_validators: Vec<AsyncValidator<T>>,
+    migrations: Migrations,
+    pre_load_hooks: Vec<PreLoadHook>,
+    post_load_hooks: Vec<PostLoadHook<T>>,
+    pre_swap_hooks: Vec<PreSwapHook<T>>,
+    interpolate: bool,
+    #[cfg(feature = "expressions")]
+    expressions: bool,
+    unknown_keys: UnknownKeys,
+    error_mode: ErrorMode,
+    source_limits: SourceLimits,
+    immutable_paths: Vec<String>,
+    immutable_keys: ImmutableKeys,
+    restart_handler: Option<RestartHandler>,
+    prepare_handler: Option<PrepareHandler>,
+    update_policy: Option<UpdatePolicy>,
     #[cfg(feature = "file-watch")]
     enable_file_watch: bool,
     #[cfg(feature = "file-watch")]
     watch_debounce: Duration,
-    #[cfg(feature = "metrics")]
-    meter: Option<Meter>,
+    /// Watcher shared with other handles of a registry, used instead of one
+    /// of this handle's own
+    #[cfg(feature = "file-watch")]
+    shared_watcher: Option<Arc<crate::core::SharedWatcher>>,
+    #[cfg(feature = "tokio")]
+    reload_error_handler: Option<ReloadErrorHandler>,
+    /// Initial and maximum delay between startup retries
+    #[cfg(feature = "tokio")]
+    startup_retry: (Duration, Duration),
+    #[cfg(feature = "tokio")]
+    reload_schedule: Option<crate::core::Schedule>,
+    #[cfg(feature = "tokio")]
+    min_reload_interval: Option<Duration>,
+    #[cfg(feature = "signal")]
+    reload_signals: Vec<crate::core::Signal>,
+    #[cfg(feature = "webhook")]
+    reload_webhook: Option<crate::core::webhook::ReloadWebhook>,
+    #[cfg(feature = "tokio")]
+    reload_triggers: Vec<Box<dyn crate::core::ReloadTrigger>>,
+    /// Stops the background tasks when cancelled
+    #[cfg(feature = "tokio")]
+    cancellation_token: Option<tokio_util::sync::CancellationToken>,
+    #[cfg(feature = "json-schema")]
+    json_schema: Option<serde_json::Value>,
+    #[cfg(feature = "signing")]
+    signature_verifier: Option<crate::signing::SignatureVerifier>,
+    #[cfg(feature = "encryption")]
+    key_provider: Option<Arc<dyn crate::encryption::KeyProvider>>,
+    #[cfg(feature = "encryption")]
+    field_decryptor: Option<Arc<dyn crate::encryption::Decryptor>>,
+    #[cfg(feature = "templating")]
+    template: Option<crate::templating::TemplateContext>,
+    #[cfg(feature = "gradual-rollout")]
+    canary_reloads: Option<(u8, Option<Duration>)>,
+    #[cfg(feature = "gradual-rollout")]
+    sticky_buckets: Option<Arc<dyn crate::features::BucketStore>>,
+    #[cfg(feature = "gradual-rollout")]
+    exposure_handler: Option<crate::features::gradual::ExposureHandler>,
+    metrics: Option<Arc<dyn MetricsRecorder>>,
 }
 
-impl HotswapConfigBuilder {
+impl<T> HotswapConfigBuilder<T> {
     /// Create a new builder with default settings.
     pub fn new() -> Self {
         Self {
-            file_paths: Vec::new(),
+            files: Vec::new(),
+            environment: None,
+            env_selector: DEFAULT_ENV_SELECTOR.to_string(),
+            profile_dir: PathBuf::from("config"),
             env_prefix: None,
             env_separator: None,
             custom_sources: Vec::new(),
-            validator: None,
+            overlays: Vec::new(),
+            #[cfg(feature = "dotenv")]
+            dotenv_paths: Vec::new(),
+            snapshot_fallback: None,
+            validators: Vec::new(),
+            section_validators: Vec::new(),
+            transition_validators: Vec::new(),
+            async_validators: Vec::new(),
+            migrations: Migrations::default(),
+            pre_load_hooks: Vec::new(),
+            post_load_hooks: Vec::new(),
+            pre_swap_hooks: Vec::new(),
+            interpolate: false,
+            #[cfg(feature = "expressions")]
+            expressions: false,
+            unknown_keys: UnknownKeys::Allow,
+            error_mode: ErrorMode::FailFast,
+            source_limits: SourceLimits::new(),
+            immutable_paths: Vec::new(),
+            immutable_keys: ImmutableKeys::Reject,
+            restart_handler: None,
+            prepare_handler: None,
+            update_policy: None,
             #[cfg(feature = "file-watch")]
             enable_file_watch: false,
             #[cfg(feature = "file-watch")]
             watch_debounce: Duration::from_millis(500),
-            #[cfg(feature = "metrics")]
-            meter: None,
+            #[cfg(feature = "file-watch")]
+            shared_watcher: None,
+            #[cfg(feature = "tokio")]
+            reload_error_handler: None,
+            #[cfg(feature = "tokio")]
+            startup_retry: (Duration::from_secs(1), Duration::from_secs(60)),
+            #[cfg(feature = "tokio")]
+            reload_schedule: None,
+            #[cfg(feature = "tokio")]
+            min_reload_interval: None,
+            #[cfg(feature = "signal")]
+            reload_signals: Vec::new(),
+            #[cfg(feature = "webhook")]
+            reload_webhook: None,
+            #[cfg(feature = "tokio")]
+            reload_triggers: Vec::new(),
+            #[cfg(feature = "tokio")]
+            cancellation_token: None,
+            #[cfg(feature = "json-schema")]
+            json_schema: None,
+            #[cfg(feature = "signing")]
+            signature_verifier: None,
+            #[cfg(feature = "encryption")]
+            key_provider: None,
+            #[cfg(feature = "encryption")]
+            field_decryptor: None,
+            #[cfg(feature = "templating")]
+            template: None,
+            #[cfg(feature = "gradual-rollout")]
+            canary_reloads: None,
+            #[cfg(feature = "gradual-rollout")]
+            sticky_buckets: None,
+            #[cfg(feature = "gradual-rollout")]
+            exposure_handler: None,
+            metrics: None,
         }
     }
 
@@ -94,23 +249,274 @@ impl HotswapConfigBuilder {
     /// ```rust,no_run
     /// use hotswap_config::prelude::*;
     ///
+    /// # struct AppConfig;
     /// # async fn example() {
-    /// HotswapConfig::builder()
+    /// HotswapConfig::<AppConfig>::builder()
     ///     .with_file("config/default.yaml")
     ///     .with_file("config/production.yaml");
     /// # }
     /// ```
+    ///
+    /// The file must exist; a missing file fails the build. Use
+    /// [`with_file_optional`](Self::with_file_optional) for files that may be absent.
     pub fn with_file(mut self, path: impl Into<PathBuf>) -> Self {
-        self.file_paths.push(path.into());
+        self.files.push(FileSpec::File(FileSource::new(path)));
+        self
+    }
+
+    /// Add a file source that must exist.
+    ///
+    /// Same as [`with_file`](Self::with_file); spelled out for readability
+    /// next to optional files.
+    pub fn with_file_required(self, path: impl Into<PathBuf>) -> Self {
+        self.with_file(path)
+    }
+
+    /// Add a file source that is skipped if the file doesn't exist.
+    ///
+    /// A file that exists but fails to parse still fails the build. With file
+    /// watching enabled, creating or deleting the file later triggers a reload,
+    /// so its directory must exist.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    ///
+    /// # struct AppConfig;
+    /// # async fn example() {
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file_required("config/default.yaml")
+    ///     .with_file_optional("config/local.yaml");
+    /// # }
+    /// ```
+    pub fn with_file_optional(mut self, path: impl Into<PathBuf>) -> Self {
+        self.files.push(FileSpec::File(FileSource::optional(path)));
+        self
+    }
+
+    /// Add an optional file whose path depends on the selected environment.
+    ///
+    /// `{env}` in the template is replaced with the environment set by
+    /// [`with_environment`](Self::with_environment), or else read from the
+    /// `APP_ENV` environment variable (see
+    /// [`with_env_selector`](Self::with_env_selector)) when the config is
+    /// built. If no environment is selected the file is skipped, and like
+    /// [`with_file_optional`](Self::with_file_optional), a missing file is
+    /// skipped too.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    ///
+    /// # struct AppConfig;
+    /// # async fn example() {
+    /// // With APP_ENV=production, loads config/default.yaml then config/production.yaml
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config/default.yaml")
+    ///     .with_env_file("config/{env}.yaml");
+    /// # }
+    /// ```
+    pub fn with_env_file(mut self, template: impl Into<String>) -> Self {
+        self.files.push(FileSpec::EnvFile(template.into()));
+        self
+    }
+
+    /// Select the environment substituted into [`with_env_file`](Self::with_env_file) templates.
+    ///
+    /// Takes precedence over the environment variable selector. This is the
+    /// same selection as [`with_profile`](Self::with_profile), without loading
+    /// profile files.
+    pub fn with_environment(mut self, environment: impl Into<String>) -> Self {
+        self.environment = Some(environment.into());
+        self
+    }
+
+    /// Read the environment for [`with_env_file`](Self::with_env_file) and
+    /// [`with_profiles`](Self::with_profiles) from this environment variable
+    /// instead of `APP_ENV`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    ///
+    /// # struct AppConfig;
+    /// # async fn example() {
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_env_selector("DEPLOY_ENV")
+    ///     .with_env_file("config/{env}.toml");
+    /// # }
+    /// ```
+    pub fn with_env_selector(mut self, variable: impl Into<String>) -> Self {
+        self.env_selector = variable.into();
+        self
+    }
+
+    /// Add every supported file in a directory (`conf.d` style).
+    ///
+    /// Files are merged in lexical order of their names, so later fragments
+    /// override earlier ones. The directory takes the next file priority, like
+    /// `with_file`, so it overrides files added before it. With file watching
+    /// enabled, fragments added to or removed from the directory trigger a
+    /// reload. Hidden files and unsupported extensions are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    ///
+    /// # struct AppConfig;
+    /// # async fn example() {
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config/default.yaml")
+    ///     .with_directory("config/conf.d")
+    ///     .with_file_watch(true);
+    /// # }
+    /// ```
+    pub fn with_directory(mut self, path: impl Into<PathBuf>) -> Self {
+        self.files
+            .push(FileSpec::Directory(DirectorySource::new(path)));
+        self
+    }
+
+    /// Add every file matching a glob pattern.
+    ///
+    /// Behaves like [`with_directory`](Self::with_directory), but only loads
+    /// files whose names match the pattern. Wildcards (`*` and `?`) are only
+    /// supported in the file name.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    ///
+    /// # struct AppConfig;
+    /// # async fn example() {
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config/default.yaml")
+    ///     .with_glob("config/conf.d/*.yaml");
+    /// # }
+    /// ```
+    pub fn with_glob(mut self, pattern: impl AsRef<std::path::Path>) -> Self {
+        self.files
+            .push(FileSpec::Directory(DirectorySource::glob(pattern)));
+        self
+    }
+
+    /// Load configuration for a profile, e.g. `"production"`.
+    ///
+    /// Loads `default` and then the profile's file from the profile directory
+    /// (`config/` unless set with [`with_profile_dir`](Self::with_profile_dir)),
+    /// with any supported extension, so `config/production.yaml` overrides
+    /// `config/default.yaml`. Both files must exist. The files take the file
+    /// priority of the first profile call, like [`with_file`](Self::with_file).
+    ///
+    /// With [`with_env_overrides`](Self::with_env_overrides), variables with
+    /// the profile in their prefix (`APP_PRODUCTION__SERVER__PORT`) override the
+    /// general ones (`APP__SERVER__PORT`). The profile is also substituted for
+    /// `{env}` in [`with_env_file`](Self::with_env_file) templates, and is
+    /// available from [`HotswapConfig::profile`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    ///
+    /// # struct AppConfig;
+    /// # async fn example() {
+    /// // Loads config/default.yaml, then config/production.yaml
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_profile("production")
+    ///     .with_env_overrides("APP", "__");
+    /// # }
+    /// ```
+    pub fn with_profile(mut self, profile: impl Into<String>) -> Self {
+        self.environment = Some(profile.into());
+        self.with_profiles()
+    }
+
+    /// Load configuration for the profile named by the `APP_ENV` environment variable.
+    ///
+    /// Behaves like [`with_profile`](Self::with_profile), detecting the profile
+    /// when the config is built (see [`with_env_selector`](Self::with_env_selector)).
+    /// If no profile is selected, only the `default` file is loaded.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    ///
+    /// # struct AppConfig;
+    /// # async fn example() {
+    /// // APP_ENV=staging loads settings/default.toml, then settings/staging.toml
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_profile_dir("settings")
+    ///     .with_profiles();
+    /// # }
+    /// ```
+    pub fn with_profiles(mut self) -> Self {
+        if !self
+            .files
+            .iter()
+            .any(|spec| matches!(spec, FileSpec::Profiles))
+        {
+            self.files.push(FileSpec::Profiles);
+        }
+        self
+    }
+
+    /// Set the directory profile files are loaded from (default: `config`).
+    pub fn with_profile_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.profile_dir = dir.into();
         self
     }
 
+    /// The profile the config will be built for, if any.
+    ///
+    /// The profile set with [`with_profile`](Self::with_profile) or
+    /// [`with_environment`](Self::with_environment), or else the value of the
+    /// selector environment variable. Useful for validation rules that differ
+    /// by profile.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    /// # use serde::Deserialize;
+    /// # #[derive(Debug, Deserialize, Clone)]
+    /// # struct AppConfig { debug: bool }
+    ///
+    /// # async fn example() -> Result<()> {
+    /// let builder = HotswapConfig::<AppConfig>::builder().with_profiles();
+    /// let production = builder.active_profile().as_deref() == Some("production");
+    /// let config = builder
+    ///     .with_validation(move |cfg: &AppConfig| {
+    ///         if production && cfg.debug {
+    ///             return Err(ValidationError::invalid_field("debug", "not allowed in production"));
+    ///         }
+    ///         Ok(())
+    ///     })
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn active_profile(&self) -> Option<String> {
+        self.environment.clone().or_else(|| {
+            std::env::var(&self.env_selector)
+                .ok()
+                .filter(|env| !env.is_empty())
+        })
+    }
+
     /// Add environment variable source with custom prefix.
     ///
     /// # Arguments
     ///
     /// * `prefix` - Prefix for environment variables (e.g., "APP")
-    /// * `separator` - Separator for nested keys (e.g., "__" for APP_DB__HOST)
+    /// * `separator` - Separator for nested keys (e.g., "__" for APP__DB__HOST)
     ///
     /// Environment variables have the highest priority by default (300).
     ///
@@ -119,9 +525,10 @@ impl HotswapConfigBuilder {
     /// ```rust,no_run
     /// use hotswap_config::prelude::*;
     ///
+    /// # struct AppConfig;
     /// # async fn example() {
-    /// // APP_SERVER__PORT=8080 -> server.port = 8080
-    /// HotswapConfig::builder()
+    /// // APP__SERVER__PORT=8080 -> server.port = 8080
+    /// HotswapConfig::<AppConfig>::builder()
     ///     .with_env_overrides("APP", "__");
     /// # }
     /// ```
@@ -131,244 +538,2426 @@ impl HotswapConfigBuilder {
         self
     }
 
-    /// Add a custom configuration source.
+    /// Add a dotenv (`.env`) file source.
+    ///
+    /// Variables are mapped to nested keys using the same prefix/separator as
+    /// `with_env_overrides` (if set), and sit just below real environment
+    /// variables in priority (290). Dotenv files are watched along with other
+    /// files when file watching is enabled.
+    ///
+    /// Use `with_source(DotenvSource::new(..).with_priority(..))` for a custom
+    /// priority.
     ///
     /// # Examples
     ///
     /// ```rust,no_run
     /// use hotswap_config::prelude::*;
-    /// use hotswap_config::sources::FileSource;
     ///
+    /// # struct AppConfig;
     /// # async fn example() {
-    /// let custom_source = FileSource::new("config/custom.yaml")
-    ///     .with_priority(150);
-    ///
-    /// HotswapConfig::builder()
-    ///     .with_source(custom_source);
+    /// // APP__SERVER__PORT=8080 in .env -> server.port = 8080
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config/default.yaml")
+    ///     .with_dotenv(".env")
+    ///     .with_env_overrides("APP", "__");
     /// # }
     /// ```
-    pub fn with_source<S: ConfigSource + 'static>(mut self, source: S) -> Self {
-        self.custom_sources.push(Box::new(source));
+    #[cfg(feature = "dotenv")]
+    pub fn with_dotenv(mut self, path: impl Into<PathBuf>) -> Self {
+        self.dotenv_paths.push(path.into());
         self
     }
 
-    /// Add a validation function that must pass before the config is loaded.
+    /// Boot from a snapshot file if the initial load from sources fails.
     ///
-    /// The validator is called during the initial build. In Phase 2, it will also
-    /// be called before any reload.
+    /// Pair with [`HotswapConfig::write_snapshot`] to keep a last-known-good
+    /// copy of the effective configuration, so a service can still start when
+    /// remote sources are unreachable. The snapshot goes through the same
+    /// validation as a normal load. Later reloads use the configured sources
+    /// as usual, so the service converges once they recover.
     ///
     /// # Examples
     ///
     /// ```rust,no_run
     /// use hotswap_config::prelude::*;
-    /// use hotswap_config::error::ValidationError;
-    /// use serde::Deserialize;
+    /// use serde::{Deserialize, Serialize};
     ///
-    /// #[derive(Debug, Deserialize, Clone)]
+    /// #[derive(Debug, Serialize, Deserialize, Clone)]
     /// struct AppConfig {
     ///     port: u16,
     /// }
     ///
     /// # async fn example() -> Result<()> {
-    /// let config = HotswapConfig::builder()
+    /// let config = HotswapConfig::<AppConfig>::builder()
     ///     .with_file("config.yaml")
-    ///     .with_validation(|config: &AppConfig| {
-    ///         if config.port < 1024 {
-    ///             return Err(ValidationError::invalid_field(
-    ///                 "port",
-    ///                 "must be >= 1024"
-    ///             ));
-    ///         }
-    ///         Ok(())
-    ///     })
-    ///     .build::<AppConfig>()
+    ///     .with_snapshot_fallback("/var/lib/myapp/last-good.yaml")
+    ///     .build()
     ///     .await?;
+    ///
+    /// // After a successful load, refresh the snapshot
+    /// config.write_snapshot("/var/lib/myapp/last-good.yaml")?;
     /// # Ok(())
     /// # }
     /// ```
-    pub fn with_validation<F, T>(mut self, validator: F) -> Self
-    where
-        F: Fn(&T) -> std::result::Result<(), ValidationError> + Send + Sync + 'static,
-        T: 'static,
-    {
-        self.validator = Some(Arc::new(move |config: &dyn std::any::Any| {
-            let typed_config = config
-                .downcast_ref::<T>()
-                .ok_or_else(|| ValidationError::custom("Type mismatch in validator"))?;
-            validator(typed_config)
-        }));
+    pub fn with_snapshot_fallback(mut self, path: impl Into<PathBuf>) -> Self {
+        self.snapshot_fallback = Some(path.into());
         self
     }
 
-    /// Enable file watching for automatic reloads.
+    /// Add a custom configuration source.
     ///
-    /// When enabled, the configuration will automatically reload when any
-    /// watched file changes. Uses a default debounce of 500ms.
+    /// If the source supports [`ConfigSource::watch`], it is watched after
+    /// the build and re-loaded whenever it reports a change; watch failures
+    /// and failed reloads go to the
+    /// [reload error handler](Self::with_reload_error_handler).
     ///
     /// # Examples
     ///
     /// ```rust,no_run
     /// use hotswap_config::prelude::*;
+    /// use hotswap_config::sources::FileSource;
     ///
+    /// # struct AppConfig;
     /// # async fn example() {
-    /// HotswapConfig::builder()
-    ///     .with_file("config.yaml")
-    ///     .with_file_watch(true);
+    /// let custom_source = FileSource::new("config/custom.yaml")
+    ///     .with_priority(150);
+    ///
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_source(custom_source);
     /// # }
     /// ```
-    #[cfg(feature = "file-watch")]
-    pub fn with_file_watch(mut self, enabled: bool) -> Self {
-        self.enable_file_watch = enabled;
+    pub fn with_source<S: ConfigSource + 'static>(mut self, source: S) -> Self {
+        self.custom_sources.push(Box::new(source));
         self
     }
 
-    /// Set the debounce duration for file watching.
+    /// Add a gRPC source that streams config pushes from a server.
     ///
-    /// This is the minimum time between reload triggers when files change rapidly.
-    /// Default is 500ms.
+    /// The source's first push is part of the initial build. Each later push
+    /// triggers a reload through the usual validation pipeline and is
+    /// acknowledged to the server once live, or rejected with the error.
+    /// Rejected pushes and stream failures go to the
+    /// [reload error handler](Self::with_reload_error_handler). Equivalent
+    /// to [`with_source`](Self::with_source).
     ///
     /// # Examples
     ///
     /// ```rust,no_run
     /// use hotswap_config::prelude::*;
-    /// use std::time::Duration;
+    /// use hotswap_config::sources::GrpcSource;
     ///
-    /// # async fn example() {
-    /// HotswapConfig::builder()
-    ///     .with_file("config.yaml")
-    ///     .with_file_watch(true)
-    ///     .with_watch_debounce(Duration::from_secs(1));
+    /// # struct AppConfig;
+    /// # async fn example() -> Result<()> {
+    /// let flags = GrpcSource::builder()
+    ///     .with_endpoint("http://config.internal:50051")
+    ///     .connect()
+    ///     .await?;
+    ///
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config/default.yaml")
+    ///     .with_grpc(flags);
+    /// # Ok(())
     /// # }
     /// ```
-    #[cfg(feature = "file-watch")]
-    pub fn with_watch_debounce(mut self, duration: Duration) -> Self {
-        self.watch_debounce = duration;
-        self
+    #[cfg(feature = "grpc")]
+    pub fn with_grpc(self, source: crate::sources::GrpcSource) -> Self {
+        self.with_source(source)
     }
 
-    /// Enable metrics collection with the provided meter.
+    /// Add a Redis source, reloading whenever its invalidation channel
+    /// receives a message.
     ///
-    /// When enabled, the configuration will track reload attempts, success/failure
-    /// rates, latencies, and subscriber counts using OpenTelemetry metrics.
+    /// The key is read on every load, like any other source; the channel only
+    /// decides when to reload. Subscription failures are retried with backoff
+    /// and reported to the
+    /// [reload error handler](Self::with_reload_error_handler). Equivalent
+    /// to [`with_source`](Self::with_source).
     ///
     /// # Examples
     ///
     /// ```rust,no_run
     /// use hotswap_config::prelude::*;
-    /// use opentelemetry::global;
+    /// use hotswap_config::sources::RedisSource;
     ///
-    /// # async fn example() {
-    /// let meter = global::meter("my-app");
+    /// # struct AppConfig;
+    /// # fn example() -> Result<()> {
+    /// let source = RedisSource::builder()
+    ///     .with_url("redis://cache.internal:6379")
+    ///     .with_key("myapp:config")
+    ///     .with_channel("myapp:config:changed")
+    ///     .build()?;
     ///
-    /// HotswapConfig::builder()
-    ///     .with_file("config.yaml")
-    ///     .with_metrics(meter);
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config/default.yaml")
+    ///     .with_redis(source);
+    /// # Ok(())
     /// # }
     /// ```
-    #[cfg(feature = "metrics")]
-    pub fn with_metrics(mut self, meter: Meter) -> Self {
-        self.meter = Some(meter);
-        self
+    #[cfg(feature = "redis")]
+    pub fn with_redis(self, source: crate::sources::RedisSource) -> Self {
+        self.with_source(source)
     }
 
-    /// Build the configuration handle.
+    /// Add a ZooKeeper source, reloading whenever a znode in its tree changes.
     ///
-    /// This performs the initial load from all sources and validates the result.
+    /// Session failures are retried with backoff, followed by a reload in
+    /// case changes were missed, and reported to the
+    /// [reload error handler](Self::with_reload_error_handler). Equivalent
+    /// to [`with_source`](Self::with_source).
     ///
-    /// # Type Parameters
+    /// # Examples
     ///
-    /// * `T` - The configuration type (must implement `DeserializeOwned`)
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    /// use hotswap_config::sources::ZookeeperSource;
     ///
-    /// # Errors
+    /// # struct AppConfig;
+    /// # async fn example() -> Result<()> {
+    /// let source = ZookeeperSource::builder()
+    ///     .with_hosts("zk1:2181,zk2:2181")
+    ///     .with_root("/config/myapp")
+    ///     .connect()
+    ///     .await?;
     ///
-    /// Returns an error if:
-    /// - Initial configuration load fails
-    /// - Deserialization fails
-    /// - Validation fails
-    pub async fn build<T>(self) -> Result<HotswapConfig<T>>
-    where
-        T: DeserializeOwned + Clone + Send + Sync + 'static,
-    {
-        let mut loader = ConfigLoader::new();
-
-        // Add file sources with increasing priority
-        for (index, path) in self.file_paths.iter().enumerate() {
-            let priority = 100 + (index as i32 * 10); // 100, 110, 120, etc.
-            let source = FileSource::new(path).with_priority(priority);
-            loader.add_source(Box::new(source));
-        }
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config/default.yaml")
+    ///     .with_zookeeper(source);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "zookeeper")]
+    pub fn with_zookeeper(self, source: crate::sources::ZookeeperSource) -> Self {
+        self.with_source(source)
+    }
 
-        // Add custom sources
-        for source in self.custom_sources {
-            loader.add_source(source);
-        }
+    /// Add an object storage source, polling it for changes if it has a
+    /// poll interval.
+    ///
+    /// Polls send the last ETag, so only a changed object triggers a reload.
+    /// Failed polls keep the last-known-good config and are reported to the
+    /// [reload error handler](Self::with_reload_error_handler). Equivalent
+    /// to [`with_source`](Self::with_source).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    /// use hotswap_config::sources::ObjectStoreSource;
+    /// use object_store::memory::InMemory;
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    ///
+    /// # struct AppConfig;
+    /// # async fn example() -> Result<()> {
+    /// let source = ObjectStoreSource::builder(Arc::new(InMemory::new()), "config.yaml")
+    ///     .with_poll_interval(Duration::from_secs(30))
+    ///     .connect()
+    ///     .await?;
+    ///
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config/default.yaml")
+    ///     .with_object_store(source);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "object-store")]
+    pub fn with_object_store(self, source: crate::sources::ObjectStoreSource) -> Self {
+        self.with_source(source)
+    }
 
-        // Add environment variable source (highest priority)
-        if let (Some(prefix), Some(separator)) = (self.env_prefix, self.env_separator) {
-            let env_source = EnvSource::new(prefix, separator);
-            loader.add_source(Box::new(env_source));
+    /// Add an overlay source, such as secrets fetched from Vault or AWS.
+    ///
+    /// Overlays form a separate layer merged after every other source,
+    /// regardless of priority, so they override file and environment values.
+    /// Reloads triggered by file changes reuse the overlay's last values
+    /// instead of fetching it again, and
+    /// [`HotswapConfig::refresh_overlays`] re-fetches overlays without
+    /// re-reading files. Use [`with_overlay_every`](Self::with_overlay_every)
+    /// to refresh on a schedule.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    /// use hotswap_config::sources::FileSource;
+    ///
+    /// # struct AppConfig;
+    /// # async fn example() {
+    /// // A secret mounted by the orchestrator overrides the file's placeholder
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config/default.yaml")
+    ///     .with_overlay(FileSource::new("/run/secrets/database.yaml"));
+    /// # }
+    /// ```
+    pub fn with_overlay<S: ConfigSource + 'static>(mut self, source: S) -> Self {
+        self.overlays.push((Box::new(source), None));
+        self
+    }
+
+    /// Add an overlay source that is re-fetched every `interval`.
+    ///
+    /// Like [`with_overlay`](Self::with_overlay), with a background task
+    /// refreshing just this overlay on its own cadence, so secrets rotate
+    /// without re-reading any other source. A refresh that changes nothing is
+    /// skipped; failures keep the current config and are reported to the
+    /// [reload error handler](Self::with_reload_error_handler).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    /// use hotswap_config::sources::FileSource;
+    /// use std::time::Duration;
+    ///
+    /// # struct AppConfig;
+    /// # async fn example() {
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config/default.yaml")
+    ///     .with_overlay_every(
+    ///         FileSource::new("/run/secrets/database.yaml"),
+    ///         Duration::from_secs(300),
+    ///     );
+    /// # }
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub fn with_overlay_every<S: ConfigSource + 'static>(
+        mut self,
+        source: S,
+        interval: Duration,
+    ) -> Self {
+        self.overlays.push((Box::new(source), Some(interval)));
+        self
+    }
+
+    /// Use a serializable value as the lowest-priority source of defaults.
+    ///
+    /// Every other source overrides the defaults key by key, so nested structs
+    /// only need the fields that differ. Defaults must serialize to a map.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Debug, Serialize, Deserialize, Clone)]
+    /// struct AppConfig {
+    ///     port: u16,
+    ///     host: String,
+    /// }
+    ///
+    /// impl Default for AppConfig {
+    ///     fn default() -> Self {
+    ///         Self { port: 8080, host: "localhost".to_string() }
+    ///     }
+    /// }
+    ///
+    /// # async fn example() -> Result<()> {
+    /// let config = HotswapConfig::<AppConfig>::builder()
+    ///     .with_defaults(AppConfig::default())
+    ///     .with_file("config.yaml")
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_defaults<D: Serialize>(self, defaults: D) -> Self {
+        self.with_source(DefaultsSource::new(&defaults))
+    }
+
+    /// Use the value returned by `f` as the lowest-priority source of defaults.
+    ///
+    /// Equivalent to `with_defaults(f())`, convenient with `T::default`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize, Default)]
+    /// struct AppConfig {
+    ///     port: u16,
+    /// }
+    ///
+    /// # async fn example() {
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_defaults_fn(AppConfig::default)
+    ///     .with_file("config.yaml");
+    /// # }
+    /// ```
+    pub fn with_defaults_fn<D, F>(self, f: F) -> Self
+    where
+        D: Serialize,
+        F: FnOnce() -> D,
+    {
+        self.with_defaults(f())
+    }
+
+    /// Add a validation function that must pass before the config is loaded.
+    ///
+    /// The validator is called during the initial build and before every reload
+    /// or update. Validators accumulate: all of them run, and their failures are
+    /// reported together as [`ValidationError::Multiple`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    /// use hotswap_config::error::ValidationError;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Debug, Deserialize, Clone)]
+    /// struct AppConfig {
+    ///     port: u16,
+    /// }
+    ///
+    /// # async fn example() -> Result<()> {
+    /// let config = HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config.yaml")
+    ///     .with_validation(|config: &AppConfig| {
+    ///         if config.port < 1024 {
+    ///             return Err(ValidationError::invalid_field(
+    ///                 "port",
+    ///                 "must be >= 1024"
+    ///             ));
+    ///         }
+    ///         Ok(())
+    ///     })
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_validation<F>(mut self, validator: F) -> Self
+    where
+        F: Fn(&T) -> std::result::Result<(), ValidationError> + Send + Sync + 'static,
+    {
+        self.validators.push(Arc::new(validator));
+        self
+    }
+
+    /// Add a validation function for the section at a dotted key path.
+    ///
+    /// The section is deserialized from the merged sources as `S` and passed to
+    /// the validator, so each part of the config can be validated on its own
+    /// terms. Field paths in the returned errors are prefixed with `path`
+    /// (`port` becomes `database.port`), and a section that is missing or
+    /// doesn't deserialize is reported as an error at `path`.
+    ///
+    /// Section validators run on the initial build and every reload, alongside
+    /// [`with_validation`](Self::with_validation) validators, and all failures
+    /// are reported together. They check loaded sources only: values passed to
+    /// [`HotswapConfig::update`] are checked by `with_validation` validators.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Debug, Deserialize, Clone)]
+    /// struct DatabaseConfig {
+    ///     url: String,
+    ///     max_connections: u32,
+    /// }
+    ///
+    /// #[derive(Debug, Deserialize, Clone)]
+    /// struct AppConfig {
+    ///     port: u16,
+    ///     database: DatabaseConfig,
+    /// }
+    ///
+    /// # async fn example() -> Result<()> {
+    /// let config = HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config.yaml")
+    ///     .with_validation_at("database", |db: &DatabaseConfig| {
+    ///         if db.max_connections == 0 {
+    ///             // Reported as `database.max_connections`
+    ///             return Err(ValidationError::invalid_field(
+    ///                 "max_connections",
+    ///                 "must be greater than 0",
+    ///             ));
+    ///         }
+    ///         Ok(())
+    ///     })
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_validation_at<F, S>(mut self, path: impl Into<String>, validator: F) -> Self
+    where
+        F: Fn(&S) -> std::result::Result<(), ValidationError> + Send + Sync + 'static,
+        S: DeserializeOwned + 'static,
+    {
+        let path = path.into();
+        self.section_validators
+            .push(Arc::new(move |raw: &config::Config| {
+                let section: S = raw
+                    .get(&path)
+                    .map_err(|e| ValidationError::invalid_field(path.as_str(), e.to_string()))?;
+                validator(&section).map_err(|e| e.at(&path))
+            }));
+        self
+    }
+
+    /// Add a validation function for changes from the current config to a
+    /// new one.
+    ///
+    /// Some values are only invalid relative to the running state, such as
+    /// shrinking a pool below the connections it has open, or changing an
+    /// identifier that other systems depend on. The validator receives the
+    /// current config and the new one before every reload or update, and
+    /// rejects the change like any other validation failure, keeping the
+    /// current config. It doesn't run on the initial build, which has nothing
+    /// to compare against.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Debug, Deserialize, Clone)]
+    /// struct AppConfig {
+    ///     pool_size: u32,
+    /// }
+    ///
+    /// # async fn example() -> Result<()> {
+    /// let config = HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config.yaml")
+    ///     .with_transition_validation(|old: &AppConfig, new: &AppConfig| {
+    ///         if new.pool_size < old.pool_size / 2 {
+    ///             return Err(ValidationError::invalid_field(
+    ///                 "pool_size",
+    ///                 "can shrink by at most half per reload",
+    ///             ));
+    ///         }
+    ///         Ok(())
+    ///     })
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_transition_validation<F>(mut self, validator: F) -> Self
+    where
+        F: Fn(&T, &T) -> std::result::Result<(), ValidationError> + Send + Sync + 'static,
+    {
+        self.transition_validators.push(Arc::new(validator));
+        self
+    }
+
+    /// Mark a field as immutable at runtime.
+    ///
+    /// A reload or update that changes the value returned by `get` is
+    /// rejected with an error on `field`, and the current config is kept.
+    /// Shorthand for a [transition validator](Self::with_transition_validation)
+    /// comparing the old and new value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Debug, Deserialize, Clone)]
+    /// struct AppConfig {
+    ///     cluster_id: String,
+    /// }
+    ///
+    /// # async fn example() {
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config.yaml")
+    ///     .with_immutable_field("cluster_id", |c: &AppConfig| &c.cluster_id);
+    /// # }
+    /// ```
+    pub fn with_immutable_field<F, V>(self, field: impl Into<String>, get: F) -> Self
+    where
+        F: Fn(&T) -> &V + Send + Sync + 'static,
+        V: PartialEq + ?Sized,
+    {
+        let field = field.into();
+        self.with_transition_validation(move |old: &T, new: &T| {
+            if get(old) == get(new) {
+                return Ok(());
+            }
+            Err(ValidationError::invalid_field(
+                field.as_str(),
+                "cannot be changed at runtime",
+            ))
+        })
+    }
+
+    /// Add an async validator that verifies a new config against the outside
+    /// world before it is applied.
+    ///
+    /// Use it for checks that need I/O, such as test-connecting to a new
+    /// database URL or resolving a host name. Async validators run after the
+    /// synchronous ones, on the initial build and before every reload or update
+    /// is swapped in, each bounded by `timeout`.
+    ///
+    /// Return [`VerificationError::Invalid`] (or a [`ValidationError`] via `?`
+    /// or `into()`) when the config is wrong; it is reported as
+    /// [`ConfigError::ValidationError`]. Return [`VerificationError::Failed`]
+    /// when the check couldn't be completed; it is reported, like a timeout, as
+    /// [`ConfigError::VerificationFailed`]. Either way, the old config stays
+    /// active.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    /// use hotswap_config::error::VerificationError;
+    /// use serde::Deserialize;
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    ///
+    /// #[derive(Debug, Deserialize, Clone)]
+    /// struct AppConfig {
+    ///     database_addr: String,
+    /// }
+    ///
+    /// # async fn example() -> Result<()> {
+    /// let config = HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config.yaml")
+    ///     .with_async_validation(
+    ///         |config: Arc<AppConfig>| async move {
+    ///             tokio::net::TcpStream::connect(&config.database_addr)
+    ///                 .await
+    ///                 .map_err(|e| VerificationError::failed(format!("database unreachable: {}", e)))?;
+    ///             Ok(())
+    ///         },
+    ///         Duration::from_secs(5),
+    ///     )
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub fn with_async_validation<F, Fut>(mut self, validator: F, timeout: Duration) -> Self
+    where
+        F: Fn(Arc<T>) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = std::result::Result<(), VerificationError>>
+            + Send
+            + 'static,
+    {
+        self.async_validators
+            .push(Arc::new(move |config: Arc<T>| -> VerifyFuture {
+                crate::core::with_timeout(validator(config), timeout)
+            }));
+        self
+    }
+
+    /// Add a migration of the raw configuration from schema version `from`
+    /// to `to`.
+    ///
+    /// The version is read from the `config_version` key of the merged
+    /// sources; without one, the sources are taken to be at the oldest
+    /// version a migration starts from. On the initial build and every
+    /// reload, migrations are chained from that version for as long as one
+    /// starts where the last ended, before pre-load hooks, validation, and
+    /// deserialization, so files written for an older `T` keep loading.
+    /// `config_version` is then set to the version reached, and the chain is
+    /// reported by [`HotswapConfig::applied_migrations`] and the
+    /// [startup report](HotswapConfig::report). A failing migration fails
+    /// the load.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `to` isn't greater than `from`, or a migration from `from`
+    /// was already added.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Debug, Deserialize, Clone)]
+    /// struct ServerConfig {
+    ///     host: String,
+    ///     port: u16,
+    /// }
+    ///
+    /// #[derive(Debug, Deserialize, Clone)]
+    /// struct AppConfig {
+    ///     config_version: u32,
+    ///     server: ServerConfig,
+    /// }
+    ///
+    /// # async fn example() -> Result<()> {
+    /// let config = HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config.yaml")
+    ///     // Version 1 had `host` and `port` at the top level
+    ///     .with_migration(1, 2, |raw| {
+    ///         let mut server = config::Map::new();
+    ///         for key in ["host", "port"] {
+    ///             if let Some(value) = raw.remove(key) {
+    ///                 server.insert(key.to_string(), value);
+    ///             }
+    ///         }
+    ///         raw.insert("server".to_string(), server.into());
+    ///         Ok(())
+    ///     })
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_migration<F>(mut self, from: u64, to: u64, migrate: F) -> Self
+    where
+        F: Fn(&mut std::collections::HashMap<String, config::Value>) -> Result<()>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.migrations.add(from, to, Arc::new(migrate));
+        self
+    }
+
+    /// Add a hook that rewrites the merged raw configuration before it is
+    /// checked and deserialized.
+    ///
+    /// The hook receives the tree merged from every source (after
+    /// interpolation) on the initial build and every reload, and may add,
+    /// change, or remove keys, e.g. to rename a key that moved or to expand a
+    /// shorthand. Hooks run in the order they were added; an error fails the
+    /// load and keeps the current config.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Debug, Deserialize, Clone)]
+    /// struct AppConfig {
+    ///     port: u16,
+    /// }
+    ///
+    /// # async fn example() -> Result<()> {
+    /// let config = HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config.yaml")
+    ///     .with_pre_load_hook(|raw| {
+    ///         // Accept the key's old name
+    ///         if let Some(port) = raw.remove("listen_port") {
+    ///             raw.entry("port".to_string()).or_insert(port);
+    ///         }
+    ///         Ok(())
+    ///     })
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_pre_load_hook<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&mut std::collections::HashMap<String, config::Value>) -> Result<()>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.pre_load_hooks.push(Arc::new(hook));
+        self
+    }
+
+    /// Add a hook that transforms every loaded configuration before it is
+    /// validated.
+    ///
+    /// Use it to normalize values (trim, lowercase, resolve relative paths)
+    /// or fill in fields computed from others. The hook runs on the initial
+    /// build and every reload, after deserialization and before any
+    /// validator, so validators see its result. Hooks run in the order they
+    /// were added; an error fails the load and keeps the current config.
+    /// Values passed to [`HotswapConfig::update`] are taken as they are.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Debug, Deserialize, Clone)]
+    /// struct AppConfig {
+    ///     host: String,
+    ///     #[serde(skip)]
+    ///     url: String,
+    /// }
+    ///
+    /// # async fn example() -> Result<()> {
+    /// let config = HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config.yaml")
+    ///     .with_post_load_hook(|mut config| {
+    ///         config.host = config.host.to_lowercase();
+    ///         config.url = format!("https://{}", config.host);
+    ///         Ok(config)
+    ///     })
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_post_load_hook<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(T) -> Result<T> + Send + Sync + 'static,
+    {
+        self.post_load_hooks.push(Arc::new(hook));
+        self
+    }
+
+    /// Add a hook that may veto a new configuration just before it is
+    /// swapped in.
+    ///
+    /// The hook receives the current config and the new one after every
+    /// validator has passed, on every reload and update, and returns
+    /// [`PolicyDecision::Deny`] to keep the current config; the reload or
+    /// update then fails with [`ConfigError::SwapVetoed`]. Hooks run in the
+    /// order they were added, and the first denial wins. They don't run on
+    /// the initial build, which has nothing to swap out.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    /// use serde::Deserialize;
+    /// use std::sync::Arc;
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    ///
+    /// #[derive(Debug, Deserialize, Clone)]
+    /// struct AppConfig {
+    ///     port: u16,
+    /// }
+    ///
+    /// # async fn example() -> Result<()> {
+    /// let draining = Arc::new(AtomicBool::new(false));
+    /// let flag = Arc::clone(&draining);
+    /// let config = HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config.yaml")
+    ///     .with_pre_swap_hook(move |old, new| {
+    ///         if flag.load(Ordering::Relaxed) && old.port != new.port {
+    ///             return PolicyDecision::deny("can't move the listener while draining");
+    ///         }
+    ///         PolicyDecision::Allow
+    ///     })
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_pre_swap_hook<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&T, &T) -> PolicyDecision + Send + Sync + 'static,
+    {
+        self.pre_swap_hooks.push(Arc::new(hook));
+        self
+    }
+
+    /// Validate the merged raw configuration against a JSON Schema.
+    ///
+    /// The schema is checked after all sources are merged and before
+    /// deserialization, on the initial build and on every reload. Violations
+    /// are reported with their key paths (e.g. `server.port`), and all of them
+    /// are collected rather than only the first.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    /// use serde_json::json;
+    ///
+    /// # struct AppConfig;
+    /// # async fn example() {
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config.yaml")
+    ///     .with_json_schema(json!({
+    ///         "type": "object",
+    ///         "properties": {
+    ///             "port": { "type": "integer", "minimum": 1024 }
+    ///         },
+    ///         "required": ["port"]
+    ///     }));
+    /// # }
+    /// ```
+    #[cfg(feature = "json-schema")]
+    pub fn with_json_schema(mut self, schema: serde_json::Value) -> Self {
+        self.json_schema = Some(schema);
+        self
+    }
+
+    /// Require signed configuration content.
+    ///
+    /// Every source that can be signed must carry a detached ed25519 signature
+    /// by one of the verifier's keys, on the initial build and on every
+    /// reload; content with a missing or invalid signature is rejected with
+    /// [`ConfigError::SignatureError`] and the current config is kept. Files
+    /// are signed by a sidecar `.sig` file (watched along with the file), HTTP
+    /// sources by an `X-Config-Signature` header, object storage sources by a
+    /// `.sig` object, and gRPC sources by the response's `signature` field.
+    /// Sources that can't be signed, such as
+    /// environment variables and directories, are not verified. See
+    /// [`signing`](crate::signing) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    /// use hotswap_config::signing::SignatureVerifier;
+    ///
+    /// # struct AppConfig;
+    /// # fn example() -> Result<()> {
+    /// let verifier = SignatureVerifier::new()
+    ///     .with_public_key("MCowBQYDK2VwAyEA6kpsY+KcUgq+9VB7Ey7F+ZVHdq6+vnuSQh7qaRRG0iw=")?;
+    ///
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config/default.yaml")
+    ///     .with_signature_verification(verifier);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "signing")]
+    pub fn with_signature_verification(
+        mut self,
+        verifier: crate::signing::SignatureVerifier,
+    ) -> Self {
+        self.signature_verifier = Some(verifier);
+        self
+    }
+
+    /// Decrypt encrypted values with age identities from `provider`.
+    ///
+    /// SOPS documents encrypted to age recipients, and values holding an
+    /// ASCII-armored age file, are decrypted as each source loads, on the
+    /// initial build and on every reload. Content that can't be decrypted
+    /// fails the load with [`ConfigError::DecryptionError`]. See
+    /// [`encryption`](crate::encryption) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::encryption::AgeKeyFile;
+    /// use hotswap_config::prelude::*;
+    ///
+    /// # struct AppConfig;
+    /// # async fn example() {
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config/default.yaml")
+    ///     .with_file("config/secrets.enc.yaml")
+    ///     .with_decryption(AgeKeyFile::new("/etc/myapp/age-keys.txt"));
+    /// # }
+    /// ```
+    #[cfg(feature = "encryption")]
+    pub fn with_decryption(
+        mut self,
+        provider: impl crate::encryption::KeyProvider + 'static,
+    ) -> Self {
+        self.key_provider = Some(Arc::new(provider));
+        self
+    }
+
+    /// Decrypt encrypted fields with the AES-256 key from `decryptor`.
+    ///
+    /// `ENC[AES256_GCM,...]` values in documents without SOPS metadata, as
+    /// written by [`encrypt_field`](crate::encryption::encrypt_field), are
+    /// decrypted as each source loads. Combines with
+    /// [`with_decryption`](Self::with_decryption), which keeps handling SOPS
+    /// documents and age values.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::encryption::LocalKey;
+    /// use hotswap_config::prelude::*;
+    ///
+    /// # struct AppConfig;
+    /// # fn example() -> Result<()> {
+    /// let key = LocalKey::from_base64(&std::env::var("CONFIG_KEY").expect("CONFIG_KEY is set"))?;
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config/default.yaml")
+    ///     .with_field_decryption(key);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "encryption")]
+    pub fn with_field_decryption(
+        mut self,
+        decryptor: impl crate::encryption::Decryptor + 'static,
+    ) -> Self {
+        self.field_decryptor = Some(Arc::new(decryptor));
+        self
+    }
+
+    /// Render files added with this builder as Tera templates before parsing.
+    ///
+    /// Applies to files added with `with_file`, `with_optional_file`,
+    /// `with_directory`, `with_env_file`, and profiles, but not to sources
+    /// added with `with_source`. Templates see the environment as `env` and
+    /// the variables set with [`with_template_var`](Self::with_template_var).
+    /// See [`templating`](crate::templating) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    ///
+    /// # struct AppConfig;
+    /// # async fn example() {
+    /// // config.yaml: `log_level: {{ env.LOG_LEVEL | default(value="info") }}`
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config.yaml")
+    ///     .with_templating();
+    /// # }
+    /// ```
+    #[cfg(feature = "templating")]
+    pub fn with_templating(mut self) -> Self {
+        self.template.get_or_insert_default();
+        self
+    }
+
+    /// Make `value` available to templated files as `name`, enabling
+    /// [templating](Self::with_templating).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    ///
+    /// # struct AppConfig;
+    /// # async fn example() {
+    /// // config.yaml:
+    /// // endpoints:
+    /// // {% for region in regions %}
+    /// //   - https://api.{{ region }}.example.com
+    /// // {% endfor %}
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config.yaml")
+    ///     .with_template_var("regions", ["us-east-1", "eu-west-1"]);
+    /// # }
+    /// ```
+    #[cfg(feature = "templating")]
+    pub fn with_template_var(mut self, name: impl Into<String>, value: impl Serialize) -> Self {
+        self.template.get_or_insert_default().set_var(name, value);
+        self
+    }
+
+    /// Validate the merged raw configuration against a schema generated from `S`.
+    ///
+    /// This is a convenience for `with_json_schema` using `schemars` to derive
+    /// the schema from the configuration type itself.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    /// use schemars::JsonSchema;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Debug, Deserialize, Clone, JsonSchema)]
+    /// struct AppConfig {
+    ///     port: u16,
+    /// }
+    ///
+    /// # async fn example() -> Result<()> {
+    /// let config = HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config.yaml")
+    ///     .with_generated_schema::<AppConfig>()
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "schemars")]
+    pub fn with_generated_schema<S: schemars::JsonSchema>(self) -> Self {
+        self.with_json_schema(schemars::schema_for!(S).to_value())
+    }
+
+    /// Enable `${path.to.key}` references between config values.
+    ///
+    /// References are resolved after all sources are merged, so they see the
+    /// final value of the referenced key. A value consisting of a single
+    /// reference keeps the referenced type; use `$${` for a literal `${`.
+    /// Missing keys and reference cycles fail the load with
+    /// [`ConfigError::InterpolationError`] naming the offending key.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    ///
+    /// # struct AppConfig;
+    /// # async fn example() {
+    /// // base_url: "https://${server.host}:${server.port}"
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config.yaml")
+    ///     .with_interpolation();
+    /// # }
+    /// ```
+    pub fn with_interpolation(mut self) -> Self {
+        self.interpolate = true;
+        self
+    }
+
+    /// Enable `${...}` expressions computing config values from other keys.
+    ///
+    /// Implies [`with_interpolation`](Self::with_interpolation): each `${...}`
+    /// is evaluated after all sources are merged, and a value consisting of
+    /// a single expression takes the type of its result. Expressions can read
+    /// keys by path, the environment with `env("NAME")` or
+    /// `env("NAME", default)`, and the `num_cpus` variable, and combine them
+    /// with arithmetic, comparisons, `cond ? a : b`, and functions such as
+    /// `min`, `max`, `clamp`, `round`, `int`, and `len`. They are sandboxed:
+    /// there are no loops, and their length and nesting are limited. Invalid
+    /// expressions fail the load with [`ConfigError::InterpolationError`]
+    /// naming the offending key.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    ///
+    /// # struct AppConfig;
+    /// # async fn example() {
+    /// // worker_threads: "${max(2, num_cpus / 2)}"
+    /// // pool_size: "${clamp(worker_threads * 4, 8, 64)}"
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config.yaml")
+    ///     .with_expressions();
+    /// # }
+    /// ```
+    #[cfg(feature = "expressions")]
+    pub fn with_expressions(mut self) -> Self {
+        self.interpolate = true;
+        self.expressions = true;
+        self
+    }
+
+    /// Report keys in files, env vars, or other sources that the config type
+    /// never consumes.
+    ///
+    /// Unknown keys are compared against the config type during
+    /// deserialization and named by their dotted path (`server.prot`). With
+    /// [`UnknownKeys::Warn`] they are logged on every load; with
+    /// [`UnknownKeys::Deny`] the build or reload fails with
+    /// [`ConfigError::UnknownKeys`]. Keys captured by `#[serde(flatten)]`
+    /// maps count as consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    ///
+    /// # struct AppConfig;
+    /// # async fn example() {
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config.yaml")
+    ///     .with_unknown_keys(UnknownKeys::Deny);
+    /// # }
+    /// ```
+    pub fn with_unknown_keys(mut self, policy: UnknownKeys) -> Self {
+        self.unknown_keys = policy;
+        self
+    }
+
+    /// Report every load and validation error at once instead of the first.
+    ///
+    /// With [`ErrorMode::CollectAll`], every source is loaded even after one
+    /// fails, and the schema, key, and validator checks all run, so the build
+    /// or reload fails with a [`ConfigError::Multiple`] listing each failing
+    /// source and field. If any source fails, the merged configuration isn't
+    /// checked further, since it would be missing that source's keys.
+    /// Useful for CI validation and boot logs.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    ///
+    /// # struct AppConfig;
+    /// # async fn example() {
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config.yaml")
+    ///     .with_error_mode(ErrorMode::CollectAll);
+    /// # }
+    /// ```
+    pub fn with_error_mode(mut self, mode: ErrorMode) -> Self {
+        self.error_mode = mode;
+        self
+    }
+
+    /// Limit the size and structure of what each source loads.
+    ///
+    /// A source over a limit fails the load with
+    /// [`ConfigError::LimitExceeded`], so an untrusted or compromised source
+    /// can't exhaust memory. See [`SourceLimits`] for what is checked and
+    /// when.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    /// use hotswap_config::sources::SourceLimits;
+    ///
+    /// # struct AppConfig;
+    /// # async fn example() {
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config.yaml")
+    ///     .with_source_limits(
+    ///         SourceLimits::new()
+    ///             .with_max_payload_size(1024 * 1024)
+    ///             .with_max_depth(16)
+    ///             .with_max_keys(10_000)
+    ///             .with_max_string_length(64 * 1024),
+    ///     );
+    /// # }
+    /// ```
+    pub fn with_source_limits(mut self, limits: SourceLimits) -> Self {
+        self.source_limits = limits;
+        self
+    }
+
+    /// Declare keys that are read once at startup and can't change at runtime.
+    ///
+    /// Keys are dotted paths into the merged sources (`server.port`), and are
+    /// compared against their values at the first load. By default a reload
+    /// that changes one is rejected with a validation error on the key and the
+    /// current config is kept; see [`with_immutable_keys`](Self::with_immutable_keys)
+    /// to apply the rest of the reload instead. Values passed to
+    /// [`HotswapConfig::update`] aren't checked.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    ///
+    /// # struct AppConfig;
+    /// # async fn example() {
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config.yaml")
+    ///     .with_immutable_paths(["server.port", "database.url"]);
+    /// # }
+    /// ```
+    pub fn with_immutable_paths<I, S>(mut self, paths: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.immutable_paths
+            .extend(paths.into_iter().map(Into::into));
+        self
+    }
+
+    /// Set what a reload that changes an
+    /// [immutable key](Self::with_immutable_paths) does.
+    ///
+    /// With [`ImmutableKeys::RestartRequired`], the reload is applied with the
+    /// changed keys kept at their boot-time values, the keys are listed by
+    /// [`HotswapConfig::restart_required`], and the
+    /// [restart handler](Self::with_restart_required_handler) is called.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    ///
+    /// # struct AppConfig;
+    /// # async fn example() {
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config.yaml")
+    ///     .with_immutable_paths(["server.port"])
+    ///     .with_immutable_keys(ImmutableKeys::RestartRequired);
+    /// # }
+    /// ```
+    pub fn with_immutable_keys(mut self, policy: ImmutableKeys) -> Self {
+        self.immutable_keys = policy;
+        self
+    }
+
+    /// Set a handler called when a reload holds back changes to immutable
+    /// keys until a restart.
+    ///
+    /// Called with every key waiting for a restart whenever that set changes,
+    /// under [`ImmutableKeys::RestartRequired`]; use it to alert or to
+    /// schedule a rolling restart.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    ///
+    /// # struct AppConfig;
+    /// # async fn example() {
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config.yaml")
+    ///     .with_immutable_paths(["server.port"])
+    ///     .with_immutable_keys(ImmutableKeys::RestartRequired)
+    ///     .with_restart_required_handler(|keys: &[String]| {
+    ///         eprintln!("Restart required to apply: {}", keys.join(", "));
+    ///     });
+    /// # }
+    /// ```
+    pub fn with_restart_required_handler<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&[String]) + Send + Sync + 'static,
+    {
+        self.restart_handler = Some(Arc::new(handler));
+        self
+    }
+
+    /// Prepare reloaded configurations instead of swapping them in, so an
+    /// orchestrator can activate the same version on every replica at once.
+    ///
+    /// Every reload, automatic or not, loads and validates the new config as
+    /// [`HotswapConfig::prepare`] does and calls `handler` with its version;
+    /// use it to report the version to the orchestrator. The config stays
+    /// current until [`HotswapConfig::activate`] is called with that
+    /// version, which is when the reload counts as applied. Updates and
+    /// rollbacks still swap immediately.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    ///
+    /// # struct AppConfig;
+    /// # async fn example() {
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config.yaml")
+    ///     .with_coordinated_activation(|version: &str| {
+    ///         println!("prepared config {}, waiting for activation", version);
+    ///     });
+    /// # }
+    /// ```
+    pub fn with_coordinated_activation<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&str) + Send + Sync + 'static,
+    {
+        self.prepare_handler = Some(Arc::new(handler));
+        self
+    }
+
+    /// Set a policy deciding whether updates and patches may be applied.
+    ///
+    /// The policy is called before validation with the JSON Pointer paths an
+    /// update changes (e.g. `/server/port`) and an [`UpdateContext`] saying how
+    /// and by whom it was requested. Patches pass the paths they touch;
+    /// [`HotswapConfig::update`] and rollbacks replace the whole configuration
+    /// and pass the root path `""`. A [`PolicyDecision::Deny`] rejects the
+    /// update with [`ConfigError::UpdateDenied`], carrying the reason. Reloads
+    /// from sources are not subject to the policy.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    ///
+    /// # struct AppConfig;
+    /// # async fn example() {
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config.yaml")
+    ///     .with_update_policy(|paths: &[String], context: &UpdateContext| {
+    ///         let admin = context.actor.as_deref() == Some("admin");
+    ///         if !admin && paths.iter().any(|p| p.is_empty() || p.starts_with("/database")) {
+    ///             PolicyDecision::deny("only admin may change the database settings")
+    ///         } else {
+    ///             PolicyDecision::Allow
+    ///         }
+    ///     });
+    /// # }
+    /// ```
+    pub fn with_update_policy<F>(mut self, policy: F) -> Self
+    where
+        F: Fn(&[String], &UpdateContext) -> PolicyDecision + Send + Sync + 'static,
+    {
+        self.update_policy = Some(Arc::new(policy));
+        self
+    }
+
+    /// Enable file watching for automatic reloads.
+    ///
+    /// When enabled, the configuration will automatically reload when any
+    /// watched file changes. Uses a default debounce of 500ms.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    ///
+    /// # struct AppConfig;
+    /// # async fn example() {
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config.yaml")
+    ///     .with_file_watch(true);
+    /// # }
+    /// ```
+    #[cfg(feature = "file-watch")]
+    pub fn with_file_watch(mut self, enabled: bool) -> Self {
+        self.enable_file_watch = enabled;
+        self
+    }
+
+    /// Set the debounce duration for file watching.
+    ///
+    /// This is the minimum time between reload triggers when files change rapidly.
+    /// Default is 500ms.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// # struct AppConfig;
+    /// # async fn example() {
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config.yaml")
+    ///     .with_file_watch(true)
+    ///     .with_watch_debounce(Duration::from_secs(1));
+    /// # }
+    /// ```
+    #[cfg(feature = "file-watch")]
+    pub fn with_watch_debounce(mut self, duration: Duration) -> Self {
+        self.watch_debounce = duration;
+        self
+    }
+
+    /// Set a handler that is invoked when an automatic reload fails.
+    ///
+    /// Reloads triggered by file watching or overlay refreshes run in a
+    /// background task, so their errors can't be returned to the caller. Use
+    /// this to log, alert, or emit metrics on failure. Without a handler,
+    /// failures are printed to stderr.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    ///
+    /// # struct AppConfig;
+    /// # async fn example() {
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config.yaml")
+    ///     .with_file_watch(true)
+    ///     .with_reload_error_handler(|err: &ConfigError| {
+    ///         eprintln!("Config reload failed, keeping previous config: {}", err);
+    ///     });
+    /// # }
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub fn with_reload_error_handler<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&ConfigError) + Send + Sync + 'static,
+    {
+        self.reload_error_handler = Some(Arc::new(handler));
+        self
+    }
+
+    /// Set the delay before the first background retry of
+    /// [`build_with_defaults_on_failure`](Self::build_with_defaults_on_failure)
+    /// and the cap it doubles up to.
+    ///
+    /// Default is 1 second, capped at 60 seconds.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// # struct AppConfig;
+    /// # async fn example() {
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config/default.yaml")
+    ///     .with_startup_retry(Duration::from_millis(500), Duration::from_secs(30));
+    /// # }
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub fn with_startup_retry(mut self, initial: Duration, max: Duration) -> Self {
+        self.startup_retry = (initial, max.max(initial));
+        self
+    }
+
+    /// Reload every source on a schedule, in addition to any change notifications.
+    ///
+    /// For environments where file events are unreliable, such as NFS mounts
+    /// or some container volumes, this guarantees the config is re-evaluated
+    /// at least as often as the [`Schedule`](crate::core::Schedule) fires.
+    /// Reloads that find nothing changed are skipped; failures keep the
+    /// current config and are reported to the
+    /// [reload error handler](Self::with_reload_error_handler).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::core::Schedule;
+    /// use hotswap_config::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// # struct AppConfig;
+    /// # async fn example() {
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("/mnt/nfs/config.yaml")
+    ///     .with_file_watch(true)
+    ///     .with_reload_schedule(Schedule::every(Duration::from_secs(300)));
+    /// # }
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub fn with_reload_schedule(mut self, schedule: crate::core::Schedule) -> Self {
+        self.reload_schedule = Some(schedule);
+        self
+    }
+
+    /// Reload every source when the process receives `signal`.
+    ///
+    /// Lets operators trigger a reload like they would for a classic daemon,
+    /// with `kill -HUP <pid>` or an `ExecReload=` line in a systemd unit,
+    /// alongside or instead of file watching. Can be called more than once to
+    /// listen for several signals. Signals are listened for from the build
+    /// on, and stop the process no more; see [`Signal`](crate::core::Signal).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::core::Signal;
+    /// use hotswap_config::prelude::*;
+    ///
+    /// # struct AppConfig;
+    /// # async fn example() {
+    /// # #[cfg(unix)]
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config.yaml")
+    ///     .with_file_watch(false)
+    ///     .with_reload_on_signal(Signal::SIGHUP);
+    /// # }
+    /// ```
+    #[cfg(feature = "signal")]
+    pub fn with_reload_on_signal(mut self, signal: crate::core::Signal) -> Self {
+        if !self.reload_signals.contains(&signal) {
+            self.reload_signals.push(signal);
+        }
+        self
+    }
+
+    /// Reload every source on authenticated requests to a small HTTP
+    /// listener on `addr`.
+    ///
+    /// For CI/CD pipelines and config servers that know when the
+    /// configuration changed, so they can reload it at once instead of
+    /// waiting for a poll, without exposing the full
+    /// [admin API](crate::admin). The listener binds during the build, which
+    /// fails if it can't, and serves `POST /reload` until the handle shuts
+    /// down. Requests must carry an `Authorization: Bearer <token>` header;
+    /// others get `401 Unauthorized`. Each accepted request reloads right
+    /// away, without waiting out the
+    /// [minimum reload interval](Self::with_min_reload_interval), and answers
+    /// `200 OK`, or `500 Internal Server Error` with the reason the reload
+    /// failed. The listener speaks plain HTTP, so bind it to a private
+    /// interface or put it behind TLS termination. The build fails if
+    /// `token` is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    ///
+    /// # struct AppConfig;
+    /// # async fn example() {
+    /// let token = std::env::var("RELOAD_TOKEN").expect("RELOAD_TOKEN is set");
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config.yaml")
+    ///     .with_reload_webhook("0.0.0.0:9123", token);
+    ///
+    /// // curl -X POST -H "Authorization: Bearer $RELOAD_TOKEN" http://host:9123/reload
+    /// # }
+    /// ```
+    #[cfg(feature = "webhook")]
+    pub fn with_reload_webhook(
+        mut self,
+        addr: impl Into<String>,
+        token: impl Into<String>,
+    ) -> Self {
+        self.reload_webhook = Some(crate::core::webhook::ReloadWebhook::new(
+            addr.into(),
+            token.into(),
+        ));
+        self
+    }
+
+    /// Reload every source whenever `trigger` fires.
+    ///
+    /// For reloads driven by something outside the handle, such as a message
+    /// queue or a feature service's webhook. The reason the trigger gives is
+    /// reported to metrics and by
+    /// [`HotswapConfig::reload_reason`](crate::core::HotswapConfig::reload_reason).
+    /// Can be called more than once; each trigger runs on its own task until
+    /// it stops or the handle shuts down. See
+    /// [`ReloadTrigger`](crate::core::ReloadTrigger).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::core::ReloadReason;
+    /// use hotswap_config::prelude::*;
+    ///
+    /// # struct AppConfig;
+    /// # async fn example() {
+    /// let (trigger, reloads) = tokio::sync::mpsc::channel(16);
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config.yaml")
+    ///     .with_reload_trigger(reloads);
+    ///
+    /// trigger.send(ReloadReason::custom("deploy")).await.unwrap();
+    /// # }
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub fn with_reload_trigger(mut self, trigger: impl crate::core::ReloadTrigger) -> Self {
+        self.reload_triggers.push(Box::new(trigger));
+        self
+    }
+
+    /// Start automatic reloads at least `interval` apart.
+    ///
+    /// Applies to every reload the handle triggers itself: file watching,
+    /// source watches, push and polling sources, overlay refreshes, the
+    /// [reload schedule](Self::with_reload_schedule),
+    /// [signals](Self::with_reload_on_signal), and
+    /// [custom triggers](Self::with_reload_trigger). A change arriving
+    /// sooner waits until the interval has passed, and every change that
+    /// arrives meanwhile is folded into that one reload, so a flapping file
+    /// can't reconfigure the application more often than this, however each
+    /// source debounces. Manual [`reload`](HotswapConfig::reload) calls are not
+    /// limited.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// # struct AppConfig;
+    /// # async fn example() {
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config.yaml")
+    ///     .with_file_watch(true)
+    ///     .with_min_reload_interval(Duration::from_secs(5));
+    /// # }
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub fn with_min_reload_interval(mut self, interval: Duration) -> Self {
+        self.min_reload_interval = Some(interval);
+        self
+    }
+
+    /// Stop the handle's background tasks when `token` is cancelled.
+    ///
+    /// Ties file watching, scheduled refreshes, and streaming sources to an
+    /// application-wide shutdown signal. Cancelling the token stops the tasks
+    /// like [`HotswapConfig::shutdown`], without waiting for them; shutting
+    /// down the handle doesn't cancel `token`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    /// use tokio_util::sync::CancellationToken;
+    ///
+    /// # struct AppConfig;
+    /// # async fn example() {
+    /// let shutdown = CancellationToken::new();
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config.yaml")
+    ///     .with_cancellation_token(shutdown.clone());
+    ///
+    /// // Later, stop everything
+    /// shutdown.cancel();
+    /// # }
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub fn with_cancellation_token(mut self, token: tokio_util::sync::CancellationToken) -> Self {
+        self.cancellation_token = Some(token);
+        self
+    }
+
+    /// Stage reloaded configs as canaries instead of swapping them in immediately.
+    ///
+    /// Each successful reload becomes the canary of an attached
+    /// [`GradualRollout`](crate::features::GradualRollout), served to
+    /// `percentage`% of requests via
+    /// [`HotswapConfig::canary_rollout`]. If `promote_after` is set, a canary
+    /// that is still staged after that delay is promoted automatically;
+    /// otherwise promote or roll back through the rollout. Promotion makes the
+    /// canary the current config and notifies subscribers, unless the handle
+    /// is frozen or a pre-swap hook or transactional subscriber rejects it;
+    /// the canary then stays staged, and a failed automatic promotion goes to
+    /// the [reload error handler](Self::with_reload_error_handler).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// # struct AppConfig;
+    /// # async fn example() {
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config.yaml")
+    ///     .with_file_watch(true)
+    ///     .with_canary_reloads(10, Some(Duration::from_secs(300)));
+    /// # }
+    /// ```
+    #[cfg(feature = "gradual-rollout")]
+    pub fn with_canary_reloads(mut self, percentage: u8, promote_after: Option<Duration>) -> Self {
+        self.canary_reloads = Some((percentage, promote_after));
+        self
+    }
+
+    /// Keep the canary assignments of keys in `store`, for
+    /// [canary reloads](Self::with_canary_reloads).
+    ///
+    /// See [`GradualRollout::with_sticky_buckets`](crate::features::GradualRollout::with_sticky_buckets):
+    /// each key keeps the bucket it was first given, so raising the
+    /// percentage only adds keys to the canary.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::features::HashBucketStore;
+    /// use hotswap_config::prelude::*;
+    ///
+    /// # struct AppConfig;
+    /// # async fn example() {
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config.yaml")
+    ///     .with_canary_reloads(10, None)
+    ///     .with_sticky_buckets(HashBucketStore::with_salt("checkout-v2"));
+    /// # }
+    /// ```
+    #[cfg(feature = "gradual-rollout")]
+    pub fn with_sticky_buckets(
+        mut self,
+        store: impl crate::features::BucketStore + 'static,
+    ) -> Self {
+        self.sticky_buckets = Some(Arc::new(store));
+        self
+    }
+
+    /// Report which configuration each key is served while a
+    /// [canary reload](Self::with_canary_reloads) is staged.
+    ///
+    /// See [`GradualRollout::with_exposure_handler`](crate::features::GradualRollout::with_exposure_handler).
+    /// Selections are also counted by the
+    /// [metrics recorder](Self::with_metrics_recorder), if any.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    ///
+    /// # struct AppConfig;
+    /// # async fn example() {
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config.yaml")
+    ///     .with_canary_reloads(10, None)
+    ///     .with_exposure_handler(|key, variant| {
+    ///         println!("{} saw the {} config", key, variant);
+    ///     });
+    /// # }
+    /// ```
+    #[cfg(feature = "gradual-rollout")]
+    pub fn with_exposure_handler<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&str, crate::features::Variant) + Send + Sync + 'static,
+    {
+        self.exposure_handler = Some(Arc::new(handler));
+        self
+    }
+
+    /// Enable metrics collection with the provided meter.
+    ///
+    /// When enabled, the configuration will track reload attempts, success/failure
+    /// rates, latencies, and subscriber counts using OpenTelemetry metrics.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    /// use opentelemetry::global;
+    ///
+    /// # struct AppConfig;
+    /// # async fn example() {
+    /// let meter = global::meter("my-app");
+    ///
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config.yaml")
+    ///     .with_metrics(meter);
+    /// # }
+    /// ```
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics(self, meter: Meter) -> Self {
+        self.with_metrics_recorder(ConfigMetrics::new(meter))
+    }
+
+    /// Record metrics with a custom [`MetricsRecorder`].
+    ///
+    /// Use this for backends other than OpenTelemetry, such as
+    /// [`PrometheusRecorder`](crate::metrics::PrometheusRecorder). Replaces any
+    /// recorder set by [`with_metrics`](Self::with_metrics).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # #[cfg(feature = "prometheus")]
+    /// # struct AppConfig;
+    /// # fn example() -> hotswap_config::error::Result<()> {
+    /// use hotswap_config::metrics::PrometheusRecorder;
+    /// use hotswap_config::prelude::*;
+    ///
+    /// let registry = prometheus::Registry::new();
+    ///
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config.yaml")
+    ///     .with_metrics_recorder(PrometheusRecorder::new(&registry)?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_metrics_recorder<R: MetricsRecorder + 'static>(mut self, recorder: R) -> Self {
+        self.metrics = Some(Arc::new(recorder));
+        self
+    }
+
+    /// Record metrics with a recorder shared with other handles.
+    pub(crate) fn with_shared_metrics(
+        mut self,
+        recorder: Option<Arc<dyn MetricsRecorder>>,
+    ) -> Self {
+        self.metrics = recorder;
+        self
+    }
+
+    /// Watch files with a watcher shared with other handles, if file
+    /// watching is enabled.
+    #[cfg(feature = "file-watch")]
+    pub(crate) fn with_shared_watcher(mut self, watcher: Arc<crate::core::SharedWatcher>) -> Self {
+        self.shared_watcher = Some(watcher);
+        self
+    }
+
+    /// Build the configuration handle.
+    ///
+    /// This performs the initial load from all sources and validates the result.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Initial configuration load fails
+    /// - Deserialization fails
+    /// - Validation fails
+    pub async fn build(self) -> Result<HotswapConfig<T>>
+    where
+        T: DeserializeOwned + Clone + Send + Sync + 'static,
+    {
+        self.build_inner(None).await
+    }
+
+    /// Build the configuration handle, validating it with its [`Validate`]
+    /// implementation.
+    ///
+    /// `T::validate` runs on the initial build and before every reload or
+    /// update is applied, alongside any other validators, exactly as if it
+    /// had been added with [`with_validation`](Self::with_validation). The
+    /// other build methods can use it the same way with
+    /// `.with_validation(AppConfig::validate)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for the same reasons as [`build`](Self::build),
+    /// including `T::validate` failing.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    /// use hotswap_config::error::ValidationError;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Debug, Deserialize, Clone)]
+    /// struct AppConfig {
+    ///     port: u16,
+    /// }
+    ///
+    /// impl Validate for AppConfig {
+    ///     fn validate(&self) -> std::result::Result<(), ValidationError> {
+    ///         if self.port < 1024 {
+    ///             return Err(ValidationError::invalid_field("port", "must be >= 1024"));
+    ///         }
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// # async fn example() -> Result<()> {
+    /// let config = HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config.yaml")
+    ///     .build_validated()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "validation")]
+    pub async fn build_validated(self) -> Result<HotswapConfig<T>>
+    where
+        T: Validate + DeserializeOwned + Clone + Send + Sync + 'static,
+    {
+        self.with_validation(T::validate).build().await
+    }
+
+    /// Build the configuration without an async runtime.
+    ///
+    /// Returns a [`blocking::HotswapConfig`](crate::blocking::HotswapConfig)
+    /// whose `reload` and `update` block the calling thread. File watching
+    /// and other background work run on a thread owned by the handle.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the background thread can't be started, or for
+    /// the same reasons as [`build`](Self::build).
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from within an async runtime; use `build` there.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Debug, Deserialize, Clone)]
+    /// struct AppConfig {
+    ///     port: u16,
+    /// }
+    ///
+    /// # fn example() -> Result<()> {
+    /// let config = HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config.yaml")
+    ///     .build_blocking()?;
+    /// println!("Port: {}", config.get().port);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "blocking")]
+    pub fn build_blocking(self) -> Result<crate::blocking::HotswapConfig<T>>
+    where
+        T: DeserializeOwned + Clone + Send + Sync + 'static,
+    {
+        let runtime = crate::blocking::runtime()?;
+        let config = runtime.block_on(self.build())?;
+        Ok(crate::blocking::HotswapConfig::new(config, runtime))
+    }
+
+    /// Build the configuration handle, starting with `defaults` if the
+    /// sources can't be loaded.
+    ///
+    /// If the initial load fails, the [snapshot](Self::with_snapshot_fallback)
+    /// is tried first, then `defaults`; either goes through the usual
+    /// validation. Sources are then retried in the background with
+    /// exponential backoff (see [`with_startup_retry`](Self::with_startup_retry))
+    /// until a reload succeeds and the real configuration is swapped in,
+    /// notifying subscribers. Failed attempts go to the
+    /// [reload error handler](Self::with_reload_error_handler), and
+    /// [`HotswapConfig::is_fallback`] reports whether the real configuration
+    /// has arrived.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the defaults fail validation.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Debug, Deserialize, Clone)]
+    /// struct AppConfig {
+    ///     port: u16,
+    /// }
+    ///
+    /// # async fn example() -> Result<()> {
+    /// let config = HotswapConfig::builder()
+    ///     .with_file("config/default.yaml")
+    ///     .build_with_defaults_on_failure(AppConfig { port: 8080 })
+    ///     .await?;
+    ///
+    /// if config.is_fallback() {
+    ///     eprintln!("Config sources unavailable, running with defaults");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub async fn build_with_defaults_on_failure(self, defaults: T) -> Result<HotswapConfig<T>>
+    where
+        T: DeserializeOwned + Clone + Send + Sync + 'static,
+    {
+        self.build_inner(Some(defaults)).await
+    }
+
+    /// Build the handle, falling back to `defaults` (and retrying sources)
+    /// if given.
+    async fn build_inner(self, defaults: Option<T>) -> Result<HotswapConfig<T>>
+    where
+        T: DeserializeOwned + Clone + Send + Sync + 'static,
+    {
+        #[cfg(feature = "tokio")]
+        let retry_sources = defaults.is_some();
+        let mut loader = ConfigLoader::new();
+
+        // Add file and directory sources with increasing priority
+        let environment = self.active_profile();
+        let profiles = self
+            .files
+            .iter()
+            .any(|spec| matches!(spec, FileSpec::Profiles));
+        #[cfg(feature = "file-watch")]
+        let mut watch_paths = Vec::new();
+        // Files render as templates if templating is enabled
+        #[cfg(feature = "templating")]
+        let template = self.template.clone();
+        let file = |source: FileSource| {
+            #[cfg(feature = "templating")]
+            if let Some(context) = &template {
+                return source.with_template(context.clone());
+            }
+            source
+        };
+        for (index, spec) in self.files.into_iter().enumerate() {
+            let priority = 100 + (index as i32 * 10); // 100, 110, 120, etc.
+            #[cfg_attr(not(feature = "file-watch"), allow(unused_variables))]
+            let (source, optional): (Box<dyn ConfigSource>, bool) = match spec {
+                FileSpec::Profiles => {
+                    // The profile file sits between default and the next file
+                    if let Some(profile) = &environment {
+                        let path = crate::sources::find_file(&self.profile_dir, profile);
+                        let source = file(FileSource::new(path)).with_priority(priority + 5);
+                        #[cfg(feature = "file-watch")]
+                        watch_paths.extend(source.paths().into_iter().map(|path| (path, false)));
+                        loader.add_source(Box::new(source));
+                    }
+                    let path = crate::sources::find_file(&self.profile_dir, "default");
+                    (
+                        Box::new(file(FileSource::new(path)).with_priority(priority)),
+                        false,
+                    )
+                }
+                FileSpec::File(source) => {
+                    let optional = !source.is_required();
+                    (Box::new(file(source).with_priority(priority)), optional)
+                }
+                FileSpec::Directory(source) => {
+                    #[cfg(feature = "templating")]
+                    let source = match &template {
+                        Some(context) => source.with_template(context.clone()),
+                        None => source,
+                    };
+                    (Box::new(source.with_priority(priority)), false)
+                }
+                FileSpec::EnvFile(template) => {
+                    let Some(environment) = &environment else {
+                        continue;
+                    };
+                    let path = template.replace("{env}", environment);
+                    (
+                        Box::new(file(FileSource::optional(path)).with_priority(priority)),
+                        true,
+                    )
+                }
+            };
+            #[cfg(feature = "file-watch")]
+            watch_paths.extend(source.paths().into_iter().map(|path| (path, optional)));
+            loader.add_source(source);
+        }
+
+        // Add custom sources
+        for source in self.custom_sources {
+            loader.add_source(source);
+        }
+
+        // Add dotenv sources, sharing the env var prefix/separator
+        #[cfg(feature = "dotenv")]
+        for path in &self.dotenv_paths {
+            let mut source = crate::sources::DotenvSource::new(path);
+            if let (Some(prefix), Some(separator)) = (&self.env_prefix, &self.env_separator) {
+                source = source.with_prefix(prefix, separator);
+            }
+            loader.add_source(Box::new(source));
         }
 
-        // Load the configuration
-        let config: T = loader.load()?;
+        // Add environment variable source (highest priority), with profile
+        // variables above the general ones
+        if let (Some(prefix), Some(separator)) = (self.env_prefix, self.env_separator) {
+            let mut env_source = EnvSource::new(&prefix, &separator);
+            if let (true, Some(profile)) = (profiles, &environment) {
+                let profile_prefix =
+                    format!("{}_{}", prefix, profile.to_uppercase().replace('-', "_"));
+                env_source = env_source.excluding(&profile_prefix);
+                loader.add_source(Box::new(
+                    EnvSource::new(profile_prefix, &separator).with_priority(310),
+                ));
+            }
+            loader.add_source(Box::new(env_source));
+        }
+
+        // Add overlays, merged after everything else
+        #[cfg_attr(not(feature = "tokio"), allow(unused_variables))]
+        let overlay_intervals: Vec<Option<Duration>> = self
+            .overlays
+            .into_iter()
+            .map(|(source, interval)| {
+                #[cfg(feature = "file-watch")]
+                watch_paths.extend(source.paths().into_iter().map(|path| (path, false)));
+                loader.add_overlay(source);
+                interval
+            })
+            .collect();
+
+        loader.set_interpolation(self.interpolate);
+        #[cfg(feature = "expressions")]
+        loader.set_expressions(self.expressions);
+        loader.set_unknown_keys(self.unknown_keys);
+        loader.set_immutable_paths(self.immutable_paths, self.immutable_keys);
+        loader.set_error_mode(self.error_mode);
+        loader.set_limits(self.source_limits);
+        for validator in &self.section_validators {
+            loader.add_section_validator(Arc::clone(validator));
+        }
+        loader.set_migrations(self.migrations);
+        for hook in self.pre_load_hooks {
+            loader.add_pre_load_hook(hook);
+        }
+
+        // Chain the post-load hooks into one that runs them in order
+        let post_load_hook: Option<PostLoadHook<T>> =
+            (!self.post_load_hooks.is_empty()).then(|| {
+                let hooks = self.post_load_hooks;
+                Arc::new(move |config: T| {
+                    hooks.iter().try_fold(config, |config, hook| hook(config))
+                }) as PostLoadHook<T>
+            });
+
+        #[cfg(feature = "json-schema")]
+        if let Some(schema) = self.json_schema {
+            loader.set_schema(crate::core::ConfigSchema::new(schema)?);
+        }
+
+        #[cfg(feature = "encryption")]
+        if let Some(provider) = self.key_provider {
+            loader.set_key_provider(provider);
+        }
+        #[cfg(feature = "encryption")]
+        if let Some(decryptor) = self.field_decryptor {
+            loader.set_field_decryptor(decryptor);
+        }
+
+        // Verify signed sources, watching signature files along with the files
+        #[cfg(feature = "signing")]
+        if let Some(verifier) = self.signature_verifier {
+            loader.set_verifier(verifier);
+            #[cfg(feature = "file-watch")]
+            {
+                let signatures: Vec<_> = watch_paths
+                    .iter()
+                    .filter(|(path, _)| !path.is_dir())
+                    .map(|(path, _)| (crate::sources::signature_path(path), true))
+                    .collect();
+                watch_paths.extend(signatures);
+            }
+        }
+
+        // Load the configuration, falling back to the snapshot or defaults
+        let (config, fingerprint, section_errors, load_errors): (T, Option<_>, _, _) =
+            match loader.load_fingerprinted() {
+                Ok(loaded) => (
+                    match &post_load_hook {
+                        Some(hook) => hook(loaded.config)?,
+                        None => loaded.config,
+                    },
+                    Some((
+                        loaded.fingerprint,
+                        loaded.version,
+                        loaded.provenance,
+                        loaded.skipped,
+                        loaded.migrations,
+                    )),
+                    loaded.section_errors,
+                    loaded.errors,
+                ),
+                Err(e) => (
+                    load_fallback(
+                        e,
+                        self.snapshot_fallback.as_deref(),
+                        &self.section_validators,
+                        defaults,
+                    )?,
+                    None,
+                    Vec::new(),
+                    Vec::new(),
+                ),
+            };
+        #[cfg_attr(not(feature = "tokio"), allow(unused_variables))]
+        let booted_from_fallback = fingerprint.is_none();
+
+        // Combine the validators into one that runs them all
+        let typed_validator: Option<TypedValidator<T>> = (!self.validators.is_empty()).then(|| {
+            let validators = self.validators;
+            Arc::new(move |config: &T| {
+                ValidationError::collect(
+                    validators
+                        .iter()
+                        .filter_map(|validator| validator(config).err()),
+                )
+            }) as TypedValidator<T>
+        });
+
+        // Likewise for transition validators, which only run once there is a
+        // current config to compare against
+        let transition_validator: Option<TypedTransitionValidator<T>> =
+            (!self.transition_validators.is_empty()).then(|| {
+                let validators = self.transition_validators;
+                Arc::new(move |old: &T, new: &T| {
+                    ValidationError::collect(
+                        validators
+                            .iter()
+                            .filter_map(|validator| validator(old, new).err()),
+                    )
+                }) as TypedTransitionValidator<T>
+            });
 
-        // Convert the Any-based validator to a typed validator
-        let typed_validator: Option<TypedValidator<T>> = self.validator.as_ref().map(|v| {
-            let validator = Arc::clone(v);
-            Arc::new(move |config: &T| validator(config as &dyn std::any::Any)) as TypedValidator<T>
+        // Likewise for pre-swap hooks, the first denial winning
+        let pre_swap_hook: Option<PreSwapHook<T>> = (!self.pre_swap_hooks.is_empty()).then(|| {
+            let hooks = self.pre_swap_hooks;
+            Arc::new(move |old: &T, new: &T| {
+                hooks
+                    .iter()
+                    .map(|hook| hook(old, new))
+                    .find(|decision| matches!(decision, PolicyDecision::Deny(_)))
+                    .unwrap_or(PolicyDecision::Allow)
+            }) as PreSwapHook<T>
         });
 
-        // Validate if a validator was provided
+        // Validate sections and the whole config, reporting every failure
+        let mut errors = section_errors;
         if let Some(validator) = &typed_validator {
-            validator(&config).map_err(|e| ConfigError::ValidationError(e.to_string()))?;
+            errors.extend(validator(&config).err());
         }
+        let validation = ValidationError::collect(errors).map_err(ConfigError::from);
+        ConfigError::collect(load_errors.into_iter().chain(validation.err()))?;
 
-        // Also validate using Validate trait if feature is enabled
-        #[cfg(feature = "validation")]
-        if let Some(validatable) = (&config as &dyn std::any::Any).downcast_ref::<&dyn Validate>() {
-            validatable
-                .validate()
-                .map_err(|e| ConfigError::ValidationError(e.to_string()))?;
-        }
+        // Verify against external systems
+        let async_validators = self.async_validators;
+        let config = Arc::new(config);
+        crate::core::verify(&async_validators, &config).await?;
+        let config = Arc::unwrap_or_clone(config);
+
+        // Start sources that push their own change notifications
+        #[cfg(feature = "tokio")]
+        let source_changes = loader.watch();
 
         // Create the config handle with loader, validator, and metrics
+        let hotswap_config =
+            HotswapConfig::with_loader(config, loader, typed_validator, self.metrics)
+                .with_transition_validator(transition_validator)
+                .with_restart_handler(self.restart_handler)
+                .with_update_policy(self.update_policy)
+                .with_async_validators(async_validators)
+                .with_post_load_hook(post_load_hook)
+                .with_pre_swap_hook(pre_swap_hook)
+                .with_prepare_handler(self.prepare_handler)
+                .with_profile(environment);
+        let hotswap_config = match fingerprint {
+            Some((fingerprint, version, provenance, skipped, migrations)) => hotswap_config
+                .with_fingerprint(fingerprint, version, provenance)
+                .with_skipped_sources(skipped)
+                .with_applied_migrations(migrations),
+            None => hotswap_config.with_fallback(),
+        };
+
+        // Space automatic reloads apart if requested
+        #[cfg(feature = "tokio")]
+        let hotswap_config = match self.min_reload_interval {
+            Some(interval) => hotswap_config.with_min_reload_interval(interval),
+            None => hotswap_config,
+        };
+
+        // Report failed automatic reloads to the handler, if any
+        #[cfg(feature = "tokio")]
+        let hotswap_config =
+            hotswap_config.with_reload_error_handler(self.reload_error_handler.clone());
+
+        // Stop the background tasks with the application if requested
+        #[cfg(feature = "tokio")]
+        let hotswap_config = match self.cancellation_token {
+            Some(token) => hotswap_config.with_cancellation_token(token),
+            None => hotswap_config,
+        };
+
+        // Stage reloads into an attached canary rollout if requested, once its
+        // promotions can run as background tasks
+        #[cfg(feature = "gradual-rollout")]
+        let hotswap_config = match self.canary_reloads {
+            Some((percentage, promote_after)) => hotswap_config.with_canary_reloads(
+                percentage,
+                promote_after,
+                self.sticky_buckets,
+                self.exposure_handler,
+            ),
+            None => hotswap_config,
+        };
+
         #[cfg(feature = "file-watch")]
-        let mut hotswap_config = HotswapConfig::with_loader(
-            config,
-            loader,
-            typed_validator,
-            #[cfg(feature = "metrics")]
-            self.meter,
-        );
-        #[cfg(not(feature = "file-watch"))]
-        let hotswap_config = HotswapConfig::with_loader(
-            config,
-            loader,
-            typed_validator,
-            #[cfg(feature = "metrics")]
-            self.meter,
-        );
+        let mut hotswap_config = hotswap_config;
 
         // Set up file watching if enabled
         #[cfg(feature = "file-watch")]
         if self.enable_file_watch {
-            let (watcher, mut rx) = ConfigWatcher::new(self.watch_debounce)
-                .map_err(|e| ConfigError::Other(format!("Failed to create file watcher: {}", e)))?;
+            let (watcher, rx) = match &self.shared_watcher {
+                Some(shared) => (shared.watcher()?, None),
+                None => {
+                    let (watcher, rx) = ConfigWatcher::new(self.watch_debounce).map_err(|e| {
+                        ConfigError::Other(format!("Failed to create file watcher: {}", e))
+                    })?;
+                    (Arc::new(watcher), Some(rx))
+                }
+            };
 
-            // Watch all file paths
-            for path in &self.file_paths {
+            // Watch all files and directories
+            for (path, optional) in &watch_paths {
+                if *optional {
+                    watcher.watch_optional(path).await?;
+                } else {
+                    watcher.watch(path).await?;
+                }
+            }
+            #[cfg(feature = "dotenv")]
+            for path in &self.dotenv_paths {
                 watcher.watch(path).await?;
             }
 
-            let watcher_arc = Arc::new(watcher);
-            hotswap_config = hotswap_config.with_watcher(Arc::clone(&watcher_arc));
+            hotswap_config = hotswap_config.with_watcher(watcher);
+
+            match rx {
+                Some(rx) => {
+                    hotswap_config.follow_trigger(Box::new(crate::core::FileTrigger(rx)));
+                }
+                // The shared watcher reports every handle's files; only
+                // reload for this handle's, until it shuts down
+                None => {
+                    let config_clone = hotswap_config.detached();
+                    let error_handler = self.reload_error_handler.clone();
+                    self.shared_watcher
+                        .unwrap()
+                        .add_target(Arc::new(move |changed| {
+                            let config = config_clone.clone();
+                            let error_handler = error_handler.clone();
+                            Box::pin(async move {
+                                if config.is_shut_down() || !config.reads_changed(&changed) {
+                                    return;
+                                }
+                                let reason = ReloadReason::FileChanged(changed.to_vec());
+                                let Turn::Lead(slot) = config.throttle(reason).await else {
+                                    return;
+                                };
+                                let result = config.reload_with_reason(slot.reason.clone()).await;
+                                slot.finish(&result);
+                                if let Err(e) = result {
+                                    report_reload_error(error_handler.as_ref(), &e);
+                                }
+                            })
+                        }));
+                }
+            }
+        }
+
+        // Refresh each scheduled overlay on its own
+        #[cfg(feature = "tokio")]
+        for (index, interval) in overlay_intervals.into_iter().enumerate() {
+            let Some(interval) = interval else { continue };
+            let config = hotswap_config.detached();
+            let error_handler = self.reload_error_handler.clone();
+            hotswap_config.spawn(async move {
+                let mut ticks =
+                    tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+                ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                loop {
+                    ticks.tick().await;
+                    let result = match config.throttle(ReloadReason::Schedule).await {
+                        Turn::Lead(slot) => {
+                            let result = if slot.joined {
+                                // Other triggers joined in, which this overlay alone doesn't cover
+                                config.reload_with_reason(slot.reason.clone()).await
+                            } else {
+                                config.refresh_overlay(index).await
+                            };
+                            slot.finish(&result);
+                            result
+                        }
+                        Turn::Join(_) => Ok(()),
+                    };
+                    if let Err(e) = result {
+                        report_reload_error(error_handler.as_ref(), &e);
+                    }
+                }
+            });
+        }
+
+        // Force full reloads on the schedule, on signals, on custom
+        // triggers, and on webhook requests
+        #[cfg(feature = "tokio")]
+        if let Some(schedule) = self.reload_schedule {
+            hotswap_config.follow_trigger(Box::new(schedule));
+        }
+        #[cfg(feature = "signal")]
+        for signal in self.reload_signals {
+            hotswap_config.follow_trigger(Box::new(signal.listen()?));
+        }
+        #[cfg(feature = "tokio")]
+        for trigger in self.reload_triggers {
+            hotswap_config.follow_trigger(trigger);
+        }
+        #[cfg(feature = "webhook")]
+        if let Some(webhook) = self.reload_webhook {
+            webhook.start(&hotswap_config).await?;
+        }
+
+        // Swap in configs scheduled by their sources when they take effect
+        #[cfg(feature = "tokio")]
+        hotswap_config.follow_activations();
 
-            // Spawn a task to handle reload signals
-            let config_clone = hotswap_config.clone();
-            tokio::spawn(async move {
-                while let Some(()) = rx.recv().await {
-                    if let Err(e) = config_clone.reload().await {
-                        eprintln!("Auto-reload failed: {}", e);
+        // Re-load each watching source when it reports a change
+        #[cfg(feature = "tokio")]
+        for (index, changes) in source_changes {
+            hotswap_config.follow_source(index, changes);
+        }
+
+        // Keep trying the sources until the real config replaces the fallback
+        #[cfg(feature = "tokio")]
+        if booted_from_fallback && retry_sources {
+            let config = hotswap_config.detached();
+            let error_handler = self.reload_error_handler.clone();
+            let (initial, max) = self.startup_retry;
+            hotswap_config.spawn(async move {
+                let mut delay = initial;
+                while config.is_fallback() {
+                    tokio::time::sleep(delay).await;
+                    match config.reload_with_reason(ReloadReason::StartupRetry).await {
+                        Ok(()) => {
+                            #[cfg(feature = "tracing")]
+                            tracing::info!("Configuration sources recovered");
+                        }
+                        Err(e) => {
+                            report_reload_error(error_handler.as_ref(), &e);
+                            delay = (delay * 2).min(max);
+                        }
                     }
                 }
             });
@@ -378,15 +2967,74 @@ impl HotswapConfigBuilder {
     }
 }
 
-impl Default for HotswapConfigBuilder {
+/// Pick the startup fallback after the initial load failed with `error`:
+/// the snapshot if configured and loadable, else `defaults`.
+fn load_fallback<T>(
+    error: ConfigError,
+    snapshot: Option<&std::path::Path>,
+    section_validators: &[SectionValidator],
+    defaults: Option<T>,
+) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    let error = match snapshot {
+        Some(path) => {
+            let mut snapshot_loader = ConfigLoader::new();
+            snapshot_loader.add_source(Box::new(FileSource::new(path)));
+            for validator in section_validators {
+                snapshot_loader.add_section_validator(Arc::clone(validator));
+            }
+            // Section validators fail the snapshot load directly
+            match snapshot_loader.load() {
+                Ok(config) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(
+                        snapshot = %path.display(),
+                        error = %error,
+                        "Failed to load configuration, booting from snapshot"
+                    );
+                    #[cfg(not(feature = "tracing"))]
+                    eprintln!(
+                        "Failed to load configuration, booting from snapshot {}: {}",
+                        path.display(),
+                        error
+                    );
+                    return Ok(config);
+                }
+                Err(snapshot_err) => ConfigError::LoadError(format!(
+                    "{}; snapshot fallback {} also failed: {}",
+                    error,
+                    path.display(),
+                    snapshot_err
+                )),
+            }
+        }
+        None => error,
+    };
+
+    let Some(defaults) = defaults else {
+        return Err(error);
+    };
+    #[cfg(feature = "tracing")]
+    tracing::warn!(error = %error, "Failed to load configuration, starting with defaults");
+    #[cfg(not(feature = "tracing"))]
+    eprintln!(
+        "Failed to load configuration, starting with defaults: {}",
+        error
+    );
+    Ok(defaults)
+}
+
+impl<T> Default for HotswapConfigBuilder<T> {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl HotswapConfig<()> {
-    /// Create a new builder for constructing a configuration handle.
-    pub fn builder() -> HotswapConfigBuilder {
+impl<T> HotswapConfig<T> {
+    /// Create a new builder for constructing a configuration handle of `T`.
+    pub fn builder() -> HotswapConfigBuilder<T> {
         HotswapConfigBuilder::new()
     }
 }
@@ -412,22 +3060,783 @@ mod tests {
         });
 
         // Should be able to build (validation happens in build())
-        assert!(builder.file_paths.is_empty());
+        assert!(builder.files.is_empty());
     }
 
     #[test]
     fn test_builder_accumulates_files() {
-        let builder = HotswapConfigBuilder::new()
+        let builder = HotswapConfigBuilder::<TestConfig>::new()
             .with_file("config1.yaml")
             .with_file("config2.yaml")
             .with_file("config3.yaml");
 
-        assert_eq!(builder.file_paths.len(), 3);
+        assert_eq!(builder.files.len(), 3);
+    }
+
+    #[cfg(feature = "file-watch")]
+    #[test]
+    fn test_builder_reload_error_handler() {
+        let builder = HotswapConfigBuilder::<TestConfig>::new();
+        assert!(builder.reload_error_handler.is_none());
+
+        let builder = builder.with_reload_error_handler(|_err| {});
+        assert!(builder.reload_error_handler.is_some());
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test(start_paused = true)]
+    async fn test_min_reload_interval_spaces_automatic_reloads() {
+        use crate::testing::MemorySource;
+
+        let source = MemorySource::new()
+            .with_value("port", 8080)
+            .with_value("host", "localhost");
+        let config = HotswapConfigBuilder::<TestConfig>::new()
+            .with_source(source.clone())
+            .with_min_reload_interval(Duration::from_secs(1))
+            .build()
+            .await
+            .unwrap();
+        let mut changes = config.changes();
+
+        let start = tokio::time::Instant::now();
+        source.set("port", 9090);
+        changes.changed().await.unwrap();
+        assert_eq!(config.generation(), 1);
+
+        // A burst of changes waits out the interval, then reloads once
+        for port in [9091, 9092, 9093] {
+            source.set("port", port);
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert_eq!(config.get().port, 9090);
+
+        changes.changed().await.unwrap();
+        assert!(start.elapsed() >= Duration::from_secs(1));
+        assert_eq!(config.get().port, 9093);
+        assert_eq!(config.generation(), 2);
+
+        // Manual reloads aren't limited
+        source.set("port", 9094);
+        config.reload().await.unwrap();
+        assert_eq!(config.get().port, 9094);
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test(start_paused = true)]
+    async fn test_shutdown_stops_automatic_reloads() {
+        use crate::testing::MemorySource;
+        use tokio_util::sync::CancellationToken;
+
+        let source = MemorySource::new()
+            .with_value("port", 8080)
+            .with_value("host", "localhost");
+        let build = |token: Option<CancellationToken>| {
+            let builder = HotswapConfigBuilder::<TestConfig>::new().with_source(source.clone());
+            let builder = match token {
+                Some(token) => builder.with_cancellation_token(token),
+                None => builder,
+            };
+            builder.build()
+        };
+
+        let config = build(None).await.unwrap();
+        let mut changes = config.changes();
+        source.set("port", 9090);
+        changes.changed().await.unwrap();
+
+        config.shutdown().await;
+        assert!(config.is_shut_down());
+        source.set("port", 9091);
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert_eq!(config.get().port, 9090);
+
+        // Manual reloads still work
+        config.reload().await.unwrap();
+        assert_eq!(config.get().port, 9091);
+
+        // Cancelling the application's token stops the tasks too
+        let token = CancellationToken::new();
+        let config = build(Some(token.clone())).await.unwrap();
+        token.cancel();
+        assert!(config.is_shut_down());
+
+        // So does dropping every handle
+        let config = build(None).await.unwrap();
+        let stopped = config.cancellation_token();
+        let clone = config.clone();
+        drop(config);
+        assert!(!stopped.is_cancelled());
+        drop(clone);
+        assert!(stopped.is_cancelled());
+    }
+
+    #[cfg(feature = "dotenv")]
+    #[tokio::test]
+    async fn test_builder_with_dotenv() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.yaml");
+        let dotenv_path = temp_dir.path().join(".env");
+        std::fs::write(&config_path, "port: 8080\nhost: localhost\n").unwrap();
+        std::fs::write(&dotenv_path, "HOTSWAP_DOTENV_TEST__PORT=9090\n").unwrap();
+
+        let config = HotswapConfigBuilder::<TestConfig>::new()
+            .with_file(&config_path)
+            .with_dotenv(&dotenv_path)
+            .with_env_overrides("HOTSWAP_DOTENV_TEST", "__")
+            .build()
+            .await
+            .unwrap();
+
+        assert_eq!(config.get().port, 9090);
+        assert_eq!(config.get().host, "localhost");
+    }
+
+    #[tokio::test]
+    async fn test_builder_with_defaults() {
+        #[derive(Debug, serde::Serialize, Deserialize, Clone)]
+        struct Defaults {
+            port: u16,
+            host: String,
+        }
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.yaml");
+        std::fs::write(&config_path, "port: 9090\n").unwrap();
+
+        let config = HotswapConfigBuilder::<TestConfig>::new()
+            .with_defaults(Defaults {
+                port: 8080,
+                host: "localhost".to_string(),
+            })
+            .with_file(&config_path)
+            .build()
+            .await
+            .unwrap();
+
+        assert_eq!(config.get().port, 9090);
+        assert_eq!(config.get().host, "localhost");
+    }
+
+    #[tokio::test]
+    async fn test_transition_validation() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.yaml");
+        std::fs::write(&config_path, "port: 8080\nhost: localhost\n").unwrap();
+
+        let config = HotswapConfigBuilder::<TestConfig>::new()
+            .with_file(&config_path)
+            .with_immutable_field("host", |c: &TestConfig| c.host.as_str())
+            .with_transition_validation(|old: &TestConfig, new: &TestConfig| {
+                if new.port < old.port {
+                    return Err(ValidationError::invalid_field("port", "can't decrease"));
+                }
+                Ok(())
+            })
+            .build()
+            .await
+            .unwrap();
+
+        // Both reported together, and the current config kept
+        std::fs::write(&config_path, "port: 80\nhost: example.com\n").unwrap();
+        let error = config.reload().await.unwrap_err().to_string();
+        assert!(
+            error.contains("'host'") && error.contains("'port'"),
+            "{}",
+            error
+        );
+        assert_eq!(config.get().port, 8080);
+
+        let update = TestConfig {
+            port: 9090,
+            host: "example.com".to_string(),
+        };
+        assert!(config.update(update).await.is_err());
+
+        std::fs::write(&config_path, "port: 9090\nhost: localhost\n").unwrap();
+        config.reload().await.unwrap();
+        assert_eq!(config.get().port, 9090);
+    }
+
+    #[cfg(feature = "validation")]
+    impl Validate for TestConfig {
+        fn validate(&self) -> std::result::Result<(), ValidationError> {
+            if self.port < 1024 {
+                return Err(ValidationError::invalid_field("port", "must be >= 1024"));
+            }
+            Ok(())
+        }
+    }
+
+    #[cfg(feature = "validation")]
+    #[tokio::test]
+    async fn test_build_validated() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.yaml");
+        std::fs::write(&config_path, "port: 80\nhost: localhost\n").unwrap();
+        let builder = || HotswapConfigBuilder::<TestConfig>::new().with_file(&config_path);
+        assert!(builder().build_validated().await.is_err());
+
+        std::fs::write(&config_path, "port: 8080\nhost: localhost\n").unwrap();
+        let config = builder().build_validated().await.unwrap();
+
+        // Reloads and updates are validated too
+        std::fs::write(&config_path, "port: 80\nhost: localhost\n").unwrap();
+        assert!(config.reload().await.is_err());
+        let update = TestConfig {
+            port: 443,
+            host: "localhost".to_string(),
+        };
+        assert!(config.update(update).await.is_err());
+        assert_eq!(config.get().port, 8080);
+    }
+
+    #[tokio::test]
+    async fn test_hooks() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.yaml");
+        std::fs::write(&config_path, "listen_port: 8080\nhost: LOCALHOST\n").unwrap();
+
+        let config = HotswapConfigBuilder::<TestConfig>::new()
+            .with_file(&config_path)
+            .with_pre_load_hook(|raw| {
+                if let Some(port) = raw.remove("listen_port") {
+                    raw.insert("port".to_string(), port);
+                }
+                Ok(())
+            })
+            .with_post_load_hook(|mut config| {
+                config.host = config.host.to_lowercase();
+                Ok(config)
+            })
+            .with_validation(|config| match config.host.as_str() {
+                "localhost" | "example.com" => Ok(()),
+                _ => Err(ValidationError::invalid_field("host", "unknown host")),
+            })
+            .with_pre_swap_hook(|old, new| {
+                if new.port < old.port {
+                    return PolicyDecision::deny("port can't decrease");
+                }
+                PolicyDecision::Allow
+            })
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(config.get().port, 8080);
+        assert_eq!(config.get().host, "localhost");
+
+        // Vetoed reloads and updates keep the current config
+        std::fs::write(&config_path, "listen_port: 80\nhost: Example.com\n").unwrap();
+        let error = config.reload().await.unwrap_err();
+        assert!(matches!(error, ConfigError::SwapVetoed { .. }), "{}", error);
+        let update = TestConfig {
+            port: 80,
+            host: "localhost".to_string(),
+        };
+        assert!(config.update(update).await.is_err());
+        assert_eq!(config.get().port, 8080);
+
+        std::fs::write(&config_path, "listen_port: 9090\nhost: Example.com\n").unwrap();
+        config.reload().await.unwrap();
+        assert_eq!(config.get().port, 9090);
+        assert_eq!(config.get().host, "example.com");
+    }
+
+    #[tokio::test]
+    async fn test_migrations() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.yaml");
+        std::fs::write(&config_path, "listen_port: 8080\nhost: localhost\n").unwrap();
+
+        let config = HotswapConfigBuilder::<TestConfig>::new()
+            .with_file(&config_path)
+            .with_migration(1, 2, |raw| {
+                let port = raw
+                    .remove("listen_port")
+                    .ok_or_else(|| ConfigError::ValidationError("no listen_port".into()))?;
+                raw.insert("port".to_string(), port);
+                Ok(())
+            })
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(config.get().port, 8080);
+        assert_eq!(
+            config.applied_migrations(),
+            [crate::core::AppliedMigration { from: 1, to: 2 }]
+        );
+
+        std::fs::write(
+            &config_path,
+            "config_version: 2\nport: 9090\nhost: localhost\n",
+        )
+        .unwrap();
+        config.reload().await.unwrap();
+        assert_eq!(config.get().port, 9090);
+        assert!(config.applied_migrations().is_empty());
+
+        // A failing migration fails the reload
+        std::fs::write(
+            &config_path,
+            "config_version: 1\nport: 80\nhost: localhost\n",
+        )
+        .unwrap();
+        assert!(config.reload().await.is_err());
+        assert_eq!(config.get().port, 9090);
+    }
+
+    #[tokio::test]
+    async fn test_coordinated_activation() {
+        /// A remote document versioned by its server, like an `ETag`.
+        #[derive(Clone)]
+        struct Versioned(Arc<std::sync::Mutex<(&'static str, u16)>>);
+
+        impl ConfigSource for Versioned {
+            fn load(&self) -> Result<std::collections::HashMap<String, config::Value>> {
+                let port = self.0.lock().unwrap().1;
+                Ok(std::collections::HashMap::from([
+                    ("port".to_string(), port.into()),
+                    ("host".to_string(), "localhost".into()),
+                ]))
+            }
+
+            fn name(&self) -> String {
+                "versioned".to_string()
+            }
+
+            fn content_version(&self) -> Option<String> {
+                Some(self.0.lock().unwrap().0.to_string())
+            }
+        }
+
+        /// Counts successful reloads.
+        #[derive(Clone, Default)]
+        struct Successes(Arc<std::sync::atomic::AtomicUsize>);
+
+        impl crate::metrics::MetricsRecorder for Successes {
+            fn reload_attempted(&self) {}
+            fn reload_succeeded(&self, _duration: std::time::Duration) {
+                self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            }
+            fn reload_failed(&self, _duration: std::time::Duration) {}
+            fn validation_failed(&self) {}
+            fn config_updated(&self) {}
+            fn subscribers_changed(&self, _count: usize) {}
+        }
+
+        let remote = Versioned(Arc::new(std::sync::Mutex::new(("v1", 8080))));
+        let prepared = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = Arc::clone(&prepared);
+        let successes = Successes::default();
+        let config = HotswapConfigBuilder::<TestConfig>::new()
+            .with_source(remote.clone())
+            .with_coordinated_activation(move |version: &str| {
+                seen.lock().unwrap().push(version.to_string())
+            })
+            .with_metrics_recorder(successes.clone())
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(config.version().as_deref(), Some("v1"));
+        let succeeded = || successes.0.load(std::sync::atomic::Ordering::SeqCst);
+
+        // Reloads prepare the new version once, keeping the current one live
+        *remote.0.lock().unwrap() = ("v2", 9090);
+        config.reload().await.unwrap();
+        config.reload().await.unwrap();
+        assert_eq!(*prepared.lock().unwrap(), ["v2"]);
+        assert_eq!(config.prepared_version().as_deref(), Some("v2"));
+        assert_eq!(config.get().port, 8080);
+        // Staging isn't a completed reload yet
+        assert_eq!(succeeded(), 0);
+        assert_eq!(config.reload_reason(), None);
+
+        let error = config.activate("v3").await.unwrap_err();
+        assert!(
+            matches!(error, ConfigError::NotPrepared { .. }),
+            "{}",
+            error
+        );
+        config.activate("v2").await.unwrap();
+        assert_eq!(config.get().port, 9090);
+        assert_eq!(config.version().as_deref(), Some("v2"));
+        assert_eq!(config.prepared_version(), None);
+        assert_eq!(succeeded(), 1);
+        assert_eq!(config.reload_reason(), Some(ReloadReason::Manual));
+        // Activating the current version again is a no-op
+        config.activate("v2").await.unwrap();
+
+        // Swapping in anything else invalidates what was prepared
+        *remote.0.lock().unwrap() = ("v3", 7070);
+        assert_eq!(config.prepare().await.unwrap(), "v3");
+        config.update(config.get().as_ref().clone()).await.unwrap();
+        assert_eq!(config.version(), None);
+        assert!(config.activate("v3").await.is_err());
+        assert_eq!(config.get().port, 9090);
+    }
+
+    #[tokio::test]
+    async fn test_scheduled_activation() {
+        use std::time::{Duration, SystemTime};
+
+        /// A document that carries the time it takes effect.
+        #[derive(Clone)]
+        struct Timed(Arc<std::sync::Mutex<(u16, Option<SystemTime>)>>);
+
+        impl ConfigSource for Timed {
+            fn load(&self) -> Result<std::collections::HashMap<String, config::Value>> {
+                let port = self.0.lock().unwrap().0;
+                Ok(std::collections::HashMap::from([
+                    ("port".to_string(), port.into()),
+                    ("host".to_string(), "localhost".into()),
+                ]))
+            }
+
+            fn name(&self) -> String {
+                "timed".to_string()
+            }
+
+            fn effective_at(&self) -> Option<SystemTime> {
+                self.0.lock().unwrap().1
+            }
+        }
+
+        let in_future = |delay| Some(SystemTime::now() + delay);
+        let remote = Timed(Arc::new(std::sync::Mutex::new((
+            8080,
+            in_future(Duration::from_secs(60)),
+        ))));
+        // The initial build can't keep an earlier config
+        let config = HotswapConfigBuilder::<TestConfig>::new()
+            .with_source(remote.clone())
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(config.get().port, 8080);
+        assert_eq!(config.scheduled_at(), None);
+
+        // Held until it takes effect, then swapped in without a reload
+        let at = in_future(Duration::from_millis(300));
+        *remote.0.lock().unwrap() = (9090, at);
+        config.reload().await.unwrap();
+        config.reload().await.unwrap();
+        assert_eq!(config.get().port, 8080);
+        assert_eq!(config.scheduled_at(), at);
+        tokio::time::sleep(Duration::from_millis(600)).await;
+        assert_eq!(config.get().port, 9090);
+        assert_eq!(config.reload_reason(), Some(ReloadReason::Activation));
+        assert_eq!(config.scheduled_at(), None);
+
+        // Cancelled when the sources go back to the current config
+        *remote.0.lock().unwrap() = (7070, in_future(Duration::from_secs(60)));
+        config.reload().await.unwrap();
+        assert!(config.scheduled_at().is_some());
+        *remote.0.lock().unwrap() = (9090, None);
+        config.reload().await.unwrap();
+        assert_eq!(config.scheduled_at(), None);
+
+        // A time already passed takes effect right away
+        *remote.0.lock().unwrap() = (6060, Some(SystemTime::now() - Duration::from_secs(1)));
+        config.reload().await.unwrap();
+        assert_eq!(config.get().port, 6060);
+        assert_eq!(config.reload_reason(), Some(ReloadReason::Manual));
+    }
+
+    #[tokio::test]
+    async fn test_custom_reload_trigger() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.yaml");
+        std::fs::write(&config_path, "port: 8080\nhost: localhost\n").unwrap();
+
+        let (trigger, reloads) = tokio::sync::mpsc::unbounded_channel();
+        let config = HotswapConfigBuilder::<TestConfig>::new()
+            .with_file(&config_path)
+            .with_file_watch(false)
+            .with_reload_trigger(reloads)
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(config.reload_reason(), None);
+
+        std::fs::write(&config_path, "port: 9090\nhost: localhost\n").unwrap();
+        trigger.send(ReloadReason::custom("webhook")).unwrap();
+        for _ in 0..200 {
+            if config.get().port == 9090 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(config.get().port, 9090);
+        assert_eq!(
+            config.reload_reason(),
+            Some(ReloadReason::custom("webhook"))
+        );
+
+        // A reload that changes nothing keeps the reason of the last change
+        config.reload().await.unwrap();
+        assert_eq!(
+            config.reload_reason(),
+            Some(ReloadReason::custom("webhook"))
+        );
+        std::fs::write(&config_path, "port: 7070\nhost: localhost\n").unwrap();
+        config.reload().await.unwrap();
+        assert_eq!(config.reload_reason(), Some(ReloadReason::Manual));
+    }
+
+    #[tokio::test]
+    async fn test_reload_trigger_receives_results() {
+        use crate::core::{ReloadTrigger, TriggerFuture};
+
+        /// Fires on each message, passing on whether each reload succeeded.
+        struct Acknowledged {
+            fires: tokio::sync::mpsc::UnboundedReceiver<ReloadReason>,
+            results: tokio::sync::mpsc::UnboundedSender<bool>,
+        }
+
+        impl ReloadTrigger for Acknowledged {
+            fn next(&mut self) -> TriggerFuture<'_> {
+                Box::pin(self.fires.recv())
+            }
+
+            fn reloaded(&mut self, result: &Result<()>) {
+                self.results.send(result.is_ok()).unwrap();
+            }
+        }
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.yaml");
+        std::fs::write(&config_path, "port: 8080\nhost: localhost\n").unwrap();
+
+        let (trigger, fires) = tokio::sync::mpsc::unbounded_channel();
+        let (results, mut acks) = tokio::sync::mpsc::unbounded_channel();
+        let config = HotswapConfigBuilder::<TestConfig>::new()
+            .with_file(&config_path)
+            .with_file_watch(false)
+            .with_reload_trigger(Acknowledged { fires, results })
+            .with_reload_error_handler(|_| {})
+            .build()
+            .await
+            .unwrap();
+
+        std::fs::write(&config_path, "port: 9090\nhost: localhost\n").unwrap();
+        trigger.send(ReloadReason::custom("deploy")).unwrap();
+        assert_eq!(acks.recv().await, Some(true));
+        assert_eq!(config.get().port, 9090);
+
+        std::fs::write(&config_path, "port: nope\nhost: localhost\n").unwrap();
+        trigger.send(ReloadReason::custom("deploy")).unwrap();
+        assert_eq!(acks.recv().await, Some(false));
+        assert_eq!(config.get().port, 9090);
+    }
+
+    #[tokio::test]
+    async fn test_immutable_paths() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.yaml");
+        std::fs::write(&config_path, "port: 8080\nhost: localhost\n").unwrap();
+
+        let config = HotswapConfigBuilder::<TestConfig>::new()
+            .with_file(&config_path)
+            .with_immutable_paths(["port"])
+            .build()
+            .await
+            .unwrap();
+        std::fs::write(&config_path, "port: 9090\nhost: example.com\n").unwrap();
+        let error = config.reload().await.unwrap_err();
+        assert!(error.to_string().contains("'port'"), "{}", error);
+        assert_eq!(config.get().host, "localhost");
+
+        // Deferred: the rest applies, and the key waits for a restart
+        std::fs::write(&config_path, "port: 8080\nhost: localhost\n").unwrap();
+        let reported = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let handler_reported = Arc::clone(&reported);
+        let config = HotswapConfigBuilder::<TestConfig>::new()
+            .with_file(&config_path)
+            .with_immutable_paths(["port"])
+            .with_immutable_keys(ImmutableKeys::RestartRequired)
+            .with_restart_required_handler(move |keys: &[String]| {
+                handler_reported.lock().unwrap().push(keys.to_vec());
+            })
+            .build()
+            .await
+            .unwrap();
+        std::fs::write(&config_path, "port: 9090\nhost: example.com\n").unwrap();
+        config.reload().await.unwrap();
+        assert_eq!(
+            *config.get(),
+            TestConfig {
+                port: 8080,
+                host: "example.com".to_string(),
+            }
+        );
+        assert_eq!(config.restart_required(), ["port"]);
+
+        // Reported once, and cleared when the sources match again
+        config.reload().await.unwrap();
+        std::fs::write(&config_path, "port: 8080\nhost: example.com\n").unwrap();
+        config.reload().await.unwrap();
+        assert!(config.restart_required().is_empty());
+        assert_eq!(*reported.lock().unwrap(), [["port"]]);
+    }
+
+    #[cfg(feature = "templating")]
+    #[tokio::test]
+    async fn test_templated_files() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.yaml");
+        std::fs::write(
+            &config_path,
+            "port: {{ base_port + 1 }}\nhost: {% if env.HOTSWAP_TEMPLATE_TEST_HOST %}{{ env.HOTSWAP_TEMPLATE_TEST_HOST }}{% else %}localhost{% endif %}\n",
+        )
+        .unwrap();
+
+        let config = HotswapConfigBuilder::<TestConfig>::new()
+            .with_file(&config_path)
+            .with_template_var("base_port", 8079)
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(config.get().port, 8080);
+        assert_eq!(config.get().host, "localhost");
+
+        // Templates are rendered again on reload, and errors name the file
+        std::fs::write(&config_path, "port: {{ missing }}\nhost: localhost\n").unwrap();
+        let error = config.reload().await.unwrap_err();
+        assert!(error.to_string().contains("config.yaml"), "{}", error);
+        assert_eq!(config.get().port, 8080);
+    }
+
+    #[tokio::test]
+    async fn test_source_limits() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.yaml");
+        std::fs::write(&config_path, "port: 8080\nhost: localhost\n").unwrap();
+
+        let config = HotswapConfigBuilder::<TestConfig>::new()
+            .with_file(&config_path)
+            .with_source_limits(SourceLimits::new().with_max_string_length(16))
+            .build()
+            .await
+            .unwrap();
+        std::fs::write(&config_path, "port: 9090\nhost: a-very-long-host-name\n").unwrap();
+        let error = config.reload().await.unwrap_err();
+        assert_eq!(error.key_path(), Some("host"));
+        assert!(matches!(error.root(), ConfigError::LimitExceeded { .. }));
+        assert_eq!(config.get().port, 8080);
+
+        // Oversized files are refused before they are read
+        let Err(error) = HotswapConfigBuilder::<TestConfig>::new()
+            .with_file(&config_path)
+            .with_source_limits(SourceLimits::new().with_max_payload_size(16))
+            .build()
+            .await
+        else {
+            panic!("oversized file was loaded");
+        };
+        assert!(error.to_string().contains("payload size"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn test_builder_snapshot_fallback() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let missing_path = temp_dir.path().join("missing.yaml");
+        let snapshot_path = temp_dir.path().join("snapshot.yaml");
+        std::fs::write(&snapshot_path, "port: 8080\nhost: snapshot\n").unwrap();
+
+        let config = HotswapConfigBuilder::<TestConfig>::new()
+            .with_file(&missing_path)
+            .with_snapshot_fallback(&snapshot_path)
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(config.get().host, "snapshot");
+
+        // Without a usable snapshot the original error is kept
+        let result = HotswapConfigBuilder::<TestConfig>::new()
+            .with_file(&missing_path)
+            .with_snapshot_fallback(temp_dir.path().join("none.yaml"))
+            .build()
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_builder_scoped_validation_collects_all_errors() {
+        #[derive(Debug, Deserialize, Clone)]
+        struct Database {
+            max_connections: u32,
+        }
+
+        #[derive(Debug, Deserialize, Clone)]
+        struct App {
+            port: u16,
+            database: Database,
+        }
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.yaml");
+        std::fs::write(&config_path, "port: 80\ndatabase:\n  max_connections: 0\n").unwrap();
+
+        let result = HotswapConfigBuilder::<App>::new()
+            .with_file(&config_path)
+            .with_validation(|c: &App| {
+                if c.port < 1024 {
+                    return Err(ValidationError::invalid_field("port", "must be >= 1024"));
+                }
+                Ok(())
+            })
+            .with_validation_at("database", |db: &Database| {
+                if db.max_connections == 0 {
+                    return Err(ValidationError::invalid_field(
+                        "max_connections",
+                        "must be > 0",
+                    ));
+                }
+                Ok(())
+            })
+            .with_validation_at("cache", |_: &Database| Ok(()))
+            .build()
+            .await;
+
+        let message = match result {
+            Err(ConfigError::ValidationError(message)) => message,
+            other => panic!("expected validation error, got {:?}", other.map(|_| ())),
+        };
+        assert!(
+            message.contains("Multiple validation errors"),
+            "{}",
+            message
+        );
+        assert!(
+            message.contains("'database.max_connections'"),
+            "{}",
+            message
+        );
+        assert!(message.contains("'port'"), "{}", message);
+        assert!(message.contains("'cache'"), "{}", message);
+
+        // Passing sections build normally
+        std::fs::write(
+            &config_path,
+            "port: 8080\ndatabase:\n  max_connections: 5\n",
+        )
+        .unwrap();
+        let config = HotswapConfigBuilder::<App>::new()
+            .with_file(&config_path)
+            .with_validation_at("database", |db: &Database| {
+                assert_eq!(db.max_connections, 5);
+                Ok(())
+            })
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(config.get().port, 8080);
+        assert_eq!(config.get().database.max_connections, 5);
     }
 
     #[test]
     fn test_builder_env_overrides() {
-        let builder = HotswapConfigBuilder::new().with_env_overrides("APP", "__");
+        let builder = HotswapConfigBuilder::<TestConfig>::new().with_env_overrides("APP", "__");
 
         assert_eq!(builder.env_prefix, Some("APP".to_string()));
         assert_eq!(builder.env_separator, Some("__".to_string()));
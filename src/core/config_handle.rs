@@ -1,20 +1,144 @@
 //! The main configuration handle providing lock-free access.
 
-use crate::core::ConfigLoader;
-use crate::error::{ConfigError, Result, ValidationError};
+use crate::core::{
+    AppliedMigration, ApplyHandle, ApplyRegistry, AsyncValidator, ConfigLoader, Explanation,
+    PolicyDecision, Provenance, Refresh, ReloadFlight, ReloadReason, SkippedSource, UpdateContext,
+    UpdatePolicy,
+};
+use crate::error::{ApplyError, ConfigError, Result, ValidationError};
+use crate::sources::ConfigSource;
 use arc_swap::ArcSwap;
 use serde::de::DeserializeOwned;
-use std::sync::Arc;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Instant;
+#[cfg(feature = "tokio")]
+use std::time::SystemTime;
 
+#[cfg(feature = "file-watch")]
+use crate::core::reads_only;
 #[cfg(feature = "file-watch")]
 use crate::notify::{ConfigWatcher, SubscriberRegistry};
 
-#[cfg(feature = "metrics")]
-use crate::metrics::ConfigMetrics;
+use crate::metrics::MetricsRecorder;
+
+#[cfg(all(feature = "gradual-rollout", feature = "partial-updates"))]
+use crate::features::gradual::UpdateHook;
+#[cfg(feature = "gradual-rollout")]
+use crate::features::gradual::{
+    Background, CanaryReloads, ExposureHandler, GradualRollout, PromoteHook,
+};
+
+/// Whether a reload replaced the configuration.
+enum ReloadOutcome {
+    Applied,
+    Unchanged,
+    /// Held for a coordinated activation, which completes the reload
+    Staged,
+    /// Held until the sources take effect
+    #[cfg(feature = "tokio")]
+    Scheduled,
+}
+
+/// Priority of files added with [`HotswapConfig::watch_path`], above the
+/// builder's files and below environment variables.
+#[cfg(feature = "file-watch")]
+const WATCHED_PATH_PRIORITY: i32 = 200;
 
 /// Type alias for validator functions.
 type Validator<T> = Arc<dyn Fn(&T) -> std::result::Result<(), ValidationError> + Send + Sync>;
 
+/// Type alias for validators of a change from the current config (first
+/// argument) to a new one.
+type TransitionValidator<T> =
+    Arc<dyn Fn(&T, &T) -> std::result::Result<(), ValidationError> + Send + Sync>;
+
+/// Type alias for hooks transforming a freshly loaded config.
+pub(crate) type PostLoadHook<T> = Arc<dyn Fn(T) -> Result<T> + Send + Sync>;
+
+/// Type alias for hooks that may veto swapping the current config (first
+/// argument) for a new one.
+pub(crate) type PreSwapHook<T> = Arc<dyn Fn(&T, &T) -> PolicyDecision + Send + Sync>;
+
+/// Type alias for the handler told the version of every prepared config.
+pub(crate) type PrepareHandler = Arc<dyn Fn(&str) + Send + Sync>;
+
+/// Type alias for the handler told which keys need a restart to apply.
+pub(crate) type RestartHandler = Arc<dyn Fn(&[String]) + Send + Sync>;
+
+/// Type alias for the handler invoked when an automatic reload fails.
+#[cfg(feature = "tokio")]
+pub(crate) type ReloadErrorHandler = Arc<dyn Fn(&ConfigError) + Send + Sync>;
+
+/// State of a frozen configuration.
+struct Freeze {
+    reason: Option<String>,
+    /// Whether a reload was rejected while frozen
+    missed_reload: bool,
+}
+
+/// A loaded and validated configuration waiting to be activated.
+struct Prepared<T> {
+    version: String,
+    config: Arc<T>,
+    /// Generation of the config it was validated against; swapping in any
+    /// other config makes it stale
+    generation: u64,
+    fingerprint: u64,
+    provenance: Arc<Provenance>,
+    migrations: Vec<AppliedMigration>,
+    restart_required: Vec<String>,
+    /// The reason and duration of the reload that staged it, recorded once
+    /// it is activated
+    staged_by: Option<(ReloadReason, std::time::Duration)>,
+}
+
+/// A loaded and validated configuration waiting for its sources to take
+/// effect.
+#[cfg(feature = "tokio")]
+struct Scheduled<T> {
+    prepared: Prepared<T>,
+    at: SystemTime,
+}
+
+/// Notification work left over after a projection was updated.
+type Pending = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Updates a projected handle (or a binding such as the log level) from a new
+/// parent value; `None` once the projected handle has been dropped.
+type Projection<T> = Box<dyn Fn(&T) -> Option<Pending> + Send + Sync>;
+
+/// Handles derived from a configuration with [`HotswapConfig::project`], and
+/// other bindings updated on every swap.
+struct Projections<T>(RwLock<Vec<Projection<T>>>);
+
+impl<T> Projections<T> {
+    fn new() -> Self {
+        Self(RwLock::new(Vec::new()))
+    }
+
+    /// Push a new parent value into every live projection, dropping dead ones.
+    ///
+    /// Projected values are swapped immediately; the returned futures notify
+    /// their subscribers.
+    fn publish(&self, value: &T) -> Vec<Pending> {
+        let mut pending = Vec::new();
+        self.0
+            .write()
+            .unwrap()
+            .retain(|projection| match projection(value) {
+                Some(notify) => {
+                    pending.push(notify);
+                    true
+                }
+                None => false,
+            });
+        pending
+    }
+}
+
 /// The main configuration handle providing lock-free reads and atomic updates.
 ///
 /// This is the primary interface for accessing configuration. It uses `arc-swap`
@@ -32,9 +156,9 @@ type Validator<T> = Arc<dyn Fn(&T) -> std::result::Result<(), ValidationError> +
 /// }
 ///
 /// # async fn example() -> Result<()> {
-/// let config = HotswapConfig::builder()
+/// let config = HotswapConfig::<AppConfig>::builder()
 ///     .with_file("config.yaml")
-///     .build::<AppConfig>()
+///     .build()
 ///     .await?;
 ///
 /// // Zero-cost read
@@ -46,19 +170,100 @@ type Validator<T> = Arc<dyn Fn(&T) -> std::result::Result<(), ValidationError> +
 pub struct HotswapConfig<T> {
     /// The current configuration, wrapped in ArcSwap for atomic updates
     current: Arc<ArcSwap<T>>,
+    /// Incremented after every swap of `current`
+    generation: Arc<AtomicU64>,
     /// Configuration loader for reloading
     loader: Option<Arc<ConfigLoader>>,
     /// Optional validator function
     validator: Option<Validator<T>>,
+    /// Optional validator of changes relative to the current config
+    transition_validator: Option<TransitionValidator<T>>,
+    /// Validators that verify the config against external systems before a swap
+    async_validators: Vec<AsyncValidator<T>>,
+    /// Transforms every loaded config before it is validated
+    post_load_hook: Option<PostLoadHook<T>>,
+    /// May veto every config about to be swapped in
+    pre_swap_hook: Option<PreSwapHook<T>>,
     /// Optional file watcher for auto-reload
     #[cfg(feature = "file-watch")]
     watcher: Option<Arc<ConfigWatcher>>,
     /// Subscriber registry for change notifications
     #[cfg(feature = "file-watch")]
     subscribers: Arc<SubscriberRegistry>,
-    /// Optional metrics collector
-    #[cfg(feature = "metrics")]
-    metrics: Option<Arc<ConfigMetrics>>,
+    /// Publishes every swapped-in configuration to `changes` receivers
+    #[cfg(feature = "tokio")]
+    changes: Arc<tokio::sync::watch::Sender<Arc<T>>>,
+    /// Optional metrics recorder
+    metrics: Option<Arc<dyn MetricsRecorder>>,
+    /// Fingerprint of the merged sources behind the last loaded config; `None`
+    /// after a manual update
+    fingerprint: Arc<Mutex<Option<u64>>>,
+    /// Which source set each key of the last loaded config; `None` after a
+    /// manual update
+    provenance: Arc<Mutex<Option<Arc<Provenance>>>>,
+    /// Sources that failed during the last load and were skipped or served
+    /// from cache
+    skipped: Arc<Mutex<Vec<SkippedSource>>>,
+    /// Migrations applied to the last loaded config
+    migrations: Arc<Mutex<Vec<AppliedMigration>>>,
+    /// Version of the sources behind the last loaded config; `None` after a
+    /// manual update
+    version: Arc<Mutex<Option<String>>>,
+    /// Why the last loaded config was reloaded; `None` for the initial load
+    /// and after a manual update
+    reload_reason: Arc<Mutex<Option<ReloadReason>>>,
+    /// Config loaded by `prepare`, waiting for `activate`
+    prepared: Arc<Mutex<Option<Prepared<T>>>>,
+    /// Config whose sources take effect later, waiting for that time
+    #[cfg(feature = "tokio")]
+    scheduled: Arc<Mutex<Option<Scheduled<T>>>>,
+    /// Wakes the activation timer when `scheduled` changes
+    #[cfg(feature = "tokio")]
+    scheduled_changed: Arc<tokio::sync::Notify>,
+    /// Told the version of every prepared config; when set, reloads prepare
+    /// the new config instead of swapping it in
+    prepare_handler: Option<PrepareHandler>,
+    /// Set while serving a snapshot or defaults because sources failed at
+    /// startup, until a reload from sources succeeds
+    fallback: Arc<AtomicBool>,
+    /// Immutable keys changed by the sources since startup, held at their
+    /// boot-time values
+    restart_required: Arc<Mutex<Vec<String>>>,
+    /// Called when keys newly need a restart
+    restart_handler: Option<RestartHandler>,
+    /// Called when an automatic reload fails
+    #[cfg(feature = "tokio")]
+    reload_error_handler: Option<ReloadErrorHandler>,
+    /// Decides whether updates and patches may be applied
+    update_policy: Option<UpdatePolicy>,
+    /// Optional canary rollout that reloads are staged into
+    #[cfg(feature = "gradual-rollout")]
+    canary: Option<Arc<CanaryReloads<T>>>,
+    /// Set while the configuration is frozen
+    frozen: Arc<Mutex<Option<Freeze>>>,
+    /// Subscribers that must apply a new config before it is swapped in
+    appliers: ApplyRegistry<T>,
+    /// Handles derived with `project`, updated on every swap
+    projections: Arc<Projections<T>>,
+    /// Whether this handle is a projection, which can't be updated directly
+    projected: bool,
+    /// Profile selected when the config was built
+    profile: Option<Arc<str>>,
+    /// Reloads in progress, shared by clones so they coalesce too
+    flight: Arc<ReloadFlight>,
+    /// Spaces automatic reloads apart, if a minimum interval is set
+    #[cfg(feature = "tokio")]
+    throttle: Option<Arc<crate::core::ReloadThrottle>>,
+    /// Tasks reloading the config in the background
+    #[cfg(feature = "tokio")]
+    background: Arc<crate::core::BackgroundTasks>,
+    /// Stops the background tasks when the last handle holding it is
+    /// dropped; `None` in the clones owned by the tasks themselves
+    #[cfg(feature = "tokio")]
+    keep_running: Option<Arc<tokio_util::sync::DropGuard>>,
+    /// Failures queued by `TestConfigExt`
+    #[cfg(feature = "testing")]
+    faults: Arc<crate::testing::Faults>,
 }
 
 impl<T> HotswapConfig<T> {
@@ -76,16 +281,57 @@ impl<T> HotswapConfig<T> {
     /// assert_eq!(*config.get(), 42);
     /// ```
     pub fn new(initial: T) -> Self {
+        let initial = Arc::new(initial);
         Self {
-            current: Arc::new(ArcSwap::new(Arc::new(initial))),
+            current: Arc::new(ArcSwap::new(Arc::clone(&initial))),
+            generation: Arc::new(AtomicU64::new(0)),
             loader: None,
             validator: None,
+            transition_validator: None,
+            async_validators: Vec::new(),
+            post_load_hook: None,
+            pre_swap_hook: None,
             #[cfg(feature = "file-watch")]
             watcher: None,
             #[cfg(feature = "file-watch")]
             subscribers: Arc::new(SubscriberRegistry::new()),
-            #[cfg(feature = "metrics")]
+            #[cfg(feature = "tokio")]
+            changes: Arc::new(tokio::sync::watch::Sender::new(initial)),
             metrics: None,
+            fingerprint: Arc::new(Mutex::new(None)),
+            provenance: Arc::new(Mutex::new(None)),
+            skipped: Arc::new(Mutex::new(Vec::new())),
+            migrations: Arc::new(Mutex::new(Vec::new())),
+            version: Arc::new(Mutex::new(None)),
+            reload_reason: Arc::new(Mutex::new(None)),
+            prepared: Arc::new(Mutex::new(None)),
+            #[cfg(feature = "tokio")]
+            scheduled: Arc::new(Mutex::new(None)),
+            #[cfg(feature = "tokio")]
+            scheduled_changed: Arc::new(tokio::sync::Notify::new()),
+            prepare_handler: None,
+            fallback: Arc::new(AtomicBool::new(false)),
+            restart_required: Arc::new(Mutex::new(Vec::new())),
+            restart_handler: None,
+            #[cfg(feature = "tokio")]
+            reload_error_handler: None,
+            update_policy: None,
+            #[cfg(feature = "gradual-rollout")]
+            canary: None,
+            frozen: Arc::new(Mutex::new(None)),
+            appliers: ApplyRegistry::new(),
+            projections: Arc::new(Projections::new()),
+            projected: false,
+            profile: None,
+            flight: Arc::default(),
+            #[cfg(feature = "tokio")]
+            throttle: None,
+            #[cfg(feature = "tokio")]
+            background: Arc::default(),
+            #[cfg(feature = "tokio")]
+            keep_running: None,
+            #[cfg(feature = "testing")]
+            faults: Arc::default(),
         }
     }
 
@@ -94,24 +340,234 @@ impl<T> HotswapConfig<T> {
         initial: T,
         loader: ConfigLoader,
         validator: Option<Validator<T>>,
-        #[cfg(feature = "metrics")] meter: Option<opentelemetry::metrics::Meter>,
+        metrics: Option<Arc<dyn MetricsRecorder>>,
     ) -> Self {
-        #[cfg(feature = "metrics")]
-        let metrics = meter.map(|m| Arc::new(ConfigMetrics::new(m)));
+        // Report subscriber count changes, including unsubscribes
+        #[cfg(feature = "file-watch")]
+        let subscribers = SubscriberRegistry::with_count_listener(metrics.as_ref().map(|m| {
+            let metrics = Arc::clone(m);
+            Box::new(move |count: usize| metrics.subscribers_changed(count))
+                as Box<dyn Fn(usize) + Send + Sync>
+        }));
+
+        #[cfg(feature = "tokio")]
+        let token = tokio_util::sync::CancellationToken::new();
 
+        let initial = Arc::new(initial);
         Self {
-            current: Arc::new(ArcSwap::new(Arc::new(initial))),
+            current: Arc::new(ArcSwap::new(Arc::clone(&initial))),
+            generation: Arc::new(AtomicU64::new(0)),
             loader: Some(Arc::new(loader)),
             validator,
+            transition_validator: None,
+            async_validators: Vec::new(),
+            post_load_hook: None,
+            pre_swap_hook: None,
             #[cfg(feature = "file-watch")]
             watcher: None,
             #[cfg(feature = "file-watch")]
-            subscribers: Arc::new(SubscriberRegistry::new()),
-            #[cfg(feature = "metrics")]
+            subscribers: Arc::new(subscribers),
+            #[cfg(feature = "tokio")]
+            changes: Arc::new(tokio::sync::watch::Sender::new(initial)),
             metrics,
+            fingerprint: Arc::new(Mutex::new(None)),
+            provenance: Arc::new(Mutex::new(None)),
+            skipped: Arc::new(Mutex::new(Vec::new())),
+            migrations: Arc::new(Mutex::new(Vec::new())),
+            version: Arc::new(Mutex::new(None)),
+            reload_reason: Arc::new(Mutex::new(None)),
+            prepared: Arc::new(Mutex::new(None)),
+            #[cfg(feature = "tokio")]
+            scheduled: Arc::new(Mutex::new(None)),
+            #[cfg(feature = "tokio")]
+            scheduled_changed: Arc::new(tokio::sync::Notify::new()),
+            prepare_handler: None,
+            fallback: Arc::new(AtomicBool::new(false)),
+            restart_required: Arc::new(Mutex::new(Vec::new())),
+            restart_handler: None,
+            #[cfg(feature = "tokio")]
+            reload_error_handler: None,
+            update_policy: None,
+            #[cfg(feature = "gradual-rollout")]
+            canary: None,
+            frozen: Arc::new(Mutex::new(None)),
+            appliers: ApplyRegistry::new(),
+            projections: Arc::new(Projections::new()),
+            projected: false,
+            profile: None,
+            flight: Arc::default(),
+            #[cfg(feature = "tokio")]
+            throttle: None,
+            #[cfg(feature = "tokio")]
+            background: Arc::new(crate::core::BackgroundTasks::new(token.clone())),
+            #[cfg(feature = "tokio")]
+            keep_running: Some(Arc::new(token.drop_guard())),
+            #[cfg(feature = "testing")]
+            faults: Arc::default(),
+        }
+    }
+
+    /// Failures queued by `TestConfigExt`.
+    #[cfg(feature = "testing")]
+    pub(crate) fn faults(&self) -> &crate::testing::Faults {
+        &self.faults
+    }
+
+    /// Set the validator run against the current and new config before every
+    /// reload or update is applied.
+    pub(crate) fn with_transition_validator(
+        mut self,
+        validator: Option<TransitionValidator<T>>,
+    ) -> Self {
+        self.transition_validator = validator;
+        self
+    }
+
+    /// Set the async validators run before every reload or update is applied.
+    pub(crate) fn with_async_validators(mut self, validators: Vec<AsyncValidator<T>>) -> Self {
+        self.async_validators = validators;
+        self
+    }
+
+    /// Set the hook transforming every reloaded config before it is validated.
+    pub(crate) fn with_post_load_hook(mut self, hook: Option<PostLoadHook<T>>) -> Self {
+        self.post_load_hook = hook;
+        self
+    }
+
+    /// Set the hook that may veto every reload or update just before it is
+    /// swapped in.
+    pub(crate) fn with_pre_swap_hook(mut self, hook: Option<PreSwapHook<T>>) -> Self {
+        self.pre_swap_hook = hook;
+        self
+    }
+
+    /// Record the fingerprint and version of the sources the initial config
+    /// was loaded from, and which source set each key.
+    pub(crate) fn with_fingerprint(
+        self,
+        fingerprint: u64,
+        version: String,
+        provenance: Provenance,
+    ) -> Self {
+        *self.fingerprint.lock().unwrap() = Some(fingerprint);
+        *self.version.lock().unwrap() = Some(version);
+        *self.provenance.lock().unwrap() = Some(Arc::new(provenance));
+        self
+    }
+
+    /// Prepare reloads instead of swapping them in, telling `handler` the
+    /// version of each prepared config.
+    pub(crate) fn with_prepare_handler(mut self, handler: Option<PrepareHandler>) -> Self {
+        self.prepare_handler = handler;
+        self
+    }
+
+    /// Record the migrations applied while loading the initial config.
+    pub(crate) fn with_applied_migrations(self, migrations: Vec<AppliedMigration>) -> Self {
+        *self.migrations.lock().unwrap() = migrations;
+        self
+    }
+
+    /// Record the sources that were skipped while loading the initial config.
+    pub(crate) fn with_skipped_sources(self, skipped: Vec<SkippedSource>) -> Self {
+        *self.skipped.lock().unwrap() = skipped;
+        self
+    }
+
+    /// Mark the initial config as a fallback served because sources failed.
+    pub(crate) fn with_fallback(self) -> Self {
+        self.fallback.store(true, Ordering::Release);
+        self
+    }
+
+    /// Set the handler told which keys need a restart to apply.
+    pub(crate) fn with_restart_handler(mut self, handler: Option<RestartHandler>) -> Self {
+        self.restart_handler = handler;
+        self
+    }
+
+    /// Set the handler invoked when an automatic reload fails.
+    #[cfg(feature = "tokio")]
+    pub(crate) fn with_reload_error_handler(mut self, handler: Option<ReloadErrorHandler>) -> Self {
+        self.reload_error_handler = handler;
+        self
+    }
+
+    /// Report a failed automatic reload to the reload error handler, or log
+    /// it.
+    #[cfg(feature = "tokio")]
+    pub(crate) fn report_reload_error(&self, error: &ConfigError) {
+        report_reload_error(self.reload_error_handler.as_ref(), error);
+    }
+
+    /// Set the policy deciding whether updates and patches may be applied.
+    pub(crate) fn with_update_policy(mut self, policy: Option<UpdatePolicy>) -> Self {
+        self.update_policy = policy;
+        self
+    }
+
+    /// Record the profile the config was built for.
+    pub(crate) fn with_profile(mut self, profile: Option<String>) -> Self {
+        self.profile = profile.map(Arc::from);
+        self
+    }
+
+    /// Space automatic reloads at least `interval` apart.
+    #[cfg(feature = "tokio")]
+    pub(crate) fn with_min_reload_interval(mut self, interval: std::time::Duration) -> Self {
+        self.throttle = Some(Arc::new(crate::core::ReloadThrottle::new(interval)));
+        self
+    }
+
+    /// Wait until an automatic reload for `reason` may start, if a minimum
+    /// reload interval is set.
+    ///
+    /// Returns [`Turn::Join`](crate::core::Turn::Join) if the reload was
+    /// folded into one already waiting.
+    #[cfg(feature = "tokio")]
+    pub(crate) async fn throttle(&self, reason: ReloadReason) -> crate::core::Turn {
+        match &self.throttle {
+            Some(throttle) => throttle.wait(reason).await,
+            None => crate::core::Turn::Lead(crate::core::Slot::new(reason)),
         }
     }
 
+    /// Stop the background tasks when `token` is cancelled, as well as on
+    /// [`shutdown`](Self::shutdown).
+    #[cfg(feature = "tokio")]
+    pub(crate) fn with_cancellation_token(
+        mut self,
+        token: tokio_util::sync::CancellationToken,
+    ) -> Self {
+        let token = token.child_token();
+        self.background = Arc::new(crate::core::BackgroundTasks::new(token.clone()));
+        self.keep_running = Some(Arc::new(token.drop_guard()));
+        self
+    }
+
+    /// Run `task` in the background until it finishes or the handle shuts
+    /// down.
+    #[cfg(feature = "tokio")]
+    pub(crate) fn spawn(&self, task: impl Future<Output = ()> + Send + 'static) {
+        self.background.spawn(task);
+    }
+
+    /// A clone for a background task to own, which doesn't keep the
+    /// background tasks running once every other handle is dropped.
+    #[cfg(feature = "tokio")]
+    pub(crate) fn detached(&self) -> Self {
+        let mut clone = self.clone();
+        clone.keep_running = None;
+        clone
+    }
+
+    /// The metrics recorder, for features that record into it too.
+    #[cfg(feature = "gradual-rollout")]
+    pub(crate) fn metrics_recorder(&self) -> Option<&Arc<dyn MetricsRecorder>> {
+        self.metrics.as_ref()
+    }
+
     /// Set the file watcher for this configuration.
     #[cfg(feature = "file-watch")]
     pub(crate) fn with_watcher(mut self, watcher: Arc<ConfigWatcher>) -> Self {
@@ -119,6 +575,147 @@ impl<T> HotswapConfig<T> {
         self
     }
 
+    /// Route reloads into the canary slot of an attached rollout.
+    ///
+    /// Promoting the rollout publishes the canary as this handle's current
+    /// configuration and notifies subscribers. Promotions scheduled with
+    /// `promote_after` run as background tasks, reporting failures like
+    /// automatic reloads, so set the error handler and cancellation token
+    /// first.
+    #[cfg(feature = "gradual-rollout")]
+    pub(crate) fn with_canary_reloads(
+        mut self,
+        percentage: u8,
+        promote_after: Option<std::time::Duration>,
+        buckets: Option<Arc<dyn crate::features::BucketStore>>,
+        exposure_handler: Option<ExposureHandler>,
+    ) -> Self
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        let handle = self.detached();
+        let hook: PromoteHook<T> = Arc::new(move |promoted| {
+            let handle = handle.clone();
+            Box::pin(async move { handle.swap_promoted(promoted).await })
+        });
+
+        let rollout = GradualRollout::new(self.get())
+            .with_promote_hook(hook)
+            .with_shutdown(self.cancellation_token());
+        let rollout = match buckets {
+            Some(store) => rollout.with_bucket_store(store),
+            None => rollout,
+        };
+        let rollout = match exposure_handler {
+            Some(handler) => rollout.with_shared_exposure_handler(handler),
+            None => rollout,
+        };
+        let rollout = match &self.metrics {
+            Some(metrics) => rollout.with_shared_metrics(Arc::clone(metrics)),
+            None => rollout,
+        };
+
+        // Promote canary patches like any other patch, so the policy and
+        // validators see them
+        #[cfg(feature = "partial-updates")]
+        let rollout = {
+            let handle = self.detached();
+            let update: UpdateHook<T> = Arc::new(move |promoted, paths| {
+                let handle = handle.clone();
+                Box::pin(async move {
+                    handle.authorize(
+                        &paths,
+                        &UpdateContext::new(crate::core::UpdateOrigin::Patch),
+                    )?;
+                    handle.apply_update(promoted).await?;
+                    Ok(handle.get())
+                })
+            });
+            rollout.with_update_hook(update)
+        };
+        let handle = self.detached();
+        let background: Background = Arc::new(move |task| {
+            let reporter = handle.clone();
+            handle.spawn(async move {
+                if let Err(e) = task.await {
+                    reporter.report_reload_error(&e);
+                }
+            });
+        });
+        self.canary = Some(Arc::new(CanaryReloads::new(
+            rollout,
+            percentage,
+            promote_after,
+            background,
+        )));
+        self
+    }
+
+    /// Swap in a promoted canary, checked like a reload: not while frozen,
+    /// and only if the pre-swap hooks and transactional subscribers accept
+    /// it.
+    #[cfg(feature = "gradual-rollout")]
+    async fn swap_promoted(&self, promoted: Arc<T>) -> Result<()> {
+        if let Some(freeze) = self.frozen.lock().unwrap().as_ref() {
+            return Err(ConfigError::Frozen {
+                reason: freeze.reason.clone(),
+            });
+        }
+        self.pre_swap(&promoted)?;
+
+        // Let transactional subscribers apply it, keeping the old config if one can't
+        self.appliers.apply(&self.current.load(), &promoted)?;
+
+        self.current.store(Arc::clone(&promoted));
+        self.generation.fetch_add(1, Ordering::Release);
+        self.changes.send_replace(Arc::clone(&promoted));
+        let pending = self.projections.publish(&promoted);
+        if let Some(metrics) = &self.metrics {
+            metrics.config_updated();
+        }
+        #[cfg(feature = "tracing")]
+        tracing::info!("Swapped in promoted canary configuration");
+
+        // Notify subscribers
+        #[cfg(feature = "file-watch")]
+        self.subscribers.notify_all();
+        for notify in pending {
+            notify.await;
+        }
+
+        Ok(())
+    }
+
+    /// Get the rollout that reloads are staged into, if canary reloads are enabled.
+    ///
+    /// Use it on the request path to pick stable or canary per key, and to
+    /// promote or roll back the staged config. [`get`](Self::get) keeps
+    /// returning the stable config until the canary is promoted.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use hotswap_config::prelude::*;
+    /// # use serde::Deserialize;
+    /// # #[derive(Debug, Deserialize, Clone)]
+    /// # struct AppConfig { port: u16 }
+    /// # async fn example(config: HotswapConfig<AppConfig>) -> Result<()> {
+    /// if let Some(rollout) = config.canary_rollout() {
+    ///     let cfg = rollout.get(Some("user123"));
+    ///     println!("Serving user123 with port {}", cfg.port);
+    ///
+    ///     if rollout.has_canary() {
+    ///         rollout.promote().await?;
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "gradual-rollout")]
+    pub fn canary_rollout(&self) -> Option<&GradualRollout<T>> {
+        self.canary.as_deref().map(CanaryReloads::rollout)
+    }
+
     /// Get a reference-counted handle to the current configuration.
     ///
     /// This is a zero-cost operation that returns an `Arc<T>`. Readers never
@@ -144,18 +741,35 @@ impl<T> HotswapConfig<T> {
         self.current.load_full()
     }
 
-    /// Manually reload configuration from all sources.
+    /// Borrow the current configuration without bumping its reference count.
     ///
-    /// This triggers a full reload, respecting the precedence order.
-    /// If validation fails, the old configuration is retained.
+    /// For per-request hot loops where even the atomic increment of
+    /// [`get`](Self::get) shows up in profiles. The returned guard should be
+    /// dropped quickly: a thread holding many guards at once, or one guard
+    /// across a long `.await`, makes reads and swaps slower. Keep using `get`
+    /// for snapshots held for longer.
     ///
-    /// # Errors
+    /// # Examples
     ///
-    /// Returns an error if:
-    /// - No loader is available (shouldn't happen with normal usage)
-    /// - Configuration sources cannot be read
-    /// - Deserialization fails
-    /// - Validation fails (if a validator is configured)
+    /// ```rust
+    /// use hotswap_config::prelude::*;
+    ///
+    /// let config = HotswapConfig::new(vec![1, 2, 3]);
+    /// let total: i32 = config.load().iter().sum();
+    /// assert_eq!(total, 6);
+    /// ```
+    pub fn load(&self) -> crate::core::ConfigGuard<T> {
+        crate::core::ConfigGuard::new(self.current.load())
+    }
+
+    /// Cache the current configuration for one reader, revalidating it only
+    /// every N reads.
+    ///
+    /// See [`Pinned`] for how often it checks for a newer configuration. Use
+    /// it where a reader does so many reads that even [`load`](Self::load)
+    /// matters, and keep one per thread or worker.
+    ///
+    /// [`Pinned`]: crate::core::Pinned
     ///
     /// # Examples
     ///
@@ -163,80 +777,35 @@ impl<T> HotswapConfig<T> {
     /// # use hotswap_config::prelude::*;
     /// # use serde::Deserialize;
     /// # #[derive(Debug, Deserialize, Clone)]
-    /// # struct AppConfig { port: u16 }
-    /// # async fn example(config: HotswapConfig<AppConfig>) -> Result<()> {
-    /// // Manually trigger a reload
-    /// config.reload().await?;
-    ///
-    /// let cfg = config.get();
-    /// println!("Reloaded config, port: {}", cfg.port);
-    /// # Ok(())
+    /// # struct AppConfig { sample_rate: f64 }
+    /// # fn handle(_packet: &[u8], _sample_rate: f64) {}
+    /// # fn example(config: HotswapConfig<AppConfig>, packets: Vec<Vec<u8>>) {
+    /// let mut pinned = config.pin();
+    /// for packet in packets {
+    ///     handle(&packet, pinned.get().sample_rate);
+    /// }
     /// # }
     /// ```
-    pub async fn reload(&self) -> Result<()>
-    where
-        T: DeserializeOwned + Clone,
-    {
-        #[cfg(feature = "metrics")]
-        let timer = self.metrics.as_ref().map(|m| m.start_reload());
-
-        let result = self.reload_inner().await;
-
-        #[cfg(feature = "metrics")]
-        if let Some(metrics) = &self.metrics {
-            if let Some(start) = timer {
-                match &result {
-                    Ok(_) => metrics.record_reload_success(start),
-                    Err(_) => metrics.record_reload_failure(start),
-                }
-            }
-        }
-
-        result
+    pub fn pin(&self) -> crate::core::Pinned<T> {
+        crate::core::Pinned::new(Arc::clone(&self.current), Arc::clone(&self.generation))
     }
 
-    async fn reload_inner(&self) -> Result<()>
-    where
-        T: DeserializeOwned + Clone,
-    {
-        let loader = self
-            .loader
-            .as_ref()
-            .ok_or_else(|| ConfigError::Other("No loader available for reload".to_string()))?;
-
-        // Load the new configuration
-        let new_config: T = loader.load()?;
-
-        // Validate if a validator was provided
-        if let Some(validator) = &self.validator {
-            let validation_result = validator(&new_config);
-            if validation_result.is_err() {
-                #[cfg(feature = "metrics")]
-                if let Some(metrics) = &self.metrics {
-                    metrics.record_validation_failure();
-                }
-                return validation_result.map_err(|e| ConfigError::ValidationError(e.to_string()));
-            }
-        }
-
-        // Atomically swap to the new configuration
-        self.current.store(Arc::new(new_config));
-
-        // Notify subscribers
-        #[cfg(feature = "file-watch")]
-        self.subscribers.notify_all().await;
-
-        Ok(())
+    /// The generation of the current configuration.
+    ///
+    /// Starts at 0 and increases by one every time a configuration is swapped
+    /// in, whether by a reload, an update, or a canary promotion. Reloads
+    /// that change nothing don't count.
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
     }
 
-    /// Update configuration with a new value directly.
-    ///
-    /// This bypasses the loader and directly updates the configuration.
-    /// Useful for programmatic updates or testing.
+    /// Get the current configuration along with its generation.
     ///
-    /// # Errors
+    /// Like [`get`](Self::get), but the returned [`Snapshot`] can cheaply
+    /// tell whether the configuration has changed since, so a long-running
+    /// operation can decide whether to re-read it.
     ///
-    /// Returns an error if validation fails.
+    /// [`Snapshot`]: crate::core::Snapshot
     ///
     /// # Examples
     ///
@@ -244,46 +813,37 @@ impl<T> HotswapConfig<T> {
     /// # use hotswap_config::prelude::*;
     /// # use serde::Deserialize;
     /// # #[derive(Debug, Deserialize, Clone)]
-    /// # struct AppConfig { port: u16 }
-    /// # async fn example(config: HotswapConfig<AppConfig>) -> Result<()> {
-    /// let new_config = AppConfig { port: 9090 };
-    /// config.update(new_config).await?;
-    /// # Ok(())
+    /// # struct AppConfig { batch_size: usize }
+    /// # fn process(_batch: &[u8], _size: usize) {}
+    /// # fn example(config: HotswapConfig<AppConfig>, batches: Vec<Vec<u8>>) {
+    /// let mut cfg = config.snapshot();
+    /// for batch in batches {
+    ///     if cfg.is_stale() {
+    ///         cfg = config.snapshot();
+    ///     }
+    ///     process(&batch, cfg.batch_size);
+    /// }
     /// # }
     /// ```
-    pub async fn update(&self, new_config: T) -> Result<()> {
-        // Validate if a validator was provided
-        if let Some(validator) = &self.validator {
-            let validation_result = validator(&new_config);
-            if validation_result.is_err() {
-                #[cfg(feature = "metrics")]
-                if let Some(metrics) = &self.metrics {
-                    metrics.record_validation_failure();
-                }
-                return validation_result.map_err(|e| ConfigError::ValidationError(e.to_string()));
-            }
-        }
-
-        // Atomically swap to the new configuration
-        self.current.store(Arc::new(new_config));
-
-        // Record the update in metrics
-        #[cfg(feature = "metrics")]
-        if let Some(metrics) = &self.metrics {
-            metrics.record_update();
-        }
-
-        // Notify subscribers
-        #[cfg(feature = "file-watch")]
-        self.subscribers.notify_all().await;
-
-        Ok(())
+    pub fn snapshot(&self) -> crate::core::Snapshot<T> {
+        // Read the generation first, so a snapshot is never labeled newer
+        // than its config
+        let generation = self.generation.load(Ordering::Acquire);
+        crate::core::Snapshot::new(
+            self.current.load_full(),
+            generation,
+            Arc::clone(&self.generation),
+        )
     }
 
-    /// Subscribe to configuration changes.
+    /// Receive every configuration swapped in from now on.
     ///
-    /// The provided callback will be invoked whenever the configuration
-    /// is reloaded or updated. Returns a handle that can be dropped to unsubscribe.
+    /// An alternative to callbacks for async tasks: await
+    /// [`changed()`](tokio::sync::watch::Receiver::changed) in a `select!`
+    /// loop and read the new value with `borrow_and_update()`. The current
+    /// configuration is marked as seen, so `changed()` waits for the next
+    /// reload, update, or canary promotion. Reloads that change nothing are
+    /// not published, and a slow receiver only sees the latest value.
     ///
     /// # Examples
     ///
@@ -292,78 +852,2366 @@ impl<T> HotswapConfig<T> {
     /// # use serde::Deserialize;
     /// # #[derive(Debug, Deserialize, Clone)]
     /// # struct AppConfig { port: u16 }
-    /// # async fn example(config: HotswapConfig<AppConfig>) {
-    /// let handle = config.subscribe(|| {
-    ///     println!("Configuration changed!");
-    /// }).await;
-    ///
-    /// // Later, unsubscribe
-    /// drop(handle);
+    /// # async fn example(config: HotswapConfig<AppConfig>, shutdown: tokio::sync::oneshot::Receiver<()>) {
+    /// let mut changes = config.changes();
+    /// tokio::pin!(shutdown);
+    /// loop {
+    ///     tokio::select! {
+    ///         Ok(()) = changes.changed() => {
+    ///             let cfg = changes.borrow_and_update().clone();
+    ///             println!("Now listening on port {}", cfg.port);
+    ///         }
+    ///         _ = &mut shutdown => break,
+    ///     }
+    /// }
     /// # }
     /// ```
-    #[cfg(feature = "file-watch")]
-    pub async fn subscribe<F>(&self, callback: F) -> crate::notify::SubscriptionHandle
-    where
-        F: Fn() + Send + Sync + 'static,
-    {
-        let handle = self.subscribers.subscribe(callback).await;
-
-        // Update subscriber count metric
-        #[cfg(feature = "metrics")]
-        if let Some(metrics) = &self.metrics {
-            let count = self.subscribers.subscriber_count().await as i64;
-            metrics.update_subscriber_count(count);
-        }
-
-        handle
+    #[cfg(feature = "tokio")]
+    pub fn changes(&self) -> tokio::sync::watch::Receiver<Arc<T>> {
+        self.changes.subscribe()
     }
 
-    /// Start watching configuration files for changes.
+    /// Get the current configuration as JSON with secret fields masked.
     ///
-    /// When enabled, the configuration will automatically reload when any
-    /// watched file changes. This requires a file watcher to be set up
-    /// via the builder.
+    /// Fields wrapped in [`Secret`](crate::secrets::Secret) or annotated with
+    /// `#[serde(with = "hotswap_config::secrets::redact")]` are replaced with
+    /// `"[REDACTED]"`. Safe to log or expose via admin endpoints.
     ///
     /// # Errors
     ///
-    /// Returns an error if no file watcher is configured.
+    /// Returns an error if the configuration cannot be serialized.
     ///
     /// # Examples
     ///
-    /// ```rust,no_run
-    /// # use hotswap_config::prelude::*;
-    /// # use serde::Deserialize;
-    /// # #[derive(Debug, Deserialize, Clone)]
-    /// # struct AppConfig { port: u16 }
-    /// # async fn example() -> Result<()> {
-    /// let config = HotswapConfig::builder()
-    ///     .with_file("config.yaml")
-    ///     .with_file_watch(true)
-    ///     .build::<AppConfig>()
-    ///     .await?;
+    /// ```rust
+    /// use hotswap_config::prelude::*;
+    /// use hotswap_config::secrets::Secret;
+    /// use serde::Serialize;
     ///
-    /// // File watching is now active
-    /// # Ok(())
-    /// # }
+    /// #[derive(Serialize)]
+    /// struct AppConfig {
+    ///     user: String,
+    ///     password: Secret<String>,
+    /// }
+    ///
+    /// let config = HotswapConfig::new(AppConfig {
+    ///     user: "admin".to_string(),
+    ///     password: Secret::new("hunter2".to_string()),
+    /// });
+    ///
+    /// let json = config.get_redacted_json().unwrap();
+    /// assert_eq!(json["user"], "admin");
+    /// assert_eq!(json["password"], "[REDACTED]");
     /// ```
-    #[cfg(feature = "file-watch")]
+    #[cfg(feature = "serde_json")]
+    pub fn get_redacted_json(&self) -> Result<serde_json::Value>
+    where
+        T: serde::Serialize,
+    {
+        use crate::secrets::Redact;
+        self.get().to_redacted_json()
+    }
+
+    /// Export the effective configuration as a snapshot string.
+    ///
+    /// The snapshot is the current value (after merging, reloads, and any
+    /// updates) serialized in `format`. Secrets are included unredacted so the
+    /// snapshot can be loaded back; see
+    /// [`HotswapConfigBuilder::with_snapshot_fallback`](crate::core::HotswapConfigBuilder::with_snapshot_fallback).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails or the format's feature is not
+    /// enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "json")]
+    /// # {
+    /// use hotswap_config::prelude::*;
+    /// use hotswap_config::core::SnapshotFormat;
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// struct AppConfig {
+    ///     port: u16,
+    /// }
+    ///
+    /// let config = HotswapConfig::new(AppConfig { port: 8080 });
+    /// let snapshot = config.export_snapshot(SnapshotFormat::Json).unwrap();
+    /// assert!(snapshot.contains("\"port\": 8080"));
+    /// # }
+    /// ```
+    pub fn export_snapshot(&self, format: crate::core::SnapshotFormat) -> Result<String>
+    where
+        T: serde::Serialize,
+    {
+        format.serialize(&*self.get())
+    }
+
+    /// Write a snapshot of the effective configuration to a file.
+    ///
+    /// The format is detected from the file extension. The file is written
+    /// to a temporary path and renamed, so readers never see a partial
+    /// snapshot.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the extension is not recognized, serialization
+    /// fails, or the file cannot be written.
+    pub fn write_snapshot(&self, path: impl AsRef<std::path::Path>) -> Result<()>
+    where
+        T: serde::Serialize,
+    {
+        let path = path.as_ref();
+        let format = crate::core::SnapshotFormat::from_path(path).ok_or_else(|| {
+            ConfigError::Other(format!(
+                "Unsupported snapshot extension: {}. Supported: .yaml, .yml, .toml, .json",
+                path.display()
+            ))
+        })?;
+
+        let contents = self.export_snapshot(format)?;
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        std::fs::write(&tmp, contents)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Manually reload configuration from all sources.
+    ///
+    /// This triggers a full reload, respecting the precedence order.
+    /// If validation fails, the old configuration is retained. With canary
+    /// reloads enabled, the new configuration is staged as the canary instead
+    /// of replacing the current one.
+    ///
+    /// If the merged sources are identical to those of the last load, the
+    /// reload is skipped: nothing is swapped and subscribers aren't notified.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - No loader is available (shouldn't happen with normal usage)
+    /// - Configuration sources cannot be read
+    /// - Deserialization fails
+    /// - Validation fails (if a validator is configured)
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use hotswap_config::prelude::*;
+    /// # use serde::Deserialize;
+    /// # #[derive(Debug, Deserialize, Clone)]
+    /// # struct AppConfig { port: u16 }
+    /// # async fn example(config: HotswapConfig<AppConfig>) -> Result<()> {
+    /// // Manually trigger a reload
+    /// config.reload().await?;
+    ///
+    /// let cfg = config.get();
+    /// println!("Reloaded config, port: {}", cfg.port);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn reload(&self) -> Result<()>
+    where
+        T: DeserializeOwned + Clone,
+    {
+        self.reload_with_reason(ReloadReason::Manual).await
+    }
+
+    /// Reload configuration from sources, for `reason`.
+    ///
+    /// Like [`reload`](Self::reload), but `reason` is what the
+    /// [metrics recorder](crate::metrics::MetricsRecorder::reload_triggered)
+    /// and logs see, and what [`reload_reason`](Self::reload_reason) reports
+    /// if the config changes. A [`ReloadReason::FileChanged`] reason only
+    /// re-loads the sources that read those files.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the reload fails, like [`reload`](Self::reload).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use hotswap_config::core::ReloadReason;
+    /// # use hotswap_config::prelude::*;
+    /// # use serde::Deserialize;
+    /// # #[derive(Debug, Deserialize, Clone)]
+    /// # struct AppConfig { port: u16 }
+    /// # async fn example(config: HotswapConfig<AppConfig>) -> Result<()> {
+    /// // From a deploy hook
+    /// config.reload_with_reason(ReloadReason::custom("deploy")).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "hotswap_config.reload", skip_all, fields(reason = %reason))
+    )]
+    pub async fn reload_with_reason(&self, reason: ReloadReason) -> Result<()>
+    where
+        T: DeserializeOwned + Clone,
+    {
+        match &reason {
+            #[cfg(feature = "file-watch")]
+            ReloadReason::FileChanged(changed) => {
+                let changed = changed.clone();
+                self.reload_from(Refresh::Changed(&changed), reason).await
+            }
+            _ => self.reload_from(Refresh::All, reason).await,
+        }
+    }
+
+    /// Why the current configuration was reloaded.
+    ///
+    /// Set by every reload that changes the configuration, so subscribers can
+    /// tell a file edit from a scheduled refresh or an admin request, and
+    /// history entries can record it. `None` until the first such reload, and
+    /// after an [`update`](Self::update).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use hotswap_config::prelude::*;
+    /// # use serde::Deserialize;
+    /// # #[derive(Debug, Deserialize, Clone)]
+    /// # struct AppConfig { port: u16 }
+    /// # async fn example(config: HotswapConfig<AppConfig>) {
+    /// if let Some(reason) = config.reload_reason() {
+    ///     println!("Configuration reloaded ({})", reason);
+    /// }
+    /// # }
+    /// ```
+    pub fn reload_reason(&self) -> Option<ReloadReason> {
+        self.reload_reason.lock().unwrap().clone()
+    }
+
+    /// Whether a reload for the `changed` files would re-load any source.
+    #[cfg(feature = "file-watch")]
+    pub(crate) fn reads_changed(&self, changed: &[std::path::PathBuf]) -> bool {
+        self.loader
+            .as_ref()
+            .is_some_and(|loader| loader.reads_changed(changed))
+    }
+
+    /// Re-load overlay sources and swap in the result.
+    ///
+    /// Only overlays (see
+    /// [`HotswapConfigBuilder::with_overlay`](crate::core::HotswapConfigBuilder::with_overlay))
+    /// are fetched again; every other source is merged from its last loaded
+    /// values, so rotating a secret doesn't re-read files. The result goes
+    /// through validation and is applied like any other reload.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an overlay fails to load or the result fails
+    /// validation; the current config is kept.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use hotswap_config::prelude::*;
+    /// # use serde::Deserialize;
+    /// # #[derive(Debug, Deserialize, Clone)]
+    /// # struct AppConfig { db_password: String }
+    /// # async fn example(config: HotswapConfig<AppConfig>) -> Result<()> {
+    /// // After rotating the database password in the secret store
+    /// config.refresh_overlays().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "hotswap_config.reload", skip_all, fields(overlays = true))
+    )]
+    pub async fn refresh_overlays(&self) -> Result<()>
+    where
+        T: DeserializeOwned + Clone,
+    {
+        self.reload_from(Refresh::Overlays(None), ReloadReason::Manual)
+            .await
+    }
+
+    /// Re-load only the `index`th overlay, on its own refresh schedule.
+    #[cfg(feature = "tokio")]
+    pub(crate) async fn refresh_overlay(&self, index: usize) -> Result<()>
+    where
+        T: DeserializeOwned + Clone,
+    {
+        self.reload_from(Refresh::Overlays(Some(index)), ReloadReason::Schedule)
+            .await
+    }
+
+    /// Re-load only the source at `index`, for `reason`.
+    #[cfg(feature = "tokio")]
+    pub(crate) async fn reload_source(&self, index: usize, reason: ReloadReason) -> Result<()>
+    where
+        T: DeserializeOwned + Clone,
+    {
+        self.reload_from(Refresh::Source(index), reason).await
+    }
+
+    /// Whether a reload is running right now.
+    ///
+    /// Reloads of a handle and its clones run one at a time, whether they
+    /// come from [`reload`](Self::reload), a watcher, or a refresh schedule.
+    /// Calls to `reload` made while another one is in flight join it instead
+    /// of loading the sources again, and return its result.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use hotswap_config::prelude::*;
+    /// # use serde::Deserialize;
+    /// # #[derive(Debug, Deserialize, Clone)]
+    /// # struct AppConfig { port: u16 }
+    /// # async fn example(config: HotswapConfig<AppConfig>) {
+    /// if config.reload_in_progress() {
+    ///     println!("Configuration is being reloaded");
+    /// }
+    /// # }
+    /// ```
+    pub fn reload_in_progress(&self) -> bool {
+        self.flight.in_progress()
+    }
+
+    /// Reload the sources covered by `refresh` for `reason`, joining a full
+    /// reload that is already in flight.
+    async fn reload_from(&self, refresh: Refresh<'_>, reason: ReloadReason) -> Result<()>
+    where
+        T: DeserializeOwned + Clone,
+    {
+        #[cfg(feature = "tokio")]
+        if matches!(refresh, Refresh::All) {
+            use crate::core::Flight;
+
+            loop {
+                match self.flight.join_or_lead() {
+                    Flight::Join(rx) => {
+                        if let Some(metrics) = &self.metrics {
+                            metrics.reload_coalesced();
+                        }
+                        // Lead a new reload if the joined one was cancelled
+                        if let Some(result) = ReloadFlight::wait(rx).await {
+                            return result;
+                        }
+                    }
+                    Flight::Lead(tx) => {
+                        let result = self.reload_once(Refresh::All, reason.clone()).await;
+                        return self.flight.finish(tx, result);
+                    }
+                }
+            }
+        }
+        self.reload_once(refresh, reason).await
+    }
+
+    /// Reload the sources covered by `refresh` for `reason`, once no other
+    /// reload is running.
+    async fn reload_once(&self, refresh: Refresh<'_>, reason: ReloadReason) -> Result<()>
+    where
+        T: DeserializeOwned + Clone,
+    {
+        let _running = self.flight.start(self.metrics.as_deref()).await;
+        let start = Instant::now();
+        if let Some(metrics) = &self.metrics {
+            metrics.reload_attempted();
+            metrics.reload_triggered(&reason);
+        }
+        #[cfg(feature = "tracing")]
+        tracing::debug!(reason = %reason, "Reloading configuration");
+
+        // Set before subscribers are notified, and put back unless the
+        // config changed
+        let previous = self.reload_reason.lock().unwrap().replace(reason.clone());
+        let result = self.reload_inner(refresh, &reason, start).await;
+        if !matches!(result, Ok(ReloadOutcome::Applied)) {
+            *self.reload_reason.lock().unwrap() = previous;
+        }
+
+        // Notify error subscribers so failures aren't silently swallowed
+        #[cfg(feature = "file-watch")]
+        if let Err(e) = &result {
+            self.subscribers.notify_error(e);
+        }
+
+        if let Some(metrics) = &self.metrics {
+            match &result {
+                Ok(ReloadOutcome::Applied) => metrics.reload_succeeded(start.elapsed()),
+                Ok(ReloadOutcome::Unchanged) => metrics.reload_skipped(start.elapsed()),
+                // Recorded when the staged config is activated
+                Ok(ReloadOutcome::Staged) => {}
+                #[cfg(feature = "tokio")]
+                Ok(ReloadOutcome::Scheduled) => metrics.reload_succeeded(start.elapsed()),
+                Err(_) => metrics.reload_failed(start.elapsed()),
+            }
+        }
+
+        result.map(|_| ())
+    }
+
+    async fn reload_inner(
+        &self,
+        refresh: Refresh<'_>,
+        reason: &ReloadReason,
+        start: Instant,
+    ) -> Result<ReloadOutcome>
+    where
+        T: DeserializeOwned + Clone,
+    {
+        if let Some(freeze) = self.frozen.lock().unwrap().as_mut() {
+            freeze.missed_reload = true;
+            return Err(ConfigError::Frozen {
+                reason: freeze.reason.clone(),
+            });
+        }
+        #[cfg(feature = "testing")]
+        if let Some(e) = self.faults.next_reload_failure() {
+            return Err(e);
+        }
+
+        let loader = self
+            .loader
+            .as_ref()
+            .ok_or_else(|| ConfigError::Other("No loader available for reload".to_string()))?;
+
+        // Load the new configuration, skipping it if the sources haven't changed
+        let loaded = match refresh {
+            Refresh::All => loader.load_fingerprinted()?,
+            #[cfg(feature = "file-watch")]
+            Refresh::Changed(changed) => loader.load_changed(changed)?,
+            #[cfg(not(feature = "file-watch"))]
+            Refresh::Changed(_) => loader.load_fingerprinted()?,
+            Refresh::Overlays(index) => loader.load_overlays(index)?,
+            #[cfg(feature = "tokio")]
+            Refresh::Source(index) => loader.load_source_at(index)?,
+            #[cfg(not(feature = "tokio"))]
+            Refresh::Source(_) => loader.load_fingerprinted()?,
+            Refresh::Merge => loader.load_merged()?,
+        };
+        let fingerprint = loaded.fingerprint;
+        let restart_required = loaded.restart_required;
+        *self.skipped.lock().unwrap() = loaded.skipped;
+        if *self.fingerprint.lock().unwrap() == Some(fingerprint) {
+            #[cfg(feature = "tracing")]
+            tracing::debug!("Configuration unchanged, skipping reload");
+            // The sources no longer hold what was scheduled
+            #[cfg(feature = "tokio")]
+            self.cancel_scheduled();
+            self.require_restart(restart_required);
+            return Ok(ReloadOutcome::Unchanged);
+        }
+        if self.prepare_handler.is_some() && self.is_prepared(fingerprint) {
+            #[cfg(feature = "tracing")]
+            tracing::debug!("Configuration already prepared, skipping reload");
+            return Ok(ReloadOutcome::Unchanged);
+        }
+
+        // Swap in a scheduled config once it takes effect, as validated then
+        #[cfg(feature = "tokio")]
+        if let Some(scheduled) = self.take_scheduled(fingerprint) {
+            if scheduled.at > SystemTime::now() {
+                #[cfg(feature = "tracing")]
+                tracing::debug!("Configuration already scheduled, skipping reload");
+                *self.scheduled.lock().unwrap() = Some(scheduled);
+                return Ok(ReloadOutcome::Unchanged);
+            }
+            self.pre_swap(&scheduled.prepared.config)?;
+            self.swap_loaded(scheduled.prepared).await?;
+            return Ok(ReloadOutcome::Applied);
+        }
+        #[cfg(feature = "tokio")]
+        let effective_at = loaded.effective_at;
+
+        let generation = self.generation.load(Ordering::Acquire);
+        let new_config = self
+            .check(loaded.config, loaded.section_errors, loaded.errors)
+            .await?;
+        self.pre_swap(&new_config)?;
+        let mut prepared = Prepared {
+            version: loaded.version,
+            config: new_config,
+            generation,
+            fingerprint,
+            provenance: Arc::new(loaded.provenance),
+            migrations: loaded.migrations,
+            restart_required,
+            staged_by: None,
+        };
+
+        // Hold for a coordinated activation instead of swapping
+        if self.prepare_handler.is_some() {
+            prepared.staged_by = Some((reason.clone(), start.elapsed()));
+            self.stage(prepared);
+            return Ok(ReloadOutcome::Staged);
+        }
+
+        // Hold until the sources take effect
+        #[cfg(feature = "tokio")]
+        if let Some(at) = effective_at.filter(|at| *at > SystemTime::now()) {
+            self.schedule(Scheduled { prepared, at });
+            return Ok(ReloadOutcome::Scheduled);
+        }
+
+        #[cfg(feature = "tokio")]
+        self.cancel_scheduled();
+        self.swap_loaded(prepared).await?;
+        Ok(ReloadOutcome::Applied)
+    }
+
+    /// Swap in a loaded configuration that passed validation, recording
+    /// where it was loaded from.
+    async fn swap_loaded(&self, loaded: Prepared<T>) -> Result<()>
+    where
+        T: Clone,
+    {
+        let Prepared {
+            version,
+            config: new_config,
+            fingerprint,
+            provenance,
+            migrations,
+            restart_required,
+            ..
+        } = loaded;
+
+        // Stage as canary instead of swapping; promotion runs the pre-swap
+        // hooks and transactional subscribers, then publishes it
+        #[cfg(feature = "gradual-rollout")]
+        if let Some(canary) = &self.canary {
+            canary.stage(new_config);
+            *self.fingerprint.lock().unwrap() = Some(fingerprint);
+            *self.version.lock().unwrap() = Some(version);
+            *self.provenance.lock().unwrap() = Some(provenance);
+            *self.migrations.lock().unwrap() = migrations;
+            self.fallback.store(false, Ordering::Release);
+            self.require_restart(restart_required);
+            #[cfg(feature = "tracing")]
+            tracing::info!("Staged reloaded configuration as canary");
+            return Ok(());
+        }
+
+        // Let transactional subscribers apply it, keeping the old config if one can't
+        self.appliers.apply(&self.current.load(), &new_config)?;
+
+        // Atomically swap to the new configuration
+        self.current.store(Arc::clone(&new_config));
+        self.generation.fetch_add(1, Ordering::Release);
+        #[cfg(feature = "tokio")]
+        self.changes.send_replace(Arc::clone(&new_config));
+        let pending = self.projections.publish(&new_config);
+        *self.fingerprint.lock().unwrap() = Some(fingerprint);
+        *self.version.lock().unwrap() = Some(version);
+        *self.provenance.lock().unwrap() = Some(provenance);
+        *self.migrations.lock().unwrap() = migrations;
+        self.fallback.store(false, Ordering::Release);
+        self.require_restart(restart_required);
+        #[cfg(feature = "tracing")]
+        tracing::info!("Swapped in reloaded configuration");
+
+        // Notify subscribers
+        #[cfg(feature = "file-watch")]
+        self.subscribers.notify_all();
+        for notify in pending {
+            notify.await;
+        }
+
+        Ok(())
+    }
+
+    /// Whether a config loaded from sources with `fingerprint` is already
+    /// prepared.
+    fn is_prepared(&self, fingerprint: u64) -> bool {
+        self.prepared
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|prepared| prepared.fingerprint == fingerprint)
+    }
+
+    /// Take the scheduled config if it was loaded from sources with
+    /// `fingerprint` and validated against the current config.
+    #[cfg(feature = "tokio")]
+    fn take_scheduled(&self, fingerprint: u64) -> Option<Scheduled<T>> {
+        let generation = self.generation.load(Ordering::Acquire);
+        self.scheduled.lock().unwrap().take_if(|scheduled| {
+            scheduled.prepared.fingerprint == fingerprint
+                && scheduled.prepared.generation == generation
+        })
+    }
+
+    /// Hold `scheduled` until it takes effect, replacing any config
+    /// scheduled before.
+    #[cfg(feature = "tokio")]
+    fn schedule(&self, scheduled: Scheduled<T>) {
+        #[cfg(feature = "tracing")]
+        tracing::info!(
+            version = %scheduled.prepared.version,
+            at = ?scheduled.at,
+            "Scheduled configuration to take effect"
+        );
+        *self.scheduled.lock().unwrap() = Some(scheduled);
+        self.scheduled_changed.notify_one();
+    }
+
+    /// Drop the scheduled config, if any.
+    #[cfg(feature = "tokio")]
+    fn cancel_scheduled(&self) {
+        if self.scheduled.lock().unwrap().take().is_some() {
+            self.scheduled_changed.notify_one();
+        }
+    }
+
+    /// Hold `prepared` until it is activated, replacing any config prepared
+    /// before, and tell the prepare handler its version.
+    fn stage(&self, prepared: Prepared<T>) {
+        let version = prepared.version.clone();
+        *self.prepared.lock().unwrap() = Some(prepared);
+        #[cfg(feature = "tracing")]
+        tracing::info!(version = %version, "Prepared configuration for activation");
+        if let Some(handler) = &self.prepare_handler {
+            handler(&version);
+        }
+    }
+
+    /// Record the immutable keys a successful reload held back, reporting
+    /// them if the set changed.
+    fn require_restart(&self, keys: Vec<String>) {
+        {
+            let mut held = self.restart_required.lock().unwrap();
+            if *held == keys {
+                return;
+            }
+            held.clone_from(&keys);
+        }
+        if keys.is_empty() {
+            return;
+        }
+        #[cfg(feature = "tracing")]
+        tracing::warn!(keys = ?keys, "Configuration changes require a restart to take effect");
+        if let Some(handler) = &self.restart_handler {
+            handler(&keys);
+        }
+    }
+
+    /// Run the post-load hook and every validator against a freshly loaded
+    /// configuration, reporting failures along with errors the loader
+    /// deferred.
+    async fn check(
+        &self,
+        config: T,
+        section_errors: Vec<ValidationError>,
+        load_errors: Vec<ConfigError>,
+    ) -> Result<Arc<T>> {
+        let config = match &self.post_load_hook {
+            Some(hook) => hook(config)?,
+            None => config,
+        };
+
+        // Validate sections and the whole config, reporting every failure
+        let mut errors = section_errors;
+        if let Some(validator) = &self.validator {
+            errors.extend(validator(&config).err());
+        }
+        if let Some(transition) = &self.transition_validator {
+            errors.extend(transition(&self.current.load(), &config).err());
+        }
+        #[cfg(feature = "testing")]
+        errors.extend(self.faults.next_rejection());
+        let validation = ValidationError::collect(errors);
+        if validation.is_err() {
+            if let Some(metrics) = &self.metrics {
+                metrics.validation_failed();
+            }
+            #[cfg(feature = "tracing")]
+            if let Err(e) = &validation {
+                tracing::warn!(error = %e, "Configuration rejected by validation");
+            }
+        }
+        ConfigError::collect(
+            load_errors
+                .into_iter()
+                .chain(validation.err().map(Into::into)),
+        )?;
+
+        // Verify against external systems before anything sees the new config
+        let config = Arc::new(config);
+        self.verify(&config).await?;
+        Ok(config)
+    }
+
+    /// Ask the pre-swap hook whether `new` may replace the current config.
+    fn pre_swap(&self, new: &T) -> Result<()> {
+        if let Some(hook) = &self.pre_swap_hook
+            && let PolicyDecision::Deny(reason) = hook(&self.current.load(), new)
+        {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(reason = %reason, "Configuration swap vetoed");
+            return Err(ConfigError::SwapVetoed { reason });
+        }
+        Ok(())
+    }
+
+    /// Load and validate the configuration without swapping it in.
+    ///
+    /// Runs everything a [`reload`](Self::reload) would up to the swap: all
+    /// sources are loaded, and every hook and validator (including async
+    /// ones) is run.
+    /// Nothing is applied, subscribers aren't notified, and a frozen
+    /// configuration can still be dry-run. The returned [`ConfigDiff`] holds
+    /// the prospective config and the keys that would change, so it can back a
+    /// pre-deploy check or an admin endpoint.
+    ///
+    /// [`ConfigDiff`]: crate::core::ConfigDiff
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if loading or validation fails, i.e. if a reload would
+    /// fail for the same reason.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use hotswap_config::prelude::*;
+    /// # use serde::{Deserialize, Serialize};
+    /// # #[derive(Debug, Serialize, Deserialize, Clone)]
+    /// # struct AppConfig { port: u16 }
+    /// # async fn example(config: HotswapConfig<AppConfig>) -> Result<()> {
+    /// let diff = config.reload_dry_run().await?;
+    /// for change in diff.changes() {
+    ///     println!("{}", change);
+    /// }
+    /// if diff.proposed().port != config.get().port {
+    ///     println!("reload would move the listener");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn reload_dry_run(&self) -> Result<crate::core::ConfigDiff<T>>
+    where
+        T: DeserializeOwned + serde::Serialize,
+    {
+        let loader = self
+            .loader
+            .as_ref()
+            .ok_or_else(|| ConfigError::Other("No loader available for reload".to_string()))?;
+
+        let loaded = loader.load_fingerprinted()?;
+        let proposed = self
+            .check(loaded.config, loaded.section_errors, loaded.errors)
+            .await?;
+        self.pre_swap(&proposed)?;
+        crate::core::ConfigDiff::new(self.get(), proposed)
+    }
+
+    /// Load and validate the configuration, holding it until it is
+    /// [activated](Self::activate), and return its version.
+    ///
+    /// This is the first half of a reload, split so that an orchestrator can
+    /// have every replica fetch and validate a new configuration, then tell
+    /// all of them to swap it in at once. The version comes from the sources'
+    /// [`content_version`](crate::sources::ConfigSource::content_version)
+    /// (e.g. the `ETag` of a remote config), so replicas that fetched the
+    /// same content report the same version; sources without one are
+    /// versioned by a hash of their content.
+    ///
+    /// Only the most recently prepared config is held. If the sources haven't
+    /// changed, nothing is prepared and the version of the current config is
+    /// returned, which [`activate`](Self::activate) accepts as a no-op.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if loading or validation fails, or a
+    /// [pre-swap hook](crate::core::HotswapConfigBuilder::with_pre_swap_hook)
+    /// vetoes the config; the current config is kept either way.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use hotswap_config::prelude::*;
+    /// # use serde::Deserialize;
+    /// # #[derive(Debug, Deserialize, Clone)]
+    /// # struct AppConfig { port: u16 }
+    /// # async fn example(config: HotswapConfig<AppConfig>) -> Result<()> {
+    /// let version = config.prepare().await?;
+    /// // ... report `version` to the orchestrator, and once every replica
+    /// // has prepared it:
+    /// config.activate(&version).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn prepare(&self) -> Result<String>
+    where
+        T: DeserializeOwned,
+    {
+        let loader = self
+            .loader
+            .as_ref()
+            .ok_or_else(|| ConfigError::Other("No loader available for reload".to_string()))?;
+
+        let generation = self.generation.load(Ordering::Acquire);
+        let loaded = loader.load_fingerprinted()?;
+        if *self.fingerprint.lock().unwrap() == Some(loaded.fingerprint) {
+            return Ok(loaded.version);
+        }
+        let config = self
+            .check(loaded.config, loaded.section_errors, loaded.errors)
+            .await?;
+        self.pre_swap(&config)?;
+        let version = loaded.version.clone();
+        self.stage(Prepared {
+            version: loaded.version,
+            config,
+            generation,
+            fingerprint: loaded.fingerprint,
+            provenance: Arc::new(loaded.provenance),
+            migrations: loaded.migrations,
+            restart_required: loaded.restart_required,
+            staged_by: None,
+        });
+        Ok(version)
+    }
+
+    /// Swap in the prepared configuration with `version`.
+    ///
+    /// The config was loaded and validated by [`prepare`](Self::prepare), or
+    /// by a reload under
+    /// [`with_coordinated_activation`](crate::core::HotswapConfigBuilder::with_coordinated_activation),
+    /// so activating it only runs the pre-swap hooks and transactional
+    /// subscribers before the swap. Activating the version that is already
+    /// current does nothing. A config prepared by a reload completes that
+    /// reload here: [`reload_reason`](Self::reload_reason) reports its reason
+    /// and metrics count it as a success or failure only now.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigError::NotPrepared`] if `version` isn't the prepared
+    /// config, or was prepared before another config was swapped in (it
+    /// must then be prepared again), and fails like a reload if the config
+    /// is frozen, vetoed, or can't be applied. A prepared config that fails to
+    /// activate is discarded.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use hotswap_config::prelude::*;
+    /// # use serde::Deserialize;
+    /// # #[derive(Debug, Deserialize, Clone)]
+    /// # struct AppConfig { port: u16 }
+    /// # async fn example(config: HotswapConfig<AppConfig>) -> Result<()> {
+    /// // Told by the orchestrator to activate "v42" on every replica
+    /// config.activate("v42").await?;
+    /// assert_eq!(config.version().as_deref(), Some("v42"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn activate(&self, version: &str) -> Result<()>
+    where
+        T: Clone,
+    {
+        if let Some(freeze) = self.frozen.lock().unwrap().as_ref() {
+            return Err(ConfigError::Frozen {
+                reason: freeze.reason.clone(),
+            });
+        }
+
+        let prepared = {
+            let mut prepared = self.prepared.lock().unwrap();
+            let generation = self.generation.load(Ordering::Acquire);
+            // Validated against a config that has since been replaced
+            if prepared
+                .as_ref()
+                .is_some_and(|p| p.generation != generation)
+            {
+                *prepared = None;
+            }
+            match prepared.take_if(|p| p.version == version) {
+                Some(prepared) => prepared,
+                None if self.version.lock().unwrap().as_deref() == Some(version) => {
+                    return Ok(());
+                }
+                None => {
+                    return Err(ConfigError::NotPrepared {
+                        version: version.to_string(),
+                    });
+                }
+            }
+        };
+
+        self.pre_swap(&prepared.config)?;
+        let Some((reason, duration)) = prepared.staged_by.clone() else {
+            return self.swap_loaded(prepared).await;
+        };
+
+        // Complete the reload that staged it, with its reason set before
+        // subscribers are notified
+        let previous = self.reload_reason.lock().unwrap().replace(reason);
+        let result = self.swap_loaded(prepared).await;
+        if result.is_err() {
+            *self.reload_reason.lock().unwrap() = previous;
+        }
+        if let Some(metrics) = &self.metrics {
+            match &result {
+                Ok(()) => metrics.reload_succeeded(duration),
+                Err(_) => metrics.reload_failed(duration),
+            }
+        }
+        result
+    }
+
+    /// The version of the current configuration.
+    ///
+    /// See [`prepare`](Self::prepare) for how versions are assigned. `None`
+    /// if the config wasn't loaded from sources, or was last changed by
+    /// [`update`](Self::update) rather than a load.
+    pub fn version(&self) -> Option<String> {
+        self.version.lock().unwrap().clone()
+    }
+
+    /// The version of the configuration waiting to be
+    /// [activated](Self::activate), if any.
+    pub fn prepared_version(&self) -> Option<String> {
+        let prepared = self.prepared.lock().unwrap();
+        let generation = self.generation.load(Ordering::Acquire);
+        prepared
+            .as_ref()
+            .filter(|prepared| prepared.generation == generation)
+            .map(|prepared| prepared.version.clone())
+    }
+
+    /// When the configuration waiting for its sources to take effect will be
+    /// swapped in, if any.
+    ///
+    /// A reload whose sources report an
+    /// [effective time](crate::sources::ConfigSource::effective_at) in the
+    /// future validates the new config and holds it until then; a later
+    /// reload replaces it, or cancels it if the sources go back to the
+    /// current config. At that instant it is swapped in without re-reading
+    /// the sources, and [`reload_reason`](Self::reload_reason) reports
+    /// [`ReloadReason::Activation`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use hotswap_config::prelude::*;
+    /// # use serde::Deserialize;
+    /// # #[derive(Debug, Deserialize, Clone)]
+    /// # struct AppConfig { port: u16 }
+    /// # async fn example(config: HotswapConfig<AppConfig>) {
+    /// if let Some(at) = config.scheduled_at() {
+    ///     println!("New configuration takes effect at {:?}", at);
+    /// }
+    /// # }
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub fn scheduled_at(&self) -> Option<SystemTime> {
+        self.scheduled
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|scheduled| scheduled.at)
+    }
+
+    /// Update configuration with a new value directly.
+    ///
+    /// This bypasses the loader and directly updates the configuration.
+    /// Useful for programmatic updates or testing.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if validation fails, if the
+    /// [update policy](crate::core::HotswapConfigBuilder::with_update_policy)
+    /// denies it, or if this handle was created with [`project`](Self::project).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use hotswap_config::prelude::*;
+    /// # use serde::Deserialize;
+    /// # #[derive(Debug, Deserialize, Clone)]
+    /// # struct AppConfig { port: u16 }
+    /// # async fn example(config: HotswapConfig<AppConfig>) -> Result<()> {
+    /// let new_config = AppConfig { port: 9090 };
+    /// config.update(new_config).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn update(&self, new_config: T) -> Result<()> {
+        self.update_with_context(new_config, &UpdateContext::default())
+            .await
+    }
+
+    /// Update configuration with a new value directly, on behalf of the
+    /// requester described by `context`.
+    ///
+    /// Like [`update`](Self::update), but the
+    /// [update policy](crate::core::HotswapConfigBuilder::with_update_policy)
+    /// sees `context`, e.g. to allow updates only from some users. The whole
+    /// configuration is replaced, so the policy is given the root path `""`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if validation fails, if the update policy denies it,
+    /// or if this handle was created with [`project`](Self::project).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use hotswap_config::prelude::*;
+    /// # use serde::Deserialize;
+    /// # #[derive(Debug, Deserialize, Clone)]
+    /// # struct AppConfig { port: u16 }
+    /// # async fn example(config: HotswapConfig<AppConfig>) -> Result<()> {
+    /// let context = UpdateContext::default().with_actor("deploy-bot");
+    /// config
+    ///     .update_with_context(AppConfig { port: 9090 }, &context)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn update_with_context(&self, new_config: T, context: &UpdateContext) -> Result<()> {
+        self.authorize(&[String::new()], context)?;
+        self.apply_update(new_config).await
+    }
+
+    /// Ask the update policy whether an update changing the JSON Pointer
+    /// `paths` may be applied.
+    pub(crate) fn authorize(&self, paths: &[String], context: &UpdateContext) -> Result<()> {
+        if let Some(policy) = &self.update_policy
+            && let PolicyDecision::Deny(reason) = policy(paths, context)
+        {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(
+                paths = ?paths,
+                origin = ?context.origin,
+                actor = context.actor.as_deref(),
+                reason = %reason,
+                "Update denied by policy"
+            );
+            return Err(ConfigError::UpdateDenied { reason });
+        }
+        Ok(())
+    }
+
+    /// Validate and swap in an update the policy has allowed.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "hotswap_config.update", skip_all)
+    )]
+    pub(crate) async fn apply_update(&self, new_config: T) -> Result<()> {
+        if self.projected {
+            return Err(ConfigError::Other(
+                "Cannot update a projected configuration handle; update its parent".to_string(),
+            ));
+        }
+        if let Some(freeze) = self.frozen.lock().unwrap().as_ref() {
+            return Err(ConfigError::Frozen {
+                reason: freeze.reason.clone(),
+            });
+        }
+
+        // Validate the new config on its own and as a change from the current one
+        let mut errors = Vec::new();
+        if let Some(validator) = &self.validator {
+            errors.extend(validator(&new_config).err());
+        }
+        if let Some(transition) = &self.transition_validator {
+            errors.extend(transition(&self.current.load(), &new_config).err());
+        }
+        #[cfg(feature = "testing")]
+        errors.extend(self.faults.next_rejection());
+        let validation_result = ValidationError::collect(errors);
+        if validation_result.is_err() {
+            if let Some(metrics) = &self.metrics {
+                metrics.validation_failed();
+            }
+            #[cfg(feature = "tracing")]
+            if let Err(e) = &validation_result {
+                tracing::warn!(error = %e, "Configuration rejected by validation");
+            }
+            return validation_result.map_err(|e| ConfigError::ValidationError(e.to_string()));
+        }
+
+        // Verify against external systems before anything sees the new config
+        let new_config = Arc::new(new_config);
+        self.verify(&new_config).await?;
+        self.pre_swap(&new_config)?;
+
+        // Let transactional subscribers apply it, keeping the old config if one can't
+        self.appliers.apply(&self.current.load(), &new_config)?;
+
+        // Atomically swap to the new configuration
+        self.current.store(Arc::clone(&new_config));
+        self.generation.fetch_add(1, Ordering::Release);
+        #[cfg(feature = "tokio")]
+        self.changes.send_replace(Arc::clone(&new_config));
+        let pending = self.projections.publish(&new_config);
+
+        // The current config no longer matches the sources, so the next reload
+        // must apply even if they haven't changed
+        *self.fingerprint.lock().unwrap() = None;
+        *self.version.lock().unwrap() = None;
+        *self.reload_reason.lock().unwrap() = None;
+        *self.provenance.lock().unwrap() = None;
+        #[cfg(feature = "tracing")]
+        tracing::info!("Swapped in updated configuration");
+
+        // Keep an attached rollout's stable slot in sync
+        #[cfg(feature = "gradual-rollout")]
+        if let Some(canary) = &self.canary {
+            canary.rollout().set_stable(new_config);
+        }
+
+        // Record the update in metrics
+        if let Some(metrics) = &self.metrics {
+            metrics.config_updated();
+        }
+
+        // Notify subscribers
+        #[cfg(feature = "file-watch")]
+        self.subscribers.notify_all();
+        for notify in pending {
+            notify.await;
+        }
+
+        Ok(())
+    }
+
+    /// The active profile, if the config was built with profiles.
+    ///
+    /// See [`HotswapConfigBuilder::with_profile`](crate::core::HotswapConfigBuilder::with_profile).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use hotswap_config::prelude::*;
+    /// # use serde::Deserialize;
+    /// # #[derive(Debug, Deserialize, Clone)]
+    /// # struct AppConfig { port: u16 }
+    /// # async fn example() -> Result<()> {
+    /// let config = HotswapConfig::<AppConfig>::builder()
+    ///     .with_profile("production")
+    ///     .build()
+    ///     .await?;
+    /// println!("running with profile {}", config.profile().unwrap_or("none"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn profile(&self) -> Option<&str> {
+        self.profile.as_deref()
+    }
+
+    /// Immutable keys whose changed values are waiting for a restart.
+    ///
+    /// Under [`ImmutableKeys::RestartRequired`], a reload that changes a key
+    /// declared with
+    /// [`with_immutable_paths`](crate::core::HotswapConfigBuilder::with_immutable_paths)
+    /// keeps its boot-time value and lists it here. Empty when every
+    /// immutable key still matches its sources.
+    ///
+    /// [`ImmutableKeys::RestartRequired`]: crate::core::ImmutableKeys::RestartRequired
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use hotswap_config::prelude::*;
+    /// # async fn example(config: HotswapConfig<u16>) {
+    /// let pending = config.restart_required();
+    /// if !pending.is_empty() {
+    ///     eprintln!("restart to apply changes to {}", pending.join(", "));
+    /// }
+    /// # }
+    /// ```
+    pub fn restart_required(&self) -> Vec<String> {
+        self.restart_required.lock().unwrap().clone()
+    }
+
+    /// Sources that failed during the last load and were skipped or served
+    /// from their last loaded values.
+    ///
+    /// A source's [`FailurePolicy`](crate::sources::FailurePolicy) decides
+    /// whether its failure fails the load; sources that are
+    /// [`Optional`](crate::sources::FailurePolicy::Optional) or
+    /// [`FallbackToCache`](crate::sources::FailurePolicy::FallbackToCache)
+    /// are reported here instead. Empty when every source loaded.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use hotswap_config::prelude::*;
+    /// # async fn example(config: HotswapConfig<u16>) {
+    /// for skipped in config.skipped_sources() {
+    ///     eprintln!("{} unavailable: {}", skipped.name, skipped.error);
+    /// }
+    /// # }
+    /// ```
+    pub fn skipped_sources(&self) -> Vec<SkippedSource> {
+        self.skipped.lock().unwrap().clone()
+    }
+
+    /// Migrations applied to the sources of the current config, in order.
+    ///
+    /// Empty if the sources were already at the latest schema version, or no
+    /// migrations were registered with
+    /// [`HotswapConfigBuilder::with_migration`](crate::core::HotswapConfigBuilder::with_migration).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use hotswap_config::prelude::*;
+    /// # async fn example(config: HotswapConfig<u16>) {
+    /// for migration in config.applied_migrations() {
+    ///     eprintln!("config migrated from version {}", migration);
+    /// }
+    /// # }
+    /// ```
+    pub fn applied_migrations(&self) -> Vec<AppliedMigration> {
+        self.migrations.lock().unwrap().clone()
+    }
+
+    /// Check whether the config is a startup fallback.
+    ///
+    /// True while serving the snapshot from
+    /// [`with_snapshot_fallback`](crate::core::HotswapConfigBuilder::with_snapshot_fallback)
+    /// or the defaults passed to
+    /// [`build_with_defaults_on_failure`](crate::core::HotswapConfigBuilder::build_with_defaults_on_failure)
+    /// because sources failed to load at startup. Cleared by the first
+    /// successful reload from sources.
+    pub fn is_fallback(&self) -> bool {
+        self.fallback.load(Ordering::Acquire)
+    }
+
+    /// Summarize how the configuration was loaded, for logging at startup.
+    ///
+    /// The report lists the merged sources with their priorities, load
+    /// durations, and key counts, sources that failed and were skipped, keys
+    /// set by environment variables, the effective values with secrets
+    /// redacted, and the result of running the validators against the current
+    /// config.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the config can't be serialized.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use hotswap_config::prelude::*;
+    /// # use serde::{Deserialize, Serialize};
+    /// # #[derive(Debug, Serialize, Deserialize, Clone)]
+    /// # struct AppConfig { port: u16 }
+    /// # async fn example() -> Result<()> {
+    /// let config = HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config/default.yaml")
+    ///     .with_env_overrides("APP", "__")
+    ///     .build()
+    ///     .await?;
+    ///
+    /// // Configuration (profile: none)
+    /// // Sources:
+    /// //   file:config/default.yaml (priority 100): 12 keys in 1.2ms
+    /// //   env:APP* (priority 300): 1 keys in 40.0µs
+    /// // Environment overrides: port
+    /// // Validation: passed
+    /// // Values: { ... }
+    /// println!("{}", config.report()?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "serde_json")]
+    pub fn report(&self) -> Result<crate::core::ConfigReport>
+    where
+        T: serde::Serialize,
+    {
+        let current = self.get();
+        let provenance = self.provenance.lock().unwrap().clone();
+        let (sources, env_overrides) = match &provenance {
+            Some(provenance) => (
+                provenance.sources().to_vec(),
+                provenance
+                    .winners()
+                    .filter(|c| c.source.starts_with("env:"))
+                    .map(|c| c.key.clone())
+                    .collect(),
+            ),
+            None => (Vec::new(), Vec::new()),
+        };
+
+        Ok(crate::core::ConfigReport {
+            profile: self.profile().map(str::to_string),
+            fallback: self.is_fallback(),
+            sources,
+            skipped: self.skipped_sources(),
+            migrations: self.applied_migrations(),
+            env_overrides,
+            values: crate::secrets::Redact::to_redacted_json(&*current)?,
+            validation: match &self.validator {
+                Some(validator) => validator(&current),
+                None => Ok(()),
+            },
+        })
+    }
+
+    /// Explain where the effective value of a key came from.
+    ///
+    /// Returns the value of `key` (a dotted path like `database.pool_size`)
+    /// in the merged sources, and every source that set it or a key nested
+    /// under it, in merge order, with the ones that won marked. Values are
+    /// shown before interpolation and aren't redacted.
+    ///
+    /// Returns `None` if the config wasn't loaded from sources, or was last
+    /// changed by [`update`](Self::update) rather than a load.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use hotswap_config::prelude::*;
+    /// # use serde::Deserialize;
+    /// # #[derive(Debug, Deserialize, Clone)]
+    /// # struct AppConfig { port: u16 }
+    /// # async fn example() -> Result<()> {
+    /// let config = HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config/default.yaml")
+    ///     .with_env_overrides("APP", "__")
+    ///     .build()
+    ///     .await?;
+    ///
+    /// if let Some(explanation) = config.explain("database.pool_size") {
+    ///     // database.pool_size = 20
+    ///     //   file:config/default.yaml (priority 100): 10
+    ///     //   env:APP* (priority 300): 20  <- effective
+    ///     print!("{}", explanation);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn explain(&self, key: &str) -> Option<Explanation> {
+        let provenance = self.provenance.lock().unwrap().clone()?;
+        Some(provenance.explain(key))
+    }
+
+    /// Read a key of the merged source tree by dotted path, without
+    /// deserializing into `T`.
+    ///
+    /// For dynamic code (plugins, scripts) that reads keys the static config
+    /// type doesn't know about. Values are taken after merging and
+    /// interpolation, and aren't redacted. Returns `None` if the key isn't
+    /// set, or if there is no merged tree: the config wasn't loaded from
+    /// sources, or was last changed by [`update`](Self::update).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use hotswap_config::prelude::*;
+    /// # use serde::Deserialize;
+    /// # #[derive(Debug, Deserialize, Clone)]
+    /// # struct AppConfig { port: u16 }
+    /// # async fn example(config: HotswapConfig<AppConfig>) {
+    /// if let Some(value) = config.get_raw("plugins.audit.endpoint") {
+    ///     println!("Audit endpoint: {}", value);
+    /// }
+    /// # }
+    /// ```
+    pub fn get_raw(&self, key: &str) -> Option<config::Value> {
+        let provenance = self.provenance.lock().unwrap().clone()?;
+        provenance.value(key).cloned()
+    }
+
+    /// Read a key of the merged source tree by dotted path as type `V`.
+    ///
+    /// Like [`get_raw`](Self::get_raw), deserialized with the same rules as
+    /// the whole config, so strings from environment variables convert to
+    /// numbers and booleans.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigError::LoadError`] if the key isn't set (see
+    /// [`get_raw`](Self::get_raw)), or [`ConfigError::DeserializationError`]
+    /// naming the key if its value can't be converted to `V`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use hotswap_config::prelude::*;
+    /// # use serde::Deserialize;
+    /// # #[derive(Debug, Deserialize, Clone)]
+    /// # struct AppConfig { port: u16 }
+    /// # fn example(config: HotswapConfig<AppConfig>) -> Result<()> {
+    /// let port = config.get_typed::<u16>("server.port")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_typed<V>(&self, key: &str) -> Result<V>
+    where
+        V: DeserializeOwned,
+    {
+        let value = self
+            .get_raw(key)
+            .ok_or_else(|| ConfigError::LoadError(format!("Key '{}' is not set", key)))?;
+        value
+            .try_deserialize()
+            .map_err(|e| ConfigError::DeserializationError(e.to_string()).at_key(key))
+    }
+
+    /// Freeze the configuration, e.g. during incident response.
+    ///
+    /// While frozen, reloads (manual or from the file watcher) and updates
+    /// (including partial updates and rollbacks) fail with
+    /// [`ConfigError::Frozen`] and the current config stays in place. The file
+    /// watcher keeps running, and [`unfreeze`](Self::unfreeze) catches up on
+    /// any reload that was rejected in the meantime. Freezing an already frozen
+    /// configuration replaces the reason.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hotswap_config::prelude::*;
+    ///
+    /// # async fn example() -> Result<()> {
+    /// let config = HotswapConfig::new(8080u16);
+    ///
+    /// config.freeze(Some("INC-1234: pinning config during incident"));
+    /// assert!(matches!(config.update(9090).await, Err(ConfigError::Frozen { .. })));
+    ///
+    /// config.unfreeze().await?;
+    /// config.update(9090).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn freeze(&self, reason: Option<&str>) {
+        let mut frozen = self.frozen.lock().unwrap();
+        let missed_reload = frozen.as_ref().is_some_and(|f| f.missed_reload);
+        *frozen = Some(Freeze {
+            reason: reason.map(str::to_string),
+            missed_reload,
+        });
+        #[cfg(feature = "tracing")]
+        tracing::warn!(reason = reason.unwrap_or_default(), "Configuration frozen");
+    }
+
+    /// Check whether the configuration is frozen.
+    pub fn is_frozen(&self) -> bool {
+        self.frozen.lock().unwrap().is_some()
+    }
+
+    /// Unfreeze the configuration, allowing reloads and updates again.
+    ///
+    /// If a reload was rejected while frozen, the configuration is reloaded
+    /// from its sources now so it doesn't stay stale until the next change.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the catch-up reload fails; the configuration is
+    /// unfrozen regardless.
+    pub async fn unfreeze(&self) -> Result<()>
+    where
+        T: DeserializeOwned + Clone,
+    {
+        let freeze = self.frozen.lock().unwrap().take();
+        #[cfg(feature = "tracing")]
+        if freeze.is_some() {
+            tracing::info!("Configuration unfrozen");
+        }
+
+        match freeze {
+            Some(Freeze {
+                missed_reload: true,
+                ..
+            }) => self.reload().await,
+            _ => Ok(()),
+        }
+    }
+
+    /// Subscribe to configuration changes with the ability to reject them.
+    ///
+    /// `callback` is handed each new configuration before it is swapped in by
+    /// a reload or update, so it can apply it (e.g. load a new TLS
+    /// certificate). If it returns an error, transactional subscribers that
+    /// already applied the new config are handed the previous one again, the
+    /// swap doesn't happen, and the reload or update fails with
+    /// [`ConfigError::ApplyFailed`] naming `name`. Readers never see a config
+    /// a subscriber couldn't apply.
+    ///
+    /// Transactional subscribers run in registration order, after validation
+    /// and before regular subscribers are notified. Reloads staged as a canary
+    /// skip them. Returns a handle that can be dropped to unsubscribe.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hotswap_config::prelude::*;
+    /// use hotswap_config::error::ApplyError;
+    ///
+    /// #[derive(Debug, Clone)]
+    /// struct TlsConfig {
+    ///     cert_path: String,
+    /// }
+    ///
+    /// # async fn example() -> Result<()> {
+    /// let config = HotswapConfig::new(TlsConfig { cert_path: "cert.pem".to_string() });
+    ///
+    /// let _handle = config.subscribe_transactional("tls", |tls: &TlsConfig| {
+    ///     if tls.cert_path.is_empty() {
+    ///         return Err(ApplyError::new("no certificate configured"));
+    ///     }
+    ///     Ok(())
+    /// });
+    ///
+    /// let rejected = config.update(TlsConfig { cert_path: String::new() }).await;
+    /// assert!(matches!(rejected, Err(ConfigError::ApplyFailed { .. })));
+    /// assert_eq!(config.get().cert_path, "cert.pem");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn subscribe_transactional<F>(&self, name: impl Into<String>, callback: F) -> ApplyHandle
+    where
+        F: Fn(&T) -> std::result::Result<(), ApplyError> + Send + Sync + 'static,
+        T: 'static,
+    {
+        self.appliers.subscribe(name.into(), callback)
+    }
+
+    /// Run the async validators, recording rejections like other validation
+    /// failures.
+    async fn verify(&self, config: &Arc<T>) -> Result<()> {
+        let result = crate::core::verify(&self.async_validators, config).await;
+        if let Err(e) = &result {
+            if let (ConfigError::ValidationError(_), Some(metrics)) = (e, &self.metrics) {
+                metrics.validation_failed();
+            }
+            #[cfg(feature = "tracing")]
+            tracing::warn!(error = %e, "Configuration rejected by async validation");
+        }
+        result
+    }
+
+    /// Derive a read-only handle to part of this configuration.
+    ///
+    /// The projected handle holds `f` applied to the current configuration,
+    /// and is updated whenever this handle swaps in a new one (by reload,
+    /// update, or canary promotion). Its subscribers are only notified when
+    /// the projected value actually changes, so a component handed
+    /// `HotswapConfig<DatabaseConfig>` isn't woken by unrelated edits.
+    ///
+    /// Projected handles can't be reloaded or updated directly, and can be
+    /// projected further. Dropping every clone of a projected handle detaches
+    /// it from its parent.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hotswap_config::prelude::*;
+    ///
+    /// #[derive(Debug, Clone, PartialEq)]
+    /// struct DatabaseConfig {
+    ///     url: String,
+    /// }
+    ///
+    /// #[derive(Debug, Clone)]
+    /// struct AppConfig {
+    ///     port: u16,
+    ///     database: DatabaseConfig,
+    /// }
+    ///
+    /// # async fn example() -> Result<()> {
+    /// let config = HotswapConfig::new(AppConfig {
+    ///     port: 8080,
+    ///     database: DatabaseConfig { url: "postgres://localhost/db".to_string() },
+    /// });
+    ///
+    /// let database = config.project(|c: &AppConfig| c.database.clone());
+    /// assert_eq!(database.get().url, "postgres://localhost/db");
+    ///
+    /// config
+    ///     .update(AppConfig {
+    ///         port: 8080,
+    ///         database: DatabaseConfig { url: "postgres://replica/db".to_string() },
+    ///     })
+    ///     .await?;
+    /// assert_eq!(database.get().url, "postgres://replica/db");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn project<U, F>(&self, f: F) -> HotswapConfig<U>
+    where
+        U: PartialEq + Send + Sync + 'static,
+        F: Fn(&T) -> U + Send + Sync + 'static,
+    {
+        // Hold the lock so a concurrent swap is either seen here or published
+        let mut registered = self.projections.0.write().unwrap();
+        let mut projected = HotswapConfig::new(f(&self.get()));
+        projected.projected = true;
+        projected.profile = self.profile.clone();
+        // The projection is updated by this handle's background tasks
+        #[cfg(feature = "tokio")]
+        {
+            projected.keep_running = self.keep_running.clone();
+        }
+
+        let current = Arc::downgrade(&projected.current);
+        let generation = Arc::downgrade(&projected.generation);
+        #[cfg(feature = "tokio")]
+        let changes = Arc::downgrade(&projected.changes);
+        let projections = Arc::downgrade(&projected.projections);
+        #[cfg(feature = "file-watch")]
+        let subscribers = Arc::downgrade(&projected.subscribers);
+
+        let projection: Projection<T> = Box::new(move |parent: &T| {
+            let current = current.upgrade()?;
+            let value = f(parent);
+            if **current.load() == value {
+                return Some(Box::pin(async {}));
+            }
+
+            let value = Arc::new(value);
+            current.store(Arc::clone(&value));
+            if let Some(generation) = generation.upgrade() {
+                generation.fetch_add(1, Ordering::Release);
+            }
+            #[cfg(feature = "tokio")]
+            if let Some(changes) = changes.upgrade() {
+                changes.send_replace(Arc::clone(&value));
+            }
+            let pending = projections
+                .upgrade()
+                .map(|p| p.publish(&value))
+                .unwrap_or_default();
+
+            #[cfg(feature = "file-watch")]
+            let subscribers = std::sync::Weak::clone(&subscribers);
+            Some(Box::pin(async move {
+                #[cfg(feature = "file-watch")]
+                if let Some(subscribers) = subscribers.upgrade() {
+                    subscribers.notify_all();
+                }
+                for notify in pending {
+                    notify.await;
+                }
+            }))
+        });
+        registered.push(projection);
+
+        projected
+    }
+
+    /// Drive the `tracing` log level from a field of the configuration.
+    ///
+    /// Returns a [`reload`](tracing_subscriber::reload) layer filtering events
+    /// by the selected directives (e.g. `"info"` or `"warn,my_app=debug"`;
+    /// see [`Targets`](tracing_subscriber::filter::Targets)). Add it to the
+    /// global subscriber; whenever a swap changes the field, the filter is
+    /// replaced. A new value that doesn't parse is logged and the previous
+    /// filter kept.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigError::ParseError`] if the current value doesn't parse.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    /// use serde::Deserialize;
+    /// use tracing_subscriber::prelude::*;
+    ///
+    /// #[derive(Debug, Deserialize, Clone)]
+    /// struct Observability {
+    ///     log_level: String,
+    /// }
+    ///
+    /// #[derive(Debug, Deserialize, Clone)]
+    /// struct AppConfig {
+    ///     observability: Observability,
+    /// }
+    ///
+    /// # fn example(config: HotswapConfig<AppConfig>) -> Result<()> {
+    /// let filter = config.bind_log_level(|c| &c.observability.log_level)?;
+    /// tracing_subscriber::registry().with(filter).init();
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "log-level")]
+    pub fn bind_log_level<S, L, F>(
+        &self,
+        select: F,
+    ) -> Result<tracing_subscriber::reload::Layer<tracing_subscriber::filter::Targets, S>>
+    where
+        S: 'static,
+        L: AsRef<str> + ?Sized,
+        F: Fn(&T) -> &L + Send + Sync + 'static,
+    {
+        use tracing_subscriber::filter::Targets;
+
+        let parse = |level: &str| {
+            level.parse::<Targets>().map_err(|e| {
+                ConfigError::ParseError(format!("Invalid log level '{}': {}", level, e))
+            })
+        };
+
+        // Hold the lock so a concurrent swap is either seen here or published
+        let mut registered = self.projections.0.write().unwrap();
+        let current = select(&self.get()).as_ref().to_string();
+        let (layer, handle) = tracing_subscriber::reload::Layer::new(parse(&current)?);
+
+        let last = Mutex::new(current);
+        registered.push(Box::new(move |config: &T| {
+            let level = select(config).as_ref();
+            let mut last = last.lock().unwrap();
+            if *last != level {
+                match parse(level) {
+                    // The subscriber holding the layer is gone, so unbind
+                    Ok(filter) => handle.reload(filter).ok()?,
+                    Err(e) => tracing::warn!(error = %e, "Keeping the previous log level"),
+                }
+                *last = level.to_string();
+            }
+            Some(Box::pin(async {}))
+        }));
+
+        Ok(layer)
+    }
+
+    /// Subscribe to configuration changes.
+    ///
+    /// The provided callback will be invoked whenever the configuration
+    /// is reloaded or updated. Returns a handle that can be dropped to unsubscribe.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use hotswap_config::prelude::*;
+    /// # use serde::Deserialize;
+    /// # #[derive(Debug, Deserialize, Clone)]
+    /// # struct AppConfig { port: u16 }
+    /// # async fn example(config: HotswapConfig<AppConfig>) {
+    /// let handle = config.subscribe(|| {
+    ///     println!("Configuration changed!");
+    /// }).await;
+    ///
+    /// // Later, unsubscribe
+    /// drop(handle);
+    /// # }
+    /// ```
+    #[cfg(feature = "file-watch")]
+    pub async fn subscribe<F>(&self, callback: F) -> crate::notify::SubscriptionHandle
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.subscribers.subscribe(callback)
+    }
+
+    /// Subscribe to configuration changes with a priority and name.
+    ///
+    /// Subscribers are notified highest priority first, then in subscription
+    /// order, so dependent work happens in a fixed sequence (e.g. re-initialize
+    /// TLS before re-binding listeners). Plain [`subscribe`](Self::subscribe)
+    /// uses priority 0.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use hotswap_config::prelude::*;
+    /// # use hotswap_config::notify::SubscribeOptions;
+    /// # use serde::Deserialize;
+    /// # #[derive(Debug, Deserialize, Clone)]
+    /// # struct AppConfig { port: u16 }
+    /// # async fn example(config: HotswapConfig<AppConfig>) {
+    /// let tls = config.subscribe_with_options(
+    ///     || println!("Reloading certificates"),
+    ///     SubscribeOptions { priority: 100, name: Some("tls".to_string()) },
+    /// ).await;
+    /// let listeners = config.subscribe(|| println!("Re-binding listeners")).await;
+    /// # }
+    /// ```
+    #[cfg(feature = "file-watch")]
+    pub async fn subscribe_with_options<F>(
+        &self,
+        callback: F,
+        options: crate::notify::SubscribeOptions,
+    ) -> crate::notify::SubscriptionHandle
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.subscribers.subscribe_with_options(callback, options)
+    }
+
+    /// Subscribe to configuration changes, delivered from the subscriber's
+    /// own task with the given [`DeliveryOptions`](crate::notify::DeliveryOptions).
+    ///
+    /// Use a debounce window so a burst of source updates results in one
+    /// call, or coalescing so changes queued while the callback runs are
+    /// delivered as one.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use hotswap_config::prelude::*;
+    /// # use hotswap_config::notify::DeliveryOptions;
+    /// # use serde::Deserialize;
+    /// # use std::time::Duration;
+    /// # #[derive(Debug, Deserialize, Clone)]
+    /// # struct AppConfig { port: u16 }
+    /// # async fn example(config: HotswapConfig<AppConfig>) {
+    /// let handle = config.subscribe_with_delivery(
+    ///     || println!("Configuration settled"),
+    ///     DeliveryOptions::default().with_debounce(Duration::from_millis(200)),
+    /// ).await;
+    /// # }
+    /// ```
+    #[cfg(feature = "file-watch")]
+    pub async fn subscribe_with_delivery<F>(
+        &self,
+        callback: F,
+        options: crate::notify::DeliveryOptions,
+    ) -> crate::notify::SubscriptionHandle
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.subscribers.subscribe_queued(
+            move || {
+                callback();
+                std::future::ready(())
+            },
+            options,
+            self.cancellation_token(),
+        )
+    }
+
+    /// Subscribe to configuration changes with an async callback.
+    ///
+    /// The callback's future runs on the subscriber's own task, so heavyweight
+    /// subscribers don't block reloads or other subscribers.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use hotswap_config::prelude::*;
+    /// # use hotswap_config::notify::DeliveryOptions;
+    /// # use serde::Deserialize;
+    /// # #[derive(Debug, Deserialize, Clone)]
+    /// # struct AppConfig { port: u16 }
+    /// # async fn example(config: HotswapConfig<AppConfig>) {
+    /// let pool_config = config.clone();
+    /// let handle = config.subscribe_async(
+    ///     move || {
+    ///         let config = pool_config.clone();
+    ///         async move {
+    ///             println!("Reconnecting on port {}", config.get().port);
+    ///         }
+    ///     },
+    ///     DeliveryOptions::default().with_coalesce(true),
+    /// ).await;
+    /// # }
+    /// ```
+    #[cfg(feature = "file-watch")]
+    pub async fn subscribe_async<F, Fut>(
+        &self,
+        callback: F,
+        options: crate::notify::DeliveryOptions,
+    ) -> crate::notify::SubscriptionHandle
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = ()> + Send + 'static,
+    {
+        self.subscribers
+            .subscribe_queued(callback, options, self.cancellation_token())
+    }
+
+    /// Subscribe to reload failures.
+    ///
+    /// The provided callback will be invoked with the error whenever a reload
+    /// (manual or automatic) fails and the previous configuration is retained.
+    /// Returns a handle that can be dropped to unsubscribe.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use hotswap_config::prelude::*;
+    /// # use serde::Deserialize;
+    /// # #[derive(Debug, Deserialize, Clone)]
+    /// # struct AppConfig { port: u16 }
+    /// # async fn example(config: HotswapConfig<AppConfig>) {
+    /// let handle = config.subscribe_errors(|err| {
+    ///     eprintln!("Configuration reload failed: {}", err);
+    /// }).await;
+    /// # }
+    /// ```
+    #[cfg(feature = "file-watch")]
+    pub async fn subscribe_errors<F>(&self, callback: F) -> crate::notify::SubscriptionHandle
+    where
+        F: Fn(&ConfigError) + Send + Sync + 'static,
+    {
+        self.subscribers.subscribe_errors(callback)
+    }
+
+    /// Start watching configuration files for changes.
+    ///
+    /// When enabled, the configuration will automatically reload when any
+    /// watched file changes. This requires a file watcher to be set up
+    /// via the builder.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no file watcher is configured.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use hotswap_config::prelude::*;
+    /// # use serde::Deserialize;
+    /// # #[derive(Debug, Deserialize, Clone)]
+    /// # struct AppConfig { port: u16 }
+    /// # async fn example() -> Result<()> {
+    /// let config = HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config.yaml")
+    ///     .with_file_watch(true)
+    ///     .build()
+    ///     .await?;
+    ///
+    /// // File watching is now active
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "file-watch")]
     pub fn is_watching(&self) -> bool {
         self.watcher.is_some()
     }
+
+    /// Start watching a file and merge it into the configuration.
+    ///
+    /// For files that only appear after the handle was built, such as a
+    /// secrets file mounted later. The file is added as a source at priority
+    /// 200, above files added with the builder and below environment
+    /// variables, and loaded; the result is validated and swapped in like any reload.
+    /// From then on, changes to the file reload the configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the handle isn't watching files, the file is
+    /// already a source, or it fails to load or the result fails validation;
+    /// the file is then neither watched nor merged.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use hotswap_config::prelude::*;
+    /// # use serde::Deserialize;
+    /// # #[derive(Debug, Deserialize, Clone)]
+    /// # struct AppConfig { port: u16 }
+    /// # async fn example(config: HotswapConfig<AppConfig>) -> Result<()> {
+    /// // Once the secrets volume is mounted
+    /// config.watch_path("/run/secrets/app.yaml").await?;
+    ///
+    /// // When it is decommissioned
+    /// config.unwatch_path("/run/secrets/app.yaml").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "file-watch")]
+    pub async fn watch_path(&self, path: impl AsRef<std::path::Path>) -> Result<()>
+    where
+        T: DeserializeOwned + Clone,
+    {
+        let path = path.as_ref();
+        let (loader, watcher) = self.watched_loader()?;
+        if loader.has_source(|source| reads_only(source, path)) {
+            return Err(ConfigError::Other(format!(
+                "{} is already a configuration source",
+                path.display()
+            )));
+        }
+
+        watcher.watch(path).await?;
+        let source = crate::sources::FileSource::new(path).with_priority(WATCHED_PATH_PRIORITY);
+        let index = loader.insert_source(Box::new(source));
+        if let Err(e) = self.reload_source(index, ReloadReason::Manual).await {
+            loader.remove_sources(|i, _| i == index);
+            let _ = watcher.unwatch(path).await;
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Stop watching a file and drop it from the configuration.
+    ///
+    /// Works for files added with [`watch_path`](Self::watch_path) or with
+    /// the builder. The remaining sources are merged again from their last
+    /// loaded values, without re-reading them, and the result is validated
+    /// and swapped in like any reload.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the handle isn't watching files, no source reads
+    /// exactly this file, or the configuration without it fails to load or
+    /// validate; the file then stays watched and merged.
+    #[cfg(feature = "file-watch")]
+    pub async fn unwatch_path(&self, path: impl AsRef<std::path::Path>) -> Result<()>
+    where
+        T: DeserializeOwned + Clone,
+    {
+        let path = path.as_ref();
+        let (loader, watcher) = self.watched_loader()?;
+        let removed = loader.remove_sources(|_, source| reads_only(source, path));
+        if removed.is_empty() {
+            return Err(ConfigError::Other(format!(
+                "{} is not a configuration source",
+                path.display()
+            )));
+        }
+
+        if let Err(e) = self.reload_from(Refresh::Merge, ReloadReason::Manual).await {
+            for source in removed {
+                loader.insert_source(source);
+            }
+            return Err(e);
+        }
+        watcher.unwatch(path).await
+    }
+
+    /// Add a source to the configuration at runtime.
+    ///
+    /// For sources that only become available after startup, such as a
+    /// remote source found through service discovery. The source is merged
+    /// at its own [`priority`](crate::sources::ConfigSource::priority), like
+    /// one added with
+    /// [`HotswapConfigBuilder::with_source`](crate::core::HotswapConfigBuilder::with_source),
+    /// and loaded; the result is validated and swapped in like any reload.
+    /// If the source [watches](crate::sources::ConfigSource::watch) for
+    /// changes, they reload it from then on.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the handle has no sources to add to (it wasn't
+    /// built with the builder), or the source fails to load or the result
+    /// fails validation; the source is then not added.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use hotswap_config::prelude::*;
+    /// # use hotswap_config::sources::FileSource;
+    /// # use serde::Deserialize;
+    /// # #[derive(Debug, Deserialize, Clone)]
+    /// # struct AppConfig { port: u16 }
+    /// # async fn example(config: HotswapConfig<AppConfig>) -> Result<()> {
+    /// let discovered = FileSource::new("/etc/app/discovered.yaml").with_priority(150);
+    /// config.add_source(discovered).await?;
+    ///
+    /// // Once the source is decommissioned
+    /// config.remove_source("file:/etc/app/discovered.yaml").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn add_source(&self, source: impl ConfigSource + 'static) -> Result<()>
+    where
+        T: DeserializeOwned + Clone + Send + Sync + 'static,
+    {
+        let loader = self.source_loader()?;
+        #[cfg(feature = "tokio")]
+        let changes = source.watch();
+        let index = loader.insert_source(Box::new(source));
+        // Load only the new source; the others are merged from their last
+        // values
+        if let Err(e) = self
+            .reload_from(Refresh::Source(index), ReloadReason::Manual)
+            .await
+        {
+            loader.remove_sources(|i, _| i == index);
+            return Err(e);
+        }
+        #[cfg(feature = "tokio")]
+        if let Some(changes) = changes {
+            self.follow_source(index, changes);
+        }
+        Ok(())
+    }
+
+    /// Remove every source named `name` from the configuration at runtime.
+    ///
+    /// The remaining sources are merged again from their last loaded values,
+    /// without re-reading them, and the result is validated and swapped in
+    /// like any reload. Names are the ones shown by
+    /// [`explain`](Self::explain) and in errors, such as
+    /// `file:config/app.yaml`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no source is named `name`, or the configuration
+    /// without it fails to load or validate; the source is then kept.
+    pub async fn remove_source(&self, name: &str) -> Result<()>
+    where
+        T: DeserializeOwned + Clone,
+    {
+        let loader = self.source_loader()?;
+        let removed = loader.remove_sources(|_, source| source.name() == name);
+        if removed.is_empty() {
+            return Err(ConfigError::Other(format!(
+                "No configuration source is named {}",
+                name
+            )));
+        }
+
+        if let Err(e) = self.reload_from(Refresh::Merge, ReloadReason::Manual).await {
+            for source in removed {
+                loader.insert_source(source);
+            }
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// The loader of a handle built from sources.
+    fn source_loader(&self) -> Result<&ConfigLoader> {
+        self.loader
+            .as_deref()
+            .ok_or_else(|| ConfigError::Other("Configuration has no sources".to_string()))
+    }
+
+    /// Reload the source at `index` whenever `changes` reports a change,
+    /// until the source is removed or the handle shuts down.
+    #[cfg(feature = "tokio")]
+    pub(crate) fn follow_source(&self, index: usize, changes: crate::sources::ChangeStream)
+    where
+        T: DeserializeOwned + Clone + Send + Sync + 'static,
+    {
+        let Some(name) = self.loader.as_ref().and_then(|l| l.source_name(index)) else {
+            return;
+        };
+        let config = self.detached();
+        let trigger =
+            crate::core::SourceTrigger::new(name, changes, move |e| config.report_reload_error(e));
+        self.follow(Box::new(trigger), Some(index));
+    }
+
+    /// Reload whenever `trigger` fires, until it stops or the handle shuts
+    /// down.
+    #[cfg(feature = "tokio")]
+    pub(crate) fn follow_trigger(&self, trigger: Box<dyn crate::core::ReloadTrigger>)
+    where
+        T: DeserializeOwned + Clone + Send + Sync + 'static,
+    {
+        self.follow(trigger, None);
+    }
+
+    /// Reload whenever `trigger` fires, re-loading only the source at
+    /// `source` if given, until the trigger stops, the source is removed, or
+    /// the handle shuts down.
+    #[cfg(feature = "tokio")]
+    fn follow(&self, mut trigger: Box<dyn crate::core::ReloadTrigger>, source: Option<usize>)
+    where
+        T: DeserializeOwned + Clone + Send + Sync + 'static,
+    {
+        use crate::core::Turn;
+
+        let config = self.detached();
+        self.spawn(async move {
+            while let Some(reason) = trigger.next().await {
+                if let Some(index) = source
+                    && config
+                        .loader
+                        .as_ref()
+                        .and_then(|l| l.source_name(index))
+                        .is_none()
+                {
+                    break;
+                }
+                let result = match config.throttle(reason).await {
+                    Turn::Lead(slot) => {
+                        // Reload for triggers that piled up while throttled together
+                        let mut reason = slot.reason.clone();
+                        while let Some(more) = trigger.try_next() {
+                            reason = reason.fold(more);
+                        }
+                        let result = match source {
+                            // Other triggers joined in, which this source alone doesn't cover
+                            Some(index) if !slot.joined => {
+                                config.reload_source(index, reason).await
+                            }
+                            _ => config.reload_with_reason(reason).await,
+                        };
+                        slot.finish(&result);
+                        if let Err(e) = &result {
+                            config.report_reload_error(e);
+                        }
+                        result
+                    }
+                    // The reload folded into reports its own failure
+                    Turn::Join(rx) => match ReloadFlight::wait(rx).await {
+                        Some(result) => result,
+                        None => continue,
+                    },
+                };
+                trigger.reloaded(&result);
+            }
+        });
+    }
+
+    /// Swap in each scheduled config when it takes effect, until the handle
+    /// shuts down.
+    #[cfg(feature = "tokio")]
+    pub(crate) fn follow_activations(&self)
+    where
+        T: DeserializeOwned + Clone + Send + Sync + 'static,
+    {
+        let config = self.detached();
+        self.spawn(async move {
+            loop {
+                let Some(at) = config.scheduled_at() else {
+                    config.scheduled_changed.notified().await;
+                    continue;
+                };
+                let delay = at.duration_since(SystemTime::now()).unwrap_or_default();
+                tokio::select! {
+                    _ = tokio::time::sleep(delay) => {
+                        // The sources were loaded when it was scheduled
+                        let reason = ReloadReason::Activation;
+                        if let Err(e) = config.reload_from(Refresh::Merge, reason).await {
+                            config.report_reload_error(&e);
+                        }
+                        // Don't retry a config that failed to take effect
+                        if config.scheduled_at().is_some_and(|at| at <= SystemTime::now()) {
+                            config.cancel_scheduled();
+                        }
+                    }
+                    _ = config.scheduled_changed.notified() => {}
+                }
+            }
+        });
+    }
+
+    /// The loader and watcher of a handle that watches files.
+    #[cfg(feature = "file-watch")]
+    fn watched_loader(&self) -> Result<(&ConfigLoader, &ConfigWatcher)> {
+        match (&self.loader, &self.watcher) {
+            (Some(loader), Some(watcher)) => Ok((loader, watcher)),
+            _ => Err(ConfigError::Other(
+                "File watching is not enabled for this configuration".to_string(),
+            )),
+        }
+    }
+
+    /// Stop the background tasks and wait for them to finish.
+    ///
+    /// Stops the file watcher, scheduled and overlay refreshes, watching and
+    /// streaming sources, startup retries, scheduled and automatic canary
+    /// promotions, and the tasks delivering changes to queued and async
+    /// subscribers, for this handle and all its clones. A reload in progress is abandoned at its next await point and
+    /// the current config stays live. Reading, [`reload`](Self::reload), and
+    /// [`update`](Self::update) keep working; only automatic reloads stop.
+    ///
+    /// The tasks are also stopped, without waiting, once every clone of the
+    /// handle is dropped, or when the token given to
+    /// [`HotswapConfigBuilder::with_cancellation_token`](crate::core::HotswapConfigBuilder::with_cancellation_token)
+    /// is cancelled.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use hotswap_config::prelude::*;
+    /// # use serde::Deserialize;
+    /// # #[derive(Debug, Deserialize, Clone)]
+    /// # struct AppConfig { port: u16 }
+    /// # async fn example() -> Result<()> {
+    /// let config = HotswapConfig::<AppConfig>::builder()
+    ///     .with_file("config.yaml")
+    ///     .build()
+    ///     .await?;
+    ///
+    /// // ... serve until asked to stop ...
+    /// config.shutdown().await;
+    /// assert!(config.is_shut_down());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub async fn shutdown(&self) {
+        self.background.shutdown().await;
+    }
+
+    /// Whether the background tasks have been stopped.
+    #[cfg(feature = "tokio")]
+    pub fn is_shut_down(&self) -> bool {
+        self.background.token().is_cancelled()
+    }
+
+    /// The token that stops the background tasks.
+    ///
+    /// Cancelling it stops the tasks without waiting for them, and
+    /// [`cancelled`](tokio_util::sync::CancellationToken::cancelled) waits
+    /// until the handle is shut down.
+    #[cfg(feature = "tokio")]
+    pub fn cancellation_token(&self) -> tokio_util::sync::CancellationToken {
+        self.background.token().clone()
+    }
+}
+
+/// Report a failed automatic reload to `handler`, or log it.
+#[cfg(feature = "tokio")]
+pub(crate) fn report_reload_error(handler: Option<&ReloadErrorHandler>, error: &ConfigError) {
+    match handler {
+        Some(handler) => handler(error),
+        #[cfg(feature = "tracing")]
+        None => tracing::error!(error = %error, "Auto-reload failed"),
+        #[cfg(not(feature = "tracing"))]
+        None => eprintln!("Auto-reload failed: {}", error),
+    }
 }
 
 impl<T> Clone for HotswapConfig<T> {
     fn clone(&self) -> Self {
         Self {
             current: Arc::clone(&self.current),
+            generation: Arc::clone(&self.generation),
             loader: self.loader.clone(),
             validator: self.validator.clone(),
+            transition_validator: self.transition_validator.clone(),
+            async_validators: self.async_validators.clone(),
+            post_load_hook: self.post_load_hook.clone(),
+            pre_swap_hook: self.pre_swap_hook.clone(),
             #[cfg(feature = "file-watch")]
             watcher: self.watcher.clone(),
             #[cfg(feature = "file-watch")]
             subscribers: Arc::clone(&self.subscribers),
-            #[cfg(feature = "metrics")]
+            #[cfg(feature = "tokio")]
+            changes: Arc::clone(&self.changes),
             metrics: self.metrics.clone(),
+            fingerprint: Arc::clone(&self.fingerprint),
+            provenance: Arc::clone(&self.provenance),
+            skipped: Arc::clone(&self.skipped),
+            migrations: Arc::clone(&self.migrations),
+            version: Arc::clone(&self.version),
+            reload_reason: Arc::clone(&self.reload_reason),
+            prepared: Arc::clone(&self.prepared),
+            #[cfg(feature = "tokio")]
+            scheduled: Arc::clone(&self.scheduled),
+            #[cfg(feature = "tokio")]
+            scheduled_changed: Arc::clone(&self.scheduled_changed),
+            prepare_handler: self.prepare_handler.clone(),
+            fallback: Arc::clone(&self.fallback),
+            restart_required: Arc::clone(&self.restart_required),
+            restart_handler: self.restart_handler.clone(),
+            #[cfg(feature = "tokio")]
+            reload_error_handler: self.reload_error_handler.clone(),
+            update_policy: self.update_policy.clone(),
+            #[cfg(feature = "gradual-rollout")]
+            canary: self.canary.clone(),
+            frozen: Arc::clone(&self.frozen),
+            appliers: self.appliers.clone(),
+            projections: Arc::clone(&self.projections),
+            projected: self.projected,
+            profile: self.profile.clone(),
+            flight: Arc::clone(&self.flight),
+            #[cfg(feature = "tokio")]
+            throttle: self.throttle.clone(),
+            #[cfg(feature = "tokio")]
+            background: Arc::clone(&self.background),
+            #[cfg(feature = "tokio")]
+            keep_running: self.keep_running.clone(),
+            #[cfg(feature = "testing")]
+            faults: Arc::clone(&self.faults),
         }
     }
 }
@@ -372,7 +3220,7 @@ impl<T> Clone for HotswapConfig<T> {
 mod tests {
     use super::*;
 
-    #[derive(Debug, Clone, PartialEq)]
+    #[derive(Debug, Clone, PartialEq, serde::Deserialize)]
     struct TestConfig {
         value: i32,
     }
@@ -384,6 +3232,487 @@ mod tests {
         assert_eq!(cfg.value, 42);
     }
 
+    #[cfg(feature = "yaml")]
+    #[tokio::test]
+    async fn test_snapshot_round_trip() {
+        #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+        struct Server {
+            host: String,
+            port: u16,
+        }
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("snapshot.yaml");
+
+        let config = HotswapConfig::new(Server {
+            host: "localhost".to_string(),
+            port: 8080,
+        });
+        config.write_snapshot(&path).unwrap();
+
+        let restored = HotswapConfig::<Server>::builder()
+            .with_snapshot_fallback(&path)
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(*restored.get(), *config.get());
+
+        assert!(
+            config
+                .write_snapshot(temp_dir.path().join("x.ini"))
+                .is_err()
+        );
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct AppConfig {
+        port: u16,
+        database: TestConfig,
+    }
+
+    #[tokio::test]
+    async fn test_project() {
+        let config = HotswapConfig::new(AppConfig {
+            port: 8080,
+            database: TestConfig { value: 1 },
+        });
+        let database = config.project(|c: &AppConfig| c.database.clone());
+        let value = database.project(|db: &TestConfig| db.value);
+        assert_eq!(database.get().value, 1);
+        assert_eq!(*value.get(), 1);
+
+        config
+            .update(AppConfig {
+                port: 8080,
+                database: TestConfig { value: 2 },
+            })
+            .await
+            .unwrap();
+        assert_eq!(database.get().value, 2);
+        assert_eq!(*value.get(), 2);
+
+        // Projections are read-only
+        assert!(database.update(TestConfig { value: 3 }).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_load_guard_outlives_swap() {
+        let config = HotswapConfig::new(TestConfig { value: 1 });
+        let guard = config.load();
+        config.update(TestConfig { value: 2 }).await.unwrap();
+
+        // The guard keeps the config it borrowed
+        assert_eq!(guard.value, 1);
+        assert_eq!(guard.into_arc().value, 1);
+        assert_eq!(config.load().value, 2);
+    }
+
+    #[tokio::test]
+    async fn test_generation_counts_swaps() {
+        let config = HotswapConfig::new(AppConfig {
+            port: 8080,
+            database: TestConfig { value: 1 },
+        });
+        let database = config.project(|c: &AppConfig| c.database.clone());
+        let snapshot = config.snapshot();
+        let database_snapshot = database.snapshot();
+        assert_eq!(snapshot.generation(), 0);
+        assert_eq!(snapshot.port, 8080);
+
+        config
+            .update(AppConfig {
+                port: 9090,
+                database: TestConfig { value: 1 },
+            })
+            .await
+            .unwrap();
+        assert_eq!(config.generation(), 1);
+        assert!(snapshot.is_stale());
+        assert!(!config.snapshot().is_stale());
+
+        // Projections count only the swaps that change them
+        assert_eq!(database.generation(), 0);
+        assert!(!database_snapshot.is_stale());
+
+        // Failed updates don't count
+        config.freeze(None);
+        assert!(config.update((*config.get()).clone()).await.is_err());
+        assert_eq!(config.generation(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_reloads_coalesce() {
+        use crate::sources::ConfigSource;
+        use std::collections::HashMap;
+        use std::sync::atomic::AtomicUsize;
+
+        /// Loads a new value every time
+        struct Counting(Arc<AtomicUsize>);
+
+        impl ConfigSource for Counting {
+            fn load(&self) -> Result<HashMap<String, config::Value>> {
+                let loads = self.0.fetch_add(1, Ordering::SeqCst) as i64;
+                Ok(HashMap::from([("value".to_string(), loads.into())]))
+            }
+
+            fn name(&self) -> String {
+                "counting".to_string()
+            }
+
+            fn priority(&self) -> i32 {
+                100
+            }
+        }
+
+        let loads = Arc::new(AtomicUsize::new(0));
+        let config = HotswapConfig::<TestConfig>::builder()
+            .with_source(Counting(Arc::clone(&loads)))
+            // Suspends the reload, so the others arrive while it's in flight
+            .with_async_validation(
+                |_: Arc<TestConfig>| async {
+                    tokio::task::yield_now().await;
+                    Ok(())
+                },
+                std::time::Duration::from_secs(5),
+            )
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(loads.load(Ordering::SeqCst), 1);
+        assert!(!config.reload_in_progress());
+
+        // Clones share reloads too
+        let clone = config.clone();
+        let (first, second, third, in_progress) =
+            tokio::join!(config.reload(), config.reload(), clone.reload(), async {
+                config.reload_in_progress()
+            });
+        first.unwrap();
+        second.unwrap();
+        third.unwrap();
+        assert!(in_progress);
+        assert_eq!(loads.load(Ordering::SeqCst), 2);
+        assert_eq!(config.get().value, 1);
+        assert!(!config.reload_in_progress());
+
+        // Once it finished, the next reload loads again
+        config.reload().await.unwrap();
+        assert_eq!(loads.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_coalesced_reload_errors() {
+        use crate::error::VerificationError;
+        use crate::sources::ConfigSource;
+        use std::collections::HashMap;
+        use std::sync::atomic::{AtomicBool, AtomicUsize};
+
+        /// Loads a new value every time
+        struct Counting(AtomicUsize);
+
+        impl ConfigSource for Counting {
+            fn load(&self) -> Result<HashMap<String, config::Value>> {
+                let loads = self.0.fetch_add(1, Ordering::SeqCst) as i64;
+                Ok(HashMap::from([("value".to_string(), loads.into())]))
+            }
+
+            fn name(&self) -> String {
+                "counting".to_string()
+            }
+
+            fn priority(&self) -> i32 {
+                100
+            }
+        }
+
+        let failing = Arc::new(AtomicBool::new(false));
+        let verify = Arc::clone(&failing);
+        let config = HotswapConfig::<TestConfig>::builder()
+            .with_source(Counting(AtomicUsize::new(0)))
+            // Suspends the reload, so a second one can join it
+            .with_async_validation(
+                move |_: Arc<TestConfig>| {
+                    let failing = verify.load(Ordering::SeqCst);
+                    async move {
+                        tokio::task::yield_now().await;
+                        if failing {
+                            return Err(VerificationError::failed("unreachable"));
+                        }
+                        Ok(())
+                    }
+                },
+                std::time::Duration::from_secs(5),
+            )
+            .build()
+            .await
+            .unwrap();
+        failing.store(true, Ordering::SeqCst);
+
+        // Alone, the leader gets its own error
+        let alone = config.reload().await.unwrap_err();
+        assert!(!matches!(alone, ConfigError::Shared(_)));
+
+        // With a caller joined, the leader still does, and the caller shares it
+        let (lead, joined) = tokio::join!(config.reload(), config.reload());
+        let lead = lead.unwrap_err();
+        assert!(!matches!(lead, ConfigError::Shared(_)));
+        let joined = joined.unwrap_err();
+        assert!(matches!(joined, ConfigError::Shared(_)));
+        assert_eq!(joined.to_string(), lead.to_string());
+        assert_eq!(lead.to_string(), alone.to_string());
+    }
+
+    #[cfg(feature = "file-watch")]
+    #[tokio::test(start_paused = true)]
+    async fn test_shutdown_stops_pending_delivery() {
+        use crate::notify::DeliveryOptions;
+        use std::sync::atomic::AtomicUsize;
+        use std::time::Duration;
+
+        let config = HotswapConfig::new(TestConfig { value: 1 });
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let _handle = config
+            .subscribe_with_delivery(
+                move || {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                },
+                DeliveryOptions::default().with_debounce(Duration::from_millis(100)),
+            )
+            .await;
+
+        // The change waits out the debounce window when the handle shuts down
+        config.update(TestConfig { value: 2 }).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        config.shutdown().await;
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[cfg(feature = "file-watch")]
+    #[tokio::test]
+    async fn test_project_notifies_only_on_change() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let config = HotswapConfig::new(AppConfig {
+            port: 8080,
+            database: TestConfig { value: 1 },
+        });
+        let database = config.project(|c: &AppConfig| c.database.clone());
+
+        let notified = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&notified);
+        let _handle = database
+            .subscribe(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+            })
+            .await;
+
+        // Unrelated change
+        config
+            .update(AppConfig {
+                port: 9090,
+                database: TestConfig { value: 1 },
+            })
+            .await
+            .unwrap();
+        assert_eq!(notified.load(Ordering::SeqCst), 0);
+
+        config
+            .update(AppConfig {
+                port: 9090,
+                database: TestConfig { value: 2 },
+            })
+            .await
+            .unwrap();
+        assert_eq!(notified.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_changes_receives_swaps() {
+        let config = HotswapConfig::new(AppConfig {
+            port: 8080,
+            database: TestConfig { value: 1 },
+        });
+        let database = config.project(|c: &AppConfig| c.database.clone());
+        let mut changes = config.changes();
+        let mut database_changes = database.changes();
+        assert!(!changes.has_changed().unwrap());
+
+        config
+            .update(AppConfig {
+                port: 9090,
+                database: TestConfig { value: 1 },
+            })
+            .await
+            .unwrap();
+        changes.changed().await.unwrap();
+        assert_eq!(changes.borrow_and_update().port, 9090);
+        // The projected value didn't change
+        assert!(!database_changes.has_changed().unwrap());
+
+        config
+            .update(AppConfig {
+                port: 9090,
+                database: TestConfig { value: 2 },
+            })
+            .await
+            .unwrap();
+        database_changes.changed().await.unwrap();
+        assert_eq!(database_changes.borrow_and_update().value, 2);
+    }
+
+    #[test]
+    fn test_dropped_projection_is_detached() {
+        let config = HotswapConfig::new(TestConfig { value: 1 });
+        let projected = config.project(|c: &TestConfig| c.value);
+        drop(projected);
+
+        let new_config = Arc::new(TestConfig { value: 2 });
+        assert!(config.projections.publish(&new_config).is_empty());
+        assert!(config.projections.0.read().unwrap().is_empty());
+    }
+
+    #[cfg(feature = "log-level")]
+    #[tokio::test]
+    async fn test_bind_log_level_follows_swaps() {
+        use tracing::Level;
+        use tracing_subscriber::prelude::*;
+
+        #[derive(Debug, Clone)]
+        struct LogConfig {
+            log_level: String,
+        }
+        let log_config = |level: &str| LogConfig {
+            log_level: level.to_string(),
+        };
+
+        let config = HotswapConfig::new(log_config("info"));
+        let filter = config.bind_log_level(|c| &c.log_level).unwrap();
+        let _guard = tracing::subscriber::set_default(tracing_subscriber::registry().with(filter));
+        assert!(tracing::enabled!(Level::INFO));
+        assert!(!tracing::enabled!(Level::DEBUG));
+
+        config.update(log_config("debug")).await.unwrap();
+        assert!(tracing::enabled!(Level::DEBUG));
+
+        // An invalid level keeps the previous filter
+        config.update(log_config("app=loudest")).await.unwrap();
+        assert!(tracing::enabled!(Level::DEBUG));
+        let rebound = config.bind_log_level::<tracing_subscriber::Registry, _, _>(|c| &c.log_level);
+        assert!(rebound.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_freeze_rejects_updates() {
+        let config = HotswapConfig::new(TestConfig { value: 1 });
+        let clone = config.clone();
+
+        clone.freeze(None);
+        assert!(config.is_frozen());
+        match config.update(TestConfig { value: 2 }).await {
+            Err(e @ ConfigError::Frozen { reason: None }) => {
+                assert_eq!(e.to_string(), "Configuration is frozen")
+            }
+            other => panic!("expected frozen error, got {:?}", other),
+        }
+        assert_eq!(config.get().value, 1);
+
+        // Nothing to catch up on, so unfreezing doesn't need a loader
+        config.unfreeze().await.unwrap();
+        config.update(TestConfig { value: 2 }).await.unwrap();
+        assert_eq!(clone.get().value, 2);
+    }
+
+    #[cfg(all(feature = "file-watch", feature = "yaml"))]
+    #[tokio::test]
+    async fn test_watch_and_unwatch_path() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let base = temp_dir.path().join("base.yaml");
+        let secrets = temp_dir.path().join("secrets.yaml");
+        let broken = temp_dir.path().join("broken.yaml");
+        std::fs::write(&base, "value: 1\n").unwrap();
+        std::fs::write(&secrets, "value: 2\n").unwrap();
+        std::fs::write(&broken, "value: [\n").unwrap();
+
+        let config = HotswapConfig::<TestConfig>::builder()
+            .with_file(&base)
+            .with_file_watch(true)
+            .build()
+            .await
+            .unwrap();
+
+        config.watch_path(&secrets).await.unwrap();
+        assert_eq!(config.get().value, 2);
+        assert!(config.watch_path(&secrets).await.is_err());
+
+        // Changes to the new file reload the config
+        let mut changes = config.changes();
+        std::fs::write(&secrets, "value: 3\n").unwrap();
+        tokio::time::timeout(std::time::Duration::from_secs(5), changes.changed())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(config.get().value, 3);
+
+        config.unwatch_path(&secrets).await.unwrap();
+        assert_eq!(config.get().value, 1);
+        assert!(config.unwatch_path(&secrets).await.is_err());
+
+        // A file that fails to load isn't added
+        assert!(config.watch_path(&broken).await.is_err());
+        config.reload().await.unwrap();
+        assert_eq!(config.get().value, 1);
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_add_and_remove_sources() {
+        use crate::sources::DefaultsSource;
+        use crate::testing::MemorySource;
+        use std::collections::HashMap;
+
+        let config = HotswapConfig::<TestConfig>::builder()
+            .with_source(DefaultsSource::new(&HashMap::from([("value", 1)])))
+            .build()
+            .await
+            .unwrap();
+
+        let discovered = MemorySource::new()
+            .with_value("value", 2)
+            .with_priority(200);
+        config.add_source(discovered.clone()).await.unwrap();
+        assert_eq!(config.get().value, 2);
+
+        // Changes to the added source reload it
+        let mut changes = config.changes();
+        discovered.set("value", 3);
+        changes.changed().await.unwrap();
+        assert_eq!(config.get().value, 3);
+
+        config.remove_source("memory").await.unwrap();
+        assert_eq!(config.get().value, 1);
+        assert!(config.remove_source("memory").await.is_err());
+
+        // Changes to a removed source are ignored
+        discovered.set("value", 4);
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert_eq!(config.get().value, 1);
+
+        // Removing the last source fails and keeps it
+        assert!(config.remove_source("defaults").await.is_err());
+        config.reload().await.unwrap();
+        assert_eq!(config.get().value, 1);
+
+        // A source that doesn't deserialize isn't added
+        let broken = MemorySource::new()
+            .with_value("value", "not a number")
+            .with_priority(200);
+        assert!(config.add_source(broken).await.is_err());
+        config.reload().await.unwrap();
+        assert_eq!(config.get().value, 1);
+    }
+
     #[test]
     fn test_clone() {
         let config = HotswapConfig::new(TestConfig { value: 42 });
@@ -0,0 +1,216 @@
+//! Resources built from configuration and rebuilt when it changes.
+
+use crate::core::HotswapConfig;
+use arc_swap::ArcSwapOption;
+use std::sync::{Arc, Mutex};
+
+/// Type alias for checking whether a swap affects the resource.
+type Dependency<T> = Box<dyn Fn(&T, &T) -> bool + Send + Sync>;
+
+/// Type alias for the hook run on replaced resources.
+type Teardown<R> = Box<dyn Fn(&R) + Send + Sync>;
+
+/// A resource and the configuration it was built from.
+struct Built<T, R> {
+    config: Arc<T>,
+    resource: Arc<R>,
+}
+
+/// A resource (e.g. a connection pool or HTTP client) built from the
+/// configuration, cached, and rebuilt when the configuration changes.
+///
+/// The resource is built on the first [`get`](Self::get). Later calls return
+/// the cached resource until a reload or update swaps in a configuration
+/// that changes its [dependency](Self::with_dependency); the next `get`
+/// then builds a replacement and hands the old one to the
+/// [teardown hook](Self::with_teardown). Callers never see a half-built
+/// resource, and concurrent callers share one rebuild.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use hotswap_config::core::Derived;
+/// use hotswap_config::prelude::*;
+/// use serde::Deserialize;
+///
+/// #[derive(Debug, Deserialize, Clone, PartialEq)]
+/// struct DatabaseConfig {
+///     url: String,
+///     max_connections: u32,
+/// }
+///
+/// #[derive(Debug, Deserialize, Clone)]
+/// struct AppConfig {
+///     port: u16,
+///     database: DatabaseConfig,
+/// }
+///
+/// struct Pool;
+///
+/// impl Pool {
+///     fn connect(_config: &DatabaseConfig) -> Self {
+///         Pool
+///     }
+///
+///     fn close(&self) {}
+/// }
+///
+/// # fn example(config: HotswapConfig<AppConfig>) {
+/// let pool = Derived::new(&config, |c: &AppConfig| Pool::connect(&c.database))
+///     .with_dependency(|c| c.database.clone())
+///     .with_teardown(|old: &Pool| old.close());
+///
+/// // Rebuilt only after a reload changes the database section
+/// let current = pool.get();
+/// # }
+/// ```
+pub struct Derived<T, R> {
+    config: HotswapConfig<T>,
+    build: Box<dyn Fn(&T) -> R + Send + Sync>,
+    dependency: Dependency<T>,
+    teardown: Option<Teardown<R>>,
+    built: ArcSwapOption<Built<T, R>>,
+    /// Held while building, so concurrent callers don't build twice
+    rebuilding: Mutex<()>,
+}
+
+impl<T, R> Derived<T, R> {
+    /// Build resources from `config` with `build`.
+    ///
+    /// By default the resource is rebuilt after every swap; see
+    /// [`with_dependency`](Self::with_dependency).
+    pub fn new<F>(config: &HotswapConfig<T>, build: F) -> Self
+    where
+        F: Fn(&T) -> R + Send + Sync + 'static,
+    {
+        Self {
+            config: config.clone(),
+            build: Box::new(build),
+            dependency: Box::new(|_, _| true),
+            teardown: None,
+            built: ArcSwapOption::empty(),
+            rebuilding: Mutex::new(()),
+        }
+    }
+
+    /// Only rebuild when the part of the configuration selected by `select`
+    /// changes.
+    pub fn with_dependency<K, F>(mut self, select: F) -> Self
+    where
+        K: PartialEq,
+        F: Fn(&T) -> K + Send + Sync + 'static,
+    {
+        self.dependency = Box::new(move |old, new| select(old) != select(new));
+        self
+    }
+
+    /// Call `teardown` with each resource after it has been replaced.
+    ///
+    /// Callers that got the old resource from [`get`](Self::get) may still
+    /// hold it when the hook runs.
+    pub fn with_teardown<F>(mut self, teardown: F) -> Self
+    where
+        F: Fn(&R) + Send + Sync + 'static,
+    {
+        self.teardown = Some(Box::new(teardown));
+        self
+    }
+
+    /// Get the resource for the current configuration, building it first if
+    /// needed.
+    pub fn get(&self) -> Arc<R> {
+        let config = self.config.get();
+        if let Some(built) = self.built.load().as_ref() {
+            if Arc::ptr_eq(&built.config, &config) {
+                return Arc::clone(&built.resource);
+            }
+        }
+
+        let _rebuilding = self.rebuilding.lock().unwrap();
+        let config = self.config.get();
+        let previous = self.built.load_full();
+        if let Some(built) = &previous {
+            if Arc::ptr_eq(&built.config, &config) {
+                return Arc::clone(&built.resource);
+            }
+            if !(self.dependency)(&built.config, &config) {
+                // Unaffected swap; remember it so the fast path applies again
+                let resource = Arc::clone(&built.resource);
+                self.built.store(Some(Arc::new(Built {
+                    config,
+                    resource: Arc::clone(&resource),
+                })));
+                return resource;
+            }
+        }
+
+        let resource = Arc::new((self.build)(&config));
+        self.built.store(Some(Arc::new(Built {
+            config,
+            resource: Arc::clone(&resource),
+        })));
+        if let (Some(teardown), Some(previous)) = (&self.teardown, previous) {
+            teardown(&previous.resource);
+        }
+        resource
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct AppConfig {
+        port: u16,
+        url: String,
+    }
+
+    #[tokio::test]
+    async fn test_rebuilds_on_dependency_change() {
+        let config = HotswapConfig::new(AppConfig {
+            port: 8080,
+            url: "postgres://primary".to_string(),
+        });
+        let builds = Arc::new(AtomicUsize::new(0));
+        let torn_down = Arc::new(Mutex::new(Vec::new()));
+
+        let counter = Arc::clone(&builds);
+        let log = Arc::clone(&torn_down);
+        let pool = Derived::new(&config, move |c: &AppConfig| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            c.url.clone()
+        })
+        .with_dependency(|c| c.url.clone())
+        .with_teardown(move |old: &String| log.lock().unwrap().push(old.clone()));
+
+        // Built lazily, then cached
+        assert_eq!(builds.load(Ordering::SeqCst), 0);
+        assert_eq!(*pool.get(), "postgres://primary");
+        assert!(Arc::ptr_eq(&pool.get(), &pool.get()));
+        assert_eq!(builds.load(Ordering::SeqCst), 1);
+
+        // Swaps that don't touch the dependency keep the resource
+        config
+            .update(AppConfig {
+                port: 9090,
+                url: "postgres://primary".to_string(),
+            })
+            .await
+            .unwrap();
+        pool.get();
+        assert_eq!(builds.load(Ordering::SeqCst), 1);
+
+        config
+            .update(AppConfig {
+                port: 9090,
+                url: "postgres://replica".to_string(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(*pool.get(), "postgres://replica");
+        assert_eq!(builds.load(Ordering::SeqCst), 2);
+        assert_eq!(*torn_down.lock().unwrap(), ["postgres://primary"]);
+    }
+}
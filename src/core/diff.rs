@@ -0,0 +1,273 @@
+//! Structured differences between two configurations.
+
+use crate::error::{ConfigError, Result};
+use crate::secrets::redacted;
+use config::{Value, ValueKind};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// How a configuration key changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    /// The key only exists in the proposed config
+    Added,
+    /// The key only exists in the current config
+    Removed,
+    /// The key exists in both with different values
+    Modified,
+}
+
+/// A single changed key in a [`ConfigDiff`].
+///
+/// Values of [`Secret`](crate::secrets::Secret) and
+/// [`redact`](crate::secrets::redact)-annotated fields are reported as
+/// [`REDACTED`](crate::secrets::REDACTED), so diffs are safe to log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigChange {
+    /// Dotted path of the key, e.g. `server.port`
+    pub path: String,
+    /// How the key changed
+    pub kind: ChangeKind,
+    /// Value in the current config, `None` if added
+    pub old: Option<Value>,
+    /// Value in the proposed config, `None` if removed
+    pub new: Option<Value>,
+}
+
+impl fmt::Display for ConfigChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.old, &self.new) {
+            (Some(old), Some(new)) => write!(f, "~ {}: {} -> {}", self.path, old, new),
+            (None, Some(new)) => write!(f, "+ {}: {}", self.path, new),
+            (Some(old), None) => write!(f, "- {}: {}", self.path, old),
+            (None, None) => write!(f, "  {}", self.path),
+        }
+    }
+}
+
+/// The result of a dry-run reload: the prospective config and what would change.
+///
+/// Returned by [`HotswapConfig::reload_dry_run`](crate::core::HotswapConfig::reload_dry_run).
+#[derive(Debug, Clone)]
+pub struct ConfigDiff<T> {
+    current: Arc<T>,
+    proposed: Arc<T>,
+    changes: Vec<ConfigChange>,
+}
+
+impl<T: Serialize> ConfigDiff<T> {
+    /// Compare two configurations.
+    ///
+    /// Nested structs and maps are compared key by key; arrays are compared as
+    /// a whole. Changes are sorted by path.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either config fails to serialize to a map.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hotswap_config::core::{ChangeKind, ConfigDiff};
+    /// use serde::Serialize;
+    /// use std::sync::Arc;
+    ///
+    /// #[derive(Serialize)]
+    /// struct AppConfig {
+    ///     port: u16,
+    ///     host: String,
+    /// }
+    ///
+    /// let current = AppConfig { port: 8080, host: "localhost".into() };
+    /// let proposed = AppConfig { port: 9090, host: "localhost".into() };
+    ///
+    /// let diff = ConfigDiff::new(Arc::new(current), Arc::new(proposed)).unwrap();
+    /// assert_eq!(diff.changes().len(), 1);
+    /// assert_eq!(diff.changes()[0].path, "port");
+    /// assert_eq!(diff.changes()[0].kind, ChangeKind::Modified);
+    /// assert_eq!(diff.to_string(), "~ port: 8080 -> 9090\n");
+    /// ```
+    pub fn new(current: Arc<T>, proposed: Arc<T>) -> Result<Self> {
+        // Changes are detected on the real values but reported with secrets masked
+        let old = flatten(&*current)?;
+        let new = flatten(&*proposed)?;
+        let old_redacted = redacted(|| flatten(&*current))?;
+        let new_redacted = redacted(|| flatten(&*proposed))?;
+
+        let mut changes: Vec<ConfigChange> = old
+            .iter()
+            .filter_map(|(path, value)| match new.get(path) {
+                None => Some((path, ChangeKind::Removed)),
+                Some(other) if other.kind != value.kind => Some((path, ChangeKind::Modified)),
+                Some(_) => None,
+            })
+            .chain(
+                new.keys()
+                    .filter(|path| !old.contains_key(*path))
+                    .map(|path| (path, ChangeKind::Added)),
+            )
+            .map(|(path, kind)| ConfigChange {
+                path: path.clone(),
+                kind,
+                old: old_redacted.get(path).cloned(),
+                new: new_redacted.get(path).cloned(),
+            })
+            .collect();
+        changes.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(Self {
+            current,
+            proposed,
+            changes,
+        })
+    }
+}
+
+impl<T> ConfigDiff<T> {
+    /// The configuration currently in place.
+    pub fn current(&self) -> &Arc<T> {
+        &self.current
+    }
+
+    /// The configuration a reload would swap in.
+    pub fn proposed(&self) -> &Arc<T> {
+        &self.proposed
+    }
+
+    /// Consume the diff and return the proposed configuration.
+    pub fn into_proposed(self) -> Arc<T> {
+        self.proposed
+    }
+
+    /// The changed keys, sorted by path.
+    pub fn changes(&self) -> &[ConfigChange] {
+        &self.changes
+    }
+
+    /// Check whether the proposed config is identical to the current one.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+/// One change per line, e.g. `~ server.port: 8080 -> 9090`.
+impl<T> fmt::Display for ConfigDiff<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{}", change)?;
+        }
+        Ok(())
+    }
+}
+
+/// Serialize `value` and collect its leaves by dotted path.
+fn flatten<T: Serialize>(value: &T) -> Result<BTreeMap<String, Value>> {
+    let table = config::Config::try_from(value)
+        .and_then(|config| config.try_deserialize::<config::Map<String, Value>>())
+        .map_err(|e| ConfigError::Other(format!("Failed to serialize config for diff: {}", e)))?;
+
+    let mut leaves = BTreeMap::new();
+    collect(&mut leaves, None, table);
+    Ok(leaves)
+}
+
+fn collect(
+    leaves: &mut BTreeMap<String, Value>,
+    prefix: Option<&str>,
+    table: config::Map<String, Value>,
+) {
+    for (key, value) in table {
+        let path = match prefix {
+            Some(prefix) => format!("{}.{}", prefix, key),
+            None => key,
+        };
+        match value.kind {
+            // Empty tables are kept as leaves so they still show up as added/removed
+            ValueKind::Table(table) if !table.is_empty() => collect(leaves, Some(&path), table),
+            _ => {
+                leaves.insert(path, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secrets::{REDACTED, Secret};
+    use std::collections::HashMap;
+
+    #[derive(Serialize)]
+    struct TestConfig {
+        server: Server,
+        password: Secret<String>,
+        tags: Vec<String>,
+        limits: HashMap<String, u32>,
+    }
+
+    #[derive(Serialize)]
+    struct Server {
+        port: u16,
+        host: String,
+    }
+
+    fn config() -> TestConfig {
+        TestConfig {
+            server: Server {
+                port: 8080,
+                host: "localhost".to_string(),
+            },
+            password: Secret::new("hunter2".to_string()),
+            tags: vec!["a".to_string()],
+            limits: HashMap::from([("reads".to_string(), 10)]),
+        }
+    }
+
+    #[test]
+    fn test_identical_configs() {
+        let diff = ConfigDiff::new(Arc::new(config()), Arc::new(config())).unwrap();
+        assert!(diff.is_empty());
+        assert_eq!(diff.to_string(), "");
+    }
+
+    #[test]
+    fn test_nested_changes() {
+        let mut proposed = config();
+        proposed.server.port = 9090;
+        proposed.tags.push("b".to_string());
+        proposed.limits = HashMap::from([("writes".to_string(), 5)]);
+
+        let diff = ConfigDiff::new(Arc::new(config()), Arc::new(proposed)).unwrap();
+        let summary: Vec<(&str, ChangeKind)> = diff
+            .changes()
+            .iter()
+            .map(|c| (c.path.as_str(), c.kind))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("limits.reads", ChangeKind::Removed),
+                ("limits.writes", ChangeKind::Added),
+                ("server.port", ChangeKind::Modified),
+                ("tags", ChangeKind::Modified),
+            ]
+        );
+        assert!(diff.to_string().contains("~ server.port: 8080 -> 9090"));
+    }
+
+    #[test]
+    fn test_secret_changes_are_redacted() {
+        let mut proposed = config();
+        proposed.password = Secret::new("correct horse".to_string());
+
+        let diff = ConfigDiff::new(Arc::new(config()), Arc::new(proposed)).unwrap();
+        assert_eq!(diff.changes().len(), 1);
+        let change = &diff.changes()[0];
+        assert_eq!(change.path, "password");
+        assert_eq!(change.old.as_ref().unwrap().to_string(), REDACTED);
+        assert_eq!(change.new.as_ref().unwrap().to_string(), REDACTED);
+        assert!(!diff.to_string().contains("hunter2"));
+    }
+}
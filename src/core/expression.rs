@@ -0,0 +1,736 @@
+//! Expressions computing config values from other keys.
+//!
+//! With expressions enabled, the contents of a `${...}` are evaluated instead
+//! of looked up, so `worker_threads: "${max(2, num_cpus / 2)}"` computes an
+//! integer. Expressions are sandboxed: they can read keys, the environment,
+//! and a fixed set of functions, have no loops, and are limited in length and
+//! nesting, so evaluating one always terminates.
+//!
+//! Supported syntax:
+//!
+//! - literals: `42`, `1.5`, `"text"` or `'text'`, `true`, `false`
+//! - key paths: `server.port`, `hosts.0`
+//! - variables: `num_cpus`, used when no key has the name
+//! - arithmetic `+ - * / %` (integer division for integers, `+` joins strings),
+//!   comparisons `== != < <= > >=`, logic `&& || !`, and `cond ? a : b`
+//! - functions: `min`, `max`, `clamp`, `abs`, `floor`, `ceil`, `round`, `int`,
+//!   `float`, `string`, `len`, `lower`, `upper`, `trim`, and `env(name)` or
+//!   `env(name, default)`
+
+use crate::error::{ConfigError, Result};
+use config::{Value, ValueKind};
+use std::cmp::Ordering;
+
+/// Longest expression accepted, in bytes.
+const MAX_LENGTH: usize = 1024;
+
+/// Deepest nesting of parentheses, calls, and operators accepted.
+const MAX_DEPTH: usize = 32;
+
+/// Evaluate `expression`, found in the value of `key`.
+///
+/// `lookup` resolves key paths, returning `None` for missing keys.
+///
+/// # Errors
+///
+/// Returns [`ConfigError::InterpolationError`] naming `key` if the expression
+/// is invalid or fails to evaluate, and any error from `lookup`.
+pub(crate) fn evaluate(
+    key: &str,
+    expression: &str,
+    lookup: &mut dyn FnMut(&str) -> Result<Option<Value>>,
+) -> Result<ValueKind> {
+    let fail = |reason: String| ConfigError::InterpolationError {
+        key: key.to_string(),
+        reason: format!("invalid expression '{}': {}", expression, reason),
+    };
+    if expression.len() > MAX_LENGTH {
+        return Err(fail(format!("longer than {} bytes", MAX_LENGTH)));
+    }
+    let tokens = tokenize(expression).map_err(fail)?;
+    let expr = Parser { tokens, pos: 0 }.parse().map_err(fail)?;
+    match (Evaluator { lookup }).eval(&expr) {
+        Ok(kind) => Ok(kind),
+        Err(Failure::Invalid(reason)) => Err(fail(reason)),
+        Err(Failure::Lookup(e)) => Err(e),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Int(i64),
+    Float(f64),
+    Str(String),
+    /// A name or dotted key path
+    Path(String),
+    Op(&'static str),
+}
+
+/// Operators and punctuation, two-character ones first.
+const OPS: &[&str] = &[
+    "==", "!=", "<=", ">=", "&&", "||", "+", "-", "*", "/", "%", "!", "<", ">", "(", ")", ",", "?",
+    ":",
+];
+
+fn tokenize(s: &str) -> std::result::Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = s.trim_start();
+    while let Some(c) = rest.chars().next() {
+        let len = if c.is_ascii_digit() {
+            let len = rest
+                .find(|c: char| !c.is_ascii_digit() && c != '.')
+                .unwrap_or(rest.len());
+            let number = &rest[..len];
+            tokens.push(if number.contains('.') {
+                Token::Float(
+                    number
+                        .parse()
+                        .map_err(|_| format!("invalid number '{}'", number))?,
+                )
+            } else {
+                Token::Int(
+                    number
+                        .parse()
+                        .map_err(|_| format!("integer '{}' is too large", number))?,
+                )
+            });
+            len
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let len = rest
+                .find(|c: char| !c.is_ascii_alphanumeric() && c != '_' && c != '.')
+                .unwrap_or(rest.len());
+            let path = &rest[..len];
+            if path.ends_with('.') || path.contains("..") {
+                return Err(format!("invalid key path '{}'", path));
+            }
+            tokens.push(Token::Path(path.to_string()));
+            len
+        } else if c == '"' || c == '\'' {
+            let (text, len) = string(rest, c)?;
+            tokens.push(Token::Str(text));
+            len
+        } else if let Some(op) = OPS.iter().find(|op| rest.starts_with(**op)) {
+            tokens.push(Token::Op(op));
+            op.len()
+        } else {
+            return Err(format!("unexpected character '{}'", c));
+        };
+        rest = rest[len..].trim_start();
+    }
+    Ok(tokens)
+}
+
+/// Read a string literal opened by `quote`, returning its text and length.
+fn string(s: &str, quote: char) -> std::result::Result<(String, usize), String> {
+    let mut text = String::new();
+    let mut chars = s.char_indices().skip(1);
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some((_, 'n')) => text.push('\n'),
+                Some((_, 't')) => text.push('\t'),
+                Some((_, c)) => text.push(c),
+                None => break,
+            },
+            c if c == quote => return Ok((text, i + 1)),
+            c => text.push(c),
+        }
+    }
+    Err("unterminated string".to_string())
+}
+
+#[derive(Debug)]
+enum Expr {
+    Literal(ValueKind),
+    Path(String),
+    Not(Box<Expr>),
+    Negate(Box<Expr>),
+    Binary(&'static str, Box<Expr>, Box<Expr>),
+    Conditional(Box<Expr>, Box<Expr>, Box<Expr>),
+    Call(String, Vec<Expr>),
+}
+
+/// Binary operators by precedence, lowest first.
+const PRECEDENCE: &[&[&str]] = &[
+    &["||"],
+    &["&&"],
+    &["==", "!=", "<", "<=", ">", ">="],
+    &["+", "-"],
+    &["*", "/", "%"],
+];
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn parse(mut self) -> std::result::Result<Expr, String> {
+        let expr = self.conditional(0)?;
+        match self.tokens.get(self.pos) {
+            None => Ok(expr),
+            Some(token) => Err(format!("unexpected {}", describe(token))),
+        }
+    }
+
+    fn peek_op(&self) -> Option<&'static str> {
+        match self.tokens.get(self.pos) {
+            Some(Token::Op(op)) => Some(op),
+            _ => None,
+        }
+    }
+
+    fn expect(&mut self, op: &str) -> std::result::Result<(), String> {
+        match self.tokens.get(self.pos) {
+            Some(Token::Op(found)) if *found == op => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(token) => Err(format!("expected '{}', found {}", op, describe(token))),
+            None => Err(format!("expected '{}', found end of expression", op)),
+        }
+    }
+
+    fn conditional(&mut self, depth: usize) -> std::result::Result<Expr, String> {
+        if depth > MAX_DEPTH {
+            return Err(format!("nested deeper than {} levels", MAX_DEPTH));
+        }
+        let condition = self.binary(0, depth)?;
+        if self.peek_op() != Some("?") {
+            return Ok(condition);
+        }
+        self.pos += 1;
+        let then = self.conditional(depth + 1)?;
+        self.expect(":")?;
+        let otherwise = self.conditional(depth + 1)?;
+        Ok(Expr::Conditional(
+            Box::new(condition),
+            Box::new(then),
+            Box::new(otherwise),
+        ))
+    }
+
+    fn binary(&mut self, level: usize, depth: usize) -> std::result::Result<Expr, String> {
+        let Some(ops) = PRECEDENCE.get(level) else {
+            return self.unary(depth);
+        };
+        let mut left = self.binary(level + 1, depth)?;
+        while let Some(op) = self.peek_op().filter(|op| ops.contains(op)) {
+            self.pos += 1;
+            let right = self.binary(level + 1, depth)?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self, depth: usize) -> std::result::Result<Expr, String> {
+        if depth > MAX_DEPTH {
+            return Err(format!("nested deeper than {} levels", MAX_DEPTH));
+        }
+        match self.peek_op() {
+            Some("!") => {
+                self.pos += 1;
+                Ok(Expr::Not(Box::new(self.unary(depth + 1)?)))
+            }
+            Some("-") => {
+                self.pos += 1;
+                Ok(Expr::Negate(Box::new(self.unary(depth + 1)?)))
+            }
+            _ => self.primary(depth),
+        }
+    }
+
+    fn primary(&mut self, depth: usize) -> std::result::Result<Expr, String> {
+        let Some(token) = self.tokens.get(self.pos).cloned() else {
+            return Err("unexpected end of expression".to_string());
+        };
+        self.pos += 1;
+        Ok(match token {
+            Token::Int(n) => Expr::Literal(ValueKind::I64(n)),
+            Token::Float(n) => Expr::Literal(ValueKind::Float(n)),
+            Token::Str(s) => Expr::Literal(ValueKind::String(s)),
+            Token::Path(path) if path == "true" => Expr::Literal(ValueKind::Boolean(true)),
+            Token::Path(path) if path == "false" => Expr::Literal(ValueKind::Boolean(false)),
+            Token::Path(name) if self.peek_op() == Some("(") => {
+                self.pos += 1;
+                let mut args = Vec::new();
+                if self.peek_op() != Some(")") {
+                    loop {
+                        args.push(self.conditional(depth + 1)?);
+                        if self.peek_op() != Some(",") {
+                            break;
+                        }
+                        self.pos += 1;
+                    }
+                }
+                self.expect(")")?;
+                Expr::Call(name, args)
+            }
+            Token::Path(path) => Expr::Path(path),
+            Token::Op("(") => {
+                let expr = self.conditional(depth + 1)?;
+                self.expect(")")?;
+                expr
+            }
+            token => return Err(format!("unexpected {}", describe(&token))),
+        })
+    }
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Int(n) => format!("'{}'", n),
+        Token::Float(n) => format!("'{}'", n),
+        Token::Str(s) => format!("string \"{}\"", s),
+        Token::Path(path) => format!("'{}'", path),
+        Token::Op(op) => format!("'{}'", op),
+    }
+}
+
+enum Failure {
+    Invalid(String),
+    /// A referenced key failed to resolve
+    Lookup(ConfigError),
+}
+
+impl From<String> for Failure {
+    fn from(reason: String) -> Self {
+        Failure::Invalid(reason)
+    }
+}
+
+type Eval<T> = std::result::Result<T, Failure>;
+
+/// A number, widened from any integer or float kind.
+#[derive(Debug, Clone, Copy)]
+enum Number {
+    Int(i64),
+    Float(f64),
+}
+
+impl Number {
+    fn float(self) -> f64 {
+        match self {
+            Number::Int(n) => n as f64,
+            Number::Float(n) => n,
+        }
+    }
+
+    fn kind(self) -> ValueKind {
+        match self {
+            Number::Int(n) => ValueKind::I64(n),
+            Number::Float(n) => ValueKind::Float(n),
+        }
+    }
+}
+
+struct Evaluator<'a> {
+    lookup: &'a mut dyn FnMut(&str) -> Result<Option<Value>>,
+}
+
+impl Evaluator<'_> {
+    fn eval(&mut self, expr: &Expr) -> Eval<ValueKind> {
+        match expr {
+            Expr::Literal(kind) => Ok(kind.clone()),
+            Expr::Path(path) => self.path(path),
+            Expr::Not(expr) => Ok(ValueKind::Boolean(!self.boolean(expr)?)),
+            Expr::Negate(expr) => match self.number(expr)? {
+                Number::Int(n) => n
+                    .checked_neg()
+                    .map(ValueKind::I64)
+                    .ok_or_else(|| "integer overflow".to_string().into()),
+                Number::Float(n) => Ok(ValueKind::Float(-n)),
+            },
+            Expr::Binary("&&", left, right) => Ok(ValueKind::Boolean(
+                self.boolean(left)? && self.boolean(right)?,
+            )),
+            Expr::Binary("||", left, right) => Ok(ValueKind::Boolean(
+                self.boolean(left)? || self.boolean(right)?,
+            )),
+            Expr::Binary(op, left, right) => {
+                let (left, right) = (self.eval(left)?, self.eval(right)?);
+                binary(op, left, right)
+            }
+            Expr::Conditional(condition, then, otherwise) => {
+                if self.boolean(condition)? {
+                    self.eval(then)
+                } else {
+                    self.eval(otherwise)
+                }
+            }
+            Expr::Call(name, args) => {
+                let args = args
+                    .iter()
+                    .map(|arg| self.eval(arg))
+                    .collect::<Eval<Vec<_>>>()?;
+                call(name, args)
+            }
+        }
+    }
+
+    fn path(&mut self, path: &str) -> Eval<ValueKind> {
+        if let Some(value) = (self.lookup)(path).map_err(Failure::Lookup)? {
+            return Ok(value.kind);
+        }
+        match path {
+            "num_cpus" => Ok(ValueKind::I64(
+                std::thread::available_parallelism().map_or(1, |n| n.get() as i64),
+            )),
+            _ => Err(format!("unknown key or variable '{}'", path).into()),
+        }
+    }
+
+    fn boolean(&mut self, expr: &Expr) -> Eval<bool> {
+        match self.eval(expr)? {
+            ValueKind::Boolean(b) => Ok(b),
+            other => Err(format!("expected a boolean, found {}", type_name(&other)).into()),
+        }
+    }
+
+    fn number(&mut self, expr: &Expr) -> Eval<Number> {
+        let kind = self.eval(expr)?;
+        Ok(number(&kind)?)
+    }
+}
+
+fn number(kind: &ValueKind) -> std::result::Result<Number, String> {
+    let wide = |n: Option<i64>, f: f64| n.map_or(Number::Float(f), Number::Int);
+    match *kind {
+        ValueKind::I64(n) => Ok(Number::Int(n)),
+        ValueKind::I128(n) => Ok(wide(i64::try_from(n).ok(), n as f64)),
+        ValueKind::U64(n) => Ok(wide(i64::try_from(n).ok(), n as f64)),
+        ValueKind::U128(n) => Ok(wide(i64::try_from(n).ok(), n as f64)),
+        ValueKind::Float(n) => Ok(Number::Float(n)),
+        ref other => Err(format!("expected a number, found {}", type_name(other))),
+    }
+}
+
+fn type_name(kind: &ValueKind) -> &'static str {
+    match kind {
+        ValueKind::Nil => "nil",
+        ValueKind::Boolean(_) => "a boolean",
+        ValueKind::I64(_) | ValueKind::I128(_) | ValueKind::U64(_) | ValueKind::U128(_) => {
+            "an integer"
+        }
+        ValueKind::Float(_) => "a float",
+        ValueKind::String(_) => "a string",
+        ValueKind::Table(_) => "a table",
+        ValueKind::Array(_) => "an array",
+    }
+}
+
+/// Render a scalar as text, for joining strings.
+fn text(kind: ValueKind) -> std::result::Result<String, String> {
+    match kind {
+        ValueKind::String(s) => Ok(s),
+        ValueKind::Table(_) | ValueKind::Array(_) | ValueKind::Nil => {
+            Err(format!("cannot convert {} to a string", type_name(&kind)))
+        }
+        kind => Ok(Value::new(None, kind).to_string()),
+    }
+}
+
+fn binary(op: &str, left: ValueKind, right: ValueKind) -> Eval<ValueKind> {
+    if op == "+" && (matches!(left, ValueKind::String(_)) || matches!(right, ValueKind::String(_)))
+    {
+        return Ok(ValueKind::String(text(left)? + &text(right)?));
+    }
+    if matches!(op, "==" | "!=" | "<" | "<=" | ">" | ">=") {
+        let ordering = compare(&left, &right)?;
+        return Ok(ValueKind::Boolean(match op {
+            "==" => ordering == Some(Ordering::Equal),
+            "!=" => ordering != Some(Ordering::Equal),
+            _ => {
+                let ordering = ordering.ok_or_else(|| {
+                    format!(
+                        "cannot order {} and {}",
+                        type_name(&left),
+                        type_name(&right)
+                    )
+                })?;
+                match op {
+                    "<" => ordering.is_lt(),
+                    "<=" => ordering.is_le(),
+                    ">" => ordering.is_gt(),
+                    _ => ordering.is_ge(),
+                }
+            }
+        }));
+    }
+
+    let (left, right) = (number(&left)?, number(&right)?);
+    let result = match (left, right) {
+        (Number::Int(a), Number::Int(b)) => {
+            if matches!(op, "/" | "%") && b == 0 {
+                return Err("division by zero".to_string().into());
+            }
+            let result = match op {
+                "+" => a.checked_add(b),
+                "-" => a.checked_sub(b),
+                "*" => a.checked_mul(b),
+                "/" => a.checked_div(b),
+                _ => a.checked_rem(b),
+            };
+            Number::Int(result.ok_or_else(|| "integer overflow".to_string())?)
+        }
+        (a, b) => {
+            let (a, b) = (a.float(), b.float());
+            if matches!(op, "/" | "%") && b == 0.0 {
+                return Err("division by zero".to_string().into());
+            }
+            Number::Float(match op {
+                "+" => a + b,
+                "-" => a - b,
+                "*" => a * b,
+                "/" => a / b,
+                _ => a % b,
+            })
+        }
+    };
+    Ok(result.kind())
+}
+
+/// Compare two values; `None` if they're of the same kind but unordered
+/// (booleans).
+fn compare(left: &ValueKind, right: &ValueKind) -> Eval<Option<Ordering>> {
+    if let (Ok(a), Ok(b)) = (number(left), number(right)) {
+        return Ok(match (a, b) {
+            (Number::Int(a), Number::Int(b)) => Some(a.cmp(&b)),
+            (a, b) => a.float().partial_cmp(&b.float()),
+        });
+    }
+    match (left, right) {
+        (ValueKind::String(a), ValueKind::String(b)) => Ok(Some(a.cmp(b))),
+        (ValueKind::Boolean(a), ValueKind::Boolean(b)) => Ok((a == b).then_some(Ordering::Equal)),
+        _ => Err(format!(
+            "cannot compare {} with {}",
+            type_name(left),
+            type_name(right)
+        )
+        .into()),
+    }
+}
+
+fn call(name: &str, args: Vec<ValueKind>) -> Eval<ValueKind> {
+    let arity = |expected: &str| -> Failure {
+        format!(
+            "{}() takes {} arguments, found {}",
+            name,
+            expected,
+            args.len()
+        )
+        .into()
+    };
+    let numbers = || {
+        args.iter()
+            .map(number)
+            .collect::<std::result::Result<Vec<_>, _>>()
+    };
+    match (name, args.as_slice()) {
+        ("min" | "max", [_, ..]) => {
+            let mut numbers = numbers()?.into_iter();
+            let first = numbers.next().unwrap_or(Number::Int(0));
+            let pick_max = name == "max";
+            let result = numbers.fold(first, |best, n| {
+                let ordering = match (best, n) {
+                    (Number::Int(a), Number::Int(b)) => b.cmp(&a),
+                    (a, b) => b.float().total_cmp(&a.float()),
+                };
+                if ordering
+                    == if pick_max {
+                        Ordering::Greater
+                    } else {
+                        Ordering::Less
+                    }
+                {
+                    n
+                } else {
+                    best
+                }
+            });
+            Ok(result.kind())
+        }
+        ("min" | "max", []) => Err(arity("at least 1")),
+        ("clamp", [value, low, high]) => {
+            let value = call("max", vec![value.clone(), low.clone()])?;
+            call("min", vec![value, high.clone()])
+        }
+        ("abs", [value]) => match number(value)? {
+            Number::Int(n) => n
+                .checked_abs()
+                .map(ValueKind::I64)
+                .ok_or_else(|| "integer overflow".to_string().into()),
+            Number::Float(n) => Ok(ValueKind::Float(n.abs())),
+        },
+        ("floor" | "ceil" | "round", [value]) => match number(value)? {
+            Number::Int(n) => Ok(ValueKind::I64(n)),
+            Number::Float(n) => {
+                let n = match name {
+                    "floor" => n.floor(),
+                    "ceil" => n.ceil(),
+                    _ => n.round(),
+                };
+                float_to_int(n)
+            }
+        },
+        ("int", [value]) => match value {
+            ValueKind::String(s) => s
+                .trim()
+                .parse()
+                .map(ValueKind::I64)
+                .map_err(|_| format!("int() cannot parse \"{}\"", s).into()),
+            ValueKind::Boolean(b) => Ok(ValueKind::I64(i64::from(*b))),
+            value => match number(value)? {
+                Number::Int(n) => Ok(ValueKind::I64(n)),
+                Number::Float(n) => float_to_int(n.trunc()),
+            },
+        },
+        ("float", [value]) => match value {
+            ValueKind::String(s) => s
+                .trim()
+                .parse()
+                .map(ValueKind::Float)
+                .map_err(|_| format!("float() cannot parse \"{}\"", s).into()),
+            value => Ok(ValueKind::Float(number(value)?.float())),
+        },
+        ("string", [value]) => Ok(ValueKind::String(text(value.clone())?)),
+        ("len", [value]) => {
+            let len = match value {
+                ValueKind::String(s) => s.chars().count(),
+                ValueKind::Array(items) => items.len(),
+                ValueKind::Table(table) => table.len(),
+                other => return Err(format!("len() of {}", type_name(other)).into()),
+            };
+            Ok(ValueKind::I64(len as i64))
+        }
+        ("lower" | "upper" | "trim", [ValueKind::String(s)]) => Ok(ValueKind::String(match name {
+            "lower" => s.to_lowercase(),
+            "upper" => s.to_uppercase(),
+            _ => s.trim().to_string(),
+        })),
+        ("env", [ValueKind::String(var)]) => std::env::var(var)
+            .map(ValueKind::String)
+            .map_err(|_| format!("environment variable {} is not set", var).into()),
+        ("env", [ValueKind::String(var), default]) => Ok(std::env::var(var)
+            .map(ValueKind::String)
+            .unwrap_or_else(|_| default.clone())),
+        ("abs" | "floor" | "ceil" | "round" | "int" | "float" | "string" | "len", _) => {
+            Err(arity("1"))
+        }
+        ("clamp", _) => Err(arity("3")),
+        ("lower" | "upper" | "trim", _) => Err(format!("{}() takes a string", name).into()),
+        ("env", _) => Err(format!("{}() takes a variable name and optional default", name).into()),
+        _ => Err(format!("unknown function '{}'", name).into()),
+    }
+}
+
+fn float_to_int(n: f64) -> Eval<ValueKind> {
+    if n.is_finite() && n >= i64::MIN as f64 && n < i64::MAX as f64 {
+        Ok(ValueKind::I64(n as i64))
+    } else {
+        Err(format!("{} is out of range for an integer", n).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(expression: &str) -> Result<ValueKind> {
+        evaluate("computed", expression, &mut |path| {
+            Ok(match path {
+                "server.port" => Some(Value::from(8080i64)),
+                "pool.ratio" => Some(Value::from(0.5)),
+                "name" => Some(Value::from("api")),
+                "hosts" => Some(Value::from(vec!["a", "b", "c"])),
+                _ => None,
+            })
+        })
+    }
+
+    #[test]
+    fn test_arithmetic_and_functions() {
+        let cpus = std::thread::available_parallelism().map_or(1, |n| n.get() as i64);
+        assert_eq!(
+            eval("max(2, num_cpus / 2)").unwrap(),
+            ValueKind::I64(std::cmp::max(2, cpus / 2))
+        );
+        assert_eq!(eval("server.port + 1").unwrap(), ValueKind::I64(8081));
+        assert_eq!(eval("7 / 2 * 2 + 7 % 2").unwrap(), ValueKind::I64(7));
+        assert_eq!(eval("-(1 + 2) * 3").unwrap(), ValueKind::I64(-9));
+        assert_eq!(eval("pool.ratio * 10").unwrap(), ValueKind::Float(5.0));
+        assert_eq!(eval("round(pool.ratio * 5)").unwrap(), ValueKind::I64(3));
+        assert_eq!(
+            eval("clamp(server.port, 1, 1024)").unwrap(),
+            ValueKind::I64(1024)
+        );
+        assert_eq!(eval("min(3, 1.5, 2)").unwrap(), ValueKind::Float(1.5));
+        assert_eq!(eval("int('42') + len(hosts)").unwrap(), ValueKind::I64(45));
+        assert_eq!(
+            eval("upper(name) + ':' + server.port").unwrap(),
+            ValueKind::String("API:8080".to_string())
+        );
+    }
+
+    #[test]
+    fn test_logic_and_conditionals() {
+        assert_eq!(
+            eval("server.port > 1024 && name == 'api' ? 'high' : 'low'").unwrap(),
+            ValueKind::String("high".to_string())
+        );
+        assert_eq!(
+            eval("!(1 < 2) || 2 >= 2.0").unwrap(),
+            ValueKind::Boolean(true)
+        );
+        // Branches not taken aren't evaluated
+        assert_eq!(eval("true ? 1 : missing").unwrap(), ValueKind::I64(1));
+        assert_eq!(eval("false && missing").unwrap(), ValueKind::Boolean(false));
+    }
+
+    #[test]
+    fn test_env() {
+        assert_eq!(
+            eval("env('HOTSWAP_EXPRESSION_TEST_UNSET', 'fallback')").unwrap(),
+            ValueKind::String("fallback".to_string())
+        );
+        let error = eval("env('HOTSWAP_EXPRESSION_TEST_UNSET')").unwrap_err();
+        assert!(error.to_string().contains("is not set"), "{}", error);
+    }
+
+    #[test]
+    fn test_errors() {
+        for (expression, reason) in [
+            ("1 / 0", "division by zero"),
+            ("9223372036854775807 + 1", "integer overflow"),
+            ("missing + 1", "unknown key or variable 'missing'"),
+            ("name * 2", "expected a number, found a string"),
+            ("exec('rm')", "unknown function 'exec'"),
+            ("max()", "takes at least 1 arguments"),
+            ("(1 + 2", "expected ')'"),
+            ("1 2", "unexpected '2'"),
+            ("'open", "unterminated string"),
+            ("1 ; 2", "unexpected character ';'"),
+        ] {
+            match eval(expression) {
+                Err(ConfigError::InterpolationError { key, reason: found }) => {
+                    assert_eq!(key, "computed");
+                    assert!(found.contains(reason), "{}: {}", expression, found);
+                }
+                other => panic!("{}: expected an error, got {:?}", expression, other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_limits() {
+        let deep = format!("{}1{}", "(".repeat(100), ")".repeat(100));
+        assert!(
+            eval(&deep)
+                .unwrap_err()
+                .to_string()
+                .contains("nested deeper")
+        );
+        let long = vec!["1"; 600].join("+");
+        assert!(eval(&long).unwrap_err().to_string().contains("longer than"));
+    }
+}
@@ -0,0 +1,312 @@
+//! Single-flight coordination of concurrent reloads.
+
+use crate::metrics::MetricsRecorder;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[cfg(feature = "tokio")]
+use crate::core::ReloadReason;
+#[cfg(feature = "tokio")]
+use crate::error::{ConfigError, Result};
+#[cfg(feature = "tokio")]
+use std::sync::{Arc, Mutex};
+#[cfg(feature = "tokio")]
+use std::time::Duration;
+#[cfg(feature = "tokio")]
+use tokio::sync::watch;
+
+/// Result of a full reload, shared with the callers that joined it.
+#[cfg(feature = "tokio")]
+type Shared = Option<std::result::Result<(), Arc<ConfigError>>>;
+
+/// Reloads in progress on a handle and its clones.
+///
+/// Reloads run one at a time. A full reload requested while another full
+/// reload is running joins it instead of loading again, and gets its result.
+#[derive(Default)]
+pub(crate) struct ReloadFlight {
+    /// Number of reloads running
+    running: AtomicUsize,
+    /// Held by the reload that is running
+    #[cfg(feature = "tokio")]
+    lock: tokio::sync::Mutex<()>,
+    /// Result of the full reload running or waiting for `lock`, once known
+    #[cfg(feature = "tokio")]
+    full: Mutex<Option<watch::Receiver<Shared>>>,
+}
+
+/// Marks a reload as running until dropped.
+pub(crate) struct Running<'a> {
+    flight: &'a ReloadFlight,
+    metrics: Option<&'a dyn MetricsRecorder>,
+    #[cfg(feature = "tokio")]
+    _lock: tokio::sync::MutexGuard<'a, ()>,
+}
+
+impl Drop for Running<'_> {
+    fn drop(&mut self) {
+        if self.flight.running.fetch_sub(1, Ordering::AcqRel) == 1
+            && let Some(metrics) = self.metrics
+        {
+            metrics.reload_in_progress(false);
+        }
+    }
+}
+
+/// What a full reload request should do.
+#[cfg(feature = "tokio")]
+pub(crate) enum Flight {
+    /// Run the reload, then hand the result to [`ReloadFlight::finish`]
+    Lead(watch::Sender<Shared>),
+    /// Wait for the result of the reload already running
+    Join(watch::Receiver<Shared>),
+}
+
+impl ReloadFlight {
+    /// Whether a reload is running.
+    pub(crate) fn in_progress(&self) -> bool {
+        self.running.load(Ordering::Acquire) > 0
+    }
+
+    /// Mark a reload as running, for as long as the returned guard lives.
+    ///
+    /// With the `tokio` feature, waits for any other reload to finish first.
+    pub(crate) async fn start<'a>(
+        &'a self,
+        metrics: Option<&'a dyn MetricsRecorder>,
+    ) -> Running<'a> {
+        #[cfg(feature = "tokio")]
+        let lock = self.lock.lock().await;
+        if self.running.fetch_add(1, Ordering::AcqRel) == 0
+            && let Some(metrics) = metrics
+        {
+            metrics.reload_in_progress(true);
+        }
+        Running {
+            flight: self,
+            metrics,
+            #[cfg(feature = "tokio")]
+            _lock: lock,
+        }
+    }
+
+    /// Join the full reload in progress, or lead a new one.
+    #[cfg(feature = "tokio")]
+    pub(crate) fn join_or_lead(&self) -> Flight {
+        let mut full = self.full.lock().unwrap();
+        // A closed channel is left behind by a leader that was cancelled
+        if let Some(rx) = full.as_ref().filter(|rx| rx.has_changed().is_ok()) {
+            return Flight::Join(rx.clone());
+        }
+        let (tx, rx) = watch::channel(None);
+        *full = Some(rx);
+        Flight::Lead(tx)
+    }
+
+    /// Publish the result of a full reload to the callers that joined it.
+    ///
+    /// The leader gets its own error back; callers that joined get it as
+    /// [`ConfigError::Shared`], whether or not anyone joined in time.
+    #[cfg(feature = "tokio")]
+    pub(crate) fn finish(&self, tx: watch::Sender<Shared>, result: Result<()>) -> Result<()> {
+        // Later requests start a new reload
+        self.full.lock().unwrap().take();
+        let shared = match &result {
+            Ok(()) => Ok(()),
+            Err(e) => Err(Arc::new(e.duplicate())),
+        };
+        tx.send_replace(Some(shared));
+        result
+    }
+
+    /// Wait for the result of a full reload joined with
+    /// [`join_or_lead`](Self::join_or_lead); `None` if its leader was
+    /// cancelled.
+    #[cfg(feature = "tokio")]
+    pub(crate) async fn wait(mut rx: watch::Receiver<Shared>) -> Option<Result<()>> {
+        let result = rx.wait_for(Option::is_some).await.ok()?;
+        result.clone().map(|r| r.map_err(ConfigError::Shared))
+    }
+}
+
+/// Spaces automatic reloads at least a minimum interval apart.
+///
+/// Triggers that arrive while a reload waits for its slot are folded into
+/// it, so a burst of changes costs one reload rather than one per change.
+#[cfg(feature = "tokio")]
+pub(crate) struct ReloadThrottle {
+    min_interval: Duration,
+    /// When the next automatic reload may start
+    next: tokio::sync::Mutex<Option<tokio::time::Instant>>,
+    /// The reload waiting for its slot, if any
+    pending: Mutex<Option<Slot>>,
+}
+
+/// An automatic reload that may start.
+#[cfg(feature = "tokio")]
+#[derive(Debug)]
+pub(crate) struct Slot {
+    /// Why to reload, with the reasons of any triggers folded in
+    pub(crate) reason: ReloadReason,
+    /// Whether other triggers were folded in, so a reload scoped to the
+    /// first one's source no longer covers them all
+    pub(crate) joined: bool,
+    /// Result of the reload, for the triggers folded in
+    done: watch::Sender<Shared>,
+}
+
+#[cfg(feature = "tokio")]
+impl Slot {
+    /// A slot for `reason` alone.
+    pub(crate) fn new(reason: ReloadReason) -> Self {
+        Self {
+            reason,
+            joined: false,
+            done: watch::channel(None).0,
+        }
+    }
+
+    /// Publish the result of the reload to the triggers folded into it.
+    pub(crate) fn finish(&self, result: &Result<()>) {
+        let shared = match result {
+            Ok(()) => Ok(()),
+            Err(e) => Err(Arc::new(e.duplicate())),
+        };
+        self.done.send_replace(Some(shared));
+    }
+}
+
+/// What an automatic reload should do once throttled.
+#[cfg(feature = "tokio")]
+pub(crate) enum Turn {
+    /// Run the reload, then hand the result to [`Slot::finish`]
+    Lead(Slot),
+    /// Wait for the result of the reload it was folded into, with
+    /// [`ReloadFlight::wait`]
+    Join(watch::Receiver<Shared>),
+}
+
+/// Clears the pending reload if its waiter is cancelled.
+#[cfg(feature = "tokio")]
+struct Waiting<'a>(&'a Mutex<Option<Slot>>);
+
+#[cfg(feature = "tokio")]
+impl Drop for Waiting<'_> {
+    fn drop(&mut self) {
+        self.0.lock().unwrap().take();
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl Waiting<'_> {
+    /// Take the pending reload, to start it.
+    fn start(self) -> Slot {
+        let slot = self.0.lock().unwrap().take();
+        std::mem::forget(self);
+        slot.expect("only the waiter takes its pending reload")
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl ReloadThrottle {
+    pub(crate) fn new(min_interval: Duration) -> Self {
+        Self {
+            min_interval,
+            next: tokio::sync::Mutex::new(None),
+            pending: Mutex::new(None),
+        }
+    }
+
+    /// Wait until an automatic reload for `reason` may start, and claim the
+    /// slot.
+    ///
+    /// Reloads start at least `min_interval` apart. If a reload was already
+    /// waiting, folds `reason` into it and returns right away, so the caller
+    /// can wait for its result instead.
+    pub(crate) async fn wait(&self, reason: ReloadReason) -> Turn {
+        {
+            let mut pending = self.pending.lock().unwrap();
+            if let Some(slot) = pending.as_mut() {
+                slot.reason =
+                    std::mem::replace(&mut slot.reason, ReloadReason::Manual).fold(reason);
+                slot.joined = true;
+                return Turn::Join(slot.done.subscribe());
+            }
+            *pending = Some(Slot::new(reason));
+        }
+        let waiting = Waiting(&self.pending);
+
+        let mut next = self.next.lock().await;
+        if let Some(at) = *next {
+            tokio::time::sleep_until(at).await;
+        }
+        *next = Some(tokio::time::Instant::now() + self.min_interval);
+        // Triggers from here on wait for the next slot
+        Turn::Lead(waiting.start())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_throttle_coalesces_bursts() {
+        let throttle = Arc::new(ReloadThrottle::new(Duration::from_secs(10)));
+        let start = tokio::time::Instant::now();
+
+        // Nothing ran yet, so the first reload starts right away
+        let Turn::Lead(first) = throttle.wait(ReloadReason::Schedule).await else {
+            panic!("the first reload should lead");
+        };
+        assert_eq!(first.reason, ReloadReason::Schedule);
+        assert!(!first.joined);
+
+        // A burst right after it waits out the interval as one reload
+        let waits = (0..5).map(|i| {
+            let throttle = Arc::clone(&throttle);
+            tokio::spawn(async move {
+                let reason = ReloadReason::FileChanged(vec![format!("{}.yaml", i).into()]);
+                match throttle.wait(reason).await {
+                    Turn::Lead(slot) => Ok((slot, start.elapsed())),
+                    Turn::Join(rx) => Err(rx),
+                }
+            })
+        });
+        let (mut slots, mut joined) = (Vec::new(), Vec::new());
+        for wait in waits.collect::<Vec<_>>() {
+            match wait.await.unwrap() {
+                Ok(slot) => slots.push(slot),
+                Err(rx) => joined.push(rx),
+            }
+        }
+        assert_eq!((slots.len(), joined.len()), (1, 4));
+        let (slot, elapsed) = slots.remove(0);
+        assert_eq!(elapsed, Duration::from_secs(10));
+        assert!(slot.joined);
+        let ReloadReason::FileChanged(paths) = &slot.reason else {
+            panic!("expected a file change, got {}", slot.reason);
+        };
+        assert_eq!(paths.len(), 5);
+
+        // The triggers folded in get the reload's result
+        slot.finish(&Err(ConfigError::Other("reload failed".to_string())));
+        for rx in joined {
+            let result = ReloadFlight::wait(rx).await.unwrap();
+            assert!(matches!(result, Err(ConfigError::Shared(_))));
+        }
+
+        // A cancelled waiter doesn't swallow later triggers
+        let cancelled = tokio::spawn({
+            let throttle = Arc::clone(&throttle);
+            async move { throttle.wait(ReloadReason::Schedule).await }
+        });
+        tokio::task::yield_now().await;
+        cancelled.abort();
+        let _ = cancelled.await;
+        assert!(matches!(
+            throttle.wait(ReloadReason::Admin).await,
+            Turn::Lead(_)
+        ));
+    }
+}
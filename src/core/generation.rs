@@ -0,0 +1,98 @@
+//! Configuration snapshots tagged with the generation they were read at.
+
+use std::fmt;
+use std::ops::Deref;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// A configuration read from a handle, with the generation it was read at.
+///
+/// Returned by [`HotswapConfig::snapshot`](crate::core::HotswapConfig::snapshot).
+/// Dereferences to the configuration. A long-running operation can hold a
+/// snapshot and check [`is_stale`](Self::is_stale) between steps, a single
+/// atomic load, to find out whether the configuration was swapped since it
+/// started.
+///
+/// # Examples
+///
+/// ```rust
+/// use hotswap_config::prelude::*;
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<()> {
+/// let config = HotswapConfig::new(8080);
+/// let snapshot = config.snapshot();
+/// assert_eq!(*snapshot, 8080);
+/// assert!(!snapshot.is_stale());
+///
+/// config.update(9090).await?;
+/// assert!(snapshot.is_stale());
+/// assert_eq!(config.snapshot().generation(), snapshot.generation() + 1);
+/// # Ok(())
+/// # }
+/// ```
+pub struct Snapshot<T> {
+    config: Arc<T>,
+    generation: u64,
+    /// The handle's generation counter
+    latest: Arc<AtomicU64>,
+}
+
+impl<T> Snapshot<T> {
+    /// Create a snapshot of `config`, read at `generation` of `latest`.
+    pub(crate) fn new(config: Arc<T>, generation: u64, latest: Arc<AtomicU64>) -> Self {
+        Self {
+            config,
+            generation,
+            latest,
+        }
+    }
+
+    /// The generation the configuration was read at.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Whether the handle has swapped in a newer configuration since this
+    /// snapshot was taken.
+    pub fn is_stale(&self) -> bool {
+        self.latest.load(Ordering::Acquire) != self.generation
+    }
+
+    /// The configuration.
+    pub fn config(&self) -> &Arc<T> {
+        &self.config
+    }
+
+    /// Unwrap the configuration.
+    pub fn into_inner(self) -> Arc<T> {
+        self.config
+    }
+}
+
+impl<T> Deref for Snapshot<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.config
+    }
+}
+
+impl<T> Clone for Snapshot<T> {
+    fn clone(&self) -> Self {
+        Self {
+            config: Arc::clone(&self.config),
+            generation: self.generation,
+            latest: Arc::clone(&self.latest),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Snapshot<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Snapshot")
+            .field("generation", &self.generation)
+            .field("config", &self.config)
+            .finish()
+    }
+}
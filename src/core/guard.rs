@@ -0,0 +1,41 @@
+//! Borrowed reads of the current configuration.
+
+use arc_swap::Guard;
+use std::fmt;
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// A short-lived borrow of the current configuration.
+///
+/// Returned by [`HotswapConfig::load`](crate::core::HotswapConfig::load).
+/// Reading through a guard skips the reference count increment of
+/// [`get`](crate::core::HotswapConfig::get), but each thread can only hold a
+/// few guards cheaply; hold one for a single request or loop iteration, and
+/// use `get` (or [`into_arc`](Self::into_arc)) for anything kept longer.
+pub struct ConfigGuard<T>(Guard<Arc<T>>);
+
+impl<T> ConfigGuard<T> {
+    /// Wrap an `arc_swap` guard.
+    pub(crate) fn new(guard: Guard<Arc<T>>) -> Self {
+        Self(guard)
+    }
+
+    /// Upgrade to an owned `Arc<T>` that can be held indefinitely.
+    pub fn into_arc(self) -> Arc<T> {
+        Guard::into_inner(self.0)
+    }
+}
+
+impl<T> Deref for ConfigGuard<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ConfigGuard<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
@@ -0,0 +1,382 @@
+//! Cross-key interpolation of merged configuration values.
+//!
+//! String values may reference other keys with `${path.to.key}`. References are
+//! resolved against the fully merged tree, so a value from a higher priority
+//! source is seen by every key that references it. `$${` produces a literal
+//! `${`.
+//!
+//! A string consisting of a single reference takes the referenced value as-is,
+//! keeping its type (e.g. `port: "${server.port}"` stays an integer). References
+//! embedded in longer strings must point at scalar values.
+//!
+//! With the `expressions` feature, [`evaluate`] resolves the contents of each
+//! `${...}` as an [expression](super::expression) instead of a key path.
+
+use crate::error::{ConfigError, Result};
+use config::{Map, Value, ValueKind};
+use std::collections::HashMap;
+
+/// Resolve all `${...}` references in a merged configuration table.
+///
+/// # Errors
+///
+/// Returns [`ConfigError::InterpolationError`] naming the offending key if a
+/// reference is unterminated, points at a missing key, points at a non-scalar
+/// value inside a longer string, or forms a cycle.
+pub(crate) fn interpolate(root: &Map<String, Value>) -> Result<Map<String, Value>> {
+    Resolver {
+        root,
+        resolved: HashMap::new(),
+        stack: Vec::new(),
+        #[cfg(feature = "expressions")]
+        expressions: false,
+    }
+    .resolve_all()
+}
+
+/// Resolve all `${...}` expressions in a merged configuration table.
+///
+/// # Errors
+///
+/// Returns [`ConfigError::InterpolationError`] naming the offending key if an
+/// expression is invalid or fails to evaluate, or for any of the reasons
+/// [`interpolate`] does.
+#[cfg(feature = "expressions")]
+pub(crate) fn evaluate(root: &Map<String, Value>) -> Result<Map<String, Value>> {
+    Resolver {
+        root,
+        resolved: HashMap::new(),
+        stack: Vec::new(),
+        expressions: true,
+    }
+    .resolve_all()
+}
+
+/// A piece of a string value: literal text or a reference to another key.
+enum Segment<'a> {
+    Literal(String),
+    Reference(&'a str),
+}
+
+struct Resolver<'a> {
+    root: &'a Map<String, Value>,
+    /// Fully resolved string values, keyed by path.
+    resolved: HashMap<String, Value>,
+    /// Paths currently being resolved, for cycle detection.
+    stack: Vec<String>,
+    /// Evaluate `${...}` as expressions rather than key paths.
+    #[cfg(feature = "expressions")]
+    expressions: bool,
+}
+
+impl Resolver<'_> {
+    fn resolve_all(mut self) -> Result<Map<String, Value>> {
+        let root = self.root;
+        root.iter()
+            .map(|(key, value)| Ok((key.clone(), self.resolve(key, value)?)))
+            .collect()
+    }
+
+    fn resolve(&mut self, path: &str, value: &Value) -> Result<Value> {
+        let origin = value.origin().map(str::to_string);
+        let kind = match &value.kind {
+            ValueKind::String(s) => return self.resolve_string(path, s, origin),
+            ValueKind::Table(table) => ValueKind::Table(
+                table
+                    .iter()
+                    .map(|(key, child)| {
+                        Ok((
+                            key.clone(),
+                            self.resolve(&format!("{}.{}", path, key), child)?,
+                        ))
+                    })
+                    .collect::<Result<_>>()?,
+            ),
+            ValueKind::Array(items) => ValueKind::Array(
+                items
+                    .iter()
+                    .enumerate()
+                    .map(|(index, item)| self.resolve(&format!("{}.{}", path, index), item))
+                    .collect::<Result<_>>()?,
+            ),
+            _ => return Ok(value.clone()),
+        };
+
+        Ok(Value::new(origin.as_ref(), kind))
+    }
+
+    fn resolve_string(&mut self, path: &str, s: &str, origin: Option<String>) -> Result<Value> {
+        if let Some(value) = self.resolved.get(path) {
+            return Ok(value.clone());
+        }
+
+        if let Some(start) = self.stack.iter().position(|p| p == path) {
+            let mut chain = self.stack[start..].to_vec();
+            chain.push(path.to_string());
+            return Err(error(
+                path,
+                format!("reference cycle detected: {}", chain.join(" -> ")),
+            ));
+        }
+
+        let segments = parse(path, s)?;
+
+        self.stack.push(path.to_string());
+        let result = self.render(path, &segments, origin);
+        self.stack.pop();
+
+        let value = result?;
+        self.resolved.insert(path.to_string(), value.clone());
+        Ok(value)
+    }
+
+    fn render(
+        &mut self,
+        path: &str,
+        segments: &[Segment<'_>],
+        origin: Option<String>,
+    ) -> Result<Value> {
+        // A lone reference keeps the referenced value's type
+        if let [Segment::Reference(target)] = segments {
+            return self.expand(path, target, origin);
+        }
+
+        let mut out = String::new();
+        for segment in segments {
+            match segment {
+                Segment::Literal(text) => out.push_str(text),
+                Segment::Reference(target) => {
+                    let value = self.expand(path, target, None)?;
+                    match value.kind {
+                        ValueKind::Table(_) | ValueKind::Array(_) | ValueKind::Nil => {
+                            return Err(error(
+                                path,
+                                format!("reference '${{{}}}' is not a scalar value", target),
+                            ));
+                        }
+                        _ => out.push_str(&value.to_string()),
+                    }
+                }
+            }
+        }
+
+        Ok(Value::new(origin.as_ref(), ValueKind::String(out)))
+    }
+
+    /// Resolve the contents of a `${...}` in the value at `path`.
+    #[cfg_attr(not(feature = "expressions"), allow(unused_variables))]
+    fn expand(&mut self, path: &str, target: &str, origin: Option<String>) -> Result<Value> {
+        #[cfg(feature = "expressions")]
+        if self.expressions {
+            let root = self.root;
+            let kind = super::expression::evaluate(path, target, &mut |key| {
+                lookup(root, key)
+                    .map(|value| self.resolve(key, value))
+                    .transpose()
+            })?;
+            return Ok(Value::new(origin.as_ref(), kind));
+        }
+        self.reference(path, target)
+    }
+
+    fn reference(&mut self, path: &str, target: &str) -> Result<Value> {
+        let value = lookup(self.root, target)
+            .ok_or_else(|| error(path, format!("unresolved reference '${{{}}}'", target)))?;
+        self.resolve(target, value)
+    }
+}
+
+/// Split a string into literal text and references.
+fn parse<'a>(path: &str, s: &'a str) -> Result<Vec<Segment<'a>>> {
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    let mut rest = s;
+
+    while let Some(pos) = rest.find('$') {
+        literal.push_str(&rest[..pos]);
+        let tail = &rest[pos..];
+
+        if let Some(after) = tail.strip_prefix("$${") {
+            literal.push_str("${");
+            rest = after;
+        } else if let Some(after) = tail.strip_prefix("${") {
+            let end = after
+                .find('}')
+                .ok_or_else(|| error(path, format!("unterminated reference in '{}'", s)))?;
+            let target = after[..end].trim();
+            if target.is_empty() {
+                return Err(error(path, format!("empty reference in '{}'", s)));
+            }
+
+            if !literal.is_empty() {
+                segments.push(Segment::Literal(std::mem::take(&mut literal)));
+            }
+            segments.push(Segment::Reference(target));
+            rest = &after[end + 1..];
+        } else {
+            literal.push('$');
+            rest = &tail[1..];
+        }
+    }
+
+    literal.push_str(rest);
+    if !literal.is_empty() || segments.is_empty() {
+        segments.push(Segment::Literal(literal));
+    }
+
+    Ok(segments)
+}
+
+/// Look up a dotted path; numeric segments index into arrays.
+fn lookup<'a>(root: &'a Map<String, Value>, path: &str) -> Option<&'a Value> {
+    let mut parts = path.split('.');
+    let mut current = root.get(parts.next()?)?;
+
+    for part in parts {
+        current = match &current.kind {
+            ValueKind::Table(table) => table.get(part)?,
+            ValueKind::Array(items) => items.get(part.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+
+    Some(current)
+}
+
+fn error(key: &str, reason: String) -> ConfigError {
+    ConfigError::InterpolationError {
+        key: key.to_string(),
+        reason,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(entries: Vec<(&str, Value)>) -> Value {
+        let map: Map<String, Value> = entries
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect();
+        Value::from(map)
+    }
+
+    fn root(entries: Vec<(&str, Value)>) -> Map<String, Value> {
+        table(entries).into_table().unwrap()
+    }
+
+    fn get(map: &Map<String, Value>, path: &str) -> Value {
+        lookup(map, path).unwrap().clone()
+    }
+
+    #[test]
+    fn test_embedded_references() {
+        let map = interpolate(&root(vec![
+            (
+                "server",
+                table(vec![
+                    ("host", Value::from("example.com")),
+                    ("port", Value::from(8443i64)),
+                ]),
+            ),
+            (
+                "base_url",
+                Value::from("https://${server.host}:${server.port}/api"),
+            ),
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            get(&map, "base_url").into_string().unwrap(),
+            "https://example.com:8443/api"
+        );
+    }
+
+    #[test]
+    fn test_whole_reference_keeps_type() {
+        let map = interpolate(&root(vec![
+            ("port", Value::from(8080i64)),
+            ("admin", table(vec![("port", Value::from("${port}"))])),
+        ]))
+        .unwrap();
+
+        assert_eq!(get(&map, "admin.port").kind, ValueKind::I64(8080));
+    }
+
+    #[test]
+    fn test_chained_and_array_references() {
+        let map = interpolate(&root(vec![
+            ("hosts", Value::from(vec!["a.local", "b.local"])),
+            ("primary", Value::from("${hosts.0}")),
+            ("url", Value::from("http://${primary}")),
+        ]))
+        .unwrap();
+
+        assert_eq!(get(&map, "url").into_string().unwrap(), "http://a.local");
+    }
+
+    #[test]
+    fn test_escape() {
+        let map = interpolate(&root(vec![("template", Value::from("$${name} costs $5"))])).unwrap();
+        assert_eq!(
+            get(&map, "template").into_string().unwrap(),
+            "${name} costs $5"
+        );
+    }
+
+    #[test]
+    fn test_cycle_detection() {
+        let result = interpolate(&root(vec![
+            ("a", Value::from("${b}")),
+            ("b", Value::from("x-${a}")),
+        ]));
+
+        match result {
+            Err(ConfigError::InterpolationError { key, reason }) => {
+                // Detection starts from whichever key is visited first
+                let other = if key == "a" { "b" } else { "a" };
+                let chain = format!("{} -> {} -> {}", key, other, key);
+                assert!(reason.contains(&chain), "{}", reason);
+            }
+            other => panic!("expected interpolation error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unresolved_reference() {
+        let result = interpolate(&root(vec![(
+            "db",
+            table(vec![("url", Value::from("${db.hostt}/app"))]),
+        )]));
+
+        match result {
+            Err(ConfigError::InterpolationError { key, reason }) => {
+                assert_eq!(key, "db.url");
+                assert!(reason.contains("${db.hostt}"));
+            }
+            other => panic!("expected interpolation error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_non_scalar_in_string() {
+        let result = interpolate(&root(vec![
+            ("server", table(vec![("port", Value::from(80i64))])),
+            ("url", Value::from("http://${server}")),
+        ]));
+        assert!(matches!(
+            result,
+            Err(ConfigError::InterpolationError { .. })
+        ));
+    }
+
+    #[test]
+    fn test_unterminated_reference() {
+        let result = interpolate(&root(vec![("url", Value::from("http://${host"))]));
+        assert!(matches!(
+            result,
+            Err(ConfigError::InterpolationError { .. })
+        ));
+    }
+}
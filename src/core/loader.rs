@@ -1,28 +1,361 @@
 //! Configuration loader that merges multiple sources.
 
-use crate::error::{ConfigError, Result};
-use crate::sources::ConfigSource;
+use crate::core::provenance::lookup;
+use crate::core::{AppliedMigration, Migrations, Provenance};
+use crate::error::{ConfigError, Result, ValidationError};
+use crate::sources::{ConfigSource, FailurePolicy, SourceLimits};
 use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+
+/// Values loaded from a single source.
+type SourceValues = HashMap<String, config::Value>;
+
+/// Validates one section of the merged configuration.
+pub(crate) type SectionValidator =
+    Arc<dyn Fn(&config::Config) -> std::result::Result<(), ValidationError> + Send + Sync>;
+
+/// Rewrites the merged tree before it is checked and deserialized.
+pub(crate) type PreLoadHook = Arc<dyn Fn(&mut SourceValues) -> Result<()> + Send + Sync>;
+
+/// A loaded configuration, before validation by the handle's validator.
+pub(crate) struct Loaded<T> {
+    pub(crate) config: T,
+    /// Fingerprint of the merged sources; equal fingerprints mean the sources
+    /// produced the same tree, so a reload can be skipped
+    pub(crate) fingerprint: u64,
+    /// Version of the merged sources, see [`ConfigLoader::version`]
+    pub(crate) version: String,
+    /// Failures reported by section validators
+    pub(crate) section_errors: Vec<ValidationError>,
+    /// Which source set each key
+    pub(crate) provenance: Provenance,
+    /// Sources that failed and were skipped or served from cache
+    pub(crate) skipped: Vec<SkippedSource>,
+    /// Errors deferred by [`ErrorMode::CollectAll`], reported together with
+    /// validation failures
+    pub(crate) errors: Vec<ConfigError>,
+    /// Immutable keys the sources changed, held at their boot-time values
+    /// under [`ImmutableKeys::RestartRequired`]
+    pub(crate) restart_required: Vec<String>,
+    /// Migrations applied to the merged sources, in order
+    pub(crate) migrations: Vec<AppliedMigration>,
+    /// When the merged sources take effect, if a source set a time
+    #[cfg(feature = "tokio")]
+    pub(crate) effective_at: Option<SystemTime>,
+}
+
+/// A source of the loader.
+struct Slot {
+    /// `None` once removed; slots aren't reused, so indices stay stable
+    source: Option<Box<dyn ConfigSource>>,
+    /// Whether the source is an overlay
+    overlay: bool,
+}
+
+/// The merged values of every source.
+struct Merged {
+    values: config::Map<String, config::Value>,
+    provenance: Provenance,
+    skipped: Vec<SkippedSource>,
+}
+
+/// A source that failed to load and was skipped, or served from its last
+/// loaded values, according to its [`FailurePolicy`].
+///
+/// Reported by [`HotswapConfig::skipped_sources`](crate::core::HotswapConfig::skipped_sources).
+#[derive(Debug, Clone)]
+pub struct SkippedSource {
+    /// Name of the source
+    pub name: String,
+    /// The error the source failed with
+    pub error: Arc<ConfigError>,
+    /// Whether the source's last loaded values were used in its place
+    pub served_from_cache: bool,
+}
+
+/// What to do with keys in the merged configuration that the config type
+/// doesn't consume.
+///
+/// Unknown keys are usually typos or settings left over from an older
+/// version, and are otherwise silently ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnknownKeys {
+    /// Ignore unknown keys
+    #[default]
+    Allow,
+    /// Log a warning naming each unknown key
+    Warn,
+    /// Fail the load with [`ConfigError::UnknownKeys`]
+    Deny,
+}
+
+/// What a reload does when it changes a key declared immutable with
+/// [`HotswapConfigBuilder::with_immutable_paths`](crate::core::HotswapConfigBuilder::with_immutable_paths).
+///
+/// Immutable keys are compared against their values at the first load.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ImmutableKeys {
+    /// Reject the reload with a validation error on each changed key
+    #[default]
+    Reject,
+    /// Apply the rest of the reload, keep the changed keys at their boot-time
+    /// values, and report that a restart is required to apply them
+    RestartRequired,
+}
+
+/// Whether a load stops at the first error or reports every error it finds.
+///
+/// Set with
+/// [`HotswapConfigBuilder::with_error_mode`](crate::core::HotswapConfigBuilder::with_error_mode).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ErrorMode {
+    /// Fail with the first source, schema, or key error
+    #[default]
+    FailFast,
+    /// Load every source and run every check, failing with
+    /// [`ConfigError::Multiple`] if more than one failed
+    CollectAll,
+}
+
+/// Which sources a load re-reads; the rest are merged from their last values.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Refresh<'a> {
+    /// Re-read every source
+    All,
+    /// Re-read sources that read one of the changed files
+    #[cfg_attr(not(feature = "file-watch"), allow(dead_code))]
+    Changed(&'a [PathBuf]),
+    /// Re-read the overlay at this index among overlays, or all overlays
+    Overlays(Option<usize>),
+    /// Re-read the source at this index, which reported a change
+    #[cfg_attr(not(feature = "tokio"), allow(dead_code))]
+    Source(usize),
+    /// Re-read no source, only merge them again, e.g. after one was removed
+    Merge,
+}
+
+#[cfg(feature = "json-schema")]
+use crate::core::ConfigSchema;
+#[cfg(feature = "encryption")]
+use crate::encryption::{Decryptor, KeyProvider};
+#[cfg(feature = "signing")]
+use crate::signing::SignatureVerifier;
+#[cfg(feature = "tokio")]
+use std::time::SystemTime;
 
 /// Loads and merges configuration from multiple sources.
 ///
 /// The loader handles precedence by sorting sources by priority and merging them
-/// in order (lower priority first, higher priority sources override).
+/// in order (lower priority first, higher priority sources override). Overlay
+/// sources are merged after all others, and can be refreshed on their own.
 pub struct ConfigLoader {
-    sources: Vec<Box<dyn ConfigSource>>,
+    /// Sources in the order they were added, including any added or removed
+    /// at runtime
+    sources: RwLock<Vec<Slot>>,
+    /// Last loaded values of each source, indexed like `sources`
+    cache: Mutex<Vec<Option<SourceValues>>>,
+    interpolate: bool,
+    #[cfg(feature = "expressions")]
+    expressions: bool,
+    unknown_keys: UnknownKeys,
+    error_mode: ErrorMode,
+    /// Keys that can't change after the first load
+    immutable_paths: Vec<String>,
+    immutable_keys: ImmutableKeys,
+    /// Values of `immutable_paths` at the first load
+    boot_values: Mutex<Option<Vec<Option<config::Value>>>>,
+    #[cfg(feature = "json-schema")]
+    schema: Option<ConfigSchema>,
+    section_validators: Vec<SectionValidator>,
+    migrations: Migrations,
+    pre_load_hooks: Vec<PreLoadHook>,
+    limits: SourceLimits,
+    #[cfg(feature = "signing")]
+    verifier: Option<SignatureVerifier>,
+    #[cfg(feature = "encryption")]
+    key_provider: Option<Arc<dyn KeyProvider>>,
+    #[cfg(feature = "encryption")]
+    field_decryptor: Option<Arc<dyn Decryptor>>,
 }
 
 impl ConfigLoader {
     /// Create a new configuration loader.
     pub fn new() -> Self {
         Self {
-            sources: Vec::new(),
+            sources: RwLock::new(Vec::new()),
+            cache: Mutex::new(Vec::new()),
+            interpolate: false,
+            #[cfg(feature = "expressions")]
+            expressions: false,
+            unknown_keys: UnknownKeys::Allow,
+            error_mode: ErrorMode::FailFast,
+            immutable_paths: Vec::new(),
+            immutable_keys: ImmutableKeys::Reject,
+            boot_values: Mutex::new(None),
+            #[cfg(feature = "json-schema")]
+            schema: None,
+            section_validators: Vec::new(),
+            migrations: Migrations::default(),
+            pre_load_hooks: Vec::new(),
+            limits: SourceLimits::new(),
+            #[cfg(feature = "signing")]
+            verifier: None,
+            #[cfg(feature = "encryption")]
+            key_provider: None,
+            #[cfg(feature = "encryption")]
+            field_decryptor: None,
         }
     }
 
     /// Add a configuration source.
     pub fn add_source(&mut self, source: Box<dyn ConfigSource>) {
-        self.sources.push(source);
+        self.sources.get_mut().unwrap().push(Slot {
+            source: Some(source),
+            overlay: false,
+        });
+    }
+
+    /// Add an overlay source, merged after every regular source regardless of
+    /// priority. Overlays are ordered among themselves by priority.
+    pub fn add_overlay(&mut self, source: Box<dyn ConfigSource>) {
+        self.sources.get_mut().unwrap().push(Slot {
+            source: Some(source),
+            overlay: true,
+        });
+    }
+
+    /// Add a regular source to a loader in use, returning its index.
+    pub(crate) fn insert_source(&self, source: Box<dyn ConfigSource>) -> usize {
+        let mut sources = self.sources.write().unwrap();
+        sources.push(Slot {
+            source: Some(source),
+            overlay: false,
+        });
+        sources.len() - 1
+    }
+
+    /// Get the name of the source at `index`, unless it was removed.
+    #[cfg(feature = "tokio")]
+    pub(crate) fn source_name(&self, index: usize) -> Option<String> {
+        let sources = self.sources.read().unwrap();
+        Some(sources.get(index)?.source.as_ref()?.name())
+    }
+
+    /// Whether any source is one `matches` picks.
+    #[cfg(feature = "file-watch")]
+    pub(crate) fn has_source(&self, matches: impl Fn(&dyn ConfigSource) -> bool) -> bool {
+        self.sources
+            .read()
+            .unwrap()
+            .iter()
+            .filter_map(|slot| slot.source.as_deref())
+            .any(matches)
+    }
+
+    /// Remove the sources `matches` picks, by index and source, from a
+    /// loader in use, returning them. The next load merges the rest without
+    /// them.
+    pub(crate) fn remove_sources(
+        &self,
+        matches: impl Fn(usize, &dyn ConfigSource) -> bool,
+    ) -> Vec<Box<dyn ConfigSource>> {
+        let mut sources = self.sources.write().unwrap();
+        let mut cache = self.cache.lock().unwrap();
+        let mut removed = Vec::new();
+        for (i, slot) in sources.iter_mut().enumerate() {
+            if slot
+                .source
+                .as_deref()
+                .is_some_and(|source| matches(i, source))
+            {
+                removed.extend(slot.source.take());
+                if let Some(values) = cache.get_mut(i) {
+                    *values = None;
+                }
+            }
+        }
+        removed
+    }
+
+    /// Enable resolution of `${path.to.key}` references after merging.
+    pub fn set_interpolation(&mut self, enabled: bool) {
+        self.interpolate = enabled;
+    }
+
+    /// Evaluate `${...}` as expressions when interpolation is enabled.
+    #[cfg(feature = "expressions")]
+    pub fn set_expressions(&mut self, enabled: bool) {
+        self.expressions = enabled;
+    }
+
+    /// Set what to do with keys the config type doesn't consume.
+    pub fn set_unknown_keys(&mut self, policy: UnknownKeys) {
+        self.unknown_keys = policy;
+    }
+
+    /// Set whether loads stop at the first error or collect every error.
+    pub fn set_error_mode(&mut self, mode: ErrorMode) {
+        self.error_mode = mode;
+    }
+
+    /// Declare keys that can't change after the first load, and what a load
+    /// that changes them does.
+    pub fn set_immutable_paths(&mut self, paths: Vec<String>, policy: ImmutableKeys) {
+        self.immutable_paths = paths;
+        self.immutable_keys = policy;
+    }
+
+    /// Limit the size and structure of what each source loads.
+    pub fn set_limits(&mut self, limits: SourceLimits) {
+        self.limits = limits;
+    }
+
+    /// Set a JSON Schema that the merged configuration must satisfy.
+    #[cfg(feature = "json-schema")]
+    pub fn set_schema(&mut self, schema: ConfigSchema) {
+        self.schema = Some(schema);
+    }
+
+    /// Require content from sources that support signing to be signed by
+    /// one of the verifier's keys.
+    #[cfg(feature = "signing")]
+    pub fn set_verifier(&mut self, verifier: SignatureVerifier) {
+        self.verifier = Some(verifier);
+    }
+
+    /// Decrypt SOPS documents and age-encrypted values from every source with
+    /// the provider's identities.
+    #[cfg(feature = "encryption")]
+    pub fn set_key_provider(&mut self, provider: Arc<dyn KeyProvider>) {
+        self.key_provider = Some(provider);
+    }
+
+    /// Decrypt encrypted fields outside SOPS documents with the decryptor's
+    /// key.
+    #[cfg(feature = "encryption")]
+    pub fn set_field_decryptor(&mut self, decryptor: Arc<dyn Decryptor>) {
+        self.field_decryptor = Some(decryptor);
+    }
+
+    /// Add a validator run against a section of every merged configuration.
+    pub(crate) fn add_section_validator(&mut self, validator: SectionValidator) {
+        self.section_validators.push(validator);
+    }
+
+    /// Migrate every merged tree to the latest schema version before it is
+    /// checked and deserialized.
+    pub(crate) fn set_migrations(&mut self, migrations: Migrations) {
+        self.migrations = migrations;
+    }
+
+    /// Add a hook run on every merged tree, in the order added, before it is
+    /// checked and deserialized.
+    pub(crate) fn add_pre_load_hook(&mut self, hook: PreLoadHook) {
+        self.pre_load_hooks.push(hook);
     }
 
     /// Load and merge configuration from all sources.
@@ -39,58 +372,679 @@ impl ConfigLoader {
     /// Returns an error if:
     /// - Any source fails to load
     /// - Deserialization fails
+    /// - A section validator fails
     pub fn load<T>(&self) -> Result<T>
     where
         T: DeserializeOwned,
     {
-        if self.sources.is_empty() {
+        let loaded = self.load_fingerprinted()?;
+        let validation = ValidationError::collect(loaded.section_errors).map_err(ConfigError::from);
+        ConfigError::collect(loaded.errors.into_iter().chain(validation.err()))?;
+        Ok(loaded.config)
+    }
+
+    /// Load configuration along with a fingerprint of the merged values and
+    /// any section validation failures.
+    pub(crate) fn load_fingerprinted<T>(&self) -> Result<Loaded<T>>
+    where
+        T: DeserializeOwned,
+    {
+        self.load_with(Refresh::All)
+    }
+
+    /// Whether any source reads one of the `changed` files.
+    #[cfg(feature = "file-watch")]
+    pub(crate) fn reads_changed(&self, changed: &[PathBuf]) -> bool {
+        self.sources
+            .read()
+            .unwrap()
+            .iter()
+            .filter_map(|slot| slot.source.as_deref())
+            .any(|source| reads_changed(source, changed))
+    }
+
+    /// Like [`load_fingerprinted`](Self::load_fingerprinted), but only re-load
+    /// sources that read one of the `changed` files. Other sources are merged
+    /// from their values at the last load.
+    #[cfg(feature = "file-watch")]
+    pub(crate) fn load_changed<T>(&self, changed: &[PathBuf]) -> Result<Loaded<T>>
+    where
+        T: DeserializeOwned,
+    {
+        self.load_with(Refresh::Changed(changed))
+    }
+
+    /// Like [`load_fingerprinted`](Self::load_fingerprinted), but only re-load
+    /// overlay sources (the `index`th overlay, or all of them).
+    pub(crate) fn load_overlays<T>(&self, index: Option<usize>) -> Result<Loaded<T>>
+    where
+        T: DeserializeOwned,
+    {
+        self.load_with(Refresh::Overlays(index))
+    }
+
+    /// Like [`load_fingerprinted`](Self::load_fingerprinted), but only re-load
+    /// the source at `index`, which reported a change through its
+    /// [`ChangeStream`](crate::sources::ChangeStream).
+    #[cfg(feature = "tokio")]
+    pub(crate) fn load_source_at<T>(&self, index: usize) -> Result<Loaded<T>>
+    where
+        T: DeserializeOwned,
+    {
+        self.load_with(Refresh::Source(index))
+    }
+
+    /// Like [`load_fingerprinted`](Self::load_fingerprinted), but merge every
+    /// source from its last loaded values; only sources never loaded are read.
+    pub(crate) fn load_merged<T>(&self) -> Result<Loaded<T>>
+    where
+        T: DeserializeOwned,
+    {
+        self.load_with(Refresh::Merge)
+    }
+
+    /// Start watching every source that supports it, returning each stream
+    /// with the index of its source.
+    #[cfg(feature = "tokio")]
+    pub(crate) fn watch(&self) -> Vec<(usize, crate::sources::ChangeStream)> {
+        self.sources
+            .read()
+            .unwrap()
+            .iter()
+            .enumerate()
+            .filter_map(|(index, slot)| Some((index, slot.source.as_ref()?.watch()?)))
+            .collect()
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "hotswap_config.load", skip_all, fields(sources = self.sources.read().unwrap().len()))
+    )]
+    fn load_with<T>(&self, refresh: Refresh<'_>) -> Result<Loaded<T>>
+    where
+        T: DeserializeOwned,
+    {
+        let Merged {
+            values: mut merged,
+            provenance,
+            skipped,
+        } = self.merge_sources(refresh)?;
+        let migrations = self.migrations.apply(&mut merged)?;
+        for hook in &self.pre_load_hooks {
+            hook(&mut merged)?;
+        }
+        let (restart_required, mut section_errors) = self.hold_immutable(&mut merged);
+        let fingerprint = fingerprint(&merged);
+        let version = self.version(fingerprint);
+        // Deserialize from the tree itself: the config crate lowercases keys,
+        // which would hide camelCase ones from serde
+        let tree = config::Value::new(None, config::ValueKind::Table(merged.clone()));
+        let config = build_config(merged)?;
+
+        // Validate the raw tree before deserialization for path-aware errors
+        let mut errors = Vec::new();
+        #[cfg(feature = "json-schema")]
+        if let Some(schema) = &self.schema {
+            match tree.clone().try_deserialize::<serde_json::Value>() {
+                Ok(raw) => match schema.validate(&raw) {
+                    Ok(()) => {}
+                    Err(e) if self.error_mode == ErrorMode::CollectAll => section_errors.push(e),
+                    Err(e) => return Err(e.into()),
+                },
+                Err(e) => self.defer(
+                    &mut errors,
+                    ConfigError::DeserializationError(format!(
+                        "Failed to convert configuration for schema validation: {}",
+                        e
+                    )),
+                )?,
+            }
+        }
+
+        // Run every section validator so all failures are reported together
+        section_errors.extend(
+            self.section_validators
+                .iter()
+                .filter_map(|validator| validator(&config).err()),
+        );
+
+        // Deserialize into target type, tracking the key that failed and any
+        // keys the type ignored
+        let mut unknown = Vec::new();
+        let mut ignored = |path: serde_ignored::Path<'_>| {
+            if self.unknown_keys != UnknownKeys::Allow {
+                unknown.push(key_path(&path));
+            }
+        };
+        let deserializer = serde_ignored::Deserializer::new(tree, &mut ignored);
+        let config = match serde_path_to_error::deserialize::<_, T>(deserializer) {
+            Ok(config) => Some(config),
+            Err(e) => {
+                let key_path = e.path().to_string();
+                let error = ConfigError::DeserializationError(e.into_inner().to_string());
+                let error = if key_path == "." {
+                    error
+                } else {
+                    error.at_key(key_path)
+                };
+                self.defer(&mut errors, error)?;
+                None
+            }
+        };
+
+        if !unknown.is_empty() {
+            unknown.sort();
+            match self.unknown_keys {
+                UnknownKeys::Allow => {}
+                UnknownKeys::Warn => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(keys = ?unknown, "Configuration has unknown keys");
+                    #[cfg(not(feature = "tracing"))]
+                    eprintln!(
+                        "hotswap-config: configuration has unknown keys: {}",
+                        unknown.join(", ")
+                    );
+                }
+                UnknownKeys::Deny => self.defer(&mut errors, ConfigError::UnknownKeys(unknown))?,
+            }
+        }
+
+        // Without a config the remaining validators can't run, so report
+        // everything found so far
+        let Some(config) = config else {
+            let validation = ValidationError::collect(section_errors).map_err(ConfigError::from);
+            return Err(
+                ConfigError::collect(errors.into_iter().chain(validation.err()))
+                    .expect_err("the deserialization error was collected"),
+            );
+        };
+
+        Ok(Loaded {
+            config,
+            fingerprint,
+            version,
+            section_errors,
+            provenance,
+            skipped,
+            errors,
+            restart_required,
+            migrations,
+            #[cfg(feature = "tokio")]
+            effective_at: self.effective_at(),
+        })
+    }
+
+    /// Latest [effective time](ConfigSource::effective_at) of the sources.
+    #[cfg(feature = "tokio")]
+    fn effective_at(&self) -> Option<SystemTime> {
+        let sources = self.sources.read().unwrap();
+        merge_order(&sources)
+            .into_iter()
+            .filter_map(|(_, source)| source.effective_at())
+            .max()
+    }
+
+    /// Version of a merged tree with `fingerprint`: the
+    /// [content versions](ConfigSource::content_version) of the sources that
+    /// report one, in merge order and separated by commas, or the fingerprint
+    /// in hex if none does.
+    fn version(&self, fingerprint: u64) -> String {
+        let sources = self.sources.read().unwrap();
+        let versions: Vec<String> = merge_order(&sources)
+            .into_iter()
+            .filter_map(|(_, source)| source.content_version())
+            .collect();
+        if versions.is_empty() {
+            format!("{:016x}", fingerprint)
+        } else {
+            versions.join(",")
+        }
+    }
+
+    /// Check the immutable keys of a merged tree against their boot-time
+    /// values, recording them on the first load.
+    ///
+    /// Returns the changed keys that were set back to their boot-time values,
+    /// under [`ImmutableKeys::RestartRequired`], or an error for each changed
+    /// key under [`ImmutableKeys::Reject`].
+    fn hold_immutable(
+        &self,
+        merged: &mut config::Map<String, config::Value>,
+    ) -> (Vec<String>, Vec<ValidationError>) {
+        let mut held = Vec::new();
+        let mut errors = Vec::new();
+        if self.immutable_paths.is_empty() {
+            return (held, errors);
+        }
+
+        let mut boot_values = self.boot_values.lock().unwrap();
+        let Some(boot_values) = boot_values.as_ref() else {
+            *boot_values = Some(
+                self.immutable_paths
+                    .iter()
+                    .map(|path| lookup(merged, path).cloned())
+                    .collect(),
+            );
+            return (held, errors);
+        };
+
+        for (path, boot) in self.immutable_paths.iter().zip(boot_values) {
+            let current = lookup(merged, path);
+            if current.map(value_fingerprint) == boot.as_ref().map(value_fingerprint) {
+                continue;
+            }
+            match self.immutable_keys {
+                ImmutableKeys::Reject => errors.push(ValidationError::invalid_field(
+                    path.as_str(),
+                    "cannot be changed at runtime; restart to apply",
+                )),
+                ImmutableKeys::RestartRequired => {
+                    set_path(merged, path, boot.clone());
+                    held.push(path.clone());
+                }
+            }
+        }
+        (held, errors)
+    }
+
+    /// Handle an error according to the error mode: return it, or collect it
+    /// into `errors` and carry on.
+    fn defer(&self, errors: &mut Vec<ConfigError>, error: ConfigError) -> Result<()> {
+        match self.error_mode {
+            ErrorMode::FailFast => Err(error),
+            ErrorMode::CollectAll => {
+                errors.push(error);
+                Ok(())
+            }
+        }
+    }
+
+    /// Load sources and deep-merge them in priority order, overlays last,
+    /// recording which source set each key.
+    ///
+    /// Sources that `refresh` doesn't cover are taken from the cache (if they
+    /// have been loaded before). If interpolation is enabled, `${...}`
+    /// references are resolved against the merged tree before it is returned.
+    fn merge_sources(&self, refresh: Refresh<'_>) -> Result<Merged> {
+        let sources = self.sources.read().unwrap();
+        let order = merge_order(&sources);
+        if order.is_empty() {
             return Err(ConfigError::LoadError(
                 "No configuration sources specified".to_string(),
             ));
         }
 
-        // Sort sources by priority (lowest first)
-        let mut sorted_sources: Vec<_> = self.sources.iter().collect();
-        sorted_sources.sort_by_key(|s| s.priority());
+        let mut cache = self.cache.lock().unwrap();
+        cache.resize(sources.len(), None);
 
-        // Start with an empty config builder
-        let mut builder = config::Config::builder();
+        // Deep-merge each source in priority order, so a higher priority source
+        // only overrides the nested keys it actually sets
+        let mut merged = config::Map::new();
+        let mut provenance = Provenance::default();
+        let mut skipped = Vec::new();
+        let mut failed = Vec::new();
+        for (i, source) in order {
+            let reload = match refresh {
+                Refresh::All => true,
+                Refresh::Changed(changed) => reads_changed(source, changed),
+                Refresh::Overlays(None) => sources[i].overlay,
+                Refresh::Overlays(Some(index)) => overlay_index(&sources, i) == Some(index),
+                Refresh::Source(index) => i == index,
+                Refresh::Merge => false,
+            };
+            let cached = cache[i].as_ref().filter(|_| !reload);
+            if let Some(values) = cached {
+                provenance.record(&source.name(), source.priority(), values);
+                for (key, value) in values.clone() {
+                    merge_value(&mut merged, key, value);
+                }
+                continue;
+            }
 
-        // Merge each source in priority order
-        for source in sorted_sources {
-            let values = source.load().map_err(|e| {
-                ConfigError::LoadError(format!("Failed to load source '{}': {}", source.name(), e))
-            })?;
+            #[cfg(feature = "tracing")]
+            let _span = tracing::debug_span!(
+                "hotswap_config.source",
+                source = %source.name(),
+                priority = source.priority()
+            )
+            .entered();
+            let start = std::time::Instant::now();
 
-            // Convert HashMap<String, config::Value> to config::Config and add as source
-            for (key, value) in values {
-                builder = builder.set_override(&key, value).map_err(|e| {
-                    ConfigError::LoadError(format!(
-                        "Failed to merge source '{}': {}",
-                        source.name(),
-                        e
-                    ))
-                })?;
+            let values = match self.load_source(source) {
+                Ok(values) => values,
+                Err(e) => {
+                    let policy = source.failure_policy();
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(error = %e, ?policy, "Source failed to load");
+                    let fallback = match policy {
+                        FailurePolicy::Required => None,
+                        FailurePolicy::Optional => Some(None),
+                        // Without a previous load there's nothing to fall back to
+                        FailurePolicy::FallbackToCache => cache[i].clone().map(Some),
+                    };
+                    let Some(fallback) = fallback else {
+                        self.defer(&mut failed, e)?;
+                        continue;
+                    };
+                    skipped.push(SkippedSource {
+                        name: source.name(),
+                        error: Arc::new(e),
+                        served_from_cache: fallback.is_some(),
+                    });
+                    match fallback {
+                        Some(values) => values,
+                        None => {
+                            cache[i] = None;
+                            continue;
+                        }
+                    }
+                }
+            };
+
+            #[cfg(feature = "tracing")]
+            tracing::debug!(
+                keys = values.len(),
+                duration_ms = start.elapsed().as_secs_f64() * 1000.0,
+                "Loaded source"
+            );
+
+            provenance.record_loaded(
+                &source.name(),
+                source.priority(),
+                &values,
+                Some(start.elapsed()),
+            );
+            for (key, value) in values.clone() {
+                merge_value(&mut merged, key, value);
             }
+            cache[i] = Some(values);
         }
 
-        // Build the final config
-        let config = builder
-            .build()
-            .map_err(|e| ConfigError::LoadError(format!("Failed to build configuration: {}", e)))?;
+        // The merged tree is missing the failed sources' keys, so checking it
+        // would only report errors they caused
+        ConfigError::collect(failed)?;
+
+        // Resolve references against the fully merged tree
+        if self.interpolate {
+            merged = self.resolve_references(&merged)?;
+        }
 
-        // Deserialize into target type
-        config.try_deserialize::<T>().map_err(|e| {
-            ConfigError::DeserializationError(format!("Failed to deserialize configuration: {}", e))
+        let provenance = provenance.finish(&merged);
+        Ok(Merged {
+            values: merged,
+            provenance,
+            skipped,
         })
     }
 
-    /// Get the list of source names in priority order.
+    /// Resolve `${...}` in a merged tree, as expressions if they're enabled.
+    fn resolve_references(
+        &self,
+        merged: &config::Map<String, config::Value>,
+    ) -> Result<config::Map<String, config::Value>> {
+        #[cfg(feature = "expressions")]
+        if self.expressions {
+            return super::interpolate::evaluate(merged);
+        }
+        super::interpolate::interpolate(merged)
+    }
+
+    /// Load a single source, decrypting its values if decryption is enabled.
+    ///
+    /// Errors carry the source's name, and its file if it reads exactly one.
+    fn load_source(&self, source: &dyn ConfigSource) -> Result<SourceValues> {
+        self.read_source(source)
+            .and_then(|values| self.decrypt(values))
+            .map_err(|e| {
+                let mut paths = source.paths();
+                let path = if paths.len() == 1 { paths.pop() } else { None };
+                e.in_source(source.name(), path)
+            })
+    }
+
+    /// Decrypt encrypted values if a key provider or field decryptor is set.
+    fn decrypt(&self, values: SourceValues) -> Result<SourceValues> {
+        #[cfg(feature = "encryption")]
+        if self.key_provider.is_some() || self.field_decryptor.is_some() {
+            return crate::encryption::decrypt(
+                values,
+                self.key_provider.as_deref(),
+                self.field_decryptor.as_deref(),
+            );
+        }
+
+        Ok(values)
+    }
+
+    /// Read a single source within the source limits.
+    fn read_source(&self, source: &dyn ConfigSource) -> Result<SourceValues> {
+        // Refuse oversized files before reading them
+        if self.limits.max_payload_size().is_some() {
+            for path in source.paths() {
+                if let Ok(metadata) = std::fs::metadata(&path)
+                    && metadata.is_file()
+                {
+                    self.limits.check_payload(metadata.len())?;
+                }
+            }
+        }
+
+        let values = self.read_verified(source)?;
+        self.limits.check_values(&values)?;
+        Ok(values)
+    }
+
+    /// Read a single source, verifying its signature if a verifier is set.
+    fn read_verified(&self, source: &dyn ConfigSource) -> Result<SourceValues> {
+        #[cfg(feature = "signing")]
+        if let Some(verifier) = &self.verifier {
+            if let Some(signed) = source.load_signed()? {
+                self.limits.check_payload(signed.content.len() as u64)?;
+                verifier.verify(&signed.content, signed.signature.as_deref())?;
+                return Ok(signed.values);
+            }
+        }
+
+        source.load()
+    }
+
+    /// Get the list of source names in merge order.
     #[allow(dead_code)]
     pub fn source_names(&self) -> Vec<String> {
-        let mut sorted_sources: Vec<_> = self.sources.iter().collect();
-        sorted_sources.sort_by_key(|s| s.priority());
-        sorted_sources.iter().map(|s| s.name()).collect()
+        let sources = self.sources.read().unwrap();
+        merge_order(&sources)
+            .into_iter()
+            .map(|(_, source)| source.name())
+            .collect()
+    }
+}
+
+/// The sources that haven't been removed, with their indices, sorted by
+/// priority (lowest first) with overlays after the rest.
+fn merge_order(sources: &[Slot]) -> Vec<(usize, &dyn ConfigSource)> {
+    let mut order: Vec<_> = sources
+        .iter()
+        .enumerate()
+        .filter_map(|(i, slot)| Some((i, slot.source.as_deref()?)))
+        .collect();
+    order.sort_by_key(|&(i, source)| (sources[i].overlay, source.priority()));
+    order
+}
+
+/// Position of the source at `i` among overlays, if it is one. Removed
+/// overlays keep their positions.
+fn overlay_index(sources: &[Slot], i: usize) -> Option<usize> {
+    sources[i]
+        .overlay
+        .then(|| sources[..i].iter().filter(|slot| slot.overlay).count())
+}
+
+/// Whether `source` reads exactly the file at `path`.
+#[cfg(feature = "file-watch")]
+pub(crate) fn reads_only(source: &dyn ConfigSource, path: &Path) -> bool {
+    let resolve = |path: &Path| {
+        path.canonicalize()
+            .or_else(|_| std::path::absolute(path))
+            .unwrap_or_else(|_| path.to_path_buf())
+    };
+    matches!(source.paths().as_slice(), [only] if resolve(only) == resolve(path))
+}
+
+/// Check whether a source reads any of the changed files (or their detached
+/// signatures), or any file in a directory it reads.
+fn reads_changed(source: &dyn ConfigSource, changed: &[PathBuf]) -> bool {
+    let paths = source.paths();
+    let signatures = paths
+        .iter()
+        .map(|path| crate::sources::signature_path(path));
+    paths.iter().cloned().chain(signatures).any(|path| {
+        // Watcher events carry absolute paths; a deleted file can't be canonicalized
+        let resolved = path
+            .canonicalize()
+            .or_else(|_| std::path::absolute(&path))
+            .unwrap_or_else(|_| path.clone());
+        changed
+            .iter()
+            .any(|c| c == &resolved || c.parent() == Some(&resolved) || same_file_name(c, &path))
+    })
+}
+
+/// Fall back to comparing the parent directory and name when paths differ
+/// only by symlinks (e.g. `/tmp` vs `/private/tmp`).
+fn same_file_name(changed: &Path, path: &Path) -> bool {
+    let parent = |p: &Path| p.parent().and_then(|d| d.canonicalize().ok());
+    changed.file_name().is_some()
+        && changed.file_name() == path.file_name()
+        && parent(changed).is_some()
+        && parent(changed) == parent(path)
+}
+
+/// Build a `config::Config` from a merged tree.
+fn build_config(merged: config::Map<String, config::Value>) -> Result<config::Config> {
+    let mut builder = config::Config::builder();
+    for (key, value) in merged {
+        builder = builder
+            .set_override(&key, value)
+            .map_err(|e| ConfigError::LoadError(format!("Failed to merge key '{}': {}", key, e)))?;
+    }
+
+    builder
+        .build()
+        .map_err(|e| ConfigError::LoadError(format!("Failed to build configuration: {}", e)))
+}
+
+/// Hash a merged tree. Table keys are hashed in sorted order, so the result
+/// doesn't depend on map iteration order.
+fn fingerprint(merged: &config::Map<String, config::Value>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    hash_table(merged, &mut hasher);
+    hasher.finish()
+}
+
+fn hash_table(table: &config::Map<String, config::Value>, hasher: &mut DefaultHasher) {
+    let mut keys: Vec<_> = table.keys().collect();
+    keys.sort();
+    keys.len().hash(hasher);
+    for key in keys {
+        key.hash(hasher);
+        hash_value(&table[key], hasher);
+    }
+}
+
+/// Fingerprint of a single value, ignoring where it was loaded from.
+fn value_fingerprint(value: &config::Value) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    hash_value(value, &mut hasher);
+    hasher.finish()
+}
+
+fn hash_value(value: &config::Value, hasher: &mut DefaultHasher) {
+    use config::ValueKind;
+
+    std::mem::discriminant(&value.kind).hash(hasher);
+    match &value.kind {
+        ValueKind::Nil => {}
+        ValueKind::Boolean(b) => b.hash(hasher),
+        ValueKind::I64(n) => n.hash(hasher),
+        ValueKind::I128(n) => n.hash(hasher),
+        ValueKind::U64(n) => n.hash(hasher),
+        ValueKind::U128(n) => n.hash(hasher),
+        ValueKind::Float(f) => f.to_bits().hash(hasher),
+        ValueKind::String(s) => s.hash(hasher),
+        ValueKind::Table(table) => hash_table(table, hasher),
+        ValueKind::Array(items) => {
+            items.len().hash(hasher);
+            for item in items {
+                hash_value(item, hasher);
+            }
+        }
+    }
+}
+
+/// Render an ignored key's path as a dotted key path, skipping the markers
+/// `serde_ignored` adds for `Option` and newtype wrappers.
+fn key_path(path: &serde_ignored::Path<'_>) -> String {
+    path.to_string()
+        .split('.')
+        .filter(|segment| *segment != "?")
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Merge `value` into `target[key]`, recursing into tables on both sides.
+///
+/// Non-table values (including arrays) replace the existing value.
+pub(crate) fn merge_value(
+    target: &mut config::Map<String, config::Value>,
+    key: String,
+    value: config::Value,
+) {
+    if let Some(config::ValueKind::Table(existing)) = target.get_mut(&key).map(|v| &mut v.kind) {
+        if let config::ValueKind::Table(incoming) = value.kind {
+            for (child_key, child_value) in incoming {
+                merge_value(existing, child_key, child_value);
+            }
+            return;
+        }
+    }
+
+    target.insert(key, value);
+}
+
+/// Set the value at a dotted path in a tree, creating tables on the way, or
+/// remove it if `value` is `None`.
+pub(crate) fn set_path(
+    table: &mut config::Map<String, config::Value>,
+    key: &str,
+    value: Option<config::Value>,
+) {
+    use config::ValueKind;
+
+    let Some((head, rest)) = key.split_once('.') else {
+        match value {
+            Some(value) => {
+                table.insert(key.to_string(), value);
+            }
+            None => {
+                table.remove(key);
+            }
+        }
+        return;
+    };
+    if value.is_none() && !table.contains_key(head) {
+        return;
+    }
+    let entry = table
+        .entry(head.to_string())
+        .or_insert_with(|| config::Value::new(None, ValueKind::Table(config::Map::new())));
+    if !matches!(entry.kind, ValueKind::Table(_)) {
+        if value.is_none() {
+            return;
+        }
+        entry.kind = ValueKind::Table(config::Map::new());
+    }
+    if let ValueKind::Table(nested) = &mut entry.kind {
+        set_path(nested, rest, value);
     }
 }
 
@@ -168,6 +1122,39 @@ mod tests {
         assert_eq!(config.host, "localhost");
     }
 
+    #[test]
+    fn test_errors_carry_context() {
+        use crate::sources::FileSource;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("missing.yaml");
+        let mut loader = ConfigLoader::new();
+        loader.add_source(Box::new(FileSource::new(&path)));
+
+        let error = loader.load::<TestConfig>().unwrap_err();
+        assert!(matches!(error.root(), ConfigError::LoadError(_)));
+        assert!(error.source_name().unwrap().contains("missing.yaml"));
+        assert_eq!(error.path(), Some(path.as_path()));
+        assert_eq!(error.key_path(), None);
+
+        #[derive(Debug, Deserialize)]
+        #[allow(dead_code)]
+        struct Nested {
+            server: TestConfig,
+        }
+        let mut server = config::Map::new();
+        server.insert("port".to_string(), config::Value::from("not a port"));
+        server.insert("host".to_string(), config::Value::from("localhost"));
+        let mut loader = ConfigLoader::new();
+        loader.add_source(Box::new(
+            MockSource::new("test", 100).with_value("server", server),
+        ));
+        let error = loader.load::<Nested>().unwrap_err();
+        assert!(matches!(error.root(), ConfigError::DeserializationError(_)));
+        assert_eq!(error.key_path(), Some("server.port"));
+        assert_eq!(error.source_name(), None);
+    }
+
     #[test]
     fn test_precedence() {
         let mut loader = ConfigLoader::new();
@@ -188,6 +1175,510 @@ mod tests {
         assert_eq!(config.host, "localhost"); // From default
     }
 
+    #[test]
+    fn test_nested_tables_deep_merge() {
+        #[derive(Debug, Deserialize)]
+        struct Nested {
+            server: TestConfig,
+        }
+
+        let mut server = config::Map::new();
+        server.insert("port".to_string(), config::Value::from(8080i64));
+        server.insert("host".to_string(), config::Value::from("localhost"));
+
+        let mut server_override = config::Map::new();
+        server_override.insert("port".to_string(), config::Value::from(9090i64));
+
+        let mut loader = ConfigLoader::new();
+        loader.add_source(Box::new(
+            MockSource::new("default", 100).with_value("server", server),
+        ));
+        loader.add_source(Box::new(
+            MockSource::new("override", 200).with_value("server", server_override),
+        ));
+
+        let config: Nested = loader.load().unwrap();
+        assert_eq!(config.server.port, 9090);
+        assert_eq!(config.server.host, "localhost");
+    }
+
+    #[test]
+    fn test_failure_policies() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        struct FlakySource {
+            failing: Arc<AtomicBool>,
+            policy: FailurePolicy,
+        }
+
+        impl ConfigSource for FlakySource {
+            fn load(&self) -> Result<HashMap<String, config::Value>> {
+                if self.failing.load(Ordering::SeqCst) {
+                    return Err(ConfigError::LoadError("connection refused".to_string()));
+                }
+                Ok(HashMap::from([("port".to_string(), 9090i64.into())]))
+            }
+
+            fn name(&self) -> String {
+                "flaky".to_string()
+            }
+
+            fn priority(&self) -> i32 {
+                250
+            }
+
+            fn failure_policy(&self) -> FailurePolicy {
+                self.policy
+            }
+        }
+
+        let loader_with = |policy, failing: &Arc<AtomicBool>| {
+            let mut loader = ConfigLoader::new();
+            loader.add_source(Box::new(
+                MockSource::new("defaults", 100)
+                    .with_value("port", 8080i64)
+                    .with_value("host", "localhost"),
+            ));
+            loader.add_source(Box::new(FlakySource {
+                failing: Arc::clone(failing),
+                policy,
+            }));
+            loader
+        };
+        let port = |loaded: &Loaded<TestConfig>| loaded.config.port;
+
+        // Required sources fail the load
+        let failing = Arc::new(AtomicBool::new(true));
+        let loader = loader_with(FailurePolicy::Required, &failing);
+        assert!(loader.load_fingerprinted::<TestConfig>().is_err());
+
+        // Optional sources are skipped
+        let loader = loader_with(FailurePolicy::Optional, &failing);
+        let loaded = loader.load_fingerprinted::<TestConfig>().unwrap();
+        assert_eq!(port(&loaded), 8080);
+        assert_eq!(loaded.skipped.len(), 1);
+        assert_eq!(loaded.skipped[0].name, "flaky");
+        assert!(!loaded.skipped[0].served_from_cache);
+
+        // Cached sources need a successful load to fall back to
+        let loader = loader_with(FailurePolicy::FallbackToCache, &failing);
+        assert!(loader.load_fingerprinted::<TestConfig>().is_err());
+        failing.store(false, Ordering::SeqCst);
+        let loaded = loader.load_fingerprinted::<TestConfig>().unwrap();
+        assert_eq!(port(&loaded), 9090);
+        assert!(loaded.skipped.is_empty());
+        failing.store(true, Ordering::SeqCst);
+        let loaded = loader.load_fingerprinted::<TestConfig>().unwrap();
+        assert_eq!(port(&loaded), 9090);
+        assert!(loaded.skipped[0].served_from_cache);
+        assert!(matches!(
+            loaded.skipped[0].error.root(),
+            ConfigError::LoadError(_)
+        ));
+    }
+
+    #[test]
+    fn test_collect_all_errors() {
+        struct BrokenSource(&'static str);
+
+        impl ConfigSource for BrokenSource {
+            fn load(&self) -> Result<HashMap<String, config::Value>> {
+                Err(ConfigError::ParseError("invalid YAML".to_string()))
+            }
+
+            fn name(&self) -> String {
+                self.0.to_string()
+            }
+        }
+
+        // Every failing source is reported, not just the first
+        let mut loader = ConfigLoader::new();
+        loader.set_error_mode(ErrorMode::CollectAll);
+        loader.add_source(Box::new(BrokenSource("first")));
+        loader.add_source(Box::new(
+            MockSource::new("ok", 100).with_value("port", 8080i64),
+        ));
+        loader.add_source(Box::new(BrokenSource("second")));
+        match loader.load::<TestConfig>() {
+            Err(ConfigError::Multiple(errors)) => {
+                let names: Vec<_> = errors.iter().filter_map(|e| e.source_name()).collect();
+                assert_eq!(names, ["first", "second"]);
+            }
+            other => panic!("expected multiple errors, got {:?}", other),
+        }
+
+        // Type and section errors are reported together
+        let collecting = |source: MockSource| {
+            let mut loader = ConfigLoader::new();
+            loader.set_error_mode(ErrorMode::CollectAll);
+            loader.set_unknown_keys(UnknownKeys::Deny);
+            loader.add_section_validator(Arc::new(|_: &config::Config| {
+                Err(ValidationError::invalid_field("host", "must not be empty"))
+            }));
+            loader.add_source(Box::new(source));
+            loader
+        };
+        let mut loader = collecting(
+            MockSource::new("file", 100)
+                .with_value("port", "not-a-port")
+                .with_value("host", ""),
+        );
+        match loader.load::<TestConfig>() {
+            Err(ConfigError::Multiple(errors)) => {
+                assert_eq!(errors.len(), 2);
+                assert_eq!(errors[0].key_path(), Some("port"));
+                assert!(
+                    matches!(&errors[1], ConfigError::ValidationError(e) if e.contains("host"))
+                );
+            }
+            other => panic!("expected multiple errors, got {:?}", other),
+        }
+
+        // Fail-fast stops at the first
+        loader.set_error_mode(ErrorMode::FailFast);
+        assert!(loader.load::<TestConfig>().unwrap_err().key_path() == Some("port"));
+
+        // Unknown keys are collected too, though only all known once
+        // deserialization succeeds
+        let loader = collecting(
+            MockSource::new("file", 100)
+                .with_value("port", 8080i64)
+                .with_value("host", "")
+                .with_value("prot", 8080i64),
+        );
+        match loader.load::<TestConfig>() {
+            Err(ConfigError::Multiple(errors)) => {
+                assert_eq!(errors.len(), 2);
+                assert!(matches!(&errors[0], ConfigError::UnknownKeys(keys) if keys == &["prot"]));
+                assert!(matches!(&errors[1], ConfigError::ValidationError(_)));
+            }
+            other => panic!("expected multiple errors, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unknown_keys() {
+        #[derive(Debug, Deserialize)]
+        #[allow(dead_code)]
+        struct Nested {
+            server: TestConfig,
+            tls: Option<TestConfig>,
+        }
+
+        let mut server = config::Map::new();
+        server.insert("port".to_string(), config::Value::from(8080i64));
+        server.insert("host".to_string(), config::Value::from("localhost"));
+        server.insert("prot".to_string(), config::Value::from(9090i64));
+        let mut tls = server.clone();
+        tls.remove("prot");
+        tls.insert("cert".to_string(), config::Value::from("cert.pem"));
+
+        let mut loader = ConfigLoader::new();
+        loader.add_source(Box::new(
+            MockSource::new("test", 100)
+                .with_value("server", server)
+                .with_value("tls", tls)
+                .with_value("debug", true),
+        ));
+
+        // Allowed by default
+        assert!(loader.load::<Nested>().is_ok());
+
+        loader.set_unknown_keys(UnknownKeys::Warn);
+        assert!(loader.load::<Nested>().is_ok());
+
+        loader.set_unknown_keys(UnknownKeys::Deny);
+        match loader.load::<Nested>() {
+            Err(ConfigError::UnknownKeys(keys)) => {
+                assert_eq!(keys, ["debug", "server.prot", "tls.cert"]);
+            }
+            other => panic!("expected unknown keys, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn test_interpolation_across_sources() {
+        #[derive(Debug, Deserialize)]
+        struct UrlConfig {
+            host: String,
+            url: String,
+        }
+
+        let mut loader = ConfigLoader::new();
+        loader.add_source(Box::new(
+            MockSource::new("default", 100)
+                .with_value("host", "localhost")
+                .with_value("url", "http://${host}:8080"),
+        ));
+        loader.add_source(Box::new(
+            MockSource::new("override", 200).with_value("host", "example.com"),
+        ));
+
+        // Disabled by default
+        let config: UrlConfig = loader.load().unwrap();
+        assert_eq!(config.url, "http://${host}:8080");
+
+        loader.set_interpolation(true);
+        let config: UrlConfig = loader.load().unwrap();
+        assert_eq!(config.host, "example.com");
+        assert_eq!(config.url, "http://example.com:8080");
+    }
+
+    #[cfg(feature = "expressions")]
+    #[test]
+    fn test_expressions_across_sources() {
+        #[derive(Debug, Deserialize)]
+        struct PoolConfig {
+            workers: i64,
+            pool_size: i64,
+            label: String,
+        }
+
+        let mut loader = ConfigLoader::new();
+        loader.add_source(Box::new(
+            MockSource::new("default", 100)
+                .with_value("workers", 4)
+                .with_value("pool_size", "${clamp(workers * 4, 8, 64)}")
+                .with_value("label", "pool of ${pool_size}"),
+        ));
+        loader.add_source(Box::new(
+            MockSource::new("override", 200).with_value("workers", 32),
+        ));
+        loader.set_interpolation(true);
+        loader.set_expressions(true);
+
+        let config: PoolConfig = loader.load().unwrap();
+        assert_eq!(config.workers, 32);
+        assert_eq!(config.pool_size, 64);
+        assert_eq!(config.label, "pool of 64");
+
+        loader.add_source(Box::new(
+            MockSource::new("broken", 300).with_value("pool_size", "${workers / 0}"),
+        ));
+        match loader.load::<PoolConfig>() {
+            Err(ConfigError::InterpolationError { key, reason }) => {
+                assert_eq!(key, "pool_size");
+                assert!(reason.contains("division by zero"), "{}", reason);
+            }
+            other => panic!("expected interpolation error, got {:?}", other),
+        }
+    }
+
+    #[cfg(feature = "json-schema")]
+    #[test]
+    fn test_schema_validation() {
+        let mut loader = ConfigLoader::new();
+        loader.add_source(Box::new(
+            MockSource::new("test", 100)
+                .with_value("port", "not-a-port")
+                .with_value("host", "localhost"),
+        ));
+        loader.set_schema(
+            ConfigSchema::new(serde_json::json!({
+                "type": "object",
+                "properties": { "port": { "type": "integer" } }
+            }))
+            .unwrap(),
+        );
+
+        let result: Result<TestConfig> = loader.load();
+        match result {
+            Err(ConfigError::ValidationError(msg)) => assert!(msg.contains("port")),
+            other => panic!("expected validation error, got {:?}", other),
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_tracing_spans() {
+        use std::sync::{Arc, Mutex};
+        use tracing_subscriber::layer::{Context, SubscriberExt};
+
+        #[derive(Clone, Default)]
+        struct SpanNames(Arc<Mutex<Vec<&'static str>>>);
+
+        impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for SpanNames {
+            fn on_new_span(
+                &self,
+                attrs: &tracing::span::Attributes<'_>,
+                _id: &tracing::span::Id,
+                _ctx: Context<'_, S>,
+            ) {
+                self.0.lock().unwrap().push(attrs.metadata().name());
+            }
+        }
+
+        let mut loader = ConfigLoader::new();
+        loader.add_source(Box::new(
+            MockSource::new("file", 100).with_value("port", 8080),
+        ));
+        loader.add_source(Box::new(
+            MockSource::new("env", 300).with_value("host", "localhost"),
+        ));
+
+        let names = SpanNames::default();
+        let subscriber = tracing_subscriber::registry().with(names.clone());
+        let config: TestConfig =
+            tracing::subscriber::with_default(subscriber, || loader.load().unwrap());
+
+        assert_eq!(config.port, 8080);
+        assert_eq!(
+            *names.0.lock().unwrap(),
+            vec![
+                "hotswap_config.load",
+                "hotswap_config.source",
+                "hotswap_config.source"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let load = |port: i64| {
+            let mut loader = ConfigLoader::new();
+            loader.add_source(Box::new(
+                MockSource::new("file", 100)
+                    .with_value("port", port)
+                    .with_value("host", "localhost"),
+            ));
+            let loaded: Loaded<TestConfig> = loader.load_fingerprinted().unwrap();
+            assert_eq!(loaded.config.port as i64, port);
+            loaded.fingerprint
+        };
+
+        assert_eq!(load(8080), load(8080));
+        assert_ne!(load(8080), load(8081));
+    }
+
+    #[cfg(feature = "file-watch")]
+    #[test]
+    fn test_load_changed_reloads_only_dirty_sources() {
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        struct CountingSource {
+            path: PathBuf,
+            key: &'static str,
+            loads: Arc<AtomicUsize>,
+        }
+
+        impl ConfigSource for CountingSource {
+            fn load(&self) -> Result<HashMap<String, config::Value>> {
+                self.loads.fetch_add(1, Ordering::SeqCst);
+                let value = std::fs::read_to_string(&self.path).unwrap();
+                Ok(HashMap::from([(self.key.to_string(), value.into())]))
+            }
+
+            fn name(&self) -> String {
+                self.key.to_string()
+            }
+
+            fn paths(&self) -> Vec<PathBuf> {
+                vec![self.path.clone()]
+            }
+        }
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let port_path = temp_dir.path().join("port");
+        let host_path = temp_dir.path().join("host");
+        std::fs::write(&port_path, "8080").unwrap();
+        std::fs::write(&host_path, "localhost").unwrap();
+
+        let port_loads = Arc::new(AtomicUsize::new(0));
+        let host_loads = Arc::new(AtomicUsize::new(0));
+        let mut loader = ConfigLoader::new();
+        loader.add_source(Box::new(CountingSource {
+            path: port_path.clone(),
+            key: "port",
+            loads: Arc::clone(&port_loads),
+        }));
+        loader.add_source(Box::new(CountingSource {
+            path: host_path.clone(),
+            key: "host",
+            loads: Arc::clone(&host_loads),
+        }));
+
+        let _: Loaded<TestConfig> = loader.load_fingerprinted().unwrap();
+
+        std::fs::write(&port_path, "9090").unwrap();
+        let config: TestConfig = loader
+            .load_changed(&[port_path.canonicalize().unwrap()])
+            .unwrap()
+            .config;
+
+        assert_eq!(config.port, 9090);
+        assert_eq!(config.host, "localhost");
+        assert_eq!(port_loads.load(Ordering::SeqCst), 2);
+        assert_eq!(host_loads.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_overlay_merges_after_regular_sources() {
+        let mut loader = ConfigLoader::new();
+        loader.add_overlay(Box::new(
+            MockSource::new("secrets", 10).with_value("host", "secret-host"),
+        ));
+        loader.add_source(Box::new(
+            MockSource::new("file", 100)
+                .with_value("port", 8080)
+                .with_value("host", "localhost"),
+        ));
+        loader.add_source(Box::new(
+            MockSource::new("env", 300).with_value("host", "env-host"),
+        ));
+
+        let config: TestConfig = loader.load().unwrap();
+        assert_eq!(config.port, 8080);
+        assert_eq!(config.host, "secret-host");
+        assert_eq!(loader.source_names(), vec!["file", "env", "secrets"]);
+    }
+
+    #[cfg(feature = "signing")]
+    #[test]
+    fn test_signature_verification() {
+        use crate::sources::FileSource;
+        use ed25519_dalek::{Signer, SigningKey};
+
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.yaml");
+        std::fs::write(&path, "port: 8080\n").unwrap();
+        let signature_path = crate::sources::signature_path(&path);
+
+        let mut loader = ConfigLoader::new();
+        loader.add_source(Box::new(FileSource::new(&path)));
+        // Sources that can't be signed are merged unverified
+        loader.add_source(Box::new(
+            MockSource::new("env", 300).with_value("host", "localhost"),
+        ));
+        loader.set_verifier(
+            SignatureVerifier::new()
+                .with_public_key_bytes(key.verifying_key().to_bytes())
+                .unwrap(),
+        );
+
+        assert!(matches!(
+            loader.load::<TestConfig>().unwrap_err().root(),
+            ConfigError::SignatureError(_)
+        ));
+
+        std::fs::write(&signature_path, key.sign(b"port: 8080\n").to_bytes()).unwrap();
+        let config: TestConfig = loader.load().unwrap();
+        assert_eq!(config.port, 8080);
+
+        std::fs::write(&path, "port: 6666\n").unwrap();
+        let error = loader.load::<TestConfig>().unwrap_err();
+        assert!(matches!(error.root(), ConfigError::SignatureError(_)));
+        assert!(error.source_name().unwrap().contains("config.yaml"));
+
+        // A changed signature file re-loads the file it signs
+        let file = FileSource::new(&path);
+        assert!(reads_changed(
+            &file,
+            &[signature_path.canonicalize().unwrap()]
+        ));
+    }
+
     #[test]
     fn test_source_names() {
         let mut loader = ConfigLoader::new();
@@ -0,0 +1,153 @@
+//! Migrations of the raw configuration across schema versions.
+
+use crate::error::{ConfigError, Result};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::Arc;
+
+/// Key holding the schema version of a configuration.
+pub(crate) const VERSION_KEY: &str = "config_version";
+
+/// Rewrites a merged tree from one schema version to the next.
+type MigrationFn = Arc<dyn Fn(&mut HashMap<String, config::Value>) -> Result<()> + Send + Sync>;
+
+/// A migration applied to the configuration while it was loaded.
+///
+/// Reported by
+/// [`HotswapConfig::applied_migrations`](crate::core::HotswapConfig::applied_migrations).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AppliedMigration {
+    /// Version the configuration was at
+    pub from: u64,
+    /// Version the migration brought it to
+    pub to: u64,
+}
+
+impl fmt::Display for AppliedMigration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} -> {}", self.from, self.to)
+    }
+}
+
+/// Migrations registered with the builder, by the version they start from.
+#[derive(Default)]
+pub(crate) struct Migrations {
+    steps: BTreeMap<u64, (u64, MigrationFn)>,
+}
+
+impl Migrations {
+    /// Register `migrate`, taking a tree from version `from` to `to`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `to` isn't greater than `from`, or a migration from `from`
+    /// is already registered.
+    pub(crate) fn add(&mut self, from: u64, to: u64, migrate: MigrationFn) {
+        assert!(
+            to > from,
+            "migration from version {} must go to a later version, not {}",
+            from,
+            to
+        );
+        let previous = self.steps.insert(from, (to, migrate));
+        assert!(
+            previous.is_none(),
+            "a migration from version {} is already registered",
+            from
+        );
+    }
+
+    /// Migrate `tree` to the latest version it has a migration chain to,
+    /// returning the migrations applied in order.
+    ///
+    /// A tree without a `config_version` is taken to be at the oldest
+    /// version a migration starts from. Once migrated, its `config_version`
+    /// is set to the version it reached.
+    pub(crate) fn apply(
+        &self,
+        tree: &mut HashMap<String, config::Value>,
+    ) -> Result<Vec<AppliedMigration>> {
+        let Some(&oldest) = self.steps.keys().next() else {
+            return Ok(Vec::new());
+        };
+        let mut version = match tree.get(VERSION_KEY) {
+            Some(value) => value.clone().into_uint().map_err(|e| {
+                ConfigError::DeserializationError(e.to_string()).at_key(VERSION_KEY)
+            })?,
+            None => oldest,
+        };
+
+        let mut applied = Vec::new();
+        while let Some((to, migrate)) = self.steps.get(&version) {
+            migrate(tree)?;
+            #[cfg(feature = "tracing")]
+            tracing::info!(from = version, to, "Migrated configuration");
+            applied.push(AppliedMigration {
+                from: version,
+                to: *to,
+            });
+            version = *to;
+        }
+        if !applied.is_empty() {
+            tree.insert(VERSION_KEY.to_string(), config::Value::from(version));
+        }
+        Ok(applied)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rename(from: &'static str, to: &'static str) -> MigrationFn {
+        Arc::new(move |tree: &mut HashMap<String, config::Value>| {
+            if let Some(value) = tree.remove(from) {
+                tree.insert(to.to_string(), value);
+            }
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_migration_chain() {
+        let mut migrations = Migrations::default();
+        migrations.add(1, 2, rename("listen", "port"));
+        migrations.add(2, 3, rename("port", "listen_port"));
+
+        // Unversioned trees start at the oldest version
+        let mut tree = HashMap::from([("listen".to_string(), config::Value::from(80))]);
+        let applied = migrations.apply(&mut tree).unwrap();
+        assert_eq!(
+            applied,
+            [
+                AppliedMigration { from: 1, to: 2 },
+                AppliedMigration { from: 2, to: 3 }
+            ]
+        );
+        assert!(tree.contains_key("listen_port"));
+        assert_eq!(tree[VERSION_KEY].clone().into_uint().unwrap(), 3);
+
+        // Versions set as strings, e.g. by environment variables, work too
+        let mut tree = HashMap::from([
+            (VERSION_KEY.to_string(), config::Value::from("2")),
+            ("port".to_string(), config::Value::from(80)),
+        ]);
+        assert_eq!(migrations.apply(&mut tree).unwrap().len(), 1);
+
+        // The latest version is left alone
+        let mut tree = HashMap::from([(VERSION_KEY.to_string(), config::Value::from(3))]);
+        assert!(migrations.apply(&mut tree).unwrap().is_empty());
+
+        let mut tree = HashMap::from([(VERSION_KEY.to_string(), config::Value::from("two"))]);
+        let error = migrations.apply(&mut tree).unwrap_err();
+        assert_eq!(error.key_path(), Some(VERSION_KEY));
+    }
+
+    #[test]
+    #[should_panic(expected = "already registered")]
+    fn test_duplicate_migration_panics() {
+        let mut migrations = Migrations::default();
+        migrations.add(1, 2, rename("a", "b"));
+        migrations.add(1, 3, rename("a", "c"));
+    }
+}
@@ -1,15 +1,91 @@
 //! Core configuration management types.
 
+mod apply;
 mod builder;
 mod config_handle;
+mod derived;
+mod diff;
+#[cfg(feature = "expressions")]
+mod expression;
+mod flight;
+mod generation;
+mod guard;
+mod interpolate;
 mod loader;
+mod migration;
+mod pinned;
+mod policy;
+mod provenance;
+mod registry;
+mod report;
+mod snapshot;
+mod trigger;
+mod verify;
+
+#[cfg(feature = "tokio")]
+mod background;
+#[cfg(feature = "tokio")]
+mod schedule;
+#[cfg(feature = "json-schema")]
+mod schema;
+#[cfg(feature = "signal")]
+mod signal;
+#[cfg(feature = "webhook")]
+mod webhook;
 
 #[cfg(feature = "validation")]
 mod validation;
 
+pub use apply::ApplyHandle;
+pub(crate) use apply::ApplyRegistry;
+#[cfg(feature = "tokio")]
+pub(crate) use background::BackgroundTasks;
 pub use builder::HotswapConfigBuilder;
 pub use config_handle::HotswapConfig;
-pub(crate) use loader::ConfigLoader;
+pub use derived::Derived;
+pub use diff::{ChangeKind, ConfigChange, ConfigDiff};
+pub(crate) use flight::ReloadFlight;
+#[cfg(feature = "tokio")]
+pub(crate) use flight::{Flight, ReloadThrottle, Slot, Turn};
+pub use generation::Snapshot;
+pub use guard::ConfigGuard;
+#[cfg(feature = "file-watch")]
+pub(crate) use loader::reads_only;
+pub(crate) use loader::{
+    ConfigLoader, PreLoadHook, Refresh, SectionValidator, merge_value, set_path,
+};
+pub use loader::{ErrorMode, ImmutableKeys, SkippedSource, UnknownKeys};
+pub use migration::AppliedMigration;
+pub(crate) use migration::Migrations;
+pub use pinned::Pinned;
+pub(crate) use policy::UpdatePolicy;
+pub use policy::{PolicyDecision, UpdateContext, UpdateOrigin};
+pub(crate) use provenance::Provenance;
+pub use provenance::{Contribution, Explanation};
+pub use registry::ConfigRegistry;
+#[cfg(feature = "file-watch")]
+pub(crate) use registry::SharedWatcher;
+pub use report::SourceReport;
+pub use snapshot::SnapshotFormat;
+pub use trigger::ReloadReason;
+pub(crate) use verify::{AsyncValidator, verify};
+#[cfg(feature = "tokio")]
+pub(crate) use verify::{VerifyFuture, with_timeout};
+
+#[cfg(feature = "serde_json")]
+pub use report::ConfigReport;
+#[cfg(feature = "tokio")]
+pub use schedule::Schedule;
+#[cfg(feature = "json-schema")]
+pub use schema::ConfigSchema;
+#[cfg(feature = "signal")]
+pub use signal::Signal;
+#[cfg(feature = "file-watch")]
+pub(crate) use trigger::FileTrigger;
+#[cfg(feature = "tokio")]
+pub(crate) use trigger::SourceTrigger;
+#[cfg(feature = "tokio")]
+pub use trigger::{ReloadTrigger, TriggerFuture};
 
 #[cfg(feature = "validation")]
 pub use validation::Validate;
@@ -0,0 +1,187 @@
+//! Per-thread cached reads of the configuration.
+
+use arc_swap::ArcSwap;
+use std::fmt;
+use std::ops::Deref;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Default number of reads between checks for a newer configuration.
+const DEFAULT_CHECK_EVERY: u32 = 1024;
+
+/// A configuration cached by one reader and only occasionally revalidated.
+///
+/// Returned by [`HotswapConfig::pin`](crate::core::HotswapConfig::pin), for
+/// services doing tens of millions of reads per second where even
+/// [`load`](crate::core::HotswapConfig::load) shows up in profiles. Reads
+/// through [`get`](Self::get) return the cached `Arc<T>` and only compare
+/// the handle's generation counter every N reads (1024 by default, see
+/// [`with_check_every`](Self::with_check_every)), reloading the `Arc` when
+/// it changed. A new configuration is therefore seen up to N reads late;
+/// [`with_max_age`](Self::with_max_age) also bounds that in time.
+///
+/// Each reader keeps its own `Pinned`, e.g. per worker thread or in a
+/// `thread_local!`, since reading takes `&mut self`.
+///
+/// # Examples
+///
+/// ```rust
+/// use hotswap_config::prelude::*;
+/// use std::time::Duration;
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<()> {
+/// let config = HotswapConfig::new(8080);
+/// let mut pinned = config
+///     .pin()
+///     .with_check_every(100)
+///     .with_max_age(Duration::from_millis(10));
+/// assert_eq!(**pinned.get(), 8080);
+///
+/// config.update(9090).await?;
+/// assert_eq!(**pinned.refresh(), 9090);
+/// # Ok(())
+/// # }
+/// ```
+pub struct Pinned<T> {
+    current: Arc<ArcSwap<T>>,
+    generation: Arc<AtomicU64>,
+    cached: Arc<T>,
+    cached_generation: u64,
+    /// Reads left until the next check
+    reads_left: u32,
+    check_every: u32,
+    max_age: Option<Duration>,
+    checked_at: Instant,
+}
+
+impl<T> Pinned<T> {
+    /// Pin the configuration in `current`, versioned by `generation`.
+    pub(crate) fn new(current: Arc<ArcSwap<T>>, generation: Arc<AtomicU64>) -> Self {
+        // Read the generation first, so the cache is never labeled newer
+        // than its config
+        let cached_generation = generation.load(Ordering::Acquire);
+        let cached = current.load_full();
+        Self {
+            current,
+            generation,
+            cached,
+            cached_generation,
+            reads_left: DEFAULT_CHECK_EVERY,
+            check_every: DEFAULT_CHECK_EVERY,
+            max_age: None,
+            checked_at: Instant::now(),
+        }
+    }
+
+    /// Check for a newer configuration every `reads` reads (at least 1).
+    pub fn with_check_every(mut self, reads: u32) -> Self {
+        self.check_every = reads.max(1);
+        self.reads_left = self.check_every;
+        self
+    }
+
+    /// Also check for a newer configuration once the cached one was last
+    /// checked more than `max_age` ago.
+    ///
+    /// This reads the clock on every [`get`](Self::get), which costs more
+    /// than the counter alone.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Get the cached configuration, checking for a newer one if a check is
+    /// due.
+    pub fn get(&mut self) -> &Arc<T> {
+        self.reads_left -= 1;
+        let expired = self
+            .max_age
+            .is_some_and(|max_age| self.checked_at.elapsed() >= max_age);
+        if self.reads_left == 0 || expired {
+            self.refresh();
+        }
+        &self.cached
+    }
+
+    /// Check for a newer configuration now, and return the current one.
+    pub fn refresh(&mut self) -> &Arc<T> {
+        self.reads_left = self.check_every;
+        if self.max_age.is_some() {
+            self.checked_at = Instant::now();
+        }
+        let generation = self.generation.load(Ordering::Acquire);
+        if generation != self.cached_generation {
+            self.cached = self.current.load_full();
+            self.cached_generation = generation;
+        }
+        &self.cached
+    }
+
+    /// The generation of the cached configuration.
+    pub fn generation(&self) -> u64 {
+        self.cached_generation
+    }
+
+    /// Whether the handle has swapped in a newer configuration than the
+    /// cached one.
+    pub fn is_stale(&self) -> bool {
+        self.generation.load(Ordering::Acquire) != self.cached_generation
+    }
+}
+
+impl<T> Deref for Pinned<T> {
+    type Target = T;
+
+    /// The cached configuration, without checking for a newer one.
+    fn deref(&self) -> &T {
+        &self.cached
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Pinned<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pinned")
+            .field("generation", &self.cached_generation)
+            .field("config", &self.cached)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_revalidates_every_n_reads() {
+        let current = Arc::new(ArcSwap::from_pointee(1));
+        let generation = Arc::new(AtomicU64::new(0));
+        let mut pinned =
+            Pinned::new(Arc::clone(&current), Arc::clone(&generation)).with_check_every(3);
+
+        current.store(Arc::new(2));
+        generation.fetch_add(1, Ordering::Release);
+        assert!(pinned.is_stale());
+
+        // Served from cache until the third read
+        assert_eq!(**pinned.get(), 1);
+        assert_eq!(**pinned.get(), 1);
+        assert_eq!(**pinned.get(), 2);
+        assert_eq!(pinned.generation(), 1);
+        assert!(!pinned.is_stale());
+    }
+
+    #[test]
+    fn test_max_age() {
+        let current = Arc::new(ArcSwap::from_pointee(1));
+        let generation = Arc::new(AtomicU64::new(0));
+        let mut pinned = Pinned::new(Arc::clone(&current), Arc::clone(&generation))
+            .with_check_every(u32::MAX)
+            .with_max_age(Duration::ZERO);
+
+        current.store(Arc::new(2));
+        generation.fetch_add(1, Ordering::Release);
+        assert_eq!(**pinned.get(), 2);
+    }
+}
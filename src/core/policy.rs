@@ -0,0 +1,74 @@
+//! Authorization of programmatic updates and patches.
+
+use std::sync::Arc;
+
+/// Type alias for the policy deciding whether an update may be applied.
+pub(crate) type UpdatePolicy =
+    Arc<dyn Fn(&[String], &UpdateContext) -> PolicyDecision + Send + Sync>;
+
+/// How an update was requested.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum UpdateOrigin {
+    /// [`HotswapConfig::update`](crate::core::HotswapConfig::update) or
+    /// [`update_with_context`](crate::core::HotswapConfig::update_with_context)
+    #[default]
+    Update,
+    /// A JSON Patch or merge patch (`partial-updates` feature)
+    Patch,
+    /// A rollback to a configuration from history (`rollback` feature)
+    Rollback,
+    /// The admin API (`admin` feature)
+    Admin,
+}
+
+/// Who is updating the configuration and how, passed to the
+/// [update policy](crate::core::HotswapConfigBuilder::with_update_policy).
+///
+/// # Examples
+///
+/// ```rust
+/// use hotswap_config::core::{UpdateContext, UpdateOrigin};
+///
+/// let context = UpdateContext::new(UpdateOrigin::Patch).with_actor("alice");
+/// assert_eq!(context.actor.as_deref(), Some("alice"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UpdateContext {
+    /// How the update was requested
+    pub origin: UpdateOrigin,
+    /// Who requested it, if known (e.g. an authenticated user)
+    pub actor: Option<String>,
+}
+
+impl UpdateContext {
+    /// Create a context for an update requested through `origin`.
+    pub fn new(origin: UpdateOrigin) -> Self {
+        Self {
+            origin,
+            actor: None,
+        }
+    }
+
+    /// Set who requested the update.
+    pub fn with_actor(mut self, actor: impl Into<String>) -> Self {
+        self.actor = Some(actor.into());
+        self
+    }
+}
+
+/// Whether an update policy lets an update through.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolicyDecision {
+    /// Apply the update
+    Allow,
+    /// Reject the update, for the given reason
+    Deny(String),
+}
+
+impl PolicyDecision {
+    /// Reject the update, for `reason`.
+    pub fn deny(reason: impl Into<String>) -> Self {
+        Self::Deny(reason.into())
+    }
+}
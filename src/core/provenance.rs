@@ -0,0 +1,276 @@
+//! Tracking which source set each key of a merged configuration.
+
+use crate::core::SourceReport;
+use config::{Map, Value, ValueKind};
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
+
+/// A value a single source set for a key.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contribution {
+    /// Dotted path of the key the source set, e.g. `database.pool_size`
+    pub key: String,
+    /// Name of the source, e.g. `file:config/default.yaml` or `env:APP_*`
+    pub source: String,
+    /// Priority of the source
+    pub priority: i32,
+    /// The value as the source set it, before interpolation
+    pub value: Value,
+    /// Whether this value is the one in the effective config
+    pub won: bool,
+}
+
+/// The effective value of a key and every source that set it.
+///
+/// Returned by [`HotswapConfig::explain`](crate::core::HotswapConfig::explain).
+/// Values are shown as the sources set them, so secrets are not redacted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Explanation {
+    /// Dotted path of the explained key
+    pub key: String,
+    /// Effective value of the key, `None` if no source set it
+    pub value: Option<Value>,
+    /// Values set by sources for the key (or keys nested under it), in merge
+    /// order
+    pub contributions: Vec<Contribution>,
+}
+
+impl Explanation {
+    /// Get the contributions that made it into the effective config.
+    pub fn winners(&self) -> impl Iterator<Item = &Contribution> {
+        self.contributions.iter().filter(|c| c.won)
+    }
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.value {
+            Some(value) => writeln!(f, "{} = {}", self.key, value)?,
+            None => writeln!(f, "{} is not set", self.key)?,
+        }
+        for contribution in &self.contributions {
+            write!(
+                f,
+                "  {} (priority {}): ",
+                contribution.source, contribution.priority
+            )?;
+            if contribution.key != self.key {
+                write!(f, "{} = ", contribution.key)?;
+            }
+            write!(f, "{}", contribution.value)?;
+            if contribution.won {
+                write!(f, "  <- effective")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Where each key of a merged configuration came from.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Provenance {
+    /// The merged tree, after interpolation
+    merged: Map<String, Value>,
+    /// Leaf values each source set, in merge order
+    contributions: Vec<Contribution>,
+    /// Each merged source, in merge order
+    sources: Vec<SourceReport>,
+}
+
+impl Provenance {
+    /// Record the leaf values a source set, overriding earlier sources.
+    pub(crate) fn record<'a>(
+        &mut self,
+        source: &str,
+        priority: i32,
+        values: impl IntoIterator<Item = (&'a String, &'a Value)>,
+    ) {
+        self.record_loaded(source, priority, values, None);
+    }
+
+    /// Like [`record`](Self::record), for values loaded in `duration` rather
+    /// than taken from the cache.
+    pub(crate) fn record_loaded<'a>(
+        &mut self,
+        source: &str,
+        priority: i32,
+        values: impl IntoIterator<Item = (&'a String, &'a Value)>,
+        duration: Option<Duration>,
+    ) {
+        let mut leaves = Vec::new();
+        flatten(None, values, &mut leaves);
+        self.sources.push(SourceReport {
+            name: source.to_string(),
+            priority,
+            keys: leaves.len(),
+            load_duration: duration,
+        });
+        self.contributions
+            .extend(leaves.into_iter().map(|(key, value)| Contribution {
+                key,
+                source: source.to_string(),
+                priority,
+                value,
+                won: false,
+            }));
+    }
+
+    /// Finish recording with the merged tree, marking the values that won.
+    pub(crate) fn finish(mut self, merged: &Map<String, Value>) -> Self {
+        let mut last = HashMap::new();
+        for (i, contribution) in self.contributions.iter().enumerate() {
+            last.insert(contribution.key.clone(), i);
+        }
+        for (key, i) in last {
+            // A later source can replace a table with a scalar, dropping keys
+            // nested under it
+            if lookup(merged, &key).is_some() {
+                self.contributions[i].won = true;
+            }
+        }
+        self.merged = merged.clone();
+        self
+    }
+
+    /// Get the merged sources, in merge order.
+    #[cfg(feature = "serde_json")]
+    pub(crate) fn sources(&self) -> &[SourceReport] {
+        &self.sources
+    }
+
+    /// Get the contributions that made it into the effective config.
+    #[cfg(feature = "serde_json")]
+    pub(crate) fn winners(&self) -> impl Iterator<Item = &Contribution> {
+        self.contributions.iter().filter(|c| c.won)
+    }
+
+    /// Get the effective value at dotted path `key`.
+    pub(crate) fn value(&self, key: &str) -> Option<&Value> {
+        lookup(&self.merged, key)
+    }
+
+    /// Explain the effective value of `key` and which sources set it.
+    pub(crate) fn explain(&self, key: &str) -> Explanation {
+        let nested = format!("{}.", key);
+        Explanation {
+            key: key.to_string(),
+            value: lookup(&self.merged, key).cloned(),
+            contributions: self
+                .contributions
+                .iter()
+                .filter(|c| c.key == key || c.key.starts_with(&nested))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+/// Collect the leaf values of a tree by dotted path. Arrays are leaves, since
+/// a higher priority source replaces them as a whole.
+fn flatten<'a>(
+    prefix: Option<&str>,
+    table: impl IntoIterator<Item = (&'a String, &'a Value)>,
+    leaves: &mut Vec<(String, Value)>,
+) {
+    for (key, value) in table {
+        let path = match prefix {
+            Some(prefix) => format!("{}.{}", prefix, key),
+            None => key.clone(),
+        };
+        match &value.kind {
+            ValueKind::Table(nested) if !nested.is_empty() => flatten(Some(&path), nested, leaves),
+            _ => leaves.push((path, value.clone())),
+        }
+    }
+}
+
+/// Look up a dotted path in a tree.
+pub(super) fn lookup<'a>(table: &'a Map<String, Value>, key: &str) -> Option<&'a Value> {
+    let mut segments = key.split('.');
+    let mut value = table.get(segments.next()?)?;
+    for segment in segments {
+        match &value.kind {
+            ValueKind::Table(nested) => value = nested.get(segment)?,
+            _ => return None,
+        }
+    }
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(entries: &[(&str, Value)]) -> Map<String, Value> {
+        entries
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_explain_marks_winner() {
+        let defaults = table(&[(
+            "database",
+            Value::from(table(&[
+                ("pool_size", Value::from(10i64)),
+                ("url", Value::from("postgres://localhost/db")),
+            ])),
+        )]);
+        let overrides = table(&[(
+            "database",
+            Value::from(table(&[("pool_size", Value::from(20i64))])),
+        )]);
+        let mut merged = defaults.clone();
+        crate::core::merge_value(
+            &mut merged,
+            "database".to_string(),
+            overrides["database"].clone(),
+        );
+
+        let mut provenance = Provenance::default();
+        provenance.record("file:default.yaml", 100, &defaults);
+        provenance.record("env:APP_*", 300, &overrides);
+        let provenance = provenance.finish(&merged);
+
+        let explanation = provenance.explain("database.pool_size");
+        assert_eq!(explanation.value, Some(Value::from(20i64)));
+        assert_eq!(explanation.contributions.len(), 2);
+        let winners: Vec<_> = explanation.winners().map(|c| c.source.as_str()).collect();
+        assert_eq!(winners, ["env:APP_*"]);
+        assert!(
+            explanation
+                .to_string()
+                .contains("env:APP_* (priority 300): 20  <- effective")
+        );
+
+        // Tables gather the keys nested under them
+        let explanation = provenance.explain("database");
+        assert_eq!(explanation.contributions.len(), 3);
+        assert_eq!(explanation.winners().count(), 2);
+
+        let explanation = provenance.explain("database.missing");
+        assert_eq!(explanation.value, None);
+        assert!(explanation.contributions.is_empty());
+    }
+
+    #[test]
+    fn test_replaced_table_keys_do_not_win() {
+        let defaults = table(&[("cache", Value::from(table(&[("ttl", Value::from(60i64))])))]);
+        let overrides = table(&[("cache", Value::from(false))]);
+        let mut merged = defaults.clone();
+        crate::core::merge_value(&mut merged, "cache".to_string(), overrides["cache"].clone());
+
+        let mut provenance = Provenance::default();
+        provenance.record("defaults", 0, &defaults);
+        provenance.record("file:config.yaml", 100, &overrides);
+        let provenance = provenance.finish(&merged);
+
+        let explanation = provenance.explain("cache");
+        assert_eq!(explanation.value, Some(Value::from(false)));
+        let winners: Vec<_> = explanation.winners().map(|c| c.key.as_str()).collect();
+        assert_eq!(winners, ["cache"]);
+    }
+}
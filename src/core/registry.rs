@@ -0,0 +1,375 @@
+//! A registry of configuration handles for applications with several configs.
+
+use crate::core::{HotswapConfig, HotswapConfigBuilder};
+use crate::error::{ConfigError, Result};
+use crate::metrics::MetricsRecorder;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::any::Any;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, RwLock};
+
+#[cfg(feature = "file-watch")]
+use crate::notify::ConfigWatcher;
+#[cfg(feature = "file-watch")]
+use std::path::PathBuf;
+#[cfg(feature = "file-watch")]
+use std::sync::Mutex;
+#[cfg(feature = "file-watch")]
+use std::time::Duration;
+
+/// Type alias for a type-erased reload of one registered handle.
+type ReloadFn = Box<dyn Fn() -> Pin<Box<dyn Future<Output = Result<()>> + Send>> + Send + Sync>;
+
+/// A registered handle and type-erased access to it.
+struct Entry {
+    name: String,
+    handle: Box<dyn Any + Send + Sync>,
+    reload: ReloadFn,
+    #[cfg(feature = "admin-api")]
+    json: Box<dyn Fn() -> Result<serde_json::Value> + Send + Sync>,
+}
+
+/// Manages several [`HotswapConfig`] handles, e.g. one per subsystem, under
+/// unique names.
+///
+/// Handles built with [`builder`](Self::builder) share the registry's
+/// metrics recorder and, when file watching is enabled, a single file
+/// watcher. Registered handles can be looked up by type or name, reloaded
+/// together, and served from one admin endpoint (see `admin_router`).
+///
+/// Cloning is cheap; clones share the same handles.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use hotswap_config::core::ConfigRegistry;
+/// use hotswap_config::prelude::*;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Debug, Serialize, Deserialize, Clone)]
+/// struct DatabaseConfig {
+///     url: String,
+/// }
+///
+/// #[derive(Debug, Serialize, Deserialize, Clone)]
+/// struct CacheConfig {
+///     ttl_secs: u64,
+/// }
+///
+/// # async fn example() -> Result<()> {
+/// let registry = ConfigRegistry::new();
+///
+/// let database = registry
+///     .builder::<DatabaseConfig>()
+///     .with_file("database.yaml")
+///     .with_file_watch(true)
+///     .build()
+///     .await?;
+/// registry.register("database", database)?;
+///
+/// let cache = registry
+///     .builder::<CacheConfig>()
+///     .with_file("cache.yaml")
+///     .with_file_watch(true)
+///     .build()
+///     .await?;
+/// registry.register("cache", cache)?;
+///
+/// let ttl = registry.get::<CacheConfig>().unwrap().get().ttl_secs;
+/// let database = registry.get_named::<DatabaseConfig>("database").unwrap();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ConfigRegistry {
+    entries: Arc<RwLock<Vec<Arc<Entry>>>>,
+    metrics: Option<Arc<dyn MetricsRecorder>>,
+    #[cfg(feature = "file-watch")]
+    watcher: Arc<SharedWatcher>,
+}
+
+impl ConfigRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self {
+            entries: Arc::default(),
+            metrics: None,
+            #[cfg(feature = "file-watch")]
+            watcher: Arc::new(SharedWatcher::new(Duration::from_millis(500))),
+        }
+    }
+
+    /// Record metrics for every handle built with [`builder`](Self::builder)
+    /// with one recorder.
+    pub fn with_metrics_recorder<R: MetricsRecorder + 'static>(mut self, recorder: R) -> Self {
+        self.metrics = Some(Arc::new(recorder));
+        self
+    }
+
+    /// Set the debounce duration of the shared file watcher.
+    ///
+    /// Replaces the builders' own
+    /// [`with_watch_debounce`](HotswapConfigBuilder::with_watch_debounce).
+    /// Defaults to 500ms.
+    #[cfg(feature = "file-watch")]
+    pub fn with_watch_debounce(mut self, duration: Duration) -> Self {
+        self.watcher = Arc::new(SharedWatcher::new(duration));
+        self
+    }
+
+    /// Create a builder whose handle shares this registry's metrics recorder
+    /// and file watcher.
+    ///
+    /// The built handle still has to be [`register`](Self::register)ed to be
+    /// looked up.
+    pub fn builder<T>(&self) -> HotswapConfigBuilder<T> {
+        let builder = HotswapConfigBuilder::new().with_shared_metrics(self.metrics.clone());
+        #[cfg(feature = "file-watch")]
+        let builder = builder.with_shared_watcher(Arc::clone(&self.watcher));
+        builder
+    }
+
+    /// Register `config` under `name`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigError::Other`] if a handle is already registered under
+    /// `name`; the existing handle is kept.
+    pub fn register<T>(&self, name: impl Into<String>, config: HotswapConfig<T>) -> Result<()>
+    where
+        T: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+    {
+        let name = name.into();
+        let mut entries = self.entries.write().unwrap();
+        if entries.iter().any(|entry| entry.name == name) {
+            return Err(ConfigError::Other(format!(
+                "A configuration named '{}' is already registered",
+                name
+            )));
+        }
+
+        let reload_config = config.clone();
+        #[cfg(feature = "admin-api")]
+        let json_config = config.clone();
+        entries.push(Arc::new(Entry {
+            name,
+            handle: Box::new(config),
+            reload: Box::new(move || {
+                let config = reload_config.clone();
+                Box::pin(async move { config.reload().await })
+            }),
+            #[cfg(feature = "admin-api")]
+            json: Box::new(move || json_config.get_redacted_json()),
+        }));
+        Ok(())
+    }
+
+    /// Get the first registered handle for config type `T`.
+    pub fn get<T>(&self) -> Option<HotswapConfig<T>>
+    where
+        T: Send + Sync + 'static,
+    {
+        self.entries
+            .read()
+            .unwrap()
+            .iter()
+            .find_map(|entry| entry.handle.downcast_ref::<HotswapConfig<T>>())
+            .cloned()
+    }
+
+    /// Get the handle registered under `name`, or `None` if there is none or
+    /// it isn't a `HotswapConfig<T>`.
+    pub fn get_named<T>(&self, name: &str) -> Option<HotswapConfig<T>>
+    where
+        T: Send + Sync + 'static,
+    {
+        self.entry(name)?
+            .handle
+            .downcast_ref::<HotswapConfig<T>>()
+            .cloned()
+    }
+
+    /// Names of the registered handles, in registration order.
+    pub fn names(&self) -> Vec<String> {
+        self.entries
+            .read()
+            .unwrap()
+            .iter()
+            .map(|entry| entry.name.clone())
+            .collect()
+    }
+
+    /// Reload the handle registered under `name` from all its sources.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigError::Other`] if nothing is registered under `name`,
+    /// or the reload error.
+    pub async fn reload(&self, name: &str) -> Result<()> {
+        let entry = self.entry(name).ok_or_else(|| {
+            ConfigError::Other(format!("No configuration named '{}' is registered", name))
+        })?;
+        (entry.reload)().await
+    }
+
+    /// Reload every registered handle.
+    ///
+    /// All handles are reloaded even if some fail.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first reload error.
+    pub async fn reload_all(&self) -> Result<()> {
+        let entries = self.entries.read().unwrap().clone();
+        let mut result = Ok(());
+        for entry in entries {
+            let reloaded = (entry.reload)().await;
+            if result.is_ok() {
+                result = reloaded;
+            }
+        }
+        result
+    }
+
+    /// Build an admin router serving every registered handle.
+    ///
+    /// | Method | Path | Description |
+    /// |--------|------|-------------|
+    /// | `GET` | `/configs` | Names of the registered handles |
+    /// | `GET` | `/configs/{name}` | Current config as JSON (secrets redacted) |
+    /// | `POST` | `/configs/{name}/reload` | Reload from all sources |
+    ///
+    /// Handles registered after the router is built are served too.
+    #[cfg(feature = "admin-api")]
+    pub fn admin_router(&self) -> axum::Router {
+        crate::admin::registry_router(self.clone())
+    }
+
+    /// Current config registered under `name` as redacted JSON.
+    #[cfg(feature = "admin-api")]
+    pub(crate) fn redacted_json(&self, name: &str) -> Option<Result<serde_json::Value>> {
+        self.entry(name).map(|entry| (entry.json)())
+    }
+
+    fn entry(&self, name: &str) -> Option<Arc<Entry>> {
+        self.entries
+            .read()
+            .unwrap()
+            .iter()
+            .find(|entry| entry.name == name)
+            .cloned()
+    }
+}
+
+impl Default for ConfigRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Type alias for a handle's reaction to a batch of changed files.
+#[cfg(feature = "file-watch")]
+pub(crate) type WatchTarget =
+    Arc<dyn Fn(Arc<[PathBuf]>) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
+
+/// One file watcher whose changes are dispatched to several handles.
+#[cfg(feature = "file-watch")]
+pub(crate) struct SharedWatcher {
+    debounce: Duration,
+    /// Created on first use, from within the runtime
+    watcher: Mutex<Option<Arc<ConfigWatcher>>>,
+    targets: Arc<RwLock<Vec<WatchTarget>>>,
+}
+
+#[cfg(feature = "file-watch")]
+impl SharedWatcher {
+    fn new(debounce: Duration) -> Self {
+        Self {
+            debounce,
+            watcher: Mutex::new(None),
+            targets: Arc::default(),
+        }
+    }
+
+    /// Get the watcher, starting it and its dispatch task on first use.
+    pub(crate) fn watcher(&self) -> Result<Arc<ConfigWatcher>> {
+        let mut slot = self.watcher.lock().unwrap();
+        if let Some(watcher) = slot.as_ref() {
+            return Ok(Arc::clone(watcher));
+        }
+
+        let (watcher, mut rx) = ConfigWatcher::new(self.debounce)
+            .map_err(|e| ConfigError::Other(format!("Failed to create file watcher: {}", e)))?;
+        let targets = Arc::clone(&self.targets);
+        tokio::spawn(async move {
+            while let Some(changed) = rx.recv().await {
+                let changed: Arc<[PathBuf]> = changed.into();
+                let current = targets.read().unwrap().clone();
+                for target in current {
+                    target(Arc::clone(&changed)).await;
+                }
+            }
+        });
+
+        let watcher = Arc::new(watcher);
+        *slot = Some(Arc::clone(&watcher));
+        Ok(watcher)
+    }
+
+    /// Pass every batch of changed files to `target`.
+    pub(crate) fn add_target(&self, target: WatchTarget) {
+        self.targets.write().unwrap().push(target);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+    struct DatabaseConfig {
+        url: String,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+    struct CacheConfig {
+        ttl_secs: u64,
+    }
+
+    #[tokio::test]
+    async fn test_lookup_by_type_and_name() {
+        let registry = ConfigRegistry::new();
+        registry
+            .register(
+                "database",
+                HotswapConfig::new(DatabaseConfig {
+                    url: "postgres://localhost".into(),
+                }),
+            )
+            .unwrap();
+        registry
+            .register("cache", HotswapConfig::new(CacheConfig { ttl_secs: 60 }))
+            .unwrap();
+
+        assert_eq!(registry.names(), ["database", "cache"]);
+        assert_eq!(registry.get::<CacheConfig>().unwrap().get().ttl_secs, 60);
+        assert!(registry.get_named::<DatabaseConfig>("database").is_some());
+        assert!(registry.get_named::<CacheConfig>("database").is_none());
+        assert!(registry.get_named::<CacheConfig>("missing").is_none());
+
+        // Names are unique, and the first registration wins
+        let duplicate = HotswapConfig::new(CacheConfig { ttl_secs: 1 });
+        assert!(registry.register("cache", duplicate).is_err());
+        assert_eq!(registry.get::<CacheConfig>().unwrap().get().ttl_secs, 60);
+    }
+
+    #[tokio::test]
+    async fn test_reload_unknown_name() {
+        let registry = ConfigRegistry::new();
+        assert!(registry.reload("missing").await.is_err());
+        assert!(registry.reload_all().await.is_ok());
+    }
+}
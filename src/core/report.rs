@@ -0,0 +1,105 @@
+//! A summary of how the configuration was loaded, for logging at startup.
+
+use std::time::Duration;
+
+#[cfg(feature = "serde_json")]
+use crate::core::{AppliedMigration, SkippedSource};
+#[cfg(feature = "serde_json")]
+use crate::error::ValidationError;
+#[cfg(feature = "serde_json")]
+use std::fmt;
+
+/// How a configuration was loaded: its sources, the keys they contributed,
+/// and the effective values with secrets redacted.
+///
+/// Returned by [`HotswapConfig::report`](crate::core::HotswapConfig::report).
+/// Its `Display` output is meant to be logged once at boot.
+#[cfg(feature = "serde_json")]
+#[derive(Debug)]
+pub struct ConfigReport {
+    /// Profile selected when the config was built
+    pub profile: Option<String>,
+    /// Whether the config is a snapshot or defaults served because sources
+    /// failed at startup
+    pub fallback: bool,
+    /// Sources merged into the config, in merge order; empty after a manual
+    /// update
+    pub sources: Vec<SourceReport>,
+    /// Sources that failed during the last load and were skipped or served
+    /// from cache
+    pub skipped: Vec<SkippedSource>,
+    /// Migrations applied to bring the sources to the latest schema version
+    pub migrations: Vec<AppliedMigration>,
+    /// Keys whose effective value was set by environment variables
+    pub env_overrides: Vec<String>,
+    /// The effective config as JSON, with secret fields masked
+    pub values: serde_json::Value,
+    /// Result of running the config's validators against it
+    pub validation: std::result::Result<(), ValidationError>,
+}
+
+/// A source merged into the configuration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceReport {
+    /// Name of the source, e.g. `file:config/default.yaml` or `env:APP_*`
+    pub name: String,
+    /// Priority of the source
+    pub priority: i32,
+    /// Number of keys the source set
+    pub keys: usize,
+    /// How long loading the source took; `None` if its values were taken
+    /// from the last load because it didn't change
+    pub load_duration: Option<Duration>,
+}
+
+#[cfg(feature = "serde_json")]
+impl fmt::Display for ConfigReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Configuration (profile: {}){}",
+            self.profile.as_deref().unwrap_or("none"),
+            if self.fallback { " [fallback]" } else { "" }
+        )?;
+
+        writeln!(f, "Sources:")?;
+        for source in &self.sources {
+            write!(
+                f,
+                "  {} (priority {}): {} keys",
+                source.name, source.priority, source.keys
+            )?;
+            match source.load_duration {
+                Some(duration) => writeln!(f, " in {:.1?}", duration)?,
+                None => writeln!(f, " (cached)")?,
+            }
+        }
+        for skipped in &self.skipped {
+            let served = if skipped.served_from_cache {
+                "served from cache"
+            } else {
+                "skipped"
+            };
+            writeln!(f, "  {} {}: {}", skipped.name, served, skipped.error)?;
+        }
+
+        if !self.migrations.is_empty() {
+            let migrations: Vec<String> = self.migrations.iter().map(|m| m.to_string()).collect();
+            writeln!(f, "Migrations: {}", migrations.join(", "))?;
+        }
+        if !self.env_overrides.is_empty() {
+            writeln!(
+                f,
+                "Environment overrides: {}",
+                self.env_overrides.join(", ")
+            )?;
+        }
+        match &self.validation {
+            Ok(()) => writeln!(f, "Validation: passed")?,
+            Err(e) => writeln!(f, "Validation: failed: {}", e)?,
+        }
+
+        let values = serde_json::to_string_pretty(&self.values).map_err(|_| fmt::Error)?;
+        write!(f, "Values: {}", values)
+    }
+}
@@ -0,0 +1,123 @@
+//! Schedules for forced periodic reloads.
+
+use std::time::Duration;
+
+#[cfg(feature = "cron")]
+use crate::error::{ConfigError, Result};
+
+/// When to reload every source, whether or not anything reported a change.
+///
+/// Use with
+/// [`HotswapConfigBuilder::with_reload_schedule`](crate::core::HotswapConfigBuilder::with_reload_schedule)
+/// where file events are unreliable (NFS mounts, some container volumes), so
+/// the config is re-evaluated at least that often. A scheduled reload that
+/// finds nothing changed is skipped like any other.
+///
+/// # Examples
+///
+/// ```rust
+/// use hotswap_config::core::Schedule;
+/// use std::time::Duration;
+///
+/// let schedule = Schedule::every(Duration::from_secs(300));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Schedule {
+    kind: Kind,
+}
+
+#[derive(Debug, Clone)]
+enum Kind {
+    Every(Duration),
+    #[cfg(feature = "cron")]
+    Cron(Box<cron::Schedule>),
+}
+
+impl Schedule {
+    /// Reload every `interval`, starting one interval after the build.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `interval` is zero.
+    pub fn every(interval: Duration) -> Self {
+        assert!(!interval.is_zero(), "reload interval must be non-zero");
+        Self {
+            kind: Kind::Every(interval),
+        }
+    }
+
+    /// Reload at the times matched by a cron expression, evaluated in UTC.
+    ///
+    /// Accepts the standard five fields (`minute hour day month weekday`), or
+    /// six or seven with leading seconds and trailing year.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigError::ParseError`] if the expression is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hotswap_config::core::Schedule;
+    ///
+    /// // Every 15 minutes
+    /// let schedule = Schedule::cron("*/15 * * * *").unwrap();
+    /// assert!(Schedule::cron("every tuesday").is_err());
+    /// ```
+    #[cfg(feature = "cron")]
+    pub fn cron(expression: &str) -> Result<Self> {
+        let fields = expression.split_whitespace().count();
+        let expression = if fields == 5 {
+            format!("0 {}", expression.trim())
+        } else {
+            expression.to_string()
+        };
+        let schedule = expression.parse::<cron::Schedule>().map_err(|e| {
+            ConfigError::ParseError(format!("Invalid cron expression '{}': {}", expression, e))
+        })?;
+        Ok(Self {
+            kind: Kind::Cron(Box::new(schedule)),
+        })
+    }
+
+    /// Get the time until the next scheduled reload, or `None` if the
+    /// schedule has no more occurrences.
+    pub(crate) fn next_delay(&self) -> Option<Duration> {
+        match &self.kind {
+            Kind::Every(interval) => Some(*interval),
+            #[cfg(feature = "cron")]
+            Kind::Cron(schedule) => {
+                let now = chrono::Utc::now();
+                let next = schedule.after(&now).next()?;
+                Some((next - now).to_std().unwrap_or_default())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_repeats_interval() {
+        let schedule = Schedule::every(Duration::from_secs(60));
+        assert_eq!(schedule.next_delay(), Some(Duration::from_secs(60)));
+    }
+
+    #[cfg(feature = "cron")]
+    #[test]
+    fn test_cron_accepts_five_and_six_fields() {
+        let minutely = Schedule::cron("* * * * *").unwrap();
+        let delay = minutely.next_delay().unwrap();
+        assert!(delay <= Duration::from_secs(60));
+
+        let secondly = Schedule::cron("* * * * * *").unwrap();
+        assert!(secondly.next_delay().unwrap() <= Duration::from_secs(1));
+
+        assert!(matches!(
+            Schedule::cron("61 * * * *"),
+            Err(ConfigError::ParseError(_))
+        ));
+    }
+}
@@ -0,0 +1,211 @@
+//! JSON Schema validation of the raw merged configuration tree.
+
+use crate::error::{ConfigError, Result, ValidationError};
+use serde_json::Value as JsonValue;
+
+/// A compiled JSON Schema used to validate the merged configuration.
+///
+/// Validation runs against the raw merged tree (after all sources are merged,
+/// before deserialization into `T`), so errors point at the exact key path
+/// (e.g. `server.port`) rather than surfacing as a single opaque serde error.
+///
+/// # Examples
+///
+/// ```rust
+/// use hotswap_config::core::ConfigSchema;
+/// use serde_json::json;
+///
+/// let schema = ConfigSchema::new(json!({
+///     "type": "object",
+///     "properties": {
+///         "port": { "type": "integer", "minimum": 1024 }
+///     },
+///     "required": ["port"]
+/// }))
+/// .unwrap();
+///
+/// assert!(schema.validate(&json!({ "port": 8080 })).is_ok());
+/// assert!(schema.validate(&json!({ "port": "http" })).is_err());
+/// ```
+pub struct ConfigSchema {
+    schema: JsonValue,
+    validator: jsonschema::Validator,
+}
+
+impl ConfigSchema {
+    /// Compile a JSON Schema document.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the schema itself is invalid.
+    pub fn new(schema: JsonValue) -> Result<Self> {
+        let validator = jsonschema::validator_for(&schema)
+            .map_err(|e| ConfigError::Other(format!("Invalid JSON Schema: {}", e)))?;
+
+        Ok(Self { schema, validator })
+    }
+
+    /// Generate and compile a schema from a type implementing `schemars::JsonSchema`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the generated schema cannot be compiled.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hotswap_config::core::ConfigSchema;
+    /// use schemars::JsonSchema;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize, JsonSchema)]
+    /// struct AppConfig {
+    ///     port: u16,
+    /// }
+    ///
+    /// let schema = ConfigSchema::for_type::<AppConfig>().unwrap();
+    /// println!("{}", serde_json::to_string_pretty(schema.as_json()).unwrap());
+    /// ```
+    #[cfg(feature = "schemars")]
+    pub fn for_type<T: schemars::JsonSchema>() -> Result<Self> {
+        Self::new(schemars::schema_for!(T).to_value())
+    }
+
+    /// Get the schema document, e.g. for exporting it to operators or tooling.
+    pub fn as_json(&self) -> &JsonValue {
+        &self.schema
+    }
+
+    /// Validate a configuration tree against the schema.
+    ///
+    /// All violations are collected. A single violation is returned as
+    /// `ValidationError::InvalidField`; several are returned as
+    /// `ValidationError::Multiple`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ValidationError` describing every schema violation.
+    pub fn validate(&self, value: &JsonValue) -> std::result::Result<(), ValidationError> {
+        let mut errors: Vec<ValidationError> = self
+            .validator
+            .iter_errors(value)
+            .map(|e| {
+                ValidationError::invalid_field(
+                    pointer_to_path(e.instance_path.as_str()),
+                    e.to_string(),
+                )
+            })
+            .collect();
+
+        match errors.len() {
+            0 => Ok(()),
+            1 => Err(errors.remove(0)),
+            _ => Err(ValidationError::Multiple(errors)),
+        }
+    }
+}
+
+impl std::fmt::Debug for ConfigSchema {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConfigSchema")
+            .field("schema", &self.schema)
+            .finish()
+    }
+}
+
+/// Convert a JSON Pointer (`/server/port`) into a dotted key path (`server.port`).
+fn pointer_to_path(pointer: &str) -> String {
+    if pointer.is_empty() {
+        return "(root)".to_string();
+    }
+
+    pointer
+        .trim_start_matches('/')
+        .split('/')
+        .map(|segment| segment.replace("~1", "/").replace("~0", "~"))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn server_schema() -> ConfigSchema {
+        ConfigSchema::new(json!({
+            "type": "object",
+            "properties": {
+                "server": {
+                    "type": "object",
+                    "properties": {
+                        "port": { "type": "integer" },
+                        "host": { "type": "string" }
+                    },
+                    "required": ["port"]
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_invalid_schema() {
+        let result = ConfigSchema::new(json!({ "type": 42 }));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_valid_config() {
+        let schema = server_schema();
+        let value = json!({ "server": { "port": 8080, "host": "localhost" } });
+        assert!(schema.validate(&value).is_ok());
+    }
+
+    #[test]
+    fn test_error_has_key_path() {
+        let schema = server_schema();
+        let value = json!({ "server": { "port": "http" } });
+
+        match schema.validate(&value) {
+            Err(ValidationError::InvalidField { field, reason }) => {
+                assert_eq!(field, "server.port");
+                assert!(reason.contains("integer"));
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_multiple_errors_collected() {
+        let schema = server_schema();
+        let value = json!({ "server": { "host": 42 } });
+
+        match schema.validate(&value) {
+            Err(ValidationError::Multiple(errors)) => assert_eq!(errors.len(), 2),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_pointer_to_path() {
+        assert_eq!(pointer_to_path(""), "(root)");
+        assert_eq!(pointer_to_path("/server/port"), "server.port");
+        assert_eq!(pointer_to_path("/paths/a~1b"), "paths.a/b");
+    }
+
+    #[cfg(feature = "schemars")]
+    #[test]
+    fn test_for_type() {
+        #[derive(serde::Deserialize, schemars::JsonSchema)]
+        #[allow(dead_code)]
+        struct AppConfig {
+            port: u16,
+        }
+
+        let schema = ConfigSchema::for_type::<AppConfig>().unwrap();
+        assert!(schema.as_json().get("properties").is_some());
+        assert!(schema.validate(&json!({ "port": 8080 })).is_ok());
+        assert!(schema.validate(&json!({ "port": -1 })).is_err());
+    }
+}
@@ -0,0 +1,121 @@
+//! Process signals that trigger reloads.
+
+use crate::core::{ReloadReason, ReloadTrigger, TriggerFuture};
+use crate::error::{ConfigError, Result};
+use std::fmt;
+
+/// A process signal that reloads every source when received.
+///
+/// Use with
+/// [`HotswapConfigBuilder::with_reload_on_signal`](crate::core::HotswapConfigBuilder::with_reload_on_signal),
+/// so operators can reload the configuration the way they would a classic
+/// daemon, e.g. with `kill -HUP <pid>` or `systemctl reload`.
+///
+/// Once listened for, a signal no longer has its default effect for the rest
+/// of the process's life: `SIGHUP` stops terminating the process, even after
+/// the handle shuts down.
+///
+/// # Examples
+///
+/// ```rust
+/// use hotswap_config::core::Signal;
+///
+/// # #[cfg(unix)]
+/// assert_eq!(Signal::SIGHUP.to_string(), "SIGHUP");
+/// ```
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Signal {
+    /// `SIGHUP`, the conventional reload signal
+    #[cfg(unix)]
+    SIGHUP,
+    /// `SIGUSR1`
+    #[cfg(unix)]
+    SIGUSR1,
+    /// `SIGUSR2`
+    #[cfg(unix)]
+    SIGUSR2,
+    /// `CTRL_BREAK_EVENT`, sent to a console process group with
+    /// `GenerateConsoleCtrlEvent`
+    ///
+    /// Windows services don't receive console events; forward
+    /// `SERVICE_CONTROL_PARAMCHANGE` from the service control handler to
+    /// [`HotswapConfig::reload`](crate::core::HotswapConfig::reload) instead.
+    #[cfg(windows)]
+    CtrlBreak,
+}
+
+impl Signal {
+    /// Start listening for the signal.
+    ///
+    /// Called while building, so a signal sent once the build has returned
+    /// is never missed.
+    pub(crate) fn listen(self) -> Result<SignalListener> {
+        let deliveries = match self {
+            #[cfg(unix)]
+            Signal::SIGHUP => unix(tokio::signal::unix::SignalKind::hangup()),
+            #[cfg(unix)]
+            Signal::SIGUSR1 => unix(tokio::signal::unix::SignalKind::user_defined1()),
+            #[cfg(unix)]
+            Signal::SIGUSR2 => unix(tokio::signal::unix::SignalKind::user_defined2()),
+            #[cfg(windows)]
+            Signal::CtrlBreak => tokio::signal::windows::ctrl_break().map(Deliveries::CtrlBreak),
+        };
+        let deliveries = deliveries
+            .map_err(|e| ConfigError::Other(format!("Failed to listen for {}: {}", self, e)))?;
+        Ok(SignalListener {
+            signal: self,
+            deliveries,
+        })
+    }
+}
+
+impl fmt::Display for Signal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            #[cfg(unix)]
+            Signal::SIGHUP => "SIGHUP",
+            #[cfg(unix)]
+            Signal::SIGUSR1 => "SIGUSR1",
+            #[cfg(unix)]
+            Signal::SIGUSR2 => "SIGUSR2",
+            #[cfg(windows)]
+            Signal::CtrlBreak => "CTRL_BREAK_EVENT",
+        };
+        f.write_str(name)
+    }
+}
+
+#[cfg(unix)]
+fn unix(kind: tokio::signal::unix::SignalKind) -> std::io::Result<Deliveries> {
+    tokio::signal::unix::signal(kind).map(Deliveries::Unix)
+}
+
+/// Reload trigger firing on each delivery of a [`Signal`].
+pub(crate) struct SignalListener {
+    signal: Signal,
+    deliveries: Deliveries,
+}
+
+enum Deliveries {
+    #[cfg(unix)]
+    Unix(tokio::signal::unix::Signal),
+    #[cfg(windows)]
+    CtrlBreak(tokio::signal::windows::CtrlBreak),
+}
+
+impl ReloadTrigger for SignalListener {
+    // Deliveries while nobody waits are collapsed into one
+    fn next(&mut self) -> TriggerFuture<'_> {
+        Box::pin(async move {
+            match &mut self.deliveries {
+                #[cfg(unix)]
+                Deliveries::Unix(signal) => signal.recv().await?,
+                #[cfg(windows)]
+                Deliveries::CtrlBreak(signal) => signal.recv().await?,
+            }
+            Some(ReloadReason::Signal(self.signal.to_string()))
+        })
+    }
+}
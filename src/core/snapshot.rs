@@ -0,0 +1,120 @@
+//! Snapshot export of the effective configuration.
+
+use crate::error::{ConfigError, Result};
+use serde::Serialize;
+use std::path::Path;
+
+/// Output format for configuration snapshots.
+///
+/// Each format requires its feature (`yaml`, `toml`, or `json`); exporting
+/// without it returns [`ConfigError::FeatureNotEnabled`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotFormat {
+    /// YAML (feature: `yaml`)
+    Yaml,
+    /// TOML (feature: `toml`)
+    Toml,
+    /// Pretty-printed JSON (feature: `json`)
+    Json,
+}
+
+impl SnapshotFormat {
+    /// Detect the format from a file extension (`.yaml`/`.yml`, `.toml`, `.json`).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hotswap_config::core::SnapshotFormat;
+    ///
+    /// assert_eq!(SnapshotFormat::from_path("last-good.yaml"), Some(SnapshotFormat::Yaml));
+    /// assert_eq!(SnapshotFormat::from_path("last-good.ini"), None);
+    /// ```
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        match path.as_ref().extension()?.to_str()? {
+            "yaml" | "yml" => Some(Self::Yaml),
+            "toml" => Some(Self::Toml),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    /// Serialize a value in this format.
+    #[cfg_attr(
+        not(any(feature = "yaml", feature = "toml", feature = "json")),
+        allow(unused_variables)
+    )]
+    pub(crate) fn serialize<T: Serialize + ?Sized>(self, value: &T) -> Result<String> {
+        match self {
+            #[cfg(feature = "yaml")]
+            Self::Yaml => serde_yaml::to_string(value).map_err(export_error),
+            #[cfg(not(feature = "yaml"))]
+            Self::Yaml => Err(ConfigError::FeatureNotEnabled("yaml")),
+
+            #[cfg(feature = "toml")]
+            Self::Toml => toml::to_string_pretty(value).map_err(export_error),
+            #[cfg(not(feature = "toml"))]
+            Self::Toml => Err(ConfigError::FeatureNotEnabled("toml")),
+
+            #[cfg(feature = "json")]
+            Self::Json => serde_json::to_string_pretty(value).map_err(export_error),
+            #[cfg(not(feature = "json"))]
+            Self::Json => Err(ConfigError::FeatureNotEnabled("json")),
+        }
+    }
+}
+
+#[cfg(any(feature = "yaml", feature = "toml", feature = "json"))]
+fn export_error(e: impl std::fmt::Display) -> ConfigError {
+    ConfigError::Other(format!("Failed to export snapshot: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn sample() -> BTreeMap<&'static str, BTreeMap<&'static str, i64>> {
+        BTreeMap::from([("server", BTreeMap::from([("port", 8080)]))])
+    }
+
+    #[test]
+    fn test_from_path() {
+        assert_eq!(
+            SnapshotFormat::from_path("a.yml"),
+            Some(SnapshotFormat::Yaml)
+        );
+        assert_eq!(
+            SnapshotFormat::from_path("a.toml"),
+            Some(SnapshotFormat::Toml)
+        );
+        assert_eq!(
+            SnapshotFormat::from_path("a.json"),
+            Some(SnapshotFormat::Json)
+        );
+        assert_eq!(SnapshotFormat::from_path("snapshot"), None);
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_serialize_yaml() {
+        let yaml = SnapshotFormat::Yaml.serialize(&sample()).unwrap();
+        assert_eq!(yaml, "server:\n  port: 8080\n");
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_serialize_toml() {
+        let toml = SnapshotFormat::Toml.serialize(&sample()).unwrap();
+        assert!(toml.contains("[server]\nport = 8080"));
+    }
+
+    #[cfg(not(feature = "json"))]
+    #[test]
+    fn test_serialize_without_feature() {
+        let result = SnapshotFormat::Json.serialize(&sample());
+        assert!(matches!(
+            result,
+            Err(ConfigError::FeatureNotEnabled("json"))
+        ));
+    }
+}
@@ -0,0 +1,303 @@
+//! Reload triggers, and the reasons reloads happen.
+
+use std::fmt;
+use std::path::PathBuf;
+
+#[cfg(feature = "tokio")]
+use crate::error::{ConfigError, Result};
+#[cfg(feature = "tokio")]
+use crate::sources::ChangeStream;
+#[cfg(feature = "tokio")]
+use std::future::Future;
+#[cfg(feature = "tokio")]
+use std::pin::Pin;
+#[cfg(feature = "tokio")]
+use tokio::sync::mpsc;
+
+/// Why a reload happened.
+///
+/// Passed to the
+/// [metrics recorder](crate::metrics::MetricsRecorder::reload_triggered),
+/// logged with each reload, and kept by
+/// [`HotswapConfig::reload_reason`](crate::core::HotswapConfig::reload_reason)
+/// for subscribers and history.
+///
+/// # Examples
+///
+/// ```rust
+/// use hotswap_config::core::ReloadReason;
+///
+/// let reason = ReloadReason::custom("feature-service");
+/// assert_eq!(reason.label(), "feature-service");
+/// assert_eq!(ReloadReason::Schedule.to_string(), "schedule");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ReloadReason {
+    /// A direct call to [`reload`](crate::core::HotswapConfig::reload)
+    Manual,
+    /// The file watcher saw these files change
+    FileChanged(Vec<PathBuf>),
+    /// The process received this signal (`signal` feature)
+    Signal(String),
+    /// The reload schedule or an overlay's refresh interval came due
+    Schedule,
+    /// The named source reported a change
+    Source(String),
+    /// A message arrived on this NATS subject or Kafka topic (`nats` and
+    /// `kafka` features)
+    MessageBus(String),
+    /// A request to the admin API (`admin-api` feature)
+    Admin,
+    /// A request to the reload webhook (`webhook` feature)
+    Webhook,
+    /// Retrying the sources after startup fell back to a snapshot or
+    /// defaults
+    StartupRetry,
+    /// Content loaded earlier reached its
+    /// [effective time](crate::sources::ConfigSource::effective_at)
+    Activation,
+    /// A custom trigger, by name
+    Custom(String),
+}
+
+impl ReloadReason {
+    /// Create the reason for a custom trigger called `name`.
+    pub fn custom(name: impl Into<String>) -> Self {
+        Self::Custom(name.into())
+    }
+
+    /// Get a short label for the kind of reason, for metrics.
+    ///
+    /// One of `manual`, `file`, `signal`, `schedule`, `source`,
+    /// `message_bus`, `admin`, `webhook`, `startup_retry`, and `activation`,
+    /// or the name of a custom trigger, which should therefore come from a
+    /// small fixed set.
+    pub fn label(&self) -> &str {
+        match self {
+            Self::Manual => "manual",
+            Self::FileChanged(_) => "file",
+            Self::Signal(_) => "signal",
+            Self::Schedule => "schedule",
+            Self::Source(_) => "source",
+            Self::MessageBus(_) => "message_bus",
+            Self::Admin => "admin",
+            Self::Webhook => "webhook",
+            Self::StartupRetry => "startup_retry",
+            Self::Activation => "activation",
+            Self::Custom(name) => name,
+        }
+    }
+
+    /// Fold a reason that came in while this one waited into it.
+    ///
+    /// Changed files are merged; any other reason needs every source
+    /// re-read, which a reason other than changed files already does.
+    #[cfg(feature = "tokio")]
+    pub(crate) fn fold(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::FileChanged(mut paths), Self::FileChanged(more)) => {
+                paths.extend(more);
+                Self::FileChanged(paths)
+            }
+            (Self::FileChanged(_), other) => other,
+            (reason, _) => reason,
+        }
+    }
+}
+
+impl fmt::Display for ReloadReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FileChanged(paths) => {
+                write!(f, "file change:")?;
+                for path in paths {
+                    write!(f, " {}", path.display())?;
+                }
+                Ok(())
+            }
+            Self::Signal(signal) => write!(f, "signal {}", signal),
+            Self::Source(name) => write!(f, "source {}", name),
+            Self::MessageBus(name) => write!(f, "message on {}", name),
+            Self::StartupRetry => f.write_str("startup retry"),
+            Self::Activation => f.write_str("scheduled activation"),
+            reason => f.write_str(reason.label()),
+        }
+    }
+}
+
+/// Future returned by [`ReloadTrigger::next`].
+#[cfg(feature = "tokio")]
+pub type TriggerFuture<'a> = Pin<Box<dyn Future<Output = Option<ReloadReason>> + Send + 'a>>;
+
+/// Decides when to reload, and why.
+///
+/// Added with
+/// [`HotswapConfigBuilder::with_reload_trigger`](crate::core::HotswapConfigBuilder::with_reload_trigger),
+/// so anything can drive reloads: a message queue consumer, a feature
+/// service's webhook, a deploy hook. Each trigger runs on a task of its own
+/// and reloads every source each time it fires, after waiting out the
+/// [minimum reload interval](crate::core::HotswapConfigBuilder::with_min_reload_interval).
+///
+/// File watching, [`Schedule`](crate::core::Schedule)s, signals, and the
+/// [change streams](crate::sources::ConfigSource::watch) of watching sources
+/// are triggers too, and the receiving half of a channel of
+/// [`ReloadReason`]s is one, which is usually all a custom trigger needs.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use hotswap_config::core::ReloadReason;
+/// use hotswap_config::prelude::*;
+///
+/// # struct AppConfig;
+/// # async fn example() {
+/// let (trigger, reloads) = tokio::sync::mpsc::unbounded_channel();
+/// HotswapConfig::<AppConfig>::builder()
+///     .with_file("config.yaml")
+///     .with_reload_trigger(reloads);
+///
+/// // From the message queue consumer
+/// trigger.send(ReloadReason::custom("config-topic")).unwrap();
+/// # }
+/// ```
+#[cfg(feature = "tokio")]
+pub trait ReloadTrigger: Send + 'static {
+    /// Wait for the next reload, returning its reason, or `None` once the
+    /// trigger won't fire again.
+    fn next(&mut self) -> TriggerFuture<'_>;
+
+    /// Get a reload that is already pending, without waiting.
+    ///
+    /// Called after waiting out the minimum reload interval, so reloads that
+    /// piled up meanwhile happen as one. Returns `None` by default.
+    fn try_next(&mut self) -> Option<ReloadReason> {
+        None
+    }
+
+    /// Receive the result of the reload for the reason `next` returned last,
+    /// e.g. to acknowledge it upstream.
+    ///
+    /// A reason folded into a reload that another trigger was waiting on
+    /// gets that reload's result, as [`ConfigError::Shared`]. Does nothing
+    /// by default.
+    fn reloaded(&mut self, _result: &Result<()>) {}
+}
+
+#[cfg(feature = "tokio")]
+impl ReloadTrigger for mpsc::Receiver<ReloadReason> {
+    fn next(&mut self) -> TriggerFuture<'_> {
+        Box::pin(self.recv())
+    }
+
+    fn try_next(&mut self) -> Option<ReloadReason> {
+        self.try_recv().ok()
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl ReloadTrigger for mpsc::UnboundedReceiver<ReloadReason> {
+    fn next(&mut self) -> TriggerFuture<'_> {
+        Box::pin(self.recv())
+    }
+
+    fn try_next(&mut self) -> Option<ReloadReason> {
+        self.try_recv().ok()
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl ReloadTrigger for crate::core::Schedule {
+    fn next(&mut self) -> TriggerFuture<'_> {
+        Box::pin(async move {
+            tokio::time::sleep(self.next_delay()?).await;
+            Some(ReloadReason::Schedule)
+        })
+    }
+}
+
+/// Changes reported by the handle's own file watcher.
+#[cfg(feature = "file-watch")]
+pub(crate) struct FileTrigger(pub(crate) mpsc::Receiver<Vec<PathBuf>>);
+
+#[cfg(feature = "file-watch")]
+impl ReloadTrigger for FileTrigger {
+    fn next(&mut self) -> TriggerFuture<'_> {
+        Box::pin(async move { self.0.recv().await.map(ReloadReason::FileChanged) })
+    }
+
+    fn try_next(&mut self) -> Option<ReloadReason> {
+        self.0.try_recv().ok().map(ReloadReason::FileChanged)
+    }
+}
+
+/// Changes reported by a watching source, reloading it alone.
+#[cfg(feature = "tokio")]
+pub(crate) struct SourceTrigger {
+    name: String,
+    changes: ChangeStream,
+    /// Receives the source's watch failures
+    on_error: Box<dyn Fn(&ConfigError) + Send>,
+}
+
+#[cfg(feature = "tokio")]
+impl SourceTrigger {
+    pub(crate) fn new(
+        name: String,
+        changes: ChangeStream,
+        on_error: impl Fn(&ConfigError) + Send + 'static,
+    ) -> Self {
+        Self {
+            name,
+            changes,
+            on_error: Box::new(on_error),
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl ReloadTrigger for SourceTrigger {
+    fn next(&mut self) -> TriggerFuture<'_> {
+        Box::pin(async move {
+            loop {
+                match self.changes.next().await? {
+                    Ok(()) => return Some(ReloadReason::Source(self.name.clone())),
+                    Err(e) => (self.on_error)(&e),
+                }
+            }
+        })
+    }
+
+    fn reloaded(&mut self, result: &Result<()>) {
+        self.changes.reloaded(result);
+    }
+}
+
+#[cfg(all(test, feature = "tokio"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fold_merges_changed_files() {
+        let a = ReloadReason::FileChanged(vec![PathBuf::from("a.yaml")]);
+        let b = ReloadReason::FileChanged(vec![PathBuf::from("b.yaml")]);
+        assert_eq!(
+            a.clone().fold(b),
+            ReloadReason::FileChanged(vec![PathBuf::from("a.yaml"), PathBuf::from("b.yaml")])
+        );
+        assert_eq!(a.clone().fold(ReloadReason::Admin), ReloadReason::Admin);
+        assert_eq!(ReloadReason::Schedule.fold(a), ReloadReason::Schedule);
+    }
+
+    #[tokio::test]
+    async fn test_channel_trigger() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        tx.send(ReloadReason::custom("webhook")).unwrap();
+        tx.send(ReloadReason::Admin).unwrap();
+        assert_eq!(rx.next().await, Some(ReloadReason::custom("webhook")));
+        assert_eq!(rx.try_next(), Some(ReloadReason::Admin));
+        assert_eq!(rx.try_next(), None);
+        drop(tx);
+        assert_eq!(rx.next().await, None);
+    }
+}
@@ -4,8 +4,12 @@ use crate::error::ValidationError;
 
 /// Trait for configuration validation.
 ///
-/// Implement this trait on your configuration types to enable automatic validation
-/// before updates are applied.
+/// Implement this trait on your configuration types and build the handle with
+/// [`build_validated`](crate::core::HotswapConfigBuilder::build_validated) (or
+/// pass `T::validate` to
+/// [`with_validation`](crate::core::HotswapConfigBuilder::with_validation)) to
+/// validate the configuration on build and before every reload or update is
+/// applied.
 ///
 /// # Examples
 ///
@@ -0,0 +1,149 @@
+//! Async validators that verify a configuration against external systems.
+//!
+//! Async validators run after the synchronous ones and before the new config is
+//! swapped in, each bounded by its own timeout.
+
+use crate::error::{ConfigError, Result, ValidationError, VerificationError};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+#[cfg(feature = "tokio")]
+use std::time::Duration;
+
+/// Future returned by an async validator.
+pub(crate) type VerifyFuture =
+    Pin<Box<dyn Future<Output = std::result::Result<(), VerificationError>> + Send>>;
+
+/// An async validator, with its timeout already applied.
+pub(crate) type AsyncValidator<T> = Arc<dyn Fn(Arc<T>) -> VerifyFuture + Send + Sync>;
+
+/// Run every async validator against `config`.
+///
+/// All validators run, so every failure is reported. Invalid configurations
+/// take precedence over failed checks, since they won't succeed on retry.
+///
+/// # Errors
+///
+/// Returns [`ConfigError::ValidationError`] if any validator found the config
+/// invalid, otherwise [`ConfigError::VerificationFailed`] if any check failed
+/// or timed out.
+pub(crate) async fn verify<T>(validators: &[AsyncValidator<T>], config: &Arc<T>) -> Result<()> {
+    let mut invalid = Vec::new();
+    let mut failed = Vec::new();
+    for validator in validators {
+        match validator(Arc::clone(config)).await {
+            Ok(()) => {}
+            Err(VerificationError::Invalid(e)) => invalid.push(e),
+            Err(VerificationError::Failed(reason)) => failed.push(reason),
+        }
+    }
+
+    ValidationError::collect(invalid).map_err(|e| ConfigError::ValidationError(e.to_string()))?;
+    if !failed.is_empty() {
+        return Err(ConfigError::VerificationFailed(failed.join("; ")));
+    }
+
+    Ok(())
+}
+
+/// Bound a validator's future by `timeout`, failing the check if it expires.
+#[cfg(feature = "tokio")]
+pub(crate) fn with_timeout<F>(future: F, timeout: Duration) -> VerifyFuture
+where
+    F: Future<Output = std::result::Result<(), VerificationError>> + Send + 'static,
+{
+    Box::pin(async move {
+        tokio::time::timeout(timeout, future)
+            .await
+            .unwrap_or_else(|_| {
+                Err(VerificationError::failed(format!(
+                    "validator timed out after {:?}",
+                    timeout
+                )))
+            })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validator<F>(f: F) -> AsyncValidator<u16>
+    where
+        F: Fn(u16) -> VerifyFuture + Send + Sync + 'static,
+    {
+        Arc::new(move |config: Arc<u16>| f(*config))
+    }
+
+    #[tokio::test]
+    async fn test_verify_passes_and_fails() {
+        let checks = vec![validator(|port| {
+            with_timeout(
+                async move {
+                    if port < 1024 {
+                        return Err(ValidationError::invalid_field("port", "too low").into());
+                    }
+                    Ok(())
+                },
+                Duration::from_secs(1),
+            )
+        })];
+
+        assert!(verify(&checks, &Arc::new(8080)).await.is_ok());
+        assert!(matches!(
+            verify(&checks, &Arc::new(80)).await,
+            Err(ConfigError::ValidationError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_takes_precedence_over_failed() {
+        let checks = vec![
+            validator(|_| {
+                with_timeout(
+                    async { Err(VerificationError::failed("connection refused")) },
+                    Duration::from_secs(1),
+                )
+            }),
+            validator(|_| {
+                with_timeout(
+                    async { Err(ValidationError::custom("bad url").into()) },
+                    Duration::from_secs(1),
+                )
+            }),
+        ];
+        assert!(matches!(
+            verify(&checks, &Arc::new(8080)).await,
+            Err(ConfigError::ValidationError(_))
+        ));
+
+        let result = verify(&checks[..1], &Arc::new(8080)).await;
+        match result {
+            Err(ConfigError::VerificationFailed(reason)) => {
+                assert!(reason.contains("connection refused"))
+            }
+            other => panic!("expected verification failure, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_timeout() {
+        let checks = vec![validator(|_| {
+            with_timeout(
+                async {
+                    tokio::time::sleep(Duration::from_secs(10)).await;
+                    Ok(())
+                },
+                Duration::from_millis(50),
+            )
+        })];
+
+        let start = std::time::Instant::now();
+        let result = verify(&checks, &Arc::new(8080)).await;
+        assert!(start.elapsed() < Duration::from_secs(5));
+        match result {
+            Err(ConfigError::VerificationFailed(reason)) => assert!(reason.contains("timed out")),
+            other => panic!("expected timeout, got {:?}", other),
+        }
+    }
+}
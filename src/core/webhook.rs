@@ -0,0 +1,209 @@
+//! HTTP listener that reloads on authenticated requests.
+
+use crate::core::{HotswapConfig, ReloadReason};
+use crate::error::{ConfigError, Result};
+use axum::Router;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use serde::de::DeserializeOwned;
+use std::sync::Arc;
+
+/// Path the webhook accepts reload requests on.
+pub(crate) const RELOAD_PATH: &str = "/reload";
+
+/// A reload webhook to serve once the handle is built.
+pub(crate) struct ReloadWebhook {
+    addr: String,
+    token: String,
+}
+
+impl ReloadWebhook {
+    /// Create a webhook listening on `addr` for requests bearing `token`.
+    pub(crate) fn new(addr: String, token: String) -> Self {
+        Self { addr, token }
+    }
+
+    /// Bind the listener and serve it on one of `config`'s background tasks.
+    ///
+    /// Binds before returning, so a request sent once the build has returned
+    /// is never refused.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the token is empty or the listener can't be bound.
+    pub(crate) async fn start<T>(self, config: &HotswapConfig<T>) -> Result<()>
+    where
+        T: DeserializeOwned + Clone + Send + Sync + 'static,
+    {
+        if self.token.is_empty() {
+            return Err(ConfigError::Other(
+                "The reload webhook token must not be empty".to_string(),
+            ));
+        }
+
+        let listener = tokio::net::TcpListener::bind(&self.addr)
+            .await
+            .map_err(|e| {
+                ConfigError::Other(format!(
+                    "Failed to bind the reload webhook to {}: {}",
+                    self.addr, e
+                ))
+            })?;
+        #[cfg(feature = "tracing")]
+        if let Ok(addr) = listener.local_addr() {
+            tracing::info!(%addr, "Listening for reload webhooks");
+        }
+
+        let app = router(config.detached(), self.token);
+        let errors = config.detached();
+        config.spawn(async move {
+            if let Err(e) = axum::serve(listener, app).await {
+                errors.report_reload_error(&ConfigError::Other(format!(
+                    "Reload webhook stopped: {}",
+                    e
+                )));
+            }
+        });
+        Ok(())
+    }
+}
+
+struct Webhook<T> {
+    config: HotswapConfig<T>,
+    token: String,
+}
+
+impl<T> Webhook<T> {
+    /// Check the request's bearer token, in constant time.
+    fn authorized(&self, headers: &HeaderMap) -> bool {
+        let Some(token) = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+        else {
+            return false;
+        };
+        let (token, expected) = (token.trim().as_bytes(), self.token.as_bytes());
+        token.len() == expected.len()
+            && token
+                .iter()
+                .zip(expected)
+                .fold(0, |diff, (a, b)| diff | (a ^ b))
+                == 0
+    }
+}
+
+/// Build the webhook's router, serving `POST /reload`.
+fn router<T>(config: HotswapConfig<T>, token: String) -> Router
+where
+    T: DeserializeOwned + Clone + Send + Sync + 'static,
+{
+    Router::new()
+        .route(RELOAD_PATH, post(reload::<T>))
+        .with_state(Arc::new(Webhook { config, token }))
+}
+
+async fn reload<T>(State(webhook): State<Arc<Webhook<T>>>, headers: HeaderMap) -> Response
+where
+    T: DeserializeOwned + Clone + Send + Sync + 'static,
+{
+    if !webhook.authorized(&headers) {
+        return (StatusCode::UNAUTHORIZED, "Missing or invalid token\n").into_response();
+    }
+    match webhook
+        .config
+        .reload_with_reason(ReloadReason::Webhook)
+        .await
+    {
+        Ok(()) => (StatusCode::OK, "Reloaded\n").into_response(),
+        Err(e) => {
+            webhook.config.report_reload_error(&e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("{}\n", e)).into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use serde::Deserialize;
+    use tower::ServiceExt;
+
+    #[derive(Debug, Clone, Deserialize)]
+    struct TestConfig {
+        port: u16,
+    }
+
+    async fn send(router: Router, authorization: Option<&str>) -> StatusCode {
+        let mut request = Request::post(RELOAD_PATH);
+        if let Some(authorization) = authorization {
+            request = request.header(header::AUTHORIZATION, authorization);
+        }
+        let response = router
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        response.status()
+    }
+
+    #[tokio::test]
+    async fn test_webhook_requires_token() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.yaml");
+        std::fs::write(&config_path, "port: 8080\n").unwrap();
+        let config = HotswapConfig::<TestConfig>::builder()
+            .with_file(&config_path)
+            .with_file_watch(false)
+            .build()
+            .await
+            .unwrap();
+        let app = router(config.clone(), "s3cr3t".to_string());
+
+        std::fs::write(&config_path, "port: 9090\n").unwrap();
+        assert_eq!(send(app.clone(), None).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            send(app.clone(), Some("Bearer wrong")).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            send(app.clone(), Some("s3cr3t")).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(config.get().port, 8080);
+
+        assert_eq!(send(app, Some("Bearer s3cr3t")).await, StatusCode::OK);
+        assert_eq!(config.get().port, 9090);
+        assert_eq!(config.reload_reason(), Some(ReloadReason::Webhook));
+    }
+
+    #[tokio::test]
+    async fn test_webhook_reports_failed_reload() {
+        let config = HotswapConfig::new(TestConfig { port: 8080 });
+        let app = router(config, "s3cr3t".to_string());
+        assert_eq!(
+            send(app, Some("Bearer s3cr3t")).await,
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+
+    #[tokio::test]
+    async fn test_empty_token_fails_build() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.yaml");
+        std::fs::write(&config_path, "port: 8080\n").unwrap();
+        let result = HotswapConfig::<TestConfig>::builder()
+            .with_file(&config_path)
+            .with_file_watch(false)
+            .with_reload_webhook("127.0.0.1:0", "")
+            .build()
+            .await;
+        match result {
+            Err(ConfigError::Other(message)) => assert!(message.contains("must not be empty")),
+            other => panic!("expected an empty token error, got {:?}", other.map(|_| ())),
+        }
+    }
+}
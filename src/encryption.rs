@@ -0,0 +1,765 @@
+//! Decryption of encrypted configuration values.
+//!
+//! With a [`KeyProvider`] set via
+//! [`HotswapConfigBuilder::with_decryption`](crate::core::HotswapConfigBuilder::with_decryption),
+//! or a [`Decryptor`] set via
+//! [`with_field_decryption`](crate::core::HotswapConfigBuilder::with_field_decryption),
+//! encrypted values are decrypted as each source loads, so secrets can be
+//! committed to git encrypted and still hot-reload like any other value:
+//!
+//! - [SOPS](https://github.com/getsops/sops) documents (YAML or JSON) with
+//!   age recipients: the data key in the `sops` metadata is decrypted with
+//!   the provider's identities, then every `ENC[AES256_GCM,...]` value is
+//!   decrypted and the metadata removed
+//! - [age](https://age-encryption.org) values: any string that is an
+//!   ASCII-armored age file (`-----BEGIN AGE ENCRYPTED FILE-----`) is
+//!   replaced by its plaintext
+//! - Encrypted fields: `ENC[AES256_GCM,...]` values in ordinary documents,
+//!   without SOPS metadata, are decrypted with the AES-256 key from the
+//!   [`Decryptor`], such as a [`LocalKey`] or a key unwrapped by a KMS.
+//!   [`encrypt_field`] produces them, so a few sensitive values can sit in an
+//!   otherwise readable file
+//!
+//! Each SOPS value and encrypted field is authenticated with its key path, so
+//! editing or moving an encrypted value fails decryption. The document-wide SOPS MAC, which
+//! also detects removed values, isn't checked; combine with
+//! [signing](crate::core::HotswapConfigBuilder::with_signature_verification)
+//! (feature: `signing`) if that matters. Only age keys are supported, not
+//! KMS or PGP recipients.
+//!
+//! Identities are fetched from the provider whenever a load finds encrypted
+//! content, so a rotated key file is picked up on the next reload.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use hotswap_config::encryption::SopsEnv;
+//! use hotswap_config::prelude::*;
+//! use serde::Deserialize;
+//!
+//! #[derive(Debug, Deserialize, Clone)]
+//! struct AppConfig {
+//!     database_password: String,
+//! }
+//!
+//! # async fn example() -> Result<()> {
+//! // Encrypted with `sops --encrypt --age age1... secrets.yaml`
+//! let config = HotswapConfig::<AppConfig>::builder()
+//!     .with_file("config/secrets.enc.yaml")
+//!     .with_decryption(SopsEnv)
+//!     .build()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Encrypting a field, then loading it:
+//!
+//! ```rust,no_run
+//! use hotswap_config::encryption::{LocalKey, encrypt_field};
+//! use hotswap_config::prelude::*;
+//! # use serde::Deserialize;
+//! # #[derive(Debug, Deserialize, Clone)]
+//! # struct AppConfig {}
+//!
+//! # async fn example() -> Result<()> {
+//! let key = LocalKey::from_base64(&std::env::var("CONFIG_KEY").expect("CONFIG_KEY is set"))?;
+//!
+//! // Write the result into the file as `database: { password: "ENC[...]" }`
+//! let encrypted = encrypt_field("database.password", "hunter2", &key)?;
+//!
+//! let config = HotswapConfig::<AppConfig>::builder()
+//!     .with_file("config/default.yaml")
+//!     .with_field_decryption(key)
+//!     .build()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{ConfigError, Result};
+use aes_gcm::aead::consts::U32;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::aes::Aes256;
+use aes_gcm::{AesGcm, Nonce};
+use age::x25519::Identity;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::PathBuf;
+
+/// Key under which SOPS stores its metadata.
+const SOPS_KEY: &str = "sops";
+/// First line of an ASCII-armored age file.
+const AGE_ARMOR_HEADER: &str = "-----BEGIN AGE ENCRYPTED FILE-----";
+
+/// AES-256-GCM with the 32-byte nonces SOPS uses.
+type SopsCipher = AesGcm<Aes256, U32>;
+
+/// Supplies the age identities used to decrypt configuration.
+///
+/// Implemented for [`AgeKeys`], [`AgeKeyFile`], [`SopsEnv`], and closures
+/// returning identities.
+pub trait KeyProvider: Send + Sync {
+    /// Get the identities to try when decrypting.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the keys can't be read.
+    fn identities(&self) -> Result<Vec<Identity>>;
+}
+
+impl<F> KeyProvider for F
+where
+    F: Fn() -> Result<Vec<Identity>> + Send + Sync,
+{
+    fn identities(&self) -> Result<Vec<Identity>> {
+        self()
+    }
+}
+
+/// A fixed set of age identities.
+#[derive(Clone)]
+pub struct AgeKeys {
+    identities: Vec<Identity>,
+}
+
+impl AgeKeys {
+    /// Parse identities in age key file format: one `AGE-SECRET-KEY-1...` per
+    /// line, with `#` comments and blank lines ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a line isn't a valid age identity, or there are
+    /// none.
+    pub fn parse(keys: &str) -> Result<Self> {
+        let identities = parse_identities(keys)?;
+        if identities.is_empty() {
+            return Err(ConfigError::DecryptionError(
+                "No age identities found".to_string(),
+            ));
+        }
+        Ok(Self { identities })
+    }
+}
+
+impl KeyProvider for AgeKeys {
+    fn identities(&self) -> Result<Vec<Identity>> {
+        Ok(self.identities.clone())
+    }
+}
+
+/// Age identities read from a key file on every use.
+#[derive(Debug, Clone)]
+pub struct AgeKeyFile {
+    path: PathBuf,
+}
+
+impl AgeKeyFile {
+    /// Read identities from the age key file at `path`.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl KeyProvider for AgeKeyFile {
+    fn identities(&self) -> Result<Vec<Identity>> {
+        let keys = std::fs::read_to_string(&self.path).map_err(|e| {
+            ConfigError::DecryptionError(format!(
+                "Failed to read age key file {}: {}",
+                self.path.display(),
+                e
+            ))
+        })?;
+        parse_identities(&keys)
+    }
+}
+
+/// Age identities located the way the `sops` CLI finds them.
+///
+/// Reads the `SOPS_AGE_KEY` environment variable if set, otherwise the key
+/// file named by `SOPS_AGE_KEY_FILE`, falling back to
+/// `$XDG_CONFIG_HOME/sops/age/keys.txt` (`~/.config/sops/age/keys.txt`).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SopsEnv;
+
+impl KeyProvider for SopsEnv {
+    fn identities(&self) -> Result<Vec<Identity>> {
+        if let Ok(keys) = std::env::var("SOPS_AGE_KEY") {
+            return parse_identities(&keys);
+        }
+        let path = match std::env::var_os("SOPS_AGE_KEY_FILE") {
+            Some(path) => PathBuf::from(path),
+            None => std::env::var_os("XDG_CONFIG_HOME")
+                .map(PathBuf::from)
+                .or_else(|| {
+                    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config"))
+                })
+                .ok_or_else(|| {
+                    ConfigError::DecryptionError(
+                        "No age key: set SOPS_AGE_KEY or SOPS_AGE_KEY_FILE".to_string(),
+                    )
+                })?
+                .join("sops/age/keys.txt"),
+        };
+        AgeKeyFile::new(path).identities()
+    }
+}
+
+/// Supplies the AES-256 key of encrypted fields in documents without SOPS
+/// metadata.
+///
+/// Implemented for [`LocalKey`] and closures returning a key. Implement it to
+/// keep the key wrapped by a KMS and unwrap it when a load needs it; the key
+/// is requested once per source load that finds encrypted fields.
+pub trait Decryptor: Send + Sync {
+    /// Get the key that encrypted fields were encrypted with.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key can't be obtained.
+    fn data_key(&self) -> Result<[u8; 32]>;
+}
+
+impl<F> Decryptor for F
+where
+    F: Fn() -> Result<[u8; 32]> + Send + Sync,
+{
+    fn data_key(&self) -> Result<[u8; 32]> {
+        self()
+    }
+}
+
+/// A fixed AES-256 key for encrypted fields.
+#[derive(Clone)]
+pub struct LocalKey {
+    key: [u8; 32],
+}
+
+impl LocalKey {
+    /// Use `key` as the field key.
+    pub fn new(key: [u8; 32]) -> Self {
+        Self { key }
+    }
+
+    /// Generate a random key.
+    ///
+    /// Store it with [`to_base64`](Self::to_base64) somewhere the application
+    /// can read it but the config files' readers can't.
+    pub fn generate() -> Self {
+        Self {
+            key: SopsCipher::generate_key(OsRng).into(),
+        }
+    }
+
+    /// Parse a base64-encoded 32-byte key.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `key` isn't base64 or doesn't decode to 32 bytes.
+    pub fn from_base64(key: &str) -> Result<Self> {
+        let key = STANDARD
+            .decode(key.trim())
+            .map_err(|e| ConfigError::DecryptionError(format!("Invalid field key: {}", e)))?;
+        let key = key
+            .try_into()
+            .map_err(|_| ConfigError::DecryptionError("Field key must be 32 bytes".to_string()))?;
+        Ok(Self { key })
+    }
+
+    /// Encode the key as base64, as read by [`from_base64`](Self::from_base64).
+    pub fn to_base64(&self) -> String {
+        STANDARD.encode(self.key)
+    }
+}
+
+impl Decryptor for LocalKey {
+    fn data_key(&self) -> Result<[u8; 32]> {
+        Ok(self.key)
+    }
+}
+
+/// Encrypt `value` as the field at `key`, a dotted path like
+/// `database.password`, for writing into a config file.
+///
+/// Returns an `ENC[AES256_GCM,...]` string in SOPS value format that
+/// decrypts, with the same key, to a value of the original type. It is
+/// authenticated with `key`, so it only decrypts at that path; for an item of
+/// a list, `key` is the path of the list.
+///
+/// # Errors
+///
+/// Returns an error if the key can't be obtained or `value` isn't a string,
+/// number, or boolean.
+pub fn encrypt_field(
+    key: &str,
+    value: impl Into<config::Value>,
+    decryptor: &dyn Decryptor,
+) -> Result<String> {
+    let value = value.into();
+    let (plaintext, kind) = match value.kind {
+        config::ValueKind::String(s) => (s, "str"),
+        config::ValueKind::Boolean(b) => (b.to_string(), "bool"),
+        config::ValueKind::I64(n) => (n.to_string(), "int"),
+        config::ValueKind::I128(n) => (n.to_string(), "int"),
+        config::ValueKind::U64(n) => (n.to_string(), "int"),
+        config::ValueKind::U128(n) => (n.to_string(), "int"),
+        config::ValueKind::Float(f) => (f.to_string(), "float"),
+        _ => {
+            return Err(ConfigError::DecryptionError(format!(
+                "'{}': only strings, numbers, and booleans can be encrypted",
+                key
+            )));
+        }
+    };
+
+    let aad = format!("{}:", key.replace('.', ":"));
+    let iv = SopsCipher::generate_nonce(OsRng);
+    let ciphertext = SopsCipher::new(&decryptor.data_key()?.into())
+        .encrypt(
+            &iv,
+            Payload {
+                msg: plaintext.as_bytes(),
+                aad: aad.as_bytes(),
+            },
+        )
+        .map_err(|_| ConfigError::DecryptionError(format!("'{}': encryption failed", key)))?;
+    let (data, tag) = ciphertext.split_at(ciphertext.len() - 16);
+    Ok(format!(
+        "ENC[AES256_GCM,data:{},iv:{},tag:{},type:{}]",
+        STANDARD.encode(data),
+        STANDARD.encode(iv),
+        STANDARD.encode(tag),
+        kind
+    ))
+}
+
+/// Parse age identities, one per line, skipping comments and blank lines.
+fn parse_identities(keys: &str) -> Result<Vec<Identity>> {
+    keys.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            line.parse::<Identity>()
+                .map_err(|e| ConfigError::DecryptionError(format!("Invalid age identity: {}", e)))
+        })
+        .collect()
+}
+
+/// Keys fetched from the provider and decryptor on first use.
+struct Keys<'a> {
+    provider: Option<&'a dyn KeyProvider>,
+    identities: Option<Vec<Identity>>,
+    decryptor: Option<&'a dyn Decryptor>,
+    /// The SOPS data key, or the decryptor's key once fetched
+    data_key: Option<[u8; 32]>,
+}
+
+impl Keys<'_> {
+    fn get(&mut self) -> Result<&[Identity]> {
+        if self.identities.is_none() {
+            let provider = self.provider.ok_or_else(|| {
+                ConfigError::DecryptionError(
+                    "SOPS documents need age identities; set them with with_decryption".to_string(),
+                )
+            })?;
+            self.identities = Some(provider.identities()?);
+        }
+        Ok(self.identities.as_deref().unwrap_or_default())
+    }
+
+    fn data_key(&mut self, path: &[String]) -> Result<[u8; 32]> {
+        if self.data_key.is_none() {
+            let decryptor = self.decryptor.ok_or_else(|| {
+                ConfigError::DecryptionError(format!(
+                    "'{}' is SOPS-encrypted but the document has no sops metadata",
+                    path.join(".")
+                ))
+            })?;
+            self.data_key = Some(decryptor.data_key()?);
+        }
+        Ok(self.data_key.unwrap_or_default())
+    }
+}
+
+/// Decrypt SOPS documents, age values, and encrypted fields in one source's
+/// values.
+pub(crate) fn decrypt(
+    mut values: HashMap<String, config::Value>,
+    provider: Option<&dyn KeyProvider>,
+    decryptor: Option<&dyn Decryptor>,
+) -> Result<HashMap<String, config::Value>> {
+    let mut keys = Keys {
+        provider,
+        identities: None,
+        decryptor,
+        data_key: None,
+    };
+    if let Some(metadata) = values.remove(SOPS_KEY) {
+        keys.data_key = Some(sops_data_key(metadata, &mut keys)?);
+    }
+
+    let mut path = Vec::new();
+    for (key, value) in values.iter_mut() {
+        path.push(key.clone());
+        decrypt_value(value, &mut path, &mut keys)?;
+        path.pop();
+    }
+    Ok(values)
+}
+
+/// Decrypt the SOPS data key with any of the age identities.
+fn sops_data_key(metadata: config::Value, keys: &mut Keys<'_>) -> Result<[u8; 32]> {
+    let recipients = metadata
+        .into_table()
+        .ok()
+        .and_then(|mut table| table.remove("age"))
+        .and_then(|age| age.into_array().ok())
+        .unwrap_or_default();
+    if recipients.is_empty() {
+        return Err(ConfigError::DecryptionError(
+            "SOPS document has no age recipients; only age keys are supported".to_string(),
+        ));
+    }
+
+    let identities = keys.get()?;
+    for recipient in recipients {
+        let Some(enc) = recipient
+            .into_table()
+            .ok()
+            .and_then(|mut table| table.remove("enc"))
+            .and_then(|enc| enc.into_string().ok())
+        else {
+            continue;
+        };
+        if let Ok(key) = decrypt_age(&enc, identities) {
+            return key.try_into().map_err(|_| {
+                ConfigError::DecryptionError("SOPS data key must be 32 bytes".to_string())
+            });
+        }
+    }
+    Err(ConfigError::DecryptionError(
+        "No age identity matches the SOPS recipients".to_string(),
+    ))
+}
+
+/// Decrypt encrypted strings in a value, recursing into tables and arrays.
+fn decrypt_value(
+    value: &mut config::Value,
+    path: &mut Vec<String>,
+    keys: &mut Keys<'_>,
+) -> Result<()> {
+    let decrypted = match &mut value.kind {
+        config::ValueKind::Table(table) => {
+            for (key, value) in table.iter_mut() {
+                path.push(key.clone());
+                decrypt_value(value, path, keys)?;
+                path.pop();
+            }
+            None
+        }
+        // SOPS authenticates list items with the path of the list
+        config::ValueKind::Array(items) => {
+            for item in items.iter_mut() {
+                decrypt_value(item, path, keys)?;
+            }
+            None
+        }
+        config::ValueKind::String(s) if s.starts_with("ENC[") => {
+            let data_key = keys.data_key(path)?;
+            let aad = format!("{}:", path.join(":"));
+            Some(decrypt_sops_value(s, &data_key, &aad).map_err(|reason| {
+                ConfigError::DecryptionError(format!("'{}': {}", path.join("."), reason))
+            })?)
+        }
+        // Without age identities, armored values are left alone
+        config::ValueKind::String(s)
+            if keys.provider.is_some() && s.trim_start().starts_with(AGE_ARMOR_HEADER) =>
+        {
+            let plaintext = decrypt_age(s, keys.get()?).map_err(|reason| {
+                ConfigError::DecryptionError(format!("'{}': {}", path.join("."), reason))
+            })?;
+            let plaintext = String::from_utf8(plaintext).map_err(|_| {
+                ConfigError::DecryptionError(format!(
+                    "'{}': decrypted value is not UTF-8",
+                    path.join(".")
+                ))
+            })?;
+            Some(config::ValueKind::String(plaintext))
+        }
+        _ => None,
+    };
+    if let Some(kind) = decrypted {
+        value.kind = kind;
+    }
+    Ok(())
+}
+
+/// Decrypt a value in SOPS format:
+/// `ENC[AES256_GCM,data:<base64>,iv:<base64>,tag:<base64>,type:<type>]`.
+fn decrypt_sops_value(
+    value: &str,
+    data_key: &[u8; 32],
+    aad: &str,
+) -> std::result::Result<config::ValueKind, String> {
+    let inner = value
+        .strip_prefix("ENC[AES256_GCM,")
+        .and_then(|rest| rest.strip_suffix(']'))
+        .ok_or("unsupported SOPS value format")?;
+    let mut fields = HashMap::new();
+    for field in inner.split(',') {
+        let (name, value) = field.split_once(':').ok_or("malformed SOPS value")?;
+        fields.insert(name, value);
+    }
+    let decode = |name: &str| {
+        let field = fields
+            .get(name)
+            .ok_or_else(|| format!("SOPS value has no {}", name))?;
+        STANDARD
+            .decode(field)
+            .map_err(|e| format!("invalid {}: {}", name, e))
+    };
+    let mut ciphertext = decode("data")?;
+    ciphertext.extend(decode("tag")?);
+    let iv = decode("iv")?;
+    if iv.len() != 32 {
+        return Err("invalid iv length".to_string());
+    }
+
+    let cipher = SopsCipher::new(data_key.into());
+    let plaintext = cipher
+        .decrypt(
+            Nonce::from_slice(&iv),
+            Payload {
+                msg: &ciphertext,
+                aad: aad.as_bytes(),
+            },
+        )
+        .map_err(|_| "decryption failed (wrong key or tampered value)".to_string())?;
+    let plaintext = String::from_utf8(plaintext).map_err(|_| "value is not UTF-8".to_string())?;
+
+    match fields.get("type").copied().unwrap_or("str") {
+        "str" | "bytes" => Ok(config::ValueKind::String(plaintext)),
+        "int" => plaintext
+            .parse()
+            .map(config::ValueKind::I64)
+            .map_err(|e| format!("invalid int: {}", e)),
+        "float" => plaintext
+            .parse()
+            .map(config::ValueKind::Float)
+            .map_err(|e| format!("invalid float: {}", e)),
+        "bool" => match plaintext.to_ascii_lowercase().as_str() {
+            "true" | "1" => Ok(config::ValueKind::Boolean(true)),
+            "false" | "0" => Ok(config::ValueKind::Boolean(false)),
+            _ => Err(format!("invalid bool: {}", plaintext)),
+        },
+        other => Err(format!("unsupported SOPS value type: {}", other)),
+    }
+}
+
+/// Decrypt an ASCII-armored age file with any of the identities.
+fn decrypt_age(armored: &str, identities: &[Identity]) -> std::result::Result<Vec<u8>, String> {
+    let decryptor =
+        age::Decryptor::new_buffered(age::armor::ArmoredReader::new(armored.trim().as_bytes()))
+            .map_err(|e| format!("invalid age ciphertext: {}", e))?;
+    let mut reader = decryptor
+        .decrypt(identities.iter().map(|i| i as &dyn age::Identity))
+        .map_err(|e| format!("age decryption failed: {}", e))?;
+    let mut plaintext = Vec::new();
+    reader
+        .read_to_end(&mut plaintext)
+        .map_err(|e| format!("age decryption failed: {}", e))?;
+    Ok(plaintext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Encrypt a value the way SOPS does.
+    fn sops_encrypt(plaintext: &str, kind: &str, data_key: &[u8; 32], aad: &str) -> String {
+        let iv = [9u8; 32];
+        let out = SopsCipher::new(data_key.into())
+            .encrypt(
+                Nonce::from_slice(&iv),
+                Payload {
+                    msg: plaintext.as_bytes(),
+                    aad: aad.as_bytes(),
+                },
+            )
+            .unwrap();
+        let (data, tag) = out.split_at(out.len() - 16);
+        format!(
+            "ENC[AES256_GCM,data:{},iv:{},tag:{},type:{}]",
+            STANDARD.encode(data),
+            STANDARD.encode(iv),
+            STANDARD.encode(tag),
+            kind
+        )
+    }
+
+    fn parse_yaml(yaml: &str) -> HashMap<String, config::Value> {
+        config::Config::builder()
+            .add_source(config::File::from_str(yaml, config::FileFormat::Yaml))
+            .build()
+            .unwrap()
+            .try_deserialize()
+            .unwrap()
+    }
+
+    /// A SOPS document with the data key encrypted to `identity`.
+    fn sops_document(identity: &Identity, data_key: &[u8; 32], body: &str) -> String {
+        let enc = age::encrypt_and_armor(&identity.to_public(), data_key).unwrap();
+        let enc = enc.lines().collect::<Vec<_>>().join("\n        ");
+        format!(
+            "{}\nsops:\n  age:\n    - recipient: {}\n      enc: |\n        {}\n  version: 3.9.0\n",
+            body,
+            identity.to_public(),
+            enc
+        )
+    }
+
+    fn keys(identity: &Identity) -> AgeKeys {
+        AgeKeys {
+            identities: vec![identity.clone()],
+        }
+    }
+
+    #[test]
+    fn test_decrypts_sops_document() {
+        let identity = Identity::generate();
+        let data_key = [3u8; 32];
+        let body = format!(
+            "database:\n  password: \"{}\"\n  port: \"{}\"\n  tls: \"{}\"\nhosts:\n  - \"{}\"\nname_unencrypted: plain\n",
+            sops_encrypt("hunter2", "str", &data_key, "database:password:"),
+            sops_encrypt("5432", "int", &data_key, "database:port:"),
+            sops_encrypt("true", "bool", &data_key, "database:tls:"),
+            sops_encrypt("db1", "str", &data_key, "hosts:"),
+        );
+        let values = parse_yaml(&sops_document(&identity, &data_key, &body));
+
+        let values = decrypt(values, Some(&keys(&identity)), None).unwrap();
+        assert!(!values.contains_key("sops"));
+        let database = values["database"].clone().into_table().unwrap();
+        assert_eq!(
+            database["password"].clone().into_string().unwrap(),
+            "hunter2"
+        );
+        assert_eq!(database["port"].clone().into_int().unwrap(), 5432);
+        assert!(database["tls"].clone().into_bool().unwrap());
+        let hosts = values["hosts"].clone().into_array().unwrap();
+        assert_eq!(hosts[0].clone().into_string().unwrap(), "db1");
+        assert_eq!(
+            values["name_unencrypted"].clone().into_string().unwrap(),
+            "plain"
+        );
+    }
+
+    #[test]
+    fn test_rejects_moved_values_and_wrong_keys() {
+        let identity = Identity::generate();
+        let data_key = [3u8; 32];
+        // Encrypted for another key path
+        let body = format!(
+            "admin_password: \"{}\"\n",
+            sops_encrypt("hunter2", "str", &data_key, "guest_password:")
+        );
+        let values = parse_yaml(&sops_document(&identity, &data_key, &body));
+        let error = decrypt(values, Some(&keys(&identity)), None).unwrap_err();
+        assert!(matches!(error, ConfigError::DecryptionError(_)));
+        assert!(error.to_string().contains("admin_password"));
+
+        let body = format!(
+            "password: \"{}\"\n",
+            sops_encrypt("hunter2", "str", &data_key, "password:")
+        );
+        let values = parse_yaml(&sops_document(&identity, &data_key, &body));
+        assert!(matches!(
+            decrypt(values.clone(), Some(&keys(&Identity::generate())), None),
+            Err(ConfigError::DecryptionError(_))
+        ));
+
+        // Without metadata there is no data key
+        let mut values = values;
+        values.remove("sops");
+        assert!(matches!(
+            decrypt(values, Some(&keys(&identity)), None),
+            Err(ConfigError::DecryptionError(_))
+        ));
+    }
+
+    #[test]
+    fn test_decrypts_age_values() {
+        let identity = Identity::generate();
+        let armored = age::encrypt_and_armor(&identity.to_public(), b"s3cret").unwrap();
+        let mut values = HashMap::new();
+        values.insert("api_key".to_string(), config::Value::from(armored));
+        values.insert("port".to_string(), config::Value::from(8080));
+
+        let values = decrypt(values, Some(&keys(&identity)), None).unwrap();
+        assert_eq!(values["api_key"].clone().into_string().unwrap(), "s3cret");
+        assert_eq!(values["port"].clone().into_int().unwrap(), 8080);
+    }
+
+    #[test]
+    fn test_keys_are_only_read_for_encrypted_content() {
+        let failing =
+            || -> Result<Vec<Identity>> { Err(ConfigError::DecryptionError("no key".to_string())) };
+        let values = parse_yaml("port: 8080\n");
+        assert!(decrypt(values, Some(&failing), None).is_ok());
+    }
+
+    #[test]
+    fn test_decrypts_encrypted_fields() {
+        let key = LocalKey::generate();
+        let body = format!(
+            "database:\n  password: \"{}\"\n  port: \"{}\"\nname: plain\n",
+            encrypt_field("database.password", "hunter2", &key).unwrap(),
+            encrypt_field("database.port", 5432, &key).unwrap(),
+        );
+        let values = parse_yaml(&body);
+
+        let decrypted = decrypt(values.clone(), None, Some(&key)).unwrap();
+        let database = decrypted["database"].clone().into_table().unwrap();
+        assert_eq!(
+            database["password"].clone().into_string().unwrap(),
+            "hunter2"
+        );
+        assert_eq!(database["port"].clone().into_int().unwrap(), 5432);
+        assert_eq!(decrypted["name"].clone().into_string().unwrap(), "plain");
+
+        // Another key, another path, or no decryptor fail
+        assert!(decrypt(values.clone(), None, Some(&LocalKey::generate())).is_err());
+        let moved = format!(
+            "password: \"{}\"\n",
+            encrypt_field("database.password", "hunter2", &key).unwrap()
+        );
+        assert!(decrypt(parse_yaml(&moved), None, Some(&key)).is_err());
+        assert!(decrypt(values, Some(&keys(&Identity::generate())), None).is_err());
+
+        let base64 = key.to_base64();
+        assert_eq!(LocalKey::from_base64(&base64).unwrap().key, key.key);
+        assert!(LocalKey::from_base64("c2hvcnQ=").is_err());
+        assert!(encrypt_field("servers", vec!["a"], &key).is_err());
+    }
+
+    #[test]
+    fn test_key_files() {
+        let identity = Identity::generate();
+        let contents = format!(
+            "# created: 2024-01-01\n# public key: {}\n{}\n",
+            identity.to_public(),
+            age::secrecy::ExposeSecret::expose_secret(&identity.to_string())
+        );
+        assert_eq!(AgeKeys::parse(&contents).unwrap().identities.len(), 1);
+        assert!(AgeKeys::parse("# no keys\n").is_err());
+        assert!(AgeKeys::parse("AGE-SECRET-KEY-1NOPE").is_err());
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("keys.txt");
+        assert!(AgeKeyFile::new(&path).identities().is_err());
+        std::fs::write(&path, contents).unwrap();
+        assert_eq!(AgeKeyFile::new(&path).identities().unwrap().len(), 1);
+    }
+}
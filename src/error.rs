@@ -1,6 +1,8 @@
 //! Error types for hotswap-config.
 
 use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 /// Result type alias for hotswap-config operations.
 pub type Result<T> = std::result::Result<T, ConfigError>;
@@ -20,6 +22,59 @@ pub enum ConfigError {
     #[error("Configuration validation failed: {0}")]
     ValidationError(String),
 
+    /// An async validator could not verify the configuration (e.g. the new
+    /// database was unreachable or the check timed out).
+    ///
+    /// Unlike [`ValidationError`](Self::ValidationError), this doesn't mean the
+    /// configuration is wrong, only that it couldn't be confirmed to work.
+    #[error("Configuration verification failed: {0}")]
+    VerificationFailed(String),
+
+    /// The configuration is frozen, so reloads and updates are rejected.
+    #[error("Configuration is frozen{}", reason.as_ref().map(|r| format!(": {}", r)).unwrap_or_default())]
+    Frozen {
+        /// Reason given when the configuration was frozen
+        reason: Option<String>,
+    },
+
+    /// The update policy refused an update or patch.
+    ///
+    /// See [`HotswapConfigBuilder::with_update_policy`](crate::core::HotswapConfigBuilder::with_update_policy).
+    #[error("Update denied by policy: {reason}")]
+    UpdateDenied {
+        /// Reason given by the policy
+        reason: String,
+    },
+
+    /// A transactional subscriber couldn't apply a new configuration, so the
+    /// previous one was kept.
+    #[error("Subscriber '{subscriber}' failed to apply configuration: {reason}")]
+    ApplyFailed {
+        /// Name of the subscriber that rejected the configuration
+        subscriber: String,
+        /// Why it couldn't be applied
+        reason: String,
+    },
+
+    /// A pre-swap hook vetoed a new configuration, so the previous one was
+    /// kept.
+    ///
+    /// See [`HotswapConfigBuilder::with_pre_swap_hook`](crate::core::HotswapConfigBuilder::with_pre_swap_hook).
+    #[error("Configuration swap vetoed: {reason}")]
+    SwapVetoed {
+        /// Reason given by the hook
+        reason: String,
+    },
+
+    /// No configuration with the given version is prepared for activation.
+    ///
+    /// See [`HotswapConfig::activate`](crate::core::HotswapConfig::activate).
+    #[error("Configuration version '{version}' is not prepared")]
+    NotPrepared {
+        /// The version that was to be activated
+        version: String,
+    },
+
     /// File watching is not supported or failed to initialize.
     #[error("File watching error: {0}")]
     WatchError(String),
@@ -40,6 +95,15 @@ pub enum ConfigError {
     #[error("Failed to parse configuration: {0}")]
     ParseError(String),
 
+    /// A `${...}` reference or expression could not be resolved.
+    #[error("Interpolation failed at '{key}': {reason}")]
+    InterpolationError {
+        /// The key whose value contains the reference
+        key: String,
+        /// Why the reference could not be resolved
+        reason: String,
+    },
+
     #[cfg(feature = "rollback")]
     /// Not enough history to rollback the requested number of steps.
     #[error("Insufficient history: cannot rollback {requested} steps (only {available} available)")]
@@ -55,9 +119,299 @@ pub enum ConfigError {
     #[error("Patch operation failed: {0}")]
     PatchError(String),
 
+    #[cfg(feature = "encryption")]
+    /// Encrypted content could not be decrypted.
+    #[error("Decryption failed: {0}")]
+    DecryptionError(String),
+
+    #[cfg(feature = "signing")]
+    /// Content failed signature verification and was rejected.
+    #[error("Signature verification failed: {0}")]
+    SignatureError(String),
+
+    /// The configuration has keys the config type doesn't consume.
+    ///
+    /// Returned when unknown keys are denied with
+    /// [`HotswapConfigBuilder::with_unknown_keys`](crate::core::HotswapConfigBuilder::with_unknown_keys).
+    #[error("Unknown configuration keys: {}", .0.join(", "))]
+    UnknownKeys(Vec<String>),
+
+    /// A source's content is larger or more deeply nested than allowed.
+    ///
+    /// See [`SourceLimits`](crate::sources::SourceLimits).
+    #[error("Limit exceeded: {limit} over the maximum of {max}{}", limit.unit())]
+    LimitExceeded {
+        /// The limit that was exceeded
+        limit: crate::sources::Limit,
+        /// The configured maximum
+        max: usize,
+    },
+
     /// Generic error for other cases.
     #[error("Configuration error: {0}")]
     Other(String),
+
+    /// Several errors, reported together when loading with
+    /// [`ErrorMode::CollectAll`](crate::core::ErrorMode::CollectAll).
+    #[error("{}", list_errors(.0))]
+    Multiple(Vec<ConfigError>),
+
+    /// The error of a reload that several concurrent callers joined.
+    ///
+    /// Concurrent reloads are coalesced into one load, and when it fails each
+    /// caller that joined gets this handle to the same error. Use
+    /// [`root`](Self::root) to match on the underlying error.
+    #[error(transparent)]
+    Shared(Arc<ConfigError>),
+
+    /// An error with context about where it happened.
+    ///
+    /// Use [`source_name`](Self::source_name), [`path`](Self::path), and
+    /// [`key_path`](Self::key_path) to read the context, and
+    /// [`root`](Self::root) to match on the underlying error.
+    #[error("{}{error}", context_prefix(.source_name, .key_path))]
+    WithContext {
+        /// Name of the source that failed, e.g. `file:config/default.yaml`
+        source_name: Option<String>,
+        /// File the source reads, if it reads exactly one
+        path: Option<PathBuf>,
+        /// Dotted path of the key the error concerns, e.g. `server.port`
+        key_path: Option<String>,
+        /// The underlying error
+        #[source]
+        error: Box<ConfigError>,
+    },
+}
+
+/// Render the context of a [`ConfigError::WithContext`] as a message prefix.
+fn context_prefix(source_name: &Option<String>, key_path: &Option<String>) -> String {
+    match (source_name, key_path) {
+        (Some(source), Some(key)) => format!("Failed to load source '{}' at '{}': ", source, key),
+        (Some(source), None) => format!("Failed to load source '{}': ", source),
+        (None, Some(key)) => format!("At '{}': ", key),
+        (None, None) => String::new(),
+    }
+}
+
+/// Render the errors of a [`ConfigError::Multiple`] as a numbered list.
+fn list_errors(errors: &[ConfigError]) -> String {
+    let mut message = format!("{} configuration errors:", errors.len());
+    for (i, error) in errors.iter().enumerate() {
+        message.push_str(&format!("\n  {}. {}", i + 1, error));
+    }
+    message
+}
+
+impl ConfigError {
+    /// Combine several errors into one.
+    ///
+    /// Returns `Ok` if there are no errors, the error itself if there is one,
+    /// and [`Multiple`](Self::Multiple) (flattened) otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns the combined error if `errors` is not empty.
+    pub fn collect(errors: impl IntoIterator<Item = ConfigError>) -> Result<()> {
+        let mut flattened = Vec::new();
+        for error in errors {
+            match error {
+                Self::Multiple(errors) => flattened.extend(errors),
+                error => flattened.push(error),
+            }
+        }
+
+        match flattened.len() {
+            0 => Ok(()),
+            1 => Err(flattened.remove(0)),
+            _ => Err(Self::Multiple(flattened)),
+        }
+    }
+
+    /// Copy this error, for handing the same failure to several callers.
+    ///
+    /// An [`IoError`](Self::IoError) keeps its kind and message but loses its
+    /// inner source, since `std::io::Error` can't be cloned.
+    #[cfg(feature = "tokio")]
+    pub(crate) fn duplicate(&self) -> Self {
+        match self {
+            Self::LoadError(s) => Self::LoadError(s.clone()),
+            Self::DeserializationError(s) => Self::DeserializationError(s.clone()),
+            Self::ValidationError(s) => Self::ValidationError(s.clone()),
+            Self::VerificationFailed(s) => Self::VerificationFailed(s.clone()),
+            Self::Frozen { reason } => Self::Frozen {
+                reason: reason.clone(),
+            },
+            Self::UpdateDenied { reason } => Self::UpdateDenied {
+                reason: reason.clone(),
+            },
+            Self::ApplyFailed { subscriber, reason } => Self::ApplyFailed {
+                subscriber: subscriber.clone(),
+                reason: reason.clone(),
+            },
+            Self::SwapVetoed { reason } => Self::SwapVetoed {
+                reason: reason.clone(),
+            },
+            Self::NotPrepared { version } => Self::NotPrepared {
+                version: version.clone(),
+            },
+            Self::WatchError(s) => Self::WatchError(s.clone()),
+            Self::FeatureNotEnabled(s) => Self::FeatureNotEnabled(s),
+            Self::WatchNotSupported => Self::WatchNotSupported,
+            Self::IoError(e) => Self::IoError(std::io::Error::new(e.kind(), e.to_string())),
+            Self::ParseError(s) => Self::ParseError(s.clone()),
+            Self::InterpolationError { key, reason } => Self::InterpolationError {
+                key: key.clone(),
+                reason: reason.clone(),
+            },
+            #[cfg(feature = "rollback")]
+            Self::InsufficientHistory {
+                requested,
+                available,
+            } => Self::InsufficientHistory {
+                requested: *requested,
+                available: *available,
+            },
+            #[cfg(feature = "partial-updates")]
+            Self::PatchError(s) => Self::PatchError(s.clone()),
+            #[cfg(feature = "encryption")]
+            Self::DecryptionError(s) => Self::DecryptionError(s.clone()),
+            #[cfg(feature = "signing")]
+            Self::SignatureError(s) => Self::SignatureError(s.clone()),
+            Self::UnknownKeys(keys) => Self::UnknownKeys(keys.clone()),
+            Self::LimitExceeded { limit, max } => Self::LimitExceeded {
+                limit: *limit,
+                max: *max,
+            },
+            Self::Other(s) => Self::Other(s.clone()),
+            Self::Multiple(errors) => Self::Multiple(errors.iter().map(Self::duplicate).collect()),
+            Self::Shared(error) => Self::Shared(Arc::clone(error)),
+            Self::WithContext {
+                source_name,
+                path,
+                key_path,
+                error,
+            } => Self::WithContext {
+                source_name: source_name.clone(),
+                path: path.clone(),
+                key_path: key_path.clone(),
+                error: Box::new(error.duplicate()),
+            },
+        }
+    }
+
+    /// Get the name of the source this error came from, if known.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hotswap_config::error::ConfigError;
+    ///
+    /// let err = ConfigError::LoadError("connection refused".to_string())
+    ///     .in_source("http:https://config.internal/app", None);
+    /// assert_eq!(err.source_name(), Some("http:https://config.internal/app"));
+    /// assert!(matches!(err.root(), ConfigError::LoadError(_)));
+    /// ```
+    pub fn source_name(&self) -> Option<&str> {
+        match self {
+            Self::WithContext {
+                source_name: Some(name),
+                ..
+            } => Some(name),
+            Self::WithContext { error, .. } => error.source_name(),
+            Self::Shared(error) => error.source_name(),
+            _ => None,
+        }
+    }
+
+    /// Get the file the failing source reads, if known.
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Self::WithContext {
+                path: Some(path), ..
+            } => Some(path),
+            Self::WithContext { error, .. } => error.path(),
+            Self::Shared(error) => error.path(),
+            _ => None,
+        }
+    }
+
+    /// Get the dotted path of the key this error concerns, if known.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hotswap_config::error::ConfigError;
+    ///
+    /// let err = ConfigError::DeserializationError("invalid type".to_string())
+    ///     .at_key("server.port");
+    /// assert_eq!(err.key_path(), Some("server.port"));
+    /// assert_eq!(err.to_string(), "At 'server.port': Failed to deserialize configuration: invalid type");
+    /// ```
+    pub fn key_path(&self) -> Option<&str> {
+        match self {
+            Self::WithContext {
+                key_path: Some(key),
+                ..
+            } => Some(key),
+            Self::WithContext { error, .. } => error.key_path(),
+            Self::Shared(error) => error.key_path(),
+            Self::InterpolationError { key, .. } => Some(key),
+            _ => None,
+        }
+    }
+
+    /// Get the underlying error, without any context.
+    pub fn root(&self) -> &ConfigError {
+        match self {
+            Self::WithContext { error, .. } => error.root(),
+            Self::Shared(error) => error.root(),
+            error => error,
+        }
+    }
+
+    /// Attach the name of the source this error came from, and the file it
+    /// reads.
+    pub fn in_source(self, source_name: impl Into<String>, path: Option<PathBuf>) -> Self {
+        match self {
+            Self::WithContext {
+                key_path, error, ..
+            } => Self::WithContext {
+                source_name: Some(source_name.into()),
+                path,
+                key_path,
+                error,
+            },
+            error => Self::WithContext {
+                source_name: Some(source_name.into()),
+                path,
+                key_path: None,
+                error: Box::new(error),
+            },
+        }
+    }
+
+    /// Attach the dotted path of the key this error concerns.
+    pub fn at_key(self, key_path: impl Into<String>) -> Self {
+        match self {
+            Self::WithContext {
+                source_name,
+                path,
+                error,
+                ..
+            } => Self::WithContext {
+                source_name,
+                path,
+                key_path: Some(key_path.into()),
+                error,
+            },
+            error => Self::WithContext {
+                source_name: None,
+                path: None,
+                key_path: Some(key_path.into()),
+                error: Box::new(error),
+            },
+        }
+    }
 }
 
 /// Validation error for configuration validation.
@@ -91,6 +445,61 @@ impl ValidationError {
             reason: reason.into(),
         }
     }
+
+    /// Scope this error to the section at `path`.
+    ///
+    /// Field paths are prefixed with `path` (`port` becomes `database.port`),
+    /// and custom errors become errors on the section itself.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hotswap_config::error::ValidationError;
+    ///
+    /// let err = ValidationError::invalid_field("port", "must be >= 1024").at("database");
+    /// assert_eq!(err.to_string(), "Field 'database.port' is invalid: must be >= 1024");
+    /// ```
+    pub fn at(self, path: &str) -> Self {
+        match self {
+            Self::Custom(reason) => Self::InvalidField {
+                field: path.to_string(),
+                reason,
+            },
+            Self::InvalidField { field, reason } => Self::InvalidField {
+                field: format!("{}.{}", path, field),
+                reason,
+            },
+            Self::Multiple(errors) => {
+                Self::Multiple(errors.into_iter().map(|e| e.at(path)).collect())
+            }
+        }
+    }
+
+    /// Combine several validation results' errors into one.
+    ///
+    /// Returns `Ok` if there are no errors, the error itself if there is one,
+    /// and [`Multiple`](Self::Multiple) (flattened) otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns the combined error if `errors` is not empty.
+    pub fn collect(
+        errors: impl IntoIterator<Item = ValidationError>,
+    ) -> std::result::Result<(), Self> {
+        let mut flattened = Vec::new();
+        for error in errors {
+            match error {
+                Self::Multiple(errors) => flattened.extend(errors),
+                error => flattened.push(error),
+            }
+        }
+
+        match flattened.len() {
+            0 => Ok(()),
+            1 => Err(flattened.remove(0)),
+            _ => Err(Self::Multiple(flattened)),
+        }
+    }
 }
 
 impl fmt::Display for ValidationError {
@@ -113,6 +522,64 @@ impl fmt::Display for ValidationError {
 
 impl std::error::Error for ValidationError {}
 
+/// Error returned by a transactional subscriber that couldn't apply a new
+/// configuration.
+#[derive(Debug)]
+pub struct ApplyError(String);
+
+impl ApplyError {
+    /// Create an apply error with a message.
+    pub fn new(msg: impl Into<String>) -> Self {
+        Self(msg.into())
+    }
+}
+
+impl fmt::Display for ApplyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ApplyError {}
+
+/// Error returned by async validators.
+///
+/// Distinguishes a configuration that is known to be wrong from one whose
+/// external check couldn't be completed.
+#[derive(Debug)]
+pub enum VerificationError {
+    /// The configuration is invalid; reported as [`ConfigError::ValidationError`].
+    Invalid(ValidationError),
+
+    /// The check itself failed (e.g. a connection error); reported as
+    /// [`ConfigError::VerificationFailed`].
+    Failed(String),
+}
+
+impl VerificationError {
+    /// Create an error for a check that could not be completed.
+    pub fn failed(msg: impl Into<String>) -> Self {
+        Self::Failed(msg.into())
+    }
+}
+
+impl fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Invalid(err) => write!(f, "{}", err),
+            Self::Failed(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for VerificationError {}
+
+impl From<ValidationError> for VerificationError {
+    fn from(err: ValidationError) -> Self {
+        Self::Invalid(err)
+    }
+}
+
 impl From<ValidationError> for ConfigError {
     fn from(err: ValidationError) -> Self {
         ConfigError::ValidationError(err.to_string())
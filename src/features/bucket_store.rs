@@ -0,0 +1,109 @@
+//! Storage backends for sticky rollout buckets.
+//!
+//! A [`BucketStore`] lets a [`GradualRollout`](super::GradualRollout) assign
+//! each key a bucket once and keep it, so raising the percentage only ever
+//! adds keys to the canary, even across restarts or hasher changes.
+
+use crate::error::Result;
+
+/// Storage backend for sticky rollout buckets.
+///
+/// Implement this trait to share assignments between instances or keep them
+/// across restarts (e.g., in Redis or a database). Within one process a key
+/// already keeps its hashed bucket, so a store only pays off when it outlives
+/// the process or the hasher. It's called on the request path for keyed
+/// lookups while a canary is partially rolled out, so it should be fast.
+pub trait BucketStore: Send + Sync {
+    /// Get the bucket assigned to `key`, assigning `bucket` if it has none.
+    ///
+    /// Must return the existing bucket when `key` already has one, so
+    /// concurrent first requests for a key agree.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store cannot be read or written.
+    fn bucket(&self, key: &str, bucket: u8) -> Result<u8>;
+
+    /// Forget every assignment, once the canary is promoted or rolled back.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the assignments cannot be deleted.
+    fn clear(&self) -> Result<()>;
+}
+
+/// Assigns each key a bucket from a fixed hash of the key, storing nothing.
+///
+/// Without a store, keyed lookups use the standard library's hasher, which
+/// may change between Rust releases. This store uses FNV-1a instead, so a key
+/// keeps its bucket across restarts, instances, and builds without any
+/// shared state. A salt gives each rollout its own assignment.
+///
+/// # Examples
+///
+/// ```rust
+/// use hotswap_config::features::{GradualRollout, HashBucketStore};
+/// use std::sync::Arc;
+///
+/// let rollout = GradualRollout::new(Arc::new(42))
+///     .with_sticky_buckets(HashBucketStore::with_salt("checkout-v2"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct HashBucketStore {
+    salt: String,
+}
+
+impl HashBucketStore {
+    /// Create a store without a salt.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a store that hashes `salt` ahead of each key.
+    pub fn with_salt(salt: impl Into<String>) -> Self {
+        Self { salt: salt.into() }
+    }
+}
+
+impl BucketStore for HashBucketStore {
+    fn bucket(&self, key: &str, _bucket: u8) -> Result<u8> {
+        const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+        const PRIME: u64 = 0x0100_0000_01b3;
+
+        let hash = self
+            .salt
+            .bytes()
+            .chain(std::iter::once(0))
+            .chain(key.bytes())
+            .fold(OFFSET, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+            });
+        Ok((hash % 100) as u8)
+    }
+
+    fn clear(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_buckets_are_fixed() {
+        let store = HashBucketStore::new();
+        assert_eq!(
+            store.bucket("alice", 0).unwrap(),
+            store.bucket("alice", 99).unwrap()
+        );
+        assert!((0..1000).all(|i| store.bucket(&format!("user{}", i), 0).unwrap() < 100));
+
+        // Salting reshuffles keys
+        let salted = HashBucketStore::with_salt("checkout-v2");
+        assert!((0..100).any(|i| {
+            let key = format!("user{}", i);
+            store.bucket(&key, 0).unwrap() != salted.bucket(&key, 0).unwrap()
+        }));
+    }
+}
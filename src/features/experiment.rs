@@ -0,0 +1,632 @@
+//! Multi-variant configuration experiments.
+//!
+//! Where a [`GradualRollout`](super::GradualRollout) compares a stable config
+//! with one canary, an [`Experiment`] serves several labelled variants side by
+//! side, each to its own share of traffic, and promotes or abandons each of
+//! them on its own.
+
+use super::BucketStore;
+use super::gradual::{clear_buckets, key_bucket};
+use crate::core::HotswapConfig;
+use crate::error::{ConfigError, Result};
+use crate::metrics::MetricsRecorder;
+use arc_swap::ArcSwap;
+use std::future::Future;
+use std::ops::Range;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+/// Callback that publishes a promoted variant, rejecting it with an error.
+type PromoteHandler<T> =
+    Arc<dyn Fn(Arc<T>) -> Pin<Box<dyn Future<Output = Result<()>> + Send>> + Send + Sync>;
+
+/// Callback told which variant each keyed lookup was served, `None` for the
+/// control.
+type ExposureHandler = Arc<dyn Fn(&str, Option<&str>) + Send + Sync>;
+
+/// Label the control is recorded under in metrics.
+const CONTROL: &str = "control";
+
+/// A variant and the buckets it's served to.
+struct Arm<T> {
+    label: String,
+    config: Arc<T>,
+    /// The variant's buckets, in one or more ranges
+    ranges: Vec<Range<u8>>,
+    /// Number of buckets, i.e. percentage of traffic
+    weight: u8,
+}
+
+impl<T> Arm<T> {
+    fn contains(&self, bucket: u8) -> bool {
+        self.ranges.iter().any(|range| range.contains(&bucket))
+    }
+}
+
+impl<T> Clone for Arm<T> {
+    fn clone(&self) -> Self {
+        Self {
+            label: self.label.clone(),
+            config: Arc::clone(&self.config),
+            ranges: self.ranges.clone(),
+            weight: self.weight,
+        }
+    }
+}
+
+/// A configuration experiment with any number of weighted variants.
+///
+/// Each key falls into one of 100 buckets, and each variant is given
+/// `weight` of the free buckets when it's added; keys in no variant's
+/// buckets get the control config. Buckets never move, so a key keeps its
+/// variant for as long as that variant runs: adding, promoting, or
+/// abandoning one variant doesn't reassign keys of the others.
+///
+/// Like [`GradualRollout`](super::GradualRollout), [`get`](Self::get) is
+/// synchronous and lock-free, apart from any
+/// [sticky bucket store](Self::with_sticky_buckets).
+///
+/// # Examples
+///
+/// ```rust
+/// use hotswap_config::features::Experiment;
+/// use std::sync::Arc;
+///
+/// # async fn example() -> hotswap_config::error::Result<()> {
+/// let experiment = Experiment::new(Arc::new(100));
+///
+/// // Try three tuning profiles on 10% of traffic each
+/// experiment.add_variant("aggressive", Arc::new(400), 10)?;
+/// experiment.add_variant("balanced", Arc::new(200), 10)?;
+/// experiment.add_variant("conservative", Arc::new(150), 10)?;
+///
+/// let config = experiment.get(Some("user123"));
+///
+/// // Keep the best, drop the rest
+/// experiment.promote("balanced").await?;
+/// experiment.abandon("aggressive")?;
+/// experiment.abandon("conservative")?;
+/// assert_eq!(*experiment.get_control(), 200);
+/// # Ok(())
+/// # }
+/// ```
+pub struct Experiment<T> {
+    control: Arc<ArcSwap<T>>,
+    arms: Arc<ArcSwap<Vec<Arm<T>>>>,
+    /// Serializes changes to the variants
+    changes: Arc<Mutex<()>>,
+    buckets: Option<Arc<dyn BucketStore>>,
+    on_promote: Option<PromoteHandler<T>>,
+    on_exposure: Option<ExposureHandler>,
+    metrics: Option<Arc<dyn MetricsRecorder>>,
+}
+
+impl<T> Experiment<T> {
+    /// Create an experiment with no variants, serving `control` to everyone.
+    pub fn new(control: Arc<T>) -> Self {
+        Self {
+            control: Arc::new(ArcSwap::new(control)),
+            arms: Arc::new(ArcSwap::from_pointee(Vec::new())),
+            changes: Arc::default(),
+            buckets: None,
+            on_promote: None,
+            on_exposure: None,
+            metrics: None,
+        }
+    }
+
+    /// Keep each key's bucket in `store`, so assignments survive restarts.
+    ///
+    /// See [`GradualRollout::with_sticky_buckets`](super::GradualRollout::with_sticky_buckets).
+    /// The assignments are cleared once no variant is left running.
+    pub fn with_sticky_buckets(mut self, store: impl BucketStore + 'static) -> Self {
+        self.buckets = Some(Arc::new(store));
+        self
+    }
+
+    /// Call `handler` with the key and variant label of every keyed lookup
+    /// made while a variant is running, or `None` for the control.
+    ///
+    /// Use it to log exposures for experiment analytics, as with
+    /// [`GradualRollout::with_exposure_handler`](super::GradualRollout::with_exposure_handler).
+    /// The handler runs on the request path, so it should be cheap and must
+    /// not block.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hotswap_config::features::Experiment;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let exposures = Arc::new(Mutex::new(Vec::new()));
+    /// let log = Arc::clone(&exposures);
+    /// let experiment = Experiment::new(Arc::new(100)).with_exposure_handler(move |key, variant| {
+    ///     log.lock().unwrap().push((key.to_string(), variant.map(str::to_string)));
+    /// });
+    ///
+    /// experiment.add_variant("fast", Arc::new(400), 100).unwrap();
+    /// experiment.get(Some("user123"));
+    /// assert_eq!(
+    ///     *exposures.lock().unwrap(),
+    ///     vec![("user123".to_string(), Some("fast".to_string()))]
+    /// );
+    /// ```
+    pub fn with_exposure_handler<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&str, Option<&str>) + Send + Sync + 'static,
+    {
+        self.on_exposure = Some(Arc::new(handler));
+        self
+    }
+
+    /// Record every lookup with `recorder`, by variant label (`"control"`
+    /// for the control).
+    ///
+    /// An experiment started from a `HotswapConfig` with
+    /// [`start_experiment`](ExperimentExt::start_experiment) records into the
+    /// handle's recorder already.
+    pub fn with_metrics<R: MetricsRecorder + 'static>(self, recorder: R) -> Self {
+        self.with_shared_metrics(Arc::new(recorder))
+    }
+
+    /// Record lookups with a recorder shared with a handle.
+    fn with_shared_metrics(mut self, recorder: Arc<dyn MetricsRecorder>) -> Self {
+        self.metrics = Some(recorder);
+        self
+    }
+
+    /// Publish promoted variants with `handler`, which may reject them.
+    fn with_promote_handler(mut self, handler: PromoteHandler<T>) -> Self {
+        self.on_promote = Some(handler);
+        self
+    }
+
+    /// Start serving `config` as the variant `label` to `weight`% of traffic.
+    ///
+    /// The variant takes the lowest `weight` free buckets, which may include
+    /// ones freed by promoted or abandoned variants, so it's added whenever
+    /// enough traffic is free, even if no single free range is large enough.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `label` is empty, `"control"`, or already running,
+    /// `weight` is not between 1 and 100, or less than `weight`% of traffic
+    /// is free.
+    pub fn add_variant(&self, label: impl Into<String>, config: Arc<T>, weight: u8) -> Result<()> {
+        let label = label.into();
+        if label.is_empty() {
+            return Err(ConfigError::Other(
+                "Experiment variant label must not be empty".to_string(),
+            ));
+        }
+        if label == CONTROL {
+            return Err(ConfigError::Other(format!(
+                "Experiment variant label '{}' is reserved for the control",
+                CONTROL
+            )));
+        }
+        if !(1..=100).contains(&weight) {
+            return Err(ConfigError::Other(format!(
+                "Weight of experiment variant '{}' must be between 1 and 100, got {}",
+                label, weight
+            )));
+        }
+
+        let _changes = self.changes.lock().unwrap();
+        let mut arms = Vec::clone(&self.arms.load());
+        if arms.iter().any(|arm| arm.label == label) {
+            return Err(ConfigError::Other(format!(
+                "Experiment variant '{}' is already running",
+                label
+            )));
+        }
+
+        let free = unassigned(&arms);
+        if free < weight {
+            return Err(ConfigError::Other(format!(
+                "No room for {}% of traffic for experiment variant '{}'; {}% is free",
+                weight, label, free
+            )));
+        }
+        let ranges = free_ranges(&arms, weight);
+        arms.push(Arm {
+            label,
+            config,
+            ranges,
+            weight,
+        });
+        self.arms.store(Arc::new(arms));
+        Ok(())
+    }
+
+    /// Stop serving the variant `label`; its keys go back to the control.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no variant `label` is running.
+    pub fn abandon(&self, label: &str) -> Result<()> {
+        self.remove(label)
+    }
+
+    /// Make the variant `label` the control, ending that variant.
+    ///
+    /// The other variants keep running. For an experiment started from a
+    /// `HotswapConfig` with
+    /// [`start_experiment`](ExperimentExt::start_experiment), the variant is
+    /// applied with [`update`](HotswapConfig::update) first, so it's validated
+    /// and subscribers are notified; if it's rejected, nothing changes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no variant `label` is running, or if the handle
+    /// rejects it.
+    pub async fn promote(&self, label: &str) -> Result<()> {
+        let config = self
+            .get_variant(label)
+            .ok_or_else(|| unknown_variant(label))?;
+
+        if let Some(handler) = &self.on_promote {
+            handler(Arc::clone(&config)).await?;
+        }
+
+        // Publish the new control before the variant's keys fall back to it;
+        // the variant may have been abandoned meanwhile, which is fine
+        self.control.store(config);
+        let _ = self.remove(label);
+        Ok(())
+    }
+
+    /// Get the config for a request, by its variant.
+    ///
+    /// With a key, the same key always gets the same variant while it runs.
+    /// Without one, a variant is picked at random by weight. Lookups are
+    /// recorded by any metrics recorder and, with a key, reported to any
+    /// [exposure handler](Self::with_exposure_handler).
+    pub fn get(&self, key: Option<&str>) -> Arc<T> {
+        let arms = self.arms.load();
+        if arms.is_empty() {
+            if let Some(metrics) = &self.metrics {
+                metrics.rollout_selected(CONTROL);
+            }
+            return self.control.load_full();
+        }
+
+        let bucket = self.bucket(key);
+        let arm = arms.iter().find(|arm| arm.contains(bucket));
+        if self.metrics.is_some() || self.on_exposure.is_some() {
+            self.record(key, arm.map(|arm| arm.label.as_str()));
+        }
+        match arm {
+            Some(arm) => Arc::clone(&arm.config),
+            None => self.control.load_full(),
+        }
+    }
+
+    /// Get the label of the variant `key` is assigned to, or `None` for the
+    /// control.
+    pub fn assignment(&self, key: &str) -> Option<String> {
+        let arms = self.arms.load();
+        if arms.is_empty() {
+            return None;
+        }
+
+        let bucket = self.bucket(Some(key));
+        arms.iter()
+            .find(|arm| arm.contains(bucket))
+            .map(|arm| arm.label.clone())
+    }
+
+    /// Get the labels of the running variants, in the order they were added.
+    pub fn variants(&self) -> Vec<String> {
+        self.arms
+            .load()
+            .iter()
+            .map(|arm| arm.label.clone())
+            .collect()
+    }
+
+    /// Get the config of the variant `label`, if it's running.
+    pub fn get_variant(&self, label: &str) -> Option<Arc<T>> {
+        self.arms
+            .load()
+            .iter()
+            .find(|arm| arm.label == label)
+            .map(|arm| Arc::clone(&arm.config))
+    }
+
+    /// Get the percentage of traffic served the variant `label`, if it's
+    /// running.
+    pub fn weight(&self, label: &str) -> Option<u8> {
+        self.arms
+            .load()
+            .iter()
+            .find(|arm| arm.label == label)
+            .map(|arm| arm.weight)
+    }
+
+    /// Get the percentage of traffic served the control.
+    pub fn control_weight(&self) -> u8 {
+        unassigned(&self.arms.load())
+    }
+
+    /// Get the control configuration.
+    pub fn get_control(&self) -> Arc<T> {
+        self.control.load_full()
+    }
+
+    /// Record a lookup served `variant`, `None` for the control.
+    fn record(&self, key: Option<&str>, variant: Option<&str>) {
+        if let Some(metrics) = &self.metrics {
+            metrics.rollout_selected(variant.unwrap_or(CONTROL));
+        }
+        if let (Some(handler), Some(key)) = (&self.on_exposure, key) {
+            handler(key, variant);
+        }
+    }
+
+    fn bucket(&self, key: Option<&str>) -> u8 {
+        match key {
+            Some(key) => key_bucket(key, self.buckets.as_deref()),
+            None => fastrand::u8(0..100),
+        }
+    }
+
+    /// Stop serving the variant `label`.
+    fn remove(&self, label: &str) -> Result<()> {
+        let _changes = self.changes.lock().unwrap();
+        let mut arms = Vec::clone(&self.arms.load());
+        let index = arms
+            .iter()
+            .position(|arm| arm.label == label)
+            .ok_or_else(|| unknown_variant(label))?;
+        arms.remove(index);
+
+        if arms.is_empty() {
+            clear_buckets(self.buckets.as_deref());
+        }
+        self.arms.store(Arc::new(arms));
+        Ok(())
+    }
+}
+
+impl<T> Clone for Experiment<T> {
+    fn clone(&self) -> Self {
+        Self {
+            control: Arc::clone(&self.control),
+            arms: Arc::clone(&self.arms),
+            changes: Arc::clone(&self.changes),
+            buckets: self.buckets.clone(),
+            on_promote: self.on_promote.clone(),
+            on_exposure: self.on_exposure.clone(),
+            metrics: self.metrics.clone(),
+        }
+    }
+}
+
+/// The lowest `weight` free buckets, as ranges. At least `weight` buckets
+/// must be free.
+fn free_ranges<T>(arms: &[Arm<T>], weight: u8) -> Vec<Range<u8>> {
+    let mut ranges: Vec<Range<u8>> = Vec::new();
+    let free = (0..100).filter(|&bucket| !arms.iter().any(|arm| arm.contains(bucket)));
+    for bucket in free.take(weight as usize) {
+        match ranges.last_mut() {
+            Some(range) if range.end == bucket => range.end += 1,
+            _ => ranges.push(bucket..bucket + 1),
+        }
+    }
+    ranges
+}
+
+/// Percentage of traffic in no variant's range.
+fn unassigned<T>(arms: &[Arm<T>]) -> u8 {
+    100 - arms.iter().map(|arm| arm.weight).sum::<u8>()
+}
+
+fn unknown_variant(label: &str) -> ConfigError {
+    ConfigError::Other(format!("No experiment variant '{}' is running", label))
+}
+
+/// Extension trait for running experiments on a HotswapConfig.
+pub trait ExperimentExt<T> {
+    /// Start an experiment with the current config as the control.
+    ///
+    /// Promoting a variant applies it to this handle with
+    /// [`update`](HotswapConfig::update).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    /// use hotswap_config::features::ExperimentExt;
+    /// use serde::Deserialize;
+    /// use std::sync::Arc;
+    ///
+    /// #[derive(Debug, Deserialize, Clone)]
+    /// struct AppConfig {
+    ///     batch_size: u32,
+    /// }
+    ///
+    /// # async fn example(config: HotswapConfig<AppConfig>) -> Result<()> {
+    /// let experiment = config.start_experiment();
+    /// experiment.add_variant("small", Arc::new(AppConfig { batch_size: 16 }), 20)?;
+    /// experiment.add_variant("large", Arc::new(AppConfig { batch_size: 256 }), 20)?;
+    ///
+    /// let batch_size = experiment.get(Some("tenant-42")).batch_size;
+    ///
+    /// experiment.promote("large").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn start_experiment(&self) -> Experiment<T>;
+}
+
+impl<T> ExperimentExt<T> for HotswapConfig<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    fn start_experiment(&self) -> Experiment<T> {
+        let handle = self.clone();
+        let handler: PromoteHandler<T> = Arc::new(move |config| {
+            let handle = handle.clone();
+            Box::pin(async move { handle.update(T::clone(&config)).await })
+        });
+        let experiment = Experiment::new(self.get()).with_promote_handler(handler);
+        match self.metrics_recorder() {
+            Some(metrics) => experiment.with_shared_metrics(Arc::clone(metrics)),
+            None => experiment,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn experiment() -> Experiment<i32> {
+        let experiment = Experiment::new(Arc::new(0));
+        experiment.add_variant("a", Arc::new(1), 20).unwrap();
+        experiment.add_variant("b", Arc::new(2), 30).unwrap();
+        experiment.add_variant("c", Arc::new(3), 10).unwrap();
+        experiment
+    }
+
+    fn keys() -> Vec<String> {
+        (0..1000).map(|i| format!("user{}", i)).collect()
+    }
+
+    #[test]
+    fn test_weighted_assignment() {
+        let experiment = experiment();
+        assert_eq!(experiment.variants(), vec!["a", "b", "c"]);
+        assert_eq!(experiment.control_weight(), 40);
+
+        let mut counts = [0i32; 4];
+        for key in keys() {
+            let config = experiment.get(Some(&key));
+            assert_eq!(*experiment.get(Some(&key)), *config);
+            counts[*config as usize] += 1;
+        }
+
+        // Roughly 40/20/30/10, with room for hashing noise
+        for (count, expected) in counts.iter().zip([400, 200, 300, 100]) {
+            assert!((count - expected).abs() < 60, "{:?}", counts);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ending_a_variant_keeps_other_assignments() {
+        let experiment = experiment();
+        let before: Vec<Option<String>> = keys()
+            .iter()
+            .map(|key| experiment.assignment(key))
+            .collect();
+
+        experiment.abandon("a").unwrap();
+        experiment.promote("b").await.unwrap();
+        assert_eq!(experiment.variants(), vec!["c"]);
+        assert_eq!(*experiment.get_control(), 2);
+
+        for (key, before) in keys().iter().zip(before) {
+            let after = experiment.assignment(key);
+            match before.as_deref() {
+                Some("c") => assert_eq!(after.as_deref(), Some("c")),
+                _ => assert_eq!(after, None),
+            }
+        }
+
+        // The freed range is reused by the next variant
+        experiment.add_variant("d", Arc::new(4), 50).unwrap();
+        assert_eq!(experiment.control_weight(), 40);
+    }
+
+    #[test]
+    fn test_variant_spans_freed_ranges() {
+        let experiment = experiment();
+        experiment.abandon("a").unwrap();
+        experiment.abandon("c").unwrap();
+        let before: Vec<Option<String>> = keys()
+            .iter()
+            .map(|key| experiment.assignment(key))
+            .collect();
+
+        // 70% is free, but no single range of 60 buckets is
+        experiment.add_variant("d", Arc::new(4), 60).unwrap();
+        assert_eq!(experiment.control_weight(), 10);
+
+        let mut on_d = 0;
+        for (key, before) in keys().iter().zip(before) {
+            let after = experiment.assignment(key);
+            match before.as_deref() {
+                Some("b") => assert_eq!(after.as_deref(), Some("b")),
+                _ => on_d += i32::from(after.is_some()),
+            }
+        }
+        assert!((on_d - 600).abs() < 60, "{}", on_d);
+    }
+
+    #[test]
+    fn test_selections_are_recorded() {
+        #[derive(Default)]
+        struct Selections(Mutex<Vec<String>>);
+
+        impl MetricsRecorder for Selections {
+            fn reload_attempted(&self) {}
+            fn reload_succeeded(&self, _duration: std::time::Duration) {}
+            fn reload_failed(&self, _duration: std::time::Duration) {}
+            fn validation_failed(&self) {}
+            fn config_updated(&self) {}
+            fn subscribers_changed(&self, _count: usize) {}
+            fn rollout_selected(&self, variant: &str) {
+                self.0.lock().unwrap().push(variant.to_string());
+            }
+        }
+
+        let recorder = Arc::new(Selections::default());
+        let exposures = Arc::new(Mutex::new(Vec::new()));
+        let log = Arc::clone(&exposures);
+        let experiment = Experiment::new(Arc::new(0))
+            .with_shared_metrics(Arc::clone(&recorder) as Arc<dyn MetricsRecorder>)
+            .with_exposure_handler(move |key, variant| {
+                log.lock()
+                    .unwrap()
+                    .push((key.to_string(), variant.map(str::to_string)));
+            });
+
+        // Nothing to expose without a variant
+        experiment.get(Some("alice"));
+
+        experiment.add_variant("a", Arc::new(1), 100).unwrap();
+        experiment.get(Some("bob"));
+        experiment.get(None);
+
+        assert_eq!(*recorder.0.lock().unwrap(), vec!["control", "a", "a"]);
+        assert_eq!(
+            *exposures.lock().unwrap(),
+            vec![("bob".to_string(), Some("a".to_string()))]
+        );
+    }
+
+    #[test]
+    fn test_variant_errors() {
+        let experiment = experiment();
+        assert!(experiment.add_variant("a", Arc::new(9), 5).is_err());
+        assert!(experiment.add_variant("", Arc::new(9), 5).is_err());
+        assert!(experiment.add_variant("control", Arc::new(9), 5).is_err());
+        assert!(experiment.add_variant("d", Arc::new(9), 0).is_err());
+        assert!(experiment.add_variant("d", Arc::new(9), 41).is_err());
+        assert!(experiment.abandon("missing").is_err());
+        assert_eq!(experiment.variants().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_promote_through_handle() {
+        let config = HotswapConfig::new(0);
+        let experiment = config.start_experiment();
+        experiment.add_variant("a", Arc::new(1), 50).unwrap();
+
+        assert!(experiment.promote("missing").await.is_err());
+        experiment.promote("a").await.unwrap();
+        assert_eq!(*config.get(), 1);
+        assert_eq!(*experiment.get(Some("user123")), 1);
+    }
+}
@@ -0,0 +1,369 @@
+//! Feature flags with per-flag targeting, read from configuration.
+//!
+//! A [`FlagSet`] deserializes from a section of the config, so flags reload
+//! with everything else. Each flag can be switched off entirely, or enabled
+//! for an allowlist of keys, for requests whose attributes match a rule, or
+//! for a percentage of keys. It is [`GradualRollout`](crate::features::GradualRollout)'s
+//! consistent hashing, applied per flag.
+//!
+//! ```yaml
+//! flags:
+//!   dark_mode: true
+//!   new_api:
+//!     percentage: 25
+//!     allow: ["user-42"]
+//!     rules:
+//!       - attribute: region
+//!         values: ["eu-west-1"]
+//! ```
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use hotswap_config::features::{EvalContext, FlagSet};
+//! use hotswap_config::prelude::*;
+//! use serde::Deserialize;
+//!
+//! #[derive(Debug, Deserialize, Clone)]
+//! struct AppConfig {
+//!     flags: FlagSet,
+//! }
+//!
+//! # async fn example(config: HotswapConfig<AppConfig>) {
+//! let context = EvalContext::new()
+//!     .with_key("user-7")
+//!     .with_attribute("region", "us-east-1");
+//! if config.get().flags.enabled("new_api", &context) {
+//!     // Serve from the new API
+//! }
+//! # }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+/// Named feature flags, deserialized from a config map of flag name to
+/// [`Flag`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct FlagSet {
+    flags: HashMap<String, Flag>,
+}
+
+impl FlagSet {
+    /// Create an empty flag set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add or replace a flag.
+    pub fn with_flag(mut self, name: impl Into<String>, flag: Flag) -> Self {
+        self.flags.insert(name.into(), flag);
+        self
+    }
+
+    /// Check whether flag `name` is enabled for `context`.
+    ///
+    /// Unknown flags are disabled.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hotswap_config::features::{EvalContext, Flag, FlagSet};
+    ///
+    /// let flags = FlagSet::new()
+    ///     .with_flag("dark_mode", Flag::on())
+    ///     .with_flag("new_api", Flag::on().with_allow(["user-42"]).with_percentage(0));
+    ///
+    /// assert!(flags.enabled("dark_mode", &EvalContext::new()));
+    /// assert!(flags.enabled("new_api", &EvalContext::new().with_key("user-42")));
+    /// assert!(!flags.enabled("new_api", &EvalContext::new().with_key("user-7")));
+    /// assert!(!flags.enabled("missing", &EvalContext::new()));
+    /// ```
+    pub fn enabled(&self, name: &str, context: &EvalContext) -> bool {
+        self.flags
+            .get(name)
+            .is_some_and(|flag| flag.evaluate(name, context))
+    }
+
+    /// Get a flag by name.
+    pub fn get(&self, name: &str) -> Option<&Flag> {
+        self.flags.get(name)
+    }
+
+    /// Iterate over the names of all flags.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.flags.keys().map(String::as_str)
+    }
+}
+
+/// A single feature flag and its targeting.
+///
+/// A flag that is switched off is disabled for everyone. Otherwise it is
+/// enabled for keys in its allowlist, for contexts matching any rule, and for
+/// its percentage of keys; a flag with none of these is enabled for everyone.
+///
+/// In config, `true` or `false` is shorthand for a flag without targeting.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "FlagRepr")]
+pub struct Flag {
+    /// Kill switch; `false` disables the flag regardless of targeting
+    pub enabled: bool,
+    /// Percentage (0-100) of keys the flag is enabled for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub percentage: Option<u8>,
+    /// Keys the flag is always enabled for
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
+    /// Attribute rules; the flag is enabled if any matches
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<Rule>,
+}
+
+impl Flag {
+    /// A flag enabled for everyone, until targeting is added.
+    pub fn on() -> Self {
+        Self {
+            enabled: true,
+            percentage: None,
+            allow: Vec::new(),
+            rules: Vec::new(),
+        }
+    }
+
+    /// A flag disabled for everyone.
+    pub fn off() -> Self {
+        Self {
+            enabled: false,
+            ..Self::on()
+        }
+    }
+
+    /// Enable the flag for `percentage`% of keys.
+    pub fn with_percentage(mut self, percentage: u8) -> Self {
+        self.percentage = Some(percentage.min(100));
+        self
+    }
+
+    /// Always enable the flag for these keys.
+    pub fn with_allow<I, S>(mut self, keys: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.allow.extend(keys.into_iter().map(Into::into));
+        self
+    }
+
+    /// Enable the flag for contexts matching `rule`.
+    pub fn with_rule(mut self, rule: Rule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Evaluate the flag named `name` for `context`.
+    fn evaluate(&self, name: &str, context: &EvalContext) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        if self.percentage.is_none() && self.allow.is_empty() && self.rules.is_empty() {
+            return true;
+        }
+
+        let key = context.key.as_deref();
+        if key.is_some_and(|key| self.allow.iter().any(|allowed| allowed == key)) {
+            return true;
+        }
+        if self.rules.iter().any(|rule| rule.matches(context)) {
+            return true;
+        }
+        match (self.percentage, key) {
+            (Some(percentage), _) if percentage >= 100 => true,
+            // Bucket per flag, so each flag reaches a different slice of keys
+            (Some(percentage), Some(key)) => {
+                let mut hasher = DefaultHasher::new();
+                name.hash(&mut hasher);
+                key.hash(&mut hasher);
+                (hasher.finish() % 100) < percentage as u64
+            }
+            _ => false,
+        }
+    }
+}
+
+/// A flag as written in config: a bare switch or a table with targeting.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum FlagRepr {
+    Switch(bool),
+    Targeted {
+        #[serde(default = "enabled_by_default")]
+        enabled: bool,
+        #[serde(default)]
+        percentage: Option<u8>,
+        #[serde(default)]
+        allow: Vec<String>,
+        #[serde(default)]
+        rules: Vec<Rule>,
+    },
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+impl From<FlagRepr> for Flag {
+    fn from(repr: FlagRepr) -> Self {
+        match repr {
+            FlagRepr::Switch(true) => Flag::on(),
+            FlagRepr::Switch(false) => Flag::off(),
+            FlagRepr::Targeted {
+                enabled,
+                percentage,
+                allow,
+                rules,
+            } => Flag {
+                enabled,
+                percentage: percentage.map(|p| p.min(100)),
+                allow,
+                rules,
+            },
+        }
+    }
+}
+
+/// Enables a flag when a context attribute has one of the listed values.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rule {
+    /// Name of the context attribute, e.g. `region` or `plan`
+    pub attribute: String,
+    /// Values of the attribute that match
+    pub values: Vec<String>,
+}
+
+impl Rule {
+    /// Match contexts whose `attribute` is one of `values`.
+    pub fn new<I, S>(attribute: impl Into<String>, values: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            attribute: attribute.into(),
+            values: values.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    fn matches(&self, context: &EvalContext) -> bool {
+        context
+            .attributes
+            .get(&self.attribute)
+            .is_some_and(|value| self.values.contains(value))
+    }
+}
+
+/// What a flag is evaluated for: a stable key (user, tenant, or request ID)
+/// and attributes for rules.
+///
+/// Percentage rollouts need a key; without one, only flags at 100% are
+/// enabled.
+#[derive(Debug, Clone, Default)]
+pub struct EvalContext {
+    key: Option<String>,
+    attributes: HashMap<String, String>,
+}
+
+impl EvalContext {
+    /// Create a context without a key or attributes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the key used for allowlists and percentage bucketing.
+    pub fn with_key(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    /// Set an attribute matched by rules.
+    pub fn with_attribute(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.attributes.insert(name.into(), value.into());
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    struct AppConfig {
+        flags: FlagSet,
+    }
+
+    fn parse(yaml: &str) -> FlagSet {
+        let config: AppConfig = config::Config::builder()
+            .add_source(config::File::from_str(yaml, config::FileFormat::Yaml))
+            .build()
+            .unwrap()
+            .try_deserialize()
+            .unwrap();
+        config.flags
+    }
+
+    #[test]
+    fn test_parse_switches_and_targeting() {
+        let flags = parse(
+            r#"
+flags:
+  dark_mode: true
+  legacy: false
+  new_api:
+    percentage: 0
+    allow: ["user-42"]
+    rules:
+      - attribute: region
+        values: ["eu-west-1"]
+  killed:
+    enabled: false
+    allow: ["user-42"]
+"#,
+        );
+        let anonymous = EvalContext::new();
+        let vip = EvalContext::new().with_key("user-42");
+        let european = EvalContext::new()
+            .with_key("user-7")
+            .with_attribute("region", "eu-west-1");
+
+        assert!(flags.enabled("dark_mode", &anonymous));
+        assert!(!flags.enabled("legacy", &anonymous));
+        assert!(!flags.enabled("new_api", &anonymous));
+        assert!(flags.enabled("new_api", &vip));
+        assert!(flags.enabled("new_api", &european));
+        assert!(!flags.enabled("killed", &vip));
+        assert!(!flags.enabled("unknown", &vip));
+        assert_eq!(flags.names().count(), 4);
+    }
+
+    #[test]
+    fn test_percentage_is_consistent_per_key() {
+        let flags = FlagSet::new()
+            .with_flag("half", Flag::on().with_percentage(50))
+            .with_flag("all", Flag::on().with_percentage(100));
+
+        let enabled = (0..1000)
+            .filter(|i| {
+                let context = EvalContext::new().with_key(format!("user-{}", i));
+                let first = flags.enabled("half", &context);
+                assert_eq!(first, flags.enabled("half", &context));
+                first
+            })
+            .count();
+        assert!((400..600).contains(&enabled), "{} of 1000 enabled", enabled);
+
+        // Without a key, only complete rollouts are enabled
+        assert!(!flags.enabled("half", &EvalContext::new()));
+        assert!(flags.enabled("all", &EvalContext::new()));
+    }
+}
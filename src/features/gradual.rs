@@ -3,21 +3,122 @@
 //! Allows rolling out configuration changes to a percentage of requests
 //! before fully committing.
 
+use super::BucketStore;
 use crate::core::HotswapConfig;
 use crate::error::{ConfigError, Result};
+use crate::metrics::MetricsRecorder;
+use arc_swap::{ArcSwap, ArcSwapOption};
 use std::collections::hash_map::DefaultHasher;
+use std::future::Future;
 use std::hash::{Hash, Hasher};
+use std::pin::Pin;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::sync::atomic::{AtomicU8, AtomicU64, Ordering};
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+/// Callback that publishes the new stable config when a canary is promoted,
+/// or refuses it.
+pub(crate) type PromoteHook<T> =
+    Arc<dyn Fn(Arc<T>) -> Pin<Box<dyn Future<Output = Result<()>> + Send>> + Send + Sync>;
+
+/// Runs a task in the background of the handle a rollout is attached to,
+/// reporting its failure.
+pub(crate) type Background =
+    Arc<dyn Fn(Pin<Box<dyn Future<Output = Result<()>> + Send>>) + Send + Sync>;
+
+/// Callback invoked with the key and variant of each keyed selection.
+pub(crate) type ExposureHandler = Arc<dyn Fn(&str, Variant) + Send + Sync>;
+
+/// Which configuration a rollout selected for a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Variant {
+    /// The stable configuration.
+    Stable,
+    /// The canary configuration.
+    Canary,
+}
+
+impl Variant {
+    /// A short, stable label for metrics: `"stable"` or `"canary"`.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Variant::Stable => "stable",
+            Variant::Canary => "canary",
+        }
+    }
+}
+
+impl std::fmt::Display for Variant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+/// Number of times a rollout selected each configuration.
+///
+/// Returned by [`GradualRollout::stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RolloutStats {
+    /// Selections of the stable configuration.
+    pub stable: u64,
+    /// Selections of the canary configuration.
+    pub canary: u64,
+}
+
+/// Selection counters, shared between clones of a rollout.
+#[derive(Default)]
+struct Selections {
+    stable: AtomicU64,
+    canary: AtomicU64,
+}
+
+/// Callback that applies a promoted canary patch as an update changing the
+/// given JSON Pointer paths, returning the config that was swapped in.
+#[cfg(feature = "partial-updates")]
+pub(crate) type UpdateHook<T> = Arc<
+    dyn Fn(T, Vec<String>) -> Pin<Box<dyn Future<Output = Result<Arc<T>>> + Send>> + Send + Sync,
+>;
+
+/// A canary generated by applying a JSON Patch to the stable config.
+#[cfg(feature = "partial-updates")]
+struct CanaryPatch<T> {
+    patch: json_patch::Patch,
+    /// Paths the patch changes, for the update policy
+    paths: Vec<String>,
+    /// Applies the patch to a stable config
+    apply: fn(&T, &json_patch::Patch) -> Result<T>,
+    /// The canary the patch generated
+    canary: Arc<T>,
+}
 
 /// Gradual rollout state for A/B testing configuration changes.
 ///
 /// Maintains two configurations (stable and canary) and selects between them
 /// based on a percentage rollout.
+///
+/// Both slots are held in `ArcSwap`s and the percentage in an atomic, so
+/// [`get`](Self::get) is synchronous and lock-free, suitable for the request
+/// path.
+///
+/// To compare more than one candidate config at a time, use an
+/// [`Experiment`](super::Experiment).
 pub struct GradualRollout<T> {
-    stable: Arc<RwLock<Arc<T>>>,
-    canary: Arc<RwLock<Option<Arc<T>>>>,
-    percentage: Arc<RwLock<u8>>,
+    stable: Arc<ArcSwap<T>>,
+    canary: Arc<ArcSwapOption<T>>,
+    percentage: Arc<AtomicU8>,
+    on_promote: Option<PromoteHook<T>>,
+    buckets: Option<Arc<dyn BucketStore>>,
+    selections: Option<Arc<Selections>>,
+    on_exposure: Option<ExposureHandler>,
+    metrics: Option<Arc<dyn MetricsRecorder>>,
+    /// Stops [`auto_promote`](Self::auto_promote) controllers, e.g. when the
+    /// handle the rollout belongs to shuts down
+    shutdown: Option<CancellationToken>,
+    #[cfg(feature = "partial-updates")]
+    canary_patch: Arc<ArcSwapOption<CanaryPatch<T>>>,
+    #[cfg(feature = "partial-updates")]
+    on_update: Option<UpdateHook<T>>,
 }
 
 impl<T: Clone> GradualRollout<T> {
@@ -37,12 +138,164 @@ impl<T: Clone> GradualRollout<T> {
     /// ```
     pub fn new(stable: Arc<T>) -> Self {
         Self {
-            stable: Arc::new(RwLock::new(stable)),
-            canary: Arc::new(RwLock::new(None)),
-            percentage: Arc::new(RwLock::new(0)),
+            stable: Arc::new(ArcSwap::new(stable)),
+            canary: Arc::new(ArcSwapOption::empty()),
+            percentage: Arc::new(AtomicU8::new(0)),
+            on_promote: None,
+            buckets: None,
+            selections: None,
+            on_exposure: None,
+            metrics: None,
+            shutdown: None,
+            #[cfg(feature = "partial-updates")]
+            canary_patch: Arc::new(ArcSwapOption::empty()),
+            #[cfg(feature = "partial-updates")]
+            on_update: None,
         }
     }
 
+    /// Keep each key's canary assignment in `store` (sticky bucketing).
+    ///
+    /// Without a store, keyed lookups hash the key into a bucket in
+    /// `[0, 100)`, which stays stable only as long as the hasher does. With
+    /// one, each key is offered its hashed bucket the first time it's looked
+    /// up while the canary is partially rolled out, and keeps the bucket the
+    /// store gives it from then on: raising the percentage only adds keys to
+    /// the canary, never moves any back, and a persistent store carries this
+    /// across restarts. The store is only consulted while the percentage is
+    /// between 0 and 100, and its assignments are cleared when the canary is
+    /// promoted or rolled back.
+    ///
+    /// [`HashBucketStore`](super::HashBucketStore) derives assignments from a
+    /// fixed hash without storing anything; implement [`BucketStore`] to keep
+    /// them in a database. If the store fails, the lookup falls back to the
+    /// hashed bucket.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hotswap_config::features::{GradualRollout, HashBucketStore};
+    /// use std::sync::Arc;
+    ///
+    /// let rollout = GradualRollout::new(Arc::new(42))
+    ///     .with_sticky_buckets(HashBucketStore::with_salt("checkout-v2"));
+    /// rollout.set_canary(Arc::new(100), 10);
+    /// let users: Vec<String> = (0..100).map(|i| format!("user{}", i)).collect();
+    /// let canary_users: Vec<&String> = users
+    ///     .iter()
+    ///     .filter(|user| *rollout.get(Some(user)) == 100)
+    ///     .collect();
+    ///
+    /// // Users on the canary at 10% stay on it at 50%
+    /// rollout.set_percentage(50);
+    /// assert!(canary_users.iter().all(|user| *rollout.get(Some(user)) == 100));
+    /// ```
+    pub fn with_sticky_buckets(self, store: impl BucketStore + 'static) -> Self {
+        self.with_bucket_store(Arc::new(store))
+    }
+
+    /// Keep canary assignments in a shared `store`.
+    pub(crate) fn with_bucket_store(mut self, store: Arc<dyn BucketStore>) -> Self {
+        self.buckets = Some(store);
+        self
+    }
+
+    /// Call `handler` with the key and [`Variant`] of every keyed selection
+    /// made while a canary is staged.
+    ///
+    /// Use it to log exposures for experiment analytics: each call of
+    /// [`get`](Self::get) with a key reports which configuration that key
+    /// was served. Lookups without a key, or with no canary staged, aren't
+    /// reported. The handler runs on the request path, so it should be cheap
+    /// and must not block (e.g. push onto a channel).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hotswap_config::features::{GradualRollout, Variant};
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let exposures = Arc::new(Mutex::new(Vec::new()));
+    /// let log = Arc::clone(&exposures);
+    /// let rollout = GradualRollout::new(Arc::new(42)).with_exposure_handler(move |key, variant| {
+    ///     log.lock().unwrap().push((key.to_string(), variant));
+    /// });
+    ///
+    /// rollout.set_canary(Arc::new(100), 100);
+    /// rollout.get(Some("user123"));
+    /// assert_eq!(
+    ///     *exposures.lock().unwrap(),
+    ///     vec![("user123".to_string(), Variant::Canary)]
+    /// );
+    /// ```
+    pub fn with_exposure_handler<F>(self, handler: F) -> Self
+    where
+        F: Fn(&str, Variant) + Send + Sync + 'static,
+    {
+        self.with_shared_exposure_handler(Arc::new(handler))
+    }
+
+    /// Report keyed selections to a shared `handler`.
+    pub(crate) fn with_shared_exposure_handler(mut self, handler: ExposureHandler) -> Self {
+        self.on_exposure = Some(handler);
+        self
+    }
+
+    /// Record every selection with `recorder`, by [`Variant`].
+    ///
+    /// A rollout attached to a `HotswapConfig` built with
+    /// [`with_metrics`](crate::core::HotswapConfigBuilder::with_metrics) or
+    /// [`with_metrics_recorder`](crate::core::HotswapConfigBuilder::with_metrics_recorder)
+    /// records into the handle's recorder already. To count selections
+    /// without a recorder, use [`with_stats`](Self::with_stats).
+    pub fn with_metrics<R: MetricsRecorder + 'static>(self, recorder: R) -> Self {
+        self.with_shared_metrics(Arc::new(recorder))
+    }
+
+    /// Record selections with a recorder shared with a handle.
+    pub(crate) fn with_shared_metrics(mut self, recorder: Arc<dyn MetricsRecorder>) -> Self {
+        self.metrics = Some(recorder);
+        self
+    }
+
+    /// Stop automatic promotions once `token` is cancelled.
+    pub(crate) fn with_shutdown(mut self, token: CancellationToken) -> Self {
+        self.shutdown = Some(token);
+        self
+    }
+
+    /// Count the selections [`get`](Self::get) makes, for [`stats`](Self::stats).
+    ///
+    /// Off by default, so a rollout without stats, a recorder, or an
+    /// exposure handler doesn't touch shared counters on the request path.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hotswap_config::features::GradualRollout;
+    /// use std::sync::Arc;
+    ///
+    /// let rollout: GradualRollout<i32> = GradualRollout::new(Arc::new(42)).with_stats();
+    /// ```
+    pub fn with_stats(mut self) -> Self {
+        self.selections = Some(Arc::default());
+        self
+    }
+
+    /// Attach a hook that publishes promoted configs (e.g. to a `HotswapConfig`).
+    pub(crate) fn with_promote_hook(mut self, hook: PromoteHook<T>) -> Self {
+        self.on_promote = Some(hook);
+        self
+    }
+
+    /// Attach a hook that promotes canary patches through the normal update
+    /// path (e.g. of a `HotswapConfig`), instead of publishing them directly.
+    #[cfg(feature = "partial-updates")]
+    pub(crate) fn with_update_hook(mut self, hook: UpdateHook<T>) -> Self {
+        self.on_update = Some(hook);
+        self
+    }
+
     /// Set the canary configuration and rollout percentage.
     ///
     /// # Arguments
@@ -55,17 +308,17 @@ impl<T: Clone> GradualRollout<T> {
     /// ```rust
     /// # use hotswap_config::features::GradualRollout;
     /// # use std::sync::Arc;
-    /// # async fn example() {
     /// let rollout: GradualRollout<i32> = GradualRollout::new(Arc::new(42));
     ///
     /// // Start with 10% rollout
-    /// rollout.set_canary(Arc::new(100), 10).await;
-    /// # }
+    /// rollout.set_canary(Arc::new(100), 10);
     /// ```
-    pub async fn set_canary(&self, canary: Arc<T>, percentage: u8) {
+    pub fn set_canary(&self, canary: Arc<T>, percentage: u8) {
         let percentage = percentage.min(100);
-        *self.canary.write().await = Some(canary);
-        *self.percentage.write().await = percentage;
+        #[cfg(feature = "partial-updates")]
+        self.canary_patch.store(None);
+        self.canary.store(Some(canary));
+        self.percentage.store(percentage, Ordering::Release);
     }
 
     /// Increase the canary rollout percentage.
@@ -81,27 +334,62 @@ impl<T: Clone> GradualRollout<T> {
     /// ```rust
     /// # use hotswap_config::features::GradualRollout;
     /// # use std::sync::Arc;
-    /// # async fn example() {
     /// let rollout: GradualRollout<i32> = GradualRollout::new(Arc::new(42));
-    /// rollout.set_canary(Arc::new(100), 10).await;
+    /// rollout.set_canary(Arc::new(100), 10);
     ///
     /// // Increase to 20%
-    /// rollout.increase_percentage(10).await;
-    /// # }
+    /// assert_eq!(rollout.increase_percentage(10), 20);
     /// ```
-    pub async fn increase_percentage(&self, delta: u8) -> u8 {
-        let mut percentage = self.percentage.write().await;
-        *percentage = (*percentage + delta).min(100);
-        *percentage
+    pub fn increase_percentage(&self, delta: u8) -> u8 {
+        let increase = |p: u8| Some(p.saturating_add(delta).min(100));
+        let previous = self
+            .percentage
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, increase)
+            .unwrap_or_else(|p| p);
+        previous.saturating_add(delta).min(100)
+    }
+
+    /// Set the canary rollout percentage (clamped to 100).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use hotswap_config::features::GradualRollout;
+    /// # use std::sync::Arc;
+    /// let rollout: GradualRollout<i32> = GradualRollout::new(Arc::new(42));
+    /// rollout.set_canary(Arc::new(100), 10);
+    ///
+    /// rollout.set_percentage(50);
+    /// assert_eq!(rollout.get_percentage(), 50);
+    /// ```
+    pub fn set_percentage(&self, percentage: u8) {
+        self.percentage
+            .store(percentage.min(100), Ordering::Release);
     }
 
     /// Promote the canary to stable.
     ///
     /// Replaces the stable configuration with the canary and clears the canary.
+    /// For a rollout attached to a `HotswapConfig` via
+    /// [`with_canary_reloads`](crate::core::HotswapConfigBuilder::with_canary_reloads),
+    /// the promoted config also becomes the handle's current config and
+    /// subscribers are notified. It's swapped in like a reload: not while the
+    /// handle is frozen, and only if the pre-swap hooks and transactional
+    /// subscribers accept it; otherwise the canary stays staged.
+    ///
+    /// A canary staged with
+    /// [`set_canary_patch`](Self::set_canary_patch) is promoted by applying
+    /// its patch to the stable config once more. On an attached rollout this
+    /// goes through the handle's update path, like
+    /// [`apply_patch`](crate::features::PartialUpdate::apply_patch): the
+    /// update policy, validators, and transactional subscribers all see it,
+    /// and if any of them rejects it the canary stays staged.
     ///
     /// # Errors
     ///
-    /// Returns an error if there is no canary configuration.
+    /// Returns an error if there is no canary configuration, if a canary
+    /// patch can't be applied to the stable config, or if an attached handle
+    /// refuses the canary.
     ///
     /// # Examples
     ///
@@ -110,7 +398,7 @@ impl<T: Clone> GradualRollout<T> {
     /// # use std::sync::Arc;
     /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
     /// let rollout: GradualRollout<i32> = GradualRollout::new(Arc::new(42));
-    /// rollout.set_canary(Arc::new(100), 50).await;
+    /// rollout.set_canary(Arc::new(100), 50);
     ///
     /// // Promote canary to stable
     /// rollout.promote().await?;
@@ -118,13 +406,31 @@ impl<T: Clone> GradualRollout<T> {
     /// # }
     /// ```
     pub async fn promote(&self) -> Result<()> {
-        let mut canary = self.canary.write().await;
-        let canary_config = canary
-            .take()
+        let canary_config = self
+            .canary
+            .load_full()
             .ok_or_else(|| ConfigError::Other("No canary configuration to promote".to_string()))?;
 
-        *self.stable.write().await = canary_config;
-        *self.percentage.write().await = 0;
+        #[cfg(feature = "partial-updates")]
+        if let Some(staged) = self.canary_patch.load_full()
+            && Arc::ptr_eq(&staged.canary, &canary_config)
+        {
+            return self.promote_patch(staged).await;
+        }
+
+        // Let an attached handle swap it in first, so if it refuses the
+        // canary stays staged
+        if let Some(hook) = &self.on_promote {
+            hook(Arc::clone(&canary_config)).await?;
+        }
+
+        // Publish stable before clearing the canary so readers only ever see
+        // the old stable, the canary, or the new stable (== canary)
+        self.stable.store(Arc::clone(&canary_config));
+        self.percentage.store(0, Ordering::Release);
+        self.canary
+            .compare_and_swap(&Some(Arc::clone(&canary_config)), None);
+        self.clear_buckets();
 
         Ok(())
     }
@@ -132,16 +438,26 @@ impl<T: Clone> GradualRollout<T> {
     /// Rollback by discarding the canary configuration.
     ///
     /// All requests will use the stable configuration.
-    pub async fn rollback_canary(&self) {
-        *self.canary.write().await = None;
-        *self.percentage.write().await = 0;
+    pub fn rollback_canary(&self) {
+        self.percentage.store(0, Ordering::Release);
+        self.canary.store(None);
+        #[cfg(feature = "partial-updates")]
+        self.canary_patch.store(None);
+        self.clear_buckets();
     }
 
     /// Get a configuration based on optional key for consistent hashing.
     ///
     /// If no key is provided, uses random selection.
     /// If a key is provided, uses consistent hashing to ensure the same key
-    /// always gets the same configuration.
+    /// always gets the same configuration, or the key's bucket in the
+    /// [sticky bucket store](Self::with_sticky_buckets) if there is one.
+    ///
+    /// This is synchronous and lock-free, apart from any bucket store.
+    /// Every call counts towards any [`stats`](Self::with_stats) and metrics
+    /// recorder, and keyed calls are reported to any
+    /// [exposure handler](Self::with_exposure_handler). Without any of
+    /// these, nothing is recorded.
     ///
     /// # Arguments
     ///
@@ -152,68 +468,422 @@ impl<T: Clone> GradualRollout<T> {
     /// ```rust
     /// # use hotswap_config::features::GradualRollout;
     /// # use std::sync::Arc;
-    /// # async fn example() {
     /// let rollout: GradualRollout<i32> = GradualRollout::new(Arc::new(42));
-    /// rollout.set_canary(Arc::new(100), 50).await;
+    /// rollout.set_canary(Arc::new(100), 50);
     ///
     /// // Random selection
-    /// let config = rollout.get(None).await;
+    /// let config = rollout.get(None);
     ///
     /// // Consistent hashing by user ID
-    /// let config = rollout.get(Some("user123")).await;
-    /// # }
+    /// let config = rollout.get(Some("user123"));
     /// ```
-    pub async fn get(&self, key: Option<&str>) -> Arc<T> {
-        let percentage = *self.percentage.read().await;
-        let canary = self.canary.read().await;
+    pub fn get(&self, key: Option<&str>) -> Arc<T> {
+        let percentage = self.percentage.load(Ordering::Acquire);
 
-        // If no canary or 0% rollout, always return stable
-        if canary.is_none() || percentage == 0 {
-            return Arc::clone(&*self.stable.read().await);
-        }
+        // Determine if this request should get canary (never at 0%)
+        let should_use_canary = percentage > 0
+            && (percentage >= 100
+                || match key {
+                    // Consistent hashing or sticky bucketing based on key
+                    Some(key) => self.bucket(key) < percentage,
+                    // Random selection
+                    None => fastrand::u8(0..100) < percentage,
+                });
 
-        // If 100% rollout, always return canary
-        if percentage == 100 {
-            return Arc::clone(canary.as_ref().unwrap());
+        let (variant, config) = match should_use_canary.then(|| self.canary.load_full()) {
+            Some(Some(canary)) => (Variant::Canary, canary),
+            _ => (Variant::Stable, self.stable.load_full()),
+        };
+        if self.selections.is_some() || self.metrics.is_some() || self.on_exposure.is_some() {
+            self.record(key, variant);
         }
+        config
+    }
 
-        // Determine if this request should get canary
-        let should_use_canary = if let Some(key) = key {
-            // Consistent hashing based on key
-            let mut hasher = DefaultHasher::new();
-            key.hash(&mut hasher);
-            let hash = hasher.finish();
-            (hash % 100) < percentage as u64
-        } else {
-            // Random selection
-            fastrand::u8(0..100) < percentage
-        };
+    /// Get the number of times [`get`](Self::get) selected each configuration.
+    ///
+    /// Selections are only counted with [`with_stats`](Self::with_stats);
+    /// otherwise both counts stay at zero. Counts are shared between clones
+    /// of the rollout and kept across promotions and rollbacks.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use hotswap_config::features::GradualRollout;
+    /// # use std::sync::Arc;
+    /// let rollout: GradualRollout<i32> = GradualRollout::new(Arc::new(42)).with_stats();
+    /// rollout.get(None);
+    ///
+    /// assert_eq!(rollout.stats().stable, 1);
+    /// assert_eq!(rollout.stats().canary, 0);
+    /// ```
+    pub fn stats(&self) -> RolloutStats {
+        self.selections
+            .as_ref()
+            .map(|selections| RolloutStats {
+                stable: selections.stable.load(Ordering::Relaxed),
+                canary: selections.canary.load(Ordering::Relaxed),
+            })
+            .unwrap_or_default()
+    }
+
+    /// Count a selection of `variant` and report it as an exposure of `key`.
+    fn record(&self, key: Option<&str>, variant: Variant) {
+        if let Some(selections) = &self.selections {
+            let counter = match variant {
+                Variant::Stable => &selections.stable,
+                Variant::Canary => &selections.canary,
+            };
+            counter.fetch_add(1, Ordering::Relaxed);
+        }
 
-        if should_use_canary {
-            Arc::clone(canary.as_ref().unwrap())
-        } else {
-            Arc::clone(&*self.stable.read().await)
+        if let Some(metrics) = &self.metrics {
+            metrics.rollout_selected(variant.label());
         }
+        if let (Some(handler), Some(key)) = (&self.on_exposure, key)
+            && self.has_canary()
+        {
+            handler(key, variant);
+        }
+    }
+
+    /// The bucket in `[0, 100)` that `key` falls into.
+    fn bucket(&self, key: &str) -> u8 {
+        key_bucket(key, self.buckets.as_deref())
     }
 
     /// Get the current rollout percentage.
-    pub async fn get_percentage(&self) -> u8 {
-        *self.percentage.read().await
+    pub fn get_percentage(&self) -> u8 {
+        self.percentage.load(Ordering::Acquire)
     }
 
     /// Check if a canary configuration is currently set.
-    pub async fn has_canary(&self) -> bool {
-        self.canary.read().await.is_some()
+    pub fn has_canary(&self) -> bool {
+        self.canary.load().is_some()
     }
 
     /// Get the stable configuration.
-    pub async fn get_stable(&self) -> Arc<T> {
-        Arc::clone(&*self.stable.read().await)
+    pub fn get_stable(&self) -> Arc<T> {
+        self.stable.load_full()
     }
 
     /// Get the canary configuration if set.
-    pub async fn get_canary(&self) -> Option<Arc<T>> {
-        self.canary.read().await.as_ref().map(Arc::clone)
+    pub fn get_canary(&self) -> Option<Arc<T>> {
+        self.canary.load_full()
+    }
+
+    /// Apply a staged canary patch to the stable config and make the result
+    /// stable.
+    #[cfg(feature = "partial-updates")]
+    async fn promote_patch(&self, staged: Arc<CanaryPatch<T>>) -> Result<()> {
+        // Patch the stable config as it is now, not as it was when staged
+        let promoted = (staged.apply)(&self.stable.load(), &staged.patch)?;
+        // The update path publishes it itself; otherwise an attached
+        // handle may still refuse it
+        let promoted = match (&self.on_update, &self.on_promote) {
+            (Some(update), _) => update(promoted, staged.paths.clone()).await?,
+            (None, Some(hook)) => {
+                let promoted = Arc::new(promoted);
+                hook(Arc::clone(&promoted)).await?;
+                promoted
+            }
+            (None, None) => Arc::new(promoted),
+        };
+
+        self.stable.store(Arc::clone(&promoted));
+        self.percentage.store(0, Ordering::Release);
+        self.canary
+            .compare_and_swap(&Some(Arc::clone(&staged.canary)), None);
+        self.canary_patch
+            .compare_and_swap(&Some(Arc::clone(&staged)), None);
+        self.clear_buckets();
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "partial-updates")]
+impl<T> GradualRollout<T>
+where
+    T: serde::Serialize + serde::de::DeserializeOwned + Clone,
+{
+    /// Stage a canary that differs from the stable config only by a JSON Patch.
+    ///
+    /// The canary is generated by applying `patch` (an RFC 6902 document, as
+    /// for [`apply_patch`](crate::features::PartialUpdate::apply_patch)) to
+    /// the stable config, and served to `percentage` of requests like one set
+    /// with [`set_canary`](Self::set_canary). When the stable config changes,
+    /// e.g. by an update to an attached `HotswapConfig`, the patch is applied
+    /// to the new stable config so only the patched fields keep differing; if
+    /// it no longer applies, the canary is rolled back.
+    ///
+    /// The canary is only checked by deserializing it; validators and the
+    /// update policy run when it's [promoted](Self::promote), which applies
+    /// the patch permanently.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the patch is malformed, can't be applied to the
+    /// stable config, or the result can't be deserialized to `T`. The
+    /// current canary is kept in that case.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use hotswap_config::features::GradualRollout;
+    /// # use serde::{Deserialize, Serialize};
+    /// # use serde_json::json;
+    /// # use std::sync::Arc;
+    /// #[derive(Clone, Serialize, Deserialize)]
+    /// struct AppConfig {
+    ///     port: u16,
+    ///     timeout_ms: u64,
+    /// }
+    ///
+    /// # fn example() -> hotswap_config::error::Result<()> {
+    /// let rollout = GradualRollout::new(Arc::new(AppConfig { port: 8080, timeout_ms: 500 }));
+    ///
+    /// // Try a longer timeout on 10% of requests
+    /// rollout.set_canary_patch(
+    ///     json!([{ "op": "replace", "path": "/timeout_ms", "value": 1000 }]),
+    ///     10,
+    /// )?;
+    /// assert_eq!(rollout.get_canary().unwrap().timeout_ms, 1000);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_canary_patch(&self, patch: serde_json::Value, percentage: u8) -> Result<()> {
+        use crate::features::partial::{parse_patch, patch_config, patch_paths};
+
+        let patch = parse_patch(patch)?;
+        let canary = Arc::new(patch_config(&**self.stable.load(), &patch)?);
+        self.canary_patch.store(Some(Arc::new(CanaryPatch {
+            paths: patch_paths(&patch),
+            patch,
+            apply: patch_config::<T>,
+            canary: Arc::clone(&canary),
+        })));
+        self.canary.store(Some(canary));
+        self.percentage
+            .store(percentage.min(100), Ordering::Release);
+        Ok(())
+    }
+}
+
+impl<T> GradualRollout<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    /// Step the canary through a percentage schedule, promoting it if healthy.
+    ///
+    /// For each `(percentage, hold)` step, the canary is served to `percentage`%
+    /// of requests for `hold`, then `health` is checked:
+    ///
+    /// - [`HealthStatus::Healthy`]: advance to the next step, or promote after
+    ///   the last one
+    /// - [`HealthStatus::Degraded`]: pause at the current percentage and check
+    ///   again after another `hold`
+    /// - [`HealthStatus::Unhealthy`]: roll the canary back and stop
+    ///
+    /// The controller stops without promoting if the canary is replaced,
+    /// promoted, or rolled back by someone else. Progress is reported as
+    /// [`RolloutEvent`]s on the returned [`AutoPromotion`] handle. Dropping the
+    /// handle does not stop the controller; use [`AutoPromotion::abort`]. For
+    /// a rollout from a `HotswapConfig`, shutting the handle down stops it
+    /// too.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no canary configuration.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::features::{GradualRollout, HealthStatus, RolloutOutcome};
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    ///
+    /// # async fn example() -> hotswap_config::error::Result<()> {
+    /// let rollout: GradualRollout<i32> = GradualRollout::new(Arc::new(42));
+    /// rollout.set_canary(Arc::new(100), 0);
+    ///
+    /// let mut promotion = rollout.auto_promote(
+    ///     &[
+    ///         (5, Duration::from_secs(60)),
+    ///         (25, Duration::from_secs(300)),
+    ///         (50, Duration::from_secs(600)),
+    ///     ],
+    ///     || HealthStatus::Healthy, // e.g. check error rates
+    /// )?;
+    ///
+    /// while let Some(event) = promotion.next_event().await {
+    ///     println!("rollout: {:?}", event);
+    /// }
+    /// assert_eq!(promotion.finished().await?, RolloutOutcome::Promoted);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn auto_promote<H>(&self, steps: &[(u8, Duration)], health: H) -> Result<AutoPromotion>
+    where
+        H: Fn() -> HealthStatus + Send + Sync + 'static,
+    {
+        let canary = self
+            .get_canary()
+            .ok_or_else(|| ConfigError::Other("No canary configuration to promote".to_string()))?;
+
+        let rollout = self.clone();
+        let steps = steps.to_vec();
+        let (events, rx) = tokio::sync::mpsc::unbounded_channel();
+        let emit = move |event| {
+            let _ = events.send(event);
+        };
+
+        let shutdown = self.shutdown.clone().unwrap_or_default();
+        let run = async move {
+            // Stop if the canary was replaced, promoted, or rolled back elsewhere
+            let still_staged = || {
+                rollout
+                    .get_canary()
+                    .is_some_and(|current| Arc::ptr_eq(&current, &canary))
+            };
+
+            for (percentage, hold) in steps {
+                if !still_staged() {
+                    emit(RolloutEvent::Cancelled);
+                    return RolloutOutcome::Cancelled;
+                }
+                rollout.set_percentage(percentage);
+                emit(RolloutEvent::StepStarted { percentage });
+
+                let mut paused = false;
+                loop {
+                    tokio::time::sleep(hold).await;
+                    if !still_staged() {
+                        emit(RolloutEvent::Cancelled);
+                        return RolloutOutcome::Cancelled;
+                    }
+
+                    match health() {
+                        HealthStatus::Healthy => {
+                            if paused {
+                                emit(RolloutEvent::Resumed { percentage });
+                            }
+                            break;
+                        }
+                        HealthStatus::Degraded => {
+                            if !paused {
+                                paused = true;
+                                emit(RolloutEvent::Paused { percentage });
+                            }
+                        }
+                        HealthStatus::Unhealthy => {
+                            rollout.rollback_canary();
+                            emit(RolloutEvent::RolledBack { percentage });
+                            return RolloutOutcome::RolledBack;
+                        }
+                    }
+                }
+            }
+
+            if !still_staged() {
+                emit(RolloutEvent::Cancelled);
+                return RolloutOutcome::Cancelled;
+            }
+            match rollout.promote().await {
+                Ok(()) => {
+                    emit(RolloutEvent::Promoted);
+                    RolloutOutcome::Promoted
+                }
+                Err(_) => {
+                    emit(RolloutEvent::Cancelled);
+                    RolloutOutcome::Cancelled
+                }
+            }
+        };
+        let task = tokio::spawn(async move {
+            tokio::select! {
+                _ = shutdown.cancelled() => None,
+                outcome = run => Some(outcome),
+            }
+        });
+
+        Ok(AutoPromotion { events: rx, task })
+    }
+}
+
+impl<T> GradualRollout<T> {
+    /// Replace the stable configuration without touching the canary.
+    pub(crate) fn set_stable(&self, stable: Arc<T>) {
+        #[cfg(feature = "partial-updates")]
+        self.rebase_canary_patch(&stable);
+        self.stable.store(stable);
+    }
+
+    /// Regenerate a canary patch's canary from a new stable config, rolling
+    /// the canary back if the patch no longer applies.
+    #[cfg(feature = "partial-updates")]
+    fn rebase_canary_patch(&self, stable: &T) {
+        let Some(staged) = self.canary_patch.load_full() else {
+            return;
+        };
+
+        match (staged.apply)(stable, &staged.patch) {
+            Ok(canary) => {
+                let canary = Arc::new(canary);
+                self.canary_patch.store(Some(Arc::new(CanaryPatch {
+                    patch: staged.patch.clone(),
+                    paths: staged.paths.clone(),
+                    apply: staged.apply,
+                    canary: Arc::clone(&canary),
+                })));
+                self.canary.store(Some(canary));
+            }
+            Err(_) => {
+                self.percentage.store(0, Ordering::Release);
+                self.canary.store(None);
+                self.canary_patch.store(None);
+                self.clear_buckets();
+            }
+        }
+    }
+
+    /// Forget the sticky bucket assignments of a canary that has ended.
+    fn clear_buckets(&self) {
+        clear_buckets(self.buckets.as_deref());
+    }
+}
+
+/// The bucket in `[0, 100)` that `key` falls into: the one kept in `store`
+/// if there is one, otherwise a hash of the key.
+pub(super) fn key_bucket(key: &str, store: Option<&dyn BucketStore>) -> u8 {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    let hashed = (hasher.finish() % 100) as u8;
+
+    let Some(store) = store else {
+        return hashed;
+    };
+    match store.bucket(key, hashed) {
+        Ok(bucket) => bucket.min(99),
+        Err(e) => {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(error = %e, "Failed to read sticky rollout bucket");
+            #[cfg(not(feature = "tracing"))]
+            eprintln!("Failed to read sticky rollout bucket: {}", e);
+            hashed
+        }
+    }
+}
+
+/// Forget the assignments in `store`, if any.
+pub(super) fn clear_buckets(store: Option<&dyn BucketStore>) {
+    if let Some(store) = store
+        && let Err(e) = store.clear()
+    {
+        #[cfg(feature = "tracing")]
+        tracing::warn!(error = %e, "Failed to clear sticky rollout buckets");
+        #[cfg(not(feature = "tracing"))]
+        eprintln!("Failed to clear sticky rollout buckets: {}", e);
     }
 }
 
@@ -223,6 +893,177 @@ impl<T: Clone> Clone for GradualRollout<T> {
             stable: Arc::clone(&self.stable),
             canary: Arc::clone(&self.canary),
             percentage: Arc::clone(&self.percentage),
+            on_promote: self.on_promote.clone(),
+            buckets: self.buckets.clone(),
+            selections: self.selections.clone(),
+            on_exposure: self.on_exposure.clone(),
+            metrics: self.metrics.clone(),
+            shutdown: self.shutdown.clone(),
+            #[cfg(feature = "partial-updates")]
+            canary_patch: Arc::clone(&self.canary_patch),
+            #[cfg(feature = "partial-updates")]
+            on_update: self.on_update.clone(),
+        }
+    }
+}
+
+/// Health of the service while a canary is being rolled out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthStatus {
+    /// Continue the rollout.
+    Healthy,
+    /// Hold at the current percentage until healthy again.
+    Degraded,
+    /// Roll the canary back.
+    Unhealthy,
+}
+
+/// Progress of an automatic canary promotion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RolloutEvent {
+    /// The canary is now served to `percentage`% of requests.
+    StepStarted {
+        /// The new rollout percentage
+        percentage: u8,
+    },
+    /// A degraded health check paused the rollout.
+    Paused {
+        /// The percentage the rollout is held at
+        percentage: u8,
+    },
+    /// The rollout resumed after health recovered.
+    Resumed {
+        /// The percentage the rollout resumed from
+        percentage: u8,
+    },
+    /// The canary was promoted to stable.
+    Promoted,
+    /// An unhealthy check rolled the canary back.
+    RolledBack {
+        /// The percentage at which the rollout failed
+        percentage: u8,
+    },
+    /// The canary was changed outside the controller, which stopped.
+    Cancelled,
+}
+
+/// How an automatic canary promotion ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RolloutOutcome {
+    /// The canary was promoted to stable.
+    Promoted,
+    /// The canary was rolled back after an unhealthy check.
+    RolledBack,
+    /// The canary was changed outside the controller.
+    Cancelled,
+}
+
+/// Handle to a running automatic promotion, from [`GradualRollout::auto_promote`].
+pub struct AutoPromotion {
+    events: tokio::sync::mpsc::UnboundedReceiver<RolloutEvent>,
+    /// `None` if stopped by shutdown
+    task: tokio::task::JoinHandle<Option<RolloutOutcome>>,
+}
+
+impl AutoPromotion {
+    /// Receive the next rollout event.
+    ///
+    /// Events are buffered from the start, so none are missed. Returns `None`
+    /// once the controller has finished and all events were received.
+    pub async fn next_event(&mut self) -> Option<RolloutEvent> {
+        self.events.recv().await
+    }
+
+    /// Stop the controller, leaving the canary at its current percentage.
+    pub fn abort(&self) {
+        self.task.abort();
+    }
+
+    /// Check whether the controller has finished.
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+
+    /// Wait for the controller to finish and return the outcome.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the controller was aborted, stopped by shutdown,
+    /// or panicked.
+    pub async fn finished(self) -> Result<RolloutOutcome> {
+        self.task
+            .await
+            .map_err(|e| ConfigError::Other(format!("Auto-promotion did not complete: {}", e)))?
+            .ok_or_else(|| {
+                ConfigError::Other("Auto-promotion did not complete: shut down".to_string())
+            })
+    }
+}
+
+/// Scheduled promotion of a staged canary.
+type SchedulePromotion<T> = Arc<dyn Fn(Arc<T>) + Send + Sync>;
+
+/// Routes reloaded configs into the canary slot of an attached rollout.
+pub(crate) struct CanaryReloads<T> {
+    rollout: GradualRollout<T>,
+    percentage: u8,
+    schedule_promotion: Option<SchedulePromotion<T>>,
+}
+
+impl<T> CanaryReloads<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    /// Stage reloads at `percentage`, promoting each after `promote_after` if
+    /// set, in `background`.
+    pub(crate) fn new(
+        rollout: GradualRollout<T>,
+        percentage: u8,
+        promote_after: Option<Duration>,
+        background: Background,
+    ) -> Self {
+        let schedule_promotion = promote_after.map(|delay| {
+            let rollout = rollout.clone();
+            Arc::new(move |canary: Arc<T>| {
+                let rollout = rollout.clone();
+                background(Box::pin(async move {
+                    tokio::time::sleep(delay).await;
+
+                    // Only promote if this canary hasn't been replaced or rolled back
+                    let still_staged = rollout
+                        .get_canary()
+                        .is_some_and(|current| Arc::ptr_eq(&current, &canary));
+                    if still_staged {
+                        rollout.promote().await?;
+                    }
+                    Ok(())
+                }));
+            }) as SchedulePromotion<T>
+        });
+
+        Self {
+            rollout,
+            percentage: percentage.min(100),
+            schedule_promotion,
+        }
+    }
+}
+
+impl<T> CanaryReloads<T> {
+    /// The attached rollout.
+    pub(crate) fn rollout(&self) -> &GradualRollout<T> {
+        &self.rollout
+    }
+}
+
+impl<T: Clone> CanaryReloads<T> {
+    /// Stage a reloaded config as the canary.
+    pub(crate) fn stage(&self, canary: Arc<T>) {
+        self.rollout
+            .set_canary(Arc::clone(&canary), self.percentage);
+
+        if let Some(schedule) = &self.schedule_promotion {
+            schedule(canary);
         }
     }
 }
@@ -248,10 +1089,10 @@ pub trait GradualRolloutExt<T> {
     ///
     /// // Set a canary config with 10% rollout
     /// let canary = AppConfig { port: 9090 };
-    /// rollout.set_canary(std::sync::Arc::new(canary), 10).await;
+    /// rollout.set_canary(std::sync::Arc::new(canary), 10);
     ///
     /// // Increase rollout
-    /// rollout.increase_percentage(10).await;
+    /// rollout.increase_percentage(10);
     ///
     /// // Promote to stable
     /// rollout.promote().await?;
@@ -267,7 +1108,7 @@ where
 {
     fn enable_gradual_rollout(&self) -> GradualRollout<T> {
         let current = self.get();
-        GradualRollout::new(current)
+        GradualRollout::new(current).with_shutdown(self.cancellation_token())
     }
 }
 
@@ -275,54 +1116,54 @@ where
 mod tests {
     use super::*;
 
-    #[tokio::test]
-    async fn test_gradual_rollout_creation() {
+    #[test]
+    fn test_gradual_rollout_creation() {
         let rollout = GradualRollout::new(Arc::new(42));
-        assert_eq!(*rollout.get_stable().await, 42);
-        assert!(!rollout.has_canary().await);
-        assert_eq!(rollout.get_percentage().await, 0);
+        assert_eq!(*rollout.get_stable(), 42);
+        assert!(!rollout.has_canary());
+        assert_eq!(rollout.get_percentage(), 0);
     }
 
-    #[tokio::test]
-    async fn test_set_canary() {
+    #[test]
+    fn test_set_canary() {
         let rollout = GradualRollout::new(Arc::new(42));
-        rollout.set_canary(Arc::new(100), 50).await;
+        rollout.set_canary(Arc::new(100), 50);
 
-        assert!(rollout.has_canary().await);
-        assert_eq!(rollout.get_percentage().await, 50);
-        assert_eq!(*rollout.get_canary().await.unwrap(), 100);
+        assert!(rollout.has_canary());
+        assert_eq!(rollout.get_percentage(), 50);
+        assert_eq!(*rollout.get_canary().unwrap(), 100);
     }
 
-    #[tokio::test]
-    async fn test_percentage_clamping() {
+    #[test]
+    fn test_percentage_clamping() {
         let rollout = GradualRollout::new(Arc::new(42));
-        rollout.set_canary(Arc::new(100), 150).await;
+        rollout.set_canary(Arc::new(100), 150);
 
-        assert_eq!(rollout.get_percentage().await, 100);
+        assert_eq!(rollout.get_percentage(), 100);
     }
 
-    #[tokio::test]
-    async fn test_increase_percentage() {
+    #[test]
+    fn test_increase_percentage() {
         let rollout = GradualRollout::new(Arc::new(42));
-        rollout.set_canary(Arc::new(100), 10).await;
+        rollout.set_canary(Arc::new(100), 10);
 
-        rollout.increase_percentage(20).await;
-        assert_eq!(rollout.get_percentage().await, 30);
+        rollout.increase_percentage(20);
+        assert_eq!(rollout.get_percentage(), 30);
 
-        rollout.increase_percentage(80).await;
-        assert_eq!(rollout.get_percentage().await, 100);
+        rollout.increase_percentage(80);
+        assert_eq!(rollout.get_percentage(), 100);
     }
 
     #[tokio::test]
     async fn test_promote() {
         let rollout = GradualRollout::new(Arc::new(42));
-        rollout.set_canary(Arc::new(100), 50).await;
+        rollout.set_canary(Arc::new(100), 50);
 
         rollout.promote().await.unwrap();
 
-        assert_eq!(*rollout.get_stable().await, 100);
-        assert!(!rollout.has_canary().await);
-        assert_eq!(rollout.get_percentage().await, 0);
+        assert_eq!(*rollout.get_stable(), 100);
+        assert!(!rollout.has_canary());
+        assert_eq!(rollout.get_percentage(), 0);
     }
 
     #[tokio::test]
@@ -332,86 +1173,470 @@ mod tests {
         assert!(result.is_err());
     }
 
-    #[tokio::test]
-    async fn test_rollback_canary() {
+    #[test]
+    fn test_rollback_canary() {
         let rollout = GradualRollout::new(Arc::new(42));
-        rollout.set_canary(Arc::new(100), 50).await;
+        rollout.set_canary(Arc::new(100), 50);
 
-        rollout.rollback_canary().await;
+        rollout.rollback_canary();
 
-        assert!(!rollout.has_canary().await);
-        assert_eq!(rollout.get_percentage().await, 0);
-        assert_eq!(*rollout.get_stable().await, 42);
+        assert!(!rollout.has_canary());
+        assert_eq!(rollout.get_percentage(), 0);
+        assert_eq!(*rollout.get_stable(), 42);
     }
 
-    #[tokio::test]
-    async fn test_get_no_canary() {
+    #[test]
+    fn test_get_no_canary() {
         let rollout = GradualRollout::new(Arc::new(42));
 
         // Should always return stable
         for _ in 0..10 {
-            let config = rollout.get(None).await;
+            let config = rollout.get(None);
             assert_eq!(*config, 42);
         }
     }
 
-    #[tokio::test]
-    async fn test_get_zero_percent() {
+    #[test]
+    fn test_get_zero_percent() {
         let rollout = GradualRollout::new(Arc::new(42));
-        rollout.set_canary(Arc::new(100), 0).await;
+        rollout.set_canary(Arc::new(100), 0);
 
         // Should always return stable
         for _ in 0..10 {
-            let config = rollout.get(None).await;
+            let config = rollout.get(None);
             assert_eq!(*config, 42);
         }
     }
 
-    #[tokio::test]
-    async fn test_get_hundred_percent() {
+    #[test]
+    fn test_get_hundred_percent() {
         let rollout = GradualRollout::new(Arc::new(42));
-        rollout.set_canary(Arc::new(100), 100).await;
+        rollout.set_canary(Arc::new(100), 100);
 
         // Should always return canary
         for _ in 0..10 {
-            let config = rollout.get(None).await;
+            let config = rollout.get(None);
             assert_eq!(*config, 100);
         }
     }
 
-    #[tokio::test]
-    async fn test_get_with_consistent_hashing() {
+    #[test]
+    fn test_get_with_consistent_hashing() {
         let rollout = GradualRollout::new(Arc::new(42));
-        rollout.set_canary(Arc::new(100), 50).await;
+        rollout.set_canary(Arc::new(100), 50);
 
         // Same key should always return same config
         let key = "user123";
-        let first = rollout.get(Some(key)).await;
+        let first = rollout.get(Some(key));
         for _ in 0..10 {
-            let config = rollout.get(Some(key)).await;
+            let config = rollout.get(Some(key));
             assert_eq!(*config, *first);
         }
     }
 
-    #[tokio::test]
-    async fn test_hotswap_config_integration() {
+    #[test]
+    fn test_hotswap_config_integration() {
         let config = HotswapConfig::new(42);
         let rollout = config.enable_gradual_rollout();
 
-        assert_eq!(*rollout.get_stable().await, 42);
+        assert_eq!(*rollout.get_stable(), 42);
+    }
+
+    #[tokio::test]
+    async fn test_promote_hook() {
+        let promoted = Arc::new(tokio::sync::RwLock::new(None));
+        let sink = Arc::clone(&promoted);
+        let rollout = GradualRollout::new(Arc::new(42)).with_promote_hook(Arc::new(
+            move |config: Arc<i32>| {
+                let sink = Arc::clone(&sink);
+                Box::pin(async move {
+                    *sink.write().await = Some(*config);
+                    Ok(())
+                })
+            },
+        ));
+
+        rollout.set_canary(Arc::new(100), 50);
+        rollout.promote().await.unwrap();
+
+        assert_eq!(*promoted.read().await, Some(100));
+
+        // A refused promotion leaves the canary staged
+        let rollout = GradualRollout::new(Arc::new(42)).with_promote_hook(Arc::new(|_| {
+            Box::pin(async { Err(ConfigError::Frozen { reason: None }) })
+        }));
+        rollout.set_canary(Arc::new(100), 50);
+        assert!(matches!(
+            rollout.promote().await,
+            Err(ConfigError::Frozen { .. })
+        ));
+        assert_eq!(*rollout.get_stable(), 42);
+        assert_eq!(rollout.get_percentage(), 50);
+        assert!(rollout.has_canary());
     }
 
+    #[tokio::test(start_paused = true)]
+    async fn test_canary_reloads_auto_promote() {
+        let reloads = CanaryReloads::new(
+            GradualRollout::new(Arc::new(42)),
+            25,
+            Some(Duration::from_secs(60)),
+            Arc::new(|task| {
+                tokio::spawn(async move { task.await.unwrap() });
+            }),
+        );
+
+        reloads.stage(Arc::new(100));
+        assert_eq!(reloads.rollout().get_percentage(), 25);
+        assert_eq!(*reloads.rollout().get_stable(), 42);
+
+        // A newer canary replaces the first; only it gets promoted
+        tokio::time::sleep(Duration::from_secs(30)).await;
+        reloads.stage(Arc::new(200));
+
+        tokio::time::sleep(Duration::from_secs(31)).await;
+        assert_eq!(*reloads.rollout().get_stable(), 42);
+        assert!(reloads.rollout().has_canary());
+
+        tokio::time::sleep(Duration::from_secs(30)).await;
+        assert_eq!(*reloads.rollout().get_stable(), 200);
+        assert!(!reloads.rollout().has_canary());
+    }
+
+    #[cfg(feature = "partial-updates")]
     #[tokio::test]
-    async fn test_gradual_rollout_distribution() {
+    async fn test_canary_patch() {
+        #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+        struct Limits {
+            rate: u32,
+            burst: u32,
+        }
+
+        let rollout = GradualRollout::new(Arc::new(Limits {
+            rate: 10,
+            burst: 20,
+        }));
+        let patch = serde_json::json!([{ "op": "replace", "path": "/burst", "value": 50 }]);
+        rollout.set_canary_patch(patch, 25).unwrap();
+
+        assert_eq!(rollout.get_percentage(), 25);
+        assert_eq!(
+            *rollout.get_canary().unwrap(),
+            Limits {
+                rate: 10,
+                burst: 50
+            }
+        );
+
+        // The canary follows the stable config outside the patched field
+        rollout.set_stable(Arc::new(Limits {
+            rate: 15,
+            burst: 20,
+        }));
+        assert_eq!(
+            *rollout.get_canary().unwrap(),
+            Limits {
+                rate: 15,
+                burst: 50
+            }
+        );
+
+        rollout.promote().await.unwrap();
+        assert_eq!(
+            *rollout.get_stable(),
+            Limits {
+                rate: 15,
+                burst: 50
+            }
+        );
+        assert!(!rollout.has_canary());
+
+        // A patch that doesn't apply keeps the stable config alone
+        let missing = serde_json::json!([{ "op": "replace", "path": "/missing", "value": 1 }]);
+        assert!(rollout.set_canary_patch(missing, 25).is_err());
+        assert!(!rollout.has_canary());
+    }
+
+    #[cfg(feature = "partial-updates")]
+    #[test]
+    fn test_canary_patch_rolled_back_when_stale() {
+        #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+        struct Settings {
+            flags: std::collections::BTreeMap<String, bool>,
+        }
+
+        let stable = |flag: &str| {
+            Arc::new(Settings {
+                flags: [(flag.to_string(), false)].into(),
+            })
+        };
+        let rollout = GradualRollout::new(stable("search"));
+        let patch =
+            serde_json::json!([{ "op": "replace", "path": "/flags/search", "value": true }]);
+        rollout.set_canary_patch(patch, 50).unwrap();
+        assert!(rollout.get_canary().unwrap().flags["search"]);
+
+        // The patched field is gone from the new stable config
+        rollout.set_stable(stable("checkout"));
+        assert!(!rollout.has_canary());
+        assert_eq!(rollout.get_percentage(), 0);
+    }
+
+    #[test]
+    fn test_sticky_buckets_only_add_keys() {
+        /// Keeps the first bucket offered for each key.
+        #[derive(Default)]
+        struct Pinned(std::sync::Mutex<std::collections::HashMap<String, u8>>);
+
+        impl Pinned {
+            fn len(&self) -> usize {
+                self.0.lock().unwrap().len()
+            }
+        }
+
+        impl BucketStore for Pinned {
+            fn bucket(&self, key: &str, bucket: u8) -> Result<u8> {
+                Ok(*self
+                    .0
+                    .lock()
+                    .unwrap()
+                    .entry(key.to_string())
+                    .or_insert(bucket))
+            }
+
+            fn clear(&self) -> Result<()> {
+                self.0.lock().unwrap().clear();
+                Ok(())
+            }
+        }
+
+        let store = Arc::new(Pinned::default());
+        let rollout = GradualRollout::new(Arc::new(42))
+            .with_bucket_store(Arc::clone(&store) as Arc<dyn BucketStore>);
+        rollout.set_canary(Arc::new(100), 10);
+
+        let keys: Vec<String> = (0..200).map(|i| format!("user{}", i)).collect();
+        let on_canary = |rollout: &GradualRollout<i32>| -> Vec<bool> {
+            keys.iter()
+                .map(|key| *rollout.get(Some(key)) == 100)
+                .collect()
+        };
+
+        let mut previous = on_canary(&rollout);
+        assert_eq!(store.len(), keys.len());
+        for percentage in [25, 50, 90] {
+            rollout.set_percentage(percentage);
+            let current = on_canary(&rollout);
+            assert!(previous.iter().zip(&current).all(|(was, is)| !was || *is));
+            previous = current;
+        }
+
+        // A new canary keeps the assignments; ending it clears them
+        rollout.set_canary(Arc::new(200), 90);
+        assert_eq!(store.len(), keys.len());
+        rollout.rollback_canary();
+        assert_eq!(store.len(), 0);
+    }
+
+    #[test]
+    fn test_sticky_buckets_fall_back_to_hashing() {
+        struct Unavailable;
+
+        impl BucketStore for Unavailable {
+            fn bucket(&self, _key: &str, _bucket: u8) -> Result<u8> {
+                Err(ConfigError::Other("store unavailable".to_string()))
+            }
+
+            fn clear(&self) -> Result<()> {
+                Ok(())
+            }
+        }
+
+        let sticky = GradualRollout::new(Arc::new(42)).with_sticky_buckets(Unavailable);
+        let hashed = GradualRollout::new(Arc::new(42));
+        sticky.set_canary(Arc::new(100), 50);
+        hashed.set_canary(Arc::new(100), 50);
+
+        for i in 0..50 {
+            let key = format!("user{}", i);
+            assert_eq!(*sticky.get(Some(&key)), *hashed.get(Some(&key)));
+        }
+    }
+
+    #[test]
+    fn test_selection_stats_and_exposures() {
+        #[derive(Default)]
+        struct Selections(std::sync::Mutex<Vec<String>>);
+
+        impl MetricsRecorder for Selections {
+            fn reload_attempted(&self) {}
+            fn reload_succeeded(&self, _duration: Duration) {}
+            fn reload_failed(&self, _duration: Duration) {}
+            fn validation_failed(&self) {}
+            fn config_updated(&self) {}
+            fn subscribers_changed(&self, _count: usize) {}
+            fn rollout_selected(&self, variant: &str) {
+                self.0.lock().unwrap().push(variant.to_string());
+            }
+        }
+
+        let recorder = Arc::new(Selections::default());
+        let exposures = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let log = Arc::clone(&exposures);
+        let rollout = GradualRollout::new(Arc::new(42))
+            .with_stats()
+            .with_shared_metrics(Arc::clone(&recorder) as Arc<dyn MetricsRecorder>)
+            .with_exposure_handler(move |key, variant| {
+                log.lock().unwrap().push((key.to_string(), variant));
+            });
+
+        // Nothing to expose without a canary
+        rollout.get(Some("alice"));
+
+        rollout.set_canary(Arc::new(100), 100);
+        rollout.get(Some("bob"));
+        rollout.get(None);
+        rollout.set_percentage(0);
+        rollout.get(Some("carol"));
+
+        assert_eq!(
+            rollout.stats(),
+            RolloutStats {
+                stable: 2,
+                canary: 2
+            }
+        );
+        assert_eq!(rollout.clone().stats(), rollout.stats());
+
+        // Nothing is counted unless asked for
+        let uncounted = GradualRollout::new(Arc::new(42));
+        uncounted.get(None);
+        assert_eq!(uncounted.stats(), RolloutStats::default());
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            vec!["stable", "canary", "canary", "stable"]
+        );
+        assert_eq!(
+            *exposures.lock().unwrap(),
+            vec![
+                ("bob".to_string(), Variant::Canary),
+                ("carol".to_string(), Variant::Stable),
+            ]
+        );
+    }
+
+    async fn collect_events(promotion: &mut AutoPromotion) -> Vec<RolloutEvent> {
+        let mut events = Vec::new();
+        while let Some(event) = promotion.next_event().await {
+            events.push(event);
+        }
+        events
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_auto_promote_healthy() {
+        let rollout = GradualRollout::new(Arc::new(42));
+        rollout.set_canary(Arc::new(100), 0);
+
+        let step = Duration::from_secs(60);
+        let mut promotion = rollout
+            .auto_promote(&[(10, step), (50, step)], || HealthStatus::Healthy)
+            .unwrap();
+
+        let events = collect_events(&mut promotion).await;
+        assert_eq!(
+            events,
+            vec![
+                RolloutEvent::StepStarted { percentage: 10 },
+                RolloutEvent::StepStarted { percentage: 50 },
+                RolloutEvent::Promoted,
+            ]
+        );
+        assert_eq!(
+            promotion.finished().await.unwrap(),
+            RolloutOutcome::Promoted
+        );
+        assert_eq!(*rollout.get_stable(), 100);
+        assert!(!rollout.has_canary());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_auto_promote_pause_then_rollback() {
+        let rollout = GradualRollout::new(Arc::new(42));
+        rollout.set_canary(Arc::new(100), 0);
+
+        let checks = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = Arc::clone(&checks);
+        let health = move || match counter.fetch_add(1, Ordering::SeqCst) {
+            0 => HealthStatus::Healthy,
+            1 => HealthStatus::Degraded,
+            2 => HealthStatus::Healthy,
+            _ => HealthStatus::Unhealthy,
+        };
+
+        let step = Duration::from_secs(60);
+        let mut promotion = rollout
+            .auto_promote(&[(10, step), (25, step), (50, step)], health)
+            .unwrap();
+
+        let events = collect_events(&mut promotion).await;
+        assert_eq!(
+            events,
+            vec![
+                RolloutEvent::StepStarted { percentage: 10 },
+                RolloutEvent::StepStarted { percentage: 25 },
+                RolloutEvent::Paused { percentage: 25 },
+                RolloutEvent::Resumed { percentage: 25 },
+                RolloutEvent::StepStarted { percentage: 50 },
+                RolloutEvent::RolledBack { percentage: 50 },
+            ]
+        );
+        assert_eq!(
+            promotion.finished().await.unwrap(),
+            RolloutOutcome::RolledBack
+        );
+        assert_eq!(*rollout.get_stable(), 42);
+        assert!(!rollout.has_canary());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_auto_promote_cancelled_by_manual_rollback() {
+        let rollout = GradualRollout::new(Arc::new(42));
+        rollout.set_canary(Arc::new(100), 0);
+
+        let step = Duration::from_secs(60);
+        let promotion = rollout
+            .auto_promote(&[(10, step), (50, step)], || HealthStatus::Healthy)
+            .unwrap();
+
+        tokio::time::sleep(Duration::from_secs(30)).await;
+        rollout.rollback_canary();
+
+        assert_eq!(
+            promotion.finished().await.unwrap(),
+            RolloutOutcome::Cancelled
+        );
+        assert_eq!(*rollout.get_stable(), 42);
+    }
+
+    #[test]
+    fn test_auto_promote_without_canary() {
+        let rollout = GradualRollout::new(Arc::new(42));
+        let result = rollout.auto_promote(&[], || HealthStatus::Healthy);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_gradual_rollout_distribution() {
         let rollout = GradualRollout::new(Arc::new(42));
-        rollout.set_canary(Arc::new(100), 50).await;
+        rollout.set_canary(Arc::new(100), 50);
 
         // Test that roughly 50% get canary (with randomness)
         let mut canary_count = 0;
         let iterations = 1000;
 
         for _ in 0..iterations {
-            let config = rollout.get(None).await;
+            let config = rollout.get(None);
             if *config != 42 {
                 canary_count += 1;
             }
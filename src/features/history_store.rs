@@ -0,0 +1,212 @@
+//! Persistence backends for configuration history.
+//!
+//! A [`HistoryStore`] lets [`ConfigHistory`](super::ConfigHistory) survive
+//! restarts: versions are written as they are recorded, loaded when the
+//! history is created, and deleted when they fall out of the size limit.
+
+use super::ConfigVersion;
+use crate::error::Result;
+
+#[cfg(feature = "persistent-history")]
+use crate::error::ConfigError;
+#[cfg(feature = "persistent-history")]
+use std::path::{Path, PathBuf};
+#[cfg(feature = "persistent-history")]
+use std::sync::Arc;
+
+/// Storage backend for configuration history.
+///
+/// Implement this trait to persist history somewhere other than the local
+/// filesystem (e.g., SQLite or a shared database).
+pub trait HistoryStore<T>: Send + Sync {
+    /// Persist a newly recorded version.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the version cannot be written.
+    fn save(&self, version: &ConfigVersion<T>) -> Result<()>;
+
+    /// Delete a version that has been dropped from the history.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the version exists but cannot be deleted.
+    fn remove(&self, version: u64) -> Result<()>;
+
+    /// Load all persisted versions, in any order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store cannot be read.
+    fn load_all(&self) -> Result<Vec<ConfigVersion<T>>>;
+}
+
+/// Stores each history version as a JSON file in a directory.
+///
+/// Files are named `version-<number>.json` and contain the version number,
+/// timestamp, source description, and the serialized configuration.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use hotswap_config::features::{ConfigHistory, JsonFileStore};
+///
+/// # fn example() -> hotswap_config::error::Result<()> {
+/// let history: ConfigHistory<serde_json::Value> =
+///     ConfigHistory::with_store(20, JsonFileStore::new("/var/lib/myapp/config-history")?)?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "persistent-history")]
+pub struct JsonFileStore {
+    dir: PathBuf,
+}
+
+#[cfg(feature = "persistent-history")]
+impl JsonFileStore {
+    /// Create a store in `dir`, creating the directory if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory cannot be created.
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    /// Get the directory versions are stored in.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path_for(&self, version: u64) -> PathBuf {
+        self.dir.join(format!("version-{:020}.json", version))
+    }
+}
+
+/// On-disk representation of a [`ConfigVersion`].
+#[cfg(feature = "persistent-history")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct StoredVersion<T> {
+    version: u64,
+    timestamp: chrono::DateTime<chrono::Utc>,
+    source: Option<String>,
+    config: T,
+}
+
+#[cfg(feature = "persistent-history")]
+impl<T> HistoryStore<T> for JsonFileStore
+where
+    T: serde::Serialize + serde::de::DeserializeOwned + Send + Sync,
+{
+    fn save(&self, version: &ConfigVersion<T>) -> Result<()> {
+        let stored = StoredVersion {
+            version: version.version,
+            timestamp: version.timestamp,
+            source: version.source.clone(),
+            config: &*version.config,
+        };
+        let json = serde_json::to_vec_pretty(&stored).map_err(|e| {
+            ConfigError::Other(format!(
+                "Failed to serialize config version {}: {}",
+                version.version, e
+            ))
+        })?;
+
+        // Write to a temporary file and rename, so a crash never leaves a
+        // truncated version behind
+        let path = self.path_for(version.version);
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+
+    fn remove(&self, version: u64) -> Result<()> {
+        match std::fs::remove_file(self.path_for(version)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    fn load_all(&self) -> Result<Vec<ConfigVersion<T>>> {
+        let mut versions = Vec::new();
+
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            let is_version_file = path.extension().is_some_and(|ext| ext == "json")
+                && path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with("version-"));
+            if !is_version_file {
+                continue;
+            }
+
+            let contents = std::fs::read(&path)?;
+            let stored: StoredVersion<T> = serde_json::from_slice(&contents).map_err(|e| {
+                ConfigError::ParseError(format!(
+                    "Failed to parse history file {}: {}",
+                    path.display(),
+                    e
+                ))
+            })?;
+
+            versions.push(ConfigVersion {
+                version: stored.version,
+                timestamp: stored.timestamp,
+                config: Arc::new(stored.config),
+                source: stored.source,
+            });
+        }
+
+        Ok(versions)
+    }
+}
+
+#[cfg(all(test, feature = "persistent-history"))]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn version(number: u64, config: &str) -> ConfigVersion<String> {
+        ConfigVersion {
+            version: number,
+            timestamp: chrono::Utc::now(),
+            config: Arc::new(config.to_string()),
+            source: Some(format!("v{}", number)),
+        }
+    }
+
+    #[test]
+    fn test_save_load_remove() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = JsonFileStore::new(temp_dir.path().join("history")).unwrap();
+
+        store.save(&version(0, "first")).unwrap();
+        store.save(&version(1, "second")).unwrap();
+        std::fs::write(store.dir().join("README"), "not a version").unwrap();
+
+        let mut loaded: Vec<ConfigVersion<String>> = store.load_all().unwrap();
+        loaded.sort_by_key(|v| v.version);
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(*loaded[1].config, "second");
+        assert_eq!(loaded[1].source.as_deref(), Some("v1"));
+
+        HistoryStore::<String>::remove(&store, 0).unwrap();
+        HistoryStore::<String>::remove(&store, 42).unwrap();
+        let loaded: Vec<ConfigVersion<String>> = store.load_all().unwrap();
+        assert_eq!(loaded.len(), 1);
+    }
+
+    #[test]
+    fn test_corrupt_file_is_an_error() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = JsonFileStore::new(temp_dir.path()).unwrap();
+        std::fs::write(temp_dir.path().join("version-1.json"), "{not json").unwrap();
+
+        let result: Result<Vec<ConfigVersion<String>>> = store.load_all();
+        assert!(matches!(result, Err(ConfigError::ParseError(_))));
+    }
+}
@@ -12,8 +12,38 @@ pub mod rollback;
 #[cfg(feature = "rollback")]
 pub use rollback::{ConfigHistory, ConfigVersion, Rollback};
 
+#[cfg(feature = "rollback")]
+mod history_store;
+
+#[cfg(feature = "rollback")]
+pub use history_store::HistoryStore;
+
+#[cfg(feature = "persistent-history")]
+pub use history_store::JsonFileStore;
+
 #[cfg(feature = "gradual-rollout")]
 pub mod gradual;
 
 #[cfg(feature = "gradual-rollout")]
-pub use gradual::{GradualRollout, GradualRolloutExt};
+pub mod experiment;
+
+#[cfg(feature = "gradual-rollout")]
+pub use experiment::{Experiment, ExperimentExt};
+
+#[cfg(feature = "gradual-rollout")]
+mod bucket_store;
+
+#[cfg(feature = "gradual-rollout")]
+pub use bucket_store::{BucketStore, HashBucketStore};
+
+#[cfg(feature = "gradual-rollout")]
+pub use gradual::{
+    AutoPromotion, GradualRollout, GradualRolloutExt, HealthStatus, RolloutEvent, RolloutOutcome,
+    RolloutStats, Variant,
+};
+
+#[cfg(feature = "flags")]
+pub mod flags;
+
+#[cfg(feature = "flags")]
+pub use flags::{EvalContext, Flag, FlagSet, Rule};
@@ -1,8 +1,8 @@
-//! Partial configuration updates using JSON Patch.
+//! Partial configuration updates using JSON Patch and JSON Merge Patch.
 //!
 //! Allows surgical updates to configuration without reloading from files.
 
-use crate::core::HotswapConfig;
+use crate::core::{HotswapConfig, UpdateContext, UpdateOrigin};
 use crate::error::{ConfigError, Result};
 use serde::{Serialize, de::DeserializeOwned};
 use serde_json::Value;
@@ -14,7 +14,10 @@ pub trait PartialUpdate<T> {
     /// Apply a JSON Patch to the configuration.
     ///
     /// The patch is applied to the serialized configuration, then validated
-    /// and deserialized before atomically swapping.
+    /// and deserialized before atomically swapping. Every RFC 6902 operation is
+    /// supported (`add`, `remove`, `replace`, `move`, `copy`, `test`), and the
+    /// patch applies all-or-nothing: a failed `test` guards the rest of the
+    /// patch, and its error shows the expected and actual values.
     ///
     /// # Arguments
     ///
@@ -54,6 +57,45 @@ pub trait PartialUpdate<T> {
     /// ```
     fn apply_patch(&self, patch: Value) -> impl std::future::Future<Output = Result<()>> + Send;
 
+    /// Apply a JSON Patch on behalf of the requester described by `context`.
+    ///
+    /// Like [`apply_patch`](Self::apply_patch), but the
+    /// [update policy](crate::core::HotswapConfigBuilder::with_update_policy)
+    /// sees `context` instead of a plain [`UpdateOrigin::Patch`]. The policy is
+    /// given the paths of the patch's operations, and `from` of `move`
+    /// operations; `test` operations change nothing and aren't passed.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`apply_patch`](Self::apply_patch), and
+    /// [`ConfigError::UpdateDenied`] if the policy denies the patch.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    /// use hotswap_config::features::PartialUpdate;
+    /// use serde_json::json;
+    ///
+    /// # #[derive(Debug, serde::Deserialize, Clone, serde::Serialize)]
+    /// # struct AppConfig { port: u16 }
+    /// # async fn example(config: HotswapConfig<AppConfig>) -> Result<()> {
+    /// let context = UpdateContext::new(UpdateOrigin::Patch).with_actor("alice");
+    /// config
+    ///     .apply_patch_with_context(
+    ///         json!([{ "op": "replace", "path": "/port", "value": 9090 }]),
+    ///         &context,
+    ///     )
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn apply_patch_with_context(
+        &self,
+        patch: Value,
+        context: &UpdateContext,
+    ) -> impl std::future::Future<Output = Result<()>> + Send;
+
     /// Update a single field in the configuration.
     ///
     /// This is a convenience method that creates a JSON Patch replace operation.
@@ -65,7 +107,9 @@ pub trait PartialUpdate<T> {
     ///
     /// # Errors
     ///
-    /// Returns an error if the path is invalid or validation fails.
+    /// Returns an error if the path is invalid or validation fails. The field
+    /// must already exist; use [`upsert_field`](Self::upsert_field) to set
+    /// one that may not, such as a new map entry.
     ///
     /// # Examples
     ///
@@ -89,6 +133,102 @@ pub trait PartialUpdate<T> {
         path: &str,
         value: V,
     ) -> impl std::future::Future<Output = Result<()>> + Send;
+
+    /// Set a single field, adding it if it doesn't exist yet.
+    ///
+    /// Like [`update_field`](Self::update_field), but uses an `add` operation
+    /// when nothing is at `path`, e.g. to insert a new entry into a map.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the parent of `path` doesn't exist or validation
+    /// fails.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    /// use hotswap_config::features::PartialUpdate;
+    /// use serde::Deserialize;
+    /// use std::collections::HashMap;
+    ///
+    /// #[derive(Debug, Deserialize, Clone, serde::Serialize)]
+    /// struct AppConfig {
+    ///     upstreams: HashMap<String, String>,
+    /// }
+    ///
+    /// # async fn example(config: HotswapConfig<AppConfig>) -> Result<()> {
+    /// config.upsert_field("/upstreams/billing", "http://billing:8080").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn upsert_field<V: Serialize + Send>(
+        &self,
+        path: &str,
+        value: V,
+    ) -> impl std::future::Future<Output = Result<()>> + Send;
+
+    /// Apply a JSON Merge Patch (RFC 7386) to the configuration.
+    ///
+    /// The patch mirrors the shape of the configuration: objects are merged
+    /// recursively, other values replace what is there, and `null` removes a
+    /// key. The result is validated and swapped in like any update.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the result cannot be deserialized to T or
+    /// validation fails.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    /// use hotswap_config::features::PartialUpdate;
+    /// use serde::Deserialize;
+    /// use serde_json::json;
+    ///
+    /// #[derive(Debug, Deserialize, Clone, serde::Serialize)]
+    /// struct AppConfig {
+    ///     port: u16,
+    ///     database: DatabaseConfig,
+    /// }
+    ///
+    /// #[derive(Debug, Deserialize, Clone, serde::Serialize)]
+    /// struct DatabaseConfig {
+    ///     pool_size: u32,
+    ///     replica: Option<String>,
+    /// }
+    ///
+    /// # async fn example(config: HotswapConfig<AppConfig>) -> Result<()> {
+    /// // Grow the pool and drop the replica, leaving everything else as is
+    /// config
+    ///     .apply_merge_patch(json!({ "database": { "pool_size": 50, "replica": null } }))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn apply_merge_patch(
+        &self,
+        patch: Value,
+    ) -> impl std::future::Future<Output = Result<()>> + Send;
+
+    /// Apply a JSON Merge Patch on behalf of the requester described by
+    /// `context`.
+    ///
+    /// Like [`apply_merge_patch`](Self::apply_merge_patch), but the
+    /// [update policy](crate::core::HotswapConfigBuilder::with_update_policy)
+    /// sees `context`. The policy is given the path of every value the patch
+    /// sets or removes.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`apply_merge_patch`](Self::apply_merge_patch), and
+    /// [`ConfigError::UpdateDenied`] if the policy denies the patch.
+    fn apply_merge_patch_with_context(
+        &self,
+        patch: Value,
+        context: &UpdateContext,
+    ) -> impl std::future::Future<Output = Result<()>> + Send;
 }
 
 impl<T> PartialUpdate<T> for HotswapConfig<T>
@@ -96,31 +236,18 @@ where
     T: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
 {
     async fn apply_patch(&self, patch: Value) -> Result<()> {
-        // Get current config
-        let current = self.get();
-
-        // Serialize to JSON
-        let mut current_json = serde_json::to_value(&*current)
-            .map_err(|e| ConfigError::Other(format!("Failed to serialize config: {}", e)))?;
-
-        // Parse patch - json_patch expects an array, deserialize it
-        let patch: json_patch::Patch = serde_json::from_value(patch)
-            .map_err(|e| ConfigError::Other(format!("Invalid JSON Patch: {}", e)))?;
+        self.apply_patch_with_context(patch, &UpdateContext::new(UpdateOrigin::Patch))
+            .await
+    }
 
-        // Apply patch
-        json_patch::patch(&mut current_json, &patch)
-            .map_err(|e| ConfigError::Other(format!("Failed to apply patch: {}", e)))?;
+    async fn apply_patch_with_context(&self, patch: Value, context: &UpdateContext) -> Result<()> {
+        let patch = parse_patch(patch)?;
+        self.authorize(&patch_paths(&patch), context)?;
 
-        // Deserialize back to T
-        let new_config: T = serde_json::from_value(current_json).map_err(|e| {
-            ConfigError::DeserializationError(format!(
-                "Failed to deserialize patched config: {}",
-                e
-            ))
-        })?;
+        let new_config = patch_config(&*self.get(), &patch)?;
 
         // Use the normal update path (which handles validation and notifications)
-        self.update(new_config).await
+        self.apply_update(new_config).await
     }
 
     async fn update_field<V: Serialize + Send>(&self, path: &str, value: V) -> Result<()> {
@@ -133,6 +260,158 @@ where
 
         self.apply_patch(patch).await
     }
+
+    async fn upsert_field<V: Serialize + Send>(&self, path: &str, value: V) -> Result<()> {
+        let value_json = serde_json::to_value(value)
+            .map_err(|e| ConfigError::Other(format!("Failed to serialize value: {}", e)))?;
+        let current = serde_json::to_value(&*self.get())
+            .map_err(|e| ConfigError::Other(format!("Failed to serialize config: {}", e)))?;
+
+        let op = if current.pointer(path).is_some() {
+            "replace"
+        } else {
+            "add"
+        };
+        let patch = serde_json::json!([
+            { "op": op, "path": path, "value": value_json }
+        ]);
+
+        self.apply_patch(patch).await
+    }
+
+    async fn apply_merge_patch(&self, patch: Value) -> Result<()> {
+        self.apply_merge_patch_with_context(patch, &UpdateContext::new(UpdateOrigin::Patch))
+            .await
+    }
+
+    async fn apply_merge_patch_with_context(
+        &self,
+        patch: Value,
+        context: &UpdateContext,
+    ) -> Result<()> {
+        let mut paths = Vec::new();
+        merge_patch_paths(&patch, String::new(), &mut paths);
+        self.authorize(&paths, context)?;
+
+        let new_config = patched(&*self.get(), |json| {
+            json_patch::merge(json, &patch);
+            Ok(())
+        })?;
+
+        self.apply_update(new_config).await
+    }
+}
+
+/// Parse a JSON Patch document (an array of operations).
+pub(crate) fn parse_patch(patch: Value) -> Result<json_patch::Patch> {
+    serde_json::from_value(patch)
+        .map_err(|e| ConfigError::Other(format!("Invalid JSON Patch: {}", e)))
+}
+
+/// Apply `patch` to a copy of `config`.
+pub(crate) fn patch_config<T>(config: &T, patch: &json_patch::Patch) -> Result<T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    patched(config, |json| {
+        json_patch::patch(json, patch).map_err(|e| describe_patch_error(json, patch, e))
+    })
+}
+
+/// JSON Pointer paths changed by the operations of `patch`, in order.
+pub(crate) fn patch_paths(patch: &json_patch::Patch) -> Vec<String> {
+    use json_patch::PatchOperation;
+
+    let mut paths: Vec<String> = Vec::new();
+    for operation in &patch.0 {
+        let changed = match operation {
+            PatchOperation::Add(op) => vec![op.path.to_string()],
+            PatchOperation::Remove(op) => vec![op.path.to_string()],
+            PatchOperation::Replace(op) => vec![op.path.to_string()],
+            PatchOperation::Move(op) => vec![op.from.to_string(), op.path.to_string()],
+            PatchOperation::Copy(op) => vec![op.path.to_string()],
+            PatchOperation::Test(_) => Vec::new(),
+        };
+        for path in changed {
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+    }
+    paths
+}
+
+/// Collect the JSON Pointer paths of the values a merge `patch` at `pointer`
+/// sets or removes.
+fn merge_patch_paths(patch: &Value, pointer: String, paths: &mut Vec<String>) {
+    match patch {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, value) in map {
+                let key = key.replace('~', "~0").replace('/', "~1");
+                merge_patch_paths(value, format!("{}/{}", pointer, key), paths);
+            }
+        }
+        _ => paths.push(pointer),
+    }
+}
+
+/// Serialize `config` to JSON, modify it with `f`, and deserialize the result.
+fn patched<T>(config: &T, f: impl FnOnce(&mut Value) -> Result<()>) -> Result<T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    let mut json = serde_json::to_value(config)
+        .map_err(|e| ConfigError::Other(format!("Failed to serialize config: {}", e)))?;
+
+    f(&mut json)?;
+
+    serde_json::from_value(json).map_err(|e| {
+        ConfigError::DeserializationError(format!("Failed to deserialize patched config: {}", e))
+    })
+}
+
+/// Explain why `patch` failed on `doc`, which it left unchanged.
+///
+/// Failed tests show the expected value and the one found (after the
+/// operations before the test), and missing paths suggest adding them.
+fn describe_patch_error(
+    doc: &Value,
+    patch: &json_patch::Patch,
+    error: json_patch::PatchError,
+) -> ConfigError {
+    use json_patch::{PatchErrorKind, PatchOperation};
+
+    let path = error.path.to_string();
+    let message = match (&error.kind, patch.0.get(error.operation)) {
+        (PatchErrorKind::TestFailed, Some(PatchOperation::Test(test))) => {
+            let mut before = doc.clone();
+            let found = json_patch::patch(&mut before, &patch.0[..error.operation])
+                .ok()
+                .and_then(|()| before.pointer(&path).cloned());
+            match found {
+                Some(found) => format!(
+                    "test failed at '{}': expected {}, found {}",
+                    path, test.value, found
+                ),
+                None => format!(
+                    "test failed at '{}': expected {}, found nothing",
+                    path, test.value
+                ),
+            }
+        }
+        (PatchErrorKind::InvalidPointer, Some(PatchOperation::Replace(_))) => format!(
+            "nothing at '{}' to replace; use an \"add\" operation or `upsert_field` to create it",
+            path
+        ),
+        (PatchErrorKind::InvalidPointer, Some(PatchOperation::Remove(_))) => {
+            format!("nothing at '{}' to remove", path)
+        }
+        (kind, _) => format!("{} at '{}'", kind, path),
+    };
+    ConfigError::Other(format!(
+        "Failed to apply patch: operation {}: {}",
+        error.operation, message
+    ))
 }
 
 #[cfg(test)]
@@ -305,4 +584,166 @@ mod tests {
         let result = config.update_field("/nonexistent", 123).await;
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_apply_merge_patch() {
+        #[derive(Debug, Clone, Serialize, Deserialize)]
+        struct Limits {
+            default: u32,
+            overrides: std::collections::BTreeMap<String, u32>,
+        }
+
+        let config = HotswapConfig::new(Limits {
+            default: 100,
+            overrides: [("batch".to_string(), 1000), ("admin".to_string(), 10)].into(),
+        });
+
+        config
+            .apply_merge_patch(json!({ "overrides": { "admin": null, "search": 50 } }))
+            .await
+            .unwrap();
+
+        let updated = config.get();
+        assert_eq!(updated.default, 100);
+        assert_eq!(
+            updated.overrides,
+            [("batch".to_string(), 1000), ("search".to_string(), 50)].into()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_upsert_field() {
+        #[derive(Debug, Clone, Serialize, Deserialize)]
+        struct Upstreams {
+            hosts: std::collections::BTreeMap<String, String>,
+        }
+
+        let config = HotswapConfig::new(Upstreams {
+            hosts: [("api".to_string(), "api:80".to_string())].into(),
+        });
+
+        // Missing entries are added, existing ones replaced
+        assert!(
+            config
+                .update_field("/hosts/billing", "billing:80")
+                .await
+                .is_err()
+        );
+        config
+            .upsert_field("/hosts/billing", "billing:80")
+            .await
+            .unwrap();
+        config.upsert_field("/hosts/api", "api:8080").await.unwrap();
+
+        let updated = config.get();
+        assert_eq!(updated.hosts["billing"], "billing:80");
+        assert_eq!(updated.hosts["api"], "api:8080");
+    }
+
+    #[tokio::test]
+    async fn test_failed_test_op_explains_mismatch() {
+        let initial = TestConfig {
+            port: 8080,
+            host: "localhost".to_string(),
+            database: DatabaseConfig {
+                url: "postgres://localhost/db".to_string(),
+                pool_size: 10,
+            },
+        };
+
+        let config = HotswapConfig::new(initial.clone());
+
+        let patch = json!([
+            { "op": "replace", "path": "/port", "value": 9090 },
+            { "op": "test", "path": "/database/pool_size", "value": 20 },
+            { "op": "replace", "path": "/database/pool_size", "value": 40 }
+        ]);
+        let error = config.apply_patch(patch).await.unwrap_err().to_string();
+        assert!(
+            error.contains("operation 1")
+                && error.contains("/database/pool_size")
+                && error.contains("expected 20, found 10"),
+            "{}",
+            error
+        );
+        // Nothing is applied
+        assert_eq!(*config.get(), initial);
+
+        let error = config.update_field("/nonexistent", 1).await.unwrap_err();
+        assert!(error.to_string().contains("upsert_field"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn test_update_policy_sees_changed_paths() {
+        use crate::core::PolicyDecision;
+        use std::sync::{Arc, Mutex};
+
+        let initial = TestConfig {
+            port: 8080,
+            host: "localhost".to_string(),
+            database: DatabaseConfig {
+                url: "postgres://localhost/db".to_string(),
+                pool_size: 10,
+            },
+        };
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&seen);
+        let config = HotswapConfig::new(initial.clone()).with_update_policy(Some(Arc::new(
+            move |paths: &[String], context: &UpdateContext| {
+                recorded
+                    .lock()
+                    .unwrap()
+                    .push((paths.to_vec(), context.origin));
+                // The root path "" replaces the whole config, database included
+                if paths
+                    .iter()
+                    .any(|p| p.is_empty() || p.starts_with("/database"))
+                {
+                    PolicyDecision::deny("database is managed by ops")
+                } else {
+                    PolicyDecision::Allow
+                }
+            },
+        )));
+
+        let patch = json!([
+            { "op": "test", "path": "/port", "value": 8080 },
+            { "op": "move", "from": "/host", "path": "/database/url" }
+        ]);
+        let error = config.apply_patch(patch).await.unwrap_err();
+        assert!(
+            matches!(error, ConfigError::UpdateDenied { ref reason } if reason == "database is managed by ops")
+        );
+        assert_eq!(*config.get(), initial);
+
+        config
+            .apply_merge_patch(json!({ "database": null, "host": "example.com" }))
+            .await
+            .unwrap_err();
+        config
+            .apply_merge_patch(json!({ "host": "example.com" }))
+            .await
+            .unwrap();
+        assert_eq!(config.get().host, "example.com");
+
+        let mut update = initial.clone();
+        update.database.pool_size = 20;
+        assert!(config.update(update).await.is_err());
+
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![
+                (
+                    vec!["/host".to_string(), "/database/url".to_string()],
+                    UpdateOrigin::Patch
+                ),
+                (
+                    vec!["/database".to_string(), "/host".to_string()],
+                    UpdateOrigin::Patch
+                ),
+                (vec!["/host".to_string()], UpdateOrigin::Patch),
+                (vec![String::new()], UpdateOrigin::Update),
+            ]
+        );
+    }
 }
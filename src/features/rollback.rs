@@ -2,7 +2,8 @@
 //!
 //! Tracks previous configuration versions and allows rolling back to earlier states.
 
-use crate::core::HotswapConfig;
+use super::HistoryStore;
+use crate::core::{HotswapConfig, UpdateContext, UpdateOrigin};
 use crate::error::{ConfigError, Result};
 use chrono::{DateTime, Utc};
 use std::collections::VecDeque;
@@ -25,11 +26,13 @@ pub struct ConfigVersion<T> {
 /// Configuration history tracker.
 ///
 /// Maintains a bounded history of configuration versions that can be
-/// rolled back to.
+/// rolled back to. History is in-memory unless created with
+/// [`with_store`](Self::with_store).
 pub struct ConfigHistory<T> {
     versions: Arc<RwLock<VecDeque<ConfigVersion<T>>>>,
     max_size: usize,
     next_version: Arc<RwLock<u64>>,
+    store: Option<Arc<dyn HistoryStore<T>>>,
 }
 
 impl<T: Clone> ConfigHistory<T> {
@@ -51,16 +54,107 @@ impl<T: Clone> ConfigHistory<T> {
             versions: Arc::new(RwLock::new(VecDeque::with_capacity(max_size))),
             max_size,
             next_version: Arc::new(RwLock::new(0)),
+            store: None,
         }
     }
 
+    /// Create a history holding `config` as version 0.
+    fn with_initial(max_size: usize, config: Arc<T>, source: Option<String>) -> Self {
+        let mut versions = VecDeque::with_capacity(max_size);
+        if max_size > 0 {
+            versions.push_back(ConfigVersion {
+                version: 0,
+                timestamp: Utc::now(),
+                config,
+                source,
+            });
+        }
+        Self {
+            versions: Arc::new(RwLock::new(versions)),
+            max_size,
+            next_version: Arc::new(RwLock::new(1)),
+            store: None,
+        }
+    }
+
+    /// Create a configuration history backed by a persistent store.
+    ///
+    /// Existing versions are loaded from the store, so history (and rollback)
+    /// survives restarts. Versions beyond `max_size` are deleted from the
+    /// store, and version numbering continues after the newest stored version.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store cannot be read or trimmed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # #[cfg(feature = "persistent-history")]
+    /// # async fn example() -> hotswap_config::error::Result<()> {
+    /// use hotswap_config::features::{ConfigHistory, JsonFileStore};
+    /// use std::sync::Arc;
+    ///
+    /// let history: ConfigHistory<String> =
+    ///     ConfigHistory::with_store(10, JsonFileStore::new("config-history")?)?;
+    ///
+    /// history
+    ///     .try_record(Arc::new("v2".to_string()), Some("deploy".to_string()))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_store<S>(max_size: usize, store: S) -> Result<Self>
+    where
+        S: HistoryStore<T> + 'static,
+    {
+        let mut loaded = store.load_all()?;
+        loaded.sort_by_key(|v| v.version);
+
+        // Garbage-collect versions beyond the size limit
+        let excess = loaded.len().saturating_sub(max_size);
+        for old in loaded.drain(..excess) {
+            store.remove(old.version)?;
+        }
+
+        let next_version = loaded.last().map_or(0, |v| v.version + 1);
+
+        Ok(Self {
+            versions: Arc::new(RwLock::new(loaded.into())),
+            max_size,
+            next_version: Arc::new(RwLock::new(next_version)),
+            store: Some(Arc::new(store)),
+        })
+    }
+
     /// Record a new configuration version.
     ///
+    /// With a persistent store, a failure to persist is logged (or printed to
+    /// stderr without the `tracing` feature);
+    /// use [`try_record`](Self::try_record) to handle it instead.
+    ///
     /// # Arguments
     ///
     /// * `config` - The configuration to record
     /// * `source` - Optional description of the change source
     pub async fn record(&self, config: Arc<T>, source: Option<String>) {
+        if let Err(e) = self.try_record(config, source).await {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(error = %e, "Failed to persist config history");
+            #[cfg(not(feature = "tracing"))]
+            eprintln!("Failed to persist config history: {}", e);
+        }
+    }
+
+    /// Record a new configuration version, returning persistence errors.
+    ///
+    /// The version is always kept in memory, even if persisting it fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store fails to save the new version or delete
+    /// versions trimmed from the history.
+    pub async fn try_record(&self, config: Arc<T>, source: Option<String>) -> Result<()> {
         let mut versions = self.versions.write().await;
         let mut next_version = self.next_version.write().await;
 
@@ -71,13 +165,23 @@ impl<T: Clone> ConfigHistory<T> {
             source,
         };
 
+        let saved = match &self.store {
+            Some(store) => store.save(&version),
+            None => Ok(()),
+        };
+
         versions.push_back(version);
         *next_version += 1;
 
         // Trim to max size
+        let mut removed = Ok(());
         while versions.len() > self.max_size {
-            versions.pop_front();
+            if let (Some(old), Some(store)) = (versions.pop_front(), &self.store) {
+                removed = removed.and(store.remove(old.version));
+            }
         }
+
+        saved.and(removed)
     }
 
     /// Get the current version number.
@@ -148,6 +252,7 @@ impl<T: Clone> Clone for ConfigHistory<T> {
             versions: Arc::clone(&self.versions),
             max_size: self.max_size,
             next_version: Arc::clone(&self.next_version),
+            store: self.store.clone(),
         }
     }
 }
@@ -212,18 +317,8 @@ where
     T: Clone + Send + Sync + 'static,
 {
     fn enable_history(&self, max_size: usize) -> ConfigHistory<T> {
-        let history = ConfigHistory::new(max_size);
-
         // Record the current configuration as version 0
-        let current = self.get();
-        let history_clone = history.clone();
-        tokio::spawn(async move {
-            history_clone
-                .record(current, Some("Initial version".to_string()))
-                .await;
-        });
-
-        history
+        ConfigHistory::with_initial(max_size, self.get(), Some("Initial version".to_string()))
     }
 
     async fn rollback(&self, history: &ConfigHistory<T>, steps: usize) -> Result<()> {
@@ -234,7 +329,11 @@ where
             ))
         })?;
 
-        self.update((*config).clone()).await?;
+        self.update_with_context(
+            (*config).clone(),
+            &UpdateContext::new(UpdateOrigin::Rollback),
+        )
+        .await?;
 
         // Record this rollback in history
         history
@@ -249,7 +348,11 @@ where
             ConfigError::Other(format!("Version {} not found in history", version))
         })?;
 
-        self.update((*config).clone()).await?;
+        self.update_with_context(
+            (*config).clone(),
+            &UpdateContext::new(UpdateOrigin::Rollback),
+        )
+        .await?;
 
         // Record this rollback in history
         history
@@ -357,13 +460,55 @@ mod tests {
         assert_eq!(history.current_version().await, 1);
     }
 
+    #[cfg(feature = "persistent-history")]
+    #[tokio::test]
+    async fn test_persistent_history_survives_restart() {
+        use crate::features::JsonFileStore;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let open = || ConfigHistory::with_store(3, JsonFileStore::new(temp_dir.path()).unwrap());
+
+        let history = open().unwrap();
+        for i in 0..5 {
+            history.try_record(Arc::new(i), None).await.unwrap();
+        }
+        assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 3);
+
+        // A new process sees the same trimmed history and keeps numbering
+        let restored: ConfigHistory<i32> = open().unwrap();
+        assert_eq!(restored.len().await, 3);
+        assert_eq!(restored.current_version().await, 4);
+        assert_eq!(*restored.rollback_steps(1).await.unwrap(), 3);
+
+        restored.try_record(Arc::new(5), None).await.unwrap();
+        assert_eq!(restored.current_version().await, 5);
+        assert!(restored.get_version(2).await.is_none());
+    }
+
+    #[cfg(feature = "persistent-history")]
+    #[tokio::test]
+    async fn test_with_store_trims_to_max_size() {
+        use crate::features::JsonFileStore;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let history =
+            ConfigHistory::with_store(5, JsonFileStore::new(temp_dir.path()).unwrap()).unwrap();
+        for i in 0..5 {
+            history.try_record(Arc::new(i), None).await.unwrap();
+        }
+
+        // Reopening with a smaller limit garbage-collects the oldest versions
+        let smaller: ConfigHistory<i32> =
+            ConfigHistory::with_store(2, JsonFileStore::new(temp_dir.path()).unwrap()).unwrap();
+        assert_eq!(smaller.len().await, 2);
+        assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 2);
+    }
+
     #[tokio::test]
     async fn test_hotswap_config_rollback() {
         let config = HotswapConfig::new(10);
         let history = config.enable_history(5);
-
-        // Wait for initial record
-        tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+        assert_eq!(history.len().await, 1);
 
         // Make some updates
         config.update(20).await.unwrap();
@@ -0,0 +1,158 @@
+//! Process-wide configuration handles, looked up by type.
+//!
+//! For code that can't have a [`HotswapConfig`] threaded through every call
+//! site, [`init`] registers a handle once at startup and [`get`] reads the
+//! current configuration from anywhere. Each config type has its own slot,
+//! so a binary can register several (e.g. `AppConfig` and `FeatureFlags`).
+//!
+//! Reads take a shared lock on the registry before the lock-free load; hot
+//! paths should keep the handle from [`handle`] instead.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use hotswap_config::global;
+//! use hotswap_config::prelude::*;
+//! use serde::Deserialize;
+//!
+//! #[derive(Debug, Deserialize, Clone)]
+//! struct AppConfig {
+//!     port: u16,
+//! }
+//!
+//! # async fn example() -> Result<()> {
+//! let config = HotswapConfig::<AppConfig>::builder()
+//!     .with_file("config.yaml")
+//!     .build()
+//!     .await?;
+//! global::init(config)?;
+//!
+//! // Anywhere else in the program
+//! let port = global::get::<AppConfig>().port;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::core::HotswapConfig;
+use crate::error::{ConfigError, Result};
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// Registered handles, keyed by config type.
+type Registry = RwLock<HashMap<TypeId, Box<dyn Any + Send + Sync>>>;
+
+fn registry() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(Default::default)
+}
+
+/// Register `config` as the global handle for `T`.
+///
+/// # Errors
+///
+/// Returns [`ConfigError::Other`] if a handle for `T` is already registered;
+/// the existing handle is kept.
+pub fn init<T>(config: HotswapConfig<T>) -> Result<()>
+where
+    T: Send + Sync + 'static,
+{
+    let mut handles = registry().write().unwrap();
+    if handles.contains_key(&TypeId::of::<T>()) {
+        return Err(ConfigError::Other(format!(
+            "Global configuration for {} is already initialized",
+            std::any::type_name::<T>()
+        )));
+    }
+    handles.insert(TypeId::of::<T>(), Box::new(config));
+    Ok(())
+}
+
+/// Get the global handle for `T`, or `None` if [`init`] hasn't been called.
+pub fn handle<T>() -> Option<HotswapConfig<T>>
+where
+    T: Send + Sync + 'static,
+{
+    registry()
+        .read()
+        .unwrap()
+        .get(&TypeId::of::<T>())
+        .and_then(|config| config.downcast_ref::<HotswapConfig<T>>())
+        .cloned()
+}
+
+/// Get the current global configuration of type `T`, or `None` if [`init`]
+/// hasn't been called.
+pub fn try_get<T>() -> Option<Arc<T>>
+where
+    T: Send + Sync + 'static,
+{
+    registry()
+        .read()
+        .unwrap()
+        .get(&TypeId::of::<T>())
+        .and_then(|config| config.downcast_ref::<HotswapConfig<T>>())
+        .map(HotswapConfig::get)
+}
+
+/// Get the current global configuration of type `T`.
+///
+/// # Panics
+///
+/// Panics if no handle for `T` has been registered with [`init`].
+pub fn get<T>() -> Arc<T>
+where
+    T: Send + Sync + 'static,
+{
+    try_get().unwrap_or_else(|| {
+        panic!(
+            "Global configuration for {} is not initialized; call hotswap_config::global::init first",
+            std::any::type_name::<T>()
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Each test uses its own types, since the registry is process-wide
+
+    #[derive(Debug, PartialEq)]
+    struct AppConfig {
+        port: u16,
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct FlagConfig {
+        beta: bool,
+    }
+
+    #[tokio::test]
+    async fn test_typed_slots_follow_updates() {
+        let app = HotswapConfig::new(AppConfig { port: 8080 });
+        init(app.clone()).unwrap();
+        init(HotswapConfig::new(FlagConfig { beta: true })).unwrap();
+
+        assert_eq!(get::<AppConfig>().port, 8080);
+        assert!(get::<FlagConfig>().beta);
+
+        app.update(AppConfig { port: 9090 }).await.unwrap();
+        assert_eq!(get::<AppConfig>().port, 9090);
+        assert_eq!(handle::<AppConfig>().unwrap().get().port, 9090);
+
+        // The first registration wins
+        assert!(init(HotswapConfig::new(AppConfig { port: 1 })).is_err());
+        assert_eq!(get::<AppConfig>().port, 9090);
+    }
+
+    #[test]
+    fn test_uninitialized_type() {
+        struct Unregistered;
+
+        assert!(try_get::<Unregistered>().is_none());
+        assert!(handle::<Unregistered>().is_none());
+        let result = std::panic::catch_unwind(get::<Unregistered>);
+        assert!(result.is_err());
+    }
+}
@@ -34,10 +34,10 @@
 //!
 //! # async fn example() -> hotswap_config::error::Result<()> {
 //! // Load configuration with standard precedence
-//! let config = HotswapConfig::builder()
+//! let config = HotswapConfig::<AppConfig>::builder()
 //!     .with_file("config/default.yaml")
 //!     .with_env_overrides("APP", "__")
-//!     .build::<AppConfig>()
+//!     .build()
 //!     .await?;
 //!
 //! // Zero-cost reads (no locks!)
@@ -75,20 +75,52 @@
 
 pub mod core;
 pub mod error;
+pub mod secrets;
 pub mod sources;
 
-#[cfg(feature = "partial-updates")]
+#[cfg(feature = "encryption")]
+pub mod encryption;
+
+#[cfg(feature = "signing")]
+pub mod signing;
+
+#[cfg(feature = "templating")]
+pub mod templating;
+
+#[cfg(any(
+    feature = "partial-updates",
+    feature = "rollback",
+    feature = "gradual-rollout",
+    feature = "flags"
+))]
 pub mod features;
 
 #[cfg(feature = "file-watch")]
 pub mod notify;
 
-#[cfg(feature = "metrics")]
 pub mod metrics;
 
+#[cfg(feature = "admin-api")]
+pub mod admin;
+
+#[cfg(feature = "web")]
+pub mod web;
+
+#[cfg(feature = "blocking")]
+pub mod blocking;
+
+#[cfg(feature = "global")]
+pub mod global;
+
+#[cfg(feature = "testing")]
+pub mod testing;
+
 /// Convenient re-exports for common usage patterns.
 pub mod prelude {
-    pub use crate::core::{HotswapConfig, HotswapConfigBuilder};
+    pub use crate::core::{
+        ErrorMode, HotswapConfig, HotswapConfigBuilder, ImmutableKeys, PolicyDecision, UnknownKeys,
+        UpdateContext, UpdateOrigin,
+    };
     pub use crate::error::{ConfigError, Result, ValidationError};
 
     #[cfg(feature = "validation")]
@@ -1,13 +1,21 @@
 //! Configuration metrics tracking using OpenTelemetry.
 
-use opentelemetry::metrics::{Counter, Gauge, Histogram, Meter};
+use super::MetricsRecorder;
+use crate::core::ReloadReason;
+use opentelemetry::KeyValue;
+use opentelemetry::metrics::{Counter, Gauge, Histogram, Meter, ObservableGauge};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 /// Metrics collector for configuration operations.
 ///
 /// Tracks reload attempts, success/failure rates, latencies, and subscriber counts
-/// using OpenTelemetry metrics.
+/// using OpenTelemetry metrics. Configuration age is observed on every metrics
+/// collection, so it doesn't need to be updated manually.
+///
+/// A handle built with [`HotswapConfigBuilder::with_metrics`](crate::core::HotswapConfigBuilder::with_metrics)
+/// records all of these automatically; use `ConfigMetrics` directly only to
+/// instrument custom reload paths.
 ///
 /// # Examples
 ///
@@ -26,12 +34,21 @@ use std::time::Instant;
 #[derive(Clone)]
 pub struct ConfigMetrics {
     reload_attempts: Counter<u64>,
+    reload_triggers: Counter<u64>,
     reload_success: Counter<u64>,
     reload_failures: Counter<u64>,
+    reload_skipped: Counter<u64>,
+    reload_coalesced: Counter<u64>,
+    reload_in_progress: Gauge<i64>,
     reload_duration: Histogram<f64>,
-    config_age_seconds: Gauge<i64>,
+    _config_age_seconds: ObservableGauge<i64>,
     active_subscribers: Gauge<i64>,
     validation_failures: Counter<u64>,
+    rollout_selections: Counter<u64>,
+    /// Attributes for the built-in rollout variants, built once so a
+    /// selection doesn't allocate
+    stable_variant: [KeyValue; 1],
+    canary_variant: [KeyValue; 1],
     last_update: Arc<parking_lot::Mutex<Instant>>,
 }
 
@@ -48,11 +65,18 @@ impl ConfigMetrics {
     /// let metrics = ConfigMetrics::new(meter);
     /// ```
     pub fn new(meter: Meter) -> Self {
+        let last_update = Arc::new(parking_lot::Mutex::new(Instant::now()));
+
         let reload_attempts = meter
             .u64_counter("hotswap_config.reload.attempts")
             .with_description("Total number of reload attempts")
             .build();
 
+        let reload_triggers = meter
+            .u64_counter("hotswap_config.reload.triggers")
+            .with_description("Number of reloads started, by what triggered them")
+            .build();
+
         let reload_success = meter
             .u64_counter("hotswap_config.reload.success")
             .with_description("Number of successful reloads")
@@ -63,16 +87,35 @@ impl ConfigMetrics {
             .with_description("Number of failed reloads")
             .build();
 
+        let reload_skipped = meter
+            .u64_counter("hotswap_config.reload.skipped_unchanged")
+            .with_description("Number of reloads skipped because sources were unchanged")
+            .build();
+
+        let reload_coalesced = meter
+            .u64_counter("hotswap_config.reload.coalesced")
+            .with_description("Number of reload requests that joined a reload already in progress")
+            .build();
+
+        let reload_in_progress = meter
+            .i64_gauge("hotswap_config.reload.in_progress")
+            .with_description("Whether a reload is running (1) or not (0)")
+            .build();
+
         let reload_duration = meter
             .f64_histogram("hotswap_config.reload.duration")
             .with_description("Duration of reload operations in seconds")
             .with_unit("s")
             .build();
 
+        let age_since = Arc::clone(&last_update);
         let config_age_seconds = meter
-            .i64_gauge("hotswap_config.age")
+            .i64_observable_gauge("hotswap_config.age")
             .with_description("Time since last configuration update in seconds")
             .with_unit("s")
+            .with_callback(move |observer| {
+                observer.observe(age_since.lock().elapsed().as_secs() as i64, &[]);
+            })
             .build();
 
         let active_subscribers = meter
@@ -85,15 +128,27 @@ impl ConfigMetrics {
             .with_description("Number of validation failures")
             .build();
 
+        let rollout_selections = meter
+            .u64_counter("hotswap_config.rollout.selections")
+            .with_description("Number of gradual rollout selections, by variant")
+            .build();
+
         Self {
             reload_attempts,
+            reload_triggers,
             reload_success,
             reload_failures,
+            reload_skipped,
+            reload_coalesced,
+            reload_in_progress,
             reload_duration,
-            config_age_seconds,
+            _config_age_seconds: config_age_seconds,
             active_subscribers,
             validation_failures,
-            last_update: Arc::new(parking_lot::Mutex::new(Instant::now())),
+            rollout_selections,
+            stable_variant: [KeyValue::new("variant", "stable")],
+            canary_variant: [KeyValue::new("variant", "canary")],
+            last_update,
         }
     }
 
@@ -113,7 +168,7 @@ impl ConfigMetrics {
     /// metrics.record_reload_success(timer);
     /// ```
     pub fn start_reload(&self) -> Instant {
-        self.reload_attempts.add(1, &[]);
+        self.reload_attempted();
         Instant::now()
     }
 
@@ -134,12 +189,7 @@ impl ConfigMetrics {
     /// metrics.record_reload_success(timer);
     /// ```
     pub fn record_reload_success(&self, start: Instant) {
-        let duration = start.elapsed().as_secs_f64();
-        self.reload_success.add(1, &[]);
-        self.reload_duration.record(duration, &[]);
-
-        // Update last update time
-        *self.last_update.lock() = Instant::now();
+        self.reload_succeeded(start.elapsed());
     }
 
     /// Record a failed reload operation.
@@ -159,9 +209,7 @@ impl ConfigMetrics {
     /// metrics.record_reload_failure(timer);
     /// ```
     pub fn record_reload_failure(&self, start: Instant) {
-        let duration = start.elapsed().as_secs_f64();
-        self.reload_failures.add(1, &[]);
-        self.reload_duration.record(duration, &[]);
+        self.reload_failed(start.elapsed());
     }
 
     /// Record a validation failure.
@@ -175,7 +223,7 @@ impl ConfigMetrics {
     /// metrics.record_validation_failure();
     /// ```
     pub fn record_validation_failure(&self) {
-        self.validation_failures.add(1, &[]);
+        self.validation_failed();
     }
 
     /// Update the number of active subscribers.
@@ -198,20 +246,10 @@ impl ConfigMetrics {
 
     /// Update the configuration age metric.
     ///
-    /// This should be called periodically to track how stale the configuration is.
-    ///
-    /// # Examples
-    ///
-    /// ```rust,no_run
-    /// # use hotswap_config::metrics::ConfigMetrics;
-    /// # use opentelemetry::global;
-    /// # let metrics = ConfigMetrics::new(global::meter("test"));
-    /// metrics.update_config_age();
-    /// ```
-    pub fn update_config_age(&self) {
-        let age_secs = self.last_update.lock().elapsed().as_secs() as i64;
-        self.config_age_seconds.record(age_secs, &[]);
-    }
+    /// The age is now observed automatically whenever metrics are collected,
+    /// so this does nothing.
+    #[deprecated(note = "configuration age is observed automatically on collection")]
+    pub fn update_config_age(&self) {}
 
     /// Record an update operation (manual update, not reload).
     ///
@@ -226,8 +264,67 @@ impl ConfigMetrics {
     /// metrics.record_update();
     /// ```
     pub fn record_update(&self) {
+        self.config_updated();
+    }
+}
+
+impl MetricsRecorder for ConfigMetrics {
+    fn reload_attempted(&self) {
+        self.reload_attempts.add(1, &[]);
+    }
+
+    fn reload_triggered(&self, reason: &ReloadReason) {
+        self.reload_triggers
+            .add(1, &[KeyValue::new("reason", reason.label().to_string())]);
+    }
+
+    fn reload_succeeded(&self, duration: Duration) {
+        self.reload_success.add(1, &[]);
+        self.reload_duration.record(duration.as_secs_f64(), &[]);
+
+        // Update last update time
         *self.last_update.lock() = Instant::now();
     }
+
+    fn reload_failed(&self, duration: Duration) {
+        self.reload_failures.add(1, &[]);
+        self.reload_duration.record(duration.as_secs_f64(), &[]);
+    }
+
+    fn reload_skipped(&self, duration: Duration) {
+        self.reload_skipped.add(1, &[]);
+        self.reload_duration.record(duration.as_secs_f64(), &[]);
+    }
+
+    fn reload_coalesced(&self) {
+        self.reload_coalesced.add(1, &[]);
+    }
+
+    fn reload_in_progress(&self, in_progress: bool) {
+        self.reload_in_progress.record(in_progress as i64, &[]);
+    }
+
+    fn validation_failed(&self) {
+        self.validation_failures.add(1, &[]);
+    }
+
+    fn config_updated(&self) {
+        *self.last_update.lock() = Instant::now();
+    }
+
+    fn subscribers_changed(&self, count: usize) {
+        self.active_subscribers.record(count as i64, &[]);
+    }
+
+    fn rollout_selected(&self, variant: &str) {
+        match variant {
+            "stable" => self.rollout_selections.add(1, &self.stable_variant),
+            "canary" => self.rollout_selections.add(1, &self.canary_variant),
+            _ => self
+                .rollout_selections
+                .add(1, &[KeyValue::new("variant", variant.to_string())]),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -249,7 +346,6 @@ mod tests {
 
         metrics.record_validation_failure();
         metrics.update_subscriber_count(5);
-        metrics.update_config_age();
         metrics.record_update();
     }
 
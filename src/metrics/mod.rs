@@ -1,31 +1,48 @@
 //! Built-in metrics for configuration operations.
 //!
-//! Provides OpenTelemetry metrics tracking:
+//! Tracks:
 //! - Reload attempts/success/failures
 //! - Reload duration
 //! - Configuration age
 //! - Active subscribers
 //! - Validation failures
+//! - Gradual rollout selections, by variant
+//!
+//! Metrics are sent to a [`MetricsRecorder`]. Backends are provided for
+//! OpenTelemetry ([`ConfigMetrics`], feature: `metrics`) and Prometheus
+//! ([`PrometheusRecorder`], feature: `prometheus`); implement the trait to use
+//! any other.
 //!
 //! # Examples
 //!
 //! ```rust,no_run
+//! # #[cfg(feature = "metrics")]
+//! # {
 //! use hotswap_config::prelude::*;
 //! use opentelemetry::global;
 //!
 //! # async fn example() -> Result<()> {
 //! let meter = global::meter("my-app");
 //!
-//! let config = HotswapConfig::builder()
+//! let config = HotswapConfig::<AppConfig>::builder()
 //!     .with_file("config.yaml")
 //!     .with_metrics(meter)
-//!     .build::<AppConfig>()
+//!     .build()
 //!     .await?;
 //! # Ok(())
 //! # }
 //! # #[derive(serde::Deserialize, Clone)] struct AppConfig {}
+//! # }
 //! ```
 
+#[cfg(feature = "metrics")]
 mod config_metrics;
+#[cfg(feature = "prometheus")]
+mod prometheus;
+mod recorder;
 
+#[cfg(feature = "metrics")]
 pub use config_metrics::ConfigMetrics;
+#[cfg(feature = "prometheus")]
+pub use prometheus::PrometheusRecorder;
+pub use recorder::MetricsRecorder;
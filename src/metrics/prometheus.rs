@@ -0,0 +1,330 @@
+//! Prometheus metrics backend.
+
+use super::MetricsRecorder;
+use crate::core::ReloadReason;
+use crate::error::{ConfigError, Result};
+use prometheus::{Histogram, HistogramOpts, IntCounter, IntCounterVec, IntGauge, Opts, Registry};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Records configuration metrics into a Prometheus registry.
+///
+/// Registers the following metrics:
+///
+/// | Metric | Type |
+/// |--------|------|
+/// | `hotswap_config_reload_attempts_total` | counter |
+/// | `hotswap_config_reload_triggers_total` | counter, by `reason` |
+/// | `hotswap_config_reload_success_total` | counter |
+/// | `hotswap_config_reload_failures_total` | counter |
+/// | `hotswap_config_reload_skipped_unchanged_total` | counter |
+/// | `hotswap_config_reload_coalesced_total` | counter |
+/// | `hotswap_config_reload_in_progress` | gauge |
+/// | `hotswap_config_reload_duration_seconds` | histogram |
+/// | `hotswap_config_validation_failures_total` | counter |
+/// | `hotswap_config_subscribers_active` | gauge |
+/// | `hotswap_config_last_update_timestamp_seconds` | gauge |
+/// | `hotswap_config_rollout_selections_total` | counter, by `variant` |
+///
+/// Configuration age is `time() - hotswap_config_last_update_timestamp_seconds`
+/// in PromQL.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use hotswap_config::metrics::PrometheusRecorder;
+/// use hotswap_config::prelude::*;
+///
+/// # async fn example() -> Result<()> {
+/// let registry = prometheus::Registry::new();
+///
+/// let config = HotswapConfig::<AppConfig>::builder()
+///     .with_file("config.yaml")
+///     .with_metrics_recorder(PrometheusRecorder::new(&registry)?)
+///     .build()
+///     .await?;
+///
+/// // Serve `registry.gather()` from your /metrics endpoint
+/// # Ok(())
+/// # }
+/// # #[derive(serde::Deserialize, Clone)] struct AppConfig {}
+/// ```
+#[derive(Clone)]
+pub struct PrometheusRecorder {
+    reload_attempts: IntCounter,
+    reload_triggers: IntCounterVec,
+    reload_success: IntCounter,
+    reload_failures: IntCounter,
+    reload_skipped: IntCounter,
+    reload_coalesced: IntCounter,
+    reload_in_progress: IntGauge,
+    reload_duration: Histogram,
+    validation_failures: IntCounter,
+    active_subscribers: IntGauge,
+    last_update: IntGauge,
+    rollout_selections: IntCounterVec,
+}
+
+impl PrometheusRecorder {
+    /// Create the metrics and register them with `registry`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a metric with the same name is already registered.
+    pub fn new(registry: &Registry) -> Result<Self> {
+        let recorder = Self {
+            reload_attempts: IntCounter::new(
+                "hotswap_config_reload_attempts_total",
+                "Total number of reload attempts",
+            )
+            .map_err(register_error)?,
+            reload_triggers: IntCounterVec::new(
+                Opts::new(
+                    "hotswap_config_reload_triggers_total",
+                    "Number of reloads started, by what triggered them",
+                ),
+                &["reason"],
+            )
+            .map_err(register_error)?,
+            reload_success: IntCounter::new(
+                "hotswap_config_reload_success_total",
+                "Number of successful reloads",
+            )
+            .map_err(register_error)?,
+            reload_failures: IntCounter::new(
+                "hotswap_config_reload_failures_total",
+                "Number of failed reloads",
+            )
+            .map_err(register_error)?,
+            reload_skipped: IntCounter::new(
+                "hotswap_config_reload_skipped_unchanged_total",
+                "Number of reloads skipped because sources were unchanged",
+            )
+            .map_err(register_error)?,
+            reload_coalesced: IntCounter::new(
+                "hotswap_config_reload_coalesced_total",
+                "Number of reload requests that joined a reload already in progress",
+            )
+            .map_err(register_error)?,
+            reload_in_progress: IntGauge::new(
+                "hotswap_config_reload_in_progress",
+                "Whether a reload is running (1) or not (0)",
+            )
+            .map_err(register_error)?,
+            reload_duration: Histogram::with_opts(HistogramOpts::new(
+                "hotswap_config_reload_duration_seconds",
+                "Duration of reload operations in seconds",
+            ))
+            .map_err(register_error)?,
+            validation_failures: IntCounter::new(
+                "hotswap_config_validation_failures_total",
+                "Number of validation failures",
+            )
+            .map_err(register_error)?,
+            active_subscribers: IntGauge::new(
+                "hotswap_config_subscribers_active",
+                "Number of active subscribers",
+            )
+            .map_err(register_error)?,
+            last_update: IntGauge::new(
+                "hotswap_config_last_update_timestamp_seconds",
+                "Unix time of the last configuration update",
+            )
+            .map_err(register_error)?,
+            rollout_selections: IntCounterVec::new(
+                Opts::new(
+                    "hotswap_config_rollout_selections_total",
+                    "Number of gradual rollout selections, by variant",
+                ),
+                &["variant"],
+            )
+            .map_err(register_error)?,
+        };
+        recorder.touch();
+
+        registry
+            .register(Box::new(recorder.reload_attempts.clone()))
+            .map_err(register_error)?;
+        registry
+            .register(Box::new(recorder.reload_triggers.clone()))
+            .map_err(register_error)?;
+        registry
+            .register(Box::new(recorder.reload_success.clone()))
+            .map_err(register_error)?;
+        registry
+            .register(Box::new(recorder.reload_failures.clone()))
+            .map_err(register_error)?;
+        registry
+            .register(Box::new(recorder.reload_skipped.clone()))
+            .map_err(register_error)?;
+        registry
+            .register(Box::new(recorder.reload_coalesced.clone()))
+            .map_err(register_error)?;
+        registry
+            .register(Box::new(recorder.reload_in_progress.clone()))
+            .map_err(register_error)?;
+        registry
+            .register(Box::new(recorder.reload_duration.clone()))
+            .map_err(register_error)?;
+        registry
+            .register(Box::new(recorder.validation_failures.clone()))
+            .map_err(register_error)?;
+        registry
+            .register(Box::new(recorder.active_subscribers.clone()))
+            .map_err(register_error)?;
+        registry
+            .register(Box::new(recorder.last_update.clone()))
+            .map_err(register_error)?;
+        registry
+            .register(Box::new(recorder.rollout_selections.clone()))
+            .map_err(register_error)?;
+
+        Ok(recorder)
+    }
+
+    /// Set the last update timestamp to now.
+    fn touch(&self) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        self.last_update.set(now as i64);
+    }
+}
+
+impl MetricsRecorder for PrometheusRecorder {
+    fn reload_attempted(&self) {
+        self.reload_attempts.inc();
+    }
+
+    fn reload_triggered(&self, reason: &ReloadReason) {
+        self.reload_triggers
+            .with_label_values(&[reason.label()])
+            .inc();
+    }
+
+    fn reload_succeeded(&self, duration: Duration) {
+        self.reload_success.inc();
+        self.reload_duration.observe(duration.as_secs_f64());
+        self.touch();
+    }
+
+    fn reload_failed(&self, duration: Duration) {
+        self.reload_failures.inc();
+        self.reload_duration.observe(duration.as_secs_f64());
+    }
+
+    fn reload_skipped(&self, duration: Duration) {
+        self.reload_skipped.inc();
+        self.reload_duration.observe(duration.as_secs_f64());
+    }
+
+    fn reload_coalesced(&self) {
+        self.reload_coalesced.inc();
+    }
+
+    fn reload_in_progress(&self, in_progress: bool) {
+        self.reload_in_progress.set(in_progress as i64);
+    }
+
+    fn validation_failed(&self) {
+        self.validation_failures.inc();
+    }
+
+    fn config_updated(&self) {
+        self.touch();
+    }
+
+    fn subscribers_changed(&self, count: usize) {
+        self.active_subscribers.set(count as i64);
+    }
+
+    fn rollout_selected(&self, variant: &str) {
+        self.rollout_selections.with_label_values(&[variant]).inc();
+    }
+}
+
+fn register_error(e: prometheus::Error) -> ConfigError {
+    ConfigError::Other(format!("Failed to register Prometheus metrics: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(registry: &Registry, name: &str) -> f64 {
+        let family = registry
+            .gather()
+            .into_iter()
+            .find(|family| family.name() == name)
+            .unwrap();
+        let metric = &family.get_metric()[0];
+        match family.get_field_type() {
+            prometheus::proto::MetricType::COUNTER => metric.get_counter().get_value(),
+            prometheus::proto::MetricType::GAUGE => metric.get_gauge().get_value(),
+            _ => metric.get_histogram().get_sample_count() as f64,
+        }
+    }
+
+    #[test]
+    fn test_records_into_registry() {
+        let registry = Registry::new();
+        let recorder = PrometheusRecorder::new(&registry).unwrap();
+
+        recorder.reload_attempted();
+        recorder.reload_triggered(&ReloadReason::Schedule);
+        recorder.reload_succeeded(Duration::from_millis(5));
+        recorder.reload_attempted();
+        recorder.reload_failed(Duration::from_millis(5));
+        recorder.reload_attempted();
+        recorder.reload_skipped(Duration::from_millis(5));
+        recorder.reload_coalesced();
+        recorder.reload_in_progress(true);
+        recorder.validation_failed();
+        recorder.subscribers_changed(3);
+        recorder.rollout_selected("canary");
+
+        assert_eq!(
+            value(&registry, "hotswap_config_reload_attempts_total"),
+            3.0
+        );
+        assert_eq!(
+            value(&registry, "hotswap_config_reload_triggers_total"),
+            1.0
+        );
+        assert_eq!(value(&registry, "hotswap_config_reload_success_total"), 1.0);
+        assert_eq!(
+            value(&registry, "hotswap_config_reload_failures_total"),
+            1.0
+        );
+        assert_eq!(
+            value(&registry, "hotswap_config_reload_skipped_unchanged_total"),
+            1.0
+        );
+        assert_eq!(
+            value(&registry, "hotswap_config_reload_coalesced_total"),
+            1.0
+        );
+        assert_eq!(value(&registry, "hotswap_config_reload_in_progress"), 1.0);
+        assert_eq!(
+            value(&registry, "hotswap_config_reload_duration_seconds"),
+            3.0
+        );
+        assert_eq!(
+            value(&registry, "hotswap_config_validation_failures_total"),
+            1.0
+        );
+        assert_eq!(value(&registry, "hotswap_config_subscribers_active"), 3.0);
+        assert!(value(&registry, "hotswap_config_last_update_timestamp_seconds") > 0.0);
+        assert_eq!(
+            value(&registry, "hotswap_config_rollout_selections_total"),
+            1.0
+        );
+    }
+
+    #[test]
+    fn test_duplicate_registration_fails() {
+        let registry = Registry::new();
+        PrometheusRecorder::new(&registry).unwrap();
+        assert!(PrometheusRecorder::new(&registry).is_err());
+    }
+}
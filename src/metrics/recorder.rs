@@ -0,0 +1,91 @@
+//! Backend-agnostic metrics recording.
+
+use crate::core::ReloadReason;
+use std::time::Duration;
+
+/// Receives metrics events from a configuration handle.
+///
+/// Implement this to send configuration metrics to any backend. Built-in
+/// implementations are [`ConfigMetrics`](super::ConfigMetrics) (OpenTelemetry,
+/// feature: `metrics`) and [`PrometheusRecorder`](super::PrometheusRecorder)
+/// (feature: `prometheus`). Methods are called on the reload and update paths,
+/// so they should be cheap and must not block.
+///
+/// # Examples
+///
+/// ```rust
+/// use hotswap_config::metrics::MetricsRecorder;
+/// use std::sync::atomic::{AtomicU64, Ordering};
+/// use std::time::Duration;
+///
+/// #[derive(Default)]
+/// struct ReloadCounter {
+///     reloads: AtomicU64,
+/// }
+///
+/// impl MetricsRecorder for ReloadCounter {
+///     fn reload_attempted(&self) {
+///         self.reloads.fetch_add(1, Ordering::Relaxed);
+///     }
+///     fn reload_succeeded(&self, _duration: Duration) {}
+///     fn reload_failed(&self, _duration: Duration) {}
+///     fn validation_failed(&self) {}
+///     fn config_updated(&self) {}
+///     fn subscribers_changed(&self, _count: usize) {}
+/// }
+/// ```
+pub trait MetricsRecorder: Send + Sync {
+    /// A reload has started.
+    fn reload_attempted(&self);
+
+    /// A reload started for `reason`, right after
+    /// [`reload_attempted`](Self::reload_attempted).
+    ///
+    /// Does nothing by default.
+    fn reload_triggered(&self, reason: &ReloadReason) {
+        let _ = reason;
+    }
+
+    /// A reload succeeded after `duration`. The new config counts as an update.
+    fn reload_succeeded(&self, duration: Duration);
+
+    /// A reload failed after `duration`.
+    fn reload_failed(&self, duration: Duration);
+
+    /// A reload found the sources unchanged and skipped the swap.
+    ///
+    /// Not counted as a success. Does nothing by default.
+    fn reload_skipped(&self, duration: Duration) {
+        let _ = duration;
+    }
+
+    /// A reload request joined a reload already in progress instead of
+    /// loading again.
+    ///
+    /// The joined reload records its own outcome. Does nothing by default.
+    fn reload_coalesced(&self) {}
+
+    /// A reload started (`true`) or finished (`false`).
+    ///
+    /// Does nothing by default.
+    fn reload_in_progress(&self, in_progress: bool) {
+        let _ = in_progress;
+    }
+
+    /// A new configuration was rejected by validation.
+    fn validation_failed(&self);
+
+    /// A configuration was applied outside a reload (manual update or canary promotion).
+    fn config_updated(&self);
+
+    /// The number of active subscribers changed.
+    fn subscribers_changed(&self, count: usize);
+
+    /// A gradual rollout served the `variant` configuration (`"stable"` or
+    /// `"canary"`) to a request.
+    ///
+    /// Called on the request path. Does nothing by default.
+    fn rollout_selected(&self, variant: &str) {
+        let _ = variant;
+    }
+}
@@ -5,5 +5,5 @@
 pub mod subscriber;
 pub mod watcher;
 
-pub use subscriber::{SubscriberRegistry, SubscriptionHandle};
+pub use subscriber::{DeliveryOptions, SubscribeOptions, SubscriberRegistry, SubscriptionHandle};
 pub use watcher::ConfigWatcher;
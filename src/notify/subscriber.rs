@@ -1,31 +1,216 @@
 //! Subscriber-based notifications for configuration changes.
 
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use crate::error::ConfigError;
+use arc_swap::ArcSwap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+/// Type alias for reload error callbacks.
+type ErrorCallback = Box<dyn Fn(&ConfigError) + Send + Sync>;
+
+/// Type alias for the listener told the subscriber count after it changes.
+pub(crate) type CountListener = Box<dyn Fn(usize) + Send + Sync>;
+
+/// How a change subscriber is called.
+enum Delivery {
+    /// Called inline by `notify_all`
+    Inline(Box<dyn Fn() + Send + Sync>),
+    /// Signalled by `notify_all` and called from its own task
+    Queued(mpsc::UnboundedSender<()>),
+}
+
+/// A registered change subscriber.
+struct Subscriber {
+    /// Set when its handle is dropped
+    removed: Arc<AtomicBool>,
+    priority: i32,
+    /// Logged as the subscriber runs
+    #[cfg_attr(not(feature = "tracing"), allow(dead_code))]
+    name: Option<String>,
+    delivery: Delivery,
+}
+
+/// A registered reload error subscriber.
+struct ErrorSubscriber {
+    /// Set when its handle is dropped
+    removed: Arc<AtomicBool>,
+    callback: ErrorCallback,
+}
+
+/// Ordering and identity of a change subscriber.
+///
+/// Subscribers with a higher priority are notified first; equal priorities
+/// are notified in the order they subscribed. Plain
+/// [`subscribe`](SubscriberRegistry::subscribe) uses priority 0 and no name.
+///
+/// # Examples
+///
+/// ```rust
+/// use hotswap_config::notify::SubscribeOptions;
+///
+/// // Re-initialize TLS before anything re-binds listeners
+/// let options = SubscribeOptions {
+///     priority: 100,
+///     name: Some("tls".to_string()),
+/// };
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SubscribeOptions {
+    /// Notification order; higher runs earlier.
+    pub priority: i32,
+    /// Name identifying the subscriber in logs.
+    pub name: Option<String>,
+}
+
+/// Options for delivering changes to a subscriber from its own task.
+///
+/// Subscribers registered with these options never block
+/// [`notify_all`](SubscriberRegistry::notify_all): each change is queued and
+/// the callback runs on a task of its own. By default every change is
+/// delivered, one call at a time.
+///
+/// # Examples
+///
+/// ```rust
+/// use hotswap_config::notify::DeliveryOptions;
+/// use std::time::Duration;
+///
+/// // One call after changes have been quiet for 200ms
+/// let options = DeliveryOptions::default().with_debounce(Duration::from_millis(200));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DeliveryOptions {
+    /// Wait until no change has arrived for this long, then call once.
+    pub debounce: Option<Duration>,
+    /// Collapse changes queued while the callback was running into one call.
+    pub coalesce: bool,
+}
+
+impl DeliveryOptions {
+    /// Call once after changes have been quiet for `window`.
+    ///
+    /// A burst of changes (several sources updating in quick succession)
+    /// results in a single call. Changes that keep arriving within the window
+    /// keep postponing the call.
+    pub fn with_debounce(mut self, window: Duration) -> Self {
+        self.debounce = Some(window);
+        self
+    }
+
+    /// Deliver only the latest state: changes that arrive while the callback
+    /// is running are collapsed into one more call.
+    pub fn with_coalesce(mut self, coalesce: bool) -> Self {
+        self.coalesce = coalesce;
+        self
+    }
+}
+
+/// Spawn the task delivering queued changes to `callback`, until the
+/// subscription is dropped or `token` is cancelled.
+fn spawn_delivery<F, Fut>(
+    callback: F,
+    options: DeliveryOptions,
+    token: CancellationToken,
+) -> mpsc::UnboundedSender<()>
+where
+    F: Fn() -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send,
+{
+    let (changes, mut queued) = mpsc::unbounded_channel();
+    let deliver = async move {
+        while queued.recv().await.is_some() {
+            if let Some(window) = options.debounce {
+                // Absorb changes until the window passes quietly
+                while let Ok(Some(())) = tokio::time::timeout(window, queued.recv()).await {}
+            }
+            if options.coalesce {
+                while queued.try_recv().is_ok() {}
+            }
+            callback().await;
+        }
+    };
+    tokio::spawn(async move {
+        tokio::select! {
+            _ = token.cancelled() => {}
+            _ = deliver => {}
+        }
+    });
+    changes
+}
 
 /// Handle for a subscription that can be dropped to unsubscribe.
 ///
 /// When the handle is dropped, the subscription is automatically removed.
+/// Dropping it is safe anywhere, including outside a Tokio runtime and from
+/// inside a subscriber's own callback; a notification already in progress
+/// doesn't call it again.
 pub struct SubscriptionHandle {
-    id: usize,
-    registry: Arc<RwLock<SubscriberRegistryInner>>,
+    removed: Arc<AtomicBool>,
+    registry: Arc<SubscriberRegistryInner>,
 }
 
 impl Drop for SubscriptionHandle {
     fn drop(&mut self) {
-        let id = self.id;
-        let registry = Arc::clone(&self.registry);
-        tokio::spawn(async move {
-            let mut inner = registry.write().await;
-            inner.subscribers.retain(|(sub_id, _)| *sub_id != id);
-        });
+        self.removed.store(true, Ordering::Release);
+        self.registry.prune();
     }
 }
 
 /// Internal subscriber registry state.
+///
+/// The lists are replaced as a whole on every change, so notifying reads a
+/// snapshot without locking and callbacks may subscribe or unsubscribe.
 struct SubscriberRegistryInner {
-    subscribers: Vec<(usize, Box<dyn Fn() + Send + Sync>)>,
-    next_id: usize,
+    /// Change subscribers, highest priority first
+    subscribers: ArcSwap<Vec<Arc<Subscriber>>>,
+    error_subscribers: ArcSwap<Vec<Arc<ErrorSubscriber>>>,
+    /// Held while replacing a list, so concurrent changes aren't lost
+    writer: Mutex<()>,
+    count_listener: Option<CountListener>,
+}
+
+impl SubscriberRegistryInner {
+    fn count_changed(&self) {
+        if let Some(listener) = &self.count_listener {
+            listener(self.subscribers.load().len());
+        }
+    }
+
+    /// Take out the subscriptions whose handles were dropped.
+    fn prune(&self) {
+        let _writer = self.writer.lock().unwrap();
+        let subscribers = self.subscribers.load();
+        if subscribers
+            .iter()
+            .any(|subscriber| subscriber.removed.load(Ordering::Acquire))
+        {
+            self.subscribers.store(Arc::new(
+                subscribers
+                    .iter()
+                    .filter(|subscriber| !subscriber.removed.load(Ordering::Acquire))
+                    .cloned()
+                    .collect(),
+            ));
+            self.count_changed();
+        }
+        let error_subscribers = self.error_subscribers.load();
+        if error_subscribers
+            .iter()
+            .any(|subscriber| subscriber.removed.load(Ordering::Acquire))
+        {
+            self.error_subscribers.store(Arc::new(
+                error_subscribers
+                    .iter()
+                    .filter(|subscriber| !subscriber.removed.load(Ordering::Acquire))
+                    .cloned()
+                    .collect(),
+            ));
+        }
+    }
 }
 
 /// Registry for managing configuration change subscribers.
@@ -44,27 +229,34 @@ struct SubscriberRegistryInner {
 ///
 /// let handle = registry.subscribe(|| {
 ///     println!("Config changed!");
-/// }).await;
+/// });
 ///
 /// // Notify all subscribers
-/// registry.notify_all().await;
+/// registry.notify_all();
 ///
 /// // Unsubscribe by dropping the handle
 /// drop(handle);
 /// # }
 /// ```
 pub struct SubscriberRegistry {
-    inner: Arc<RwLock<SubscriberRegistryInner>>,
+    inner: Arc<SubscriberRegistryInner>,
 }
 
 impl SubscriberRegistry {
     /// Create a new subscriber registry.
     pub fn new() -> Self {
+        Self::with_count_listener(None)
+    }
+
+    /// Create a registry that reports the subscriber count whenever it changes.
+    pub(crate) fn with_count_listener(count_listener: Option<CountListener>) -> Self {
         Self {
-            inner: Arc::new(RwLock::new(SubscriberRegistryInner {
-                subscribers: Vec::new(),
-                next_id: 0,
-            })),
+            inner: Arc::new(SubscriberRegistryInner {
+                subscribers: ArcSwap::from_pointee(Vec::new()),
+                error_subscribers: ArcSwap::from_pointee(Vec::new()),
+                writer: Mutex::new(()),
+                count_listener,
+            }),
         }
     }
 
@@ -86,30 +278,179 @@ impl SubscriberRegistry {
     ///
     /// let handle = registry.subscribe(|| {
     ///     println!("Configuration updated!");
-    /// }).await;
+    /// });
     ///
     /// // Later, unsubscribe
     /// drop(handle);
     /// # }
     /// ```
-    pub async fn subscribe<F>(&self, callback: F) -> SubscriptionHandle
+    pub fn subscribe<F>(&self, callback: F) -> SubscriptionHandle
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.add_subscriber(
+            Delivery::Inline(Box::new(callback)),
+            SubscribeOptions::default(),
+        )
+    }
+
+    /// Subscribe to configuration changes with a priority and name.
+    ///
+    /// `notify_all` calls subscribers in a deterministic order: highest
+    /// [`priority`](SubscribeOptions::priority) first, then in subscription
+    /// order. With the `tracing` feature, each subscriber's name is logged
+    /// as it runs.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use hotswap_config::notify::{SubscribeOptions, SubscriberRegistry};
+    /// # async fn example() {
+    /// let registry = SubscriberRegistry::new();
+    ///
+    /// let listeners = registry.subscribe(|| println!("Re-binding listeners"));
+    /// let tls = registry.subscribe_with_options(
+    ///     || println!("Reloading certificates"),
+    ///     SubscribeOptions { priority: 100, name: Some("tls".to_string()) },
+    /// );
+    /// # }
+    /// ```
+    pub fn subscribe_with_options<F>(
+        &self,
+        callback: F,
+        options: SubscribeOptions,
+    ) -> SubscriptionHandle
     where
         F: Fn() + Send + Sync + 'static,
     {
-        let mut inner = self.inner.write().await;
-        let id = inner.next_id;
-        inner.next_id += 1;
-        inner.subscribers.push((id, Box::new(callback)));
+        self.add_subscriber(Delivery::Inline(Box::new(callback)), options)
+    }
+
+    /// Subscribe to configuration changes, delivered from a task of the
+    /// subscriber's own.
+    ///
+    /// Like [`subscribe`](Self::subscribe), but `notify_all` only queues the
+    /// change, so a slow callback doesn't hold up other subscribers. Use
+    /// [`DeliveryOptions`] to debounce bursts or coalesce queued changes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use hotswap_config::notify::{DeliveryOptions, SubscriberRegistry};
+    /// # use std::time::Duration;
+    /// # async fn example() {
+    /// let registry = SubscriberRegistry::new();
+    ///
+    /// let handle = registry.subscribe_with_delivery(
+    ///     || println!("Configuration settled"),
+    ///     DeliveryOptions::default().with_debounce(Duration::from_millis(200)),
+    /// );
+    /// # }
+    /// ```
+    pub fn subscribe_with_delivery<F>(
+        &self,
+        callback: F,
+        options: DeliveryOptions,
+    ) -> SubscriptionHandle
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.subscribe_queued(
+            move || {
+                callback();
+                std::future::ready(())
+            },
+            options,
+            CancellationToken::new(),
+        )
+    }
+
+    /// Subscribe to configuration changes with an async callback.
+    ///
+    /// The returned future is awaited on the subscriber's own task, one
+    /// change at a time, so heavyweight work (reconnecting, rebuilding
+    /// caches) never blocks `notify_all`. [`DeliveryOptions`] apply as for
+    /// [`subscribe_with_delivery`](Self::subscribe_with_delivery).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use hotswap_config::notify::{DeliveryOptions, SubscriberRegistry};
+    /// # async fn example() {
+    /// let registry = SubscriberRegistry::new();
+    ///
+    /// let handle = registry.subscribe_async(
+    ///     || async {
+    ///         // Reconnect with the new settings
+    ///     },
+    ///     DeliveryOptions::default().with_coalesce(true),
+    /// );
+    /// # }
+    /// ```
+    pub fn subscribe_async<F, Fut>(
+        &self,
+        callback: F,
+        options: DeliveryOptions,
+    ) -> SubscriptionHandle
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.subscribe_queued(callback, options, CancellationToken::new())
+    }
+
+    /// Subscribe with a callback called from its own task, which also stops
+    /// once `token` is cancelled, e.g. when its handle shuts down.
+    pub(crate) fn subscribe_queued<F, Fut>(
+        &self,
+        callback: F,
+        options: DeliveryOptions,
+        token: CancellationToken,
+    ) -> SubscriptionHandle
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let changes = spawn_delivery(callback, options, token);
+        self.add_subscriber(Delivery::Queued(changes), SubscribeOptions::default())
+    }
+
+    /// Register a change subscriber after those of equal or higher priority
+    /// and hand out its handle.
+    fn add_subscriber(&self, delivery: Delivery, options: SubscribeOptions) -> SubscriptionHandle {
+        let removed = Arc::new(AtomicBool::new(false));
+        let subscriber = Arc::new(Subscriber {
+            removed: Arc::clone(&removed),
+            priority: options.priority,
+            name: options.name,
+            delivery,
+        });
+
+        let _writer = self.inner.writer.lock().unwrap();
+        let mut subscribers = Vec::clone(&self.inner.subscribers.load());
+        let position =
+            subscribers.partition_point(|subscriber| subscriber.priority >= options.priority);
+        subscribers.insert(position, subscriber);
+        self.inner.subscribers.store(Arc::new(subscribers));
+        self.inner.count_changed();
 
         SubscriptionHandle {
-            id,
+            removed,
             registry: Arc::clone(&self.inner),
         }
     }
 
     /// Notify all subscribers of a configuration change.
     ///
-    /// This calls all registered callbacks in the order they were subscribed.
+    /// This calls all registered callbacks, highest priority first and in the
+    /// order they were subscribed within a priority. Subscribers with
+    /// [`DeliveryOptions`] or async callbacks are only signalled here, and run
+    /// on their own tasks.
+    ///
+    /// No lock is held while callbacks run: they see a snapshot of the
+    /// subscribers taken when notifying starts, and may subscribe or drop
+    /// handles themselves. Subscriptions added meanwhile are notified from the
+    /// next change on.
     ///
     /// # Examples
     ///
@@ -118,24 +459,90 @@ impl SubscriberRegistry {
     /// # async fn example() {
     /// let registry = SubscriberRegistry::new();
     ///
-    /// registry.subscribe(|| println!("Subscriber 1")).await;
-    /// registry.subscribe(|| println!("Subscriber 2")).await;
+    /// registry.subscribe(|| println!("Subscriber 1"));
+    /// registry.subscribe(|| println!("Subscriber 2"));
     ///
     /// // Notify all subscribers
-    /// registry.notify_all().await;
+    /// registry.notify_all();
     /// # }
     /// ```
-    pub async fn notify_all(&self) {
-        let inner = self.inner.read().await;
-        for (_id, callback) in &inner.subscribers {
-            callback();
+    pub fn notify_all(&self) {
+        let subscribers = self.inner.subscribers.load_full();
+        #[cfg(feature = "tracing")]
+        tracing::debug!(subscribers = subscribers.len(), "Notifying subscribers");
+        for subscriber in subscribers.iter() {
+            if subscriber.removed.load(Ordering::Acquire) {
+                continue;
+            }
+            #[cfg(feature = "tracing")]
+            tracing::debug!(
+                subscriber = subscriber.name.as_deref().unwrap_or("unnamed"),
+                priority = subscriber.priority,
+                "Notifying subscriber"
+            );
+            match &subscriber.delivery {
+                Delivery::Inline(callback) => callback(),
+                // The task has exited only if the runtime is shutting down
+                Delivery::Queued(changes) => {
+                    let _ = changes.send(());
+                }
+            }
+        }
+    }
+
+    /// Subscribe to reload failures.
+    ///
+    /// The provided callback will be invoked with the error whenever a reload
+    /// fails and the previous configuration is retained. Returns a handle that
+    /// can be dropped to unsubscribe.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use hotswap_config::notify::SubscriberRegistry;
+    /// # async fn example() {
+    /// let registry = SubscriberRegistry::new();
+    ///
+    /// let handle = registry.subscribe_errors(|err| {
+    ///     eprintln!("Reload failed: {}", err);
+    /// });
+    /// # }
+    /// ```
+    pub fn subscribe_errors<F>(&self, callback: F) -> SubscriptionHandle
+    where
+        F: Fn(&ConfigError) + Send + Sync + 'static,
+    {
+        let removed = Arc::new(AtomicBool::new(false));
+        let subscriber = Arc::new(ErrorSubscriber {
+            removed: Arc::clone(&removed),
+            callback: Box::new(callback),
+        });
+
+        let _writer = self.inner.writer.lock().unwrap();
+        let mut subscribers = Vec::clone(&self.inner.error_subscribers.load());
+        subscribers.push(subscriber);
+        self.inner.error_subscribers.store(Arc::new(subscribers));
+
+        SubscriptionHandle {
+            removed,
+            registry: Arc::clone(&self.inner),
+        }
+    }
+
+    /// Notify all error subscribers that a reload failed.
+    ///
+    /// This calls all registered error callbacks in the order they were subscribed.
+    pub fn notify_error(&self, error: &ConfigError) {
+        for subscriber in self.inner.error_subscribers.load_full().iter() {
+            if !subscriber.removed.load(Ordering::Acquire) {
+                (subscriber.callback)(error);
+            }
         }
     }
 
     /// Get the number of active subscribers.
-    pub async fn subscriber_count(&self) -> usize {
-        let inner = self.inner.read().await;
-        inner.subscribers.len()
+    pub fn subscriber_count(&self) -> usize {
+        self.inner.subscribers.load().len()
     }
 }
 
@@ -164,16 +571,14 @@ mod tests {
         let counter = Arc::new(AtomicUsize::new(0));
 
         let counter_clone = Arc::clone(&counter);
-        let _handle = registry
-            .subscribe(move || {
-                counter_clone.fetch_add(1, Ordering::SeqCst);
-            })
-            .await;
+        let _handle = registry.subscribe(move || {
+            counter_clone.fetch_add(1, Ordering::SeqCst);
+        });
 
-        registry.notify_all().await;
+        registry.notify_all();
         assert_eq!(counter.load(Ordering::SeqCst), 1);
 
-        registry.notify_all().await;
+        registry.notify_all();
         assert_eq!(counter.load(Ordering::SeqCst), 2);
     }
 
@@ -184,20 +589,16 @@ mod tests {
         let counter2 = Arc::new(AtomicUsize::new(0));
 
         let counter1_clone = Arc::clone(&counter1);
-        let _handle1 = registry
-            .subscribe(move || {
-                counter1_clone.fetch_add(1, Ordering::SeqCst);
-            })
-            .await;
+        let _handle1 = registry.subscribe(move || {
+            counter1_clone.fetch_add(1, Ordering::SeqCst);
+        });
 
         let counter2_clone = Arc::clone(&counter2);
-        let _handle2 = registry
-            .subscribe(move || {
-                counter2_clone.fetch_add(1, Ordering::SeqCst);
-            })
-            .await;
+        let _handle2 = registry.subscribe(move || {
+            counter2_clone.fetch_add(1, Ordering::SeqCst);
+        });
 
-        registry.notify_all().await;
+        registry.notify_all();
         assert_eq!(counter1.load(Ordering::SeqCst), 1);
         assert_eq!(counter2.load(Ordering::SeqCst), 1);
     }
@@ -208,22 +609,17 @@ mod tests {
         let counter = Arc::new(AtomicUsize::new(0));
 
         let counter_clone = Arc::clone(&counter);
-        let handle = registry
-            .subscribe(move || {
-                counter_clone.fetch_add(1, Ordering::SeqCst);
-            })
-            .await;
+        let handle = registry.subscribe(move || {
+            counter_clone.fetch_add(1, Ordering::SeqCst);
+        });
 
-        registry.notify_all().await;
+        registry.notify_all();
         assert_eq!(counter.load(Ordering::SeqCst), 1);
 
         // Unsubscribe by dropping handle
         drop(handle);
 
-        // Give the drop task time to complete
-        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-
-        registry.notify_all().await;
+        registry.notify_all();
         // Counter should still be 1 (not incremented)
         assert_eq!(counter.load(Ordering::SeqCst), 1);
     }
@@ -231,17 +627,179 @@ mod tests {
     #[tokio::test]
     async fn test_subscriber_count() {
         let registry = SubscriberRegistry::new();
-        assert_eq!(registry.subscriber_count().await, 0);
+        assert_eq!(registry.subscriber_count(), 0);
 
-        let _handle1 = registry.subscribe(|| {}).await;
-        assert_eq!(registry.subscriber_count().await, 1);
+        let _handle1 = registry.subscribe(|| {});
+        assert_eq!(registry.subscriber_count(), 1);
 
-        let _handle2 = registry.subscribe(|| {}).await;
-        assert_eq!(registry.subscriber_count().await, 2);
+        let _handle2 = registry.subscribe(|| {});
+        assert_eq!(registry.subscriber_count(), 2);
 
         drop(_handle1);
-        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-        assert_eq!(registry.subscriber_count().await, 1);
+        assert_eq!(registry.subscriber_count(), 1);
+    }
+
+    #[test]
+    fn test_unsubscribe_outside_runtime() {
+        let registry = SubscriberRegistry::new();
+        let counter = Arc::new(AtomicUsize::new(0));
+
+        let counter_clone = Arc::clone(&counter);
+        let handle = registry.subscribe(move || {
+            counter_clone.fetch_add(1, Ordering::SeqCst);
+        });
+        let errors = registry.subscribe_errors(|_| {});
+
+        drop(handle);
+        drop(errors);
+        assert_eq!(registry.subscriber_count(), 0);
+        assert!(registry.inner.error_subscribers.load().is_empty());
+
+        registry.notify_all();
+        assert_eq!(counter.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_callbacks_may_subscribe_and_unsubscribe() {
+        let registry = SubscriberRegistry::new();
+        let handles = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let registry_clone = registry.clone();
+        let handles_clone = Arc::clone(&handles);
+        let _handle = registry.subscribe(move || {
+            // Replace the subscription made on the previous change
+            let mut handles = handles_clone.lock().unwrap();
+            handles.clear();
+            handles.push(registry_clone.subscribe(|| {}));
+        });
+
+        registry.notify_all();
+        registry.notify_all();
+        assert_eq!(registry.subscriber_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_errors() {
+        let registry = SubscriberRegistry::new();
+        let errors = Arc::new(AtomicUsize::new(0));
+        let changes = Arc::new(AtomicUsize::new(0));
+
+        let errors_clone = Arc::clone(&errors);
+        let handle = registry.subscribe_errors(move |err| {
+            assert!(err.to_string().contains("boom"));
+            errors_clone.fetch_add(1, Ordering::SeqCst);
+        });
+
+        let changes_clone = Arc::clone(&changes);
+        let _change_handle = registry.subscribe(move || {
+            changes_clone.fetch_add(1, Ordering::SeqCst);
+        });
+
+        registry.notify_error(&ConfigError::LoadError("boom".to_string()));
+        assert_eq!(errors.load(Ordering::SeqCst), 1);
+        assert_eq!(changes.load(Ordering::SeqCst), 0);
+
+        // Error subscribers don't count as change subscribers
+        assert_eq!(registry.subscriber_count(), 1);
+
+        drop(handle);
+
+        registry.notify_error(&ConfigError::LoadError("boom".to_string()));
+        assert_eq!(errors.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_priority_orders_notifications() {
+        let registry = SubscriberRegistry::new();
+        let order = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let mut handles = Vec::new();
+        for (label, priority) in [("listeners", 0), ("tls", 100), ("metrics", 0), ("dns", 50)] {
+            let order = Arc::clone(&order);
+            handles.push(registry.subscribe_with_options(
+                move || order.lock().unwrap().push(label),
+                SubscribeOptions {
+                    priority,
+                    name: Some(label.to_string()),
+                },
+            ));
+        }
+        let order_clone = Arc::clone(&order);
+        handles.push(registry.subscribe(move || order_clone.lock().unwrap().push("default")));
+
+        registry.notify_all();
+        assert_eq!(
+            *order.lock().unwrap(),
+            ["tls", "dns", "listeners", "metrics", "default"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_debounced_delivery_collapses_bursts() {
+        let registry = SubscriberRegistry::new();
+        let counter = Arc::new(AtomicUsize::new(0));
+
+        let counter_clone = Arc::clone(&counter);
+        let _handle = registry.subscribe_with_delivery(
+            move || {
+                counter_clone.fetch_add(1, Ordering::SeqCst);
+            },
+            DeliveryOptions::default().with_debounce(Duration::from_millis(50)),
+        );
+
+        for _ in 0..5 {
+            registry.notify_all();
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert_eq!(counter.load(Ordering::SeqCst), 0);
+
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_async_delivery_does_not_block_notify() {
+        let registry = SubscriberRegistry::new();
+        let every = Arc::new(AtomicUsize::new(0));
+        let coalesced = Arc::new(AtomicUsize::new(0));
+
+        let every_clone = Arc::clone(&every);
+        let _every_handle = registry.subscribe_async(
+            move || {
+                let every = Arc::clone(&every_clone);
+                async move {
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                    every.fetch_add(1, Ordering::SeqCst);
+                }
+            },
+            DeliveryOptions::default(),
+        );
+        let coalesced_clone = Arc::clone(&coalesced);
+        let _coalesced_handle = registry.subscribe_async(
+            move || {
+                let coalesced = Arc::clone(&coalesced_clone);
+                async move {
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                    coalesced.fetch_add(1, Ordering::SeqCst);
+                }
+            },
+            DeliveryOptions::default().with_coalesce(true),
+        );
+
+        let started = std::time::Instant::now();
+        registry.notify_all();
+        assert!(started.elapsed() < Duration::from_millis(20));
+
+        // Two more changes arrive while both callbacks are running
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        registry.notify_all();
+        registry.notify_all();
+        assert_eq!(registry.subscriber_count(), 2);
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        // Every change is delivered in turn, or only the first and the latest
+        assert_eq!(every.load(Ordering::SeqCst), 3);
+        assert_eq!(coalesced.load(Ordering::SeqCst), 2);
     }
 
     #[tokio::test]
@@ -252,14 +810,12 @@ mod tests {
         let counter = Arc::new(AtomicUsize::new(0));
         let counter_clone = Arc::clone(&counter);
 
-        let _handle = registry
-            .subscribe(move || {
-                counter_clone.fetch_add(1, Ordering::SeqCst);
-            })
-            .await;
+        let _handle = registry.subscribe(move || {
+            counter_clone.fetch_add(1, Ordering::SeqCst);
+        });
 
         // Notify via clone
-        registry2.notify_all().await;
+        registry2.notify_all();
         assert_eq!(counter.load(Ordering::SeqCst), 1);
     }
 }
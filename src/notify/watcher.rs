@@ -2,16 +2,25 @@
 
 use crate::error::{ConfigError, Result};
 use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher as NotifyWatcher};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use std::time::Duration;
 use tokio::sync::mpsc;
-use tokio::time::sleep;
+use tokio::time::{Instant, sleep_until};
 
 /// Configuration watcher that monitors files for changes.
 ///
 /// Uses the `notify` crate to watch configuration files and trigger reloads
-/// when they change. Includes debouncing to avoid rapid reloads.
+/// when they change.
+///
+/// Files are watched through their parent directory, so changes are still seen
+/// after an editor or deployment tool replaces the file via rename, deletes and
+/// recreates it, or swaps a symlink to it (as Kubernetes ConfigMap mounts do).
+/// Events for other files in the directory are ignored, as are deletions (the
+/// reload happens once the file is recreated). Includes debouncing to avoid rapid reloads: changes within
+/// the debounce window are coalesced into one reload signal carrying every
+/// changed path.
 ///
 /// # Examples
 ///
@@ -24,16 +33,103 @@ use tokio::time::sleep;
 /// watcher.watch("/path/to/config.yaml").await?;
 ///
 /// // Listen for reload signals
-/// while let Some(()) = rx.recv().await {
-///     println!("Config file changed, reload triggered!");
+/// while let Some(paths) = rx.recv().await {
+///     println!("Config files changed, reload triggered: {:?}", paths);
 /// }
 /// # Ok(())
 /// # }
 /// ```
 pub struct ConfigWatcher {
-    watcher: Arc<tokio::sync::Mutex<RecommendedWatcher>>,
+    watcher: Arc<tokio::sync::Mutex<WatcherState>>,
     debounce_duration: Duration,
-    watched_paths: Arc<tokio::sync::Mutex<Vec<PathBuf>>>,
+    watched: Arc<RwLock<Vec<WatchedPath>>>,
+}
+
+/// The notify watcher and how many watched paths use each directory watch.
+struct WatcherState {
+    watcher: RecommendedWatcher,
+    directories: HashMap<PathBuf, usize>,
+}
+
+/// A path being watched.
+struct WatchedPath {
+    /// Canonical parent directory joined with the file name, as reported in events
+    path: PathBuf,
+    /// Whether the path is a directory whose entries are watched
+    is_dir: bool,
+    /// Resolved target of the path, to detect symlink swaps
+    target: Option<PathBuf>,
+    /// Whether the file may be absent, so deleting it is a change
+    optional: bool,
+}
+
+impl WatchedPath {
+    /// The directory that has to be watched to see changes to this path.
+    fn directory(&self) -> &Path {
+        if self.is_dir {
+            &self.path
+        } else {
+            self.path.parent().unwrap_or(&self.path)
+        }
+    }
+}
+
+/// Find the watched paths affected by an event, updating symlink targets.
+fn changed_paths(watched: &mut [WatchedPath], event_paths: &[PathBuf]) -> Vec<PathBuf> {
+    let mut changed = Vec::new();
+
+    for entry in watched.iter_mut() {
+        if entry.is_dir {
+            // Any entry added, changed, or removed in a watched directory
+            changed.extend(
+                event_paths
+                    .iter()
+                    .filter(|p| p.parent() == Some(entry.path.as_path()))
+                    .cloned(),
+            );
+            continue;
+        }
+
+        if !event_paths
+            .iter()
+            .any(|p| p.parent() == Some(entry.directory()))
+        {
+            continue;
+        }
+
+        let target = entry.path.canonicalize().ok();
+        let named = event_paths.contains(&entry.path);
+        let retargeted = target != entry.target;
+
+        // Skip deletions of required files; they'll be reloaded once recreated
+        if (target.is_some() || entry.optional) && (named || retargeted) {
+            changed.push(entry.path.clone());
+        }
+        entry.target = target;
+    }
+
+    changed
+}
+
+/// Resolve a path to its canonical parent directory joined with its file name.
+///
+/// Unlike `canonicalize`, this keeps a symlinked file's own name, and works for
+/// files that no longer exist as long as the directory does.
+fn resolve(path: &Path) -> Result<PathBuf> {
+    let resolve_error =
+        |e: std::io::Error| ConfigError::LoadError(format!("Failed to resolve path: {}", e));
+
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => {
+            let parent = if parent.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                parent
+            };
+            Ok(parent.canonicalize().map_err(resolve_error)?.join(name))
+        }
+        _ => path.canonicalize().map_err(resolve_error),
+    }
 }
 
 impl ConfigWatcher {
@@ -46,27 +142,34 @@ impl ConfigWatcher {
     /// # Returns
     ///
     /// Returns a tuple of (ConfigWatcher, receiver channel). The receiver will
-    /// receive a message whenever a reload should be triggered.
+    /// receive the changed paths whenever a reload should be triggered.
     ///
     /// # Errors
     ///
     /// Returns an error if the underlying file watcher cannot be created.
-    pub fn new(debounce_duration: Duration) -> Result<(Self, mpsc::Receiver<()>)> {
+    pub fn new(debounce_duration: Duration) -> Result<(Self, mpsc::Receiver<Vec<PathBuf>>)> {
         let (tx, rx) = mpsc::channel(100);
         let debounce = debounce_duration;
 
-        // Channel for raw events from notify
-        let (event_tx, mut event_rx) = mpsc::unbounded_channel::<Event>();
+        // Channel for watched paths changed by notify events
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel::<Vec<PathBuf>>();
 
         // Create the notify watcher
+        let watched: Arc<RwLock<Vec<WatchedPath>>> = Arc::new(RwLock::new(Vec::new()));
+        let filter = Arc::clone(&watched);
         let watcher = notify::recommended_watcher(move |res: notify::Result<Event>| {
             if let Ok(event) = res {
-                // Only care about write/modify events
+                // Only care about content and directory entry changes
                 if matches!(
                     event.kind,
-                    notify::EventKind::Modify(_) | notify::EventKind::Create(_)
+                    notify::EventKind::Modify(_)
+                        | notify::EventKind::Create(_)
+                        | notify::EventKind::Remove(_)
                 ) {
-                    let _ = event_tx.send(event);
+                    let changed = changed_paths(&mut filter.write().unwrap(), &event.paths);
+                    if !changed.is_empty() {
+                        let _ = event_tx.send(changed);
+                    }
                 }
             }
         })
@@ -74,36 +177,56 @@ impl ConfigWatcher {
 
         // Spawn a task to debounce events and trigger reloads
         tokio::spawn(async move {
-            let mut last_reload = tokio::time::Instant::now();
-
-            while let Some(_event) = event_rx.recv().await {
-                let now = tokio::time::Instant::now();
-                let elapsed = now.duration_since(last_reload);
-
-                if elapsed >= debounce {
-                    // Trigger reload
-                    if tx.send(()).await.is_err() {
-                        // Receiver dropped, exit
-                        break;
+            let mut last_reload = Instant::now() - debounce;
+            let mut pending: Vec<PathBuf> = Vec::new();
+            // Set while a delayed reload is scheduled
+            let mut deadline: Option<Instant> = None;
+
+            loop {
+                tokio::select! {
+                    changed = event_rx.recv() => {
+                        let Some(changed) = changed else { break };
+                        for path in changed {
+                            if !pending.contains(&path) {
+                                pending.push(path);
+                            }
+                        }
+
+                        if deadline.is_none() {
+                            if last_reload.elapsed() >= debounce {
+                                // Trigger reload
+                                if tx.send(std::mem::take(&mut pending)).await.is_err() {
+                                    // Receiver dropped, exit
+                                    break;
+                                }
+                                last_reload = Instant::now();
+                            } else {
+                                // Schedule a delayed reload for the rest of the window
+                                deadline = Some(last_reload + debounce);
+                            }
+                        }
+                    }
+                    _ = sleep_until(deadline.unwrap_or_else(Instant::now)), if deadline.is_some() => {
+                        deadline = None;
+                        if tx.send(std::mem::take(&mut pending)).await.is_err() {
+                            break;
+                        }
+                        last_reload = Instant::now();
                     }
-                    last_reload = now;
-                } else {
-                    // Schedule a delayed reload
-                    let remaining = debounce - elapsed;
-                    let tx_clone = tx.clone();
-                    tokio::spawn(async move {
-                        sleep(remaining).await;
-                        let _ = tx_clone.send(()).await;
-                    });
+                    // The handle's reload task stopped, e.g. on shutdown
+                    _ = tx.closed() => break,
                 }
             }
         });
 
         Ok((
             Self {
-                watcher: Arc::new(tokio::sync::Mutex::new(watcher)),
+                watcher: Arc::new(tokio::sync::Mutex::new(WatcherState {
+                    watcher,
+                    directories: HashMap::new(),
+                })),
                 debounce_duration,
-                watched_paths: Arc::new(tokio::sync::Mutex::new(Vec::new())),
+                watched,
             },
             rx,
         ))
@@ -111,6 +234,9 @@ impl ConfigWatcher {
 
     /// Add a path to watch for changes.
     ///
+    /// A file is watched through its parent directory; a directory is watched
+    /// for entries being added, changed, or removed.
+    ///
     /// # Arguments
     ///
     /// * `path` - Path to the file or directory to watch
@@ -119,23 +245,60 @@ impl ConfigWatcher {
     ///
     /// Returns an error if the path cannot be watched (e.g., doesn't exist).
     pub async fn watch(&self, path: impl AsRef<Path>) -> Result<()> {
-        let path = path.as_ref().to_path_buf();
-
-        // Canonicalize the path to get the absolute path
-        let canonical_path = path
+        let path = path.as_ref();
+        let target = path
             .canonicalize()
             .map_err(|e| ConfigError::LoadError(format!("Failed to resolve path: {}", e)))?;
+        self.add(path, Some(target), false).await
+    }
 
-        let mut watcher = self.watcher.lock().await;
-        watcher
-            .watch(&canonical_path, RecursiveMode::NonRecursive)
-            .map_err(|e| ConfigError::Other(format!("Failed to watch path: {}", e)))?;
+    /// Add a file that may not exist to watch for changes.
+    ///
+    /// Unlike [`watch`](Self::watch), creating the file later or deleting it
+    /// are both reported as changes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file's directory doesn't exist or cannot be watched.
+    pub async fn watch_optional(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        self.add(path, path.canonicalize().ok(), true).await
+    }
 
-        // Track watched paths
-        let mut paths = self.watched_paths.lock().await;
-        if !paths.contains(&canonical_path) {
-            paths.push(canonical_path);
+    async fn add(&self, path: &Path, target: Option<PathBuf>, optional: bool) -> Result<()> {
+        let is_dir = target.as_ref().is_some_and(|t| t.is_dir());
+        let entry = WatchedPath {
+            path: match &target {
+                Some(target) if is_dir => target.clone(),
+                _ => resolve(path)?,
+            },
+            is_dir,
+            target,
+            optional,
+        };
+
+        let mut state = self.watcher.lock().await;
+        if self
+            .watched
+            .read()
+            .unwrap()
+            .iter()
+            .any(|w| w.path == entry.path)
+        {
+            return Ok(());
+        }
+
+        let directory = entry.directory().to_path_buf();
+        if !state.directories.contains_key(&directory) {
+            state
+                .watcher
+                .watch(&directory, RecursiveMode::NonRecursive)
+                .map_err(|e| ConfigError::Other(format!("Failed to watch path: {}", e)))?;
         }
+        *state.directories.entry(directory).or_insert(0) += 1;
+
+        // Track watched paths
+        self.watched.write().unwrap().push(entry);
 
         Ok(())
     }
@@ -150,19 +313,31 @@ impl ConfigWatcher {
     ///
     /// Returns an error if the path cannot be unwatched.
     pub async fn unwatch(&self, path: impl AsRef<Path>) -> Result<()> {
-        let path = path.as_ref();
-        let canonical_path = path.canonicalize().map_err(|e| {
+        let resolved = resolve(path.as_ref()).map_err(|e| {
             ConfigError::LoadError(format!("Failed to resolve path for unwatching: {}", e))
         })?;
 
-        let mut watcher = self.watcher.lock().await;
-        watcher
-            .unwatch(&canonical_path)
-            .map_err(|e| ConfigError::Other(format!("Failed to unwatch path: {}", e)))?;
-
-        // Remove from tracked paths
-        let mut paths = self.watched_paths.lock().await;
-        paths.retain(|p| p != &canonical_path);
+        let mut state = self.watcher.lock().await;
+        let entry = {
+            let mut watched = self.watched.write().unwrap();
+            match watched.iter().position(|w| w.path == resolved) {
+                Some(index) => watched.remove(index),
+                None => return Ok(()),
+            }
+        };
+
+        // Drop the directory watch once nothing in it is watched
+        let directory = entry.directory().to_path_buf();
+        if let Some(count) = state.directories.get_mut(&directory) {
+            *count -= 1;
+            if *count == 0 {
+                state.directories.remove(&directory);
+                state
+                    .watcher
+                    .unwatch(&directory)
+                    .map_err(|e| ConfigError::Other(format!("Failed to unwatch path: {}", e)))?;
+            }
+        }
 
         Ok(())
     }
@@ -174,7 +349,12 @@ impl ConfigWatcher {
 
     /// Get a list of currently watched paths.
     pub async fn watched_paths(&self) -> Vec<PathBuf> {
-        self.watched_paths.lock().await.clone()
+        self.watched
+            .read()
+            .unwrap()
+            .iter()
+            .map(|w| w.path.clone())
+            .collect()
     }
 }
 
@@ -218,6 +398,8 @@ mod tests {
         let config_path = temp_dir.path().join("config.yaml");
         fs::write(&config_path, "port: 8080").unwrap();
 
+        let canonical = config_path.canonicalize().unwrap();
+
         let (watcher, mut rx) = ConfigWatcher::new(Duration::from_millis(100)).unwrap();
         watcher.watch(&config_path).await.unwrap();
 
@@ -229,8 +411,138 @@ mod tests {
 
         // Wait for reload signal with timeout
         let result = timeout(Duration::from_secs(2), rx.recv()).await;
-        assert!(result.is_ok());
-        assert!(result.unwrap().is_some());
+        let paths = result.unwrap().unwrap();
+        assert_eq!(paths, vec![canonical]);
+    }
+
+    #[tokio::test]
+    async fn test_changes_within_debounce_are_coalesced() {
+        let temp_dir = TempDir::new().unwrap();
+        let first = temp_dir.path().join("first.yaml");
+        let second = temp_dir.path().join("second.yaml");
+        fs::write(&first, "a: 1").unwrap();
+        fs::write(&second, "b: 1").unwrap();
+
+        let (watcher, mut rx) = ConfigWatcher::new(Duration::from_millis(300)).unwrap();
+        watcher.watch(&first).await.unwrap();
+        watcher.watch(&second).await.unwrap();
+
+        // The first change fires immediately
+        fs::write(&first, "a: 2").unwrap();
+        let paths = timeout(Duration::from_secs(2), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(paths.contains(&first.canonicalize().unwrap()));
+
+        // Changes inside the window arrive together once it closes
+        fs::write(&first, "a: 3").unwrap();
+        fs::write(&second, "b: 2").unwrap();
+        let mut paths = Vec::new();
+        while !paths.contains(&second.canonicalize().unwrap()) {
+            paths.extend(
+                timeout(Duration::from_secs(2), rx.recv())
+                    .await
+                    .unwrap()
+                    .unwrap(),
+            );
+        }
+        assert!(paths.contains(&first.canonicalize().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_atomic_replace_and_recreate() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.yaml");
+        fs::write(&config_path, "port: 8080").unwrap();
+        let canonical = config_path.canonicalize().unwrap();
+
+        let (watcher, mut rx) = ConfigWatcher::new(Duration::from_millis(10)).unwrap();
+        watcher.watch(&config_path).await.unwrap();
+
+        // Other files in the directory don't trigger reloads
+        fs::write(temp_dir.path().join("other.txt"), "ignored").unwrap();
+        assert!(
+            timeout(Duration::from_millis(200), rx.recv())
+                .await
+                .is_err()
+        );
+
+        // Replace via rename, as editors and atomic writers do
+        let tmp_path = temp_dir.path().join("config.yaml.tmp");
+        fs::write(&tmp_path, "port: 9090").unwrap();
+        fs::rename(&tmp_path, &config_path).unwrap();
+        let paths = timeout(Duration::from_secs(2), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(paths, vec![canonical.clone()]);
+
+        // Delete and recreate; still watched afterwards
+        fs::remove_file(&config_path).unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        fs::write(&config_path, "port: 7070").unwrap();
+        let paths = timeout(Duration::from_secs(2), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(paths, vec![canonical]);
+    }
+
+    #[tokio::test]
+    async fn test_optional_file_created_and_deleted() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("local.yaml");
+        let resolved = temp_dir.path().canonicalize().unwrap().join("local.yaml");
+
+        let (watcher, mut rx) = ConfigWatcher::new(Duration::from_millis(10)).unwrap();
+        assert!(watcher.watch(&config_path).await.is_err());
+        watcher.watch_optional(&config_path).await.unwrap();
+
+        fs::write(&config_path, "port: 9090").unwrap();
+        let paths = timeout(Duration::from_secs(2), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(paths, vec![resolved.clone()]);
+
+        // Unlike a required file, deleting it is a change
+        fs::remove_file(&config_path).unwrap();
+        let paths = timeout(Duration::from_secs(2), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(paths, vec![resolved]);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_symlink_swap() {
+        let temp_dir = TempDir::new().unwrap();
+        let v1 = temp_dir.path().join("v1.yaml");
+        let v2 = temp_dir.path().join("v2.yaml");
+        fs::write(&v1, "port: 8080").unwrap();
+        fs::write(&v2, "port: 9090").unwrap();
+
+        let config_path = temp_dir.path().join("config.yaml");
+        std::os::unix::fs::symlink(&v1, &config_path).unwrap();
+
+        let (watcher, mut rx) = ConfigWatcher::new(Duration::from_millis(10)).unwrap();
+        watcher.watch(&config_path).await.unwrap();
+
+        // Swap the link atomically
+        let tmp_link = temp_dir.path().join("config.yaml.tmp");
+        std::os::unix::fs::symlink(&v2, &tmp_link).unwrap();
+        fs::rename(&tmp_link, &config_path).unwrap();
+
+        let paths = timeout(Duration::from_secs(2), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            paths,
+            vec![temp_dir.path().canonicalize().unwrap().join("config.yaml")]
+        );
     }
 
     #[tokio::test]
@@ -0,0 +1,263 @@
+//! Secret values and redaction.
+//!
+//! Configuration often carries passwords and tokens that must never show up in
+//! logs, debug output, or admin endpoints. This module provides:
+//!
+//! - [`Secret<T>`]: a wrapper whose `Debug`/`Display` never print the value
+//! - [`redact`]: a `#[serde(with = ...)]` helper for plain fields
+//! - [`redacted`]: a scope in which secrets serialize as [`REDACTED`]
+//! - [`Redact`]: produces a redacted JSON view of any serializable config
+//!
+//! Secrets serialize normally outside a redaction scope, so features that
+//! round-trip the config through serde (such as partial updates) keep the real
+//! values.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use hotswap_config::secrets::Secret;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Serialize, Deserialize, Clone)]
+//! struct DatabaseConfig {
+//!     url: String,
+//!     password: Secret<String>,
+//!     #[serde(with = "hotswap_config::secrets::redact")]
+//!     api_key: String,
+//! }
+//!
+//! let db = DatabaseConfig {
+//!     url: "postgres://localhost/db".to_string(),
+//!     password: Secret::new("hunter2".to_string()),
+//!     api_key: "abc123".to_string(),
+//! };
+//!
+//! assert!(!format!("{:?}", db).contains("hunter2"));
+//! assert_eq!(db.password.expose(), "hunter2");
+//! ```
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cell::Cell;
+use std::fmt;
+
+/// Placeholder emitted in place of secret values.
+pub const REDACTED: &str = "[REDACTED]";
+
+thread_local! {
+    static REDACTING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Run `f` with redaction enabled on the current thread.
+///
+/// Any [`Secret`] or [`redact`]-annotated field serialized inside `f` is
+/// emitted as [`REDACTED`].
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(feature = "serde_json")]
+/// # {
+/// use hotswap_config::secrets::{Secret, redacted};
+///
+/// let token = Secret::new("abc".to_string());
+/// let json = redacted(|| serde_json::to_string(&token).unwrap());
+/// assert_eq!(json, "\"[REDACTED]\"");
+/// # }
+/// ```
+pub fn redacted<R>(f: impl FnOnce() -> R) -> R {
+    /// Restores the previous state even if `f` panics.
+    struct Reset(bool);
+
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            REDACTING.with(|r| r.set(self.0));
+        }
+    }
+
+    let _reset = Reset(REDACTING.with(|r| r.replace(true)));
+    f()
+}
+
+/// Check whether redaction is enabled on the current thread.
+pub fn is_redacting() -> bool {
+    REDACTING.with(|r| r.get())
+}
+
+/// A secret value that is never printed.
+///
+/// `Debug` and `Display` always print [`REDACTED`]. Serialization emits the real
+/// value unless inside a [`redacted`] scope. Deserialization is transparent, so
+/// `Secret<String>` can be used anywhere a `String` field was.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct Secret<T>(T);
+
+impl<T> Secret<T> {
+    /// Wrap a value as a secret.
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    /// Access the secret value.
+    pub fn expose(&self) -> &T {
+        &self.0
+    }
+
+    /// Consume the wrapper and return the secret value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for Secret<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T> fmt::Debug for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Secret({})", REDACTED)
+    }
+}
+
+impl<T> fmt::Display for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl<T: Serialize> Serialize for Secret<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        redact::serialize(&self.0, serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Secret<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Self)
+    }
+}
+
+/// Serde helper for redacting plain fields.
+///
+/// Use with `#[serde(with = "hotswap_config::secrets::redact")]` on fields that
+/// should be masked in redacted views without changing their type.
+pub mod redact {
+    use super::{REDACTED, is_redacting};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    /// Serialize the value, or [`REDACTED`] inside a redaction scope.
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize + ?Sized,
+        S: Serializer,
+    {
+        if is_redacting() {
+            serializer.serialize_str(REDACTED)
+        } else {
+            value.serialize(serializer)
+        }
+    }
+
+    /// Deserialize the value unchanged.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        T::deserialize(deserializer)
+    }
+}
+
+/// Produce a redacted JSON view of a value.
+///
+/// Implemented for every `Serialize` type: [`Secret`] and [`redact`]-annotated
+/// fields are replaced with [`REDACTED`].
+#[cfg(feature = "serde_json")]
+pub trait Redact {
+    /// Serialize to JSON with all secret fields masked.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails.
+    fn to_redacted_json(&self) -> crate::error::Result<serde_json::Value>;
+}
+
+#[cfg(feature = "serde_json")]
+impl<T: Serialize + ?Sized> Redact for T {
+    fn to_redacted_json(&self) -> crate::error::Result<serde_json::Value> {
+        redacted(|| serde_json::to_value(self)).map_err(|e| {
+            crate::error::ConfigError::Other(format!("Failed to serialize config: {}", e))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+    struct DatabaseConfig {
+        url: String,
+        password: Secret<String>,
+        #[serde(with = "redact")]
+        api_key: String,
+    }
+
+    fn database() -> DatabaseConfig {
+        DatabaseConfig {
+            url: "postgres://localhost/db".to_string(),
+            password: Secret::new("hunter2".to_string()),
+            api_key: "abc123".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_debug_and_display_hide_value() {
+        let secret = Secret::new("hunter2".to_string());
+        assert_eq!(format!("{:?}", secret), "Secret([REDACTED])");
+        assert_eq!(format!("{}", secret), REDACTED);
+        assert!(!format!("{:?}", database()).contains("hunter2"));
+    }
+
+    #[test]
+    fn test_expose_and_into_inner() {
+        let secret: Secret<String> = "hunter2".to_string().into();
+        assert_eq!(secret.expose(), "hunter2");
+        assert_eq!(secret.into_inner(), "hunter2");
+    }
+
+    #[test]
+    fn test_redacted_scope() {
+        assert!(!is_redacting());
+        redacted(|| assert!(is_redacting()));
+        assert!(!is_redacting());
+    }
+
+    #[test]
+    fn test_redacted_scope_resets_after_panic() {
+        let result = std::panic::catch_unwind(|| redacted(|| panic!("boom")));
+        assert!(result.is_err());
+        assert!(!is_redacting());
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn test_serialization_round_trip() {
+        let json = serde_json::to_value(database()).unwrap();
+        assert_eq!(json["password"], "hunter2");
+        assert_eq!(json["api_key"], "abc123");
+
+        let parsed: DatabaseConfig = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, database());
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn test_to_redacted_json() {
+        let json = database().to_redacted_json().unwrap();
+        assert_eq!(json["url"], "postgres://localhost/db");
+        assert_eq!(json["password"], REDACTED);
+        assert_eq!(json["api_key"], REDACTED);
+    }
+}
@@ -0,0 +1,260 @@
+//! Signature verification for configuration content.
+//!
+//! With a [`SignatureVerifier`] set via
+//! [`HotswapConfigBuilder::with_signature_verification`](crate::core::HotswapConfigBuilder::with_signature_verification),
+//! every source that can return signed content (see
+//! [`ConfigSource::load_signed`](crate::sources::ConfigSource::load_signed))
+//! must carry a detached ed25519 signature made by one of the trusted keys.
+//! Content with a missing or invalid signature is rejected, so a compromised
+//! config server or bucket can't inject settings:
+//!
+//! - files are signed by a sidecar file with `.sig` appended
+//!   (`config/default.yaml.sig`)
+//! - HTTP sources read the `X-Config-Signature` response header
+//! - object storage sources read the `.sig` object next to the config object
+//! - gRPC sources read the `signature` field of each pushed response
+//!
+//! Signatures are the 64-byte ed25519 signature of the exact content, either
+//! raw or base64 encoded. For example, with OpenSSL:
+//!
+//! ```text
+//! openssl genpkey -algorithm ed25519 -out signing-key.pem
+//! openssl pkey -in signing-key.pem -pubout -out signing-key.pub.pem
+//! openssl pkeyutl -sign -rawin -inkey signing-key.pem \
+//!     -in config/default.yaml -out config/default.yaml.sig
+//! ```
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use hotswap_config::prelude::*;
+//! use hotswap_config::signing::SignatureVerifier;
+//! use serde::Deserialize;
+//!
+//! #[derive(Debug, Deserialize, Clone)]
+//! struct AppConfig {
+//!     port: u16,
+//! }
+//!
+//! # async fn example() -> Result<()> {
+//! let verifier = SignatureVerifier::new()
+//!     .with_public_key(&std::fs::read_to_string("signing-key.pub.pem")?)?;
+//!
+//! let config = HotswapConfig::<AppConfig>::builder()
+//!     .with_file("config/default.yaml")
+//!     .with_signature_verification(verifier)
+//!     .build()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{ConfigError, Result};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use ed25519_dalek::{Signature, VerifyingKey};
+
+/// DER prefix of an ed25519 `SubjectPublicKeyInfo`, followed by the raw key.
+const SPKI_PREFIX: [u8; 12] = [
+    0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00,
+];
+
+/// Verifies detached ed25519 signatures against a set of trusted keys.
+///
+/// Content is accepted if its signature matches any of the keys, so a new key
+/// can be added before content is re-signed with it and the old key removed
+/// afterwards.
+#[derive(Debug, Clone, Default)]
+pub struct SignatureVerifier {
+    keys: Vec<VerifyingKey>,
+}
+
+impl SignatureVerifier {
+    /// Create a verifier with no trusted keys.
+    ///
+    /// A verifier without keys rejects everything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Trust a public key given as base64 or PEM.
+    ///
+    /// Accepts the raw 32-byte key in base64, or a `SubjectPublicKeyInfo` in
+    /// base64 or PEM (as written by `openssl pkey -pubout`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key can't be decoded or isn't a valid ed25519
+    /// public key.
+    pub fn with_public_key(self, key: &str) -> Result<Self> {
+        let encoded: String = key
+            .lines()
+            .filter(|line| !line.starts_with("-----"))
+            .flat_map(|line| line.chars().filter(|c| !c.is_whitespace()))
+            .collect();
+        let bytes = STANDARD
+            .decode(&encoded)
+            .map_err(|e| ConfigError::SignatureError(format!("Invalid public key: {}", e)))?;
+        let raw = match bytes.strip_prefix(&SPKI_PREFIX[..]) {
+            Some(raw) => raw,
+            None => &bytes[..],
+        };
+        let raw: [u8; 32] = raw.try_into().map_err(|_| {
+            ConfigError::SignatureError(format!(
+                "Invalid public key: expected 32 bytes, got {}",
+                raw.len()
+            ))
+        })?;
+        self.with_public_key_bytes(raw)
+    }
+
+    /// Trust a raw 32-byte public key.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes aren't a valid ed25519 public key.
+    pub fn with_public_key_bytes(mut self, key: [u8; 32]) -> Result<Self> {
+        let key = VerifyingKey::from_bytes(&key)
+            .map_err(|e| ConfigError::SignatureError(format!("Invalid public key: {}", e)))?;
+        self.keys.push(key);
+        Ok(self)
+    }
+
+    /// Get the number of trusted keys.
+    pub fn key_count(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Verify that `signature` is a valid signature of `content` by one of
+    /// the trusted keys.
+    ///
+    /// The signature may be raw (64 bytes) or base64 encoded.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigError::SignatureError`] if the signature is missing,
+    /// malformed, or doesn't match any trusted key.
+    pub fn verify(&self, content: &[u8], signature: Option<&[u8]>) -> Result<()> {
+        let signature = signature
+            .ok_or_else(|| ConfigError::SignatureError("missing signature".to_string()))?;
+        let signature = decode_signature(signature)?;
+        if self
+            .keys
+            .iter()
+            .any(|key| key.verify_strict(content, &signature).is_ok())
+        {
+            Ok(())
+        } else {
+            Err(ConfigError::SignatureError(
+                "signature does not match any trusted key".to_string(),
+            ))
+        }
+    }
+}
+
+/// Decode a raw or base64 signature.
+fn decode_signature(signature: &[u8]) -> Result<Signature> {
+    let bytes = if signature.len() == Signature::BYTE_SIZE {
+        signature.to_vec()
+    } else {
+        let text = std::str::from_utf8(signature)
+            .map_err(|_| ConfigError::SignatureError("malformed signature".to_string()))?;
+        STANDARD
+            .decode(text.trim())
+            .map_err(|e| ConfigError::SignatureError(format!("malformed signature: {}", e)))?
+    };
+    Signature::from_slice(&bytes)
+        .map_err(|e| ConfigError::SignatureError(format!("malformed signature: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    fn key(seed: u8) -> SigningKey {
+        SigningKey::from_bytes(&[seed; 32])
+    }
+
+    fn verifier(seed: u8) -> SignatureVerifier {
+        SignatureVerifier::new()
+            .with_public_key_bytes(key(seed).verifying_key().to_bytes())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_verify_raw_and_base64_signatures() {
+        let content = b"port: 8080\n";
+        let signature = key(7).sign(content).to_bytes();
+        let verifier = verifier(7);
+
+        assert!(verifier.verify(content, Some(&signature)).is_ok());
+        let encoded = format!("{}\n", STANDARD.encode(signature));
+        assert!(verifier.verify(content, Some(encoded.as_bytes())).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_tampered_missing_and_untrusted() {
+        let content = b"port: 8080\n";
+        let signature = key(7).sign(content).to_bytes();
+
+        let verifier = verifier(7);
+        assert!(matches!(
+            verifier.verify(b"port: 6666\n", Some(&signature)),
+            Err(ConfigError::SignatureError(_))
+        ));
+        assert!(matches!(
+            verifier.verify(content, None),
+            Err(ConfigError::SignatureError(_))
+        ));
+        assert!(verifier.verify(content, Some(b"garbage")).is_err());
+        assert!(
+            SignatureVerifier::new()
+                .verify(content, Some(&signature))
+                .is_err()
+        );
+        assert!(self::verifier(8).verify(content, Some(&signature)).is_err());
+    }
+
+    #[test]
+    fn test_any_trusted_key_is_accepted() {
+        let content = b"port: 8080\n";
+        let verifier = verifier(7)
+            .with_public_key_bytes(key(8).verifying_key().to_bytes())
+            .unwrap();
+        assert_eq!(verifier.key_count(), 2);
+
+        assert!(
+            verifier
+                .verify(content, Some(&key(7).sign(content).to_bytes()))
+                .is_ok()
+        );
+        assert!(
+            verifier
+                .verify(content, Some(&key(8).sign(content).to_bytes()))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_public_key_formats() {
+        let raw = key(7).verifying_key().to_bytes();
+        let mut spki = SPKI_PREFIX.to_vec();
+        spki.extend_from_slice(&raw);
+        let pem = format!(
+            "-----BEGIN PUBLIC KEY-----\n{}\n-----END PUBLIC KEY-----\n",
+            STANDARD.encode(&spki)
+        );
+
+        for encoded in [STANDARD.encode(raw), STANDARD.encode(&spki), pem] {
+            let verifier = SignatureVerifier::new().with_public_key(&encoded).unwrap();
+            assert_eq!(verifier.keys[0].to_bytes(), raw);
+        }
+        assert!(
+            SignatureVerifier::new()
+                .with_public_key("not base64!")
+                .is_err()
+        );
+        assert!(SignatureVerifier::new().with_public_key("AAAA").is_err());
+    }
+}
@@ -0,0 +1,284 @@
+//! Command-line argument configuration source.
+
+use super::ConfigSource;
+use crate::error::{ConfigError, Result};
+use config::{Value, ValueKind};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Configuration source for command-line flags, at the highest priority.
+///
+/// Flags like `--server.port=8080` (or `--server.port 8080`) set the key of
+/// the same dotted path, so the usual precedence becomes defaults, files,
+/// environment variables, then command-line flags. The source sits at
+/// priority 400, above environment variables (300).
+///
+/// Arguments are captured when the source is created; a reload re-merges the
+/// same flags.
+///
+/// # Examples
+///
+/// ```rust
+/// use hotswap_config::sources::{CliSource, ConfigSource};
+///
+/// let source = CliSource::from_args(["my-app", "--server.port=9090", "--debug"]);
+/// let values = source.load().unwrap();
+///
+/// let server = values["server"].clone().into_table().unwrap();
+/// assert_eq!(server["port"].clone().into_int().unwrap(), 9090);
+/// assert!(values["debug"].clone().into_bool().unwrap());
+/// ```
+pub struct CliSource {
+    values: std::result::Result<HashMap<String, Value>, String>,
+    priority: i32,
+}
+
+impl CliSource {
+    /// Read flags from the arguments the process was started with.
+    pub fn from_env() -> Self {
+        Self::from_args(std::env::args())
+    }
+
+    /// Read flags from `args`, where the first item is the program name.
+    ///
+    /// Each `--key=value` or `--key value` sets `key`; a `--key` followed by
+    /// another flag (or nothing) sets it to `true`; use `--key=-1` for values
+    /// starting with `-`. Positional arguments and everything after `--` are
+    /// ignored.
+    pub fn from_args<I, S>(args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut flags = Vec::new();
+        let mut args = args.into_iter().skip(1).map(Into::into).peekable();
+        while let Some(arg) = args.next() {
+            if arg == "--" {
+                break;
+            }
+            let Some(flag) = arg.strip_prefix("--").filter(|flag| !flag.is_empty()) else {
+                continue;
+            };
+            let (key, value) = match flag.split_once('=') {
+                Some((key, value)) => (key.to_string(), value.to_string()),
+                None => {
+                    let value = args.next_if(|next| !next.starts_with('-'));
+                    (
+                        flag.to_string(),
+                        value.unwrap_or_else(|| "true".to_string()),
+                    )
+                }
+            };
+            flags.push((key, vec![value]));
+        }
+        Self::from_flags(flags)
+    }
+
+    /// Read flags from `clap` matches.
+    ///
+    /// Every argument given on the command line sets the key named by its ID
+    /// (e.g. `#[arg(id = "server.port", long = "server.port")]`); arguments
+    /// with several values set an array. Arguments left at their clap
+    /// defaults are skipped, so they don't override files or environment
+    /// variables.
+    pub fn from_matches(matches: &clap::ArgMatches) -> Self {
+        let flags = matches
+            .ids()
+            .filter(|id| {
+                matches.value_source(id.as_str()) == Some(clap::parser::ValueSource::CommandLine)
+            })
+            .filter_map(|id| {
+                let values = matches.try_get_raw(id.as_str()).ok().flatten()?;
+                let values = values
+                    .map(|value| value.to_string_lossy().into_owned())
+                    .collect();
+                Some((id.to_string(), values))
+            })
+            .collect();
+        Self::from_flags(flags)
+    }
+
+    /// Use a parsed command-line struct (e.g. a `clap` derive) as flags.
+    ///
+    /// The value must serialize to a map. Fields that serialize to nothing,
+    /// such as `None` options, are skipped so they don't override lower
+    /// priority sources.
+    pub fn from_parsed<A: Serialize>(args: &A) -> Self {
+        let values = config::Config::try_from(args)
+            .and_then(|config| config.try_deserialize::<HashMap<String, Value>>())
+            .map(|mut values| {
+                for key in remove_unset(values.iter_mut()) {
+                    values.remove(&key);
+                }
+                values
+            })
+            .map_err(|e| e.to_string());
+
+        Self {
+            values,
+            priority: 400, // Above environment variables
+        }
+    }
+
+    /// Build the source from flag names and their values.
+    fn from_flags(flags: Vec<(String, Vec<String>)>) -> Self {
+        let values = flags
+            .into_iter()
+            .try_fold(
+                config::Config::builder(),
+                |builder, (key, mut values)| match values.len() {
+                    1 => builder.set_override(key, values.remove(0)),
+                    _ => builder.set_override(key, values),
+                },
+            )
+            .and_then(|builder| builder.build())
+            .and_then(|config| config.try_deserialize::<HashMap<String, Value>>())
+            .map_err(|e| e.to_string());
+
+        Self {
+            values,
+            priority: 400, // Above environment variables
+        }
+    }
+
+    /// Set the priority for this source.
+    ///
+    /// Higher priority sources override lower priority ones.
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+}
+
+/// Remove nil values, and tables left empty, below the top level; returns
+/// the top-level keys to remove.
+fn remove_unset<'a>(values: impl IntoIterator<Item = (&'a String, &'a mut Value)>) -> Vec<String> {
+    let mut unset = Vec::new();
+    for (key, value) in values {
+        match &mut value.kind {
+            ValueKind::Nil => unset.push(key.clone()),
+            ValueKind::Table(table) => {
+                for nested in remove_unset(table.iter_mut()) {
+                    table.remove(&nested);
+                }
+                if table.is_empty() {
+                    unset.push(key.clone());
+                }
+            }
+            _ => {}
+        }
+    }
+    unset
+}
+
+impl ConfigSource for CliSource {
+    fn load(&self) -> Result<HashMap<String, Value>> {
+        self.values.clone().map_err(|e| {
+            ConfigError::LoadError(format!("Failed to read command-line flags: {}", e))
+        })
+    }
+
+    fn name(&self) -> String {
+        "cli".to_string()
+    }
+
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(value: &Value) -> config::Map<String, Value> {
+        value.clone().into_table().unwrap()
+    }
+
+    #[test]
+    fn test_flag_forms() {
+        let source = CliSource::from_args([
+            "my-app",
+            "serve",
+            "--server.port=9090",
+            "--server.host",
+            "0.0.0.0",
+            "--verbose",
+            "--debug",
+            "--",
+            "--ignored=1",
+        ]);
+        let values = source.load().unwrap();
+
+        let server = table(&values["server"]);
+        assert_eq!(server["port"].clone().into_int().unwrap(), 9090);
+        assert_eq!(server["host"].clone().into_string().unwrap(), "0.0.0.0");
+        assert!(values["verbose"].clone().into_bool().unwrap());
+        assert!(values["debug"].clone().into_bool().unwrap());
+        assert!(!values.contains_key("ignored"));
+        assert!(!values.contains_key("serve"));
+        assert_eq!(source.priority(), 400);
+    }
+
+    #[test]
+    fn test_from_matches_skips_defaults() {
+        use clap::{Arg, ArgAction, Command};
+
+        let command = Command::new("my-app")
+            .arg(Arg::new("server.port").long("server.port"))
+            .arg(
+                Arg::new("server.host")
+                    .long("server.host")
+                    .default_value("localhost"),
+            )
+            .arg(Arg::new("tags").long("tag").action(ArgAction::Append));
+        let matches = command
+            .try_get_matches_from([
+                "my-app",
+                "--server.port",
+                "9090",
+                "--tag",
+                "a",
+                "--tag",
+                "b",
+            ])
+            .unwrap();
+        let values = CliSource::from_matches(&matches).load().unwrap();
+
+        let server = table(&values["server"]);
+        assert_eq!(server["port"].clone().into_int().unwrap(), 9090);
+        assert!(!server.contains_key("host"));
+        let tags = values["tags"].clone().into_array().unwrap();
+        assert_eq!(tags.len(), 2);
+    }
+
+    #[test]
+    fn test_from_parsed_skips_unset_options() {
+        #[derive(Serialize)]
+        struct Server {
+            port: Option<u16>,
+            host: Option<String>,
+        }
+
+        #[derive(Serialize)]
+        struct Args {
+            server: Server,
+            log_level: Option<String>,
+        }
+
+        let values = CliSource::from_parsed(&Args {
+            server: Server {
+                port: Some(9090),
+                host: None,
+            },
+            log_level: None,
+        })
+        .load()
+        .unwrap();
+
+        let server = table(&values["server"]);
+        assert_eq!(server["port"].clone().into_int().unwrap(), 9090);
+        assert!(!server.contains_key("host"));
+        assert!(!values.contains_key("log_level"));
+    }
+}
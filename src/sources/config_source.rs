@@ -2,6 +2,199 @@
 
 use crate::error::Result;
 use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+#[cfg(feature = "tokio")]
+use crate::error::ConfigError;
+#[cfg(feature = "tokio")]
+use tokio::sync::{mpsc, oneshot};
+
+/// Raw content loaded by a source, with its detached signature.
+///
+/// Returned by [`ConfigSource::load_signed`] so the loader can verify the
+/// exact bytes a source read before accepting the values parsed from them.
+#[derive(Debug, Clone)]
+pub struct SignedContent {
+    /// The bytes the signature covers, exactly as read
+    pub content: Vec<u8>,
+    /// The detached signature, raw or base64, if the source found one
+    pub signature: Option<Vec<u8>>,
+    /// The values parsed from `content`
+    pub values: HashMap<String, config::Value>,
+}
+
+/// What the loader does when a source fails to load.
+///
+/// Returned by [`ConfigSource::failure_policy`]. Sources that are skipped or
+/// served from cache are reported by
+/// [`HotswapConfig::skipped_sources`](crate::core::HotswapConfig::skipped_sources).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FailurePolicy {
+    /// Fail the whole load
+    #[default]
+    Required,
+    /// Skip the source, merging the rest without it
+    Optional,
+    /// Use the values from the source's last successful load, failing if it
+    /// hasn't loaded before
+    FallbackToCache,
+}
+
+/// Change notifications pushed by a source that watches for changes itself.
+///
+/// Returned by [`ConfigSource::watch`]. The source keeps the
+/// [`ChangeNotifier`] from [`ChangeStream::channel`] and signals through it
+/// from its own task; the builder drives every source's stream from one
+/// reload loop, re-loading only the source that changed. A burst of change
+/// signals is collapsed into a single reload.
+///
+/// # Examples
+///
+/// ```rust
+/// use hotswap_config::sources::ChangeStream;
+///
+/// # async fn example() {
+/// let (notifier, mut changes) = ChangeStream::channel();
+/// notifier.changed();
+/// notifier.changed();
+/// assert!(changes.next().await.unwrap().is_ok());
+/// # }
+/// ```
+#[cfg(feature = "tokio")]
+pub struct ChangeStream {
+    rx: mpsc::UnboundedReceiver<Event>,
+    /// An error drained while collapsing a burst, returned next
+    pending: Option<ConfigError>,
+    /// Notifiers waiting for the result of the change returned last
+    waiting: Vec<oneshot::Sender<Result<()>>>,
+}
+
+/// A message from a [`ChangeNotifier`].
+#[cfg(feature = "tokio")]
+enum Event {
+    /// The content changed, with a sender for the reload's result if the
+    /// notifier waits for it
+    Changed(Option<oneshot::Sender<Result<()>>>),
+    /// Watching failed
+    Failed(ConfigError),
+}
+
+#[cfg(feature = "tokio")]
+impl ChangeStream {
+    /// Create a connected notifier and stream.
+    pub fn channel() -> (ChangeNotifier, ChangeStream) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let changes = ChangeStream {
+            rx,
+            pending: None,
+            waiting: Vec::new(),
+        };
+        (ChangeNotifier { tx }, changes)
+    }
+
+    /// Wait for the next change (`Ok`) or watch failure (`Err`).
+    ///
+    /// Returns `None` once every notifier has been dropped. Notifiers still
+    /// waiting on the previous change are told it wasn't reloaded.
+    pub async fn next(&mut self) -> Option<Result<()>> {
+        self.waiting.clear();
+        if let Some(error) = self.pending.take() {
+            return Some(Err(error));
+        }
+        match self.rx.recv().await? {
+            Event::Changed(waiting) => {
+                self.waiting.extend(waiting);
+                // Collapse changes that are already queued into this one
+                while let Ok(next) = self.rx.try_recv() {
+                    match next {
+                        Event::Changed(waiting) => self.waiting.extend(waiting),
+                        Event::Failed(error) => {
+                            self.pending = Some(error);
+                            break;
+                        }
+                    }
+                }
+                Some(Ok(()))
+            }
+            Event::Failed(error) => Some(Err(error)),
+        }
+    }
+
+    /// Send the result of reloading for the change returned last to the
+    /// notifiers waiting for it.
+    pub(crate) fn reloaded(&mut self, result: &Result<()>) {
+        for waiting in self.waiting.drain(..) {
+            let result = match result {
+                Ok(()) => Ok(()),
+                Err(e) => Err(e.duplicate()),
+            };
+            let _ = waiting.send(result);
+        }
+    }
+}
+
+/// Sending half of a [`ChangeStream`].
+#[cfg(feature = "tokio")]
+#[derive(Clone)]
+pub struct ChangeNotifier {
+    tx: mpsc::UnboundedSender<Event>,
+}
+
+#[cfg(feature = "tokio")]
+impl ChangeNotifier {
+    /// Signal that the source's content changed.
+    ///
+    /// Returns `false` once the stream has been dropped, so the watching
+    /// task can stop.
+    pub fn changed(&self) -> bool {
+        self.tx.send(Event::Changed(None)).is_ok()
+    }
+
+    /// Signal that the source's content changed, and wait for the reload.
+    ///
+    /// Returns the reload's result, e.g. to acknowledge a pushed version to
+    /// the server or reject it with the error, which also goes to the
+    /// [reload error handler](crate::core::HotswapConfigBuilder::with_reload_error_handler).
+    /// Returns `None` if the stream was dropped without reloading, so the
+    /// watching task can stop.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hotswap_config::sources::ChangeStream;
+    ///
+    /// # async fn example() {
+    /// let (notifier, changes) = ChangeStream::channel();
+    /// drop(changes);
+    /// assert!(notifier.changed_and_wait().await.is_none());
+    /// # }
+    /// ```
+    pub async fn changed_and_wait(&self) -> Option<Result<()>> {
+        let (tx, rx) = oneshot::channel();
+        self.tx.send(Event::Changed(Some(tx))).ok()?;
+        rx.await.ok()
+    }
+
+    /// Report that watching failed, e.g. a lost connection.
+    ///
+    /// The error goes to the
+    /// [reload error handler](crate::core::HotswapConfigBuilder::with_reload_error_handler).
+    /// Returns `false` once the stream has been dropped.
+    pub fn error(&self, error: ConfigError) -> bool {
+        self.tx.send(Event::Failed(error)).is_ok()
+    }
+
+    /// Check whether the stream has been dropped.
+    pub fn is_closed(&self) -> bool {
+        self.tx.is_closed()
+    }
+
+    /// Wait until the stream has been dropped.
+    pub async fn closed(&self) {
+        self.tx.closed().await
+    }
+}
 
 /// Trait for configuration sources.
 ///
@@ -27,11 +220,89 @@ pub trait ConfigSource: Send + Sync {
     /// Get the priority of this source (higher = takes precedence).
     ///
     /// Default priorities:
+    /// - Command-line flags: 400
     /// - Environment variables: 300
     /// - Environment-specific file: 200
     /// - Default file: 100
     /// - Remote sources: 50
+    /// - Defaults: 0
     fn priority(&self) -> i32 {
         100
     }
+
+    /// Get the files this source reads from.
+    ///
+    /// When the file watcher reports changes, only sources whose paths changed
+    /// are re-loaded; the rest are re-merged from the loader's cache. Sources
+    /// that don't read files return an empty list (the default) and are only
+    /// re-loaded by a full [`reload`](crate::core::HotswapConfig::reload).
+    fn paths(&self) -> Vec<PathBuf> {
+        Vec::new()
+    }
+
+    /// Load configuration along with the raw content and its signature.
+    ///
+    /// Called instead of [`load`](Self::load) when signature verification is
+    /// enabled (feature: `signing`). Sources whose content can be signed, such
+    /// as files and remote documents, return the content they read and its
+    /// detached signature; a missing signature then fails verification.
+    /// Sources that return `None` (the default) are loaded with `load` and
+    /// not verified, so local sources like environment variables keep working.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the source cannot be loaded or parsed.
+    fn load_signed(&self) -> Result<Option<SignedContent>> {
+        Ok(None)
+    }
+
+    /// Get what the loader does when this source fails to load.
+    ///
+    /// Defaults to [`FailurePolicy::Required`], so a failing source fails the
+    /// whole load.
+    fn failure_policy(&self) -> FailurePolicy {
+        FailurePolicy::Required
+    }
+
+    /// Get the version of the content this source last loaded, if it has one.
+    ///
+    /// Remote sources return the version their server assigned it, such as
+    /// an `ETag`, so instances that fetched the same content agree on its
+    /// version and can be told to
+    /// [activate](crate::core::HotswapConfig::activate) it together. Sources
+    /// that return `None` (the default) are versioned by their content.
+    fn content_version(&self) -> Option<String> {
+        None
+    }
+
+    /// Get when the content this source last loaded takes effect, if that's
+    /// later than when it was loaded.
+    ///
+    /// Sources whose content carries an activation time, such as a change
+    /// scheduled for midnight across a fleet, return it here. While it is in
+    /// the future, reloads validate the new config but hold it, and the
+    /// handle swaps it in at that instant, so every instance that loaded it
+    /// changes together (see
+    /// [`HotswapConfig::scheduled_at`](crate::core::HotswapConfig::scheduled_at)).
+    /// The initial build uses the content right away, as there is no config
+    /// to keep until then. Sources that return `None` (the default) take
+    /// effect as soon as they are loaded.
+    fn effective_at(&self) -> Option<SystemTime> {
+        None
+    }
+
+    /// Start watching for changes, if this source supports native change
+    /// notification.
+    ///
+    /// Called once by [`HotswapConfigBuilder::build`](crate::core::HotswapConfigBuilder::build)
+    /// after the initial load, from within the Tokio runtime, so the source
+    /// can spawn a task that signals through a [`ChangeNotifier`]. Each
+    /// change re-loads this source and re-merges the others from their last
+    /// values. Sources that return `None` (the default) are only re-loaded by
+    /// a full reload or a change in one of their [`paths`](Self::paths)
+    /// picked up by the builder's file watcher.
+    #[cfg(feature = "tokio")]
+    fn watch(&self) -> Option<ChangeStream> {
+        None
+    }
 }
@@ -0,0 +1,123 @@
+//! Defaults configuration source built from a Rust value.
+
+use super::ConfigSource;
+use crate::error::{ConfigError, Result};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Configuration source that provides defaults from a serializable value.
+///
+/// The value is serialized once when the source is created and sits at the
+/// lowest priority (0), so every other source overrides it key by key. This
+/// gives complete type-level defaults (typically from `T::default()`) without
+/// `#[serde(default)]` on every field or a separate defaults file.
+///
+/// # Examples
+///
+/// ```rust
+/// use hotswap_config::sources::{ConfigSource, DefaultsSource};
+/// use serde::Serialize;
+///
+/// #[derive(Serialize, Default)]
+/// struct AppConfig {
+///     port: u16,
+///     host: String,
+/// }
+///
+/// let source = DefaultsSource::new(&AppConfig::default());
+/// assert_eq!(source.priority(), 0);
+/// ```
+pub struct DefaultsSource {
+    values: std::result::Result<HashMap<String, config::Value>, String>,
+    priority: i32,
+}
+
+impl DefaultsSource {
+    /// Create a defaults source from a serializable value.
+    ///
+    /// The value must serialize to a map (a struct or map type). Serialization
+    /// errors are reported when the source is loaded.
+    pub fn new<D: Serialize>(defaults: &D) -> Self {
+        let values = config::Config::try_from(defaults)
+            .and_then(|config| config.try_deserialize::<HashMap<String, config::Value>>())
+            .map_err(|e| e.to_string());
+
+        Self {
+            values,
+            priority: 0, // Below every other source
+        }
+    }
+
+    /// Set the priority for this source.
+    ///
+    /// Higher priority sources override lower priority ones.
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+}
+
+impl ConfigSource for DefaultsSource {
+    fn load(&self) -> Result<HashMap<String, config::Value>> {
+        self.values
+            .clone()
+            .map_err(|e| ConfigError::LoadError(format!("Failed to serialize defaults: {}", e)))
+    }
+
+    fn name(&self) -> String {
+        "defaults".to_string()
+    }
+
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Server {
+        port: u16,
+        host: String,
+    }
+
+    #[derive(Serialize)]
+    struct Defaults {
+        server: Server,
+        debug: bool,
+    }
+
+    #[test]
+    fn test_load_nested_defaults() {
+        let source = DefaultsSource::new(&Defaults {
+            server: Server {
+                port: 8080,
+                host: "localhost".to_string(),
+            },
+            debug: false,
+        });
+
+        let map = source.load().unwrap();
+        assert!(!map["debug"].clone().into_bool().unwrap());
+
+        let server = map["server"].clone().into_table().unwrap();
+        assert_eq!(server["port"].clone().into_int().unwrap(), 8080);
+        assert_eq!(server["host"].clone().into_string().unwrap(), "localhost");
+    }
+
+    #[test]
+    fn test_non_map_defaults_fail_on_load() {
+        let source = DefaultsSource::new(&42u32);
+        assert!(source.load().is_err());
+    }
+
+    #[test]
+    fn test_priority() {
+        let source = DefaultsSource::new(&HashMap::<String, String>::new());
+        assert_eq!(source.priority(), 0);
+        assert_eq!(source.with_priority(5).priority(), 5);
+        assert_eq!(DefaultsSource::new(&()).name(), "defaults");
+    }
+}
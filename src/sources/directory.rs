@@ -0,0 +1,297 @@
+//! Directory and glob configuration sources (`conf.d` style).
+
+use super::ConfigSource;
+use super::file::{FileSource, is_supported};
+use crate::error::{ConfigError, Result};
+#[cfg(feature = "templating")]
+use crate::templating::TemplateContext;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Loads every matching file in a directory as one source.
+///
+/// Files are merged in lexical order of their names, so each file overrides the
+/// ones before it (`10-base.yaml` < `20-region.yaml` < `99-local.yaml`). The
+/// directory is re-listed on every load, so fragments added or removed since
+/// the last load are picked up on the next reload. Hidden files and files with
+/// unsupported extensions are skipped.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use hotswap_config::sources::DirectorySource;
+///
+/// // All supported files in config/conf.d
+/// let source = DirectorySource::new("config/conf.d");
+///
+/// // Only the YAML fragments
+/// let source = DirectorySource::glob("config/conf.d/*.yaml");
+/// ```
+pub struct DirectorySource {
+    directory: PathBuf,
+    pattern: Option<String>,
+    priority: i32,
+    #[cfg(feature = "templating")]
+    template: Option<TemplateContext>,
+}
+
+impl DirectorySource {
+    /// Load all files with a supported extension in `directory`.
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+            pattern: None,
+            priority: 100,
+            #[cfg(feature = "templating")]
+            template: None,
+        }
+    }
+
+    /// Load all files matching a glob pattern.
+    ///
+    /// Only the file name may contain wildcards: `*` matches any run of
+    /// characters and `?` matches a single character (e.g.
+    /// `config/conf.d/*.yaml`). Loading fails if the directory part contains
+    /// wildcards.
+    pub fn glob(pattern: impl AsRef<Path>) -> Self {
+        let pattern = pattern.as_ref();
+        let directory = match pattern.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+
+        Self {
+            directory,
+            pattern: Some(
+                pattern
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+            ),
+            priority: 100,
+            #[cfg(feature = "templating")]
+            template: None,
+        }
+    }
+
+    /// Set the priority for this source.
+    ///
+    /// Higher priority sources override lower priority ones.
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Render every file as a Tera template with `context` before parsing it.
+    ///
+    /// See [`templating`](crate::templating) for what templates can use.
+    #[cfg(feature = "templating")]
+    pub fn with_template(mut self, context: TemplateContext) -> Self {
+        self.template = Some(context);
+        self
+    }
+
+    /// Get the directory this source reads from.
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// List the matching files in lexical order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory can't be read or the pattern has
+    /// wildcards outside the file name.
+    pub fn files(&self) -> Result<Vec<PathBuf>> {
+        if self.directory.to_string_lossy().contains(['*', '?']) {
+            return Err(ConfigError::LoadError(format!(
+                "Wildcards are only supported in the file name: {}",
+                self.directory.display()
+            )));
+        }
+
+        let entries = std::fs::read_dir(&self.directory).map_err(|e| {
+            ConfigError::LoadError(format!(
+                "Failed to read configuration directory {}: {}",
+                self.directory.display(),
+                e
+            ))
+        })?;
+
+        let mut files = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+
+            // Skip editor swap files, Kubernetes `..data` links, and the like
+            if name.starts_with('.') || !path.is_file() {
+                continue;
+            }
+
+            let selected = match &self.pattern {
+                Some(pattern) => wildcard_match(pattern, name),
+                None => is_supported(&path),
+            };
+            if selected {
+                files.push(path);
+            }
+        }
+
+        files.sort();
+        Ok(files)
+    }
+}
+
+impl ConfigSource for DirectorySource {
+    fn load(&self) -> Result<HashMap<String, config::Value>> {
+        let mut merged = config::Map::new();
+        for path in self.files()? {
+            let source = FileSource::new(path);
+            #[cfg(feature = "templating")]
+            let source = match &self.template {
+                Some(context) => source.with_template(context.clone()),
+                None => source,
+            };
+            for (key, value) in source.load()? {
+                crate::core::merge_value(&mut merged, key, value);
+            }
+        }
+
+        Ok(merged)
+    }
+
+    fn name(&self) -> String {
+        match &self.pattern {
+            Some(pattern) => format!("glob:{}", self.directory.join(pattern).display()),
+            None => format!("directory:{}", self.directory.display()),
+        }
+    }
+
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    fn paths(&self) -> Vec<PathBuf> {
+        vec![self.directory.clone()]
+    }
+}
+
+/// Match a file name against a pattern with `*` and `?` wildcards.
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    // Greedy matching with backtracking to the most recent `*`
+    let (mut p, mut n) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                Some((star_p, star_n)) => {
+                    p = star_p + 1;
+                    n = star_n + 1;
+                    star = Some((star_p, star_n + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("*.yaml", "10-base.yaml"));
+        assert!(wildcard_match("??-*.yaml", "10-base.yaml"));
+        assert!(wildcard_match("*", "anything"));
+        assert!(wildcard_match("a*b*c", "aXbYbZc"));
+        assert!(!wildcard_match("*.yaml", "base.toml"));
+        assert!(!wildcard_match("?.yaml", "10.yaml"));
+    }
+
+    #[test]
+    fn test_files_in_lexical_order() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("20-b.yaml"), "a: 1").unwrap();
+        fs::write(temp_dir.path().join("10-a.yaml"), "a: 1").unwrap();
+        fs::write(temp_dir.path().join("README.md"), "docs").unwrap();
+        fs::write(temp_dir.path().join(".10-a.yaml.swp"), "").unwrap();
+        fs::create_dir(temp_dir.path().join("nested.yaml")).unwrap();
+
+        let names: Vec<_> = DirectorySource::new(temp_dir.path())
+            .files()
+            .unwrap()
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, vec!["10-a.yaml", "20-b.yaml"]);
+    }
+
+    #[test]
+    fn test_later_files_override_earlier() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("10-base.yaml"),
+            "server:\n  port: 8080\n  host: localhost\n",
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("20-override.yaml"),
+            "server:\n  port: 9090\n",
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("30-ignored.toml"),
+            "[server]\nport = 1\n",
+        )
+        .unwrap();
+
+        let map = DirectorySource::glob(temp_dir.path().join("*.yaml"))
+            .load()
+            .unwrap();
+        let server = map["server"].clone().into_table().unwrap();
+        assert_eq!(server["port"].clone().into_int().unwrap(), 9090);
+        assert_eq!(server["host"].clone().into_string().unwrap(), "localhost");
+    }
+
+    #[test]
+    fn test_empty_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        let map = DirectorySource::new(temp_dir.path()).load().unwrap();
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn test_missing_directory_or_wildcard_directory() {
+        assert!(DirectorySource::new("/nonexistent/conf.d").load().is_err());
+        assert!(DirectorySource::glob("config/*/app.yaml").load().is_err());
+    }
+
+    #[test]
+    fn test_name_and_paths() {
+        let source = DirectorySource::glob("conf.d/*.yaml").with_priority(150);
+        assert_eq!(source.name(), "glob:conf.d/*.yaml");
+        assert_eq!(source.paths(), vec![PathBuf::from("conf.d")]);
+        assert_eq!(source.priority(), 150);
+
+        let source = DirectorySource::new("conf.d");
+        assert_eq!(source.name(), "directory:conf.d");
+    }
+}
@@ -0,0 +1,170 @@
+//! Dotenv (`.env`) file configuration source.
+
+use super::ConfigSource;
+use super::env::{EnvParsing, vars_to_map};
+use crate::error::{ConfigError, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Dotenv (`.env`) file configuration source.
+///
+/// Loads `KEY=value` pairs from a `.env` file and maps them to nested keys
+/// using the same prefix/separator rules as [`EnvSource`](super::EnvSource).
+/// The process environment is not modified.
+///
+/// By default, dotenv values sit just below real environment variables
+/// (priority 290), so an exported variable still wins over the file.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use hotswap_config::sources::DotenvSource;
+///
+/// // APP__SERVER__PORT=8080 in .env -> server.port = 8080
+/// let source = DotenvSource::new(".env").with_prefix("APP", "__");
+/// ```
+pub struct DotenvSource {
+    path: PathBuf,
+    prefix: Option<String>,
+    separator: String,
+    priority: i32,
+}
+
+impl DotenvSource {
+    /// Create a new dotenv source.
+    ///
+    /// Without a prefix, all variables in the file are loaded and `__`
+    /// separates nested keys.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            prefix: None,
+            separator: "__".to_string(),
+            priority: 290, // Just below real environment variables
+        }
+    }
+
+    /// Only load variables with the given prefix, splitting nested keys on `separator`.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Prefix for variables (e.g., "APP")
+    /// * `separator` - Separator for nested keys (e.g., "__" for APP__DB__HOST)
+    pub fn with_prefix(mut self, prefix: impl Into<String>, separator: impl Into<String>) -> Self {
+        self.prefix = Some(prefix.into());
+        self.separator = separator.into();
+        self
+    }
+
+    /// Set the priority for this source.
+    ///
+    /// Higher priority sources override lower priority ones.
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Get the path of the dotenv file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl ConfigSource for DotenvSource {
+    fn load(&self) -> Result<HashMap<String, config::Value>> {
+        let iter = dotenvy::from_path_iter(&self.path).map_err(|e| {
+            ConfigError::LoadError(format!(
+                "Failed to read dotenv file {}: {}",
+                self.path.display(),
+                e
+            ))
+        })?;
+
+        let mut vars = HashMap::new();
+        for item in iter {
+            let (key, value) = item.map_err(|e| {
+                ConfigError::ParseError(format!(
+                    "Failed to parse dotenv file {}: {}",
+                    self.path.display(),
+                    e
+                ))
+            })?;
+            vars.insert(key, value);
+        }
+
+        vars_to_map(
+            self.prefix.as_deref(),
+            &self.separator,
+            Some(vars),
+            &EnvParsing::default(),
+        )
+    }
+
+    fn name(&self) -> String {
+        format!("dotenv:{}", self.path.display())
+    }
+
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    fn paths(&self) -> Vec<PathBuf> {
+        vec![self.path.clone()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_load_with_prefix() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(".env");
+        fs::write(
+            &path,
+            "# comment\nAPP__SERVER__PORT=8080\nexport APP__SERVER__HOST=\"localhost\"\nOTHER=1\n",
+        )
+        .unwrap();
+
+        let source = DotenvSource::new(&path).with_prefix("APP", "__");
+        let map = source.load().unwrap();
+
+        assert!(!map.contains_key("other"));
+        let server = map["server"].clone().into_table().unwrap();
+        assert_eq!(server["port"].clone().into_int().unwrap(), 8080);
+        assert_eq!(server["host"].clone().into_string().unwrap(), "localhost");
+    }
+
+    #[test]
+    fn test_load_without_prefix() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(".env");
+        fs::write(&path, "DATABASE__URL=postgres://localhost/db\n").unwrap();
+
+        let map = DotenvSource::new(&path).load().unwrap();
+        let database = map["database"].clone().into_table().unwrap();
+        assert_eq!(
+            database["url"].clone().into_string().unwrap(),
+            "postgres://localhost/db"
+        );
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let source = DotenvSource::new("/nonexistent/.env");
+        assert!(source.load().is_err());
+    }
+
+    #[test]
+    fn test_priority_and_name() {
+        let source = DotenvSource::new(".env");
+        assert_eq!(source.priority(), 290);
+        assert_eq!(source.name(), "dotenv:.env");
+
+        let source = source.with_priority(50);
+        assert_eq!(source.priority(), 50);
+    }
+}
@@ -1,27 +1,114 @@
 //! Environment variable configuration source.
 
 use super::ConfigSource;
-use crate::error::Result;
-use config::Environment;
+use crate::error::{ConfigError, Result};
+use config::ValueKind;
 use std::collections::HashMap;
 
+/// Where values read from the environment come from, for error messages.
+const ORIGIN: &str = "the environment";
+
 /// Environment variable configuration source.
 ///
 /// Loads configuration from environment variables with a specified prefix
 /// and separator for nested keys.
 ///
+/// Values that look like booleans or numbers are parsed as such, which can
+/// change them: `1.10` becomes the float `1.1`, and `01234` the integer
+/// `1234`. [`with_type_hint`](Self::with_type_hint) and
+/// [`keep_as_string`](Self::keep_as_string) fix the type of a key, and
+/// [`strict`](Self::strict) fails the load instead of changing a value.
+///
+/// Keys are lowercase with `_` between words, like `max_connections`.
+/// [`with_key_case`](Self::with_key_case) converts them to match fields
+/// renamed with `#[serde(rename_all = "camelCase")]` and the like, and
+/// [`with_alias`](Self::with_alias) maps any variable to any key.
+///
+/// Lists and maps can be set from a single variable by giving their key a
+/// type hint of [`EnvType::List`] or [`EnvType::Map`]. Maps can also be
+/// built a key at a time: `APP__LABELS__TEAM=payments` sets `labels.team`.
+///
 /// # Examples
 ///
 /// ```rust
 /// use hotswap_config::sources::EnvSource;
 ///
-/// // APP_SERVER__PORT=8080 -> server.port = 8080
+/// // APP__SERVER__PORT=8080 -> server.port = 8080
 /// let source = EnvSource::new("APP", "__");
 /// ```
 pub struct EnvSource {
     prefix: String,
     separator: String,
     priority: i32,
+    excluded: Vec<String>,
+    parsing: EnvParsing,
+}
+
+/// Case to convert the keys of environment variables to.
+///
+/// Given to [`EnvSource::with_key_case`]. Each part of a nested key is
+/// converted on its own, treating `_` as the break between words, so
+/// `APP__DATABASE__MAX_CONNECTIONS` becomes `database.maxConnections` in
+/// camel case.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum KeyCase {
+    /// `max_connections`, the default
+    #[default]
+    Snake,
+    /// `maxConnections`
+    Camel,
+    /// `MaxConnections`
+    Pascal,
+    /// `max-connections`
+    Kebab,
+}
+
+impl KeyCase {
+    /// Convert one part of a key from snake case.
+    fn convert(self, part: &str) -> String {
+        let capitalize = |word: &str| -> String {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect())
+                .unwrap_or_default()
+        };
+        let mut words = part.split('_').filter(|word| !word.is_empty());
+        match self {
+            Self::Snake => part.to_string(),
+            Self::Camel => {
+                let first = words.next().unwrap_or_default().to_string();
+                words.fold(first, |key, word| key + &capitalize(word))
+            }
+            Self::Pascal => words.map(capitalize).collect(),
+            Self::Kebab => words.collect::<Vec<_>>().join("-"),
+        }
+    }
+}
+
+/// Type to parse an environment variable's value as.
+///
+/// Given to [`EnvSource::with_type_hint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EnvType {
+    /// Keep the value as it is
+    String,
+    /// A 64-bit signed integer; leading zeros are dropped
+    Integer,
+    /// A 64-bit float
+    Float,
+    /// `true` or `false`, in any case
+    Boolean,
+    /// A list of strings split on the
+    /// [list separator](EnvSource::with_list_separator), e.g. `a.com,b.com`;
+    /// items are trimmed, and an empty value is an empty list
+    List,
+    /// A map of strings from `key=value` entries split on the
+    /// [list separator](EnvSource::with_list_separator), e.g.
+    /// `team=payments,tier=gold`
+    Map,
 }
 
 impl EnvSource {
@@ -30,14 +117,14 @@ impl EnvSource {
     /// # Arguments
     ///
     /// * `prefix` - Prefix for environment variables (e.g., "APP")
-    /// * `separator` - Separator for nested keys (e.g., "__" for APP_DB__HOST)
+    /// * `separator` - Separator for nested keys (e.g., "__" for APP__DB__HOST)
     ///
     /// # Examples
     ///
     /// ```rust
     /// use hotswap_config::sources::EnvSource;
     ///
-    /// // Matches: APP_SERVER__PORT, APP_DB__HOST, etc.
+    /// // Matches: APP__SERVER__PORT, APP__DB__HOST, etc.
     /// let source = EnvSource::new("APP", "__");
     /// ```
     pub fn new(prefix: impl Into<String>, separator: impl Into<String>) -> Self {
@@ -45,9 +132,30 @@ impl EnvSource {
             prefix: prefix.into(),
             separator: separator.into(),
             priority: 300, // Env vars have highest priority by default
+            excluded: Vec::new(),
+            parsing: EnvParsing::default(),
         }
     }
 
+    /// Skip variables with a more specific prefix, e.g. ones another source loads.
+    ///
+    /// The prefix is matched case-insensitively and followed by the separator,
+    /// like the source's own prefix.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hotswap_config::sources::EnvSource;
+    ///
+    /// // APP__SERVER__PORT is loaded, APP__PRODUCTION__SERVER__PORT is not
+    /// let source = EnvSource::new("APP", "__").excluding("APP__PRODUCTION");
+    /// ```
+    pub fn excluding(mut self, prefix: impl Into<String>) -> Self {
+        self.excluded
+            .push(format!("{}{}", prefix.into(), self.separator).to_uppercase());
+        self
+    }
+
     /// Set the priority for this source.
     ///
     /// Higher priority sources override lower priority ones.
@@ -55,37 +163,159 @@ impl EnvSource {
         self.priority = priority;
         self
     }
+
+    /// Parse the value for `key` as `ty`, instead of guessing its type.
+    ///
+    /// `key` is the dotted configuration key, e.g. `server.port` for
+    /// `APP__SERVER__PORT`, matched case-insensitively. A value that isn't a
+    /// valid `ty` fails the load.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hotswap_config::sources::{EnvSource, EnvType};
+    ///
+    /// // APP__RETRY__BACKOFF=2 loads as the float 2.0
+    /// let source = EnvSource::new("APP", "__").with_type_hint("retry.backoff", EnvType::Float);
+    /// ```
+    pub fn with_type_hint(mut self, key: impl Into<String>, ty: EnvType) -> Self {
+        self.parsing.types.insert(key.into().to_lowercase(), ty);
+        self
+    }
+
+    /// Keep the values for `keys` as strings, instead of guessing their type.
+    ///
+    /// Short for a [type hint](Self::with_type_hint) of [`EnvType::String`]
+    /// for each key, for values like versions and zip codes that only look
+    /// like numbers.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hotswap_config::sources::EnvSource;
+    ///
+    /// // APP__VERSION=1.10 stays "1.10", APP__ADDRESS__ZIP=01234 stays "01234"
+    /// let source = EnvSource::new("APP", "__").keep_as_string(["version", "address.zip"]);
+    /// ```
+    pub fn keep_as_string<I, S>(mut self, keys: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        for key in keys {
+            self = self.with_type_hint(key, EnvType::String);
+        }
+        self
+    }
+
+    /// Parse the values for `keys` as lists.
+    ///
+    /// Short for a [type hint](Self::with_type_hint) of [`EnvType::List`]
+    /// for each key.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hotswap_config::sources::EnvSource;
+    ///
+    /// // APP__SECURITY__ALLOWED_ORIGINS=a.com,b.com -> ["a.com", "b.com"]
+    /// let source = EnvSource::new("APP", "__").with_list_keys(["security.allowed_origins"]);
+    /// ```
+    pub fn with_list_keys<I, S>(mut self, keys: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        for key in keys {
+            self = self.with_type_hint(key, EnvType::List);
+        }
+        self
+    }
+
+    /// Set what separates the items of [lists](EnvType::List) and the
+    /// entries of [maps](EnvType::Map) (default `,`).
+    ///
+    /// An empty separator makes [`load`](ConfigSource::load) fail.
+    pub fn with_list_separator(mut self, separator: impl Into<String>) -> Self {
+        self.parsing.list_separator = separator.into();
+        self
+    }
+
+    /// Convert keys to `case`, so they match renamed serde fields.
+    ///
+    /// [Type hints](Self::with_type_hint) name keys after conversion, e.g.
+    /// `database.maxConnections`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hotswap_config::sources::{EnvSource, KeyCase};
+    ///
+    /// // APP__DATABASE__MAX_CONNECTIONS=20 -> database.maxConnections = 20
+    /// let source = EnvSource::new("APP", "__").with_key_case(KeyCase::Camel);
+    /// ```
+    pub fn with_key_case(mut self, case: KeyCase) -> Self {
+        self.parsing.key_case = case;
+        self
+    }
+
+    /// Load variable `var` as the dotted `key`, exactly as written.
+    ///
+    /// `var` is matched case-insensitively and needn't have the source's
+    /// prefix, so conventional variables like `DATABASE_URL` can be mapped
+    /// too. An alias wins over a variable whose key would otherwise be the
+    /// same.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hotswap_config::sources::EnvSource;
+    ///
+    /// let source = EnvSource::new("APP", "__")
+    ///     .with_alias("APP_DB_URL", "database.url")
+    ///     .with_alias("PORT", "server.port");
+    /// ```
+    pub fn with_alias(mut self, var: impl Into<String>, key: impl Into<String>) -> Self {
+        self.parsing
+            .aliases
+            .insert(var.into().to_uppercase(), key.into());
+        self
+    }
+
+    /// Fail the load when guessing a value's type would change it.
+    ///
+    /// Values without a [type hint](Self::with_type_hint) are still parsed
+    /// as booleans and numbers when they read back the same, like `8080` or
+    /// `true`, but `1.10`, `01234`, `1e3`, and `TRUE` fail the load with
+    /// [`ConfigError::ParseError`] naming the variable, until they are given
+    /// a type hint.
+    pub fn strict(mut self) -> Self {
+        self.parsing.strict = true;
+        self
+    }
 }
 
 impl ConfigSource for EnvSource {
     fn load(&self) -> Result<HashMap<String, config::Value>> {
-        // Use the config crate's Environment source
-        let env_source = Environment::with_prefix(&self.prefix)
-            .separator(&self.separator)
-            .try_parsing(true); // Try to parse numbers, booleans, etc.
-
-        // Build a config with just this environment source
-        let config_builder = config::Config::builder()
-            .add_source(env_source)
-            .build()
-            .map_err(|e| {
-                crate::error::ConfigError::LoadError(format!(
-                    "Failed to load environment variables: {}",
-                    e
-                ))
-            })?;
-
-        // Extract as HashMap
-        let map = config_builder
-            .try_deserialize::<HashMap<String, config::Value>>()
-            .map_err(|e| {
-                crate::error::ConfigError::DeserializationError(format!(
-                    "Failed to parse environment variables: {}",
-                    e
-                ))
-            })?;
-
-        Ok(map)
+        if self.parsing.list_separator.is_empty() {
+            return Err(ConfigError::LoadError(format!(
+                "The list separator of {} must not be empty",
+                self.name()
+            )));
+        }
+
+        let vars = (!self.excluded.is_empty()).then(|| {
+            std::env::vars_os()
+                .filter_map(|(key, value)| {
+                    Some((key.into_string().ok()?, value.into_string().ok()?))
+                })
+                .filter(|(key, _)| {
+                    let key = key.to_uppercase();
+                    !self.excluded.iter().any(|prefix| key.starts_with(prefix))
+                })
+                .collect()
+        });
+        vars_to_map(Some(&self.prefix), &self.separator, vars, &self.parsing)
     }
 
     fn name(&self) -> String {
@@ -97,6 +327,166 @@ impl ConfigSource for EnvSource {
     }
 }
 
+/// How values read from environment variables are parsed.
+#[derive(Debug, Clone)]
+pub(crate) struct EnvParsing {
+    /// Type hints, by lowercase dotted key
+    types: HashMap<String, EnvType>,
+    /// Separates list items and map entries
+    list_separator: String,
+    /// Fail instead of guessing a type that changes the value
+    strict: bool,
+    /// Case keys are converted to
+    key_case: KeyCase,
+    /// Keys for variables, by uppercase variable name
+    aliases: HashMap<String, String>,
+}
+
+impl Default for EnvParsing {
+    fn default() -> Self {
+        Self {
+            types: HashMap::new(),
+            list_separator: ",".to_string(),
+            strict: false,
+            key_case: KeyCase::Snake,
+            aliases: HashMap::new(),
+        }
+    }
+}
+
+impl EnvParsing {
+    /// Parse the value of variable `var`, loaded as `key`.
+    fn parse(&self, var: &str, key: &str, value: String) -> Result<ValueKind> {
+        let Some(ty) = self.types.get(&key.to_lowercase()) else {
+            return self.guess(var, key, value);
+        };
+        let parsed = match ty {
+            EnvType::String => Some(ValueKind::String(value)),
+            EnvType::Integer => value.trim().parse().ok().map(ValueKind::I64),
+            EnvType::Float => value.trim().parse().ok().map(ValueKind::Float),
+            EnvType::Boolean => value
+                .trim()
+                .to_lowercase()
+                .parse()
+                .ok()
+                .map(ValueKind::Boolean),
+            EnvType::List => Some(ValueKind::Array(
+                self.items(&value)
+                    .map(|item| config::Value::new(Some(&ORIGIN.to_string()), item))
+                    .collect(),
+            )),
+            EnvType::Map => self
+                .items(&value)
+                .map(|entry| {
+                    let (key, value) = entry.split_once('=')?;
+                    let value = config::Value::new(Some(&ORIGIN.to_string()), value.trim());
+                    Some((key.trim().to_string(), value))
+                })
+                .collect::<Option<_>>()
+                .map(ValueKind::Table),
+        };
+        parsed.ok_or_else(|| {
+            let expected = match ty {
+                EnvType::String => "a string",
+                EnvType::Integer => "an integer",
+                EnvType::Float => "a float",
+                EnvType::Boolean => "a boolean",
+                EnvType::List => "a list",
+                EnvType::Map => "a map of `key=value` entries",
+            };
+            ConfigError::ParseError(format!("{} must be {}", var, expected)).at_key(key)
+        })
+    }
+
+    /// Split a list or map value into its trimmed items.
+    fn items<'a>(&'a self, value: &'a str) -> impl Iterator<Item = &'a str> {
+        value
+            .split(self.list_separator.as_str())
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+    }
+
+    /// Parse a value without a type hint as a boolean or number if it looks
+    /// like one.
+    fn guess(&self, var: &str, key: &str, value: String) -> Result<ValueKind> {
+        // Convert to lowercase because bool parsing expects all lowercase
+        let (kind, text) = if let Ok(parsed) = value.to_lowercase().parse::<bool>() {
+            (ValueKind::Boolean(parsed), parsed.to_string())
+        } else if let Ok(parsed) = value.parse::<i64>() {
+            (ValueKind::I64(parsed), parsed.to_string())
+        } else if let Ok(parsed) = value.parse::<f64>() {
+            (ValueKind::Float(parsed), parsed.to_string())
+        } else {
+            return Ok(ValueKind::String(value));
+        };
+        if self.strict && text != value {
+            return Err(ConfigError::ParseError(format!(
+                "{} is `{}`, which would load as {}; give it a type hint",
+                var, value, text
+            ))
+            .at_key(key));
+        }
+        Ok(kind)
+    }
+}
+
+/// Map environment-style variables to a nested configuration tree.
+///
+/// Variables are filtered by `prefix` followed by `separator` (if any), lowercased,
+/// and split into nested keys on `separator`, unless `parsing` has an alias for them. Keys are
+/// converted and values parsed as `parsing` says.
+/// When `vars` is `None`, the process environment is used.
+pub(crate) fn vars_to_map(
+    prefix: Option<&str>,
+    separator: &str,
+    vars: Option<HashMap<String, String>>,
+    parsing: &EnvParsing,
+) -> Result<HashMap<String, config::Value>> {
+    // The prefix is followed by the separator, as in the config crate
+    let prefix = prefix.map(|prefix| format!("{}{}", prefix, separator).to_lowercase());
+    let vars = vars.unwrap_or_else(|| {
+        std::env::vars_os()
+            .filter_map(|(key, value)| Some((key.into_string().ok()?, value.into_string().ok()?)))
+            .collect()
+    });
+
+    let mut parsed = Vec::new();
+    let mut aliased = Vec::new();
+    for (var, value) in vars {
+        if let Some(key) = parsing.aliases.get(&var.to_uppercase()) {
+            let value = parsing.parse(&var, key, value)?;
+            aliased.push((key.clone(), value));
+            continue;
+        }
+        let mut key = var.to_lowercase();
+        if let Some(prefix) = &prefix {
+            match key.strip_prefix(prefix.as_str()) {
+                Some(rest) => key = rest.to_string(),
+                None => continue,
+            }
+        }
+        let parts: Vec<String> = if separator.is_empty() {
+            vec![parsing.key_case.convert(&key)]
+        } else {
+            key.split(separator)
+                .map(|part| parsing.key_case.convert(part))
+                .collect()
+        };
+        let key = parts.join(".");
+        let value = parsing.parse(&var, &key, value)?;
+        parsed.push((key, value));
+    }
+
+    // Nest the keys ourselves: the config crate would lowercase converted
+    // ones. Aliases go last, to win over the variables they shadow.
+    let mut map = config::Map::new();
+    for (key, value) in parsed.into_iter().chain(aliased) {
+        let value = config::Value::new(Some(&ORIGIN.to_string()), value);
+        crate::core::set_path(&mut map, &key, Some(value));
+    }
+    Ok(map.into_iter().collect())
+}
+
 #[cfg(test)]
 #[allow(unsafe_code)] // For env var manipulation in tests
 mod tests {
@@ -142,6 +532,208 @@ mod tests {
         assert!(map.is_empty() || !map.is_empty()); // Either is valid
     }
 
+    #[test]
+    fn test_vars_to_map_nested() {
+        let vars = HashMap::from([
+            ("APP__SERVER__PORT".to_string(), "8080".to_string()),
+            ("APP__DEBUG".to_string(), "true".to_string()),
+            ("APP_LOG__LEVEL".to_string(), "ignored".to_string()),
+            ("OTHER_VALUE".to_string(), "ignored".to_string()),
+        ]);
+
+        // The prefix is followed by the separator, not a single `_`
+        let map = vars_to_map(Some("APP"), "__", Some(vars), &EnvParsing::default()).unwrap();
+        assert!(!map.contains_key("other_value"));
+        assert!(!map.contains_key("log"));
+        assert!(map["debug"].clone().into_bool().unwrap());
+
+        let server = map["server"].clone().into_table().unwrap();
+        assert_eq!(server["port"].clone().into_int().unwrap(), 8080);
+    }
+
+    #[test]
+    fn test_excluding_prefix() {
+        unsafe {
+            env::set_var("HOTSWAP_EXCL_TEST__PORT", "8080");
+            env::set_var("HOTSWAP_EXCL_TEST__PROD__PORT", "9090");
+        }
+
+        let map = EnvSource::new("HOTSWAP_EXCL_TEST", "__")
+            .excluding("hotswap_excl_test__prod")
+            .load()
+            .unwrap();
+        assert_eq!(map["port"].clone().into_int().unwrap(), 8080);
+        assert!(!map.contains_key("prod"));
+
+        unsafe {
+            env::remove_var("HOTSWAP_EXCL_TEST__PORT");
+            env::remove_var("HOTSWAP_EXCL_TEST__PROD__PORT");
+        }
+    }
+
+    #[test]
+    fn test_type_hints() {
+        let vars = || {
+            HashMap::from([
+                ("APP__VERSION".to_string(), "1.10".to_string()),
+                ("APP__ADDRESS__ZIP".to_string(), "01234".to_string()),
+                ("APP__RETRY__BACKOFF".to_string(), "2".to_string()),
+                ("APP__PORT".to_string(), "8080".to_string()),
+            ])
+        };
+
+        // Guessing changes the value
+        let map = vars_to_map(Some("APP"), "__", Some(vars()), &EnvParsing::default()).unwrap();
+        assert_eq!(map["version"].clone().into_string().unwrap(), "1.1");
+
+        let source = EnvSource::new("APP", "__")
+            .keep_as_string(["version", "Address.Zip"])
+            .with_type_hint("retry.backoff", EnvType::Float);
+        let map = vars_to_map(Some("APP"), "__", Some(vars()), &source.parsing).unwrap();
+        assert_eq!(map["version"].clone().into_string().unwrap(), "1.10");
+        let address = map["address"].clone().into_table().unwrap();
+        assert_eq!(address["zip"].clone().into_string().unwrap(), "01234");
+        let retry = map["retry"].clone().into_table().unwrap();
+        assert!(matches!(retry["backoff"].kind, ValueKind::Float(f) if f == 2.0));
+        assert_eq!(map["port"].clone().into_int().unwrap(), 8080);
+
+        let source = EnvSource::new("APP", "__").with_type_hint("version", EnvType::Integer);
+        let error = vars_to_map(Some("APP"), "__", Some(vars()), &source.parsing).unwrap_err();
+        assert_eq!(error.key_path(), Some("version"));
+        assert!(
+            error
+                .to_string()
+                .contains("APP__VERSION must be an integer")
+        );
+    }
+
+    #[test]
+    fn test_strict_rejects_lossy_guesses() {
+        let source = EnvSource::new("APP", "__").strict();
+        let load = |value: &str| {
+            let vars = HashMap::from([("APP__VALUE".to_string(), value.to_string())]);
+            vars_to_map(Some("APP"), "__", Some(vars), &source.parsing)
+        };
+
+        assert_eq!(
+            load("8080").unwrap()["value"].clone().into_int().unwrap(),
+            8080
+        );
+        assert!(load("true").unwrap()["value"].clone().into_bool().unwrap());
+        assert!(load("1.5").is_ok());
+        assert!(load("localhost").is_ok());
+        for value in ["1.10", "01234", "1e3", "TRUE"] {
+            let error = load(value).unwrap_err();
+            assert_eq!(error.key_path(), Some("value"), "{}", value);
+        }
+
+        let source = source.keep_as_string(["value"]);
+        let vars = HashMap::from([("APP__VALUE".to_string(), "01234".to_string())]);
+        let map = vars_to_map(Some("APP"), "__", Some(vars), &source.parsing).unwrap();
+        assert_eq!(map["value"].clone().into_string().unwrap(), "01234");
+    }
+
+    #[test]
+    fn test_lists_and_maps() {
+        let vars = || {
+            HashMap::from([
+                (
+                    "APP__SECURITY__ALLOWED_ORIGINS".to_string(),
+                    "a.com, b.com".to_string(),
+                ),
+                ("APP__PORTS".to_string(), "80;443".to_string()),
+                (
+                    "APP__LABELS".to_string(),
+                    "team=payments,tier=gold".to_string(),
+                ),
+                ("APP__TAGS__TEAM".to_string(), "payments".to_string()),
+                ("APP__EMPTY".to_string(), String::new()),
+            ])
+        };
+        let source = EnvSource::new("APP", "__")
+            .with_list_keys(["security.allowed_origins", "empty"])
+            .with_type_hint("labels", EnvType::Map);
+        let map = vars_to_map(Some("APP"), "__", Some(vars()), &source.parsing).unwrap();
+
+        let security = map["security"].clone().into_table().unwrap();
+        let origins: Vec<String> = security["allowed_origins"]
+            .clone()
+            .into_array()
+            .unwrap()
+            .into_iter()
+            .map(|v| v.into_string().unwrap())
+            .collect();
+        assert_eq!(origins, ["a.com", "b.com"]);
+        assert!(map["empty"].clone().into_array().unwrap().is_empty());
+        let labels = map["labels"].clone().into_table().unwrap();
+        assert_eq!(labels["tier"].clone().into_string().unwrap(), "gold");
+        let tags = map["tags"].clone().into_table().unwrap();
+        assert_eq!(tags["team"].clone().into_string().unwrap(), "payments");
+
+        // Other separators
+        let source = EnvSource::new("APP", "__")
+            .with_list_separator(";")
+            .with_list_keys(["ports"]);
+        let map = vars_to_map(Some("APP"), "__", Some(vars()), &source.parsing).unwrap();
+        let ports = map["ports"].clone().into_array().unwrap();
+        assert_eq!(ports[1].clone().into_int().unwrap(), 443);
+
+        let source = EnvSource::new("APP", "__").with_type_hint("tags.team", EnvType::Map);
+        let error = vars_to_map(Some("APP"), "__", Some(vars()), &source.parsing).unwrap_err();
+        assert_eq!(error.key_path(), Some("tags.team"));
+
+        let source = EnvSource::new("APP", "__").with_list_separator("");
+        assert!(matches!(source.load(), Err(ConfigError::LoadError(_))));
+    }
+
+    #[test]
+    fn test_key_case_and_aliases() {
+        let vars = || {
+            HashMap::from([
+                (
+                    "APP__DATABASE__MAX_CONNECTIONS".to_string(),
+                    "20".to_string(),
+                ),
+                ("APP_DB_URL".to_string(), "postgres://db".to_string()),
+                ("DATABASE_URL".to_string(), "postgres://other".to_string()),
+                (
+                    "APP__DATABASE__URL".to_string(),
+                    "postgres://shadowed".to_string(),
+                ),
+            ])
+        };
+        let source = EnvSource::new("APP", "__")
+            .with_key_case(KeyCase::Camel)
+            .with_alias("app_db_url", "database.url")
+            .keep_as_string(["database.maxConnections"]);
+        let map = vars_to_map(Some("APP"), "__", Some(vars()), &source.parsing).unwrap();
+
+        let database = map["database"].clone().into_table().unwrap();
+        assert_eq!(
+            database["maxConnections"].clone().into_string().unwrap(),
+            "20"
+        );
+        assert_eq!(
+            database["url"].clone().into_string().unwrap(),
+            "postgres://db"
+        );
+        assert!(!map.contains_key("dbUrl"));
+
+        // Aliases needn't have the prefix
+        let source = EnvSource::new("APP", "__").with_alias("DATABASE_URL", "database.url");
+        let vars = HashMap::from([("DATABASE_URL".to_string(), "postgres://other".to_string())]);
+        let map = vars_to_map(Some("APP"), "__", Some(vars), &source.parsing).unwrap();
+        let database = map["database"].clone().into_table().unwrap();
+        assert_eq!(
+            database["url"].clone().into_string().unwrap(),
+            "postgres://other"
+        );
+
+        assert_eq!(KeyCase::Pascal.convert("max_connections"), "MaxConnections");
+        assert_eq!(KeyCase::Kebab.convert("max_connections"), "max-connections");
+        assert_eq!(KeyCase::Camel.convert("port"), "port");
+    }
+
     // Note: Testing actual env var loading is done in integration tests
     // because the config crate's Environment source behavior can be
     // tricky to test in unit tests due to when env vars are captured.
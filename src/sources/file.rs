@@ -1,15 +1,28 @@
 //! File-based configuration source.
 
-use super::ConfigSource;
+#[cfg(feature = "file-watch")]
+use super::ChangeStream;
+use super::{ConfigSource, Format, SignedContent};
 use crate::error::{ConfigError, Result};
-use config::File;
+#[cfg(feature = "file-watch")]
+use crate::notify::ConfigWatcher;
+#[cfg(feature = "templating")]
+use crate::templating::TemplateContext;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "file-watch")]
+use std::time::Duration;
+
+/// Human-readable list of supported extensions, for error messages.
+#[cfg(not(feature = "hcl"))]
+const SUPPORTED_EXTENSIONS: &str = ".yaml, .yml, .toml, .json";
+#[cfg(feature = "hcl")]
+const SUPPORTED_EXTENSIONS: &str = ".yaml, .yml, .toml, .json, .hcl";
 
 /// File-based configuration source.
 ///
-/// Loads configuration from YAML, TOML, or JSON files with automatic format detection
-/// based on file extension.
+/// Loads configuration from YAML, TOML, or JSON files (and HCL with the `hcl`
+/// feature) with automatic format detection based on file extension.
 ///
 /// # Examples
 ///
@@ -21,6 +34,11 @@ use std::path::PathBuf;
 pub struct FileSource {
     path: PathBuf,
     priority: i32,
+    required: bool,
+    #[cfg(feature = "file-watch")]
+    watch: bool,
+    #[cfg(feature = "templating")]
+    template: Option<TemplateContext>,
 }
 
 impl FileSource {
@@ -30,6 +48,7 @@ impl FileSource {
     /// - `.yaml`, `.yml` -> YAML
     /// - `.toml` -> TOML
     /// - `.json` -> JSON
+    /// - `.hcl` -> HCL (feature: `hcl`)
     ///
     /// # Examples
     ///
@@ -42,9 +61,42 @@ impl FileSource {
         Self {
             path: path.into(),
             priority: 100,
+            required: true,
+            #[cfg(feature = "file-watch")]
+            watch: false,
+            #[cfg(feature = "templating")]
+            template: None,
         }
     }
 
+    /// Create a file source that loads nothing if the file doesn't exist.
+    ///
+    /// Useful for local overrides such as `config/local.yaml` that only exist
+    /// on some machines. A file that exists but fails to parse is still an
+    /// error.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::sources::FileSource;
+    ///
+    /// let source = FileSource::optional("config/local.yaml");
+    /// ```
+    pub fn optional(path: impl Into<PathBuf>) -> Self {
+        Self::new(path).required(false)
+    }
+
+    /// Set whether the file must exist (the default).
+    pub fn required(mut self, required: bool) -> Self {
+        self.required = required;
+        self
+    }
+
+    /// Check whether the file must exist.
+    pub fn is_required(&self) -> bool {
+        self.required
+    }
+
     /// Set the priority for this source.
     ///
     /// Higher priority sources override lower priority ones.
@@ -53,6 +105,50 @@ impl FileSource {
         self
     }
 
+    /// Set whether the file is watched for changes when added with
+    /// [`HotswapConfigBuilder::with_source`](crate::core::HotswapConfigBuilder::with_source).
+    ///
+    /// Off by default. Files added with the builder's `with_file` methods
+    /// are watched by [`with_file_watch`](crate::core::HotswapConfigBuilder::with_file_watch)
+    /// instead.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::prelude::*;
+    /// use hotswap_config::sources::FileSource;
+    ///
+    /// # struct AppConfig;
+    /// # async fn example() {
+    /// HotswapConfig::<AppConfig>::builder()
+    ///     .with_source(FileSource::new("/etc/myapp/tuning.yaml").with_watch(true));
+    /// # }
+    /// ```
+    #[cfg(feature = "file-watch")]
+    pub fn with_watch(mut self, watch: bool) -> Self {
+        self.watch = watch;
+        self
+    }
+
+    /// Render the file as a Tera template with `context` before parsing it.
+    ///
+    /// See [`templating`](crate::templating) for what templates can use.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotswap_config::sources::FileSource;
+    /// use hotswap_config::templating::TemplateContext;
+    ///
+    /// let source = FileSource::new("config/app.yaml")
+    ///     .with_template(TemplateContext::new().with_var("regions", ["us", "eu"]));
+    /// ```
+    #[cfg(feature = "templating")]
+    pub fn with_template(mut self, context: TemplateContext) -> Self {
+        self.template = Some(context);
+        self
+    }
+
     /// Validate that the file extension is supported.
     fn validate_extension(&self) -> Result<()> {
         let extension = self
@@ -66,14 +162,90 @@ impl FileSource {
                 ))
             })?;
 
-        match extension {
-            "yaml" | "yml" | "toml" | "json" => Ok(()),
-            _ => Err(ConfigError::LoadError(format!(
-                "Unsupported file extension: {}. Supported: .yaml, .yml, .toml, .json",
-                extension
-            ))),
+        if supported_extension(extension) {
+            Ok(())
+        } else {
+            Err(ConfigError::LoadError(format!(
+                "Unsupported file extension: {}. Supported: {}",
+                extension, SUPPORTED_EXTENSIONS
+            )))
         }
     }
+
+    /// Check that the file exists, or that it's optional.
+    ///
+    /// Returns `false` if an optional file is missing.
+    fn check_exists(&self) -> Result<bool> {
+        if self.path.exists() {
+            return Ok(true);
+        }
+        if !self.required {
+            return Ok(false);
+        }
+        Err(ConfigError::LoadError(format!(
+            "Configuration file not found: {}",
+            self.path.display()
+        )))
+    }
+
+    /// Parse file content in the format given by the extension, rendering it
+    /// first if it is a template.
+    fn parse(&self, content: &str) -> Result<HashMap<String, config::Value>> {
+        #[cfg(feature = "templating")]
+        let rendered = match &self.template {
+            Some(context) => Some(context.render(&self.path, content)?),
+            None => None,
+        };
+        #[cfg(feature = "templating")]
+        let content = rendered.as_deref().unwrap_or(content);
+
+        let format = match self.path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Format::Toml,
+            Some("json") => Format::Json,
+            #[cfg(feature = "hcl")]
+            Some("hcl") => Format::Hcl,
+            _ => Format::Yaml,
+        };
+        format.parse(content)
+    }
+}
+
+/// Path of the detached signature for a file: the file name with `.sig`
+/// appended (`config.yaml` -> `config.yaml.sig`).
+pub(crate) fn signature_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".sig");
+    path.with_file_name(name)
+}
+
+/// Check whether a file extension (without the dot) is a supported format.
+fn supported_extension(extension: &str) -> bool {
+    match extension {
+        "yaml" | "yml" | "toml" | "json" => true,
+        #[cfg(feature = "hcl")]
+        "hcl" => true,
+        _ => false,
+    }
+}
+
+/// Find the file named `stem` with a supported extension in `dir`.
+///
+/// Falls back to `stem.yaml` if there is none, so a missing file is reported
+/// with a sensible name.
+pub(crate) fn find_file(dir: &Path, stem: &str) -> PathBuf {
+    ["yaml", "yml", "toml", "json", "hcl"]
+        .iter()
+        .filter(|ext| supported_extension(ext))
+        .map(|ext| dir.join(format!("{}.{}", stem, ext)))
+        .find(|path| path.is_file())
+        .unwrap_or_else(|| dir.join(format!("{}.yaml", stem)))
+}
+
+/// Check whether a path has a supported file extension.
+pub(super) fn is_supported(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(supported_extension)
 }
 
 impl ConfigSource for FileSource {
@@ -82,27 +254,12 @@ impl ConfigSource for FileSource {
         self.validate_extension()?;
 
         // Check if file exists
-        if !self.path.exists() {
-            return Err(ConfigError::LoadError(format!(
-                "Configuration file not found: {}",
-                self.path.display()
-            )));
+        if !self.check_exists()? {
+            return Ok(HashMap::new());
         }
 
-        // Build a config using the config crate (auto-detects format from extension)
-        let config_builder = config::Config::builder()
-            .add_source(File::from(self.path.clone()).required(true))
-            .build()
-            .map_err(|e| ConfigError::LoadError(format!("Failed to load file: {}", e)))?;
-
-        // Extract as HashMap
-        let map = config_builder
-            .try_deserialize::<HashMap<String, config::Value>>()
-            .map_err(|e| {
-                ConfigError::DeserializationError(format!("Failed to parse file: {}", e))
-            })?;
-
-        Ok(map)
+        let content = std::fs::read_to_string(&self.path)?;
+        self.parse(&content)
     }
 
     fn name(&self) -> String {
@@ -112,6 +269,79 @@ impl ConfigSource for FileSource {
     fn priority(&self) -> i32 {
         self.priority
     }
+
+    fn paths(&self) -> Vec<PathBuf> {
+        vec![self.path.clone()]
+    }
+
+    #[cfg(feature = "file-watch")]
+    fn watch(&self) -> Option<ChangeStream> {
+        if !self.watch {
+            return None;
+        }
+
+        let (notifier, changes) = ChangeStream::channel();
+        let path = self.path.clone();
+        let required = self.required;
+        tokio::spawn(async move {
+            let (watcher, mut rx) = match ConfigWatcher::new(Duration::from_millis(500)) {
+                Ok(watcher) => watcher,
+                Err(e) => {
+                    notifier.error(e);
+                    return;
+                }
+            };
+            let watched = if required {
+                watcher.watch(&path).await
+            } else {
+                watcher.watch_optional(&path).await
+            };
+            if let Err(e) = watched {
+                notifier.error(e);
+                return;
+            }
+
+            loop {
+                tokio::select! {
+                    changed = rx.recv() => {
+                        if changed.is_none() || !notifier.changed() {
+                            break;
+                        }
+                    }
+                    _ = notifier.closed() => break,
+                }
+            }
+        });
+        Some(changes)
+    }
+
+    fn load_signed(&self) -> Result<Option<SignedContent>> {
+        self.validate_extension()?;
+        if !self.check_exists()? {
+            return Ok(None);
+        }
+
+        let content = std::fs::read(&self.path)?;
+        let signature = match std::fs::read(signature_path(&self.path)) {
+            Ok(signature) => Some(signature),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+        let text = std::str::from_utf8(&content).map_err(|e| {
+            ConfigError::LoadError(format!(
+                "Configuration file is not UTF-8: {}: {}",
+                self.path.display(),
+                e
+            ))
+        })?;
+        let values = self.parse(text)?;
+
+        Ok(Some(SignedContent {
+            content,
+            signature,
+            values,
+        }))
+    }
 }
 
 #[cfg(test)]
@@ -141,6 +371,21 @@ mod tests {
         assert!(source.validate_extension().is_ok());
     }
 
+    #[cfg(feature = "hcl")]
+    #[test]
+    fn test_load_hcl_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.hcl");
+        fs::write(&config_path, "server {\n  port = 8080\n}\n").unwrap();
+
+        let source = FileSource::new(&config_path);
+        assert!(source.validate_extension().is_ok());
+
+        let map = source.load().unwrap();
+        let server = map["server"].clone().into_table().unwrap();
+        assert_eq!(server["port"].clone().into_int().unwrap(), 8080);
+    }
+
     #[test]
     fn test_validate_extension_unknown() {
         let source = FileSource::new("config.txt");
@@ -174,6 +419,46 @@ server:
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_load_optional_file() {
+        let source = FileSource::optional("/nonexistent/config.yaml");
+        assert!(!source.is_required());
+        assert!(source.load().unwrap().is_empty());
+
+        // An optional file that exists must still parse
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.yaml");
+        fs::write(&config_path, "port: [").unwrap();
+        assert!(FileSource::optional(&config_path).load().is_err());
+    }
+
+    #[test]
+    fn test_load_signed_reads_sidecar() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.yaml");
+        fs::write(&config_path, "port: 8080\n").unwrap();
+
+        let signed = FileSource::new(&config_path)
+            .load_signed()
+            .unwrap()
+            .unwrap();
+        assert_eq!(signed.content, b"port: 8080\n");
+        assert_eq!(signed.signature, None);
+        assert_eq!(signed.values["port"].clone().into_int().unwrap(), 8080);
+
+        let signature_path = signature_path(&config_path);
+        assert_eq!(signature_path, temp_dir.path().join("config.yaml.sig"));
+        fs::write(&signature_path, "c2lnbmF0dXJl").unwrap();
+        let signed = FileSource::new(&config_path)
+            .load_signed()
+            .unwrap()
+            .unwrap();
+        assert_eq!(signed.signature.as_deref(), Some(&b"c2lnbmF0dXJl"[..]));
+
+        let missing = FileSource::optional(temp_dir.path().join("local.yaml"));
+        assert!(missing.load_signed().unwrap().is_none());
+    }
+
     #[test]
     fn test_with_priority() {
         let source = FileSource::new("config.yaml").with_priority(200);
@@ -0,0 +1,667 @@
+//! Leader-based fetching of a remote source, for large fleets.
+//!
+//! Instead of every instance polling the config server, one elected leader
+//! fetches from the upstream source and publishes the result to its peers
+//! through a [`FleetTransport`]. Payloads carry the SHA-256 hash of their
+//! content, checked by every peer before the values are used.
+
+use super::{ChangeStream, ConfigSource, FailurePolicy};
+use crate::error::{ConfigError, Result};
+use axum::Router;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+/// Future returned by a [`FleetTransport`].
+pub type TransportFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T>> + Send + 'a>>;
+
+/// Configuration content distributed from the leader to its peers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FleetPayload {
+    /// The upstream source's values as JSON, with keys sorted
+    pub content: Vec<u8>,
+    /// Hex SHA-256 hash of `content`
+    pub hash: String,
+}
+
+impl FleetPayload {
+    /// Create a payload for `content`, computing its hash.
+    pub fn new(content: Vec<u8>) -> Self {
+        let hash = content_hash(&content);
+        Self { content, hash }
+    }
+
+    /// Create a payload holding `values`.
+    fn from_values(values: &HashMap<String, config::Value>) -> Result<Self> {
+        let json = serde_json::Value::Object(table_to_json(values));
+        let content = serde_json::to_vec(&json)
+            .map_err(|e| ConfigError::Other(format!("Failed to serialize fleet payload: {}", e)))?;
+        Ok(Self::new(content))
+    }
+
+    /// Check that the content matches its hash.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigError::LoadError`] if it doesn't, e.g. because the
+    /// content was truncated or tampered with on the way.
+    pub fn verify(&self) -> Result<()> {
+        let actual = content_hash(&self.content);
+        if actual != self.hash {
+            return Err(ConfigError::LoadError(format!(
+                "Fleet payload hash mismatch: expected {}, got {}",
+                self.hash, actual
+            )));
+        }
+        Ok(())
+    }
+
+    /// Check the hash and parse the values.
+    fn values(&self) -> Result<HashMap<String, config::Value>> {
+        self.verify()?;
+        let text = std::str::from_utf8(&self.content).map_err(|e| {
+            ConfigError::DeserializationError(format!("Fleet payload is not UTF-8: {}", e))
+        })?;
+        config::Config::builder()
+            .add_source(config::File::from_str(text, config::FileFormat::Json))
+            .build()
+            .and_then(|config| config.try_deserialize::<HashMap<String, config::Value>>())
+            .map_err(|e| {
+                ConfigError::DeserializationError(format!("Failed to parse fleet payload: {}", e))
+            })
+    }
+}
+
+/// Carries payloads from the leader of a fleet to its peers.
+///
+/// [`HttpFleetTransport`] serves them from the leader over HTTP; implement
+/// this trait to distribute them some other way, such as a shared cache or a
+/// message bus. Payloads are hash-checked by the receiving
+/// [`FleetSource`], so the transport needn't be trusted with their integrity.
+pub trait FleetTransport: Send + Sync {
+    /// Make `payload` available to peers.
+    ///
+    /// Called by the leader after every poll of the upstream source, so a
+    /// newly elected leader takes over publishing right away.
+    fn publish(&self, payload: FleetPayload) -> TransportFuture<'_, ()>;
+
+    /// Get the payload the leader last published, unless its hash is
+    /// `current`.
+    ///
+    /// Returns `None` if the payload hasn't changed, or nothing has been
+    /// published yet.
+    fn fetch<'a>(&'a self, current: Option<&'a str>) -> TransportFuture<'a, Option<FleetPayload>>;
+}
+
+/// Configuration source fetching an upstream source once per fleet.
+///
+/// Wraps a remote source such as an
+/// [`HttpSource`](crate::sources::HttpSource). On the instance for which the
+/// [leader election](FleetSourceBuilder::with_leader_election) check returns
+/// true, every poll loads the upstream source and publishes its values
+/// through the [`FleetTransport`]; every other instance polls the transport
+/// instead, so the config server sees one client however large the fleet.
+/// The values are identified by their content hash, which is also the
+/// source's [`content_version`](ConfigSource::content_version), so the whole
+/// fleet agrees on versions for
+/// [coordinated activation](crate::core::HotswapConfig::activate).
+///
+/// If the leader can't be reached or hasn't published anything yet when a
+/// peer connects, the peer loads the upstream source itself, so a fleet can
+/// start all at once. Afterwards, a failed poll keeps the current values and
+/// goes to the reload error handler. Signatures of the upstream content
+/// aren't checked by peers; the content hash covers the hop from the leader.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use hotswap_config::prelude::*;
+/// use hotswap_config::sources::{FleetSource, HttpFleetTransport, HttpSource};
+/// use serde::Deserialize;
+/// use std::sync::Arc;
+/// use std::sync::atomic::{AtomicBool, Ordering};
+/// use std::time::Duration;
+///
+/// #[derive(Debug, Deserialize, Clone)]
+/// struct AppConfig {
+///     port: u16,
+/// }
+///
+/// # async fn example(holds_lease: Arc<AtomicBool>) -> Result<()> {
+/// let upstream = HttpSource::builder()
+///     .with_url("https://config.example.com/api/config")
+///     .build()?;
+///
+/// // Serve `transport.router()` under `/fleet` on every instance; the
+/// // service routes peers to whichever instance holds the lease
+/// let transport = HttpFleetTransport::new("http://myapp-leader:8080/fleet/config");
+/// let source = FleetSource::builder(upstream, transport.clone())
+///     .with_leader_election(move || holds_lease.load(Ordering::Acquire))
+///     .with_poll_interval(Duration::from_secs(30))
+///     .connect()
+///     .await?;
+///
+/// let config = HotswapConfig::<AppConfig>::builder()
+///     .with_source(source)
+///     .build()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct FleetSource {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    upstream: Box<dyn ConfigSource>,
+    transport: Arc<dyn FleetTransport>,
+    is_leader: Box<dyn Fn() -> bool + Send + Sync>,
+    poll_interval: Option<Duration>,
+    /// The values currently served and their content hash
+    current: RwLock<(HashMap<String, config::Value>, Option<String>)>,
+}
+
+impl FleetSource {
+    /// Create a builder fetching `upstream` once per fleet, distributing it
+    /// through `transport`.
+    pub fn builder(
+        upstream: impl ConfigSource + 'static,
+        transport: impl FleetTransport + 'static,
+    ) -> FleetSourceBuilder {
+        FleetSourceBuilder::new(upstream, transport)
+    }
+
+    /// Check whether this instance is the fleet's leader right now.
+    pub fn is_leader(&self) -> bool {
+        (self.inner.is_leader)()
+    }
+
+    /// Get the content hash of the values currently served.
+    pub fn hash(&self) -> Option<String> {
+        self.inner.current.read().unwrap().1.clone()
+    }
+
+    /// Fetch the latest payload: from the upstream source, publishing it, on
+    /// the leader, and from the leader on its peers.
+    ///
+    /// Returns whether the values changed.
+    async fn poll(&self) -> Result<bool> {
+        if self.is_leader() {
+            let payload = self.fetch_upstream().await?;
+            self.inner.transport.publish(payload.clone()).await?;
+            return self.store(payload);
+        }
+        let current = self.hash();
+        match self.inner.transport.fetch(current.as_deref()).await? {
+            Some(payload) => self.store(payload),
+            None => Ok(false),
+        }
+    }
+
+    /// Load the upstream source into a payload.
+    async fn fetch_upstream(&self) -> Result<FleetPayload> {
+        let inner = Arc::clone(&self.inner);
+        let values = tokio::task::spawn_blocking(move || inner.upstream.load())
+            .await
+            .map_err(|e| {
+                ConfigError::LoadError(format!("Loading {} panicked: {}", self.name(), e))
+            })??;
+        FleetPayload::from_values(&values)
+    }
+
+    /// Serve the values of `payload` if its hash differs from the current
+    /// one, returning whether it did.
+    ///
+    /// The leader parses its own payload too, so it serves exactly what its
+    /// peers do.
+    fn store(&self, payload: FleetPayload) -> Result<bool> {
+        if self.hash().as_deref() == Some(payload.hash.as_str()) {
+            return Ok(false);
+        }
+        let values = payload.values()?;
+        *self.inner.current.write().unwrap() = (values, Some(payload.hash));
+        Ok(true)
+    }
+}
+
+impl ConfigSource for FleetSource {
+    fn load(&self) -> Result<HashMap<String, config::Value>> {
+        Ok(self.inner.current.read().unwrap().0.clone())
+    }
+
+    fn name(&self) -> String {
+        format!("fleet:{}", self.inner.upstream.name())
+    }
+
+    fn priority(&self) -> i32 {
+        self.inner.upstream.priority()
+    }
+
+    fn failure_policy(&self) -> FailurePolicy {
+        self.inner.upstream.failure_policy()
+    }
+
+    fn content_version(&self) -> Option<String> {
+        self.hash()
+    }
+
+    fn watch(&self) -> Option<ChangeStream> {
+        let interval = self.inner.poll_interval?;
+        let (notifier, changes) = ChangeStream::channel();
+        let source = self.clone();
+        tokio::spawn(async move {
+            let mut ticks =
+                tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+            ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                tokio::select! {
+                    _ = ticks.tick() => {}
+                    _ = notifier.closed() => break,
+                }
+                let open = match source.poll().await {
+                    Ok(true) => notifier.changed(),
+                    Ok(false) => true,
+                    Err(e) => notifier.error(e),
+                };
+                if !open {
+                    break;
+                }
+            }
+        });
+        Some(changes)
+    }
+}
+
+/// Builder for constructing a `FleetSource`.
+pub struct FleetSourceBuilder {
+    upstream: Box<dyn ConfigSource>,
+    transport: Arc<dyn FleetTransport>,
+    is_leader: Box<dyn Fn() -> bool + Send + Sync>,
+    poll_interval: Option<Duration>,
+}
+
+impl FleetSourceBuilder {
+    /// Create a new builder fetching `upstream` once per fleet, distributing
+    /// it through `transport`.
+    pub fn new(
+        upstream: impl ConfigSource + 'static,
+        transport: impl FleetTransport + 'static,
+    ) -> Self {
+        Self {
+            upstream: Box::new(upstream),
+            transport: Arc::new(transport),
+            is_leader: Box::new(|| false),
+            poll_interval: None,
+        }
+    }
+
+    /// Decide whether this instance is the leader, checked on every poll.
+    ///
+    /// Back it with whatever elects a leader in your deployment, such as a
+    /// Kubernetes lease or a lock in a coordination service. Without it, this
+    /// instance never leads and always fetches from the leader.
+    pub fn with_leader_election<F>(mut self, is_leader: F) -> Self
+    where
+        F: Fn() -> bool + Send + Sync + 'static,
+    {
+        self.is_leader = Box::new(is_leader);
+        self
+    }
+
+    /// Poll at this interval: the upstream source on the leader, and the
+    /// transport on its peers.
+    ///
+    /// Without a poll interval the values are only fetched once, at connect.
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = Some(interval);
+        self
+    }
+
+    /// Fetch the values and create the source.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the leader fails to load the upstream source, or a
+    /// peer can get the values neither from the leader nor from the upstream
+    /// source.
+    pub async fn connect(self) -> Result<FleetSource> {
+        let source = FleetSource {
+            inner: Arc::new(Inner {
+                upstream: self.upstream,
+                transport: self.transport,
+                is_leader: self.is_leader,
+                poll_interval: self.poll_interval,
+                current: RwLock::new((HashMap::new(), None)),
+            }),
+        };
+
+        match source.poll().await {
+            Ok(_) if source.hash().is_some() => {}
+            Err(e) if source.is_leader() => return Err(e),
+            // Start from the upstream source until the leader has published
+            _unavailable => {
+                #[cfg(feature = "tracing")]
+                if let Err(e) = &_unavailable {
+                    tracing::warn!(
+                        source = %source.name(),
+                        error = %e,
+                        "Fleet leader unavailable, loading upstream source directly"
+                    );
+                }
+                let payload = source.fetch_upstream().await?;
+                source.store(payload)?;
+            }
+        }
+        Ok(source)
+    }
+}
+
+/// [`FleetTransport`] serving payloads from the leader over HTTP.
+///
+/// The leader serves its last published payload from
+/// [`router`](Self::router), mounted on the application's own server, with
+/// the content hash as its `ETag`. Peers fetch it from the leader's URL,
+/// typically a service that routes to whichever instance is leading, with
+/// `If-None-Match` on their current hash, so an unchanged payload costs a
+/// `304`.
+///
+/// Every instance serves the router, since any of them may be elected;
+/// instances that haven't published answer `404`.
+///
+/// # Examples
+///
+/// ```rust
+/// use hotswap_config::sources::HttpFleetTransport;
+///
+/// let transport = HttpFleetTransport::new("http://myapp-leader:8080/fleet/config");
+/// // Mount under your own server, e.g. `Router::new().nest("/fleet", app)`
+/// let app = transport.router();
+/// # let _ = app;
+/// ```
+#[derive(Clone)]
+pub struct HttpFleetTransport {
+    leader_url: String,
+    client: reqwest::Client,
+    published: Arc<RwLock<Option<FleetPayload>>>,
+}
+
+impl HttpFleetTransport {
+    /// Create a transport fetching from the leader at `leader_url`.
+    pub fn new(leader_url: impl Into<String>) -> Self {
+        Self {
+            leader_url: leader_url.into(),
+            client: reqwest::Client::new(),
+            published: Arc::default(),
+        }
+    }
+
+    /// Use `client` to fetch from the leader, e.g. to set timeouts or TLS
+    /// options.
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Build the router serving the published payload at `/config`.
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/config", get(serve_payload))
+            .with_state(Arc::clone(&self.published))
+    }
+
+    fn error(&self, error: impl std::fmt::Display) -> ConfigError {
+        ConfigError::LoadError(format!(
+            "Failed to fetch from fleet leader {}: {}",
+            self.leader_url, error
+        ))
+    }
+}
+
+impl FleetTransport for HttpFleetTransport {
+    fn publish(&self, payload: FleetPayload) -> TransportFuture<'_, ()> {
+        *self.published.write().unwrap() = Some(payload);
+        Box::pin(async { Ok(()) })
+    }
+
+    fn fetch<'a>(&'a self, current: Option<&'a str>) -> TransportFuture<'a, Option<FleetPayload>> {
+        Box::pin(async move {
+            let mut request = self.client.get(&self.leader_url);
+            if let Some(hash) = current {
+                request = request.header(header::IF_NONE_MATCH, format!("\"{}\"", hash));
+            }
+            let response = request.send().await.map_err(|e| self.error(e))?;
+            match response.status() {
+                StatusCode::NOT_MODIFIED | StatusCode::NOT_FOUND => return Ok(None),
+                status if !status.is_success() => return Err(self.error(status)),
+                _ => {}
+            }
+
+            let hash = response
+                .headers()
+                .get(header::ETAG)
+                .and_then(|e_tag| e_tag.to_str().ok())
+                .map(|e_tag| e_tag.trim_matches('"').to_string())
+                .ok_or_else(|| self.error("no content hash in the response"))?;
+            let content = response.bytes().await.map_err(|e| self.error(e))?;
+            Ok(Some(FleetPayload {
+                content: content.to_vec(),
+                hash,
+            }))
+        })
+    }
+}
+
+/// Serve the published payload, or `304` if the peer already has it.
+async fn serve_payload(
+    State(published): State<Arc<RwLock<Option<FleetPayload>>>>,
+    headers: HeaderMap,
+) -> Response {
+    let Some(payload) = published.read().unwrap().clone() else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let e_tag = format!("\"{}\"", payload.hash);
+    if headers
+        .get(header::IF_NONE_MATCH)
+        .is_some_and(|value| value.as_bytes() == e_tag.as_bytes())
+    {
+        return StatusCode::NOT_MODIFIED.into_response();
+    }
+    (
+        [
+            (header::ETAG, e_tag),
+            (header::CONTENT_TYPE, "application/json".to_string()),
+        ],
+        payload.content,
+    )
+        .into_response()
+}
+
+/// Hex SHA-256 hash of `content`.
+fn content_hash(content: &[u8]) -> String {
+    Sha256::digest(content)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Convert a table to JSON with its keys in sorted order, so equal values
+/// always produce the same content.
+fn table_to_json<'a>(
+    table: impl IntoIterator<Item = (&'a String, &'a config::Value)>,
+) -> serde_json::Map<String, serde_json::Value> {
+    let mut entries: Vec<_> = table.into_iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    entries
+        .into_iter()
+        .map(|(key, value)| (key.clone(), value_to_json(value)))
+        .collect()
+}
+
+fn value_to_json(value: &config::Value) -> serde_json::Value {
+    use config::ValueKind;
+    use serde_json::json;
+
+    match &value.kind {
+        ValueKind::Nil => serde_json::Value::Null,
+        ValueKind::Boolean(b) => json!(b),
+        ValueKind::I64(n) => json!(n),
+        ValueKind::I128(n) => json!(n),
+        ValueKind::U64(n) => json!(n),
+        ValueKind::U128(n) => json!(n),
+        ValueKind::Float(f) => json!(f),
+        ValueKind::String(s) => json!(s),
+        ValueKind::Table(table) => serde_json::Value::Object(table_to_json(table)),
+        ValueKind::Array(items) => {
+            serde_json::Value::Array(items.iter().map(value_to_json).collect())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use tower::ServiceExt;
+
+    /// Upstream source counting how often it is loaded.
+    #[derive(Clone, Default)]
+    struct Upstream {
+        port: Arc<Mutex<i64>>,
+        loads: Arc<AtomicUsize>,
+    }
+
+    impl ConfigSource for Upstream {
+        fn load(&self) -> Result<HashMap<String, config::Value>> {
+            self.loads.fetch_add(1, Ordering::SeqCst);
+            let port = *self.port.lock().unwrap();
+            Ok(HashMap::from([
+                ("port".to_string(), port.into()),
+                ("hosts".to_string(), vec!["a", "b"].into()),
+            ]))
+        }
+
+        fn name(&self) -> String {
+            "upstream".to_string()
+        }
+    }
+
+    /// Transport holding the published payload in memory.
+    #[derive(Clone, Default)]
+    struct Shared(Arc<Mutex<Option<FleetPayload>>>);
+
+    impl FleetTransport for Shared {
+        fn publish(&self, payload: FleetPayload) -> TransportFuture<'_, ()> {
+            *self.0.lock().unwrap() = Some(payload);
+            Box::pin(async { Ok(()) })
+        }
+
+        fn fetch<'a>(
+            &'a self,
+            current: Option<&'a str>,
+        ) -> TransportFuture<'a, Option<FleetPayload>> {
+            let payload = self.0.lock().unwrap().clone();
+            Box::pin(async move { Ok(payload.filter(|p| Some(p.hash.as_str()) != current)) })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_peers_fetch_from_leader() {
+        let upstream = Upstream::default();
+        *upstream.port.lock().unwrap() = 8080;
+        let transport = Shared::default();
+        let leading = Arc::new(AtomicBool::new(true));
+
+        let is_leader = Arc::clone(&leading);
+        let leader = FleetSource::builder(upstream.clone(), transport.clone())
+            .with_leader_election(move || is_leader.load(Ordering::SeqCst))
+            .connect()
+            .await
+            .unwrap();
+        let peer = FleetSource::builder(upstream.clone(), transport.clone())
+            .connect()
+            .await
+            .unwrap();
+        assert_eq!(upstream.loads.load(Ordering::SeqCst), 1);
+        assert_eq!(peer.load().unwrap(), leader.load().unwrap());
+        assert_eq!(peer.content_version(), leader.content_version());
+
+        *upstream.port.lock().unwrap() = 9090;
+        assert!(leader.poll().await.unwrap());
+        assert!(peer.poll().await.unwrap());
+        assert!(!peer.poll().await.unwrap());
+        assert_eq!(upstream.loads.load(Ordering::SeqCst), 2);
+        assert_eq!(
+            peer.load().unwrap()["port"].clone().into_int().unwrap(),
+            9090
+        );
+
+        // A payload that doesn't match its hash is rejected
+        let mut tampered = FleetPayload::new(br#"{"port":8080}"#.to_vec());
+        tampered.content = br#"{"port":1}"#.to_vec();
+        *transport.0.lock().unwrap() = Some(tampered);
+        assert!(peer.poll().await.is_err());
+        assert_eq!(
+            peer.load().unwrap()["port"].clone().into_int().unwrap(),
+            9090
+        );
+    }
+
+    #[tokio::test]
+    async fn test_peer_starts_from_upstream_without_leader() {
+        let upstream = Upstream::default();
+        let peer = FleetSource::builder(upstream.clone(), Shared::default())
+            .connect()
+            .await
+            .unwrap();
+        assert_eq!(upstream.loads.load(Ordering::SeqCst), 1);
+        assert!(peer.hash().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_http_router_serves_payload() {
+        let transport = HttpFleetTransport::new("http://leader/fleet/config");
+        let get = |if_none_match: Option<String>| {
+            let mut request = axum::http::Request::get("/config");
+            if let Some(e_tag) = if_none_match {
+                request = request.header(header::IF_NONE_MATCH, e_tag);
+            }
+            transport
+                .router()
+                .oneshot(request.body(axum::body::Body::empty()).unwrap())
+        };
+        assert_eq!(get(None).await.unwrap().status(), StatusCode::NOT_FOUND);
+
+        let payload = FleetPayload::new(br#"{"port":8080}"#.to_vec());
+        transport.publish(payload.clone()).await.unwrap();
+        let response = get(None).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let e_tag = response.headers()[header::ETAG]
+            .to_str()
+            .unwrap()
+            .to_string();
+        assert_eq!(e_tag, format!("\"{}\"", payload.hash));
+
+        let response = get(Some(e_tag)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    }
+
+    #[test]
+    fn test_payload_is_canonical() {
+        let values = HashMap::from([
+            ("b".to_string(), config::Value::from(1)),
+            ("a".to_string(), config::Value::from("x")),
+        ]);
+        let payload = FleetPayload::from_values(&values).unwrap();
+        assert_eq!(payload.content, br#"{"a":"x","b":1}"#);
+        assert_eq!(payload.values().unwrap(), values);
+    }
+}
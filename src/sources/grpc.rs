@@ -0,0 +1,750 @@
+//! gRPC streaming configuration source.
+//!
+//! The client opens a bidirectional `StreamConfig` stream to a config server,
+//! xDS style: the server pushes versioned configs, and the client answers each
+//! one with an ACK once it has been validated and swapped in, or a NACK
+//! carrying the error if it was rejected. The protocol is defined in
+//! `proto/config_discovery.proto`; the messages are in [`proto`].
+
+use super::{ChangeNotifier, ChangeStream, ConfigSource, FailurePolicy, SignedContent};
+use crate::error::{ConfigError, Result};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::Streaming;
+use tonic::transport::Endpoint;
+
+/// Delay before the first reconnect attempt after the stream fails.
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+/// Upper bound for the reconnect delay.
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Messages and client for the `ConfigDiscovery` service.
+///
+/// Servers written in Rust can use these messages with
+/// `tonic_prost::ProstCodec` to serve [`METHOD_PATH`].
+pub mod proto {
+    use tonic::codegen::http;
+
+    /// Fully qualified service name.
+    pub const SERVICE_NAME: &str = "hotswap_config.v1.ConfigDiscovery";
+    /// Path of the bidirectional streaming method.
+    pub const METHOD_PATH: &str = "/hotswap_config.v1.ConfigDiscovery/StreamConfig";
+
+    /// Sent by the client when the stream opens and in reply to every push.
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct ConfigRequest {
+        /// Identifies the client to the server
+        #[prost(string, tag = "1")]
+        pub node_id: String,
+        /// The version currently applied, 0 if none
+        #[prost(uint64, tag = "2")]
+        pub version: u64,
+        /// Nonce of the push being acknowledged, empty on the initial request
+        #[prost(string, tag = "3")]
+        pub response_nonce: String,
+        /// Why the push was rejected; empty for an ACK
+        #[prost(string, tag = "4")]
+        pub error_detail: String,
+    }
+
+    /// A config version pushed by the server.
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct ConfigResponse {
+        /// Monotonically increasing version number
+        #[prost(uint64, tag = "1")]
+        pub version: u64,
+        /// Opaque value echoed back in the ACK or NACK
+        #[prost(string, tag = "2")]
+        pub nonce: String,
+        /// Payload format: `json` (the default if empty), `yaml`, or `toml`
+        #[prost(string, tag = "3")]
+        pub format: String,
+        /// The complete configuration document
+        #[prost(bytes = "vec", tag = "4")]
+        pub payload: Vec<u8>,
+        /// Detached ed25519 signature of `payload`, empty if unsigned
+        #[prost(bytes = "vec", tag = "5")]
+        pub signature: Vec<u8>,
+    }
+
+    /// Client for the `ConfigDiscovery` service.
+    #[derive(Debug, Clone)]
+    pub(crate) struct ConfigDiscoveryClient {
+        inner: tonic::client::Grpc<tonic::transport::Channel>,
+    }
+
+    impl ConfigDiscoveryClient {
+        pub(crate) fn new(channel: tonic::transport::Channel) -> Self {
+            Self {
+                inner: tonic::client::Grpc::new(channel),
+            }
+        }
+
+        /// Open the config stream, sending `request` messages upstream.
+        pub(crate) async fn stream_config(
+            &mut self,
+            request: impl tonic::IntoStreamingRequest<Message = ConfigRequest>,
+        ) -> Result<tonic::Response<tonic::Streaming<ConfigResponse>>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| tonic::Status::unavailable(format!("Service was not ready: {}", e)))?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(METHOD_PATH);
+            self.inner
+                .streaming(request.into_streaming_request(), path, codec)
+                .await
+        }
+    }
+}
+
+use proto::{ConfigDiscoveryClient, ConfigRequest, ConfigResponse};
+
+/// gRPC-based configuration source receiving pushed config versions.
+///
+/// [`connect`](GrpcSourceBuilder::connect) opens the stream and waits for the
+/// server's first push, which becomes the source's values for the initial
+/// build. Once the source is [watched](ConfigSource::watch), every later
+/// push goes through the same validation and swap as a file reload: the
+/// server gets an ACK once the new version is live, or a NACK with the error
+/// while the previous version stays in place. If the stream
+/// drops, the client reconnects with backoff, reporting the current version
+/// so the server can resume.
+///
+/// Loading the source never touches the network; it returns the last
+/// accepted push.
+///
+/// With [signature verification](crate::core::HotswapConfigBuilder::with_signature_verification),
+/// each push must carry a valid `signature` of its payload; unsigned or
+/// badly signed pushes are NACKed.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use hotswap_config::prelude::*;
+/// use hotswap_config::sources::GrpcSource;
+/// use serde::Deserialize;
+///
+/// #[derive(Debug, Deserialize, Clone)]
+/// struct AppConfig {
+///     port: u16,
+/// }
+///
+/// # async fn example() -> Result<()> {
+/// let source = GrpcSource::builder()
+///     .with_endpoint("http://config.internal:50051")
+///     .with_node_id("api-1")
+///     .connect()
+///     .await?;
+///
+/// let config = HotswapConfig::<AppConfig>::builder()
+///     .with_file("config/default.yaml")
+///     .with_grpc(source)
+///     .build()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct GrpcSource {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    endpoint: String,
+    node_id: String,
+    priority: i32,
+    failure_policy: FailurePolicy,
+    client: ConfigDiscoveryClient,
+    /// The last accepted push
+    current: RwLock<Pushed>,
+    /// The stream opened by `connect`, taken over by the watch task
+    initial: Mutex<Option<Connection>>,
+}
+
+#[derive(Clone)]
+struct Pushed {
+    version: u64,
+    values: HashMap<String, config::Value>,
+    /// The raw push, kept for signature verification
+    payload: Vec<u8>,
+    signature: Vec<u8>,
+}
+
+impl Pushed {
+    fn parse(response: &ConfigResponse) -> Result<Self> {
+        Ok(Self {
+            version: response.version,
+            values: parse_payload(&response.format, &response.payload)?,
+            payload: response.payload.clone(),
+            signature: response.signature.clone(),
+        })
+    }
+}
+
+/// An open stream: the sender for ACKs/NACKs and the incoming pushes.
+struct Connection {
+    requests: mpsc::Sender<ConfigRequest>,
+    responses: Streaming<ConfigResponse>,
+    /// A push received but not yet acknowledged
+    pending_nonce: Option<String>,
+}
+
+impl GrpcSource {
+    /// Create a new builder for constructing a gRPC source.
+    pub fn builder() -> GrpcSourceBuilder {
+        GrpcSourceBuilder::new()
+    }
+
+    /// Get the version of the last accepted push.
+    pub fn version(&self) -> u64 {
+        self.inner.current.read().unwrap().version
+    }
+
+    /// Open a stream, announcing `version` as the one currently applied.
+    async fn open(
+        client: &ConfigDiscoveryClient,
+        node_id: &str,
+        version: u64,
+    ) -> Result<Connection> {
+        let (requests, rx) = mpsc::channel(16);
+        let hello = ConfigRequest {
+            node_id: node_id.to_string(),
+            version,
+            ..Default::default()
+        };
+        // The channel was just created with spare capacity
+        let _ = requests.try_send(hello);
+
+        let responses = client
+            .clone()
+            .stream_config(ReceiverStream::new(rx))
+            .await
+            .map_err(status_error)?
+            .into_inner();
+        Ok(Connection {
+            requests,
+            responses,
+            pending_nonce: None,
+        })
+    }
+
+    /// Apply pushes until the stream of changes is dropped, reconnecting
+    /// with backoff whenever the gRPC stream fails.
+    ///
+    /// Stream failures and pushes that fail to parse go to `notifier` as
+    /// errors. The push received by `connect` is acknowledged first, since
+    /// the initial build has already accepted it.
+    async fn run(&self, notifier: &ChangeNotifier) {
+        let mut connection = self.inner.initial.lock().unwrap().take();
+        let mut backoff = INITIAL_BACKOFF;
+        loop {
+            let conn = match connection.take() {
+                Some(conn) => conn,
+                None => match Self::open(&self.inner.client, &self.inner.node_id, self.version())
+                    .await
+                {
+                    Ok(conn) => conn,
+                    Err(e) => {
+                        if !notifier.error(e) {
+                            return;
+                        }
+                        tokio::time::sleep(backoff).await;
+                        backoff = (backoff * 2).min(MAX_BACKOFF);
+                        continue;
+                    }
+                },
+            };
+
+            let Some(error) = self.serve(conn, notifier, &mut backoff).await else {
+                return;
+            };
+            #[cfg(feature = "tracing")]
+            tracing::warn!(
+                endpoint = %self.inner.endpoint,
+                error = %error,
+                "gRPC config stream failed, reconnecting"
+            );
+            if !notifier.error(error) {
+                return;
+            }
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(MAX_BACKOFF);
+        }
+    }
+
+    /// Handle pushes on one stream, returning the error that ended it, or
+    /// `None` if the stream of changes was dropped.
+    async fn serve(
+        &self,
+        mut conn: Connection,
+        notifier: &ChangeNotifier,
+        backoff: &mut Duration,
+    ) -> Option<ConfigError> {
+        if let Some(nonce) = conn.pending_nonce.take() {
+            self.reply(&conn, nonce, None).await;
+        }
+
+        loop {
+            let response = match conn.responses.message().await {
+                Ok(Some(response)) => response,
+                Ok(None) => {
+                    return Some(ConfigError::LoadError(format!(
+                        "gRPC config stream from {} closed by server",
+                        self.inner.endpoint
+                    )));
+                }
+                Err(status) => return Some(status_error(status)),
+            };
+            *backoff = INITIAL_BACKOFF;
+
+            // Re-sent versions, e.g. after a reconnect, are already applied
+            let result = if response.version == self.version() {
+                Ok(())
+            } else {
+                self.apply(&response, notifier).await?
+            };
+            #[cfg(feature = "tracing")]
+            if let Err(e) = &result {
+                tracing::warn!(
+                    endpoint = %self.inner.endpoint,
+                    version = response.version,
+                    error = %e,
+                    "Rejected pushed config version"
+                );
+            }
+            self.reply(&conn, response.nonce, result.err()).await;
+        }
+    }
+
+    /// Swap in a pushed version, restoring the previous one if the reload
+    /// fails.
+    ///
+    /// Returns `None` if the stream of changes was dropped before reloading.
+    async fn apply(
+        &self,
+        response: &ConfigResponse,
+        notifier: &ChangeNotifier,
+    ) -> Option<Result<()>> {
+        let pushed = match Pushed::parse(response) {
+            Ok(pushed) => pushed,
+            Err(e) => {
+                // Failed reloads are reported by the reload itself, but this
+                // push never gets that far
+                notifier.error(e.duplicate());
+                return Some(Err(e));
+            }
+        };
+        let previous = std::mem::replace(&mut *self.inner.current.write().unwrap(), pushed);
+
+        let result = notifier.changed_and_wait().await;
+        if let Some(Ok(())) = result {
+            #[cfg(feature = "tracing")]
+            tracing::info!(
+                endpoint = %self.inner.endpoint,
+                version = response.version,
+                "Applied pushed config version"
+            );
+        } else {
+            *self.inner.current.write().unwrap() = previous;
+        }
+        result
+    }
+
+    /// Send an ACK, or a NACK if `error` is set, for the push with `nonce`.
+    async fn reply(&self, conn: &Connection, nonce: String, error: Option<ConfigError>) {
+        let request = ConfigRequest {
+            node_id: self.inner.node_id.clone(),
+            version: self.version(),
+            response_nonce: nonce,
+            error_detail: error.map(|e| e.to_string()).unwrap_or_default(),
+        };
+        // A closed stream surfaces as an error on the next receive
+        let _ = conn.requests.send(request).await;
+    }
+}
+
+impl ConfigSource for GrpcSource {
+    fn load(&self) -> Result<HashMap<String, config::Value>> {
+        Ok(self.inner.current.read().unwrap().values.clone())
+    }
+
+    fn name(&self) -> String {
+        format!("grpc:{}", self.inner.endpoint)
+    }
+
+    fn priority(&self) -> i32 {
+        self.inner.priority
+    }
+
+    fn failure_policy(&self) -> FailurePolicy {
+        self.inner.failure_policy
+    }
+
+    fn load_signed(&self) -> Result<Option<SignedContent>> {
+        let current = self.inner.current.read().unwrap();
+        Ok(Some(SignedContent {
+            content: current.payload.clone(),
+            signature: (!current.signature.is_empty()).then(|| current.signature.clone()),
+            values: current.values.clone(),
+        }))
+    }
+
+    fn content_version(&self) -> Option<String> {
+        let version = self.version();
+        (version != 0).then(|| version.to_string())
+    }
+
+    fn watch(&self) -> Option<ChangeStream> {
+        let (notifier, changes) = ChangeStream::channel();
+        let source = self.clone();
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = source.run(&notifier) => {}
+                _ = notifier.closed() => {}
+            }
+        });
+        Some(changes)
+    }
+}
+
+/// Builder for constructing a `GrpcSource`.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use hotswap_config::sources::GrpcSource;
+/// use std::time::Duration;
+///
+/// # async fn example() -> hotswap_config::error::Result<()> {
+/// let source = GrpcSource::builder()
+///     .with_endpoint("http://config.internal:50051")
+///     .with_node_id("api-1")
+///     .with_timeout(Duration::from_secs(5))
+///     .with_priority(250)
+///     .connect()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct GrpcSourceBuilder {
+    endpoint: Option<String>,
+    node_id: Option<String>,
+    timeout: Duration,
+    priority: i32,
+    failure_policy: FailurePolicy,
+}
+
+impl GrpcSourceBuilder {
+    /// Create a new builder with default settings.
+    pub fn new() -> Self {
+        Self {
+            endpoint: None,
+            node_id: None,
+            timeout: Duration::from_secs(10),
+            priority: 250, // Same as HTTP sources: above files, below env vars
+            failure_policy: FailurePolicy::Required,
+        }
+    }
+
+    /// Set the server URI, e.g. `http://config.internal:50051`.
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = Some(endpoint.into());
+        self
+    }
+
+    /// Set the node ID sent to the server.
+    ///
+    /// Defaults to the `HOSTNAME` environment variable, or `unknown`.
+    pub fn with_node_id(mut self, node_id: impl Into<String>) -> Self {
+        self.node_id = Some(node_id.into());
+        self
+    }
+
+    /// Set how long `connect` waits to connect and receive the first push.
+    ///
+    /// Defaults to 10 seconds.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set the priority for this source.
+    ///
+    /// Defaults to 250.
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Set what the loader does when this source fails to load.
+    ///
+    /// Default is [`FailurePolicy::Required`], which fails the whole load.
+    /// [`FailurePolicy::FallbackToCache`] keeps serving the values from the
+    /// last successful load, so a flaky endpoint doesn't block reloads of
+    /// other sources.
+    pub fn with_failure_policy(mut self, policy: FailurePolicy) -> Self {
+        self.failure_policy = policy;
+        self
+    }
+
+    /// Connect and wait for the server's first push.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no endpoint is set, the server is unreachable, no
+    /// config arrives within the timeout, or the first push fails to parse.
+    pub async fn connect(self) -> Result<GrpcSource> {
+        let endpoint = self
+            .endpoint
+            .ok_or_else(|| ConfigError::LoadError("gRPC endpoint is required".to_string()))?;
+        let node_id = self
+            .node_id
+            .or_else(|| std::env::var("HOSTNAME").ok())
+            .unwrap_or_else(|| "unknown".to_string());
+
+        let channel = Endpoint::from_shared(endpoint.clone())
+            .map_err(|e| ConfigError::LoadError(format!("Invalid gRPC endpoint: {}", e)))?
+            .connect_timeout(self.timeout)
+            .connect_lazy();
+        let client = ConfigDiscoveryClient::new(channel);
+
+        let first = tokio::time::timeout(self.timeout, async {
+            let mut conn = GrpcSource::open(&client, &node_id, 0).await?;
+            let response = conn
+                .responses
+                .message()
+                .await
+                .map_err(status_error)?
+                .ok_or_else(|| {
+                    ConfigError::LoadError(format!(
+                        "gRPC config stream from {} closed before the first push",
+                        endpoint
+                    ))
+                })?;
+            conn.pending_nonce = Some(response.nonce.clone());
+            Ok::<_, ConfigError>((conn, response))
+        })
+        .await
+        .map_err(|_| {
+            ConfigError::LoadError(format!(
+                "Timed out waiting for config from gRPC endpoint {}",
+                endpoint
+            ))
+        })??;
+        let (conn, response) = first;
+
+        let current = Pushed::parse(&response)?;
+        Ok(GrpcSource {
+            inner: Arc::new(Inner {
+                endpoint,
+                node_id,
+                priority: self.priority,
+                failure_policy: self.failure_policy,
+                client,
+                current: RwLock::new(current),
+                initial: Mutex::new(Some(conn)),
+            }),
+        })
+    }
+}
+
+impl Default for GrpcSourceBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parse a pushed config document into top-level values.
+fn parse_payload(format: &str, payload: &[u8]) -> Result<HashMap<String, config::Value>> {
+    let format = match format {
+        "" | "json" => config::FileFormat::Json,
+        "yaml" | "yml" => config::FileFormat::Yaml,
+        "toml" => config::FileFormat::Toml,
+        other => {
+            return Err(ConfigError::DeserializationError(format!(
+                "Unsupported pushed config format: {}",
+                other
+            )));
+        }
+    };
+    let text = std::str::from_utf8(payload).map_err(|e| {
+        ConfigError::DeserializationError(format!("Pushed config is not UTF-8: {}", e))
+    })?;
+
+    config::Config::builder()
+        .add_source(config::File::from_str(text, format))
+        .build()
+        .and_then(|config| config.try_deserialize::<HashMap<String, config::Value>>())
+        .map_err(|e| {
+            ConfigError::DeserializationError(format!("Failed to parse pushed config: {}", e))
+        })
+}
+
+/// Convert a gRPC status into a load error.
+fn status_error(status: tonic::Status) -> ConfigError {
+    ConfigError::LoadError(format!("gRPC config stream failed: {}", status.message()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::HotswapConfig;
+    use crate::error::ValidationError;
+    use serde::Deserialize;
+    use std::convert::Infallible;
+    use std::task::{Context, Poll};
+    use tokio_stream::StreamExt;
+    use tonic::codegen::{BoxFuture, Service, http};
+    use tonic::server::NamedService;
+
+    #[derive(Debug, Clone, Deserialize)]
+    struct TestConfig {
+        port: u16,
+    }
+
+    /// Serves one stream, pushing what is sent on `pushes` and forwarding
+    /// the client's requests to `replies`.
+    #[derive(Clone)]
+    struct TestServer {
+        pushes: Arc<Mutex<Option<mpsc::Receiver<ConfigResponse>>>>,
+        replies: mpsc::UnboundedSender<ConfigRequest>,
+    }
+
+    impl Service<http::Request<tonic::body::Body>> for TestServer {
+        type Response = http::Response<tonic::body::Body>;
+        type Error = Infallible;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+
+        fn poll_ready(
+            &mut self,
+            _cx: &mut Context<'_>,
+        ) -> Poll<std::result::Result<(), Infallible>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, request: http::Request<tonic::body::Body>) -> Self::Future {
+            let server = self.clone();
+            let stream_config =
+                tower::service_fn(move |request: tonic::Request<Streaming<ConfigRequest>>| {
+                    let server = server.clone();
+                    async move {
+                        let mut requests = request.into_inner();
+                        tokio::spawn(async move {
+                            while let Ok(Some(request)) = requests.message().await {
+                                let _ = server.replies.send(request);
+                            }
+                        });
+                        let pushes = server.pushes.lock().unwrap().take().unwrap();
+                        let stream = ReceiverStream::new(pushes).map(Ok::<_, tonic::Status>);
+                        Ok::<_, tonic::Status>(tonic::Response::new(stream))
+                    }
+                });
+            Box::pin(async move {
+                let mut grpc = tonic::server::Grpc::new(tonic_prost::ProstCodec::default());
+                Ok(grpc.streaming(stream_config, request).await)
+            })
+        }
+    }
+
+    impl NamedService for TestServer {
+        const NAME: &'static str = proto::SERVICE_NAME;
+    }
+
+    fn push(version: u64, payload: &str) -> ConfigResponse {
+        ConfigResponse {
+            version,
+            nonce: format!("nonce-{}", version),
+            format: String::new(),
+            payload: payload.as_bytes().to_vec(),
+            signature: Vec::new(),
+        }
+    }
+
+    async fn next_reply(replies: &mut mpsc::UnboundedReceiver<ConfigRequest>) -> ConfigRequest {
+        tokio::time::timeout(Duration::from_secs(5), replies.recv())
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_payload_formats() {
+        let json = parse_payload("", br#"{"port": 8080}"#).unwrap();
+        assert_eq!(json["port"].clone().into_int().unwrap(), 8080);
+
+        let yaml = parse_payload("yaml", b"port: 9090").unwrap();
+        assert_eq!(yaml["port"].clone().into_int().unwrap(), 9090);
+
+        assert!(matches!(
+            parse_payload("ini", b"port = 1"),
+            Err(ConfigError::DeserializationError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_pushes_are_validated_and_acknowledged() {
+        let (push_tx, push_rx) = mpsc::channel(8);
+        let (reply_tx, mut replies) = mpsc::unbounded_channel();
+        let server = TestServer {
+            pushes: Arc::new(Mutex::new(Some(push_rx))),
+            replies: reply_tx,
+        };
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(server)
+                .serve_with_incoming(tokio_stream::wrappers::TcpListenerStream::new(listener)),
+        );
+
+        push_tx.send(push(1, r#"{"port": 8080}"#)).await.unwrap();
+        let source = GrpcSource::builder()
+            .with_endpoint(format!("http://{}", addr))
+            .with_node_id("test-node")
+            .connect()
+            .await
+            .unwrap();
+        assert_eq!(source.version(), 1);
+
+        let hello = next_reply(&mut replies).await;
+        assert_eq!(hello.node_id, "test-node");
+        assert_eq!(hello.version, 0);
+
+        let config = HotswapConfig::<TestConfig>::builder()
+            .with_source(source.clone())
+            .with_validation(|config: &TestConfig| {
+                if config.port == 0 {
+                    return Err(ValidationError::custom("port must be non-zero"));
+                }
+                Ok(())
+            })
+            .with_reload_error_handler(|_| {})
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(config.get().port, 8080);
+
+        // The initial version is acknowledged once the build succeeds
+        let ack = next_reply(&mut replies).await;
+        assert_eq!((ack.version, ack.response_nonce.as_str()), (1, "nonce-1"));
+        assert!(ack.error_detail.is_empty());
+
+        push_tx.send(push(2, r#"{"port": 9090}"#)).await.unwrap();
+        let ack = next_reply(&mut replies).await;
+        assert_eq!((ack.version, ack.response_nonce.as_str()), (2, "nonce-2"));
+        assert!(ack.error_detail.is_empty());
+        assert_eq!(config.get().port, 9090);
+
+        // An invalid version is rejected and the previous one stays live
+        push_tx.send(push(3, r#"{"port": 0}"#)).await.unwrap();
+        let nack = next_reply(&mut replies).await;
+        assert_eq!((nack.version, nack.response_nonce.as_str()), (2, "nonce-3"));
+        assert!(nack.error_detail.contains("port must be non-zero"));
+        assert_eq!(config.get().port, 9090);
+        assert_eq!(source.version(), 2);
+    }
+}
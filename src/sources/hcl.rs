@@ -0,0 +1,109 @@
+//! HCL parsing for file sources.
+
+use crate::error::{ConfigError, Result};
+use std::collections::HashMap;
+
+/// Parse HCL text into a configuration map.
+///
+/// Blocks map to nested tables (`server "main" { port = 80 }` becomes
+/// `server.main.port`), following the HCL JSON specification. Expressions are
+/// evaluated, with environment variables available as `env`, so attributes
+/// like `url = "http://${env.HOST}:${8000 + 80}"` resolve at load time.
+pub(crate) fn parse_hcl(content: &str) -> Result<HashMap<String, config::Value>> {
+    let mut ctx = hcl::eval::Context::new();
+    let env: hcl::Map<String, hcl::Value> = std::env::vars()
+        .map(|(key, value)| (key, hcl::Value::String(value)))
+        .collect();
+    ctx.declare_var("env", hcl::Value::Object(env));
+
+    let value: hcl::Value = hcl::eval::from_str(content, &ctx)
+        .map_err(|e| ConfigError::ParseError(format!("Failed to parse HCL: {}", e)))?;
+
+    match value {
+        hcl::Value::Object(map) => Ok(map
+            .into_iter()
+            .map(|(key, value)| (key, hcl_value_to_config_value(value)))
+            .collect()),
+        _ => Err(ConfigError::ParseError(
+            "Expected HCL body at root level".to_string(),
+        )),
+    }
+}
+
+/// Convert an `hcl::Value` to a `config::Value`.
+fn hcl_value_to_config_value(value: hcl::Value) -> config::Value {
+    let kind = match value {
+        hcl::Value::Null => config::ValueKind::Nil,
+        hcl::Value::Bool(b) => config::ValueKind::Boolean(b),
+        hcl::Value::Number(n) => match n.as_i64() {
+            Some(i) => config::ValueKind::I64(i),
+            None => config::ValueKind::Float(n.as_f64().unwrap_or_default()),
+        },
+        hcl::Value::String(s) => config::ValueKind::String(s),
+        hcl::Value::Array(arr) => {
+            config::ValueKind::Array(arr.into_iter().map(hcl_value_to_config_value).collect())
+        }
+        hcl::Value::Object(map) => config::ValueKind::Table(
+            map.into_iter()
+                .map(|(key, value)| (key, hcl_value_to_config_value(value)))
+                .collect(),
+        ),
+    };
+
+    config::Value::new(None, kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attributes_and_blocks() {
+        let map = parse_hcl(
+            r#"
+debug = true
+
+server {
+  port = 8000 + 80
+  host = "localhost"
+}
+
+database "primary" {
+  url = "postgres://localhost/db"
+  pool_size = 10
+}
+"#,
+        )
+        .unwrap();
+
+        assert!(map["debug"].clone().into_bool().unwrap());
+
+        let server = map["server"].clone().into_table().unwrap();
+        assert_eq!(server["port"].clone().into_int().unwrap(), 8080);
+        assert_eq!(server["host"].clone().into_string().unwrap(), "localhost");
+
+        let database = map["database"].clone().into_table().unwrap();
+        let primary = database["primary"].clone().into_table().unwrap();
+        assert_eq!(primary["pool_size"].clone().into_int().unwrap(), 10);
+    }
+
+    #[test]
+    fn test_interpolation() {
+        let path = std::env::var("PATH").unwrap_or_default();
+        let map = parse_hcl(
+            r#"
+greeting = "port-${80 * 2}"
+search_path = "${env.PATH}"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(map["greeting"].clone().into_string().unwrap(), "port-160");
+        assert_eq!(map["search_path"].clone().into_string().unwrap(), path);
+    }
+
+    #[test]
+    fn test_invalid_hcl() {
+        assert!(parse_hcl("server {").is_err());
+    }
+}
@@ -0,0 +1,252 @@
+//! Size and structure limits for source content.
+
+use crate::error::{ConfigError, Result};
+use config::{Value, ValueKind};
+use std::collections::HashMap;
+use std::fmt;
+
+/// A limit enforced by [`SourceLimits`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Limit {
+    /// Bytes of raw content read from the source
+    PayloadSize,
+    /// Levels of nested tables and arrays
+    Depth,
+    /// Keys in all tables, nested ones included
+    Keys,
+    /// Bytes in a single string value
+    StringLength,
+}
+
+impl Limit {
+    /// Unit the limit is measured in, for messages.
+    pub(crate) fn unit(self) -> &'static str {
+        match self {
+            Self::PayloadSize | Self::StringLength => " bytes",
+            Self::Depth => " levels",
+            Self::Keys => " keys",
+        }
+    }
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::PayloadSize => "payload size",
+            Self::Depth => "nesting depth",
+            Self::Keys => "key count",
+            Self::StringLength => "string length",
+        })
+    }
+}
+
+/// Limits on the size and shape of what a source loads.
+///
+/// Set with
+/// [`HotswapConfigBuilder::with_source_limits`](crate::core::HotswapConfigBuilder::with_source_limits),
+/// the limits apply to every source of the handle, so a compromised or
+/// misbehaving source can't exhaust memory or stall deserialization. Content
+/// over a limit fails the load with [`ConfigError::LimitExceeded`], and the
+/// current config stays live.
+///
+/// The payload size is checked before files are read and against signed
+/// content; HTTP and object storage sources stop downloading at their own
+/// `with_max_payload_size`. The structure limits are checked on each source's
+/// values as soon as it loads. No limit is set by default.
+///
+/// # Examples
+///
+/// ```rust
+/// use hotswap_config::sources::SourceLimits;
+///
+/// let limits = SourceLimits::new()
+///     .with_max_payload_size(1024 * 1024)
+///     .with_max_depth(16)
+///     .with_max_keys(10_000)
+///     .with_max_string_length(64 * 1024);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SourceLimits {
+    max_payload_size: Option<usize>,
+    max_depth: Option<usize>,
+    max_keys: Option<usize>,
+    max_string_length: Option<usize>,
+}
+
+impl SourceLimits {
+    /// Create limits with nothing limited.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limit the raw content of a source to `bytes`.
+    pub fn with_max_payload_size(mut self, bytes: usize) -> Self {
+        self.max_payload_size = Some(bytes);
+        self
+    }
+
+    /// Limit nesting of tables and arrays to `depth` levels; a flat set of
+    /// top-level keys has depth 1.
+    pub fn with_max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Limit the keys of a source, counting the keys of nested tables, to
+    /// `count`.
+    pub fn with_max_keys(mut self, count: usize) -> Self {
+        self.max_keys = Some(count);
+        self
+    }
+
+    /// Limit each string value to `bytes`.
+    pub fn with_max_string_length(mut self, bytes: usize) -> Self {
+        self.max_string_length = Some(bytes);
+        self
+    }
+
+    /// The payload size limit, if set.
+    pub fn max_payload_size(&self) -> Option<usize> {
+        self.max_payload_size
+    }
+
+    /// Check the size of a source's raw content.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigError::LimitExceeded`] if `size` is over the payload
+    /// size limit.
+    pub fn check_payload(&self, size: u64) -> Result<()> {
+        check_payload(self.max_payload_size, size)
+    }
+
+    /// Check the structure of a source's values.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigError::LimitExceeded`], at the offending key, if the
+    /// values are nested too deeply, have too many keys, or hold a string
+    /// that is too long.
+    pub fn check_values(&self, values: &HashMap<String, Value>) -> Result<()> {
+        if self.max_depth.is_none() && self.max_keys.is_none() && self.max_string_length.is_none() {
+            return Ok(());
+        }
+        self.check_table(values.iter(), None, 1, &mut 0)
+    }
+
+    /// Check the entries of the table at dotted `path` (`None` at the top),
+    /// `depth` levels down, counting keys into `keys`.
+    fn check_table<'a>(
+        &self,
+        entries: impl ExactSizeIterator<Item = (&'a String, &'a Value)>,
+        path: Option<&str>,
+        depth: usize,
+        keys: &mut usize,
+    ) -> Result<()> {
+        *keys += entries.len();
+        exceeds(Limit::Keys, self.max_keys, *keys).map_err(|e| match path {
+            Some(path) => e.at_key(path),
+            None => e,
+        })?;
+        for (key, value) in entries {
+            let path = match path {
+                Some(path) => format!("{}.{}", path, key),
+                None => key.clone(),
+            };
+            self.check_value(value, &path, depth, keys)?;
+        }
+        Ok(())
+    }
+
+    /// Check `value` at dotted `path`, `depth` levels down.
+    fn check_value(&self, value: &Value, path: &str, depth: usize, keys: &mut usize) -> Result<()> {
+        exceeds(Limit::Depth, self.max_depth, depth).map_err(|e| e.at_key(path))?;
+        match &value.kind {
+            ValueKind::Table(table) => self.check_table(table.iter(), Some(path), depth + 1, keys),
+            ValueKind::Array(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    self.check_value(item, &format!("{}[{}]", path, index), depth + 1, keys)?;
+                }
+                Ok(())
+            }
+            ValueKind::String(s) => exceeds(Limit::StringLength, self.max_string_length, s.len())
+                .map_err(|e| e.at_key(path)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Check a payload of `size` bytes against an optional maximum.
+pub(super) fn check_payload(max: Option<usize>, size: u64) -> Result<()> {
+    let size = usize::try_from(size).unwrap_or(usize::MAX);
+    exceeds(Limit::PayloadSize, max, size)
+}
+
+/// Fail with [`ConfigError::LimitExceeded`] if `actual` is over `max`.
+fn exceeds(limit: Limit, max: Option<usize>, actual: usize) -> Result<()> {
+    match max {
+        Some(max) if actual > max => Err(ConfigError::LimitExceeded { limit, max }),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(yaml: &str) -> HashMap<String, Value> {
+        config::Config::builder()
+            .add_source(config::File::from_str(yaml, config::FileFormat::Yaml))
+            .build()
+            .unwrap()
+            .try_deserialize()
+            .unwrap()
+    }
+
+    fn exceeded(result: Result<()>) -> (Limit, Option<String>) {
+        let error = result.unwrap_err();
+        let key = error.key_path().map(str::to_string);
+        match error.root() {
+            ConfigError::LimitExceeded { limit, .. } => (*limit, key),
+            other => panic!("unexpected error: {}", other),
+        }
+    }
+
+    #[test]
+    fn test_structure_limits() {
+        let values = values("server:\n  tls:\n    cert: abcdef\n  ports: [1, 2, 3]\n");
+        assert!(SourceLimits::new().check_values(&values).is_ok());
+
+        // Both server.tls.cert and the elements of server.ports are 3 deep
+        let depth = SourceLimits::new().with_max_depth(3).check_values(&values);
+        assert!(depth.is_ok());
+        let depth = SourceLimits::new().with_max_depth(2).check_values(&values);
+        assert_eq!(exceeded(depth).0, Limit::Depth);
+
+        // server, tls, cert, and ports; the array elements aren't keys
+        let limits = SourceLimits::new().with_max_keys(4);
+        assert!(limits.check_values(&values).is_ok());
+        let keys = SourceLimits::new().with_max_keys(3).check_values(&values);
+        assert_eq!(exceeded(keys).0, Limit::Keys);
+
+        let string = SourceLimits::new()
+            .with_max_string_length(5)
+            .check_values(&values);
+        assert_eq!(
+            exceeded(string),
+            (Limit::StringLength, Some("server.tls.cert".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_payload_limit() {
+        let limits = SourceLimits::new().with_max_payload_size(10);
+        assert!(limits.check_payload(10).is_ok());
+        let error = limits.check_payload(11).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Limit exceeded: payload size over the maximum of 10 bytes"
+        );
+    }
+}
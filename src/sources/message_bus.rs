@@ -0,0 +1,327 @@
+//! NATS subjects and Kafka topics as change notifications.
+
+use super::{ChangeStream, ConfigSource, Format};
+use crate::core::{ReloadReason, ReloadTrigger, TriggerFuture};
+use crate::error::{ConfigError, Result};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[cfg(feature = "kafka")]
+use rdkafka::consumer::{Consumer, StreamConsumer};
+#[cfg(feature = "nats")]
+use tokio_stream::StreamExt;
+
+/// Delay before receiving again after the subscription fails.
+const ERROR_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Reload trigger firing on each message published to a NATS subject or
+/// Kafka topic.
+///
+/// For config publishing pipelines that announce changes on a message bus:
+/// add it with
+/// [`HotswapConfigBuilder::with_reload_trigger`](crate::core::HotswapConfigBuilder::with_reload_trigger)
+/// to re-read every source when a message arrives, or turn it into a
+/// [`MessageBusSource`] with [`into_source`](Self::into_source) to apply
+/// each message's payload as a configuration document instead. Reloads it
+/// triggers have the reason [`ReloadReason::MessageBus`]. Failing to
+/// receive, e.g. while the Kafka brokers are unreachable, is logged and
+/// retried.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use hotswap_config::prelude::*;
+/// use hotswap_config::sources::MessageBusTrigger;
+///
+/// # struct AppConfig;
+/// # async fn example() -> Result<()> {
+/// # #[cfg(feature = "nats")]
+/// # {
+/// let client = async_nats::connect("nats://nats.internal:4222")
+///     .await
+///     .expect("NATS is reachable");
+/// let trigger = MessageBusTrigger::nats(&client, "config.changed").await?;
+///
+/// HotswapConfig::<AppConfig>::builder()
+///     .with_file("config.yaml")
+///     .with_reload_trigger(trigger);
+/// # }
+/// # Ok(())
+/// # }
+/// ```
+pub struct MessageBusTrigger {
+    messages: Messages,
+    name: String,
+}
+
+impl MessageBusTrigger {
+    /// Subscribe to `subject` on NATS.
+    ///
+    /// NATS doesn't keep messages, so only those published after subscribing
+    /// arrive; subjects may contain wildcards.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the subscription fails.
+    #[cfg(feature = "nats")]
+    pub async fn nats(client: &async_nats::Client, subject: impl Into<String>) -> Result<Self> {
+        let subject = subject.into();
+        let subscriber = client.subscribe(subject.clone()).await.map_err(|e| {
+            ConfigError::WatchError(format!(
+                "Failed to subscribe to NATS subject {}: {}",
+                subject, e
+            ))
+        })?;
+        Ok(Self {
+            messages: Messages::Nats(subscriber),
+            name: format!("nats:{}", subject),
+        })
+    }
+
+    /// Subscribe `consumer` to `topic` on Kafka.
+    ///
+    /// Kafka shares a topic's messages among the consumers of a group, so
+    /// give each instance a `group.id` of its own for every instance to
+    /// reload, and set `auto.offset.reset` to `earliest` to start from the
+    /// oldest message kept, e.g. on a compacted topic holding the latest
+    /// document.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the subscription fails.
+    #[cfg(feature = "kafka")]
+    pub fn kafka(consumer: StreamConsumer, topic: &str) -> Result<Self> {
+        consumer.subscribe(&[topic]).map_err(|e| {
+            ConfigError::WatchError(format!(
+                "Failed to subscribe to Kafka topic {}: {}",
+                topic, e
+            ))
+        })?;
+        Ok(Self {
+            messages: Messages::Kafka(Box::new(consumer)),
+            name: format!("kafka:{}", topic),
+        })
+    }
+
+    /// Get the name of the subject or topic, e.g. `nats:config.changed`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Apply each message's payload, in `format`, as a configuration
+    /// document instead of reloading every source.
+    pub fn into_source(self, format: Format) -> MessageBusSource {
+        MessageBusSource {
+            name: self.name,
+            format,
+            priority: 250, // Same as HTTP sources: above files, below env vars
+            values: Arc::new(Mutex::new(HashMap::new())),
+            messages: Mutex::new(Some(self.messages)),
+        }
+    }
+}
+
+impl ReloadTrigger for MessageBusTrigger {
+    fn next(&mut self) -> TriggerFuture<'_> {
+        Box::pin(async move {
+            loop {
+                match self.messages.recv().await? {
+                    Ok(_) => return Some(ReloadReason::MessageBus(self.name.clone())),
+                    Err(e) => {
+                        #[cfg(feature = "tracing")]
+                        tracing::warn!(source = %self.name, error = %e, "Failed to receive message");
+                        #[cfg(not(feature = "tracing"))]
+                        eprintln!("Failed to receive message from {}: {}", self.name, e);
+                        tokio::time::sleep(ERROR_BACKOFF).await;
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// Configuration source applying the documents published to a NATS subject
+/// or Kafka topic.
+///
+/// Created with [`MessageBusTrigger::into_source`]. Each message's payload
+/// is parsed and replaces the source's values, and only this source is
+/// re-loaded; until the first message arrives it contributes nothing.
+/// Payloads that fail to parse are reported to the
+/// [reload error handler](crate::core::HotswapConfigBuilder::with_reload_error_handler)
+/// and leave the last document in place. Like other remote sources, it
+/// sits at priority 250.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use hotswap_config::prelude::*;
+/// use hotswap_config::sources::{Format, MessageBusTrigger};
+///
+/// # struct AppConfig;
+/// # async fn example() -> Result<()> {
+/// # #[cfg(feature = "nats")]
+/// # {
+/// let client = async_nats::connect("nats://nats.internal:4222")
+///     .await
+///     .expect("NATS is reachable");
+/// let source = MessageBusTrigger::nats(&client, "config.published")
+///     .await?
+///     .into_source(Format::Json);
+///
+/// HotswapConfig::<AppConfig>::builder()
+///     .with_file("config.yaml")
+///     .with_source(source);
+/// # }
+/// # Ok(())
+/// # }
+/// ```
+pub struct MessageBusSource {
+    name: String,
+    format: Format,
+    priority: i32,
+    values: Arc<Mutex<HashMap<String, config::Value>>>,
+    /// The subscription, until the watch task takes it
+    messages: Mutex<Option<Messages>>,
+}
+
+impl MessageBusSource {
+    /// Set the priority (default 250).
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+}
+
+impl ConfigSource for MessageBusSource {
+    fn load(&self) -> Result<HashMap<String, config::Value>> {
+        Ok(self.values.lock().unwrap().clone())
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    fn watch(&self) -> Option<ChangeStream> {
+        let mut messages = self.messages.lock().unwrap().take()?;
+        let (notifier, changes) = ChangeStream::channel();
+        let (name, format, values) = (self.name.clone(), self.format, Arc::clone(&self.values));
+        tokio::spawn(async move {
+            loop {
+                let message = tokio::select! {
+                    message = messages.recv() => message,
+                    _ = notifier.closed() => break,
+                };
+                let open = match message {
+                    Some(Ok(payload)) => match parse(&payload, format) {
+                        Ok(document) => {
+                            *values.lock().unwrap() = document;
+                            notifier.changed()
+                        }
+                        Err(e) => notifier.error(e.in_source(&name, None)),
+                    },
+                    Some(Err(e)) => {
+                        let open = notifier.error(e);
+                        tokio::time::sleep(ERROR_BACKOFF).await;
+                        open
+                    }
+                    None => {
+                        notifier.error(ConfigError::WatchError(format!(
+                            "Subscription to {} closed",
+                            name
+                        )));
+                        break;
+                    }
+                };
+                if !open {
+                    break;
+                }
+            }
+        });
+        Some(changes)
+    }
+}
+
+/// Parse a message's payload as a document in `format`.
+fn parse(payload: &[u8], format: Format) -> Result<HashMap<String, config::Value>> {
+    let text = std::str::from_utf8(payload).map_err(|e| {
+        ConfigError::DeserializationError(format!("Message payload is not UTF-8: {}", e))
+    })?;
+    format.parse(text)
+}
+
+/// A subscription to a subject or topic.
+enum Messages {
+    #[cfg(feature = "nats")]
+    Nats(async_nats::Subscriber),
+    #[cfg(feature = "kafka")]
+    Kafka(Box<StreamConsumer>),
+}
+
+impl Messages {
+    /// Wait for the next message's payload, or `None` once the subscription
+    /// ends.
+    async fn recv(&mut self) -> Option<Result<Vec<u8>>> {
+        match self {
+            #[cfg(feature = "nats")]
+            Messages::Nats(subscriber) => {
+                let message = subscriber.next().await?;
+                Some(Ok(message.payload.to_vec()))
+            }
+            #[cfg(feature = "kafka")]
+            Messages::Kafka(consumer) => {
+                use rdkafka::Message;
+                Some(
+                    consumer
+                        .recv()
+                        .await
+                        .map(|message| message.payload().unwrap_or_default().to_vec())
+                        .map_err(|e| {
+                            ConfigError::WatchError(format!("Failed to receive from Kafka: {}", e))
+                        }),
+                )
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "kafka"))]
+mod tests {
+    use super::*;
+    use rdkafka::ClientConfig;
+
+    fn trigger() -> MessageBusTrigger {
+        let consumer: StreamConsumer = ClientConfig::new()
+            .set("bootstrap.servers", "127.0.0.1:1")
+            .set("group.id", "test")
+            .create()
+            .unwrap();
+        MessageBusTrigger::kafka(consumer, "config").unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_source_starts_empty() {
+        let trigger = trigger();
+        assert_eq!(trigger.name(), "kafka:config");
+
+        let source = trigger.into_source(Format::Json).with_priority(75);
+        assert_eq!(source.name(), "kafka:config");
+        assert_eq!(source.priority(), 75);
+        assert!(source.load().unwrap().is_empty());
+        assert!(source.watch().is_some());
+        // The subscription moves to the first watch
+        assert!(source.watch().is_none());
+    }
+
+    #[test]
+    fn test_parse_payload() {
+        let values = parse(br#"{"server": {"port": 9090}}"#, Format::Json).unwrap();
+        assert!(values.contains_key("server"));
+        assert!(parse(b"\xff", Format::Json).is_err());
+        assert!(parse(b"{", Format::Json).is_err());
+    }
+}
@@ -1,15 +1,86 @@
 //! Configuration source implementations.
 
 mod config_source;
+mod defaults;
+mod directory;
 mod env;
 mod file;
+mod limits;
+mod reader;
+
+#[cfg(feature = "clap")]
+mod cli;
+
+#[cfg(feature = "dotenv")]
+mod dotenv;
+
+#[cfg(feature = "fleet")]
+mod fleet;
+
+#[cfg(feature = "grpc")]
+mod grpc;
+
+#[cfg(feature = "hcl")]
+mod hcl;
+
+#[cfg(any(feature = "nats", feature = "kafka"))]
+mod message_bus;
+
+#[cfg(feature = "object-store")]
+mod object_store;
+
+#[cfg(feature = "redis")]
+mod redis;
 
 #[cfg(feature = "remote")]
 mod remote;
 
-pub use config_source::ConfigSource;
-pub use env::EnvSource;
+#[cfg(feature = "remote")]
+mod retry;
+
+#[cfg(feature = "zookeeper")]
+mod zookeeper;
+
+#[cfg(feature = "tokio")]
+pub use config_source::{ChangeNotifier, ChangeStream};
+pub use config_source::{ConfigSource, FailurePolicy, SignedContent};
+pub use defaults::DefaultsSource;
+pub use directory::DirectorySource;
+pub use env::{EnvSource, EnvType, KeyCase};
 pub use file::FileSource;
+pub(crate) use file::{find_file, signature_path};
+pub use limits::{Limit, SourceLimits};
+pub use reader::{Format, ReaderSource};
+
+#[cfg(feature = "clap")]
+pub use cli::CliSource;
+
+#[cfg(feature = "dotenv")]
+pub use dotenv::DotenvSource;
+
+#[cfg(feature = "fleet")]
+pub use fleet::{
+    FleetPayload, FleetSource, FleetSourceBuilder, FleetTransport, HttpFleetTransport,
+    TransportFuture,
+};
+
+#[cfg(feature = "grpc")]
+pub use grpc::{GrpcSource, GrpcSourceBuilder, proto};
+
+#[cfg(any(feature = "nats", feature = "kafka"))]
+pub use message_bus::{MessageBusSource, MessageBusTrigger};
+
+#[cfg(feature = "object-store")]
+pub use object_store::{ObjectStoreSource, ObjectStoreSourceBuilder};
+
+#[cfg(feature = "redis")]
+pub use redis::{RedisSource, RedisSourceBuilder};
 
 #[cfg(feature = "remote")]
-pub use remote::{HttpSource, HttpSourceBuilder};
+pub use remote::{HttpSource, HttpSourceBuilder, ResponseFormat};
+
+#[cfg(feature = "remote")]
+pub use retry::{BreakerState, CircuitBreaker, RetryPolicy};
+
+#[cfg(feature = "zookeeper")]
+pub use zookeeper::{ZookeeperSource, ZookeeperSourceBuilder};
@@ -0,0 +1,629 @@
+//! Object storage (S3, GCS, Azure Blob) configuration source.
+
+use super::limits::check_payload;
+use super::{ChangeStream, ConfigSource, FailurePolicy, SignedContent};
+use crate::error::{ConfigError, Result};
+use object_store::path::Path as ObjectPath;
+use object_store::{GetOptions, ObjectStore};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+/// Configuration source reading a single object from object storage.
+///
+/// The object is fetched by [`connect`](ObjectStoreSourceBuilder::connect)
+/// and loads return the fetched values without touching the network. With a
+/// [poll interval](ObjectStoreSourceBuilder::with_poll_interval), the source
+/// [watches](ConfigSource::watch) the object by re-fetching it with
+/// `If-None-Match` on the last ETag, so an unchanged object costs a `304`
+/// and no reload; only a changed object triggers a reload.
+///
+/// If a poll fails, the last-known-good config stays in place and the error
+/// goes to the reload error handler. With
+/// [`with_cache_file`](ObjectStoreSourceBuilder::with_cache_file), the last
+/// fetched object is also kept on disk and used when the store is unreachable
+/// at startup.
+///
+/// The format is taken from the object's extension (`.yaml`, `.yml`,
+/// `.toml`, or `.json`).
+///
+/// With [signature verification](crate::core::HotswapConfigBuilder::with_signature_verification),
+/// the detached signature is read from the object with `.sig` appended
+/// (`myapp/production.yaml.sig`) whenever the config object changes. Upload
+/// the new signature before the new config object, so a poll never sees the
+/// new object with the old signature.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use hotswap_config::prelude::*;
+/// use hotswap_config::sources::ObjectStoreSource;
+/// use serde::Deserialize;
+/// use std::time::Duration;
+///
+/// #[derive(Debug, Deserialize, Clone)]
+/// struct AppConfig {
+///     port: u16,
+/// }
+///
+/// # async fn example() -> Result<()> {
+/// // Credentials and region come from the usual AWS_* environment variables
+/// let source = ObjectStoreSource::s3("my-config-bucket", "myapp/production.yaml")?
+///     .with_poll_interval(Duration::from_secs(30))
+///     .with_cache_file("/var/cache/myapp/config.yaml")
+///     .connect()
+///     .await?;
+///
+/// let config = HotswapConfig::<AppConfig>::builder()
+///     .with_file("config/default.yaml")
+///     .with_object_store(source)
+///     .build()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ObjectStoreSource {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    store: Arc<dyn ObjectStore>,
+    location: ObjectPath,
+    format: config::FileFormat,
+    priority: i32,
+    failure_policy: FailurePolicy,
+    poll_interval: Option<Duration>,
+    cache_file: Option<PathBuf>,
+    max_payload_size: Option<usize>,
+    /// The last successfully fetched object
+    current: RwLock<Fetched>,
+    stale: AtomicBool,
+}
+
+struct Fetched {
+    values: HashMap<String, config::Value>,
+    e_tag: Option<String>,
+    /// The raw object, kept for signature verification
+    body: Vec<u8>,
+    signature: Option<Vec<u8>>,
+}
+
+impl ObjectStoreSource {
+    /// Create a builder reading `path` from any [`ObjectStore`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hotswap_config::sources::ObjectStoreSource;
+    /// use object_store::memory::InMemory;
+    /// use std::sync::Arc;
+    ///
+    /// let builder = ObjectStoreSource::builder(Arc::new(InMemory::new()), "config/app.yaml");
+    /// ```
+    pub fn builder(store: Arc<dyn ObjectStore>, path: &str) -> ObjectStoreSourceBuilder {
+        ObjectStoreSourceBuilder::new(store, path)
+    }
+
+    /// Create a builder reading `path` from an S3 bucket.
+    ///
+    /// Credentials, region, and endpoint are read from the standard `AWS_*`
+    /// environment variables.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the S3 client can't be configured.
+    #[cfg(feature = "s3")]
+    pub fn s3(bucket: &str, path: &str) -> Result<ObjectStoreSourceBuilder> {
+        let store = object_store::aws::AmazonS3Builder::from_env()
+            .with_bucket_name(bucket)
+            .build()
+            .map_err(|e| ConfigError::LoadError(format!("Invalid S3 configuration: {}", e)))?;
+        Ok(ObjectStoreSourceBuilder::new(Arc::new(store), path))
+    }
+
+    /// Create a builder reading `path` from a Google Cloud Storage bucket.
+    ///
+    /// Credentials are read from the standard `GOOGLE_*` environment
+    /// variables.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the GCS client can't be configured.
+    #[cfg(feature = "gcs")]
+    pub fn gcs(bucket: &str, path: &str) -> Result<ObjectStoreSourceBuilder> {
+        let store = object_store::gcp::GoogleCloudStorageBuilder::from_env()
+            .with_bucket_name(bucket)
+            .build()
+            .map_err(|e| ConfigError::LoadError(format!("Invalid GCS configuration: {}", e)))?;
+        Ok(ObjectStoreSourceBuilder::new(Arc::new(store), path))
+    }
+
+    /// Create a builder reading the blob `path` from an Azure Blob Storage
+    /// container.
+    ///
+    /// The account and credentials are read from the standard `AZURE_*`
+    /// environment variables.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the Azure client can't be configured.
+    #[cfg(feature = "azure")]
+    pub fn azure_blob(container: &str, path: &str) -> Result<ObjectStoreSourceBuilder> {
+        let store = object_store::azure::MicrosoftAzureBuilder::from_env()
+            .with_container_name(container)
+            .build()
+            .map_err(|e| {
+                ConfigError::LoadError(format!("Invalid Azure Blob configuration: {}", e))
+            })?;
+        Ok(ObjectStoreSourceBuilder::new(Arc::new(store), path))
+    }
+
+    /// Check whether the last poll failed, so the config may be out of date.
+    pub fn is_stale(&self) -> bool {
+        self.inner.stale.load(Ordering::Acquire)
+    }
+
+    /// Get the ETag of the last fetched object, if the store reported one.
+    pub fn e_tag(&self) -> Option<String> {
+        self.inner.current.read().unwrap().e_tag.clone()
+    }
+
+    /// Fetch the object unless it still matches the last ETag.
+    ///
+    /// Returns whether new values were fetched.
+    async fn fetch(&self) -> Result<bool> {
+        let options = GetOptions {
+            if_none_match: self.e_tag(),
+            ..Default::default()
+        };
+        let result = match self
+            .inner
+            .store
+            .get_opts(&self.inner.location, options)
+            .await
+        {
+            Ok(result) => result,
+            Err(object_store::Error::NotModified { .. }) => return Ok(false),
+            Err(e) => return Err(self.error(e)),
+        };
+        check_payload(self.inner.max_payload_size, result.meta.size)?;
+        let e_tag = result.meta.e_tag.clone();
+        let body = result.bytes().await.map_err(|e| self.error(e))?;
+        #[cfg(feature = "signing")]
+        let signature = self.fetch_signature().await?;
+        #[cfg(not(feature = "signing"))]
+        let signature = None;
+
+        let values = parse(&body, self.inner.format)?;
+        if let Some(path) = &self.inner.cache_file {
+            if let Err(e) = write_cache_file(path, &body, signature.as_deref()) {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(
+                    cache_file = %path.display(),
+                    error = %e,
+                    "Failed to write object store config cache"
+                );
+                #[cfg(not(feature = "tracing"))]
+                eprintln!(
+                    "Failed to write object store config cache {}: {}",
+                    path.display(),
+                    e
+                );
+            }
+        }
+        *self.inner.current.write().unwrap() = Fetched {
+            values,
+            e_tag,
+            body: body.to_vec(),
+            signature,
+        };
+        Ok(true)
+    }
+
+    /// Fetch the detached signature stored next to the object, if any.
+    #[cfg(feature = "signing")]
+    async fn fetch_signature(&self) -> Result<Option<Vec<u8>>> {
+        let location = ObjectPath::from(format!("{}.sig", self.inner.location));
+        match self.inner.store.get(&location).await {
+            Ok(result) => Ok(Some(
+                result.bytes().await.map_err(|e| self.error(e))?.to_vec(),
+            )),
+            Err(object_store::Error::NotFound { .. }) => Ok(None),
+            Err(e) => Err(self.error(e)),
+        }
+    }
+
+    fn error(&self, error: object_store::Error) -> ConfigError {
+        ConfigError::LoadError(format!("Failed to fetch {}: {}", self.name(), error))
+    }
+}
+
+impl ConfigSource for ObjectStoreSource {
+    fn load(&self) -> Result<HashMap<String, config::Value>> {
+        Ok(self.inner.current.read().unwrap().values.clone())
+    }
+
+    fn name(&self) -> String {
+        format!("object:{}/{}", self.inner.store, self.inner.location)
+    }
+
+    fn priority(&self) -> i32 {
+        self.inner.priority
+    }
+
+    fn failure_policy(&self) -> FailurePolicy {
+        self.inner.failure_policy
+    }
+
+    fn load_signed(&self) -> Result<Option<SignedContent>> {
+        let current = self.inner.current.read().unwrap();
+        Ok(Some(SignedContent {
+            content: current.body.clone(),
+            signature: current.signature.clone(),
+            values: current.values.clone(),
+        }))
+    }
+
+    fn content_version(&self) -> Option<String> {
+        self.e_tag()
+    }
+
+    fn watch(&self) -> Option<ChangeStream> {
+        let interval = self.inner.poll_interval?;
+        let (notifier, changes) = ChangeStream::channel();
+        let source = self.clone();
+        tokio::spawn(async move {
+            let mut ticks =
+                tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+            ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                tokio::select! {
+                    _ = ticks.tick() => {}
+                    _ = notifier.closed() => break,
+                }
+                let open = match source.fetch().await {
+                    Ok(changed) => {
+                        source.inner.stale.store(false, Ordering::Release);
+                        !changed || notifier.changed()
+                    }
+                    Err(e) => {
+                        source.inner.stale.store(true, Ordering::Release);
+                        notifier.error(e)
+                    }
+                };
+                if !open {
+                    break;
+                }
+            }
+        });
+        Some(changes)
+    }
+}
+
+/// Builder for constructing an `ObjectStoreSource`.
+pub struct ObjectStoreSourceBuilder {
+    store: Arc<dyn ObjectStore>,
+    path: String,
+    priority: i32,
+    failure_policy: FailurePolicy,
+    poll_interval: Option<Duration>,
+    cache_file: Option<PathBuf>,
+    max_payload_size: Option<usize>,
+}
+
+impl ObjectStoreSourceBuilder {
+    /// Create a new builder reading `path` from `store`.
+    pub fn new(store: Arc<dyn ObjectStore>, path: &str) -> Self {
+        Self {
+            store,
+            path: path.to_string(),
+            priority: 250, // Same as HTTP sources: above files, below env vars
+            failure_policy: FailurePolicy::Required,
+            poll_interval: None,
+            cache_file: None,
+            max_payload_size: None,
+        }
+    }
+
+    /// Set the priority for this source.
+    ///
+    /// Defaults to 250.
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Set what the loader does when this source fails to load.
+    ///
+    /// Default is [`FailurePolicy::Required`], which fails the whole load.
+    /// [`FailurePolicy::FallbackToCache`] keeps serving the values from the
+    /// last successful load, so a flaky endpoint doesn't block reloads of
+    /// other sources.
+    pub fn with_failure_policy(mut self, policy: FailurePolicy) -> Self {
+        self.failure_policy = policy;
+        self
+    }
+
+    /// Poll the object for changes at this interval.
+    ///
+    /// Without a poll interval the object is only fetched once, at connect.
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = Some(interval);
+        self
+    }
+
+    /// Persist the last fetched object to `path`, and load it from there if
+    /// the store is unreachable at connect.
+    pub fn with_cache_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.cache_file = Some(path.into());
+        self
+    }
+
+    /// Refuse objects larger than `bytes` before downloading them.
+    ///
+    /// A larger object fails the fetch with [`ConfigError::LimitExceeded`].
+    /// No limit by default.
+    pub fn with_max_payload_size(mut self, bytes: usize) -> Self {
+        self.max_payload_size = Some(bytes);
+        self
+    }
+
+    /// Fetch the object and create the source.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the object's extension isn't a supported format,
+    /// or the fetch fails and there is no usable cache file.
+    pub async fn connect(self) -> Result<ObjectStoreSource> {
+        let format = match Path::new(&self.path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+        {
+            Some("yaml" | "yml") => config::FileFormat::Yaml,
+            Some("toml") => config::FileFormat::Toml,
+            Some("json") => config::FileFormat::Json,
+            _ => {
+                return Err(ConfigError::LoadError(format!(
+                    "Unsupported config object format: {}",
+                    self.path
+                )));
+            }
+        };
+        let location = ObjectPath::parse(&self.path)
+            .map_err(|e| ConfigError::LoadError(format!("Invalid object path: {}", e)))?;
+
+        let source = ObjectStoreSource {
+            inner: Arc::new(Inner {
+                store: self.store,
+                location,
+                format,
+                priority: self.priority,
+                failure_policy: self.failure_policy,
+                poll_interval: self.poll_interval,
+                cache_file: self.cache_file,
+                max_payload_size: self.max_payload_size,
+                current: RwLock::new(Fetched {
+                    values: HashMap::new(),
+                    e_tag: None,
+                    body: Vec::new(),
+                    signature: None,
+                }),
+                stale: AtomicBool::new(false),
+            }),
+        };
+
+        match (source.fetch().await, &source.inner.cache_file) {
+            (Ok(_), _) => {}
+            // A missing or corrupt cache file surfaces the original error
+            (Err(e @ ConfigError::LoadError(_)), Some(path)) => {
+                let (body, values) = std::fs::read(path)
+                    .ok()
+                    .and_then(|body| parse(&body, format).ok().map(|values| (body, values)))
+                    .ok_or(e)?;
+                #[cfg(feature = "tracing")]
+                tracing::warn!(
+                    source = %source.name(),
+                    cache_file = %path.display(),
+                    "Object store unreachable, serving cached config"
+                );
+                let signature = std::fs::read(super::signature_path(path)).ok();
+                *source.inner.current.write().unwrap() = Fetched {
+                    values,
+                    e_tag: None,
+                    body,
+                    signature,
+                };
+                source.inner.stale.store(true, Ordering::Release);
+            }
+            (Err(e), _) => return Err(e),
+        }
+        Ok(source)
+    }
+}
+
+/// Parse an object body in the given format.
+fn parse(body: &[u8], format: config::FileFormat) -> Result<HashMap<String, config::Value>> {
+    let text = std::str::from_utf8(body).map_err(|e| {
+        ConfigError::DeserializationError(format!("Config object is not UTF-8: {}", e))
+    })?;
+    config::Config::builder()
+        .add_source(config::File::from_str(text, format))
+        .build()
+        .and_then(|config| config.try_deserialize::<HashMap<String, config::Value>>())
+        .map_err(|e| {
+            ConfigError::DeserializationError(format!("Failed to parse config object: {}", e))
+        })
+}
+
+/// Persist a fetched object and its signature, writing atomically via temp
+/// files.
+fn write_cache_file(path: &Path, body: &[u8], signature: Option<&[u8]>) -> Result<()> {
+    let write = |path: &Path, contents: &[u8]| -> Result<()> {
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        std::fs::write(&tmp, contents)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    };
+    let signature_path = super::signature_path(path);
+    match signature {
+        Some(signature) => write(&signature_path, signature)?,
+        None if signature_path.exists() => std::fs::remove_file(&signature_path)?,
+        None => {}
+    }
+    write(path, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::HotswapConfig;
+    use object_store::memory::InMemory;
+    use serde::Deserialize;
+
+    #[derive(Debug, Clone, Deserialize)]
+    struct TestConfig {
+        port: u16,
+    }
+
+    async fn put(store: &InMemory, body: &'static str) {
+        store
+            .put(&ObjectPath::from("app/config.yaml"), body.into())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_conditional_fetch() {
+        let store = Arc::new(InMemory::new());
+        put(&store, "port: 8080").await;
+
+        let source = ObjectStoreSource::builder(store.clone(), "app/config.yaml")
+            .connect()
+            .await
+            .unwrap();
+        assert_eq!(
+            source.load().unwrap()["port"].clone().into_int().unwrap(),
+            8080
+        );
+        assert!(source.e_tag().is_some());
+
+        // Unchanged objects aren't re-downloaded
+        assert!(!source.fetch().await.unwrap());
+
+        put(&store, "port: 9090").await;
+        assert!(source.fetch().await.unwrap());
+        assert_eq!(
+            source.load().unwrap()["port"].clone().into_int().unwrap(),
+            9090
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cache_file_fallback() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let cache = temp_dir.path().join("config.yaml");
+        let store = Arc::new(InMemory::new());
+        put(&store, "port: 8080").await;
+
+        ObjectStoreSource::builder(store, "app/config.yaml")
+            .with_cache_file(&cache)
+            .connect()
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read_to_string(&cache).unwrap(), "port: 8080");
+
+        // The object is gone from a fresh store, so the cache is served
+        let source = ObjectStoreSource::builder(Arc::new(InMemory::new()), "app/config.yaml")
+            .with_cache_file(&cache)
+            .connect()
+            .await
+            .unwrap();
+        assert!(source.is_stale());
+        assert_eq!(
+            source.load().unwrap()["port"].clone().into_int().unwrap(),
+            8080
+        );
+
+        let missing = ObjectStoreSource::builder(Arc::new(InMemory::new()), "app/config.yaml")
+            .connect()
+            .await;
+        assert!(matches!(missing, Err(ConfigError::LoadError(_))));
+    }
+
+    #[cfg(feature = "signing")]
+    #[tokio::test]
+    async fn test_signature_fetched_with_object() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let cache = temp_dir.path().join("config.yaml");
+        let store = Arc::new(InMemory::new());
+        put(&store, "port: 8080").await;
+
+        let source = ObjectStoreSource::builder(store.clone(), "app/config.yaml")
+            .with_cache_file(&cache)
+            .connect()
+            .await
+            .unwrap();
+        let signed = source.load_signed().unwrap().unwrap();
+        assert_eq!(signed.content, b"port: 8080");
+        assert_eq!(signed.signature, None);
+
+        store
+            .put(&ObjectPath::from("app/config.yaml.sig"), "c2ln".into())
+            .await
+            .unwrap();
+        put(&store, "port: 9090").await;
+        assert!(source.fetch().await.unwrap());
+        let signed = source.load_signed().unwrap().unwrap();
+        assert_eq!(signed.content, b"port: 9090");
+        assert_eq!(signed.signature.as_deref(), Some(&b"c2ln"[..]));
+
+        // The cached signature is served along with the cached object
+        let cached = ObjectStoreSource::builder(Arc::new(InMemory::new()), "app/config.yaml")
+            .with_cache_file(&cache)
+            .connect()
+            .await
+            .unwrap();
+        let signed = cached.load_signed().unwrap().unwrap();
+        assert_eq!(signed.content, b"port: 9090");
+        assert_eq!(signed.signature.as_deref(), Some(&b"c2ln"[..]));
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_extension() {
+        let result = ObjectStoreSource::builder(Arc::new(InMemory::new()), "app/config.ini")
+            .connect()
+            .await;
+        assert!(matches!(result, Err(ConfigError::LoadError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_polling_reloads_on_change() {
+        let store = Arc::new(InMemory::new());
+        put(&store, "port: 8080").await;
+        let source = ObjectStoreSource::builder(store.clone(), "app/config.yaml")
+            .with_poll_interval(Duration::from_millis(20))
+            .connect()
+            .await
+            .unwrap();
+
+        let config = HotswapConfig::<TestConfig>::builder()
+            .with_source(source)
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(config.get().port, 8080);
+
+        put(&store, "port: 9090").await;
+        for _ in 0..100 {
+            if config.get().port == 9090 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(config.get().port, 9090);
+    }
+}
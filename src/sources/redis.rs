@@ -0,0 +1,394 @@
+//! Redis configuration source with pub/sub invalidation.
+
+use super::{ChangeNotifier, ChangeStream, ConfigSource, FailurePolicy};
+use crate::error::{ConfigError, Result};
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio_stream::StreamExt;
+
+/// Delay before the first resubscribe attempt after the subscription fails.
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+/// Upper bound for the resubscribe delay.
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Redis-based configuration source.
+///
+/// Loads configuration from a single key, which may be:
+/// - a hash, with each field as a dotted key path (`server.port` → `8080`)
+/// - a string holding a JSON document
+///
+/// With an invalidation [channel](RedisSourceBuilder::with_channel), the
+/// source [watches](ConfigSource::watch) it and is re-loaded as soon as
+/// anything is published, so a writer updates the key and then publishes to
+/// roll the change out.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use hotswap_config::prelude::*;
+/// use hotswap_config::sources::RedisSource;
+/// use serde::Deserialize;
+///
+/// #[derive(Debug, Deserialize, Clone)]
+/// struct AppConfig {
+///     port: u16,
+/// }
+///
+/// # async fn example() -> Result<()> {
+/// let source = RedisSource::builder()
+///     .with_url("redis://cache.internal:6379")
+///     .with_key("myapp:config")
+///     .with_channel("myapp:config:changed")
+///     .build()?;
+///
+/// let config = HotswapConfig::<AppConfig>::builder()
+///     .with_file("config/default.yaml")
+///     .with_redis(source)
+///     .build()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct RedisSource {
+    client: redis::Client,
+    /// Server address without credentials, for names and errors
+    addr: String,
+    key: String,
+    channel: Option<String>,
+    timeout: Duration,
+    priority: i32,
+    failure_policy: FailurePolicy,
+}
+
+impl RedisSource {
+    /// Create a new builder for constructing a Redis source.
+    pub fn builder() -> RedisSourceBuilder {
+        RedisSourceBuilder::new()
+    }
+
+    /// Get the invalidation channel, if any.
+    pub fn channel(&self) -> Option<&str> {
+        self.channel.as_deref()
+    }
+
+    /// Read the key, as a hash or a JSON string.
+    fn fetch(&self) -> Result<HashMap<String, config::Value>> {
+        let mut conn = self
+            .client
+            .get_connection_with_timeout(self.timeout)
+            .map_err(|e| redis_error("connect to", &self.addr, e))?;
+        conn.set_read_timeout(Some(self.timeout))
+            .map_err(|e| redis_error("connect to", &self.addr, e))?;
+
+        let kind: String = redis::cmd("TYPE")
+            .arg(&self.key)
+            .query(&mut conn)
+            .map_err(|e| redis_error("read", &self.key, e))?;
+        match kind.as_str() {
+            "hash" => {
+                let fields: HashMap<String, String> = redis::cmd("HGETALL")
+                    .arg(&self.key)
+                    .query(&mut conn)
+                    .map_err(|e| redis_error("read", &self.key, e))?;
+                parse_hash(fields)
+            }
+            "string" => {
+                let json: String = redis::cmd("GET")
+                    .arg(&self.key)
+                    .query(&mut conn)
+                    .map_err(|e| redis_error("read", &self.key, e))?;
+                parse_json(&json)
+            }
+            "none" => Err(ConfigError::LoadError(format!(
+                "Redis key not found: {}",
+                self.key
+            ))),
+            other => Err(ConfigError::LoadError(format!(
+                "Redis key {} is a {}, expected a hash or string",
+                self.key, other
+            ))),
+        }
+    }
+
+    /// Signal a change on every message published to `channel`,
+    /// resubscribing with backoff if the subscription drops.
+    async fn run(&self, channel: &str, notifier: &ChangeNotifier) {
+        let mut backoff = INITIAL_BACKOFF;
+        loop {
+            let error = match self.client.get_async_pubsub().await {
+                Ok(mut pubsub) => match pubsub.subscribe(channel).await {
+                    Ok(()) => {
+                        backoff = INITIAL_BACKOFF;
+                        let mut messages = pubsub.on_message();
+                        while messages.next().await.is_some() {
+                            if !notifier.changed() {
+                                return;
+                            }
+                        }
+                        ConfigError::LoadError(format!("Redis subscription to {} closed", channel))
+                    }
+                    Err(e) => redis_error("subscribe to", channel, e),
+                },
+                Err(e) => redis_error("connect to", &self.addr, e),
+            };
+
+            #[cfg(feature = "tracing")]
+            tracing::warn!(
+                channel = %channel,
+                error = %error,
+                "Redis invalidation subscription failed, resubscribing"
+            );
+            if !notifier.error(error) {
+                return;
+            }
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(MAX_BACKOFF);
+        }
+    }
+}
+
+impl ConfigSource for RedisSource {
+    fn load(&self) -> Result<HashMap<String, config::Value>> {
+        self.fetch()
+    }
+
+    fn name(&self) -> String {
+        format!("redis:{}/{}", self.addr, self.key)
+    }
+
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    fn failure_policy(&self) -> FailurePolicy {
+        self.failure_policy
+    }
+
+    fn watch(&self) -> Option<ChangeStream> {
+        let channel = self.channel.clone()?;
+        let (notifier, changes) = ChangeStream::channel();
+        let source = self.clone();
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = source.run(&channel, &notifier) => {}
+                _ = notifier.closed() => {}
+            }
+        });
+        Some(changes)
+    }
+}
+
+/// Builder for constructing a `RedisSource`.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use hotswap_config::sources::RedisSource;
+/// use std::time::Duration;
+///
+/// # fn example() -> hotswap_config::error::Result<()> {
+/// let source = RedisSource::builder()
+///     .with_url("redis://cache.internal:6379")
+///     .with_key("myapp:config")
+///     .with_channel("myapp:config:changed")
+///     .with_timeout(Duration::from_secs(2))
+///     .with_priority(250)
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct RedisSourceBuilder {
+    url: Option<String>,
+    key: Option<String>,
+    channel: Option<String>,
+    timeout: Duration,
+    priority: i32,
+    failure_policy: FailurePolicy,
+}
+
+impl RedisSourceBuilder {
+    /// Create a new builder with default settings.
+    pub fn new() -> Self {
+        Self {
+            url: None,
+            key: None,
+            channel: None,
+            timeout: Duration::from_secs(5),
+            priority: 250, // Same as HTTP sources: above files, below env vars
+            failure_policy: FailurePolicy::Required,
+        }
+    }
+
+    /// Set the Redis URL, e.g. `redis://:password@host:6379/0`.
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    /// Set the key holding the configuration.
+    pub fn with_key(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    /// Set a pub/sub channel whose messages trigger a reload.
+    pub fn with_channel(mut self, channel: impl Into<String>) -> Self {
+        self.channel = Some(channel.into());
+        self
+    }
+
+    /// Set the connect and read timeout for loads.
+    ///
+    /// Defaults to 5 seconds.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set the priority for this source.
+    ///
+    /// Defaults to 250.
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Set what the loader does when this source fails to load.
+    ///
+    /// Default is [`FailurePolicy::Required`], which fails the whole load.
+    /// [`FailurePolicy::FallbackToCache`] keeps serving the values from the
+    /// last successful load, so a flaky endpoint doesn't block reloads of
+    /// other sources.
+    pub fn with_failure_policy(mut self, policy: FailurePolicy) -> Self {
+        self.failure_policy = policy;
+        self
+    }
+
+    /// Build the source.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the URL or key is missing, or the URL is invalid.
+    /// No connection is made until the first load.
+    pub fn build(self) -> Result<RedisSource> {
+        let url = self
+            .url
+            .ok_or_else(|| ConfigError::LoadError("Redis URL is required".to_string()))?;
+        let key = self
+            .key
+            .ok_or_else(|| ConfigError::LoadError("Redis key is required".to_string()))?;
+        let client = redis::Client::open(url.as_str())
+            .map_err(|e| ConfigError::LoadError(format!("Invalid Redis URL: {}", e)))?;
+
+        Ok(RedisSource {
+            addr: client.get_connection_info().addr.to_string(),
+            client,
+            key,
+            channel: self.channel,
+            timeout: self.timeout,
+            priority: self.priority,
+            failure_policy: self.failure_policy,
+        })
+    }
+}
+
+impl Default for RedisSourceBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Convert hash fields with dotted names into nested values.
+fn parse_hash(fields: HashMap<String, String>) -> Result<HashMap<String, config::Value>> {
+    let mut builder = config::Config::builder();
+    for (field, value) in fields {
+        builder = builder.set_override(field, value).map_err(|e| {
+            ConfigError::DeserializationError(format!("Invalid Redis hash field: {}", e))
+        })?;
+    }
+    builder
+        .build()
+        .and_then(|config| config.try_deserialize::<HashMap<String, config::Value>>())
+        .map_err(|e| {
+            ConfigError::DeserializationError(format!("Failed to parse Redis hash: {}", e))
+        })
+}
+
+/// Parse a JSON document stored in a string key.
+fn parse_json(json: &str) -> Result<HashMap<String, config::Value>> {
+    config::Config::builder()
+        .add_source(config::File::from_str(json, config::FileFormat::Json))
+        .build()
+        .and_then(|config| config.try_deserialize::<HashMap<String, config::Value>>())
+        .map_err(|e| {
+            ConfigError::DeserializationError(format!("Failed to parse Redis JSON: {}", e))
+        })
+}
+
+fn redis_error(action: &str, target: &str, error: redis::RedisError) -> ConfigError {
+    ConfigError::LoadError(format!("Failed to {} Redis {}: {}", action, target, error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hash_nests_dotted_fields() {
+        let fields = HashMap::from([
+            ("server.port".to_string(), "8080".to_string()),
+            ("server.host".to_string(), "localhost".to_string()),
+            ("debug".to_string(), "true".to_string()),
+        ]);
+        let values = parse_hash(fields).unwrap();
+
+        let server = values["server"].clone().into_table().unwrap();
+        assert_eq!(server["port"].clone().into_int().unwrap(), 8080);
+        assert_eq!(server["host"].clone().into_string().unwrap(), "localhost");
+        assert!(values["debug"].clone().into_bool().unwrap());
+    }
+
+    #[test]
+    fn test_parse_json() {
+        let values = parse_json(r#"{"server": {"port": 9090}}"#).unwrap();
+        let server = values["server"].clone().into_table().unwrap();
+        assert_eq!(server["port"].clone().into_int().unwrap(), 9090);
+
+        assert!(matches!(
+            parse_json("not json"),
+            Err(ConfigError::DeserializationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_builder_requires_url_and_key() {
+        assert!(RedisSource::builder().with_key("config").build().is_err());
+        assert!(
+            RedisSource::builder()
+                .with_url("redis://localhost")
+                .build()
+                .is_err()
+        );
+
+        let source = RedisSource::builder()
+            .with_url("redis://:hunter2@localhost:6379")
+            .with_key("myapp:config")
+            .build()
+            .unwrap();
+        assert_eq!(source.name(), "redis:localhost:6379/myapp:config");
+        assert_eq!(source.priority(), 250);
+        assert_eq!(source.channel(), None);
+    }
+
+    #[test]
+    fn test_unreachable_server_fails_load() {
+        let source = RedisSource::builder()
+            .with_url("redis://127.0.0.1:1")
+            .with_key("myapp:config")
+            .with_timeout(Duration::from_millis(200))
+            .build()
+            .unwrap();
+        assert!(matches!(source.load(), Err(ConfigError::LoadError(_))));
+    }
+}
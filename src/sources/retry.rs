@@ -0,0 +1,244 @@
+//! Retry with backoff and circuit breaking for remote fetches.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How often, and how patiently, a failed fetch is retried.
+///
+/// Only transient failures are retried: connection errors, timeouts, and
+/// `408`, `429`, and `5xx` responses. Delays grow exponentially from the
+/// initial backoff up to the maximum; with jitter (the default) each delay is
+/// randomized between half and all of its value, so many instances don't
+/// retry in lockstep.
+///
+/// # Examples
+///
+/// ```rust
+/// use hotswap_config::sources::RetryPolicy;
+/// use std::time::Duration;
+///
+/// let retry = RetryPolicy::new(4).with_backoff(Duration::from_millis(200), Duration::from_secs(5));
+/// assert_eq!(retry.max_attempts(), 4);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    jitter: bool,
+}
+
+impl RetryPolicy {
+    /// Make up to `max_attempts` attempts per fetch (at least one).
+    ///
+    /// Backoff starts at 100ms and is capped at 10 seconds.
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+            jitter: true,
+        }
+    }
+
+    /// Make a single attempt per fetch.
+    pub fn none() -> Self {
+        Self::new(1)
+    }
+
+    /// Set the delay before the first retry and the cap on later delays.
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max.max(initial);
+        self
+    }
+
+    /// Enable or disable randomizing delays.
+    pub fn with_jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Get the maximum number of attempts per fetch.
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Get the delay after failed attempt number `attempt` (starting at 1).
+    pub(crate) fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        let delay = self
+            .initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff);
+        if self.jitter {
+            delay / 2 + delay.mul_f64(fastrand::f64() / 2.0)
+        } else {
+            delay
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+/// State of a [`CircuitBreaker`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakerState {
+    /// Requests go through
+    Closed,
+    /// Requests fail immediately until the reset timeout passes
+    Open,
+    /// The reset timeout passed; the next request is a trial that closes the
+    /// breaker on success or re-opens it on failure
+    HalfOpen,
+}
+
+impl BreakerState {
+    /// Numeric value reported by metrics: 0 closed, 1 half-open, 2 open.
+    pub fn as_metric(self) -> i64 {
+        match self {
+            Self::Closed => 0,
+            Self::HalfOpen => 1,
+            Self::Open => 2,
+        }
+    }
+}
+
+/// Stops fetching from a server that keeps failing.
+///
+/// After `failure_threshold` consecutive failed fetches (each after its
+/// retries), the breaker opens and fetches fail immediately, without a
+/// request, for `reset_timeout`. The next fetch after that is a trial: if it
+/// succeeds the breaker closes, otherwise it opens again. Responses that
+/// arrive but don't parse don't count as failures, since the server is up.
+///
+/// # Examples
+///
+/// ```rust
+/// use hotswap_config::sources::{BreakerState, CircuitBreaker};
+/// use std::time::Duration;
+///
+/// let breaker = CircuitBreaker::new(5, Duration::from_secs(30));
+/// assert_eq!(breaker.state(), BreakerState::Closed);
+/// ```
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    reset_timeout: Duration,
+    inner: Mutex<BreakerInner>,
+}
+
+#[derive(Debug)]
+struct BreakerInner {
+    consecutive_failures: u32,
+    /// When the breaker last opened, if it is open or half-open
+    opened_at: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    /// Open after `failure_threshold` consecutive failures (at least one),
+    /// and stay open for `reset_timeout`.
+    pub fn new(failure_threshold: u32, reset_timeout: Duration) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            reset_timeout,
+            inner: Mutex::new(BreakerInner {
+                consecutive_failures: 0,
+                opened_at: None,
+            }),
+        }
+    }
+
+    /// Get the current state.
+    pub fn state(&self) -> BreakerState {
+        let inner = self.inner.lock().unwrap();
+        match inner.opened_at {
+            None => BreakerState::Closed,
+            Some(opened_at) if opened_at.elapsed() < self.reset_timeout => BreakerState::Open,
+            Some(_) => BreakerState::HalfOpen,
+        }
+    }
+
+    /// Check whether a request may be made, returning the time left until
+    /// the breaker half-opens if not.
+    pub(crate) fn check(&self) -> std::result::Result<(), Duration> {
+        let inner = self.inner.lock().unwrap();
+        match inner.opened_at {
+            Some(opened_at) if opened_at.elapsed() < self.reset_timeout => {
+                Err(self.reset_timeout - opened_at.elapsed())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Record a successful request, returning the new state if it changed.
+    pub(crate) fn record_success(&self) -> Option<BreakerState> {
+        let before = self.state();
+        let mut inner = self.inner.lock().unwrap();
+        inner.consecutive_failures = 0;
+        inner.opened_at = None;
+        (before != BreakerState::Closed).then_some(BreakerState::Closed)
+    }
+
+    /// Record a failed request, returning the new state if it changed.
+    pub(crate) fn record_failure(&self) -> Option<BreakerState> {
+        let before = self.state();
+        let mut inner = self.inner.lock().unwrap();
+        inner.consecutive_failures = inner.consecutive_failures.saturating_add(1);
+        // A failed trial re-opens immediately
+        if before == BreakerState::HalfOpen || inner.consecutive_failures >= self.failure_threshold
+        {
+            inner.opened_at = Some(Instant::now());
+            return (before != BreakerState::Open).then_some(BreakerState::Open);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_grows_and_caps() {
+        let retry = RetryPolicy::new(5)
+            .with_backoff(Duration::from_millis(100), Duration::from_millis(300))
+            .with_jitter(false);
+        assert_eq!(retry.backoff(1), Duration::from_millis(100));
+        assert_eq!(retry.backoff(2), Duration::from_millis(200));
+        assert_eq!(retry.backoff(3), Duration::from_millis(300));
+        assert_eq!(retry.backoff(40), Duration::from_millis(300));
+
+        let jittered = retry.with_jitter(true);
+        for _ in 0..100 {
+            let delay = jittered.backoff(2);
+            assert!(delay >= Duration::from_millis(100) && delay <= Duration::from_millis(200));
+        }
+        assert_eq!(RetryPolicy::new(0).max_attempts(), 1);
+    }
+
+    #[test]
+    fn test_breaker_opens_half_opens_and_closes() {
+        let breaker = CircuitBreaker::new(2, Duration::from_millis(50));
+        assert_eq!(breaker.record_failure(), None);
+        assert!(breaker.check().is_ok());
+        assert_eq!(breaker.record_failure(), Some(BreakerState::Open));
+        assert_eq!(breaker.state(), BreakerState::Open);
+        assert!(breaker.check().is_err());
+
+        std::thread::sleep(Duration::from_millis(60));
+        assert_eq!(breaker.state(), BreakerState::HalfOpen);
+        assert!(breaker.check().is_ok());
+
+        // A failed trial re-opens at once
+        assert_eq!(breaker.record_failure(), Some(BreakerState::Open));
+        std::thread::sleep(Duration::from_millis(60));
+        assert_eq!(breaker.record_success(), Some(BreakerState::Closed));
+        assert_eq!(breaker.state(), BreakerState::Closed);
+        assert_eq!(breaker.record_success(), None);
+    }
+}
@@ -0,0 +1,937 @@
+//! ZooKeeper configuration source with watch-based reloads.
+//!
+//! Speaks the read-only subset of the ZooKeeper wire protocol it needs
+//! (sessions, `getData`, `getChildren`, watches, and pings) over a plain TCP
+//! connection, so no native client library is required. All session I/O
+//! runs on the blocking thread pool.
+
+use super::{ChangeNotifier, ChangeStream, ConfigSource, FailurePolicy};
+use crate::error::{ConfigError, Result};
+use std::collections::HashMap;
+use std::io::{ErrorKind, Read, Write};
+use std::net::TcpStream;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+/// Delay before the first reconnect attempt after the session fails.
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+/// Upper bound for the reconnect delay.
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Operation codes from the ZooKeeper protocol.
+const OP_GET_DATA: i32 = 4;
+const OP_GET_CHILDREN: i32 = 8;
+const OP_PING: i32 = 11;
+const OP_CLOSE_SESSION: i32 = -11;
+
+/// Reserved xids for server-initiated messages.
+const XID_WATCH_EVENT: i32 = -1;
+const XID_PING: i32 = -2;
+
+/// Error code for a missing znode.
+const ERR_NO_NODE: i32 = -101;
+
+/// Upper bound for a single frame, matching ZooKeeper's default `jute.maxbuffer`.
+const MAX_FRAME_LEN: usize = 0xfffff;
+
+/// ZooKeeper-based configuration source.
+///
+/// Loads the znode tree under a root path: each leaf znode becomes a key
+/// named by its path relative to the root, with its data as the value, so
+/// `/config/myapp/server/port` containing `8080` loads as `server.port`.
+/// Data of znodes that have children is ignored.
+///
+/// The tree is fetched by [`connect`](ZookeeperSourceBuilder::connect) and
+/// loads return the fetched values without touching the network. While
+/// [watched](ConfigSource::watch), the source keeps a session open with
+/// watches on every znode in the tree, re-fetches the tree when any of them
+/// changes or children are added or removed, and is then re-loaded.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use hotswap_config::prelude::*;
+/// use hotswap_config::sources::ZookeeperSource;
+/// use serde::Deserialize;
+///
+/// #[derive(Debug, Deserialize, Clone)]
+/// struct AppConfig {
+///     port: u16,
+/// }
+///
+/// # async fn example() -> Result<()> {
+/// let source = ZookeeperSource::builder()
+///     .with_hosts("zk1:2181,zk2:2181,zk3:2181")
+///     .with_root("/config/myapp")
+///     .connect()
+///     .await?;
+///
+/// let config = HotswapConfig::<AppConfig>::builder()
+///     .with_file("config/default.yaml")
+///     .with_zookeeper(source)
+///     .build()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ZookeeperSource {
+    hosts: Vec<String>,
+    root: String,
+    session_timeout: Duration,
+    priority: i32,
+    failure_policy: FailurePolicy,
+    /// The values from the last walk of the tree
+    current: Arc<RwLock<HashMap<String, config::Value>>>,
+}
+
+impl ZookeeperSource {
+    /// Create a new builder for constructing a ZooKeeper source.
+    pub fn builder() -> ZookeeperSourceBuilder {
+        ZookeeperSourceBuilder::new()
+    }
+
+    /// Signal a change whenever a watched znode changes, until the stream
+    /// is dropped.
+    ///
+    /// Each round walks the tree with watches set, keeping its values, and
+    /// waits for the first notification. The next walk then signals the
+    /// change once the new values are in place. After a session failure the
+    /// source reconnects with backoff and signals a change once, since
+    /// changes may have been missed.
+    async fn run(&self, notifier: &ChangeNotifier) {
+        let mut session: Option<Session> = None;
+        let mut changed = false;
+        let mut backoff = INITIAL_BACKOFF;
+        loop {
+            let source = self.clone();
+            let previous = session.take();
+            let result = blocking(move || {
+                let mut session = match previous {
+                    Some(session) => session,
+                    None => Session::connect(&source.hosts, source.session_timeout)?,
+                };
+                let values = to_values(session.walk(&source.root, true)?)?;
+                Ok((session, values))
+            })
+            .await;
+            let watching = match result {
+                Ok((watching, values)) => {
+                    *self.current.write().unwrap() = values;
+                    watching
+                }
+                Err(e) => {
+                    if !notifier.error(e) {
+                        return;
+                    }
+                    changed = true;
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(MAX_BACKOFF);
+                    continue;
+                }
+            };
+
+            backoff = INITIAL_BACKOFF;
+            if std::mem::take(&mut changed) && !notifier.changed() {
+                return;
+            }
+
+            match blocking(move || watching.wait_for_change()).await {
+                Ok(watching) => {
+                    session = Some(watching);
+                    changed = true;
+                }
+                Err(e) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(
+                        root = %self.root,
+                        error = %e,
+                        "ZooKeeper session failed, reconnecting"
+                    );
+                    if !notifier.error(e) {
+                        return;
+                    }
+                    changed = true;
+                }
+            }
+        }
+    }
+}
+
+impl ConfigSource for ZookeeperSource {
+    fn load(&self) -> Result<HashMap<String, config::Value>> {
+        Ok(self.current.read().unwrap().clone())
+    }
+
+    fn name(&self) -> String {
+        format!("zookeeper:{}{}", self.hosts.join(","), self.root)
+    }
+
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    fn failure_policy(&self) -> FailurePolicy {
+        self.failure_policy
+    }
+
+    fn watch(&self) -> Option<ChangeStream> {
+        let (notifier, changes) = ChangeStream::channel();
+        let source = self.clone();
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = source.run(&notifier) => {}
+                _ = notifier.closed() => {}
+            }
+        });
+        Some(changes)
+    }
+}
+
+/// Builder for constructing a `ZookeeperSource`.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use hotswap_config::sources::ZookeeperSource;
+/// use std::time::Duration;
+///
+/// # async fn example() -> hotswap_config::error::Result<()> {
+/// let source = ZookeeperSource::builder()
+///     .with_hosts("zk1:2181,zk2:2181")
+///     .with_root("/config/myapp")
+///     .with_session_timeout(Duration::from_secs(10))
+///     .with_priority(250)
+///     .connect()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct ZookeeperSourceBuilder {
+    hosts: Option<String>,
+    root: Option<String>,
+    session_timeout: Duration,
+    priority: i32,
+    failure_policy: FailurePolicy,
+}
+
+impl ZookeeperSourceBuilder {
+    /// Create a new builder with default settings.
+    pub fn new() -> Self {
+        Self {
+            hosts: None,
+            root: None,
+            session_timeout: Duration::from_secs(10),
+            priority: 250, // Same as HTTP sources: above files, below env vars
+            failure_policy: FailurePolicy::Required,
+        }
+    }
+
+    /// Set the ensemble as a comma-separated `host:port` list, tried in order.
+    pub fn with_hosts(mut self, hosts: impl Into<String>) -> Self {
+        self.hosts = Some(hosts.into());
+        self
+    }
+
+    /// Set the znode whose subtree holds the configuration.
+    pub fn with_root(mut self, root: impl Into<String>) -> Self {
+        self.root = Some(root.into());
+        self
+    }
+
+    /// Set the requested session timeout, which also bounds connects and reads.
+    ///
+    /// Defaults to 10 seconds.
+    pub fn with_session_timeout(mut self, timeout: Duration) -> Self {
+        self.session_timeout = timeout;
+        self
+    }
+
+    /// Set the priority for this source.
+    ///
+    /// Defaults to 250.
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Set what the loader does when this source fails to load.
+    ///
+    /// Default is [`FailurePolicy::Required`], which fails the whole load.
+    /// [`FailurePolicy::FallbackToCache`] keeps serving the values from the
+    /// last successful load, so a flaky endpoint doesn't block reloads of
+    /// other sources.
+    pub fn with_failure_policy(mut self, policy: FailurePolicy) -> Self {
+        self.failure_policy = policy;
+        self
+    }
+
+    /// Fetch the znode tree and create the source.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if hosts or the root are missing, the root is not an
+    /// absolute znode path, or the tree can't be fetched.
+    pub async fn connect(self) -> Result<ZookeeperSource> {
+        let hosts: Vec<String> = self
+            .hosts
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|host| !host.is_empty())
+            .map(str::to_string)
+            .collect();
+        if hosts.is_empty() {
+            return Err(ConfigError::LoadError(
+                "ZooKeeper hosts are required".to_string(),
+            ));
+        }
+
+        let root = self
+            .root
+            .ok_or_else(|| ConfigError::LoadError("ZooKeeper root is required".to_string()))?;
+        if !root.starts_with('/') {
+            return Err(ConfigError::LoadError(format!(
+                "ZooKeeper root must be an absolute path: {}",
+                root
+            )));
+        }
+        let root = match root.trim_end_matches('/') {
+            "" => "/".to_string(),
+            trimmed => trimmed.to_string(),
+        };
+
+        let source = ZookeeperSource {
+            hosts,
+            root,
+            session_timeout: self.session_timeout,
+            priority: self.priority,
+            failure_policy: self.failure_policy,
+            current: Arc::new(RwLock::new(HashMap::new())),
+        };
+
+        let fetching = source.clone();
+        let values = blocking(move || {
+            let mut session = Session::connect(&fetching.hosts, fetching.session_timeout)?;
+            to_values(session.walk(&fetching.root, false)?)
+        })
+        .await?;
+        *source.current.write().unwrap() = values;
+        Ok(source)
+    }
+}
+
+impl Default for ZookeeperSourceBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Run blocking session I/O off the async runtime.
+async fn blocking<T, F>(f: F) -> Result<T>
+where
+    F: FnOnce() -> Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| ConfigError::Other(format!("ZooKeeper task failed: {}", e)))?
+}
+
+/// Turn leaf data keyed by dotted path into nested values.
+fn to_values(leaves: HashMap<String, String>) -> Result<HashMap<String, config::Value>> {
+    let mut builder = config::Config::builder();
+    for (key, value) in leaves {
+        builder = builder
+            .set_override(key, value)
+            .map_err(|e| ConfigError::DeserializationError(format!("Invalid znode name: {}", e)))?;
+    }
+    builder
+        .build()
+        .and_then(|config| config.try_deserialize::<HashMap<String, config::Value>>())
+        .map_err(|e| {
+            ConfigError::DeserializationError(format!("Failed to parse znode tree: {}", e))
+        })
+}
+
+/// A ZooKeeper client session on one server connection.
+struct Session {
+    stream: TcpStream,
+    next_xid: i32,
+    /// A watch fired while another request was in flight
+    changed: bool,
+}
+
+impl Session {
+    /// Connect to the first reachable host and establish a session.
+    fn connect(hosts: &[String], timeout: Duration) -> Result<Self> {
+        let mut last_error = None;
+        for host in hosts {
+            match Self::connect_to(host, timeout) {
+                Ok(session) => return Ok(session),
+                Err(e) => last_error = Some(e),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| ConfigError::LoadError("No ZooKeeper hosts".to_string())))
+    }
+
+    fn connect_to(host: &str, timeout: Duration) -> Result<Self> {
+        let addr = std::net::ToSocketAddrs::to_socket_addrs(host)
+            .map_err(|e| zk_error(host, e))?
+            .next()
+            .ok_or_else(|| {
+                ConfigError::LoadError(format!("Unresolvable ZooKeeper host: {}", host))
+            })?;
+        let stream = TcpStream::connect_timeout(&addr, timeout).map_err(|e| zk_error(host, e))?;
+        stream
+            .set_read_timeout(Some(timeout))
+            .map_err(|e| zk_error(host, e))?;
+        let mut session = Self {
+            stream,
+            next_xid: 1,
+            changed: false,
+        };
+
+        // ConnectRequest: protocol version, last zxid seen, timeout, session
+        // id, and password, all zero for a new session
+        let mut request = Vec::new();
+        put_i32(&mut request, 0);
+        put_i64(&mut request, 0);
+        put_i32(
+            &mut request,
+            timeout.as_millis().min(i32::MAX as u128) as i32,
+        );
+        put_i64(&mut request, 0);
+        put_buffer(&mut request, &[0; 16]);
+        session.write_frame(&request)?;
+
+        let response = session.read_frame()?;
+        let mut reader = Reader::new(&response);
+        reader.i32()?; // protocol version
+        let negotiated = reader.i32()?;
+        if negotiated <= 0 {
+            return Err(ConfigError::LoadError(format!(
+                "ZooKeeper {} rejected the session",
+                host
+            )));
+        }
+        // Ping often enough to keep the session alive while waiting for watches
+        session
+            .stream
+            .set_read_timeout(Some(
+                Duration::from_millis(negotiated as u64 / 3).max(Duration::from_millis(10)),
+            ))
+            .map_err(|e| zk_error(host, e))?;
+        Ok(session)
+    }
+
+    /// Walk the tree under `root`, returning leaf data keyed by dotted path.
+    fn walk(&mut self, root: &str, watch: bool) -> Result<HashMap<String, String>> {
+        let mut leaves = HashMap::new();
+        let Some(children) = self.get_children(root, watch)? else {
+            return Err(ConfigError::LoadError(format!(
+                "ZooKeeper node not found: {}",
+                root
+            )));
+        };
+
+        let mut pending: Vec<(String, String)> = children
+            .into_iter()
+            .map(|child| (join(root, &child), child))
+            .collect();
+        while let Some((path, key)) = pending.pop() {
+            // Nodes deleted mid-walk are skipped; their watch fires anyway
+            match self.get_children(&path, watch)? {
+                Some(children) if !children.is_empty() => {
+                    pending.extend(
+                        children
+                            .into_iter()
+                            .map(|child| (join(&path, &child), format!("{}.{}", key, child))),
+                    );
+                }
+                Some(_) => {
+                    if let Some(data) = self.get_data(&path, watch)? {
+                        leaves.insert(key, String::from_utf8_lossy(&data).into_owned());
+                    }
+                }
+                None => {}
+            }
+        }
+        Ok(leaves)
+    }
+
+    /// Block until a watch fires, pinging to keep the session alive.
+    fn wait_for_change(mut self) -> Result<Self> {
+        while !self.changed {
+            match self.read_frame_or_idle()? {
+                Some(frame) => {
+                    let xid = Reader::new(&frame).i32()?;
+                    if xid == XID_WATCH_EVENT {
+                        self.changed = true;
+                    }
+                }
+                None => self.send(XID_PING, OP_PING, &[])?,
+            }
+        }
+        self.changed = false;
+        Ok(self)
+    }
+
+    fn get_children(&mut self, path: &str, watch: bool) -> Result<Option<Vec<String>>> {
+        let mut body = Vec::new();
+        put_string(&mut body, path);
+        put_bool(&mut body, watch);
+        let Some(reply) = self.request(OP_GET_CHILDREN, &body, path)? else {
+            return Ok(None);
+        };
+        parse_children(&reply).map(Some)
+    }
+
+    fn get_data(&mut self, path: &str, watch: bool) -> Result<Option<Vec<u8>>> {
+        let mut body = Vec::new();
+        put_string(&mut body, path);
+        put_bool(&mut body, watch);
+        let Some(reply) = self.request(OP_GET_DATA, &body, path)? else {
+            return Ok(None);
+        };
+        Ok(Some(Reader::new(&reply).buffer()?))
+    }
+
+    /// Send a request and return its reply body, or `None` if the node is missing.
+    fn request(&mut self, op: i32, body: &[u8], path: &str) -> Result<Option<Vec<u8>>> {
+        let xid = self.next_xid;
+        self.next_xid = self.next_xid.wrapping_add(1).max(1);
+        self.send(xid, op, body)?;
+
+        loop {
+            let Some(frame) = self.read_frame_or_idle()? else {
+                return Err(ConfigError::LoadError(format!(
+                    "Timed out waiting for ZooKeeper reply for {}",
+                    path
+                )));
+            };
+            let mut reader = Reader::new(&frame);
+            match reader.i32()? {
+                XID_WATCH_EVENT => self.changed = true,
+                reply_xid if reply_xid == xid => {
+                    reader.i64()?; // zxid
+                    return match reader.i32()? {
+                        0 => Ok(Some(reader.rest().to_vec())),
+                        ERR_NO_NODE => Ok(None),
+                        code => Err(ConfigError::LoadError(format!(
+                            "ZooKeeper error {} reading {}",
+                            code, path
+                        ))),
+                    };
+                }
+                // Ping replies and anything else unrelated
+                _ => {}
+            }
+        }
+    }
+
+    fn send(&mut self, xid: i32, op: i32, body: &[u8]) -> Result<()> {
+        let mut frame = Vec::with_capacity(8 + body.len());
+        put_i32(&mut frame, xid);
+        put_i32(&mut frame, op);
+        frame.extend_from_slice(body);
+        self.write_frame(&frame)
+    }
+
+    fn write_frame(&mut self, payload: &[u8]) -> Result<()> {
+        let mut frame = Vec::with_capacity(4 + payload.len());
+        put_i32(&mut frame, payload.len() as i32);
+        frame.extend_from_slice(payload);
+        self.stream
+            .write_all(&frame)
+            .map_err(|e| zk_error("session", e))
+    }
+
+    fn read_frame(&mut self) -> Result<Vec<u8>> {
+        self.read_frame_or_idle()?
+            .ok_or_else(|| ConfigError::LoadError("Timed out waiting for ZooKeeper".to_string()))
+    }
+
+    /// Read one frame, or `None` if the read timed out before it started.
+    fn read_frame_or_idle(&mut self) -> Result<Option<Vec<u8>>> {
+        let mut len = [0; 4];
+        match self.stream.read_exact(&mut len) {
+            Ok(()) => {}
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                return Ok(None);
+            }
+            Err(e) => return Err(zk_error("session", e)),
+        }
+
+        let mut frame = vec![0; frame_len(len)?];
+        self.stream
+            .read_exact(&mut frame)
+            .map_err(|e| zk_error("session", e))?;
+        Ok(Some(frame))
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        // Close explicitly so the server doesn't hold the session until it expires
+        let _ = self.send(0, OP_CLOSE_SESSION, &[]);
+    }
+}
+
+/// Check a frame's length prefix before anything is allocated for it.
+fn frame_len(header: [u8; 4]) -> Result<usize> {
+    match i32::from_be_bytes(header) {
+        len if len >= 0 && len as usize <= MAX_FRAME_LEN => Ok(len as usize),
+        len => Err(ConfigError::LoadError(format!(
+            "Invalid ZooKeeper frame length: {}",
+            len
+        ))),
+    }
+}
+
+/// Decode a `getChildren` reply body into child names.
+fn parse_children(reply: &[u8]) -> Result<Vec<String>> {
+    let mut reader = Reader::new(reply);
+    let count = reader.i32()?.max(0) as usize;
+    // Each name takes at least its length prefix
+    if count > reader.rest().len() / 4 {
+        return Err(ConfigError::LoadError(format!(
+            "Invalid ZooKeeper child count: {}",
+            count
+        )));
+    }
+    (0..count).map(|_| reader.string()).collect()
+}
+
+/// Decoder for ZooKeeper's big-endian jute encoding.
+struct Reader<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(buf: &'a [u8]) -> Self {
+        Self { buf }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.buf.len() < len {
+            return Err(ConfigError::LoadError(
+                "Truncated ZooKeeper message".to_string(),
+            ));
+        }
+        let (head, tail) = self.buf.split_at(len);
+        self.buf = tail;
+        Ok(head)
+    }
+
+    fn i32(&mut self) -> Result<i32> {
+        Ok(i32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn i64(&mut self) -> Result<i64> {
+        Ok(i64::from_be_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    /// A length-prefixed byte buffer; a length of -1 is an empty (null) buffer.
+    fn buffer(&mut self) -> Result<Vec<u8>> {
+        match self.i32()? {
+            len if len < 0 => Ok(Vec::new()),
+            len => Ok(self.take(len as usize)?.to_vec()),
+        }
+    }
+
+    fn string(&mut self) -> Result<String> {
+        String::from_utf8(self.buffer()?)
+            .map_err(|e| ConfigError::LoadError(format!("Invalid ZooKeeper string: {}", e)))
+    }
+
+    fn rest(&self) -> &'a [u8] {
+        self.buf
+    }
+}
+
+fn put_i32(buf: &mut Vec<u8>, value: i32) {
+    buf.extend_from_slice(&value.to_be_bytes());
+}
+
+fn put_i64(buf: &mut Vec<u8>, value: i64) {
+    buf.extend_from_slice(&value.to_be_bytes());
+}
+
+fn put_bool(buf: &mut Vec<u8>, value: bool) {
+    buf.push(value as u8);
+}
+
+fn put_buffer(buf: &mut Vec<u8>, value: &[u8]) {
+    put_i32(buf, value.len() as i32);
+    buf.extend_from_slice(value);
+}
+
+fn put_string(buf: &mut Vec<u8>, value: &str) {
+    put_buffer(buf, value.as_bytes());
+}
+
+fn join(parent: &str, child: &str) -> String {
+    match parent {
+        "/" => format!("/{}", child),
+        parent => format!("{}/{}", parent, child),
+    }
+}
+
+fn zk_error(target: &str, error: std::io::Error) -> ConfigError {
+    ConfigError::LoadError(format!("ZooKeeper {} failed: {}", target, error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+
+    /// A single-connection fake server serving `tree` (path → data).
+    ///
+    /// Sending a path on the returned channel fires a watch event for it.
+    fn fake_server(tree: BTreeMap<&'static str, &'static str>) -> (String, mpsc::Sender<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let (events, fire) = mpsc::channel::<String>();
+
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            stream
+                .set_read_timeout(Some(Duration::from_millis(20)))
+                .unwrap();
+            let mut session = Session {
+                stream,
+                next_xid: 0,
+                changed: false,
+            };
+
+            // ConnectResponse with the requested timeout
+            let request = session.read_frame().unwrap();
+            let timeout = Reader::new(&request[12..]).i32().unwrap();
+            let mut response = Vec::new();
+            put_i32(&mut response, 0);
+            put_i32(&mut response, timeout);
+            put_i64(&mut response, 1);
+            put_buffer(&mut response, &[0; 16]);
+            session.write_frame(&response).unwrap();
+
+            loop {
+                if let Ok(path) = fire.try_recv() {
+                    let mut event = Vec::new();
+                    put_i32(&mut event, XID_WATCH_EVENT);
+                    put_i64(&mut event, -1);
+                    put_i32(&mut event, 0);
+                    put_i32(&mut event, 3); // NodeDataChanged
+                    put_i32(&mut event, 3); // SyncConnected
+                    put_string(&mut event, &path);
+                    session.write_frame(&event).unwrap();
+                }
+
+                let frame = match session.read_frame_or_idle() {
+                    Ok(Some(frame)) => frame,
+                    Ok(None) => continue,
+                    Err(_) => return,
+                };
+                let mut reader = Reader::new(&frame);
+                let xid = reader.i32().unwrap();
+                let op = reader.i32().unwrap();
+                let mut reply = Vec::new();
+                put_i32(&mut reply, xid);
+                put_i64(&mut reply, 1);
+                match op {
+                    OP_GET_CHILDREN | OP_GET_DATA => {
+                        let path = reader.string().unwrap();
+                        let Some(data) = tree.get(path.as_str()) else {
+                            put_i32(&mut reply, ERR_NO_NODE);
+                            session.write_frame(&reply).unwrap();
+                            continue;
+                        };
+                        put_i32(&mut reply, 0);
+                        if op == OP_GET_DATA {
+                            put_string(&mut reply, data);
+                        } else {
+                            let prefix = join(&path, "");
+                            let children: Vec<&str> = tree
+                                .keys()
+                                .filter_map(|key| key.strip_prefix(prefix.as_str()))
+                                .filter(|rest| !rest.contains('/'))
+                                .collect();
+                            put_i32(&mut reply, children.len() as i32);
+                            for child in children {
+                                put_string(&mut reply, child);
+                            }
+                        }
+                    }
+                    OP_PING => put_i32(&mut reply, 0),
+                    OP_CLOSE_SESSION => return,
+                    _ => put_i32(&mut reply, -6), // Unimplemented
+                }
+                session.write_frame(&reply).unwrap();
+            }
+        });
+        (addr, events)
+    }
+
+    fn tree() -> BTreeMap<&'static str, &'static str> {
+        BTreeMap::from([
+            ("/config", ""),
+            ("/config/app", ""),
+            ("/config/app/debug", "true"),
+            ("/config/app/server", ""),
+            ("/config/app/server/port", "8080"),
+            ("/config/app/server/host", "localhost"),
+        ])
+    }
+
+    #[tokio::test]
+    async fn test_load_znode_tree() {
+        let (addr, _events) = fake_server(tree());
+        let source = ZookeeperSource::builder()
+            .with_hosts(format!("127.0.0.1:1,{}", addr))
+            .with_root("/config/app/")
+            .with_session_timeout(Duration::from_secs(1))
+            .connect()
+            .await
+            .unwrap();
+        assert_eq!(source.root, "/config/app");
+        assert_eq!(
+            source.name(),
+            format!("zookeeper:127.0.0.1:1,{}/config/app", addr)
+        );
+        assert_eq!(source.priority(), 250);
+
+        let values = source.load().unwrap();
+        let server = values["server"].clone().into_table().unwrap();
+        assert_eq!(server["port"].clone().into_int().unwrap(), 8080);
+        assert_eq!(server["host"].clone().into_string().unwrap(), "localhost");
+        assert!(values["debug"].clone().into_bool().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_missing_root_fails() {
+        let (addr, _events) = fake_server(tree());
+        let result = ZookeeperSource::builder()
+            .with_hosts(addr)
+            .with_root("/config/other")
+            .connect()
+            .await;
+        assert!(matches!(result, Err(ConfigError::LoadError(_))));
+    }
+
+    #[test]
+    fn test_watch_event_ends_wait() {
+        let (addr, events) = fake_server(tree());
+        let mut session = Session::connect(&[addr], Duration::from_millis(300)).unwrap();
+        session.walk("/config/app", true).unwrap();
+
+        events.send("/config/app/server/port".to_string()).unwrap();
+        let mut session = session.wait_for_change().unwrap();
+        assert!(!session.changed);
+        // The session is still usable after waiting, including across pings
+        assert_eq!(session.walk("/config/app", true).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_frame_length_is_bounded() {
+        assert_eq!(frame_len(0i32.to_be_bytes()).unwrap(), 0);
+        assert_eq!(
+            frame_len((MAX_FRAME_LEN as i32).to_be_bytes()).unwrap(),
+            MAX_FRAME_LEN
+        );
+        assert!(frame_len((MAX_FRAME_LEN as i32 + 1).to_be_bytes()).is_err());
+        assert!(frame_len(i32::MAX.to_be_bytes()).is_err());
+        assert!(frame_len((-1i32).to_be_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_oversized_frame_fails_connect() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            // Claim a 2 GiB ConnectResponse and send nothing else
+            stream.write_all(&i32::MAX.to_be_bytes()).unwrap();
+            std::thread::sleep(Duration::from_millis(500));
+        });
+
+        let result = Session::connect(&[addr], Duration::from_secs(1));
+        assert!(
+            matches!(result, Err(ConfigError::LoadError(message)) if message.contains("frame length"))
+        );
+    }
+
+    #[test]
+    fn test_malformed_messages_fail() {
+        // Too short for an i32
+        assert!(Reader::new(&[0, 0]).i32().is_err());
+
+        // A buffer claiming more bytes than the message holds
+        let mut message = Vec::new();
+        put_i32(&mut message, 64);
+        message.extend_from_slice(b"short");
+        assert!(Reader::new(&message).buffer().is_err());
+
+        // A null buffer is empty
+        assert!(
+            Reader::new(&(-1i32).to_be_bytes())
+                .buffer()
+                .unwrap()
+                .is_empty()
+        );
+
+        // Names must be UTF-8
+        let mut message = Vec::new();
+        put_buffer(&mut message, &[0xff, 0xfe]);
+        assert!(Reader::new(&message).string().is_err());
+
+        // A child count larger than the reply could hold
+        let mut reply = Vec::new();
+        put_i32(&mut reply, i32::MAX);
+        put_string(&mut reply, "one");
+        assert!(parse_children(&reply).is_err());
+
+        // A child count with too few names behind it
+        let mut reply = Vec::new();
+        put_i32(&mut reply, 2);
+        put_string(&mut reply, "one");
+        put_string(&mut reply, "two");
+        reply.truncate(reply.len() - 3);
+        assert!(parse_children(&reply).is_err());
+
+        let mut reply = Vec::new();
+        put_i32(&mut reply, 2);
+        put_string(&mut reply, "one");
+        put_string(&mut reply, "two");
+        assert_eq!(parse_children(&reply).unwrap(), ["one", "two"]);
+    }
+
+    #[tokio::test]
+    async fn test_builder_validation() {
+        assert!(
+            ZookeeperSource::builder()
+                .with_root("/config")
+                .connect()
+                .await
+                .is_err()
+        );
+        assert!(
+            ZookeeperSource::builder()
+                .with_hosts(" , ")
+                .with_root("/config")
+                .connect()
+                .await
+                .is_err()
+        );
+        assert!(
+            ZookeeperSource::builder()
+                .with_hosts("zk:2181")
+                .with_root("config")
+                .connect()
+                .await
+                .is_err()
+        );
+    }
+}
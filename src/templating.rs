@@ -0,0 +1,166 @@
+//! Rendering configuration files as templates before parsing.
+//!
+//! With templating enabled via
+//! [`HotswapConfigBuilder::with_templating`](crate::core::HotswapConfigBuilder::with_templating)
+//! or [`with_template_var`](crate::core::HotswapConfigBuilder::with_template_var),
+//! every file added with the builder is rendered through
+//! [Tera](https://keats.github.io/tera/docs/) before it is parsed, so loops and
+//! conditionals can generate repetitive sections without external tooling.
+//! Templates see:
+//!
+//! - `env`: the process environment, read again on every load
+//!   (`{{ env.REGION }}`, `{{ env.LOG_LEVEL | default(value="info") }}`)
+//! - every variable given to the builder, at the top level (`{{ regions }}`)
+//!
+//! Rendering happens on every load, so a reload picks up both file and
+//! environment changes. Template errors fail the load like parse errors, with
+//! the file and the reason (e.g. an undefined variable).
+//!
+//! # Examples
+//!
+//! With `config/app.yaml`:
+//!
+//! ```text
+//! region: {{ env.REGION | default(value="us-east-1") }}
+//! endpoints:
+//! {% for region in regions %}
+//!   - https://api.{{ region }}.example.com
+//! {% endfor %}
+//! ```
+//!
+//! ```rust,no_run
+//! use hotswap_config::prelude::*;
+//! use serde::Deserialize;
+//!
+//! #[derive(Debug, Deserialize, Clone)]
+//! struct AppConfig {
+//!     region: String,
+//!     endpoints: Vec<String>,
+//! }
+//!
+//! # async fn example() -> Result<()> {
+//! let config = HotswapConfig::<AppConfig>::builder()
+//!     .with_file("config/app.yaml")
+//!     .with_template_var("regions", ["us-east-1", "eu-west-1"])
+//!     .build()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{ConfigError, Result};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Variables available to templated configuration files.
+///
+/// Used directly with
+/// [`FileSource::with_template`](crate::sources::FileSource::with_template) and
+/// [`DirectorySource::with_template`](crate::sources::DirectorySource::with_template);
+/// the builder keeps one for its own files. The environment is always
+/// available as `env`.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use hotswap_config::sources::FileSource;
+/// use hotswap_config::templating::TemplateContext;
+///
+/// let context = TemplateContext::new().with_var("replicas", 3);
+/// let source = FileSource::new("config/app.yaml").with_template(context);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TemplateContext {
+    vars: tera::Context,
+}
+
+impl TemplateContext {
+    /// Create a context with no variables besides `env`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Make `value` available to templates as `name`.
+    ///
+    /// Any serializable value works, including lists and maps for `{% for %}`
+    /// loops. Setting `env` hides the environment.
+    pub fn with_var(mut self, name: impl Into<String>, value: impl Serialize) -> Self {
+        self.set_var(name, value);
+        self
+    }
+
+    /// Make `value` available to templates as `name`.
+    pub(crate) fn set_var(&mut self, name: impl Into<String>, value: impl Serialize) {
+        self.vars.insert(name, &value);
+    }
+
+    /// Render the template `content` of the file at `path`.
+    pub(crate) fn render(&self, path: &Path, content: &str) -> Result<String> {
+        let name = path.display().to_string();
+        let mut context = tera::Context::new();
+        context.insert("env", &std::env::vars().collect::<BTreeMap<_, _>>());
+        context.extend(self.vars.clone());
+
+        let mut tera = tera::Tera::default();
+        tera.add_raw_template(&name, content)
+            .and_then(|()| tera.render(&name, &context))
+            .map_err(|e| ConfigError::ParseError(format!("template {}: {}", name, describe(&e))))
+    }
+}
+
+/// Describe a Tera error with its causes, which hold the useful detail.
+fn describe(error: &tera::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = std::error::Error::source(error);
+    while let Some(cause) = source {
+        message.push_str(&format!(": {}", cause));
+        source = cause.source();
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_vars_and_loops() {
+        let context = TemplateContext::new().with_var("regions", ["us", "eu"]);
+        let rendered = context
+            .render(
+                Path::new("app.yaml"),
+                "endpoints:\n{% for r in regions %}  - https://{{ r }}.example.com\n{% endfor %}",
+            )
+            .unwrap();
+        assert_eq!(
+            rendered,
+            "endpoints:\n  - https://us.example.com\n  - https://eu.example.com\n"
+        );
+    }
+
+    #[test]
+    fn test_render_env_with_default() {
+        let context = TemplateContext::new();
+        let rendered = context
+            .render(
+                Path::new("app.yaml"),
+                "home: {{ env.HOTSWAP_TEMPLATE_UNSET | default(value=\"none\") }}",
+            )
+            .unwrap();
+        assert_eq!(rendered, "home: none");
+    }
+
+    #[test]
+    fn test_undefined_variable_names_file_and_variable() {
+        let error = TemplateContext::new()
+            .render(Path::new("app.yaml"), "port: {{ port }}")
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.contains("app.yaml") && error.contains("port"),
+            "{}",
+            error
+        );
+    }
+}
@@ -0,0 +1,276 @@
+//! Helpers for testing code that reacts to configuration changes.
+//!
+//! [`MemorySource`] is a source whose values are changed at runtime, pushing
+//! each change through the same reload pipeline as a watched file, without
+//! temp files or sleeps. [`TestConfigExt`] makes the next reload fail or the
+//! next new configuration be rejected, to exercise error paths.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use hotswap_config::prelude::*;
+//! use hotswap_config::testing::MemorySource;
+//! use serde::Deserialize;
+//!
+//! #[derive(Debug, Deserialize, Clone)]
+//! struct AppConfig {
+//!     port: u16,
+//! }
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<()> {
+//! let source = MemorySource::new().with_value("port", 8080);
+//! let config = HotswapConfig::<AppConfig>::builder()
+//!     .with_source(source.clone())
+//!     .build()
+//!     .await?;
+//!
+//! let mut changes = config.changes();
+//! source.set("port", 9090);
+//! changes.changed().await.unwrap();
+//! assert_eq!(config.get().port, 9090);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::core::HotswapConfig;
+use crate::error::{ConfigError, Result, ValidationError};
+use crate::sources::{ChangeNotifier, ChangeStream, ConfigSource};
+use config::Value;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+/// Failures queued by [`TestConfigExt`], consumed one per reload or update.
+#[derive(Default)]
+pub(crate) struct Faults {
+    reloads: Mutex<VecDeque<ConfigError>>,
+    rejections: Mutex<VecDeque<ValidationError>>,
+}
+
+impl Faults {
+    /// Take the error the next reload should fail with, if one is queued.
+    pub(crate) fn next_reload_failure(&self) -> Option<ConfigError> {
+        self.reloads.lock().unwrap().pop_front()
+    }
+
+    /// Take the error the next new configuration should be rejected with, if
+    /// one is queued.
+    pub(crate) fn next_rejection(&self) -> Option<ValidationError> {
+        self.rejections.lock().unwrap().pop_front()
+    }
+}
+
+/// Simulated failures for a [`HotswapConfig`] under test.
+///
+/// Each call queues one failure; they are consumed in order by the next
+/// reloads or updates, which then fail exactly like a real failure would:
+/// metrics are recorded, error subscribers and reload error handlers are
+/// called, and the current config is kept.
+///
+/// # Examples
+///
+/// ```rust
+/// use hotswap_config::prelude::*;
+/// use hotswap_config::testing::TestConfigExt;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let config = HotswapConfig::new(8080);
+/// config.reject_next(ValidationError::invalid_field("port", "reserved"));
+///
+/// assert!(config.update(9090).await.is_err());
+/// assert_eq!(*config.get(), 8080);
+/// assert!(config.update(9090).await.is_ok());
+/// # }
+/// ```
+pub trait TestConfigExt {
+    /// Make the next reload fail with `error` before any source is loaded.
+    fn fail_next_reload(&self, error: ConfigError);
+
+    /// Make the next update, or the next reload that loads a changed
+    /// configuration, fail validation with `error`, as if a validator had
+    /// rejected the new configuration.
+    fn reject_next(&self, error: ValidationError);
+}
+
+impl<T> TestConfigExt for HotswapConfig<T> {
+    fn fail_next_reload(&self, error: ConfigError) {
+        self.faults().reloads.lock().unwrap().push_back(error);
+    }
+
+    fn reject_next(&self, error: ValidationError) {
+        self.faults().rejections.lock().unwrap().push_back(error);
+    }
+}
+
+/// Values of a [`MemorySource`] and the streams watching it.
+#[derive(Default)]
+struct MemoryState {
+    /// Dotted keys and their values, in the order they were set
+    values: Vec<(String, Value)>,
+    notifiers: Vec<ChangeNotifier>,
+}
+
+/// In-memory configuration source whose values can be changed at runtime.
+///
+/// Clones share the same values, so a test keeps one clone and hands the
+/// other to the builder. Every [`set`](Self::set) or
+/// [`remove`](Self::remove) signals a change, which reloads the configuration
+/// through the usual validation pipeline. Keys are dotted paths
+/// (`server.port`), and the source sits at priority 100, like files.
+#[derive(Clone)]
+pub struct MemorySource {
+    state: Arc<Mutex<MemoryState>>,
+    priority: i32,
+}
+
+impl MemorySource {
+    /// Create an empty source.
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(MemoryState::default())),
+            priority: 100,
+        }
+    }
+
+    /// Set `key` to `value` before the source is used.
+    pub fn with_value(self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.set(key, value);
+        self
+    }
+
+    /// Set the priority for this source.
+    ///
+    /// Higher priority sources override lower priority ones.
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Set `key` to `value` and signal a change.
+    pub fn set(&self, key: impl Into<String>, value: impl Into<Value>) {
+        let key = key.into();
+        let value = value.into();
+        self.modify(|values| match values.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, existing)) => *existing = value,
+            None => values.push((key, value)),
+        });
+    }
+
+    /// Remove `key`, and any keys set below it, and signal a change.
+    pub fn remove(&self, key: &str) {
+        let nested = format!("{}.", key);
+        self.modify(|values| values.retain(|(k, _)| k != key && !k.starts_with(&nested)));
+    }
+
+    /// Apply `change` to the values, then notify every watching stream.
+    fn modify(&self, change: impl FnOnce(&mut Vec<(String, Value)>)) {
+        let mut state = self.state.lock().unwrap();
+        change(&mut state.values);
+        state.notifiers.retain(|notifier| notifier.changed());
+    }
+}
+
+impl Default for MemorySource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConfigSource for MemorySource {
+    fn load(&self) -> Result<HashMap<String, Value>> {
+        let state = self.state.lock().unwrap();
+        state
+            .values
+            .iter()
+            .try_fold(config::Config::builder(), |builder, (key, value)| {
+                builder.set_override(key.as_str(), value.clone())
+            })
+            .and_then(|builder| builder.build())
+            .and_then(|config| config.try_deserialize::<HashMap<String, Value>>())
+            .map_err(|e| ConfigError::LoadError(format!("Failed to load memory source: {}", e)))
+    }
+
+    fn name(&self) -> String {
+        "memory".to_string()
+    }
+
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    fn watch(&self) -> Option<ChangeStream> {
+        let (notifier, changes) = ChangeStream::channel();
+        self.state.lock().unwrap().notifiers.push(notifier);
+        Some(changes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, Clone)]
+    struct AppConfig {
+        server: ServerConfig,
+    }
+
+    #[derive(Debug, Deserialize, Clone)]
+    struct ServerConfig {
+        port: u16,
+        host: Option<String>,
+    }
+
+    async fn build(source: &MemorySource) -> HotswapConfig<AppConfig> {
+        HotswapConfig::<AppConfig>::builder()
+            .with_source(source.clone())
+            .build()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_set_and_remove_trigger_reloads() {
+        let source = MemorySource::new()
+            .with_value("server.port", 8080)
+            .with_value("server.host", "localhost");
+        let config = build(&source).await;
+        let mut changes = config.changes();
+
+        source.set("server.port", 9090);
+        changes.changed().await.unwrap();
+        assert_eq!(config.get().server.port, 9090);
+
+        source.remove("server.host");
+        changes.changed().await.unwrap();
+        assert_eq!(config.get().server.host, None);
+    }
+
+    #[tokio::test]
+    async fn test_simulated_failures() {
+        let source = MemorySource::new().with_value("server.port", 8080);
+        let config = build(&source).await;
+
+        config.fail_next_reload(ConfigError::LoadError("connection refused".to_string()));
+        let error = config.reload().await.unwrap_err();
+        assert!(error.to_string().contains("connection refused"));
+
+        config.reject_next(ValidationError::custom("rejected by test"));
+        let update = AppConfig {
+            server: ServerConfig {
+                port: 9090,
+                host: None,
+            },
+        };
+        assert!(matches!(
+            config.update(update.clone()).await,
+            Err(ConfigError::ValidationError(_))
+        ));
+        assert_eq!(config.get().server.port, 8080);
+
+        // Failures are consumed, so the next update goes through
+        config.update(update).await.unwrap();
+        assert_eq!(config.get().server.port, 9090);
+    }
+}
@@ -0,0 +1,210 @@
+//! Web framework integration for serving requests with a consistent config.
+//!
+//! [`ConfigLayer`] is a tower layer that takes a snapshot of the current
+//! configuration as each request arrives and stores it in the request
+//! extensions. The [`Config<T>`] axum extractor reads that snapshot, so every
+//! handler and extractor sees the same configuration for the whole request,
+//! even if a reload swaps in a new one halfway through.
+//!
+//! Without the layer, `Config<T>` falls back to a [`HotswapConfig<T>`] added
+//! as an `Extension`, snapshotting it the first time it is extracted.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use axum::{Router, routing::get};
+//! use hotswap_config::prelude::*;
+//! use hotswap_config::web::{Config, ConfigLayer};
+//! use serde::Deserialize;
+//!
+//! #[derive(Debug, Deserialize, Clone)]
+//! struct AppConfig {
+//!     greeting: String,
+//! }
+//!
+//! async fn hello(Config(config): Config<AppConfig>) -> String {
+//!     config.greeting.clone()
+//! }
+//!
+//! # fn example(config: HotswapConfig<AppConfig>) {
+//! let app: Router = Router::new()
+//!     .route("/", get(hello))
+//!     .layer(ConfigLayer::new(config));
+//! # }
+//! ```
+
+use crate::core::HotswapConfig;
+use axum::extract::FromRequestParts;
+use axum::http::request::Parts;
+use axum::http::{Request, StatusCode};
+use std::ops::Deref;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Extractor for the configuration snapshot of the current request.
+///
+/// Clones the `Arc<T>` taken when the request arrived (see [`ConfigLayer`]),
+/// or from a `HotswapConfig<T>` extension. Rejects the request with
+/// `500 Internal Server Error` if neither is present.
+pub struct Config<T>(pub Arc<T>);
+
+impl<T> Clone for Config<T> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<T> Deref for Config<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<S, T> FromRequestParts<S> for Config<T>
+where
+    S: Send + Sync,
+    T: Send + Sync + 'static,
+{
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        if let Some(snapshot) = parts.extensions.get::<Config<T>>() {
+            return Ok(snapshot.clone());
+        }
+
+        // Snapshot the handle once, so later extractors see the same config
+        let snapshot = parts
+            .extensions
+            .get::<HotswapConfig<T>>()
+            .map(|config| Config(config.get()))
+            .ok_or((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Configuration is not available; add ConfigLayer or a HotswapConfig extension",
+            ))?;
+        parts.extensions.insert(snapshot.clone());
+        Ok(snapshot)
+    }
+}
+
+/// Tower layer that stores a snapshot of the configuration in each request's
+/// extensions, for [`Config<T>`] or `request.extensions().get::<Config<T>>()`.
+pub struct ConfigLayer<T> {
+    config: HotswapConfig<T>,
+}
+
+impl<T> ConfigLayer<T> {
+    /// Snapshot `config` for every request passing through the layer.
+    pub fn new(config: HotswapConfig<T>) -> Self {
+        Self { config }
+    }
+}
+
+impl<T> Clone for ConfigLayer<T> {
+    fn clone(&self) -> Self {
+        Self {
+            config: self.config.clone(),
+        }
+    }
+}
+
+impl<S, T> Layer<S> for ConfigLayer<T> {
+    type Service = ConfigService<S, T>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ConfigService {
+            inner,
+            config: self.config.clone(),
+        }
+    }
+}
+
+/// Service produced by [`ConfigLayer`].
+pub struct ConfigService<S, T> {
+    inner: S,
+    config: HotswapConfig<T>,
+}
+
+impl<S: Clone, T> Clone for ConfigService<S, T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            config: self.config.clone(),
+        }
+    }
+}
+
+impl<S, T, B> Service<Request<B>> for ConfigService<S, T>
+where
+    S: Service<Request<B>>,
+    T: Send + Sync + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<B>) -> Self::Future {
+        request.extensions_mut().insert(Config(self.config.get()));
+        self.inner.call(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::routing::get;
+    use axum::{Extension, Router};
+    use tower::ServiceExt;
+
+    #[derive(Debug, PartialEq)]
+    struct TestConfig {
+        port: u16,
+    }
+
+    async fn port(first: Config<TestConfig>, second: Config<TestConfig>) -> String {
+        assert!(Arc::ptr_eq(&first.0, &second.0));
+        first.port.to_string()
+    }
+
+    async fn body(router: Router) -> (StatusCode, String) {
+        let response = router
+            .oneshot(Request::builder().uri("/").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, String::from_utf8(bytes.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_layer_snapshots_current_config() {
+        let config = HotswapConfig::new(TestConfig { port: 8080 });
+        let router = Router::new()
+            .route("/", get(port))
+            .layer(ConfigLayer::new(config.clone()));
+        assert_eq!(body(router.clone()).await, (StatusCode::OK, "8080".into()));
+
+        config.update(TestConfig { port: 9090 }).await.unwrap();
+        assert_eq!(body(router).await, (StatusCode::OK, "9090".into()));
+    }
+
+    #[tokio::test]
+    async fn test_extension_fallback_and_rejection() {
+        let config = HotswapConfig::new(TestConfig { port: 8080 });
+        let router = Router::new().route("/", get(port)).layer(Extension(config));
+        assert_eq!(body(router).await, (StatusCode::OK, "8080".into()));
+
+        let (status, _) = body(Router::new().route("/", get(port))).await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
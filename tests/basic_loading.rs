@@ -44,9 +44,9 @@ database:
     )
     .unwrap();
 
-    let config: HotswapConfig<AppConfig> = HotswapConfig::builder()
+    let config: HotswapConfig<AppConfig> = HotswapConfig::<AppConfig>::builder()
         .with_file(&config_path)
-        .build::<AppConfig>()
+        .build()
         .await
         .unwrap();
 
@@ -58,7 +58,6 @@ database:
 }
 
 #[tokio::test]
-#[ignore] // Skipped: config crate doesn't deep-merge nested structs by default
 async fn test_file_precedence() {
     let temp_dir = TempDir::new().unwrap();
     let default_path = temp_dir.path().join("default.yaml");
@@ -88,10 +87,10 @@ server:
     )
     .unwrap();
 
-    let config: HotswapConfig<AppConfig> = HotswapConfig::builder()
+    let config: HotswapConfig<AppConfig> = HotswapConfig::<AppConfig>::builder()
         .with_file(&default_path)
         .with_file(&override_path)
-        .build::<AppConfig>()
+        .build()
         .await
         .unwrap();
 
@@ -101,6 +100,292 @@ server:
     assert_eq!(cfg.database.max_connections, 10); // From default
 }
 
+#[tokio::test]
+async fn test_explain_provenance() {
+    let temp_dir = TempDir::new().unwrap();
+    let default_path = temp_dir.path().join("default.yaml");
+    let override_path = temp_dir.path().join("override.yaml");
+
+    fs::write(
+        &default_path,
+        r#"
+server:
+  port: 8080
+  host: localhost
+database:
+  url: postgres://localhost/db
+  max_connections: 10
+"#,
+    )
+    .unwrap();
+    fs::write(&override_path, "server:\n  port: 9090\n").unwrap();
+
+    let config: HotswapConfig<AppConfig> = HotswapConfig::<AppConfig>::builder()
+        .with_file(&default_path)
+        .with_file(&override_path)
+        .build()
+        .await
+        .unwrap();
+
+    let explanation = config.explain("server.port").unwrap();
+    assert_eq!(explanation.value.clone().unwrap().into_int().unwrap(), 9090);
+    assert_eq!(explanation.contributions.len(), 2);
+    let winner = explanation.winners().next().unwrap();
+    assert!(winner.source.contains("override.yaml"));
+
+    let explanation = config.explain("database.max_connections").unwrap();
+    assert!(
+        explanation
+            .winners()
+            .next()
+            .unwrap()
+            .source
+            .contains("default.yaml")
+    );
+
+    // Reloads refresh the provenance; manual updates clear it
+    fs::write(&override_path, "database:\n  max_connections: 20\n").unwrap();
+    config.reload().await.unwrap();
+    let explanation = config.explain("server.port").unwrap();
+    assert_eq!(explanation.contributions.len(), 1);
+    assert_eq!(config.get().database.max_connections, 20);
+
+    let mut updated = (*config.get()).clone();
+    updated.server.port = 7070;
+    config.update(updated).await.unwrap();
+    assert!(config.explain("server.port").is_none());
+}
+
+#[cfg(feature = "json")]
+#[tokio::test]
+async fn test_startup_report() {
+    use hotswap_config::secrets::Secret;
+    use serde::Serialize;
+
+    #[derive(Debug, Serialize, Deserialize, Clone)]
+    struct Database {
+        url: String,
+        password: Secret<String>,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Clone)]
+    struct ReportConfig {
+        port: u16,
+        database: Database,
+    }
+
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.yaml");
+    fs::write(
+        &config_path,
+        "port: 8080\ndatabase:\n  url: postgres://localhost/db\n  password: hunter2\n",
+    )
+    .unwrap();
+    unsafe {
+        std::env::set_var("HOTSWAP_REPORT_TEST__PORT", "9090");
+    }
+
+    let config = HotswapConfig::<ReportConfig>::builder()
+        .with_file(&config_path)
+        .with_env_overrides("HOTSWAP_REPORT_TEST", "__")
+        .with_validation(|c: &ReportConfig| {
+            if c.port == 0 {
+                return Err(ValidationError::invalid_field("port", "must not be 0"));
+            }
+            Ok(())
+        })
+        .build()
+        .await
+        .unwrap();
+
+    let report = config.report().unwrap();
+    let sources: Vec<_> = report
+        .sources
+        .iter()
+        .map(|s| (s.name.starts_with("env:"), s.keys))
+        .collect();
+    assert_eq!(sources, [(false, 3), (true, 1)]);
+    assert!(report.sources.iter().all(|s| s.load_duration.is_some()));
+    assert_eq!(report.env_overrides, ["port"]);
+    assert_eq!(report.values["port"], 9090);
+    assert_eq!(report.values["database"]["password"], "[REDACTED]");
+    assert!(report.validation.is_ok());
+    assert!(!report.to_string().contains("hunter2"));
+}
+
+#[tokio::test]
+async fn test_raw_keys_by_path() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.yaml");
+    fs::write(
+        &config_path,
+        r#"
+server:
+  port: 8080
+  host: localhost
+database:
+  url: postgres://localhost/db
+  max_connections: "10"
+plugins:
+  audit:
+    endpoint: https://audit.internal
+    enabled: true
+"#,
+    )
+    .unwrap();
+
+    let config = HotswapConfig::<AppConfig>::builder()
+        .with_file(&config_path)
+        .build()
+        .await
+        .unwrap();
+
+    // Keys the config type doesn't have
+    let endpoint = config.get_raw("plugins.audit.endpoint").unwrap();
+    assert_eq!(endpoint.into_string().unwrap(), "https://audit.internal");
+    assert!(config.get_typed::<bool>("plugins.audit.enabled").unwrap());
+    assert_eq!(
+        config.get_typed::<u32>("database.max_connections").unwrap(),
+        10
+    );
+    assert!(config.get_raw("plugins.missing").is_none());
+    assert!(matches!(
+        config.get_typed::<u16>("plugins.missing"),
+        Err(ConfigError::LoadError(_))
+    ));
+    let error = config.get_typed::<u16>("server.host").unwrap_err();
+    assert!(error.to_string().contains("server.host"), "{}", error);
+}
+
+#[tokio::test]
+async fn test_optional_and_env_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let default_path = temp_dir.path().join("default.yaml");
+    fs::write(
+        &default_path,
+        r#"
+server:
+  port: 8080
+  host: localhost
+database:
+  url: postgres://localhost/db
+  max_connections: 10
+"#,
+    )
+    .unwrap();
+    fs::write(
+        temp_dir.path().join("production.yaml"),
+        "server:\n  port: 9090\n",
+    )
+    .unwrap();
+    let env_template = temp_dir.path().join("{env}.yaml");
+    let env_template = env_template.to_str().unwrap();
+
+    // Missing optional files are skipped; the environment file is picked up
+    let config: HotswapConfig<AppConfig> = HotswapConfig::<AppConfig>::builder()
+        .with_file_required(&default_path)
+        .with_file_optional(temp_dir.path().join("local.yaml"))
+        .with_env_file(env_template)
+        .with_environment("production")
+        .build()
+        .await
+        .unwrap();
+    assert_eq!(config.get().server.port, 9090);
+
+    // No file for this environment, and no environment selected at all
+    let staging: HotswapConfig<AppConfig> = HotswapConfig::<AppConfig>::builder()
+        .with_file(&default_path)
+        .with_env_file(env_template)
+        .with_environment("staging")
+        .build()
+        .await
+        .unwrap();
+    assert_eq!(staging.get().server.port, 8080);
+
+    let unselected: HotswapConfig<AppConfig> = HotswapConfig::<AppConfig>::builder()
+        .with_file(&default_path)
+        .with_env_selector("HOTSWAP_TEST_UNSET_ENV")
+        .with_env_file(env_template)
+        .build()
+        .await
+        .unwrap();
+    assert_eq!(unselected.get().server.port, 8080);
+
+    // Required files still fail the build
+    let result = HotswapConfig::<AppConfig>::builder()
+        .with_file(&default_path)
+        .with_file_required(temp_dir.path().join("local.yaml"))
+        .build()
+        .await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_profiles() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("default.yaml"),
+        r#"
+server:
+  port: 8080
+  host: localhost
+database:
+  url: postgres://localhost/db
+  max_connections: 10
+"#,
+    )
+    .unwrap();
+    fs::write(
+        temp_dir.path().join("production.toml"),
+        "[database]\nmax_connections = 100\n",
+    )
+    .unwrap();
+
+    // Profile variables override general ones, and aren't loaded as keys themselves
+    unsafe {
+        std::env::set_var("HOTSWAP_PROFILE_TEST__SERVER__HOST", "general");
+        std::env::set_var("HOTSWAP_PROFILE_TEST__SERVER__PORT", "8081");
+        std::env::set_var("HOTSWAP_PROFILE_TEST_PRODUCTION__SERVER__PORT", "9090");
+    }
+
+    let builder = HotswapConfig::builder()
+        .with_profile_dir(temp_dir.path())
+        .with_profile("production")
+        .with_env_overrides("HOTSWAP_PROFILE_TEST", "__");
+    assert_eq!(builder.active_profile().as_deref(), Some("production"));
+    let config: HotswapConfig<AppConfig> = builder.build().await.unwrap();
+
+    unsafe {
+        std::env::remove_var("HOTSWAP_PROFILE_TEST__SERVER__HOST");
+        std::env::remove_var("HOTSWAP_PROFILE_TEST__SERVER__PORT");
+        std::env::remove_var("HOTSWAP_PROFILE_TEST_PRODUCTION__SERVER__PORT");
+    }
+
+    assert_eq!(config.profile(), Some("production"));
+    let cfg = config.get();
+    assert_eq!(cfg.database.max_connections, 100);
+    assert_eq!(cfg.server.host, "general");
+    assert_eq!(cfg.server.port, 9090);
+
+    // A selected profile without a file fails; no profile loads only the default
+    let missing = HotswapConfig::<AppConfig>::builder()
+        .with_profile_dir(temp_dir.path())
+        .with_profile("staging")
+        .build()
+        .await;
+    assert!(missing.is_err());
+
+    let default_only: HotswapConfig<AppConfig> = HotswapConfig::builder()
+        .with_profile_dir(temp_dir.path())
+        .with_env_selector("HOTSWAP_PROFILE_TEST_UNSET")
+        .with_profiles()
+        .build()
+        .await
+        .unwrap();
+    assert_eq!(default_only.profile(), None);
+    assert_eq!(default_only.get().database.max_connections, 10);
+}
+
 #[tokio::test]
 #[ignore] // Skipped: env var testing requires special setup with cargo test
 async fn test_env_overrides() {
@@ -128,10 +413,10 @@ database:
         env::set_var("TEST_PHASE1_DATABASE__MAX_CONNECTIONS", "50");
     }
 
-    let config: HotswapConfig<AppConfig> = HotswapConfig::builder()
+    let config: HotswapConfig<AppConfig> = HotswapConfig::<AppConfig>::builder()
         .with_file(&config_path)
         .with_env_overrides("TEST_PHASE1", "__")
-        .build::<AppConfig>()
+        .build()
         .await
         .unwrap();
 
@@ -147,6 +432,58 @@ database:
     }
 }
 
+#[tokio::test]
+async fn test_env_key_case() {
+    use hotswap_config::sources::{EnvSource, KeyCase};
+    use std::env;
+
+    #[derive(Debug, Deserialize, Clone)]
+    #[serde(rename_all = "camelCase")]
+    struct PoolConfig {
+        max_connections: u32,
+        idle_timeout: u64,
+    }
+
+    #[derive(Debug, Deserialize, Clone)]
+    #[serde(rename_all = "camelCase")]
+    struct CamelConfig {
+        connection_pool: PoolConfig,
+    }
+
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.yaml");
+    fs::write(
+        &config_path,
+        "connectionPool:\n  maxConnections: 10\n  idleTimeout: 30\n",
+    )
+    .unwrap();
+
+    unsafe {
+        env::set_var("TEST_CASE__CONNECTION_POOL__MAX_CONNECTIONS", "50");
+        env::set_var("TEST_CASE_IDLE", "60");
+    }
+
+    let config = HotswapConfig::<CamelConfig>::builder()
+        .with_file(&config_path)
+        .with_source(
+            EnvSource::new("TEST_CASE", "__")
+                .with_key_case(KeyCase::Camel)
+                .with_alias("TEST_CASE_IDLE", "connectionPool.idleTimeout"),
+        )
+        .build()
+        .await
+        .unwrap();
+
+    let cfg = config.get();
+    assert_eq!(cfg.connection_pool.max_connections, 50);
+    assert_eq!(cfg.connection_pool.idle_timeout, 60);
+
+    unsafe {
+        env::remove_var("TEST_CASE__CONNECTION_POOL__MAX_CONNECTIONS");
+        env::remove_var("TEST_CASE_IDLE");
+    }
+}
+
 #[tokio::test]
 async fn test_validation_success() {
     let temp_dir = TempDir::new().unwrap();
@@ -165,7 +502,7 @@ database:
     )
     .unwrap();
 
-    let result = HotswapConfig::builder()
+    let result = HotswapConfig::<AppConfig>::builder()
         .with_file(&config_path)
         .with_validation(|config: &AppConfig| {
             if config.server.port < 1024 {
@@ -176,7 +513,7 @@ database:
             }
             Ok(())
         })
-        .build::<AppConfig>()
+        .build()
         .await;
 
     assert!(result.is_ok());
@@ -200,7 +537,7 @@ database:
     )
     .unwrap();
 
-    let result = HotswapConfig::builder()
+    let result = HotswapConfig::<AppConfig>::builder()
         .with_file(&config_path)
         .with_validation(|config: &AppConfig| {
             if config.server.port < 1024 {
@@ -211,7 +548,7 @@ database:
             }
             Ok(())
         })
-        .build::<AppConfig>()
+        .build()
         .await;
 
     assert!(result.is_err());
@@ -220,6 +557,49 @@ database:
     }
 }
 
+#[tokio::test]
+async fn test_unknown_keys_denied() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.yaml");
+
+    fs::write(
+        &config_path,
+        r#"
+server:
+  port: 8080
+  host: localhost
+  timeout_secs: 30
+database:
+  url: postgres://localhost/db
+  max_conections: 10
+  max_connections: 10
+"#,
+    )
+    .unwrap();
+
+    let result = HotswapConfig::<AppConfig>::builder()
+        .with_file(&config_path)
+        .with_unknown_keys(UnknownKeys::Deny)
+        .build()
+        .await;
+
+    match result {
+        Err(ConfigError::UnknownKeys(keys)) => {
+            assert_eq!(keys, ["database.max_conections", "server.timeout_secs"]);
+        }
+        other => panic!("expected unknown keys, got {:?}", other.map(|_| ())),
+    }
+
+    // Warnings don't fail the build
+    let config = HotswapConfig::<AppConfig>::builder()
+        .with_file(&config_path)
+        .with_unknown_keys(UnknownKeys::Warn)
+        .build()
+        .await
+        .unwrap();
+    assert_eq!(config.get().database.max_connections, 10);
+}
+
 #[tokio::test]
 async fn test_reload() {
     let temp_dir = TempDir::new().unwrap();
@@ -239,9 +619,9 @@ database:
     )
     .unwrap();
 
-    let config: HotswapConfig<AppConfig> = HotswapConfig::builder()
+    let config: HotswapConfig<AppConfig> = HotswapConfig::<AppConfig>::builder()
         .with_file(&config_path)
-        .build::<AppConfig>()
+        .build()
         .await
         .unwrap();
 
@@ -287,9 +667,9 @@ database:
     )
     .unwrap();
 
-    let config: HotswapConfig<AppConfig> = HotswapConfig::builder()
+    let config: HotswapConfig<AppConfig> = HotswapConfig::<AppConfig>::builder()
         .with_file(&config_path)
-        .build::<AppConfig>()
+        .build()
         .await
         .unwrap();
 
@@ -328,9 +708,9 @@ database:
     )
     .unwrap();
 
-    let config: HotswapConfig<AppConfig> = HotswapConfig::builder()
+    let config: HotswapConfig<AppConfig> = HotswapConfig::<AppConfig>::builder()
         .with_file(&config_path)
-        .build::<AppConfig>()
+        .build()
         .await
         .unwrap();
 
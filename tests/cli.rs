@@ -0,0 +1,123 @@
+//! Integration tests for the `hotswap-config` command-line tool.
+
+#![cfg(feature = "cli")]
+
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+fn run(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_hotswap-config"))
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn setup() -> TempDir {
+    let dir = TempDir::new().unwrap();
+    fs::write(
+        dir.path().join("default.yaml"),
+        "server:\n  port: 8080\n  host: localhost\n",
+    )
+    .unwrap();
+    fs::write(dir.path().join("prod.yaml"), "server:\n  port: 9090\n").unwrap();
+    dir
+}
+
+#[test]
+fn test_render_merges_files() {
+    let dir = setup();
+    let output = run(
+        dir.path(),
+        &["render", "--format", "json", "default.yaml", "prod.yaml"],
+    );
+    assert!(output.status.success());
+
+    let rendered: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(rendered["server"]["port"], 9090);
+    assert_eq!(rendered["server"]["host"], "localhost");
+}
+
+#[test]
+fn test_diff_exit_codes() {
+    let dir = setup();
+    let same = run(
+        dir.path(),
+        &["diff", "--from", "default.yaml", "--to", "default.yaml"],
+    );
+    assert_eq!(same.status.code(), Some(0));
+    assert!(stdout(&same).is_empty());
+
+    let changed = run(
+        dir.path(),
+        &[
+            "diff",
+            "--from",
+            "default.yaml",
+            "--to",
+            "default.yaml",
+            "prod.yaml",
+        ],
+    );
+    assert_eq!(changed.status.code(), Some(1));
+    assert_eq!(stdout(&changed), "~ server.port: 8080 -> 9090\n");
+}
+
+#[test]
+fn test_validate_against_schema() {
+    let dir = setup();
+    fs::write(
+        dir.path().join("schema.json"),
+        r#"{ "properties": { "server": { "properties": { "port": { "maximum": 9000 } } } } }"#,
+    )
+    .unwrap();
+
+    let valid = run(
+        dir.path(),
+        &["validate", "--schema", "schema.json", "default.yaml"],
+    );
+    assert!(valid.status.success());
+
+    let invalid = run(
+        dir.path(),
+        &[
+            "validate",
+            "--schema",
+            "schema.json",
+            "default.yaml",
+            "prod.yaml",
+        ],
+    );
+    assert_eq!(invalid.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&invalid.stderr).contains("server.port"));
+
+    let missing = run(dir.path(), &["validate", "missing.yaml"]);
+    assert_eq!(missing.status.code(), Some(2));
+}
+
+#[test]
+fn test_validate_reports_every_error() {
+    let dir = setup();
+    fs::write(dir.path().join("broken.yaml"), "server: [\n").unwrap();
+    fs::write(dir.path().join("also-broken.json"), "{").unwrap();
+
+    let output = run(
+        dir.path(),
+        &[
+            "validate",
+            "default.yaml",
+            "broken.yaml",
+            "also-broken.json",
+        ],
+    );
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("broken.yaml"), "{}", stderr);
+    assert!(stderr.contains("also-broken.json"), "{}", stderr);
+}
@@ -53,9 +53,9 @@ features:
     )
     .unwrap();
 
-    let config = HotswapConfig::builder()
+    let config = HotswapConfig::<IntegrationConfig>::builder()
         .with_file(&config_path)
-        .build::<IntegrationConfig>()
+        .build()
         .await
         .unwrap();
 
@@ -90,7 +90,7 @@ features:
     )
     .unwrap();
 
-    let config = HotswapConfig::builder()
+    let config = HotswapConfig::<IntegrationConfig>::builder()
         .with_file(&config_path)
         .with_validation(|cfg: &IntegrationConfig| {
             if cfg.server.port < 1024 {
@@ -102,7 +102,7 @@ features:
             Ok(())
         })
         .with_file_watch(true)
-        .build::<IntegrationConfig>()
+        .build()
         .await
         .unwrap();
 
@@ -141,6 +141,104 @@ features:
     assert!(cfg.features.enable_metrics);
 }
 
+#[cfg(feature = "file-watch")]
+#[tokio::test]
+async fn test_optional_file_is_watched() {
+    let temp_dir = TempDir::new().unwrap();
+    let base_path = temp_dir.path().join("default.yaml");
+    let local_path = temp_dir.path().join("local.yaml");
+    fs::write(
+        &base_path,
+        r#"
+server:
+  port: 8080
+  host: "localhost"
+
+database:
+  url: "postgresql://localhost/mydb"
+  max_connections: 10
+
+features:
+  enable_metrics: false
+  enable_caching: true
+"#,
+    )
+    .unwrap();
+
+    let config = HotswapConfig::<IntegrationConfig>::builder()
+        .with_file(&base_path)
+        .with_file_optional(&local_path)
+        .with_file_watch(true)
+        .with_watch_debounce(std::time::Duration::from_millis(50))
+        .build()
+        .await
+        .unwrap();
+    assert_eq!(config.get().server.port, 8080);
+
+    // Creating the override is picked up, and deleting it reverts
+    fs::write(&local_path, "server:\n  port: 9090\n").unwrap();
+    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+    assert_eq!(config.get().server.port, 9090);
+
+    fs::remove_file(&local_path).unwrap();
+    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+    assert_eq!(config.get().server.port, 8080);
+}
+
+#[tokio::test]
+async fn test_conf_d_fragments_are_watched() {
+    let temp_dir = TempDir::new().unwrap();
+    let base_path = temp_dir.path().join("default.yaml");
+    let conf_d = temp_dir.path().join("conf.d");
+    fs::create_dir(&conf_d).unwrap();
+
+    fs::write(
+        &base_path,
+        r#"
+server:
+  port: 8080
+  host: "localhost"
+
+database:
+  url: "postgresql://localhost/mydb"
+  max_connections: 10
+
+features:
+  enable_metrics: false
+  enable_caching: true
+"#,
+    )
+    .unwrap();
+    fs::write(conf_d.join("10-server.yaml"), "server:\n  port: 9090\n").unwrap();
+
+    let config = HotswapConfig::<IntegrationConfig>::builder()
+        .with_file(&base_path)
+        .with_glob(conf_d.join("*.yaml"))
+        .with_file_watch(true)
+        .with_watch_debounce(std::time::Duration::from_millis(50))
+        .build()
+        .await
+        .unwrap();
+    assert_eq!(config.get().server.port, 9090);
+    assert_eq!(config.get().server.host, "localhost");
+
+    // Drop in a new fragment that sorts after the existing one
+    fs::write(
+        conf_d.join("20-features.yaml"),
+        "server:\n  port: 9191\nfeatures:\n  enable_metrics: true\n",
+    )
+    .unwrap();
+    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+    assert_eq!(config.get().server.port, 9191);
+    assert!(config.get().features.enable_metrics);
+
+    // Removing it falls back to the remaining fragments
+    fs::remove_file(conf_d.join("20-features.yaml")).unwrap();
+    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+    assert_eq!(config.get().server.port, 9090);
+    assert!(!config.get().features.enable_metrics);
+}
+
 #[tokio::test]
 async fn test_subscribers_notification() {
     let config = HotswapConfig::new(IntegrationConfig {
@@ -191,17 +289,8 @@ async fn test_subscribers_notification() {
     assert_eq!(counter.load(std::sync::atomic::Ordering::SeqCst), 1);
 }
 
-// Note: Advanced features (partial updates, rollback, gradual rollout) are tested
-// in their respective module test files. These integration tests focus on
-// basic features working together.
-
-#[cfg(feature = "metrics")]
 #[tokio::test]
-async fn test_metrics_integration() {
-    use opentelemetry::global;
-
-    let meter = global::meter("test");
-
+async fn test_reload_error_handler_and_error_subscribers() {
     let temp_dir = TempDir::new().unwrap();
     let config_path = temp_dir.path().join("config.yaml");
 
@@ -223,53 +312,54 @@ features:
     )
     .unwrap();
 
-    let config = HotswapConfig::builder()
+    let handler_calls = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let handler_calls_clone = handler_calls.clone();
+
+    let config = HotswapConfig::<IntegrationConfig>::builder()
         .with_file(&config_path)
-        .with_metrics(meter)
-        .build::<IntegrationConfig>()
+        .with_file_watch(true)
+        .with_watch_debounce(tokio::time::Duration::from_millis(50))
+        .with_reload_error_handler(move |_err| {
+            handler_calls_clone.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        })
+        .build()
         .await
         .unwrap();
 
-    // Perform operations that generate metrics
-    config.reload().await.unwrap();
+    let subscriber_calls = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let subscriber_calls_clone = subscriber_calls.clone();
+    let _handle = config
+        .subscribe_errors(move |_err| {
+            subscriber_calls_clone.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        })
+        .await;
 
-    let new_config = IntegrationConfig {
-        server: ServerConfig {
-            port: 9090,
-            host: "0.0.0.0".to_string(),
-        },
-        database: DatabaseConfig {
-            url: "postgresql://localhost/mydb".to_string(),
-            max_connections: 20,
-        },
-        features: Features {
-            enable_metrics: true,
-            enable_caching: true,
-        },
-    };
+    // Write a config that no longer deserializes
+    fs::write(&config_path, "server:\n  port: not-a-number\n").unwrap();
+    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
 
-    config.update(new_config).await.unwrap();
+    assert!(handler_calls.load(std::sync::atomic::Ordering::SeqCst) >= 1);
+    assert!(subscriber_calls.load(std::sync::atomic::Ordering::SeqCst) >= 1);
 
-    // Metrics should be recorded (we can't easily verify values without introspection)
-    // but this test ensures metrics don't cause crashes
+    // Previous config is retained
+    assert_eq!(config.get().server.port, 8080);
 }
 
+// Note: Advanced features (partial updates, rollback, gradual rollout) are tested
+// in their respective module test files. These integration tests focus on
+// basic features working together.
+
+#[cfg(feature = "metrics")]
 #[tokio::test]
-async fn test_validation_failure_preserves_old_config() {
-    let _config = HotswapConfig::new(IntegrationConfig {
-        server: ServerConfig {
-            port: 8080,
-            host: "localhost".to_string(),
-        },
-        database: DatabaseConfig {
-            url: "postgresql://localhost/mydb".to_string(),
-            max_connections: 10,
-        },
-        features: Features {
-            enable_metrics: false,
-            enable_caching: true,
-        },
-    });
+async fn test_metrics_integration() {
+    use opentelemetry::metrics::MeterProvider;
+    use opentelemetry_sdk::metrics::data::{AggregatedMetrics, MetricData};
+    use opentelemetry_sdk::metrics::{InMemoryMetricExporter, PeriodicReader, SdkMeterProvider};
+
+    let exporter = InMemoryMetricExporter::default();
+    let provider = SdkMeterProvider::builder()
+        .with_reader(PeriodicReader::builder(exporter.clone()).build())
+        .build();
 
     let temp_dir = TempDir::new().unwrap();
     let config_path = temp_dir.path().join("config.yaml");
@@ -292,100 +382,1483 @@ features:
     )
     .unwrap();
 
-    let config_with_validation = HotswapConfig::builder()
+    let config = HotswapConfig::<IntegrationConfig>::builder()
         .with_file(&config_path)
         .with_validation(|cfg: &IntegrationConfig| {
-            if cfg.server.port < 1024 {
-                return Err(hotswap_config::error::ValidationError::invalid_field(
-                    "port",
-                    "must be >= 1024",
-                ));
+            if cfg.server.port == 0 {
+                return Err(ValidationError::invalid_field("server.port", "must be > 0"));
             }
             Ok(())
         })
-        .build::<IntegrationConfig>()
+        .with_metrics(provider.meter("test"))
+        .build()
         .await
         .unwrap();
 
-    // Try to update with invalid config
-    let invalid_config = IntegrationConfig {
-        server: ServerConfig {
-            port: 80, // Invalid!
-            host: "localhost".to_string(),
-        },
-        database: DatabaseConfig {
-            url: "postgresql://localhost/mydb".to_string(),
-            max_connections: 10,
-        },
-        features: Features {
-            enable_metrics: false,
-            enable_caching: true,
-        },
+    // Perform operations that generate metrics; the second reload sees
+    // unchanged sources and is skipped
+    let contents = fs::read_to_string(&config_path).unwrap();
+    fs::write(&config_path, contents.replace("8080", "8081")).unwrap();
+    config.reload().await.unwrap();
+    config.reload().await.unwrap();
+
+    let mut new_config = (*config.get()).clone();
+    new_config.server.port = 9090;
+    config.update(new_config.clone()).await.unwrap();
+
+    new_config.server.port = 0;
+    assert!(config.update(new_config).await.is_err());
+
+    let first = config.subscribe(|| {}).await;
+    let second = config.subscribe(|| {}).await;
+    drop(first);
+    // Unsubscribing happens on a spawned task
+    tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+
+    provider.force_flush().unwrap();
+    let exported = exporter.get_finished_metrics().unwrap();
+    let metric = |name: &str| {
+        exported
+            .iter()
+            .flat_map(|resource| resource.scope_metrics())
+            .flat_map(|scope| scope.metrics())
+            .find(|metric| metric.name() == name)
+            .unwrap_or_else(|| panic!("metric {} was not recorded", name))
+            .data()
+    };
+    let counter = |name: &str| match metric(name) {
+        AggregatedMetrics::U64(MetricData::Sum(sum)) => {
+            sum.data_points().map(|point| point.value()).sum::<u64>()
+        }
+        other => panic!("unexpected data for {}: {:?}", name, other),
+    };
+    let gauge = |name: &str| match metric(name) {
+        AggregatedMetrics::I64(MetricData::Gauge(gauge)) => {
+            gauge.data_points().next().unwrap().value()
+        }
+        other => panic!("unexpected data for {}: {:?}", name, other),
     };
 
-    let result = config_with_validation.update(invalid_config).await;
-    assert!(result.is_err());
+    assert_eq!(counter("hotswap_config.reload.attempts"), 2);
+    assert_eq!(counter("hotswap_config.reload.success"), 1);
+    assert_eq!(counter("hotswap_config.reload.skipped_unchanged"), 1);
+    assert_eq!(counter("hotswap_config.validation.failures"), 1);
+    assert_eq!(gauge("hotswap_config.subscribers.active"), 1);
+    assert_eq!(gauge("hotswap_config.age"), 0);
+    assert!(matches!(
+        metric("hotswap_config.reload.duration"),
+        AggregatedMetrics::F64(MetricData::Histogram(_))
+    ));
 
-    // Original config should be preserved
-    let cfg = config_with_validation.get();
-    assert_eq!(cfg.server.port, 8080); // Still the old value
+    drop(second);
 }
 
 #[tokio::test]
-async fn test_concurrent_reads_during_updates() {
-    let config = std::sync::Arc::new(HotswapConfig::new(IntegrationConfig {
-        server: ServerConfig {
-            port: 8080,
-            host: "localhost".to_string(),
-        },
-        database: DatabaseConfig {
-            url: "postgresql://localhost/mydb".to_string(),
-            max_connections: 10,
-        },
-        features: Features {
-            enable_metrics: false,
-            enable_caching: true,
-        },
-    }));
+async fn test_unchanged_reload_is_skipped() {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
 
-    let mut handles = vec![];
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.yaml");
+    let yaml = r#"
+server:
+  port: 8080
+  host: "localhost"
+database:
+  url: "postgresql://localhost/mydb"
+  max_connections: 10
+features:
+  enable_metrics: false
+  enable_caching: true
+"#;
+    fs::write(&config_path, yaml).unwrap();
 
-    // Spawn 10 reader tasks
-    for _ in 0..10 {
-        let cfg = config.clone();
-        let handle = tokio::spawn(async move {
-            for _ in 0..100 {
-                let data = cfg.get();
-                assert!(data.server.port >= 8080 && data.server.port <= 8090);
-                tokio::time::sleep(tokio::time::Duration::from_micros(10)).await;
-            }
-        });
-        handles.push(handle);
-    }
+    let config = HotswapConfig::<IntegrationConfig>::builder()
+        .with_file(&config_path)
+        .build()
+        .await
+        .unwrap();
 
-    // Perform updates while readers are running
-    for i in 0..10 {
-        let new_config = IntegrationConfig {
-            server: ServerConfig {
-                port: 8080 + i,
-                host: "localhost".to_string(),
-            },
-            database: DatabaseConfig {
-                url: "postgresql://localhost/mydb".to_string(),
-                max_connections: 10,
-            },
-            features: Features {
-                enable_metrics: false,
-                enable_caching: true,
+    let notifications = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&notifications);
+    let _handle = config
+        .subscribe(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        })
+        .await;
+
+    // Rewriting identical content (e.g. a touch) doesn't swap or notify
+    let before = config.get();
+    fs::write(&config_path, yaml).unwrap();
+    config.reload().await.unwrap();
+    assert!(Arc::ptr_eq(&before, &config.get()));
+    assert_eq!(notifications.load(Ordering::SeqCst), 0);
+
+    // A real change is applied
+    fs::write(&config_path, yaml.replace("8080", "9090")).unwrap();
+    config.reload().await.unwrap();
+    assert_eq!(config.get().server.port, 9090);
+    assert_eq!(notifications.load(Ordering::SeqCst), 1);
+
+    // After a manual update, reloading the unchanged file restores it
+    let mut manual = (*config.get()).clone();
+    manual.server.port = 7070;
+    config.update(manual).await.unwrap();
+    config.reload().await.unwrap();
+    assert_eq!(config.get().server.port, 9090);
+}
+
+#[tokio::test]
+async fn test_async_validation_distinguishes_invalid_and_failed() {
+    use hotswap_config::error::VerificationError;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.yaml");
+    let yaml = r#"
+server:
+  port: 8080
+  host: "db.internal"
+
+database:
+  url: "postgresql://localhost/mydb"
+  max_connections: 10
+
+features:
+  enable_metrics: false
+  enable_caching: true
+"#;
+    fs::write(&config_path, yaml).unwrap();
+
+    // Stand-in for a connectivity check: "unreachable" hosts fail the check,
+    // and "slow" hosts never answer
+    let config = HotswapConfig::<IntegrationConfig>::builder()
+        .with_file(&config_path)
+        .with_async_validation(
+            |cfg: Arc<IntegrationConfig>| async move {
+                match cfg.server.host.as_str() {
+                    "" => Err(hotswap_config::error::ValidationError::invalid_field(
+                        "server.host",
+                        "must not be empty",
+                    )
+                    .into()),
+                    "unreachable" => Err(VerificationError::failed("connection refused")),
+                    "slow" => {
+                        tokio::time::sleep(Duration::from_secs(30)).await;
+                        Ok(())
+                    }
+                    _ => Ok(()),
+                }
             },
-        };
+            Duration::from_millis(100),
+        )
+        .build()
+        .await
+        .unwrap();
 
-        config.update(new_config).await.unwrap();
-        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
-    }
+    fs::write(&config_path, yaml.replace("db.internal", "")).unwrap();
+    assert!(matches!(
+        config.reload().await,
+        Err(ConfigError::ValidationError(_))
+    ));
 
-    // Wait for all readers
-    for handle in handles {
-        handle.await.unwrap();
+    fs::write(&config_path, yaml.replace("db.internal", "unreachable")).unwrap();
+    assert!(matches!(
+        config.reload().await,
+        Err(ConfigError::VerificationFailed(_))
+    ));
+
+    fs::write(&config_path, yaml.replace("db.internal", "slow")).unwrap();
+    match config.reload().await {
+        Err(ConfigError::VerificationFailed(reason)) => assert!(reason.contains("timed out")),
+        other => panic!("expected timeout, got {:?}", other),
     }
+    assert_eq!(config.get().server.host, "db.internal");
+
+    // Updates are verified too
+    let mut updated = (*config.get()).clone();
+    updated.server.host = "unreachable".to_string();
+    assert!(config.update(updated).await.is_err());
+
+    fs::write(&config_path, yaml.replace("db.internal", "db2.internal")).unwrap();
+    config.reload().await.unwrap();
+    assert_eq!(config.get().server.host, "db2.internal");
+}
+
+#[tokio::test]
+async fn test_transactional_subscriber_rejection_reverts() {
+    use hotswap_config::error::ApplyError;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.yaml");
+    let yaml = r#"
+server:
+  port: 8080
+  host: "localhost"
+
+database:
+  url: "postgresql://localhost/mydb"
+  max_connections: 10
+
+features:
+  enable_metrics: false
+  enable_caching: true
+"#;
+    fs::write(&config_path, yaml).unwrap();
+
+    let config = HotswapConfig::<IntegrationConfig>::builder()
+        .with_file(&config_path)
+        .build()
+        .await
+        .unwrap();
+
+    // The pool applies every config; the listener can't bind port 1
+    let pool_sizes = Arc::new(Mutex::new(Vec::new()));
+    let sizes = Arc::clone(&pool_sizes);
+    let _pool = config.subscribe_transactional("pool", move |cfg: &IntegrationConfig| {
+        sizes.lock().unwrap().push(cfg.database.max_connections);
+        Ok(())
+    });
+    let _listener = config.subscribe_transactional("listener", |cfg: &IntegrationConfig| {
+        if cfg.server.port == 1 {
+            return Err(ApplyError::new("permission denied"));
+        }
+        Ok(())
+    });
+
+    let notifications = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&notifications);
+    let _handle = config
+        .subscribe(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        })
+        .await;
+    let errors = Arc::new(Mutex::new(Vec::new()));
+    let seen = Arc::clone(&errors);
+    let _error_handle = config
+        .subscribe_errors(move |e| seen.lock().unwrap().push(e.to_string()))
+        .await;
+
+    fs::write(
+        &config_path,
+        yaml.replace("8080", "1")
+            .replace("max_connections: 10", "max_connections: 20"),
+    )
+    .unwrap();
+    match config.reload().await {
+        Err(ConfigError::ApplyFailed { subscriber, reason }) => {
+            assert_eq!(subscriber, "listener");
+            assert_eq!(reason, "permission denied");
+        }
+        other => panic!("expected apply failure, got {:?}", other),
+    }
+
+    // Nothing was swapped, and the pool was handed the previous config back
+    assert_eq!(config.get().server.port, 8080);
+    assert_eq!(*pool_sizes.lock().unwrap(), vec![20, 10]);
+    assert_eq!(notifications.load(Ordering::SeqCst), 0);
+    assert!(errors.lock().unwrap()[0].contains("listener"));
+
+    fs::write(&config_path, yaml.replace("8080", "9090")).unwrap();
+    config.reload().await.unwrap();
+    assert_eq!(config.get().server.port, 9090);
+    assert_eq!(notifications.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_reload_dry_run_reports_changes_without_swapping() {
+    use hotswap_config::core::ChangeKind;
+
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.yaml");
+    let yaml = r#"
+server:
+  port: 8080
+  host: "localhost"
+
+database:
+  url: "postgresql://localhost/mydb"
+  max_connections: 10
+
+features:
+  enable_metrics: false
+  enable_caching: true
+"#;
+    fs::write(&config_path, yaml).unwrap();
+
+    let config = HotswapConfig::<IntegrationConfig>::builder()
+        .with_file(&config_path)
+        .with_validation(|cfg: &IntegrationConfig| {
+            if cfg.database.max_connections == 0 {
+                return Err(ValidationError::invalid_field(
+                    "database.max_connections",
+                    "must be positive",
+                ));
+            }
+            Ok(())
+        })
+        .build()
+        .await
+        .unwrap();
+
+    assert!(config.reload_dry_run().await.unwrap().is_empty());
+
+    fs::write(
+        &config_path,
+        yaml.replace("8080", "9090")
+            .replace("enable_metrics: false", "enable_metrics: true"),
+    )
+    .unwrap();
+    let diff = config.reload_dry_run().await.unwrap();
+    let changed: Vec<(&str, ChangeKind)> = diff
+        .changes()
+        .iter()
+        .map(|c| (c.path.as_str(), c.kind))
+        .collect();
+    assert_eq!(
+        changed,
+        vec![
+            ("features.enable_metrics", ChangeKind::Modified),
+            ("server.port", ChangeKind::Modified),
+        ]
+    );
+    assert_eq!(diff.proposed().server.port, 9090);
+    assert_eq!(config.get().server.port, 8080);
+
+    // A config that would fail to reload fails the dry run the same way
+    fs::write(
+        &config_path,
+        yaml.replace("max_connections: 10", "max_connections: 0"),
+    )
+    .unwrap();
+    assert!(matches!(
+        config.reload_dry_run().await,
+        Err(ConfigError::ValidationError(_))
+    ));
+    assert_eq!(config.get().server.port, 8080);
+}
+
+#[tokio::test]
+async fn test_freeze_rejects_reloads_until_unfrozen() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.yaml");
+    let yaml = r#"
+server:
+  port: 8080
+  host: "localhost"
+
+database:
+  url: "postgresql://localhost/mydb"
+  max_connections: 10
+
+features:
+  enable_metrics: false
+  enable_caching: true
+"#;
+    fs::write(&config_path, yaml).unwrap();
+
+    let config = HotswapConfig::<IntegrationConfig>::builder()
+        .with_file(&config_path)
+        .build()
+        .await
+        .unwrap();
+
+    config.freeze(Some("INC-42"));
+    fs::write(&config_path, yaml.replace("8080", "9090")).unwrap();
+    match config.reload().await {
+        Err(ConfigError::Frozen { reason }) => assert_eq!(reason.as_deref(), Some("INC-42")),
+        other => panic!("expected frozen error, got {:?}", other),
+    }
+    assert_eq!(config.get().server.port, 8080);
+
+    // Unfreezing catches up on the reload that was rejected
+    config.unfreeze().await.unwrap();
+    assert!(!config.is_frozen());
+    assert_eq!(config.get().server.port, 9090);
+}
+
+#[tokio::test]
+async fn test_secrets_overlay_overrides_and_refreshes() {
+    use hotswap_config::sources::FileSource;
+
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.yaml");
+    let secrets_path = temp_dir.path().join("secrets.yaml");
+    fs::write(
+        &config_path,
+        r#"
+server:
+  port: 8080
+  host: "localhost"
+
+database:
+  url: "postgresql://localhost/mydb"
+  max_connections: 10
+
+features:
+  enable_metrics: false
+  enable_caching: true
+"#,
+    )
+    .unwrap();
+    fs::write(
+        &secrets_path,
+        "database:\n  url: postgresql://user:one@db/mydb\n",
+    )
+    .unwrap();
+
+    // The overlay wins even over a higher-priority file
+    let config = HotswapConfig::<IntegrationConfig>::builder()
+        .with_overlay(FileSource::new(&secrets_path).with_priority(0))
+        .with_file(&config_path)
+        .build()
+        .await
+        .unwrap();
+    assert_eq!(config.get().database.url, "postgresql://user:one@db/mydb");
+    assert_eq!(config.get().database.max_connections, 10);
+
+    fs::write(
+        &secrets_path,
+        "database:\n  url: postgresql://user:two@db/mydb\n",
+    )
+    .unwrap();
+    config.refresh_overlays().await.unwrap();
+    assert_eq!(config.get().database.url, "postgresql://user:two@db/mydb");
+    assert_eq!(config.get().server.port, 8080);
+}
+
+#[tokio::test]
+async fn test_optional_source_failures_are_reported() {
+    use hotswap_config::sources::{ConfigSource, FailurePolicy};
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    struct FlakyRemote {
+        up: Arc<AtomicBool>,
+    }
+
+    impl ConfigSource for FlakyRemote {
+        fn load(&self) -> Result<HashMap<String, config::Value>> {
+            if !self.up.load(Ordering::SeqCst) {
+                return Err(ConfigError::LoadError("connection refused".to_string()));
+            }
+            let mut server = config::Map::new();
+            server.insert("port".to_string(), config::Value::from(9090i64));
+            Ok(HashMap::from([("server".to_string(), server.into())]))
+        }
+
+        fn name(&self) -> String {
+            "remote:flaky".to_string()
+        }
+
+        fn priority(&self) -> i32 {
+            250
+        }
+
+        fn failure_policy(&self) -> FailurePolicy {
+            FailurePolicy::Optional
+        }
+    }
+
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.yaml");
+    fs::write(
+        &config_path,
+        r#"
+server:
+  port: 8080
+  host: "localhost"
+
+database:
+  url: "postgresql://localhost/mydb"
+  max_connections: 10
+
+features:
+  enable_metrics: false
+  enable_caching: true
+"#,
+    )
+    .unwrap();
+
+    // The config builds without the remote source, which is reported
+    let up = Arc::new(AtomicBool::new(false));
+    let config = HotswapConfig::<IntegrationConfig>::builder()
+        .with_file(&config_path)
+        .with_source(FlakyRemote {
+            up: Arc::clone(&up),
+        })
+        .build()
+        .await
+        .unwrap();
+    assert_eq!(config.get().server.port, 8080);
+    let skipped = config.skipped_sources();
+    assert_eq!(skipped.len(), 1);
+    assert_eq!(skipped[0].name, "remote:flaky");
+
+    // Once it recovers, a reload picks it up
+    up.store(true, Ordering::SeqCst);
+    config.reload().await.unwrap();
+    assert_eq!(config.get().server.port, 9090);
+    assert!(config.skipped_sources().is_empty());
+}
+
+#[tokio::test]
+async fn test_watching_source_reloads_on_change() {
+    use hotswap_config::sources::{ChangeNotifier, ChangeStream, ConfigSource};
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicI64, AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    /// A source that pushes change notifications, like a key-value store watch.
+    struct PushSource {
+        port: Arc<AtomicI64>,
+        notifier: Arc<Mutex<Option<ChangeNotifier>>>,
+    }
+
+    impl ConfigSource for PushSource {
+        fn load(&self) -> Result<HashMap<String, config::Value>> {
+            let mut server = config::Map::new();
+            server.insert(
+                "port".to_string(),
+                config::Value::from(self.port.load(Ordering::SeqCst)),
+            );
+            Ok(HashMap::from([("server".to_string(), server.into())]))
+        }
+
+        fn name(&self) -> String {
+            "push:test".to_string()
+        }
+
+        fn priority(&self) -> i32 {
+            250
+        }
+
+        fn watch(&self) -> Option<ChangeStream> {
+            let (notifier, changes) = ChangeStream::channel();
+            *self.notifier.lock().unwrap() = Some(notifier);
+            Some(changes)
+        }
+    }
+
+    /// A source that counts its loads.
+    struct Counted {
+        loads: Arc<AtomicUsize>,
+    }
+
+    impl ConfigSource for Counted {
+        fn load(&self) -> Result<HashMap<String, config::Value>> {
+            self.loads.fetch_add(1, Ordering::SeqCst);
+            let mut features = config::Map::new();
+            features.insert("enable_metrics".to_string(), config::Value::from(true));
+            Ok(HashMap::from([("features".to_string(), features.into())]))
+        }
+
+        fn name(&self) -> String {
+            "counted".to_string()
+        }
+    }
+
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.yaml");
+    fs::write(
+        &config_path,
+        r#"
+server:
+  port: 8080
+  host: "localhost"
+
+database:
+  url: "postgresql://localhost/mydb"
+  max_connections: 10
+
+features:
+  enable_metrics: false
+  enable_caching: true
+"#,
+    )
+    .unwrap();
+
+    let port = Arc::new(AtomicI64::new(9090));
+    let notifier = Arc::new(Mutex::new(None));
+    let loads = Arc::new(AtomicUsize::new(0));
+    let errors = Arc::new(Mutex::new(Vec::new()));
+    let reported = Arc::clone(&errors);
+    let config = HotswapConfig::<IntegrationConfig>::builder()
+        .with_file(&config_path)
+        .with_source(PushSource {
+            port: Arc::clone(&port),
+            notifier: Arc::clone(&notifier),
+        })
+        .with_source(Counted {
+            loads: Arc::clone(&loads),
+        })
+        .with_reload_error_handler(move |e| reported.lock().unwrap().push(e.to_string()))
+        .build()
+        .await
+        .unwrap();
+    assert_eq!(config.get().server.port, 9090);
+    assert_eq!(loads.load(Ordering::SeqCst), 1);
+
+    // A pushed change re-loads only the source that changed
+    let notifier = notifier.lock().unwrap().clone().unwrap();
+    port.store(9191, Ordering::SeqCst);
+    assert!(notifier.changed());
+    for _ in 0..100 {
+        if config.get().server.port == 9191 {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    assert_eq!(config.get().server.port, 9191);
+    assert!(config.get().features.enable_metrics);
+    assert_eq!(loads.load(Ordering::SeqCst), 1);
+
+    // Watch failures go to the reload error handler
+    assert!(notifier.error(ConfigError::LoadError("watch lost".to_string())));
+    for _ in 0..100 {
+        if !errors.lock().unwrap().is_empty() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    assert!(errors.lock().unwrap()[0].contains("watch lost"));
+
+    // A notifier can wait for the result of its reload
+    port.store(9292, Ordering::SeqCst);
+    assert!(notifier.changed_and_wait().await.unwrap().is_ok());
+    assert_eq!(config.get().server.port, 9292);
+    port.store(-1, Ordering::SeqCst);
+    assert!(notifier.changed_and_wait().await.unwrap().is_err());
+    assert_eq!(config.get().server.port, 9292);
+    assert_eq!(errors.lock().unwrap().len(), 2);
+}
+
+#[cfg(feature = "file-watch")]
+#[tokio::test]
+async fn test_watched_file_source_reloads() {
+    use hotswap_config::sources::FileSource;
+
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.yaml");
+    let override_path = temp_dir.path().join("override.yaml");
+    fs::write(
+        &config_path,
+        r#"
+server:
+  port: 8080
+  host: "localhost"
+
+database:
+  url: "postgresql://localhost/mydb"
+  max_connections: 10
+
+features:
+  enable_metrics: false
+  enable_caching: true
+"#,
+    )
+    .unwrap();
+    fs::write(&override_path, "server:\n  port: 9090\n").unwrap();
+
+    // Builder file watching stays off; the source watches itself
+    let config = HotswapConfig::<IntegrationConfig>::builder()
+        .with_file(&config_path)
+        .with_source(
+            FileSource::new(&override_path)
+                .with_priority(150)
+                .with_watch(true),
+        )
+        .build()
+        .await
+        .unwrap();
+    assert_eq!(config.get().server.port, 9090);
+
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    fs::write(&override_path, "server:\n  port: 9191\n").unwrap();
+    for _ in 0..300 {
+        if config.get().server.port == 9191 {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    assert_eq!(config.get().server.port, 9191);
+}
+
+#[tokio::test]
+async fn test_defaults_on_failure_retry_until_sources_recover() {
+    use std::time::Duration;
+
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.yaml");
+    let defaults = IntegrationConfig {
+        server: ServerConfig {
+            port: 80,
+            host: "0.0.0.0".to_string(),
+        },
+        database: DatabaseConfig {
+            url: "sqlite::memory:".to_string(),
+            max_connections: 1,
+        },
+        features: Features {
+            enable_metrics: false,
+            enable_caching: false,
+        },
+    };
+
+    // The required file does not exist yet, so startup falls back to defaults
+    let config = HotswapConfig::builder()
+        .with_file(&config_path)
+        .with_startup_retry(Duration::from_millis(20), Duration::from_millis(50))
+        .build_with_defaults_on_failure(defaults.clone())
+        .await
+        .unwrap();
+    assert!(config.is_fallback());
+    assert_eq!(*config.get(), defaults);
+
+    fs::write(
+        &config_path,
+        r#"
+server:
+  port: 8080
+  host: "localhost"
+
+database:
+  url: "postgresql://localhost/mydb"
+  max_connections: 10
+
+features:
+  enable_metrics: true
+  enable_caching: true
+"#,
+    )
+    .unwrap();
+    for _ in 0..200 {
+        if !config.is_fallback() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert!(!config.is_fallback());
+    assert_eq!(config.get().server.port, 8080);
+    assert!(config.get().features.enable_metrics);
+}
+
+#[tokio::test]
+async fn test_reload_schedule_picks_up_unwatched_changes() {
+    use hotswap_config::core::Schedule;
+    use std::time::Duration;
+
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.yaml");
+    let write_port = |port: u16| {
+        fs::write(
+            &config_path,
+            format!(
+                r#"
+server:
+  port: {}
+  host: "localhost"
+
+database:
+  url: "postgresql://localhost/mydb"
+  max_connections: 10
+
+features:
+  enable_metrics: false
+  enable_caching: true
+"#,
+                port
+            ),
+        )
+        .unwrap();
+    };
+    write_port(8080);
+
+    // No file watching, so only the schedule notices the change
+    let config = HotswapConfig::<IntegrationConfig>::builder()
+        .with_file(&config_path)
+        .with_reload_schedule(Schedule::every(Duration::from_millis(20)))
+        .build()
+        .await
+        .unwrap();
+    assert_eq!(config.get().server.port, 8080);
+
+    write_port(9090);
+    for _ in 0..200 {
+        if config.get().server.port == 9090 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(config.get().server.port, 9090);
+}
+
+#[cfg(all(unix, feature = "signal"))]
+#[tokio::test]
+async fn test_reload_on_signal() {
+    use hotswap_config::core::Signal;
+    use std::time::Duration;
+
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.yaml");
+    let write_port = |port: u16| {
+        fs::write(
+            &config_path,
+            format!(
+                r#"
+server:
+  port: {}
+  host: "localhost"
+
+database:
+  url: "postgresql://localhost/mydb"
+  max_connections: 10
+
+features:
+  enable_metrics: false
+  enable_caching: true
+"#,
+                port
+            ),
+        )
+        .unwrap();
+    };
+    write_port(8080);
+
+    let config = HotswapConfig::<IntegrationConfig>::builder()
+        .with_file(&config_path)
+        .with_file_watch(false)
+        .with_reload_on_signal(Signal::SIGUSR2)
+        .build()
+        .await
+        .unwrap();
+
+    write_port(9090);
+    let status = std::process::Command::new("kill")
+        .args(["-USR2", &std::process::id().to_string()])
+        .status()
+        .unwrap();
+    assert!(status.success());
+    for _ in 0..200 {
+        if config.get().server.port == 9090 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(config.get().server.port, 9090);
+}
+
+#[cfg(feature = "webhook")]
+#[tokio::test]
+async fn test_reload_webhook() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.yaml");
+    let write_port = |port: u16| {
+        fs::write(
+            &config_path,
+            format!(
+                r#"
+server:
+  port: {}
+  host: "localhost"
+
+database:
+  url: "postgresql://localhost/mydb"
+  max_connections: 10
+
+features:
+  enable_metrics: false
+  enable_caching: true
+"#,
+                port
+            ),
+        )
+        .unwrap();
+    };
+    write_port(8080);
+
+    // Find a free port for the webhook
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let config = HotswapConfig::<IntegrationConfig>::builder()
+        .with_file(&config_path)
+        .with_file_watch(false)
+        .with_reload_webhook(addr.to_string(), "deploy-token")
+        .build()
+        .await
+        .unwrap();
+
+    let post = |token: &'static str| async move {
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let request = format!(
+            "POST /reload HTTP/1.1\r\nHost: {}\r\nAuthorization: Bearer {}\r\n\
+             Content-Length: 0\r\nConnection: close\r\n\r\n",
+            addr, token
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    };
+
+    write_port(9090);
+    assert!(post("wrong-token").await.starts_with("HTTP/1.1 401"));
+    assert_eq!(config.get().server.port, 8080);
+
+    assert!(post("deploy-token").await.starts_with("HTTP/1.1 200"));
+    assert_eq!(config.get().server.port, 9090);
+
+    // The listener stops with the handle
+    config.shutdown().await;
+    assert!(tokio::net::TcpStream::connect(addr).await.is_err());
+}
+
+#[cfg(feature = "signing")]
+#[tokio::test]
+async fn test_signed_config_rejects_tampering() {
+    use ed25519_dalek::{Signer, SigningKey};
+    use hotswap_config::signing::SignatureVerifier;
+
+    let key = SigningKey::from_bytes(&[7; 32]);
+    let sign = |content: &str| key.sign(content.as_bytes()).to_bytes();
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.yaml");
+    let signature_path = temp_dir.path().join("config.yaml.sig");
+    let original = r#"
+server:
+  port: 8080
+  host: "localhost"
+
+database:
+  url: "postgresql://localhost/mydb"
+  max_connections: 10
+
+features:
+  enable_metrics: false
+  enable_caching: true
+"#;
+    fs::write(&config_path, original).unwrap();
+    fs::write(&signature_path, sign(original)).unwrap();
+
+    let verifier = SignatureVerifier::new()
+        .with_public_key_bytes(key.verifying_key().to_bytes())
+        .unwrap();
+    let config = HotswapConfig::<IntegrationConfig>::builder()
+        .with_file(&config_path)
+        .with_signature_verification(verifier)
+        .build()
+        .await
+        .unwrap();
+    assert_eq!(config.get().database.url, "postgresql://localhost/mydb");
+
+    // Unsigned changes are rejected and the old config stays live
+    let tampered = original.replace("localhost/mydb", "evil/mydb");
+    fs::write(&config_path, &tampered).unwrap();
+    assert!(matches!(
+        config.reload().await.unwrap_err().root(),
+        ConfigError::SignatureError(_)
+    ));
+    assert_eq!(config.get().database.url, "postgresql://localhost/mydb");
+
+    // Once signed by a trusted key, the change is accepted
+    fs::write(&signature_path, sign(&tampered)).unwrap();
+    config.reload().await.unwrap();
+    assert_eq!(config.get().database.url, "postgresql://evil/mydb");
+}
+
+#[cfg(feature = "encryption")]
+#[tokio::test]
+async fn test_encrypted_values_decrypt_on_reload() {
+    use age::x25519::Identity;
+    use hotswap_config::encryption::AgeKeyFile;
+
+    let identity = Identity::generate();
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.yaml");
+    let secrets_path = temp_dir.path().join("secrets.yaml");
+    let keys_path = temp_dir.path().join("keys.txt");
+    fs::write(
+        &keys_path,
+        age::secrecy::ExposeSecret::expose_secret(&identity.to_string()),
+    )
+    .unwrap();
+    fs::write(
+        &config_path,
+        r#"
+server:
+  port: 8080
+  host: "localhost"
+
+database:
+  url: "postgresql://localhost/mydb"
+  max_connections: 10
+
+features:
+  enable_metrics: false
+  enable_caching: true
+"#,
+    )
+    .unwrap();
+    let write_secret = |url: &str| {
+        let armored = age::encrypt_and_armor(&identity.to_public(), url.as_bytes()).unwrap();
+        let indented = armored.lines().collect::<Vec<_>>().join("\n    ");
+        fs::write(
+            &secrets_path,
+            format!("database:\n  url: |\n    {}\n", indented),
+        )
+        .unwrap();
+    };
+    write_secret("postgresql://user:one@db/mydb");
+
+    let config = HotswapConfig::<IntegrationConfig>::builder()
+        .with_file(&config_path)
+        .with_file(&secrets_path)
+        .with_decryption(AgeKeyFile::new(&keys_path))
+        .build()
+        .await
+        .unwrap();
+    assert_eq!(config.get().database.url, "postgresql://user:one@db/mydb");
+
+    write_secret("postgresql://user:two@db/mydb");
+    config.reload().await.unwrap();
+    assert_eq!(config.get().database.url, "postgresql://user:two@db/mydb");
+
+    // A key that can't decrypt the value keeps the old config
+    fs::write(
+        &keys_path,
+        age::secrecy::ExposeSecret::expose_secret(&Identity::generate().to_string()),
+    )
+    .unwrap();
+    assert!(matches!(
+        config.reload().await.unwrap_err().root(),
+        ConfigError::DecryptionError(_)
+    ));
+    assert_eq!(config.get().database.url, "postgresql://user:two@db/mydb");
+}
+
+#[tokio::test]
+async fn test_validation_failure_preserves_old_config() {
+    let _config = HotswapConfig::new(IntegrationConfig {
+        server: ServerConfig {
+            port: 8080,
+            host: "localhost".to_string(),
+        },
+        database: DatabaseConfig {
+            url: "postgresql://localhost/mydb".to_string(),
+            max_connections: 10,
+        },
+        features: Features {
+            enable_metrics: false,
+            enable_caching: true,
+        },
+    });
+
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.yaml");
+
+    fs::write(
+        &config_path,
+        r#"
+server:
+  port: 8080
+  host: "localhost"
+
+database:
+  url: "postgresql://localhost/mydb"
+  max_connections: 10
+
+features:
+  enable_metrics: false
+  enable_caching: true
+"#,
+    )
+    .unwrap();
+
+    let config_with_validation = HotswapConfig::<IntegrationConfig>::builder()
+        .with_file(&config_path)
+        .with_validation(|cfg: &IntegrationConfig| {
+            if cfg.server.port < 1024 {
+                return Err(hotswap_config::error::ValidationError::invalid_field(
+                    "port",
+                    "must be >= 1024",
+                ));
+            }
+            Ok(())
+        })
+        .build()
+        .await
+        .unwrap();
+
+    // Try to update with invalid config
+    let invalid_config = IntegrationConfig {
+        server: ServerConfig {
+            port: 80, // Invalid!
+            host: "localhost".to_string(),
+        },
+        database: DatabaseConfig {
+            url: "postgresql://localhost/mydb".to_string(),
+            max_connections: 10,
+        },
+        features: Features {
+            enable_metrics: false,
+            enable_caching: true,
+        },
+    };
+
+    let result = config_with_validation.update(invalid_config).await;
+    assert!(result.is_err());
+
+    // Original config should be preserved
+    let cfg = config_with_validation.get();
+    assert_eq!(cfg.server.port, 8080); // Still the old value
+}
+
+#[tokio::test]
+async fn test_concurrent_reads_during_updates() {
+    let config = std::sync::Arc::new(HotswapConfig::new(IntegrationConfig {
+        server: ServerConfig {
+            port: 8080,
+            host: "localhost".to_string(),
+        },
+        database: DatabaseConfig {
+            url: "postgresql://localhost/mydb".to_string(),
+            max_connections: 10,
+        },
+        features: Features {
+            enable_metrics: false,
+            enable_caching: true,
+        },
+    }));
+
+    let mut handles = vec![];
+
+    // Spawn 10 reader tasks
+    for _ in 0..10 {
+        let cfg = config.clone();
+        let handle = tokio::spawn(async move {
+            for _ in 0..100 {
+                let data = cfg.get();
+                assert!(data.server.port >= 8080 && data.server.port <= 8090);
+                tokio::time::sleep(tokio::time::Duration::from_micros(10)).await;
+            }
+        });
+        handles.push(handle);
+    }
+
+    // Perform updates while readers are running
+    for i in 0..10 {
+        let new_config = IntegrationConfig {
+            server: ServerConfig {
+                port: 8080 + i,
+                host: "localhost".to_string(),
+            },
+            database: DatabaseConfig {
+                url: "postgresql://localhost/mydb".to_string(),
+                max_connections: 10,
+            },
+            features: Features {
+                enable_metrics: false,
+                enable_caching: true,
+            },
+        };
+
+        config.update(new_config).await.unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+    }
+
+    // Wait for all readers
+    for handle in handles {
+        handle.await.unwrap();
+    }
+}
+
+#[cfg(feature = "gradual-rollout")]
+#[tokio::test]
+async fn test_canary_reloads() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.yaml");
+
+    let write_config = |port: u16| {
+        fs::write(
+            &config_path,
+            format!(
+                r#"
+server:
+  port: {}
+  host: "localhost"
+database:
+  url: "postgresql://localhost/mydb"
+  max_connections: 10
+features:
+  enable_metrics: false
+  enable_caching: true
+"#,
+                port
+            ),
+        )
+        .unwrap();
+    };
+
+    write_config(8080);
+    let config = HotswapConfig::<IntegrationConfig>::builder()
+        .with_file(&config_path)
+        .with_canary_reloads(100, None)
+        .build()
+        .await
+        .unwrap();
+
+    let counter = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let counter_clone = counter.clone();
+    let _handle = config
+        .subscribe(move || {
+            counter_clone.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        })
+        .await;
+
+    // Reload stages the new config as the canary; current stays stable
+    write_config(9090);
+    config.reload().await.unwrap();
+
+    let rollout = config.canary_rollout().unwrap();
+    assert_eq!(config.get().server.port, 8080);
+    assert_eq!(rollout.get(Some("user123")).server.port, 9090);
+    assert_eq!(counter.load(std::sync::atomic::Ordering::SeqCst), 0);
+
+    // Promotion publishes the canary to the handle and notifies subscribers
+    rollout.promote().await.unwrap();
+    assert_eq!(config.get().server.port, 9090);
+    assert_eq!(rollout.get_stable().server.port, 9090);
+    assert_eq!(counter.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+    // Rolling back a canary leaves the handle untouched
+    write_config(7070);
+    config.reload().await.unwrap();
+    rollout.rollback_canary();
+    assert_eq!(config.get().server.port, 9090);
+    assert_eq!(rollout.get(None).server.port, 9090);
+}
+
+#[cfg(all(feature = "gradual-rollout", feature = "prometheus"))]
+#[tokio::test]
+async fn test_canary_reload_exposures_and_metrics() {
+    use hotswap_config::features::Variant;
+    use hotswap_config::metrics::PrometheusRecorder;
+
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.yaml");
+    fs::write(&config_path, "port: 8080\nhost: localhost\n").unwrap();
+
+    let registry = prometheus::Registry::new();
+    let exposures = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let log = exposures.clone();
+    let config = HotswapConfig::<ServerConfig>::builder()
+        .with_file(&config_path)
+        .with_canary_reloads(100, None)
+        .with_metrics_recorder(PrometheusRecorder::new(&registry).unwrap())
+        .with_exposure_handler(move |key, variant| {
+            log.lock().unwrap().push((key.to_string(), variant));
+        })
+        .build()
+        .await
+        .unwrap();
+
+    fs::write(&config_path, "port: 9090\nhost: localhost\n").unwrap();
+    config.reload().await.unwrap();
+    let rollout = config.canary_rollout().unwrap();
+    assert_eq!(rollout.get(Some("user123")).port, 9090);
+
+    assert_eq!(
+        *exposures.lock().unwrap(),
+        vec![("user123".to_string(), Variant::Canary)]
+    );
+    let selections = registry
+        .gather()
+        .into_iter()
+        .find(|family| family.name() == "hotswap_config_rollout_selections_total")
+        .unwrap();
+    assert_eq!(selections.get_metric()[0].get_counter().get_value(), 1.0);
+}
+
+#[cfg(all(feature = "gradual-rollout", feature = "partial-updates"))]
+#[tokio::test]
+async fn test_canary_patch_promotion_is_validated() {
+    use hotswap_config::features::PartialUpdate;
+
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.yaml");
+    fs::write(
+        &config_path,
+        r#"
+server:
+  port: 8080
+  host: "localhost"
+database:
+  url: "postgresql://localhost/mydb"
+  max_connections: 10
+features:
+  enable_metrics: false
+  enable_caching: true
+"#,
+    )
+    .unwrap();
+
+    let config = HotswapConfig::<IntegrationConfig>::builder()
+        .with_file(&config_path)
+        .with_canary_reloads(0, None)
+        .with_validation(|cfg: &IntegrationConfig| {
+            if cfg.database.max_connections > 100 {
+                return Err(ValidationError::invalid_field(
+                    "database.max_connections",
+                    "must be at most 100",
+                ));
+            }
+            Ok(())
+        })
+        .build()
+        .await
+        .unwrap();
+    let rollout = config.canary_rollout().unwrap();
+
+    // Only the patched field differs, and only for the canary
+    rollout
+        .set_canary_patch(
+            serde_json::json!([
+                { "op": "replace", "path": "/database/max_connections", "value": 500 }
+            ]),
+            100,
+        )
+        .unwrap();
+    assert_eq!(rollout.get(None).database.max_connections, 500);
+    assert_eq!(rollout.get(None).server.port, 8080);
+
+    // Updates to the handle carry over to the canary
+    config.update_field("/server/port", 9090).await.unwrap();
+    assert_eq!(rollout.get(None).server.port, 9090);
+    assert_eq!(rollout.get(None).database.max_connections, 500);
+
+    // Promotion goes through validation, so an invalid patch stays staged
+    assert!(rollout.promote().await.is_err());
+    assert_eq!(config.get().database.max_connections, 10);
+    assert!(rollout.has_canary());
+
+    rollout
+        .set_canary_patch(
+            serde_json::json!([
+                { "op": "replace", "path": "/database/max_connections", "value": 50 }
+            ]),
+            100,
+        )
+        .unwrap();
+    rollout.promote().await.unwrap();
+    assert_eq!(config.get().database.max_connections, 50);
+    assert_eq!(config.get().server.port, 9090);
+    assert_eq!(rollout.get_stable().database.max_connections, 50);
+    assert!(!rollout.has_canary());
+}
+
+#[cfg(feature = "gradual-rollout")]
+#[tokio::test]
+async fn test_canary_promotion_is_checked() {
+    use hotswap_config::error::ApplyError;
+
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.yaml");
+    fs::write(&config_path, "port: 8080\nhost: localhost\n").unwrap();
+
+    let errors = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let log = errors.clone();
+    let config = HotswapConfig::<ServerConfig>::builder()
+        .with_file(&config_path)
+        .with_canary_reloads(100, Some(std::time::Duration::from_millis(100)))
+        .with_reload_error_handler(move |e| log.lock().unwrap().push(e.to_string()))
+        .build()
+        .await
+        .unwrap();
+    let rollout = config.canary_rollout().unwrap();
+
+    // A frozen handle refuses the promotion, scheduled or not, and the
+    // canary stays staged
+    fs::write(&config_path, "port: 9090\nhost: localhost\n").unwrap();
+    config.reload().await.unwrap();
+    config.freeze(Some("incident"));
+    assert!(matches!(
+        rollout.promote().await,
+        Err(ConfigError::Frozen { .. })
+    ));
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+    assert_eq!(config.get().port, 8080);
+    assert!(rollout.has_canary());
+    assert!(errors.lock().unwrap()[0].contains("incident"));
+
+    // So does a transactional subscriber that can't apply it
+    config.unfreeze().await.unwrap();
+    let applier = config.subscribe_transactional("listener", |cfg: &ServerConfig| {
+        if cfg.port == 9090 {
+            return Err(ApplyError::new("port in use"));
+        }
+        Ok(())
+    });
+    assert!(matches!(
+        rollout.promote().await,
+        Err(ConfigError::ApplyFailed { .. })
+    ));
+    assert_eq!(config.get().port, 8080);
+    assert!(rollout.has_canary());
+
+    drop(applier);
+    rollout.promote().await.unwrap();
+    assert_eq!(config.get().port, 9090);
+    assert!(!rollout.has_canary());
+}
+
+#[tokio::test]
+async fn test_registry_shares_one_watcher() {
+    use hotswap_config::core::ConfigRegistry;
+
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+    struct CacheConfig {
+        ttl_secs: u64,
+    }
+
+    let temp_dir = TempDir::new().unwrap();
+    let server_path = temp_dir.path().join("server.yaml");
+    let cache_path = temp_dir.path().join("cache.yaml");
+    fs::write(&server_path, "port: 8080\nhost: localhost\n").unwrap();
+    fs::write(&cache_path, "ttl_secs: 60\n").unwrap();
+
+    let registry = ConfigRegistry::new().with_watch_debounce(std::time::Duration::from_millis(50));
+    let server = registry
+        .builder::<ServerConfig>()
+        .with_file(&server_path)
+        .with_file_watch(true)
+        .build()
+        .await
+        .unwrap();
+    registry.register("server", server).unwrap();
+    let cache = registry
+        .builder::<CacheConfig>()
+        .with_file(&cache_path)
+        .with_file_watch(true)
+        .build()
+        .await
+        .unwrap();
+    registry.register("cache", cache).unwrap();
+
+    // Each handle picks up changes to its own files
+    fs::write(&cache_path, "ttl_secs: 120\n").unwrap();
+    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+    assert_eq!(registry.get::<CacheConfig>().unwrap().get().ttl_secs, 120);
+
+    fs::write(&server_path, "port: 9090\nhost: localhost\n").unwrap();
+    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+    let server = registry.get_named::<ServerConfig>("server").unwrap();
+    assert_eq!(server.get().port, 9090);
 }